@@ -28,8 +28,17 @@ use crate::{
     DxfResult,
     ExtensionGroup,
     Handle,
+    HatchBoundaryPath,
+    HatchGradientColor,
+    HatchPatternDefinitionLine,
     LwPolylineVertex,
+    MLeaderArrowheadOverride,
+    MLeaderBlockAttribute,
+    MLeaderContextData,
+    MeshSubentityOverride,
     Point,
+    TableCell,
+    TransformationMatrix,
     Vector,
     XData,
 };
@@ -50,6 +59,7 @@ use crate::objects::*;
     fun.push_str("impl EntityType {\n");
     generate_is_supported_on_version(&mut fun, &element);
     generate_type_string(&mut fun, &element);
+    generate_add_pointers(&mut fun, &element);
     generate_try_apply_code_pair(&mut fun, &element);
     generate_get_code_pairs(&mut fun, &element);
     fun.push_str("}\n");
@@ -151,6 +161,12 @@ fn generate_base_entity(fun: &mut String, element: &Element) {
         }
     }
 
+    fun.push_str("    pub(crate) fn add_pointers(&self, handles: &mut Vec<Handle>) {\n");
+    for line in add_pointer_lines(entity, "self") {
+        fun.push_str(&format!("        {}\n", line));
+    }
+    fun.push_str("    }\n");
+
     ////////////////////////////////////////////////////// apply_individual_pair
     fun.push_str("    pub(crate) fn apply_individual_pair(&mut self, pair: &CodePair, iter: &mut CodePairPutBack) -> DxfResult<()> {\n");
     fun.push_str("        match pair.code {\n");
@@ -219,6 +235,13 @@ fn generate_entity_types(fun: &mut String, element: &Element) {
             fun.push_str(&format!("    {typ}({typ}),\n", typ = name(c)));
         }
     }
+    fun.push_str("    /// An entity of an unsupported type, only produced when unknown items are preserved while loading.  The code\n");
+    fun.push_str("    /// pairs following the type string are written back verbatim, except that the handle, layer, and owner are\n");
+    fun.push_str("    /// kept in, and written from, the common entity values.\n");
+    fun.push_str("    Unknown {\n");
+    fun.push_str("        type_string: String,\n");
+    fun.push_str("        pairs: Vec<CodePair>,\n");
+    fun.push_str("    },\n");
 
     fun.push_str("}\n");
     fun.push_str("\n");
@@ -418,6 +441,7 @@ fn generate_is_supported_on_version(fun: &mut String, element: &Element) {
             ));
         }
     }
+    fun.push_str("            &EntityType::Unknown { .. } => { true },\n");
     fun.push_str("        }\n");
     fun.push_str("    }\n");
 }
@@ -457,6 +481,40 @@ fn generate_type_string(fun: &mut String, element: &Element) {
             ));
         }
     }
+    fun.push_str("            &EntityType::Unknown { ref type_string, .. } => { type_string },\n");
+    fun.push_str("        }\n");
+    fun.push_str("    }\n");
+}
+
+fn generate_add_pointers(fun: &mut String, element: &Element) {
+    let dimension_base = element
+        .children
+        .iter()
+        .find(|c| name(c) == "DimensionBase")
+        .unwrap();
+    fun.push_str("    pub(crate) fn add_pointers(&self, handles: &mut Vec<Handle>) {\n");
+    fun.push_str("        match self {\n");
+    for c in &element.children {
+        if name(c) == "Entity" || name(c) == "DimensionBase" {
+            continue;
+        }
+        let mut lines = vec![];
+        if base_class(c) == "DimensionBase" {
+            lines.extend(add_pointer_lines(dimension_base, "ent.dimension_base"));
+        }
+        lines.extend(add_pointer_lines(c, "ent"));
+        if !lines.is_empty() {
+            fun.push_str(&format!(
+                "            EntityType::{typ}(ref ent) => {{\n",
+                typ = name(c)
+            ));
+            for line in lines {
+                fun.push_str(&format!("                {}\n", line));
+            }
+            fun.push_str("            },\n");
+        }
+    }
+    fun.push_str("            _ => (),\n");
     fun.push_str("        }\n");
     fun.push_str("    }\n");
 }
@@ -540,6 +598,7 @@ fn generate_try_apply_code_pair(fun: &mut String, element: &Element) {
         }
     }
 
+    fun.push_str("            &mut EntityType::Unknown { .. } => { return Ok(false); },\n");
     fun.push_str("        }\n");
     fun.push_str("        Ok(true)\n");
     fun.push_str("    }\n");
@@ -571,6 +630,11 @@ fn generate_get_code_pairs(fun: &mut String, element: &Element) {
             }
         }
     }
+    fun.push_str("            &EntityType::Unknown { pairs: ref unknown_pairs, .. } => {\n");
+    fun.push_str("                for pair in unknown_pairs {\n");
+    fun.push_str("                    pairs.push(pair.clone());\n");
+    fun.push_str("                }\n");
+    fun.push_str("            },\n");
     fun.push_str("        }\n");
     fun.push_str("    }\n");
 }
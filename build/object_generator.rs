@@ -59,6 +59,7 @@ use self::chrono::{DateTime, Local};
     fun.push_str("impl ObjectType {\n");
     generate_is_supported_on_version(&mut fun, &element);
     generate_type_string(&mut fun, &element);
+    generate_add_pointers(&mut fun, &element);
     generate_try_apply_code_pair(&mut fun, &element);
     generate_write(&mut fun, &element);
     fun.push_str("}\n");
@@ -158,6 +159,12 @@ fn generate_base_object(fun: &mut String, element: &Element) {
         }
     }
 
+    fun.push_str("    pub(crate) fn add_pointers(&self, handles: &mut Vec<Handle>) {\n");
+    for line in add_pointer_lines(object, "self") {
+        fun.push_str(&format!("        {}\n", line));
+    }
+    fun.push_str("    }\n");
+
     ////////////////////////////////////////////////////// apply_individual_pair
     fun.push_str("    pub(crate) fn apply_individual_pair(&mut self, pair: &CodePair, iter: &mut CodePairPutBack) -> DxfResult<bool> {\n");
     fun.push_str("        match pair.code {\n");
@@ -228,6 +235,12 @@ fn generate_object_types(fun: &mut String, element: &Element) {
             fun.push_str(&format!("    {typ}({typ}),\n", typ = name(c)));
         }
     }
+    fun.push_str("    /// An object of an unsupported type, only produced when unknown items are preserved while loading.  The code\n");
+    fun.push_str("    /// pairs following the type string are written back verbatim; the handle is kept in the common object values.\n");
+    fun.push_str("    Unknown {\n");
+    fun.push_str("        type_string: String,\n");
+    fun.push_str("        pairs: Vec<CodePair>,\n");
+    fun.push_str("    },\n");
 
     fun.push_str("}\n");
     fun.push_str("\n");
@@ -409,6 +422,7 @@ fn generate_is_supported_on_version(fun: &mut String, element: &Element) {
             ));
         }
     }
+    fun.push_str("            ObjectType::Unknown { .. } => { true },\n");
     fun.push_str("        }\n");
     fun.push_str("    }\n");
 }
@@ -444,6 +458,31 @@ fn generate_type_string(fun: &mut String, element: &Element) {
             ));
         }
     }
+    fun.push_str("            ObjectType::Unknown { ref type_string, .. } => { type_string },\n");
+    fun.push_str("        }\n");
+    fun.push_str("    }\n");
+}
+
+fn generate_add_pointers(fun: &mut String, element: &Element) {
+    fun.push_str("    pub(crate) fn add_pointers(&self, handles: &mut Vec<Handle>) {\n");
+    fun.push_str("        match self {\n");
+    for c in &element.children {
+        if name(c) == "Object" {
+            continue;
+        }
+        let lines = add_pointer_lines(c, "obj");
+        if !lines.is_empty() {
+            fun.push_str(&format!(
+                "            ObjectType::{typ}(ref obj) => {{\n",
+                typ = name(c)
+            ));
+            for line in lines {
+                fun.push_str(&format!("                {}\n", line));
+            }
+            fun.push_str("            },\n");
+        }
+    }
+    fun.push_str("            _ => (),\n");
     fun.push_str("        }\n");
     fun.push_str("    }\n");
 }
@@ -539,6 +578,7 @@ fn generate_try_apply_code_pair(fun: &mut String, element: &Element) {
         }
     }
 
+    fun.push_str("            ObjectType::Unknown { .. } => { return Ok(false); },\n");
     fun.push_str("        }\n");
     fun.push_str("        Ok(true)\n");
     fun.push_str("    }\n");
@@ -584,6 +624,11 @@ fn generate_write(fun: &mut String, element: &Element) {
             }
         }
     }
+    fun.push_str("            ObjectType::Unknown { pairs: ref unknown_pairs, .. } => {\n");
+    fun.push_str("                for pair in unknown_pairs {\n");
+    fun.push_str("                    pairs.push(pair.clone());\n");
+    fun.push_str("                }\n");
+    fun.push_str("            },\n");
     fun.push_str("        }\n");
     fun.push_str("    }\n");
 
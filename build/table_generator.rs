@@ -138,6 +138,17 @@ fn generate_table_items(fun: &mut String, element: &Element) {
         fun.push_str("    pub fn set_owner<'a>(&mut self, item: &'a mut DrawingItemMut, drawing: &'a mut Drawing) {\n");
         fun.push_str("        self.__owner_handle = drawing.assign_and_get_handle(item);\n");
         fun.push_str("    }\n");
+        for field in &table_item.children {
+            if field.name == "Pointer" {
+                fun.push_str(&methods_for_pointer_access(field));
+            }
+        }
+        fun.push_str("    pub(crate) fn add_pointers(&self, handles: &mut Vec<Handle>) {\n");
+        fun.push_str("        handles.push(self.__owner_handle);\n");
+        for line in add_pointer_lines(table_item, "self") {
+            fun.push_str(&format!("        {}\n", line));
+        }
+        fun.push_str("    }\n");
         fun.push_str("}\n");
         fun.push_str("\n");
     }
@@ -3,6 +3,8 @@ use self::xmltree::Element;
 use crate::other_helpers::*;
 use crate::ExpectedType;
 
+use std::collections::HashSet;
+
 pub fn attr(element: &Element, name: &str) -> String {
     match &element.attributes.get(name) {
         &Some(v) => v.clone(),
@@ -71,6 +73,30 @@ pub fn field_reader(element: &Element) -> String {
     }
 }
 
+/// Returns the lines that add the handles of the element's pointers to `handles`, with the pointers read from `item`.
+pub fn add_pointer_lines(element: &Element, item: &str) -> Vec<String> {
+    let mut seen_pointers = HashSet::new();
+    let mut lines = vec![];
+    for pointer in &element.children {
+        if pointer.name == "Pointer" && seen_pointers.insert(name(pointer)) {
+            if allow_multiples(pointer) {
+                lines.push(format!(
+                    "handles.extend_from_slice(&{item}.__{name}_handle);",
+                    item = item,
+                    name = name(pointer)
+                ));
+            } else {
+                lines.push(format!(
+                    "handles.push({item}.__{name}_handle);",
+                    item = item,
+                    name = name(pointer)
+                ));
+            }
+        }
+    }
+    lines
+}
+
 pub fn methods_for_pointer_access(pointer: &Element) -> String {
     let mut fun = String::new();
     let typ = attr(&pointer, "Type");
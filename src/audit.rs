@@ -0,0 +1,638 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use crate::drawing::AUTO_REPLACE_HANDLE;
+use crate::entities::*;
+use crate::enums::AcadVersion;
+use crate::tables::Layer;
+use crate::{CodePairValue, Drawing, DrawingItem, Handle, Point};
+
+/// A problem found by `Drawing::audit()`.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct AuditIssue {
+    /// The handle of the item with the problem, or the handle that is used more than once.
+    pub handle: Handle,
+    /// The kind of problem.
+    pub kind: AuditIssueKind,
+    /// `true` if the problem was fixed by `Drawing::audit_and_fix()`.
+    pub fixed: bool,
+}
+
+/// The kinds of problems found by `Drawing::audit()`.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub enum AuditIssueKind {
+    /// More than one item has the handle.  Fixed by giving all but the first item new handles.
+    DuplicateHandle,
+    /// The handle isn't below `$HANDSEED`, so a new item could be given the same handle.  Fixed by raising `$HANDSEED`.
+    HandleAboveHandleSeed,
+    /// The item's owner handle doesn't refer to an item in the drawing.
+    DanglingOwner(Handle),
+    /// One of the item's pointers doesn't refer to an item in the drawing.
+    DanglingPointer(Handle),
+    /// The entity is on a layer that doesn't exist.  Fixed by adding the layer.
+    MissingLayer(String),
+    /// The insert references a block that doesn't exist.
+    MissingBlock(String),
+    /// The entity can't be written for the drawing's `$ACADVER` and will be dropped when saving.
+    UnsupportedOnVersion(AcadVersion),
+    /// The item has a NaN or infinite value.
+    NonFiniteValue,
+    /// The attributes of an insert or the vertices of a polyline and the `SEQEND` ending them are missing handles, or
+    /// the polyline has no vertices.  Missing handles are fixed by assigning new ones.
+    MismatchedSeqEnd,
+}
+
+impl fmt::Display for AuditIssue {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "{}: ", self.handle.as_string())?;
+        match self.kind {
+            AuditIssueKind::DuplicateHandle => {
+                write!(formatter, "the handle is used by more than one item")
+            }
+            AuditIssueKind::HandleAboveHandleSeed => {
+                write!(formatter, "the handle is not below $HANDSEED")
+            }
+            AuditIssueKind::DanglingOwner(h) => {
+                write!(formatter, "the owner '{}' does not exist", h.as_string())
+            }
+            AuditIssueKind::DanglingPointer(h) => write!(
+                formatter,
+                "the referenced item '{}' does not exist",
+                h.as_string()
+            ),
+            AuditIssueKind::MissingLayer(ref name) => {
+                write!(formatter, "the layer '{}' does not exist", name)
+            }
+            AuditIssueKind::MissingBlock(ref name) => {
+                write!(formatter, "the block '{}' does not exist", name)
+            }
+            AuditIssueKind::UnsupportedOnVersion(version) => write!(
+                formatter,
+                "the entity is not supported on version {}",
+                version
+            ),
+            AuditIssueKind::NonFiniteValue => {
+                write!(formatter, "a value is NaN or infinite")
+            }
+            AuditIssueKind::MismatchedSeqEnd => write!(
+                formatter,
+                "the attribute or vertex sequence is not properly terminated"
+            ),
+        }
+    }
+}
+
+// internal visibility only
+
+pub(crate) fn audit(drawing: &Drawing) -> Vec<AuditIssue> {
+    let mut auditor = Auditor {
+        drawing,
+        layer_names: drawing
+            .layers()
+            .map(|l| l.name.to_ascii_uppercase())
+            .collect(),
+        block_names: drawing
+            .blocks()
+            .map(|b| b.name.to_ascii_uppercase())
+            .collect(),
+        issues: vec![],
+    };
+    auditor.check_handles();
+    auditor.check_pointers();
+    for block in drawing.blocks() {
+        if !is_finite(&block.base_point) {
+            auditor.add(block.handle, AuditIssueKind::NonFiniteValue);
+        }
+        for entity in &block.entities {
+            auditor.check_entity(entity);
+        }
+    }
+    for entity in drawing.entities() {
+        auditor.check_entity(entity);
+    }
+    auditor.issues
+}
+
+pub(crate) fn audit_and_fix(drawing: &mut Drawing) -> Vec<AuditIssue> {
+    let mut issues = audit(drawing);
+    let has_issue = |kind: &AuditIssueKind| issues.iter().any(|i| i.kind == *kind);
+
+    let mut next_handle = drawing.header.next_available_handle;
+    if has_issue(&AuditIssueKind::HandleAboveHandleSeed) {
+        let max_handle = all_handles(drawing).iter().map(|h| h.0).max().unwrap_or(0);
+        next_handle = Handle(max_handle).next_handle_value();
+    }
+    if has_issue(&AuditIssueKind::DuplicateHandle) {
+        let mut seen = HashSet::new();
+        visit_handles_mut(drawing, |handle| {
+            if is_assigned(*handle) && !seen.insert(*handle) {
+                *handle = next_handle;
+                next_handle = next_handle.next_handle_value();
+            }
+        });
+    }
+    if has_issue(&AuditIssueKind::MismatchedSeqEnd) {
+        let mut assign = |handle: &mut Handle| {
+            if !is_assigned(*handle) {
+                *handle = next_handle;
+                next_handle = next_handle.next_handle_value();
+            }
+        };
+        for entity in drawing.entities_mut() {
+            visit_child_handles_mut(entity, &mut assign);
+        }
+        for block in drawing.blocks_mut() {
+            for entity in &mut block.entities {
+                visit_child_handles_mut(entity, &mut assign);
+            }
+        }
+    }
+    drawing.header.next_available_handle = next_handle;
+
+    let missing_layers = issues
+        .iter()
+        .filter_map(|i| match i.kind {
+            AuditIssueKind::MissingLayer(ref name) => Some(name.clone()),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    for name in missing_layers {
+        if !drawing.layers().any(|l| l.name.eq_ignore_ascii_case(&name)) {
+            drawing.add_layer(Layer {
+                name,
+                ..Default::default()
+            });
+        }
+    }
+
+    // anything not reported again was fixed
+    let remaining = audit(drawing);
+    for issue in &mut issues {
+        issue.fixed = !remaining
+            .iter()
+            .any(|r| r.handle == issue.handle && r.kind == issue.kind);
+    }
+    issues
+}
+
+// private implementation
+
+struct Auditor<'a> {
+    drawing: &'a Drawing,
+    layer_names: HashSet<String>,
+    block_names: HashSet<String>,
+    issues: Vec<AuditIssue>,
+}
+
+impl<'a> Auditor<'a> {
+    fn add(&mut self, handle: Handle, kind: AuditIssueKind) {
+        self.issues.push(AuditIssue {
+            handle,
+            kind,
+            fixed: false,
+        });
+    }
+    fn check_handles(&mut self) {
+        let handles = all_handles(self.drawing);
+        let mut counts = HashMap::new();
+        for handle in &handles {
+            *counts.entry(*handle).or_insert(0) += 1;
+        }
+
+        let handle_seed = self.drawing.header.next_available_handle;
+        let mut reported = HashSet::new();
+        for handle in handles {
+            if !reported.insert(handle) {
+                continue;
+            }
+            if counts[&handle] > 1 {
+                self.add(handle, AuditIssueKind::DuplicateHandle);
+            }
+            if handle.0 >= handle_seed.0 {
+                self.add(handle, AuditIssueKind::HandleAboveHandleSeed);
+            }
+        }
+    }
+    fn check_pointers(&mut self) {
+        let index = self.drawing.build_handle_index();
+        for (pointer, handle) in index.unresolved_pointers() {
+            let kind = match index.item_by_handle(handle) {
+                Some(item) if item.owner_handle() == pointer => {
+                    if is_table_item(&item) {
+                        // table entries are owned by their table, which isn't an item in the drawing
+                        continue;
+                    }
+                    AuditIssueKind::DanglingOwner(pointer)
+                }
+                _ => AuditIssueKind::DanglingPointer(pointer),
+            };
+            self.add(handle, kind);
+        }
+    }
+    fn check_entity(&mut self, entity: &Entity) {
+        let handle = entity.common.handle;
+        let layer = &entity.common.layer;
+        if !layer.is_empty() && !self.layer_names.contains(&layer.to_ascii_uppercase()) {
+            self.add(handle, AuditIssueKind::MissingLayer(layer.clone()));
+        }
+        if let EntityType::Insert(ref ins) = entity.specific {
+            if !self.block_names.contains(&ins.name.to_ascii_uppercase()) {
+                self.add(handle, AuditIssueKind::MissingBlock(ins.name.clone()));
+            }
+        }
+
+        let version = self.drawing.header.version;
+        let is_supported = entity.specific.is_supported_on_version(version);
+        if !is_supported {
+            self.add(handle, AuditIssueKind::UnsupportedOnVersion(version));
+        }
+
+        // every value that would be written is checked, so unsupported entities are checked as the newest version
+        let mut pairs = vec![];
+        let pair_version = if is_supported {
+            version
+        } else {
+            AcadVersion::R2018
+        };
+        entity.add_code_pairs(&mut pairs, pair_version, false);
+        if pairs.iter().any(|p| match p.value {
+            CodePairValue::Double(d) => !d.is_finite(),
+            _ => false,
+        }) {
+            self.add(handle, AuditIssueKind::NonFiniteValue);
+        }
+
+        let is_mismatched_seqend = match entity.specific {
+            EntityType::Insert(ref ins) => {
+                !ins.__attributes_and_handles.is_empty()
+                    && (!is_assigned(ins.__seqend_handle)
+                        || ins
+                            .__attributes_and_handles
+                            .iter()
+                            .any(|a| !is_assigned(a.1)))
+            }
+            EntityType::Polyline(ref poly) => {
+                poly.__vertices_and_handles.is_empty()
+                    || !is_assigned(poly.__seqend_handle)
+                    || poly
+                        .__vertices_and_handles
+                        .iter()
+                        .any(|v| !is_assigned(v.1))
+            }
+            _ => false,
+        };
+        if is_mismatched_seqend {
+            self.add(handle, AuditIssueKind::MismatchedSeqEnd);
+        }
+    }
+}
+
+fn is_assigned(handle: Handle) -> bool {
+    !handle.is_empty() && handle != AUTO_REPLACE_HANDLE
+}
+
+fn is_finite(p: &Point) -> bool {
+    p.x.is_finite() && p.y.is_finite() && p.z.is_finite()
+}
+
+fn is_table_item(item: &DrawingItem) -> bool {
+    !matches!(
+        item,
+        DrawingItem::Block(_) | DrawingItem::Entity(_) | DrawingItem::Object(_)
+    )
+}
+
+/// Returns every assigned handle in the drawing, including those of attributes, vertices, and `SEQEND`s.
+fn all_handles(drawing: &Drawing) -> Vec<Handle> {
+    let mut handles = vec![];
+    handles.extend(drawing.app_ids().map(|i| i.handle));
+    handles.extend(drawing.block_records().map(|i| i.handle));
+    handles.extend(drawing.dim_styles().map(|i| i.handle));
+    handles.extend(drawing.layers().map(|i| i.handle));
+    handles.extend(drawing.line_types().map(|i| i.handle));
+    handles.extend(drawing.styles().map(|i| i.handle));
+    handles.extend(drawing.ucss().map(|i| i.handle));
+    handles.extend(drawing.views().map(|i| i.handle));
+    handles.extend(drawing.view_ports().map(|i| i.handle));
+    for block in drawing.blocks() {
+        handles.push(block.handle);
+        for entity in &block.entities {
+            add_entity_handles(entity, &mut handles);
+        }
+    }
+    for entity in drawing.entities() {
+        add_entity_handles(entity, &mut handles);
+    }
+    handles.extend(drawing.objects().map(|o| o.common.handle));
+    handles.retain(|h| is_assigned(*h));
+    handles
+}
+
+fn add_entity_handles(entity: &Entity, handles: &mut Vec<Handle>) {
+    handles.push(entity.common.handle);
+    match entity.specific {
+        EntityType::Insert(ref ins) if !ins.__attributes_and_handles.is_empty() => {
+            handles.extend(ins.__attributes_and_handles.iter().map(|a| a.1));
+            handles.push(ins.__seqend_handle);
+        }
+        EntityType::Polyline(ref poly) => {
+            handles.extend(poly.__vertices_and_handles.iter().map(|v| v.1));
+            handles.push(poly.__seqend_handle);
+        }
+        _ => (),
+    }
+}
+
+/// Visits the same handles as `all_handles()`, in the same order.
+fn visit_handles_mut<F>(drawing: &mut Drawing, mut visit: F)
+where
+    F: FnMut(&mut Handle),
+{
+    for item in drawing.app_ids_mut() {
+        visit(&mut item.handle);
+    }
+    for item in drawing.block_records_mut() {
+        visit(&mut item.handle);
+    }
+    for item in drawing.dim_styles_mut() {
+        visit(&mut item.handle);
+    }
+    for item in drawing.layers_mut() {
+        visit(&mut item.handle);
+    }
+    for item in drawing.line_types_mut() {
+        visit(&mut item.handle);
+    }
+    for item in drawing.styles_mut() {
+        visit(&mut item.handle);
+    }
+    for item in drawing.ucss_mut() {
+        visit(&mut item.handle);
+    }
+    for item in drawing.views_mut() {
+        visit(&mut item.handle);
+    }
+    for item in drawing.view_ports_mut() {
+        visit(&mut item.handle);
+    }
+    for block in drawing.blocks_mut() {
+        visit(&mut block.handle);
+        for entity in &mut block.entities {
+            visit(&mut entity.common.handle);
+            visit_child_handles_mut(entity, &mut visit);
+        }
+    }
+    for entity in drawing.entities_mut() {
+        visit(&mut entity.common.handle);
+        visit_child_handles_mut(entity, &mut visit);
+    }
+    for object in drawing.objects_mut() {
+        visit(&mut object.common.handle);
+    }
+}
+
+fn visit_child_handles_mut<F>(entity: &mut Entity, visit: &mut F)
+where
+    F: FnMut(&mut Handle),
+{
+    match entity.specific {
+        EntityType::Insert(ref mut ins) if !ins.__attributes_and_handles.is_empty() => {
+            for a in ins.__attributes_and_handles.iter_mut() {
+                visit(&mut a.1);
+            }
+            visit(&mut ins.__seqend_handle);
+        }
+        EntityType::Polyline(ref mut poly) => {
+            for v in poly.__vertices_and_handles.iter_mut() {
+                visit(&mut v.1);
+            }
+            visit(&mut poly.__seqend_handle);
+        }
+        _ => (),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::entities::*;
+    use crate::enums::AcadVersion;
+    use crate::helper_functions::tests::*;
+    use crate::objects::*;
+    use crate::*;
+
+    fn kinds(issues: &[AuditIssue]) -> Vec<&AuditIssueKind> {
+        issues.iter().map(|i| &i.kind).collect()
+    }
+
+    #[test]
+    fn new_drawing_has_no_issues() {
+        let drawing = Drawing::new();
+        assert_eq!(Vec::<AuditIssue>::new(), drawing.audit());
+    }
+
+    #[test]
+    fn round_tripped_drawing_has_no_issues() {
+        let mut drawing = Drawing::new();
+        drawing.header.version = AcadVersion::R2018;
+        drawing.add_entity(Entity::new(EntityType::Line(Line::default())));
+        let mut poly = Polyline::default();
+        poly.add_vertex(&mut drawing, Vertex::default());
+        drawing.add_entity(Entity::new(EntityType::Polyline(poly)));
+        let drawing = parse_drawing(&to_test_string(&drawing));
+        assert_eq!(Vec::<AuditIssue>::new(), drawing.audit());
+    }
+
+    #[test]
+    fn duplicate_handles_and_handle_seed() {
+        let mut drawing = Drawing::new();
+        let first = drawing
+            .add_entity(Entity::new(EntityType::Line(Line::default())))
+            .common
+            .handle;
+        drawing.add_entity(Entity::new(EntityType::Line(Line::default())));
+        drawing.entities_mut().nth(1).unwrap().common.handle = first;
+        let mut high = Entity::new(EntityType::Line(Line::default()));
+        drawing.add_entity(high.clone());
+        high.common.handle = Handle(0xFFFF);
+        *drawing.entities_mut().nth(2).unwrap() = high;
+
+        let issues = drawing.audit();
+        assert_eq!(
+            vec![
+                &AuditIssueKind::DuplicateHandle,
+                &AuditIssueKind::HandleAboveHandleSeed
+            ],
+            kinds(&issues)
+        );
+        assert_eq!(first, issues[0].handle);
+        assert_eq!(Handle(0xFFFF), issues[1].handle);
+
+        let issues = drawing.audit_and_fix();
+        assert!(issues.iter().all(|i| i.fixed));
+        assert_eq!(Vec::<AuditIssue>::new(), drawing.audit());
+        assert!(drawing.header.next_available_handle.0 > 0xFFFF);
+        let handles = drawing
+            .entities()
+            .map(|e| e.common.handle)
+            .collect::<Vec<_>>();
+        assert_eq!(first, handles[0]);
+        assert_ne!(first, handles[1]);
+    }
+
+    #[test]
+    fn dangling_owner_and_pointer() {
+        let drawing = drawing_from_pairs(vec![
+            CodePair::new_str(0, "SECTION"),
+            CodePair::new_str(2, "ENTITIES"),
+            CodePair::new_str(0, "LINE"),
+            CodePair::new_str(5, "A1"),
+            CodePair::new_str(330, "B1"),
+            CodePair::new_str(347, "B2"),
+            CodePair::new_str(0, "ENDSEC"),
+            CodePair::new_str(0, "EOF"),
+        ]);
+        // there's no `$HANDSEED`, so the handles are also above it
+        let issues = drawing
+            .audit()
+            .into_iter()
+            .filter(|i| i.kind != AuditIssueKind::HandleAboveHandleSeed)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                &AuditIssueKind::DanglingOwner(Handle(0xB1)),
+                &AuditIssueKind::DanglingPointer(Handle(0xB2))
+            ],
+            kinds(&issues)
+        );
+        assert!(issues.iter().all(|i| i.handle == Handle(0xA1)));
+    }
+
+    #[test]
+    fn missing_layer_is_fixed() {
+        let mut drawing = Drawing::new();
+        drawing.add_entity(Entity::new(EntityType::Line(Line::default())));
+        drawing.entities_mut().next().unwrap().common.layer = String::from("missing");
+        let issues = drawing.audit();
+        assert_eq!(
+            vec![&AuditIssueKind::MissingLayer(String::from("missing"))],
+            kinds(&issues)
+        );
+
+        let issues = drawing.audit_and_fix();
+        assert!(issues[0].fixed);
+        assert!(drawing.layers().any(|l| l.name == "missing"));
+    }
+
+    #[test]
+    fn missing_block_unsupported_entity_and_non_finite_values() {
+        let mut drawing = Drawing::new();
+        drawing.header.version = AcadVersion::R12;
+        let insert = drawing
+            .add_entity(Entity::new(EntityType::Insert(Insert {
+                name: String::from("missing"),
+                ..Default::default()
+            })))
+            .common
+            .handle;
+        let ellipse = drawing
+            .add_entity(Entity::new(EntityType::Ellipse(Ellipse::default())))
+            .common
+            .handle;
+        let line = drawing
+            .add_entity(Entity::new(EntityType::Line(Line::new(
+                Point::origin(),
+                Point::new(f64::NAN, 0.0, 0.0),
+            ))))
+            .common
+            .handle;
+
+        let issues = drawing.audit_and_fix();
+        assert_eq!(
+            vec![
+                AuditIssue {
+                    handle: insert,
+                    kind: AuditIssueKind::MissingBlock(String::from("missing")),
+                    fixed: false,
+                },
+                AuditIssue {
+                    handle: ellipse,
+                    kind: AuditIssueKind::UnsupportedOnVersion(AcadVersion::R12),
+                    fixed: false,
+                },
+                AuditIssue {
+                    handle: line,
+                    kind: AuditIssueKind::NonFiniteValue,
+                    fixed: false,
+                },
+            ],
+            issues
+        );
+    }
+
+    #[test]
+    fn missing_seqend_handles_are_fixed() {
+        let mut drawing = Drawing::new();
+        let mut poly = Polyline::default();
+        poly.add_vertex(&mut drawing, Vertex::default());
+        drawing.add_entity(Entity::new(EntityType::Polyline(poly)));
+        drawing.add_entity(Entity::new(EntityType::Polyline(Polyline::default())));
+        if let EntityType::Polyline(ref mut poly) = drawing.entities_mut().next().unwrap().specific
+        {
+            poly.__seqend_handle = Handle::empty();
+        }
+
+        let issues = drawing.audit_and_fix();
+        assert_eq!(
+            vec![
+                &AuditIssueKind::MismatchedSeqEnd,
+                &AuditIssueKind::MismatchedSeqEnd
+            ],
+            kinds(&issues)
+        );
+        // the polyline without vertices can't be fixed
+        assert!(issues[0].fixed);
+        assert!(!issues[1].fixed);
+    }
+
+    #[test]
+    fn display_issue() {
+        let issue = AuditIssue {
+            handle: Handle(0xA1),
+            kind: AuditIssueKind::MissingLayer(String::from("some-layer")),
+            fixed: false,
+        };
+        assert_eq!(
+            "A1: the layer 'some-layer' does not exist",
+            format!("{}", issue)
+        );
+    }
+
+    #[test]
+    fn dictionary_values_are_checked() {
+        let drawing = from_section(
+            "OBJECTS",
+            vec![
+                CodePair::new_str(0, "DICTIONARY"),
+                CodePair::new_str(5, "C1"),
+                CodePair::new_str(3, "key"),
+                CodePair::new_str(350, "C2"),
+            ],
+        );
+        let issues = drawing
+            .audit()
+            .into_iter()
+            .filter(|i| i.handle == Handle(0xC1) && i.kind != AuditIssueKind::HandleAboveHandleSeed)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![&AuditIssueKind::DanglingPointer(Handle(0xC2))],
+            kinds(&issues)
+        );
+        let is_dictionary = matches!(
+            drawing.objects().next().unwrap().specific,
+            ObjectType::Dictionary(_)
+        );
+        assert!(is_dictionary);
+    }
+}
@@ -0,0 +1,653 @@
+use std::borrow::Cow;
+use std::io::Read;
+
+use byteorder::{ByteOrder, LittleEndian};
+
+use crate::code_pair_iter::CodePairIter;
+use crate::code_pair_value::un_escape_ascii_to_unicode;
+use crate::enums::AcadVersion;
+use crate::header::encoding_from_code_page;
+use crate::helper_functions::*;
+use crate::{CodePair, CodePairValue, DxfError, DxfResult, ExpectedType};
+use encoding_rs::Encoding;
+
+const BINARY_SENTINEL: &[u8] = b"AutoCAD Binary DXF";
+
+/// A code pair read by a `BinaryCodePairReader`.  Strings and binary chunks borrow from the data being read when
+/// possible.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BorrowedCodePair<'a> {
+    pub code: i32,
+    pub value: BorrowedCodePairValue<'a>,
+    /// The same offset as the `CodePair` read by `Drawing::load()`.
+    pub offset: usize,
+}
+
+/// Contains the data portion of a `BorrowedCodePair`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum BorrowedCodePairValue<'a> {
+    Boolean(i16),
+    Integer(i32),
+    Long(i64),
+    Short(i16),
+    Double(f64),
+    /// Borrowed unless the string has to be decoded from the drawing's code page or contains `\U+nnnn` or `^` escape
+    /// sequences.
+    Str(Cow<'a, str>),
+    Binary(Cow<'a, [u8]>),
+}
+
+/// Reads the code pairs of a binary DXF file that's entirely in memory, e.g., a memory-mapped file, without copying
+/// strings and binary chunks where possible.  Useful for quickly scanning a file for specific items; use
+/// `BorrowedCodePair::to_code_pair()` to keep a pair after the data is gone.
+///
+/// Strings are decoded the same way as by `Drawing::load()`: with the encoding named by `$DWGCODEPAGE` before R2007
+/// and as UTF-8 after.  The reader stops after the first error.
+///
+/// ```
+/// # use dxf::*;
+/// # use dxf::entities::*;
+/// # fn main() -> DxfResult<()> {
+/// # let mut drawing = Drawing::new();
+/// # drawing.add_entity(Entity::new(EntityType::Line(Line::default())));
+/// # let mut data = vec![];
+/// # drawing.save_binary(&mut data)?;
+/// // e.g., `data` is a memory-mapped file
+/// let mut line_count = 0;
+/// for pair in BinaryCodePairReader::new(&data)? {
+///     let pair = pair?;
+///     if pair.code == 0 && pair.value.as_str() == Some("LINE") {
+///         line_count += 1;
+///     }
+/// }
+/// assert_eq!(1, line_count);
+/// # Ok(())
+/// # }
+/// ```
+pub struct BinaryCodePairReader<'a> {
+    input: SliceInput<'a>,
+    decoder: BinaryCodePairDecoder,
+    last_header_variable: Option<String>,
+}
+
+/// Decodes the code pairs of a binary file; shared by all of the binary code pair readers.
+#[derive(Clone)]
+pub(crate) struct BinaryCodePairDecoder {
+    offset: usize,
+    code_size_detection_complete: bool,
+    codes_are_two_bytes: bool,
+    string_encoding: &'static Encoding,
+    is_encoding_fixed: bool,
+}
+
+/// The bytes decoded by a `BinaryCodePairDecoder`.  Values borrow from the bytes when they're in memory.
+pub(crate) trait BinaryInput<'a> {
+    /// Returns the next byte, or `None` at the end of the input.
+    fn next_byte(&mut self) -> Option<DxfResult<u8>>;
+    /// Fills `buffer` with the next bytes.
+    fn read_exact(&mut self, buffer: &mut [u8]) -> DxfResult<()>;
+    /// Returns the next `length` bytes.
+    fn take(&mut self, length: usize) -> DxfResult<Cow<'a, [u8]>>;
+    /// Returns the bytes up to the next NULL and skips the NULL.
+    fn take_until_null(&mut self) -> DxfResult<Cow<'a, [u8]>>;
+}
+
+/// Binary code pair data that's entirely in memory.
+pub(crate) struct SliceInput<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+/// Binary code pair data read one byte at a time through `Read`.
+#[derive(Clone)]
+pub(crate) struct ReadInput<T: Read>(pub T);
+
+/// Returns code pairs as read from a binary file already read into memory.
+pub(crate) struct BufferedBinaryCodePairIter {
+    data: Vec<u8>,
+    position: usize,
+    decoder: BinaryCodePairDecoder,
+}
+
+// public implementation
+impl<'a> BorrowedCodePair<'a> {
+    /// Copies the pair into a `CodePair`.
+    pub fn to_code_pair(&self) -> CodePair {
+        CodePair::new(self.code, self.value.to_code_pair_value(), self.offset)
+    }
+}
+
+impl<'a> BorrowedCodePairValue<'a> {
+    /// Returns the string value, or `None` for other types.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            BorrowedCodePairValue::Str(s) => Some(s),
+            _ => None,
+        }
+    }
+    /// Copies the value into a `CodePairValue`.
+    pub fn to_code_pair_value(&self) -> CodePairValue {
+        self.clone().into_code_pair_value()
+    }
+}
+
+impl<'a> BinaryCodePairReader<'a> {
+    /// Creates a reader over the entire contents of a binary DXF file.  Returns an error if `data` doesn't start with
+    /// the binary DXF sentinel.
+    pub fn new(data: &'a [u8]) -> DxfResult<Self> {
+        let data = data
+            .strip_prefix(BINARY_SENTINEL)
+            .map(|rest| rest.strip_prefix(b"\r").unwrap_or(rest))
+            .and_then(|rest| rest.strip_prefix(b"\n"))
+            .ok_or(DxfError::InvalidBinaryFile)?;
+        let mut input = SliceInput::new(data);
+        let decoder = BinaryCodePairDecoder::new(&mut input, None)?;
+        Ok(BinaryCodePairReader {
+            input,
+            decoder,
+            last_header_variable: None,
+        })
+    }
+}
+
+impl<'a> Iterator for BinaryCodePairReader<'a> {
+    type Item = DxfResult<BorrowedCodePair<'a>>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let pair = self.input.next_pair(&mut self.decoder);
+        if let Some(Ok(ref pair)) = pair {
+            self.switch_encoding(pair);
+        }
+
+        pair
+    }
+}
+
+// internal visibility only
+impl<'a> BorrowedCodePair<'a> {
+    /// Moves the pair into a `CodePair`, only copying values that are borrowed.
+    pub(crate) fn into_code_pair(self) -> CodePair {
+        CodePair::new(self.code, self.value.into_code_pair_value(), self.offset)
+    }
+}
+
+impl<'a> BorrowedCodePairValue<'a> {
+    pub(crate) fn into_code_pair_value(self) -> CodePairValue {
+        match self {
+            BorrowedCodePairValue::Boolean(b) => CodePairValue::Boolean(b),
+            BorrowedCodePairValue::Integer(i) => CodePairValue::Integer(i),
+            BorrowedCodePairValue::Long(l) => CodePairValue::Long(l),
+            BorrowedCodePairValue::Short(s) => CodePairValue::Short(s),
+            BorrowedCodePairValue::Double(d) => CodePairValue::Double(d),
+            BorrowedCodePairValue::Str(s) => CodePairValue::Str(s.into_owned()),
+            BorrowedCodePairValue::Binary(b) => CodePairValue::Binary(b.into_owned()),
+        }
+    }
+}
+
+impl BinaryCodePairDecoder {
+    /// Reads the 0x1A,0x00 that ends the sentinel at the start of `input`, i.e., everything after the first line of
+    /// the file.  Strings are read with `string_encoding` if specified, otherwise with the encoding specified by
+    /// `$DWGCODEPAGE`, starting with Windows-1252.
+    pub(crate) fn new<'a, I: BinaryInput<'a>>(
+        input: &mut I,
+        string_encoding: Option<&'static Encoding>,
+    ) -> DxfResult<Self> {
+        for (i, &expected) in [0x1A, 0x00].iter().enumerate() {
+            match input.next_byte() {
+                Some(Ok(actual)) if actual == expected => (),
+                Some(Ok(_)) => return Err(DxfError::UnexpectedByte(expected, 18 + i)),
+                Some(Err(e)) => return Err(e),
+                None => return Err(DxfError::UnexpectedEndOfInput),
+            }
+        }
+
+        Ok(BinaryCodePairDecoder {
+            offset: 20,
+            code_size_detection_complete: false,
+            codes_are_two_bytes: false,
+            string_encoding: string_encoding.unwrap_or(encoding_rs::WINDOWS_1252),
+            is_encoding_fixed: string_encoding.is_some(),
+        })
+    }
+    pub(crate) fn read_as_utf8(&mut self) {
+        self.string_encoding = encoding_rs::UTF_8;
+    }
+    pub(crate) fn read_with_code_page_encoding(&mut self, encoding: &'static Encoding) {
+        if !self.is_encoding_fixed {
+            self.string_encoding = encoding;
+        }
+    }
+    /// Returns `None` at the end of `input`.
+    pub(crate) fn next<'a, I: BinaryInput<'a>>(
+        &mut self,
+        input: &mut I,
+    ) -> Option<DxfResult<BorrowedCodePair<'a>>> {
+        let code = match input.next_byte() {
+            Some(Ok(c)) => c,
+            Some(Err(e)) => return Some(Err(e)),
+            None => return None,
+        };
+        Some(self.read_code_pair(code, input))
+    }
+}
+
+impl<'a> SliceInput<'a> {
+    pub(crate) fn new(data: &'a [u8]) -> Self {
+        SliceInput { data, position: 0 }
+    }
+    /// Reads the next pair and stops at the first error.
+    pub(crate) fn next_pair(
+        &mut self,
+        decoder: &mut BinaryCodePairDecoder,
+    ) -> Option<DxfResult<BorrowedCodePair<'a>>> {
+        let result = decoder.next(self);
+        if let Some(Err(_)) = result {
+            // the position is unknown after an error
+            self.position = self.data.len();
+        }
+
+        result
+    }
+}
+
+impl<'a> BinaryInput<'a> for SliceInput<'a> {
+    fn next_byte(&mut self) -> Option<DxfResult<u8>> {
+        let b = *self.data.get(self.position)?;
+        self.position += 1;
+        Some(Ok(b))
+    }
+    fn read_exact(&mut self, buffer: &mut [u8]) -> DxfResult<()> {
+        let bytes = self.take(buffer.len())?;
+        buffer.copy_from_slice(&bytes);
+        Ok(())
+    }
+    fn take(&mut self, length: usize) -> DxfResult<Cow<'a, [u8]>> {
+        match self.data.get(self.position..self.position + length) {
+            Some(bytes) => {
+                self.position += length;
+                Ok(Cow::Borrowed(bytes))
+            }
+            None => Err(DxfError::UnexpectedEndOfInput),
+        }
+    }
+    fn take_until_null(&mut self) -> DxfResult<Cow<'a, [u8]>> {
+        let rest = &self.data[self.position.min(self.data.len())..];
+        let length = match rest.iter().position(|&b| b == 0) {
+            Some(length) => length,
+            None => return Err(DxfError::UnexpectedEndOfInput),
+        };
+        self.position += length + 1;
+        Ok(Cow::Borrowed(&rest[..length]))
+    }
+}
+
+impl<T: Read> BinaryInput<'static> for ReadInput<T> {
+    fn next_byte(&mut self) -> Option<DxfResult<u8>> {
+        read_u8(&mut self.0).map(|b| b.map_err(DxfError::IoError))
+    }
+    fn read_exact(&mut self, buffer: &mut [u8]) -> DxfResult<()> {
+        for b in buffer.iter_mut() {
+            *b = read_u8_strict(&mut self.0)?;
+        }
+
+        Ok(())
+    }
+    fn take(&mut self, length: usize) -> DxfResult<Cow<'static, [u8]>> {
+        let mut bytes = vec![0; length];
+        self.read_exact(&mut bytes)?;
+        Ok(Cow::Owned(bytes))
+    }
+    fn take_until_null(&mut self) -> DxfResult<Cow<'static, [u8]>> {
+        let mut bytes = vec![];
+        loop {
+            match read_u8_strict(&mut self.0)? {
+                0 => break,
+                b => bytes.push(b),
+            }
+        }
+
+        Ok(Cow::Owned(bytes))
+    }
+}
+
+impl BufferedBinaryCodePairIter {
+    /// `data` is everything after the first line of the file.
+    pub(crate) fn new(
+        data: Vec<u8>,
+        string_encoding: Option<&'static Encoding>,
+    ) -> DxfResult<Self> {
+        let mut input = SliceInput::new(&data);
+        let decoder = BinaryCodePairDecoder::new(&mut input, string_encoding)?;
+        let position = input.position;
+        Ok(BufferedBinaryCodePairIter {
+            data,
+            position,
+            decoder,
+        })
+    }
+}
+
+impl CodePairIter for BufferedBinaryCodePairIter {
+    fn read_as_utf8(&mut self) {
+        self.decoder.read_as_utf8();
+    }
+    fn read_with_code_page_encoding(&mut self, encoding: &'static Encoding) {
+        self.decoder.read_with_code_page_encoding(encoding);
+    }
+}
+
+impl Iterator for BufferedBinaryCodePairIter {
+    type Item = DxfResult<CodePair>;
+    fn next(&mut self) -> Option<DxfResult<CodePair>> {
+        let mut input = SliceInput {
+            data: &self.data,
+            position: self.position,
+        };
+        let pair = input.next_pair(&mut self.decoder);
+        self.position = input.position;
+        pair.map(|pair| pair.map(|pair| pair.to_code_pair()))
+    }
+}
+
+// private implementation
+impl<'a> BinaryCodePairReader<'a> {
+    /// Switches the string encoding after `$ACADVER` or `$DWGCODEPAGE` the same way as `Header::read()`.
+    fn switch_encoding(&mut self, pair: &BorrowedCodePair<'a>) {
+        match (pair.code, &pair.value, &self.last_header_variable) {
+            (9, BorrowedCodePairValue::Str(name), _) => {
+                self.last_header_variable = Some(name.to_string());
+            }
+            (1, BorrowedCodePairValue::Str(version), Some(name))
+                if name == "$ACADVER"
+                    && AcadVersion::from_safe(version.to_string()) >= AcadVersion::R2007 =>
+            {
+                self.decoder.read_as_utf8();
+            }
+            (3, BorrowedCodePairValue::Str(code_page), Some(name))
+                if name == "$DWGCODEPAGE" && self.decoder.string_encoding != encoding_rs::UTF_8 =>
+            {
+                if let Some(encoding) = encoding_from_code_page(code_page) {
+                    self.decoder.read_with_code_page_encoding(encoding);
+                }
+            }
+            _ => (),
+        }
+    }
+}
+
+impl BinaryCodePairDecoder {
+    fn read_code_pair<'a, I: BinaryInput<'a>>(
+        &mut self,
+        code: u8,
+        input: &mut I,
+    ) -> DxfResult<BorrowedCodePair<'a>> {
+        let mut buffer = [0; 8];
+        let mut code = i32::from(code);
+        self.offset += 1;
+        if self.codes_are_two_bytes {
+            // post R13 codes are 2 bytes, read the second byte of the code
+            input.read_exact(&mut buffer[..1])?;
+            code += i32::from(buffer[0]) << 8;
+            self.offset += 1;
+        } else if code == 255 {
+            // pre R13 codes are either 1 or 3 bytes
+            input.read_exact(&mut buffer[..2])?;
+            code = i32::from(LittleEndian::read_i16(&buffer));
+            self.offset += 2;
+        }
+
+        let expected_type = match ExpectedType::expected_type(code) {
+            Some(t) => t,
+            None => return Err(DxfError::UnexpectedEnumValue(self.offset)),
+        };
+        let (value, read_bytes) = match expected_type {
+            ExpectedType::Boolean => {
+                // after R13 bools are encoded as a single byte
+                if self.codes_are_two_bytes {
+                    input.read_exact(&mut buffer[..1])?;
+                    (BorrowedCodePairValue::Boolean(i16::from(buffer[0])), 1)
+                } else {
+                    input.read_exact(&mut buffer[..2])?;
+                    let b = LittleEndian::read_i16(&buffer);
+                    (BorrowedCodePairValue::Boolean(b), 2)
+                }
+            }
+            ExpectedType::Integer => {
+                input.read_exact(&mut buffer[..4])?;
+                (
+                    BorrowedCodePairValue::Integer(LittleEndian::read_i32(&buffer)),
+                    4,
+                )
+            }
+            ExpectedType::Long => {
+                input.read_exact(&mut buffer)?;
+                (
+                    BorrowedCodePairValue::Long(LittleEndian::read_i64(&buffer)),
+                    8,
+                )
+            }
+            ExpectedType::Short => {
+                input.read_exact(&mut buffer[..2])?;
+                (
+                    BorrowedCodePairValue::Short(LittleEndian::read_i16(&buffer)),
+                    2,
+                )
+            }
+            ExpectedType::Double => {
+                input.read_exact(&mut buffer)?;
+                (
+                    BorrowedCodePairValue::Double(LittleEndian::read_f64(&buffer)),
+                    8,
+                )
+            }
+            ExpectedType::Str => {
+                let mut value = input.take_until_null()?;
+                if !self.code_size_detection_complete && code == 0 && value.is_empty() {
+                    // If this is the first pair being read and the code is 0, the only valid string value is "SECTION".
+                    // If the read value is instead empty, that means the string reader found a single 0x00 byte which
+                    // indicates that this is a post R13 binary file where codes are always read as 2 bytes.  The 0x00
+                    // byte was really the second byte of {0x00, 0x00}, so we need to do one more string read to catch
+                    // the reader up.
+                    self.codes_are_two_bytes = true;
+                    self.offset += 1; // account for the NULL byte that was interpreted as an empty string
+                    value = input.take_until_null()?; // now read the actual value
+                }
+                let read_bytes = value.len() + 1; // +1 to account for the NULL terminator
+                (
+                    BorrowedCodePairValue::Str(self.decode_string(value)),
+                    read_bytes,
+                )
+            }
+            ExpectedType::Binary => {
+                input.read_exact(&mut buffer[..1])?;
+                let length = usize::from(buffer[0]);
+                (
+                    BorrowedCodePairValue::Binary(input.take(length)?),
+                    length + 1, // +1 to account for initial length byte
+                )
+            }
+        };
+        self.offset += read_bytes;
+        self.code_size_detection_complete = true;
+
+        Ok(BorrowedCodePair {
+            code,
+            value,
+            offset: self.offset,
+        })
+    }
+    /// Decodes a string with the current encoding the same way as `TextCodePairIter`.
+    fn decode_string<'a>(&self, bytes: Cow<'a, [u8]>) -> Cow<'a, str> {
+        let value =
+            match bytes {
+                Cow::Borrowed(bytes) => self.string_encoding.decode_without_bom_handling(bytes).0,
+                Cow::Owned(bytes) => {
+                    let decoded = match self.string_encoding.decode_without_bom_handling(&bytes).0 {
+                        Cow::Borrowed(_) => None,
+                        Cow::Owned(decoded) => Some(decoded),
+                    };
+                    // borrowed means `bytes` are already valid UTF-8
+                    Cow::Owned(decoded.unwrap_or_else(|| {
+                        String::from_utf8(bytes).expect("should be valid UTF-8")
+                    }))
+                }
+            };
+        let value = if self.string_encoding != encoding_rs::UTF_8 && value.contains("\\U+") {
+            Cow::Owned(un_escape_ascii_to_unicode(&value))
+        } else {
+            value
+        };
+        match value {
+            Cow::Borrowed(s) => CodePairValue::un_escape_string(s),
+            Cow::Owned(s) => {
+                let un_escaped = match CodePairValue::un_escape_string(&s) {
+                    Cow::Borrowed(_) => None,
+                    Cow::Owned(un_escaped) => Some(un_escaped),
+                };
+                Cow::Owned(un_escaped.unwrap_or(s))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+impl BinaryCodePairDecoder {
+    /// Creates a decoder for the pairs after the first one of a file, when the size of the codes is known.
+    pub(crate) fn after_first_pair(codes_are_two_bytes: bool) -> Self {
+        BinaryCodePairDecoder {
+            offset: 0,
+            code_size_detection_complete: true,
+            codes_are_two_bytes,
+            string_encoding: encoding_rs::WINDOWS_1252,
+            is_encoding_fixed: false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::code_pair_iter::new_streaming_code_pair_iter_from_reader;
+    use crate::entities::*;
+    use crate::enums::*;
+    use crate::helper_functions::tests::*;
+    use crate::*;
+    use std::borrow::Cow;
+    use std::fs::read;
+
+    /// Reads the pairs the same way as `Drawing::stream()`.
+    fn read_streaming(data: &[u8]) -> Vec<CodePair> {
+        let first_line_length = data.iter().position(|&b| b == b'\n').unwrap() + 1;
+        let data = data[first_line_length..].to_vec();
+        new_streaming_code_pair_iter_from_reader(
+            std::io::Cursor::new(data),
+            None,
+            String::from("AutoCAD Binary DXF"),
+        )
+        .unwrap()
+        .map(|pair| pair.unwrap())
+        .collect()
+    }
+
+    fn read_borrowed(data: &[u8]) -> Vec<BorrowedCodePair<'_>> {
+        BinaryCodePairReader::new(data)
+            .unwrap()
+            .map(|pair| pair.unwrap())
+            .collect()
+    }
+
+    fn assert_same_pairs(data: &[u8]) {
+        let expected = read_streaming(data);
+        let actual = read_borrowed(data);
+        assert_eq!(expected.len(), actual.len());
+        for (expected, actual) in expected.iter().zip(actual.iter()) {
+            let actual = actual.to_code_pair();
+            assert_eq!(expected.code, actual.code);
+            assert_eq!(expected.value, actual.value);
+            assert_eq!(expected.offset, actual.offset);
+        }
+    }
+
+    fn drawing_with_text(version: AcadVersion, value: &str) -> Drawing {
+        let mut drawing = Drawing::new();
+        drawing.header.version = version;
+        let text = Text {
+            value: String::from(value),
+            ..Default::default()
+        };
+        drawing.add_entity(Entity::new(EntityType::Text(text)));
+        drawing
+    }
+
+    #[test]
+    fn read_pre_r13_file() {
+        assert_same_pairs(&read("./src/misc_tests/diamond-bin.dxf").unwrap());
+    }
+
+    #[test]
+    fn read_post_r13_file() {
+        let drawing = drawing_with_text(AcadVersion::R2000, "abc");
+        assert_same_pairs(&to_binary(&drawing));
+    }
+
+    fn pre_r13_data(pairs: &[u8]) -> Vec<u8> {
+        let mut data = b"AutoCAD Binary DXF\r\n\x1A\x00".to_vec();
+        data.extend_from_slice(pairs);
+        data
+    }
+
+    #[test]
+    fn strings_are_borrowed() {
+        let data = to_binary(&drawing_with_text(AcadVersion::R2000, "abc"));
+        let pairs = read_borrowed(&data);
+        assert!(pairs
+            .iter()
+            .any(|p| p.value == BorrowedCodePairValue::Str(Cow::Borrowed("abc"))));
+    }
+
+    #[test]
+    fn non_ascii_strings_are_copied() {
+        // code 1, "caf\xE9" as Windows-1252, NUL
+        let data = pre_r13_data(&[0x01, b'c', b'a', b'f', 0xE9, 0x00]);
+        let pairs = read_borrowed(&data);
+        match pairs[0].value {
+            BorrowedCodePairValue::Str(Cow::Owned(ref s)) => assert_eq!("caf\u{E9}", s),
+            _ => panic!("expected an owned string"),
+        }
+        assert_same_pairs(&data);
+    }
+
+    #[test]
+    fn read_binary_chunk() {
+        // code 310, length 2, data [0x01, 0x02]
+        let data = pre_r13_data(&[0xFF, 0x36, 0x01, 0x02, 0x01, 0x02]);
+        let pairs = read_borrowed(&data);
+        assert_eq!(1, pairs.len());
+        assert_eq!(310, pairs[0].code);
+        assert_eq!(
+            BorrowedCodePairValue::Binary(Cow::Borrowed(&[0x01, 0x02])),
+            pairs[0].value
+        );
+        assert_same_pairs(&data);
+    }
+
+    #[test]
+    fn reader_stops_after_truncated_pair() {
+        let data = to_binary(&drawing_with_text(AcadVersion::R2000, "abc"));
+        let data = &data[..data.len() - 2];
+        let mut reader = BinaryCodePairReader::new(data).unwrap();
+        match reader.by_ref().find(|pair| pair.is_err()) {
+            Some(Err(DxfError::UnexpectedEndOfInput)) => (),
+            _ => panic!("expected the end of the input"),
+        }
+        assert!(reader.next().is_none());
+    }
+
+    #[test]
+    fn reader_requires_binary_sentinel() {
+        let data = to_test_string(&Drawing::new());
+        match BinaryCodePairReader::new(data.as_bytes()) {
+            Err(DxfError::InvalidBinaryFile) => (),
+            _ => panic!("expected an invalid binary file"),
+        }
+    }
+}
@@ -1,6 +1,6 @@
 use crate::{
-    CodePair, CodePairValue, Drawing, DrawingItem, DrawingItemMut, DxfError, DxfResult,
-    ExtensionGroup, Handle, Point, XData,
+    BoundingBox, CodePair, CodePairValue, Drawing, DrawingItem, DrawingItemMut, DxfError,
+    DxfResult, ExtensionGroup, Handle, Point, XData,
 };
 
 use crate::code_pair_put_back::CodePairPutBack;
@@ -95,6 +95,14 @@ impl Block {
     pub fn normalize(&mut self) {
         default_if_empty(&mut self.layer, "0");
     }
+    /// Returns the extents of the block's entities in block coordinates, including the contents of nested block
+    /// references, or `None` if the block has no entities with extents.
+    pub fn bounding_box(&self, drawing: &Drawing) -> Option<BoundingBox> {
+        self.entities
+            .iter()
+            .map(|e| e.bounding_box_with_blocks(drawing))
+            .fold(None, BoundingBox::union_option)
+    }
 }
 
 impl Default for Block {
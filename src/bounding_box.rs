@@ -0,0 +1,110 @@
+use crate::{Point, Vector};
+
+/// An axis-aligned box in world coordinates.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct BoundingBox {
+    /// The corner with the smallest X, Y, and Z values.
+    pub min: Point,
+    /// The corner with the largest X, Y, and Z values.
+    pub max: Point,
+}
+
+// public implementation
+impl BoundingBox {
+    /// Creates a new `BoundingBox` containing both points.
+    pub fn new(a: Point, b: Point) -> Self {
+        let mut bounding_box = BoundingBox::from_point(&a);
+        bounding_box.add_point(&b);
+        bounding_box
+    }
+    /// Creates a new `BoundingBox` containing only the specified point.
+    pub fn from_point(point: &Point) -> Self {
+        BoundingBox {
+            min: point.clone(),
+            max: point.clone(),
+        }
+    }
+    /// Creates the smallest `BoundingBox` containing all of the points, or `None` if there are none.
+    pub fn from_points<'a, I>(points: I) -> Option<Self>
+    where
+        I: IntoIterator<Item = &'a Point>,
+    {
+        let mut result: Option<BoundingBox> = None;
+        for point in points {
+            match result {
+                Some(ref mut bounding_box) => bounding_box.add_point(point),
+                None => result = Some(BoundingBox::from_point(point)),
+            }
+        }
+        result
+    }
+    /// Grows the box to contain the specified point.
+    pub fn add_point(&mut self, point: &Point) {
+        self.min.x = self.min.x.min(point.x);
+        self.min.y = self.min.y.min(point.y);
+        self.min.z = self.min.z.min(point.z);
+        self.max.x = self.max.x.max(point.x);
+        self.max.y = self.max.y.max(point.y);
+        self.max.z = self.max.z.max(point.z);
+    }
+    /// Returns the smallest box containing both boxes.
+    pub fn union(&self, other: &BoundingBox) -> BoundingBox {
+        let mut result = self.clone();
+        result.add_point(&other.min);
+        result.add_point(&other.max);
+        result
+    }
+    /// Returns whether the point is inside or on the boundary of the box.
+    pub fn contains(&self, point: &Point) -> bool {
+        point.x >= self.min.x
+            && point.y >= self.min.y
+            && point.z >= self.min.z
+            && point.x <= self.max.x
+            && point.y <= self.max.y
+            && point.z <= self.max.z
+    }
+    /// Returns the extent of the box along each axis.
+    pub fn size(&self) -> Vector {
+        Vector::new(
+            self.max.x - self.min.x,
+            self.max.y - self.min.y,
+            self.max.z - self.min.z,
+        )
+    }
+    /// Returns the center of the box.
+    pub fn center(&self) -> Point {
+        Point::new(
+            (self.min.x + self.max.x) / 2.0,
+            (self.min.y + self.max.y) / 2.0,
+            (self.min.z + self.max.z) / 2.0,
+        )
+    }
+    /// Returns the eight corners of the box.
+    pub fn corners(&self) -> Vec<Point> {
+        let mut corners = vec![];
+        for x in &[self.min.x, self.max.x] {
+            for y in &[self.min.y, self.max.y] {
+                for z in &[self.min.z, self.max.z] {
+                    corners.push(Point::new(*x, *y, *z));
+                }
+            }
+        }
+        corners
+    }
+}
+
+// internal visibility only
+impl BoundingBox {
+    /// Combines two optional boxes.
+    pub(crate) fn union_option(
+        a: Option<BoundingBox>,
+        b: Option<BoundingBox>,
+    ) -> Option<BoundingBox> {
+        match (a, b) {
+            (Some(a), Some(b)) => Some(a.union(&b)),
+            (Some(a), None) => Some(a),
+            (None, b) => b,
+        }
+    }
+}
@@ -1,12 +1,64 @@
 use crate::{CodePair, CodePairValue, DxfError, DxfResult, ExpectedType};
 
+use crate::binary_code_pair_reader::{
+    BinaryCodePairDecoder, BorrowedCodePair, BufferedBinaryCodePairIter, ReadInput,
+};
 use crate::code_pair_value::un_escape_ascii_to_unicode;
+use crate::entities::EntityType;
 use crate::helper_functions::*;
+use crate::objects::ObjectType;
 use encoding_rs::Encoding;
+#[cfg(feature = "parallel")]
+use std::any::Any;
 use std::io::{Cursor, Read};
 
+#[cfg(feature = "async")]
+use crate::code_pair_put_back::CodePairPutBack;
+#[cfg(feature = "async")]
+use crate::LoadOptions;
+#[cfg(feature = "async")]
+use futures_util::io::{AsyncRead, AsyncReadExt};
+#[cfg(feature = "async")]
+use std::cell::{Cell, RefCell};
+#[cfg(feature = "async")]
+use std::io;
+#[cfg(feature = "async")]
+use std::rc::Rc;
+
 pub(crate) trait CodePairIter: Iterator<Item = DxfResult<CodePair>> {
     fn read_as_utf8(&mut self);
+    /// Reads subsequent strings with the encoding specified by `$DWGCODEPAGE`, unless an encoding was explicitly
+    /// requested.
+    fn read_with_code_page_encoding(&mut self, encoding: &'static Encoding);
+    /// Returns `true` if the invalid code pair has been fully consumed when an error is returned, so reading can
+    /// continue with the next pair, or with the next item if `is_misaligned()`.
+    fn can_resume_after_error(&self) -> bool {
+        false
+    }
+    /// Returns `true` if the error that was just returned showed the code pairs to be out of alignment with the
+    /// lines, e.g., because a line is missing.  The next read then skips lines up to the next `0` code pair that
+    /// starts a known item.
+    fn is_misaligned(&self) -> bool {
+        false
+    }
+    /// Returns the text that hasn't been read yet if the code pairs are read from text held in memory, so it can be
+    /// parsed elsewhere.
+    #[cfg(feature = "parallel")]
+    fn unread_text(&self) -> Option<UnreadText<'_>> {
+        None
+    }
+    /// Skips over `byte_count` bytes spanning `line_count` lines of the text returned by `unread_text()`.
+    #[cfg(feature = "parallel")]
+    fn skip_text(&mut self, _byte_count: usize, _line_count: usize) {}
+}
+
+/// The text left to read by a `CodePairIter`.
+#[cfg(feature = "parallel")]
+pub(crate) struct UnreadText<'a> {
+    pub text: &'a [u8],
+    /// The line number of the first line of `text`.
+    pub offset: usize,
+    pub string_encoding: &'static Encoding,
 }
 
 /// Directly returns code pairs; primarily used in tests.
@@ -19,6 +71,9 @@ impl CodePairIter for DirectCodePairIter {
     fn read_as_utf8(&mut self) {
         // noop
     }
+    fn read_with_code_page_encoding(&mut self, _encoding: &'static Encoding) {
+        // noop
+    }
 }
 
 impl Iterator for DirectCodePairIter {
@@ -42,18 +97,57 @@ impl DirectCodePairIter {
 }
 
 /// Returns code pairs as read from text.  Handles the most common DXF files and when parsed from strings.
+#[derive(Clone)]
 pub(crate) struct TextCodePairIter<T: Read> {
     reader: T,
     string_encoding: &'static Encoding,
+    is_encoding_fixed: bool,
     first_line: String,
     read_first_line: bool,
     offset: usize,
+    /// Lines that were read ahead, the next one last.
+    put_back_lines: Vec<String>,
+    last_value_was_zero: bool,
+    is_misaligned: bool,
 }
 
-impl<T: Read> CodePairIter for TextCodePairIter<T> {
+impl<T: Read + 'static> CodePairIter for TextCodePairIter<T> {
     fn read_as_utf8(&mut self) {
         self.string_encoding = encoding_rs::UTF_8;
     }
+    fn read_with_code_page_encoding(&mut self, encoding: &'static Encoding) {
+        if !self.is_encoding_fixed {
+            self.string_encoding = encoding;
+        }
+    }
+    fn can_resume_after_error(&self) -> bool {
+        // values are always on their own line
+        true
+    }
+    fn is_misaligned(&self) -> bool {
+        self.is_misaligned
+    }
+    #[cfg(feature = "parallel")]
+    fn unread_text(&self) -> Option<UnreadText<'_>> {
+        if !self.read_first_line || !self.put_back_lines.is_empty() || self.is_misaligned {
+            return None;
+        }
+
+        let cursor = (&self.reader as &dyn Any).downcast_ref::<Cursor<Vec<u8>>>()?;
+        let position = cursor.position() as usize;
+        Some(UnreadText {
+            text: &cursor.get_ref()[position..],
+            offset: self.offset + 1,
+            string_encoding: self.string_encoding,
+        })
+    }
+    #[cfg(feature = "parallel")]
+    fn skip_text(&mut self, byte_count: usize, line_count: usize) {
+        if let Some(cursor) = (&mut self.reader as &mut dyn Any).downcast_mut::<Cursor<Vec<u8>>>() {
+            cursor.set_position(cursor.position() + byte_count as u64);
+            self.offset += line_count;
+        }
+    }
 }
 
 impl<T: Read> Iterator for TextCodePairIter<T> {
@@ -64,35 +158,42 @@ impl<T: Read> Iterator for TextCodePairIter<T> {
 }
 
 impl<T: Read> TextCodePairIter<T> {
+    /// Strings are read with `string_encoding` if specified, otherwise with the encoding specified by
+    /// `$DWGCODEPAGE`, starting with Windows-1252.
     pub fn new(
         reader: T,
-        string_encoding: &'static Encoding,
+        string_encoding: Option<&'static Encoding>,
         first_line: String,
         offset: usize,
     ) -> Self {
         TextCodePairIter {
             reader,
-            string_encoding,
+            string_encoding: string_encoding.unwrap_or(encoding_rs::WINDOWS_1252),
+            is_encoding_fixed: string_encoding.is_some(),
             first_line,
             read_first_line: false,
             offset,
+            put_back_lines: vec![],
+            last_value_was_zero: false,
+            is_misaligned: false,
         }
     }
     fn read_code_pair(&mut self) -> Option<DxfResult<CodePair>> {
+        if self.is_misaligned {
+            self.is_misaligned = false;
+            try_into_option!(self.skip_to_next_item());
+        }
+
         // Read code.  If no line is available, fail gracefully.
         let code_line = if self.read_first_line {
-            self.offset += 1;
-            match read_line(&mut self.reader, true, encoding_rs::WINDOWS_1252) {
+            match self.next_line(true, encoding_rs::WINDOWS_1252) {
                 Some(Ok(v)) => v,
                 Some(Err(e)) => return Some(Err(e)),
                 None => return None,
             }
         } else {
             self.read_first_line = true;
-
-            // .clone() is fine because it'll only ever be called once and the only valid
-            // values that might be cloned are: "0" and "999"; all others are errors.
-            self.first_line.clone()
+            std::mem::take(&mut self.first_line)
         };
         let code_line = code_line.trim();
         if code_line.is_empty() {
@@ -101,39 +202,62 @@ impl<T: Read> TextCodePairIter<T> {
         }
 
         let code_offset = self.offset;
-        let code = try_into_option!(parse_i32(String::from(code_line), code_offset));
+        let code = parse_i32(String::from(code_line), code_offset);
+
+        // A code line that's really the type of an item means the `0` code before it was read as the last value.
+        let item_type = match code {
+            Err(_) if self.last_value_was_zero && is_item_type(code_line) => {
+                Some(String::from(code_line))
+            }
+            _ => None,
+        };
 
         // Read value.  If no line is available die horribly.
-        self.offset += 1;
-        let value_line = match read_line(&mut self.reader, false, self.string_encoding) {
+        let value_line = match self.next_line(false, self.string_encoding) {
             Some(Ok(v)) => v,
             Some(Err(e)) => return Some(Err(e)),
             None => return Some(Err(DxfError::UnexpectedEndOfInput)),
         };
+        let value_is_zero = value_line.trim() == "0";
+        let code = match (item_type, code) {
+            (Some(item_type), Err(e)) => {
+                self.put_back_line(value_line);
+                self.put_back_line(item_type);
+                self.put_back_line(String::from("0"));
+                self.is_misaligned = true;
+                return Some(Err(e));
+            }
+            (_, code) => code,
+        };
+
+        // an invalid code is only reported after its value is read so the next read starts at the next pair
+        let pair = code.and_then(|code| self.parse_value(code, value_line, code_offset));
+        if pair.is_err() {
+            try_into_option!(self.check_alignment());
+        }
 
+        self.last_value_was_zero = value_is_zero;
+        Some(pair)
+    }
+    fn parse_value(
+        &self,
+        code: i32,
+        value_line: String,
+        code_offset: usize,
+    ) -> DxfResult<CodePair> {
         // construct the value pair
         let expected_type = match ExpectedType::expected_type(code) {
             Some(t) => t,
-            None => return Some(Err(DxfError::UnexpectedEnumValue(self.offset))),
+            None => return Err(DxfError::UnexpectedEnumValue(self.offset)),
         };
         let value = match expected_type {
-            ExpectedType::Boolean => {
-                CodePairValue::Boolean(try_into_option!(parse_i16(value_line, self.offset)))
-            }
-            ExpectedType::Integer => {
-                CodePairValue::Integer(try_into_option!(parse_i32(value_line, self.offset)))
-            }
-            ExpectedType::Long => {
-                CodePairValue::Long(try_into_option!(parse_i64(value_line, self.offset)))
-            }
-            ExpectedType::Short => {
-                CodePairValue::Short(try_into_option!(parse_i16(value_line, self.offset)))
-            }
-            ExpectedType::Double => {
-                CodePairValue::Double(try_into_option!(parse_f64(value_line, self.offset)))
-            }
+            ExpectedType::Boolean => CodePairValue::Boolean(parse_i16(value_line, self.offset)?),
+            ExpectedType::Integer => CodePairValue::Integer(parse_i32(value_line, self.offset)?),
+            ExpectedType::Long => CodePairValue::Long(parse_i64(value_line, self.offset)?),
+            ExpectedType::Short => CodePairValue::Short(parse_i16(value_line, self.offset)?),
+            ExpectedType::Double => CodePairValue::Double(parse_f64(value_line, self.offset)?),
             ExpectedType::Str => {
-                let value_line = if self.string_encoding == encoding_rs::WINDOWS_1252 {
+                let value_line = if self.string_encoding != encoding_rs::UTF_8 {
                     un_escape_ascii_to_unicode(&value_line)
                 } else {
                     value_line
@@ -143,28 +267,100 @@ impl<T: Read> TextCodePairIter<T> {
             }
             ExpectedType::Binary => {
                 let mut data = vec![];
-                match parse_hex_string(&value_line, &mut data, self.offset) {
-                    Ok(()) => CodePairValue::Binary(data),
-                    Err(e) => return Some(Err(e)),
-                }
+                parse_hex_string(&value_line, &mut data, self.offset)?;
+                CodePairValue::Binary(data)
             }
         };
 
-        Some(Ok(CodePair::new(code, value, code_offset)))
+        Ok(CodePair::new(code, value, code_offset))
+    }
+    /// Called after a pair can't be read.  If the next line isn't a code, the pair wasn't really invalid but out of
+    /// alignment with the lines, e.g., because a line is missing.
+    fn check_alignment(&mut self) -> DxfResult<()> {
+        if let Some(next_line) = self.next_line(true, encoding_rs::WINDOWS_1252) {
+            let next_line = next_line?;
+            self.is_misaligned = next_line.trim().parse::<i32>().is_err();
+            self.put_back_line(next_line);
+        }
+
+        Ok(())
+    }
+    /// Skips lines up to the next `0` code line followed by the type of an item, e.g., `LINE`, so the next read is
+    /// back in alignment.
+    fn skip_to_next_item(&mut self) -> DxfResult<()> {
+        // the value line of the misaligned pair could be the `0` code of the next item
+        let mut previous_was_zero = self.last_value_was_zero;
+        loop {
+            let line = match self.next_line(false, encoding_rs::WINDOWS_1252) {
+                Some(Ok(line)) => line,
+                Some(Err(e)) => return Err(e),
+                None => return Ok(()),
+            };
+            if previous_was_zero && is_item_type(line.trim()) {
+                self.put_back_line(line);
+                self.put_back_line(String::from("0"));
+                self.last_value_was_zero = false;
+                return Ok(());
+            }
+
+            previous_was_zero = line.trim() == "0";
+        }
     }
+    fn next_line(
+        &mut self,
+        allow_bom: bool,
+        encoding: &'static Encoding,
+    ) -> Option<DxfResult<String>> {
+        self.offset += 1;
+        match self.put_back_lines.pop() {
+            Some(line) => Some(Ok(line)),
+            None => read_line(&mut self.reader, allow_bom, encoding),
+        }
+    }
+    fn put_back_line(&mut self, line: String) {
+        self.offset -= 1;
+        self.put_back_lines.push(line);
+    }
+}
+
+/// Returns `true` if `line` is the type of an item that follows a `0` code, e.g., `SECTION` or `LINE`.
+fn is_item_type(line: &str) -> bool {
+    matches!(
+        line,
+        "SECTION"
+            | "ENDSEC"
+            | "EOF"
+            | "CLASS"
+            | "TABLE"
+            | "ENDTAB"
+            | "APPID"
+            | "BLOCK_RECORD"
+            | "DIMSTYLE"
+            | "LAYER"
+            | "LTYPE"
+            | "STYLE"
+            | "UCS"
+            | "VIEW"
+            | "VPORT"
+            | "BLOCK"
+            | "ENDBLK"
+    ) || EntityType::from_type_string(line).is_some()
+        || ObjectType::from_type_string(line).is_some()
 }
 
 /// Returns code pairs as read from a binary file.  Usually created _after_ the first line of a file has been read.
+#[derive(Clone)]
 pub(crate) struct BinaryCodePairIter<T: Read> {
-    reader: T,
-    code_size_detection_complete: bool,
-    codes_are_two_bytes: bool,
-    offset: usize,
+    input: ReadInput<T>,
+    decoder: BinaryCodePairDecoder,
 }
 
 impl<T: Read> CodePairIter for BinaryCodePairIter<T> {
     fn read_as_utf8(&mut self) {
-        // noop
+        self.decoder.read_as_utf8();
+    }
+    fn read_with_code_page_encoding(&mut self, encoding: &'static Encoding) {
+        self.decoder.read_with_code_page_encoding(encoding);
     }
 }
 
@@ -176,113 +372,18 @@ impl<T: Read> Iterator for BinaryCodePairIter<T> {
 }
 
 impl<T: Read> BinaryCodePairIter<T> {
-    pub fn new(reader: T, offset: usize) -> Self {
-        BinaryCodePairIter {
-            reader,
-            code_size_detection_complete: false,
-            codes_are_two_bytes: false,
-            offset,
-        }
+    /// Reads the 0x1A,0x00 that ends the first line of the file from `reader`.  Strings are read with
+    /// `string_encoding` if specified, otherwise with the encoding specified by `$DWGCODEPAGE`, starting with
+    /// Windows-1252.
+    pub fn new(reader: T, string_encoding: Option<&'static Encoding>) -> DxfResult<Self> {
+        let mut input = ReadInput(reader);
+        let decoder = BinaryCodePairDecoder::new(&mut input, string_encoding)?;
+        Ok(BinaryCodePairIter { input, decoder })
     }
     fn read_code_pair(&mut self) -> Option<DxfResult<CodePair>> {
-        // Read code.  If no data is available, fail gracefully.
-        let mut code = match read_u8(&mut self.reader) {
-            Some(Ok(c)) => i32::from(c),
-            Some(Err(e)) => return Some(Err(DxfError::IoError(e))),
-            None => return None,
-        };
-        self.offset += 1;
-
-        // If reading a larger code and no data is available, die horribly.
-        if self.codes_are_two_bytes {
-            // post R13 codes are 2 bytes, read the second byte of the code
-            let high_byte = i32::from(try_from_dxf_result!(read_u8_strict(&mut self.reader)));
-            code += high_byte << 8;
-            self.offset += 1;
-        } else if code == 255 {
-            // pre R13 codes are either 1 or 3 bytes
-            code = i32::from(try_from_dxf_result!(read_i16(&mut self.reader)));
-            self.offset += 2;
-        }
-
-        // Read value.  If no data is available die horribly.
-        let expected_type = match ExpectedType::expected_type(code) {
-            Some(t) => t,
-            None => return Some(Err(DxfError::UnexpectedEnumValue(self.offset))),
-        };
-        let (value, read_bytes) = match expected_type {
-            ExpectedType::Boolean => {
-                // after R13 bools are encoded as a single byte
-                let (b_value, read_bytes) = if self.codes_are_two_bytes {
-                    (
-                        i16::from(try_from_dxf_result!(read_u8_strict(&mut self.reader))),
-                        1,
-                    )
-                } else {
-                    (try_from_dxf_result!(read_i16(&mut self.reader)), 2)
-                };
-                (CodePairValue::Boolean(b_value), read_bytes)
-            }
-            ExpectedType::Integer => (
-                CodePairValue::Integer(try_from_dxf_result!(read_i32(&mut self.reader))),
-                4,
-            ),
-            ExpectedType::Long => (
-                CodePairValue::Long(try_from_dxf_result!(read_i64(&mut self.reader))),
-                8,
-            ),
-            ExpectedType::Short => (
-                CodePairValue::Short(try_from_dxf_result!(read_i16(&mut self.reader))),
-                2,
-            ),
-            ExpectedType::Double => (
-                CodePairValue::Double(try_from_dxf_result!(read_f64(&mut self.reader))),
-                8,
-            ),
-            ExpectedType::Str => {
-                let mut value = try_from_dxf_result!(self.read_string_binary());
-                if !self.code_size_detection_complete && code == 0 && value == "" {
-                    // If this is the first pair being read and the code is 0, the only valid string value is "SECTION".
-                    // If the read value is instead empty, that means the string reader found a single 0x00 byte which
-                    // indicates that this is a post R13 binary file where codes are always read as 2 bytes.  The 0x00
-                    // byte was really the second byte of {0x00, 0x00}, so we need to do one more string read to catch
-                    // the reader up.
-                    self.codes_are_two_bytes = true;
-                    self.offset += 1; // account for the NULL byte that was interpreted as an empty string
-                    value = try_from_dxf_result!(self.read_string_binary()); // now read the actual value
-                }
-                (
-                    CodePairValue::Str(CodePairValue::un_escape_string(&value).into_owned()),
-                    value.len() + 1, // +1 to account for the NULL terminator
-                )
-            }
-            ExpectedType::Binary => {
-                let length = try_from_dxf_result!(read_u8_strict(&mut self.reader)) as usize;
-                let mut data = vec![];
-                for _ in 0..length {
-                    data.push(try_from_dxf_result!(read_u8_strict(&mut self.reader)));
-                }
-
-                (CodePairValue::Binary(data), length + 1) // +1 to account for initial length byte
-            }
-        };
-        self.offset += read_bytes;
-        self.code_size_detection_complete = true;
-
-        Some(Ok(CodePair::new(code, value, self.offset)))
-    }
-    fn read_string_binary(&mut self) -> DxfResult<String> {
-        let mut s = String::new();
-        loop {
-            match read_u8(&mut self.reader) {
-                Some(Ok(0)) => break,
-                Some(Ok(c)) => s.push(c as char),
-                Some(Err(e)) => return Err(DxfError::IoError(e)),
-                None => return Err(DxfError::UnexpectedEndOfInput),
-            }
-        }
-
-        Ok(s)
+        self.decoder
+            .next(&mut self.input)
+            .map(|pair| pair.map(BorrowedCodePair::into_code_pair))
     }
 }
 
@@ -290,7 +391,7 @@ impl<T: Read> BinaryCodePairIter<T> {
 
 pub(crate) fn new_code_pair_iter_from_reader<T>(
     mut reader: T,
-    string_encoding: &'static Encoding,
+    string_encoding: Option<&'static Encoding>,
     first_line: String,
 ) -> DxfResult<Box<dyn CodePairIter>>
 where
@@ -298,24 +399,51 @@ where
 {
     let mut bytes = vec![];
     reader.read_to_end(&mut bytes)?;
-    let mut cursor = Cursor::new(bytes);
+    if first_line == "AutoCAD Binary DXF" {
+        // reading from memory is much faster than reading one byte at a time through `Read`
+        return Ok(Box::new(BufferedBinaryCodePairIter::new(
+            bytes,
+            string_encoding,
+        )?));
+    }
+
+    new_streaming_code_pair_iter_from_reader(Cursor::new(bytes), string_encoding, first_line)
+}
+
+/// Reads code pairs from part of the text returned by `CodePairIter::unread_text()`.  `offset` is the line number of
+/// the first line of `text`.
+#[cfg(feature = "parallel")]
+pub(crate) fn new_code_pair_iter_from_text(
+    text: Vec<u8>,
+    string_encoding: &'static Encoding,
+    offset: usize,
+) -> Box<dyn CodePairIter> {
+    Box::new(TextCodePairIter {
+        reader: Cursor::new(text),
+        string_encoding,
+        is_encoding_fixed: true,
+        first_line: String::new(),
+        read_first_line: true,
+        offset: offset - 1,
+        put_back_lines: vec![],
+        last_value_was_zero: false,
+        is_misaligned: false,
+    })
+}
+
+/// Reads code pairs from `reader` as they're requested instead of reading it to the end up front.
+pub(crate) fn new_streaming_code_pair_iter_from_reader<T>(
+    reader: T,
+    string_encoding: Option<&'static Encoding>,
+    first_line: String,
+) -> DxfResult<Box<dyn CodePairIter>>
+where
+    T: Read + 'static,
+{
     let iter: Box<dyn CodePairIter> = match &*first_line {
-        "AutoCAD Binary DXF" => {
-            // swallow 0x1A,0x00
-            assert_or_err!(
-                try_option_io_result_into_err!(read_u8(&mut cursor)),
-                0x1A,
-                18
-            );
-            assert_or_err!(
-                try_option_io_result_into_err!(read_u8(&mut cursor)),
-                0x00,
-                19
-            );
-            Box::new(BinaryCodePairIter::new(cursor, 20))
-        }
+        "AutoCAD Binary DXF" => Box::new(BinaryCodePairIter::new(reader, string_encoding)?),
         _ => Box::new(TextCodePairIter::new(
-            cursor,
+            reader,
             string_encoding,
             first_line,
             1,
@@ -324,8 +452,348 @@ where
     Ok(iter)
 }
 
+/// Reads code pairs from an `AsyncRead` as its bytes are received, for the synchronous readers to read a step at a
+/// time with `read_step()`.
+#[cfg(feature = "async")]
+pub(crate) struct AsyncCodePairReader<'a, T: ?Sized> {
+    reader: &'a mut T,
+    received: ReceivedCodePairs,
+    /// The pairs put back at the end of the last step, the next one last.
+    put_back: Vec<DxfResult<CodePair>>,
+}
+
+/// The bytes received from an `AsyncRead`.  Reading past them before the end of the input fails with
+/// `ErrorKind::WouldBlock` so the code pair can be read again once more bytes have been received.
+#[cfg(feature = "async")]
+#[derive(Clone, Default)]
+struct ReceivedBytes {
+    bytes: Vec<u8>,
+    position: usize,
+    is_complete: bool,
+}
+
+/// Code pairs read from the bytes received by an `AsyncCodePairReader`.
+#[cfg(feature = "async")]
+#[derive(Clone)]
+enum ReceivedCodePairs {
+    Text(TextCodePairIter<ReceivedBytes>),
+    Binary(BinaryCodePairIter<ReceivedBytes>),
+    Dxb(ReceivedBytes),
+}
+
+/// Lends the code pairs received by an `AsyncCodePairReader` to the synchronous readers for a step of reading.  Ends
+/// early if the step needs more code pairs than have been received.
+#[cfg(feature = "async")]
+struct ReceivedCodePairIter {
+    received: Rc<RefCell<ReceivedCodePairs>>,
+    ran_out: Rc<Cell<bool>>,
+}
+
+/// The number of bytes `AsyncCodePairReader` asks for at a time.
+#[cfg(feature = "async")]
+const ASYNC_READ_CHUNK_BYTES: usize = 8 * 1024;
+
+#[cfg(feature = "async")]
+impl<'a, T> AsyncCodePairReader<'a, T>
+where
+    T: AsyncRead + Unpin + ?Sized,
+{
+    /// Reads the first line of the file to tell how the rest of it is read.  Strings are read with `string_encoding`
+    /// if specified, otherwise with the encoding specified by `$DWGCODEPAGE`, starting with Windows-1252.
+    pub async fn new(
+        reader: &'a mut T,
+        string_encoding: Option<&'static Encoding>,
+    ) -> DxfResult<AsyncCodePairReader<'a, T>> {
+        let mut bytes = ReceivedBytes::default();
+        let first_line = loop {
+            let encoding = string_encoding.unwrap_or(encoding_rs::WINDOWS_1252);
+            match read_line(&mut bytes, true, encoding) {
+                Some(Err(ref e)) if is_cut_off(e) => {
+                    bytes.position = 0;
+                    receive(reader, &mut bytes).await?;
+                }
+                Some(Ok(line)) => break line,
+                Some(Err(e)) => return Err(e),
+                None => return Err(DxfError::UnexpectedEndOfInput),
+            }
+        };
+        let received = match &*first_line {
+            "AutoCAD DXB 1.0" => ReceivedCodePairs::Dxb(bytes),
+            "AutoCAD Binary DXF" => {
+                let position = bytes.position;
+                loop {
+                    let mut input = ReadInput(&mut bytes);
+                    match BinaryCodePairDecoder::new(&mut input, string_encoding) {
+                        Err(ref e) if is_cut_off(e) => {
+                            bytes.position = position;
+                            receive(reader, &mut bytes).await?;
+                        }
+                        Ok(decoder) => {
+                            break ReceivedCodePairs::Binary(BinaryCodePairIter {
+                                input: ReadInput(bytes),
+                                decoder,
+                            });
+                        }
+                        Err(e) => return Err(e),
+                    }
+                }
+            }
+            _ => ReceivedCodePairs::Text(TextCodePairIter::new(
+                bytes,
+                string_encoding,
+                first_line,
+                1,
+            )),
+        };
+        Ok(AsyncCodePairReader {
+            reader,
+            received,
+            put_back: vec![],
+        })
+    }
+    /// Returns `true` if the file is DXB instead of code pairs.
+    pub fn is_dxb(&self) -> bool {
+        matches!(self.received, ReceivedCodePairs::Dxb(_))
+    }
+    /// Returns everything after the first line of the file.
+    pub async fn read_to_end(mut self) -> DxfResult<Vec<u8>> {
+        while !self.received.bytes().is_complete {
+            receive(self.reader, self.received.bytes_mut()).await?;
+        }
+
+        let bytes = self.received.bytes_mut();
+        Ok(bytes.bytes.split_off(bytes.position))
+    }
+    /// Receives at least as many bytes again as are waiting to be read, so a step that keeps running out is only read
+    /// again a few times.
+    pub async fn receive(&mut self) -> DxfResult<()> {
+        receive(self.reader, self.received.bytes_mut()).await
+    }
+    /// Receives the section that's read next up to and including its `0/ENDSEC`, or only up to its name if
+    /// `reads_items(name)` because its items are read one step at a time.
+    pub async fn receive_section<F>(&mut self, reads_items: F) -> DxfResult<()>
+    where
+        F: Fn(&str) -> bool,
+    {
+        loop {
+            let checkpoint = self.received.checkpoint();
+            let is_received = self.received.scan_section(&reads_items);
+            self.received.restore(checkpoint);
+            if is_received {
+                return Ok(());
+            }
+
+            self.receive().await?;
+        }
+    }
+    /// Runs `step` over the code pairs received so far.  Returns `None`, having read nothing, if `step` needed more
+    /// code pairs than have been received, so it can be run again after `receive()`.
+    pub fn read_step<F, R>(&mut self, options: &LoadOptions, step: F) -> Option<R>
+    where
+        F: FnOnce(&mut CodePairPutBack) -> R,
+    {
+        let checkpoint = self.received.checkpoint();
+        let received = Rc::new(RefCell::new(std::mem::replace(
+            &mut self.received,
+            checkpoint,
+        )));
+        let ran_out = Rc::new(Cell::new(false));
+        let mut iter = CodePairPutBack::from_code_pair_iter(Box::new(ReceivedCodePairIter {
+            received: Rc::clone(&received),
+            ran_out: Rc::clone(&ran_out),
+        }));
+        iter.set_preserve_unknown_items(options.preserve_unknown_items);
+        iter.set_lenient(options.lenient);
+        // a step that starts with an error ends there, so only the pairs are needed to read the step again
+        let put_back_pairs = self
+            .put_back
+            .iter()
+            .filter_map(|pair| pair.as_ref().ok().cloned())
+            .map(Ok)
+            .collect();
+        iter.set_put_back(std::mem::replace(&mut self.put_back, put_back_pairs));
+        let result = step(&mut iter);
+        let put_back = iter.take_put_back();
+        drop(iter);
+        let received = match Rc::try_unwrap(received) {
+            Ok(received) => received.into_inner(),
+            Err(_) => unreachable!("the code pairs are only lent out for the step"),
+        };
+        let checkpoint = std::mem::replace(&mut self.received, received);
+        if ran_out.get() {
+            self.received.restore(checkpoint);
+            return None;
+        }
+
+        self.put_back = put_back;
+        Some(result)
+    }
+}
+
+#[cfg(feature = "async")]
+impl Read for ReceivedBytes {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let available = &self.bytes[self.position..];
+        if available.is_empty() && !self.is_complete && !buf.is_empty() {
+            return Err(io::ErrorKind::WouldBlock.into());
+        }
+
+        let length = available.len().min(buf.len());
+        buf[..length].copy_from_slice(&available[..length]);
+        self.position += length;
+        Ok(length)
+    }
+}
+
+#[cfg(feature = "async")]
+impl ReceivedCodePairs {
+    fn bytes(&self) -> &ReceivedBytes {
+        match self {
+            ReceivedCodePairs::Text(iter) => &iter.reader,
+            ReceivedCodePairs::Binary(iter) => &iter.input.0,
+            ReceivedCodePairs::Dxb(bytes) => bytes,
+        }
+    }
+    fn bytes_mut(&mut self) -> &mut ReceivedBytes {
+        match self {
+            ReceivedCodePairs::Text(iter) => &mut iter.reader,
+            ReceivedCodePairs::Binary(iter) => &mut iter.input.0,
+            ReceivedCodePairs::Dxb(bytes) => bytes,
+        }
+    }
+    /// Returns a copy of the reading state, without the received bytes, to go back to with `restore()`.
+    fn checkpoint(&mut self) -> ReceivedCodePairs {
+        let bytes = std::mem::take(&mut self.bytes_mut().bytes);
+        let checkpoint = self.clone();
+        self.bytes_mut().bytes = bytes;
+        checkpoint
+    }
+    fn restore(&mut self, checkpoint: ReceivedCodePairs) {
+        let bytes = std::mem::take(&mut self.bytes_mut().bytes);
+        *self = checkpoint;
+        self.bytes_mut().bytes = bytes;
+    }
+    fn next_pair(&mut self) -> Option<DxfResult<CodePair>> {
+        match self {
+            ReceivedCodePairs::Text(iter) => iter.next(),
+            ReceivedCodePairs::Binary(iter) => iter.next(),
+            ReceivedCodePairs::Dxb(_) => None,
+        }
+    }
+    /// Returns `true` if the section that's read next has been received; see `AsyncCodePairReader::receive_section()`.
+    fn scan_section<F>(&mut self, reads_items: F) -> bool
+    where
+        F: Fn(&str) -> bool,
+    {
+        let mut is_section_start = false;
+        let mut index = 0;
+        loop {
+            match self.next_pair() {
+                Some(Ok(CodePair {
+                    code,
+                    value: CodePairValue::Str(s),
+                    ..
+                })) => match (index, code, &*s) {
+                    (_, 0, "ENDSEC") => return true,
+                    (0, 0, "SECTION") => is_section_start = true,
+                    (1, 2, name) if is_section_start && reads_items(name) => return true,
+                    _ => (),
+                },
+                Some(Ok(_)) => (),
+                Some(Err(ref e)) if is_cut_off(e) => return false,
+                Some(Err(_)) if self.can_resume_after_error() => (),
+                Some(Err(_)) | None => return true,
+            }
+
+            index += 1;
+        }
+    }
+    fn can_resume_after_error(&self) -> bool {
+        matches!(self, ReceivedCodePairs::Text(_))
+    }
+}
+
+#[cfg(feature = "async")]
+impl CodePairIter for ReceivedCodePairIter {
+    fn read_as_utf8(&mut self) {
+        match &mut *self.received.borrow_mut() {
+            ReceivedCodePairs::Text(iter) => iter.read_as_utf8(),
+            ReceivedCodePairs::Binary(iter) => iter.read_as_utf8(),
+            ReceivedCodePairs::Dxb(_) => (),
+        }
+    }
+    fn read_with_code_page_encoding(&mut self, encoding: &'static Encoding) {
+        match &mut *self.received.borrow_mut() {
+            ReceivedCodePairs::Text(iter) => iter.read_with_code_page_encoding(encoding),
+            ReceivedCodePairs::Binary(iter) => iter.read_with_code_page_encoding(encoding),
+            ReceivedCodePairs::Dxb(_) => (),
+        }
+    }
+    fn can_resume_after_error(&self) -> bool {
+        self.received.borrow().can_resume_after_error()
+    }
+    fn is_misaligned(&self) -> bool {
+        match &*self.received.borrow() {
+            ReceivedCodePairs::Text(iter) => iter.is_misaligned(),
+            _ => false,
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+impl Iterator for ReceivedCodePairIter {
+    type Item = DxfResult<CodePair>;
+    fn next(&mut self) -> Option<DxfResult<CodePair>> {
+        if self.ran_out.get() {
+            return None;
+        }
+
+        match self.received.borrow_mut().next_pair() {
+            Some(Err(ref e)) if is_cut_off(e) => {
+                self.ran_out.set(true);
+                None
+            }
+            pair => pair,
+        }
+    }
+}
+
+/// Returns `true` if reading failed because the rest of the code pair hasn't been received yet.
+#[cfg(feature = "async")]
+fn is_cut_off(error: &DxfError) -> bool {
+    matches!(error, DxfError::IoError(e) if e.kind() == io::ErrorKind::WouldBlock)
+}
+
+/// Receives at least as many bytes again as are waiting to be read, dropping the ones that have been read.
+#[cfg(feature = "async")]
+async fn receive<T>(reader: &mut T, bytes: &mut ReceivedBytes) -> DxfResult<()>
+where
+    T: AsyncRead + Unpin + ?Sized,
+{
+    bytes.bytes.drain(..bytes.position);
+    bytes.position = 0;
+    let target_length = bytes.bytes.len() + bytes.bytes.len().max(ASYNC_READ_CHUNK_BYTES);
+    while !bytes.is_complete && bytes.bytes.len() < target_length {
+        let length = bytes.bytes.len();
+        bytes.bytes.resize(length + ASYNC_READ_CHUNK_BYTES, 0);
+        let result = reader.read(&mut bytes.bytes[length..]).await;
+        bytes
+            .bytes
+            .truncate(length + *result.as_ref().unwrap_or(&0));
+        match result {
+            Ok(0) => bytes.is_complete = true,
+            Ok(_) => (),
+            Err(ref e) if e.kind() == io::ErrorKind::Interrupted => (),
+            Err(e) => return Err(DxfError::IoError(e)),
+        }
+    }
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
+    use crate::binary_code_pair_reader::{BinaryCodePairDecoder, ReadInput};
     use crate::code_pair_iter::{BinaryCodePairIter, TextCodePairIter};
     use crate::CodePair;
 
@@ -333,10 +801,8 @@ mod tests {
 
     fn read_in_binary(codes_are_two_bytes: bool, data: Vec<u8>) -> CodePair {
         let mut reader = BinaryCodePairIter {
-            reader: data.as_slice(),
-            code_size_detection_complete: true,
-            codes_are_two_bytes,
-            offset: 0,
+            input: ReadInput(data.as_slice()),
+            decoder: BinaryCodePairDecoder::after_first_pair(codes_are_two_bytes),
         };
         reader.read_code_pair().unwrap().unwrap()
     }
@@ -364,9 +830,13 @@ mod tests {
         let mut reader = TextCodePairIter::<&[u8]> {
             reader: data.as_bytes(),
             string_encoding: encoding_rs::WINDOWS_1252,
+            is_encoding_fixed: false,
             first_line: String::from("not-important"),
             read_first_line: true,
             offset: 0,
+            put_back_lines: vec![],
+            last_value_was_zero: false,
+            is_misaligned: false,
         };
         reader.read_code_pair().unwrap().unwrap()
     }
@@ -1,15 +1,29 @@
 use crate::code_pair_iter::CodePairIter;
+#[cfg(feature = "parallel")]
+use crate::code_pair_iter::UnreadText;
 use crate::dxf_result::DxfResult;
-use crate::CodePair;
+use crate::{CodePair, DxfError, LoadWarning, SkippedContent};
+use encoding_rs::Encoding;
 
 pub(crate) struct CodePairPutBack {
     top: Vec<DxfResult<CodePair>>,
     iter: Box<dyn CodePairIter>,
+    preserve_unknown_items: bool,
+    lenient: bool,
+    warnings: Vec<LoadWarning>,
+    last_offset: usize,
 }
 
 impl CodePairPutBack {
     pub fn from_code_pair_iter(iter: Box<dyn CodePairIter>) -> Self {
-        CodePairPutBack { top: vec![], iter }
+        CodePairPutBack {
+            top: vec![],
+            iter,
+            preserve_unknown_items: false,
+            lenient: false,
+            warnings: vec![],
+            last_offset: 0,
+        }
     }
     pub fn put_back(&mut self, item: DxfResult<CodePair>) {
         self.top.push(item);
@@ -17,6 +31,81 @@ impl CodePairPutBack {
     pub fn read_as_utf8(&mut self) {
         self.iter.read_as_utf8()
     }
+    pub fn read_with_code_page_encoding(&mut self, encoding: &'static Encoding) {
+        self.iter.read_with_code_page_encoding(encoding)
+    }
+    pub fn preserve_unknown_items(&self) -> bool {
+        self.preserve_unknown_items
+    }
+    pub fn set_preserve_unknown_items(&mut self, preserve_unknown_items: bool) {
+        self.preserve_unknown_items = preserve_unknown_items;
+    }
+    pub fn set_lenient(&mut self, lenient: bool) {
+        self.lenient = lenient;
+    }
+    /// When lenient, records `error` as a warning so reading can continue after skipping `skipped`; otherwise, or if
+    /// the error is fatal, returns it.
+    pub fn recover(&mut self, error: DxfError, skipped: SkippedContent) -> DxfResult<()> {
+        if !self.lenient || error.is_fatal() {
+            return Err(error);
+        }
+
+        let offset = error.offset().unwrap_or(self.last_offset);
+        self.warnings.push(LoadWarning {
+            offset,
+            error,
+            skipped,
+        });
+        Ok(())
+    }
+    /// Skips code pairs up to the next one with code 0, which is put back.
+    pub fn skip_to_next_item(&mut self) -> DxfResult<()> {
+        loop {
+            match self.next() {
+                Some(Ok(pair)) => {
+                    if pair.code == 0 {
+                        self.put_back(Ok(pair));
+                        break;
+                    }
+                }
+                Some(Err(e)) => return Err(e),
+                None => break,
+            }
+        }
+
+        Ok(())
+    }
+    pub fn take_warnings(&mut self) -> Vec<LoadWarning> {
+        std::mem::take(&mut self.warnings)
+    }
+    /// Adds warnings recovered from while reading content elsewhere, e.g., from `unread_text()`.
+    #[cfg(feature = "parallel")]
+    pub fn add_warnings(&mut self, warnings: Vec<LoadWarning>) {
+        self.warnings.extend(warnings);
+    }
+    /// Takes the pairs that were put back so reading can continue elsewhere; see `AsyncCodePairReader::read_step()`.
+    #[cfg(feature = "async")]
+    pub fn take_put_back(&mut self) -> Vec<DxfResult<CodePair>> {
+        std::mem::take(&mut self.top)
+    }
+    /// Puts back pairs returned by `take_put_back()`.
+    #[cfg(feature = "async")]
+    pub fn set_put_back(&mut self, top: Vec<DxfResult<CodePair>>) {
+        self.top = top;
+    }
+    #[cfg(feature = "parallel")]
+    pub fn unread_text(&self) -> Option<UnreadText<'_>> {
+        if self.top.is_empty() {
+            self.iter.unread_text()
+        } else {
+            // put back pairs would be lost
+            None
+        }
+    }
+    #[cfg(feature = "parallel")]
+    pub fn skip_text(&mut self, byte_count: usize, line_count: usize) {
+        self.iter.skip_text(byte_count, line_count)
+    }
 }
 
 impl Iterator for CodePairPutBack {
@@ -28,6 +117,20 @@ impl Iterator for CodePairPutBack {
                 let pair = self.iter.next();
                 match pair {
                     Some(Ok(CodePair { code, .. })) if code == 999 => (), // a 999 comment code, try again
+                    Some(Ok(ref p)) => {
+                        self.last_offset = p.offset;
+                        return pair;
+                    }
+                    Some(Err(e)) if self.lenient && self.can_skip_pair_after(&e) => {
+                        // the invalid pair has been consumed, continue with the next one, or with the next item if
+                        // the pairs are out of alignment
+                        let skipped = if self.iter.is_misaligned() {
+                            SkippedContent::Lines
+                        } else {
+                            SkippedContent::Pair
+                        };
+                        self.recover(e, skipped).ok()?;
+                    }
                     _ => return pair,
                 }
             }
@@ -36,3 +139,16 @@ impl Iterator for CodePairPutBack {
         }
     }
 }
+
+impl CodePairPutBack {
+    fn can_skip_pair_after(&self, error: &DxfError) -> bool {
+        self.iter.can_resume_after_error()
+            && matches!(
+                *error,
+                DxfError::ParseFloatError(..)
+                    | DxfError::ParseIntError(..)
+                    | DxfError::ParseError(_)
+                    | DxfError::UnexpectedEnumValue(_)
+            )
+    }
+}
@@ -90,7 +90,13 @@ impl<'a, T: Write + ?Sized> CodePairWriter<'a, T> {
             CodePairValue::Short(s) => self.writer.write_i16::<LittleEndian>(s)?,
             CodePairValue::Double(d) => self.writer.write_f64::<LittleEndian>(d)?,
             CodePairValue::Str(ref s) => {
-                for &b in escape_control_characters(s).as_bytes() {
+                let s = escape_control_characters(s);
+                let s = if self.text_as_ascii {
+                    escape_unicode_to_ascii(&s)
+                } else {
+                    s
+                };
+                for &b in s.as_bytes() {
                     self.writer.write_u8(b)?;
                 }
 
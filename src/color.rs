@@ -76,6 +76,26 @@ impl Color {
             raw_value: i16::from(i),
         }
     }
+    /// Gets the RGB value of the color in AutoCAD's default palette, or `None` if the color isn't an index.
+    pub fn rgb(&self) -> Option<(u8, u8, u8)> {
+        self.index().map(index_rgb)
+    }
+    /// Creates the indexed `Color` whose RGB value in AutoCAD's default palette is closest to the specified value.
+    /// Grays only map to the neutral indices so they don't pick up a tint.
+    pub fn from_rgb(r: u8, g: u8, b: u8) -> Color {
+        let is_gray = r == g && g == b;
+        let distance = |(r2, g2, b2): (u8, u8, u8)| {
+            let dr = i32::from(r) - i32::from(r2);
+            let dg = i32::from(g) - i32::from(g2);
+            let db = i32::from(b) - i32::from(b2);
+            dr * dr + dg * dg + db * db
+        };
+        let index = (1..=255u8)
+            .filter(|&i| !is_gray || matches!(i, 7..=9 | 250..=255))
+            .min_by_key(|&i| distance(index_rgb(i)))
+            .unwrap_or(7);
+        Color::from_index(index)
+    }
     pub(crate) fn writable_color_value(&self, layer: &Layer) -> i16 {
         let value = self.raw_value().abs();
         if layer.is_layer_on {
@@ -85,3 +105,64 @@ impl Color {
         }
     }
 }
+
+/// Computes the RGB value of a color index in AutoCAD's default palette.
+fn index_rgb(index: u8) -> (u8, u8, u8) {
+    match index {
+        1 => (255, 0, 0),
+        2 => (255, 255, 0),
+        3 => (0, 255, 0),
+        4 => (0, 255, 255),
+        5 => (0, 0, 255),
+        6 => (255, 0, 255),
+        8 => (128, 128, 128),
+        9 => (192, 192, 192),
+        10..=249 => {
+            // 24 hues in 15 degree steps, each with 5 shades and a lighter variant of each shade
+            let hue = f64::from((index - 10) / 10) * 15.0;
+            let shade = [1.0, 0.8, 0.6, 0.5, 0.3][usize::from(index % 10 / 2)] * 255.0;
+            let is_light = index % 2 == 1;
+            let component = |offset: f64| {
+                let angle = (hue - offset).rem_euclid(360.0);
+                let angle = angle.min(360.0 - angle);
+                let full = ((120.0 - angle) / 60.0).clamp(0.0, 1.0) * shade;
+                let value = if is_light { (full + shade) / 2.0 } else { full };
+                value as u8
+            };
+            (component(0.0), component(120.0), component(240.0))
+        }
+        250 => (51, 51, 51),
+        251 => (80, 80, 80),
+        252 => (105, 105, 105),
+        253 => (130, 130, 130),
+        254 => (190, 190, 190),
+        _ => (255, 255, 255),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Color;
+
+    #[test]
+    fn rgb_of_palette_entries() {
+        assert_eq!(Some((255, 0, 0)), Color::from_index(1).rgb());
+        assert_eq!(Some((255, 63, 0)), Color::from_index(20).rgb());
+        assert_eq!(Some((255, 159, 127)), Color::from_index(21).rgb());
+        assert_eq!(Some((127, 79, 63)), Color::from_index(27).rgb());
+        assert_eq!(Some((76, 0, 0)), Color::from_index(18).rgb());
+        assert_eq!(Some((0, 0, 255)), Color::from_index(170).rgb());
+        assert_eq!(Some((130, 130, 130)), Color::from_index(253).rgb());
+        assert_eq!(None, Color::by_layer().rgb());
+    }
+
+    #[test]
+    fn nearest_index_from_rgb() {
+        assert_eq!(Some(1), Color::from_rgb(250, 5, 5).index());
+        assert_eq!(Some(5), Color::from_rgb(0, 0, 255).index());
+        assert_eq!(Some(30), Color::from_rgb(255, 127, 0).index());
+        assert_eq!(Some(7), Color::from_rgb(255, 255, 255).index());
+        assert_eq!(Some(250), Color::from_rgb(0, 0, 0).index());
+        assert_eq!(Some(8), Color::from_rgb(120, 120, 120).index());
+    }
+}
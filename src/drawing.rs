@@ -6,19 +6,27 @@ use self::image::DynamicImage;
 
 use crate::code_pair_put_back::CodePairPutBack;
 use crate::drawing_item::{DrawingItem, DrawingItemMut};
+use crate::drawing_stream::{DrawingStream, DrawingStreamItem};
 use crate::entities::*;
 use crate::enums::*;
 use crate::header::*;
 use crate::objects::*;
 use crate::tables::*;
 
-use crate::{CodePair, CodePairValue, DxfError, DxfResult, Handle};
+use crate::{
+    AuditIssue, BoundingBox, CodePair, CodePairValue, DecodedText, DxfError, DxfResult, Handle,
+    HandleIndex, LoadOptions, LoadWarning, LossyConversion, Point, SkippedContent,
+};
 
+use crate::audit;
 use crate::dxb_reader::DxbReader;
 use crate::dxb_writer::DxbWriter;
-use crate::entity_iter::EntityIter;
+use crate::entity_iter::{EntityCombiner, EntityIter};
+use crate::exploded_entity_iter::ExplodedEntityIter;
 use crate::helper_functions::*;
 use crate::object_iter::ObjectIter;
+#[cfg(feature = "parallel")]
+use crate::parallel_entities::read_entities_in_parallel;
 
 use crate::block::Block;
 use crate::class::Class;
@@ -27,17 +35,26 @@ use crate::code_pair_iter::{new_code_pair_iter_from_reader, CodePairIter};
 use crate::code_pair_writer::CodePairWriter;
 
 use crate::thumbnail;
+use crate::version_conversion::VersionConverter;
 
 use std::fs::File;
 use std::io::{BufReader, BufWriter, Read, Write};
 
-use itertools::put_back;
-use std::collections::HashSet;
+#[cfg(feature = "async")]
+use crate::code_pair_iter::AsyncCodePairReader;
+#[cfg(feature = "async")]
+use futures_util::io::{AsyncRead, AsyncWrite, AsyncWriteExt};
+
+use std::collections::{HashSet, VecDeque};
 use std::iter::Iterator;
 use std::path::Path;
 
 pub(crate) const AUTO_REPLACE_HANDLE: Handle = Handle(0xFFFF_FFFF_FFFF_FFFF);
 
+/// The number of code pairs `Drawing::save_async()` formats before handing them to the writer.
+#[cfg(feature = "async")]
+const ASYNC_WRITE_CHUNK_PAIRS: usize = 1024;
+
 /// Represents a DXF drawing.
 #[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 pub struct Drawing {
@@ -79,6 +96,39 @@ pub struct Drawing {
     pub thumbnail: Option<DynamicImage>,
 }
 
+/// How far `Drawing::read_next()` has read.
+#[derive(Clone)]
+enum ReadPosition {
+    Sections,
+    Entities(Box<EntityCombiner>),
+    Objects,
+    End,
+}
+
+/// Produces the code pairs of a drawing a few at a time, in the order they're written.
+struct CodePairProducer<'a> {
+    drawing: &'a Drawing,
+    write_handles: bool,
+    next_part: DrawingPart,
+}
+
+/// The part of a drawing that `CodePairProducer` adds the code pairs of next.
+#[derive(Clone, Copy)]
+enum DrawingPart {
+    Start,
+    Entity(usize),
+    Objects,
+    Object(usize),
+    End,
+    Done,
+}
+
+/// An entity or object returned by `Drawing::read_next()` to be added with `Drawing::add_read_item()`.
+enum ReadItem {
+    Entity(Box<Entity>),
+    Object(Box<Object>),
+}
+
 // public implementation
 impl Drawing {
     #[allow(clippy::new_without_default)] // default state of struct isn't valid
@@ -103,59 +153,85 @@ impl Drawing {
         drawing.normalize();
         drawing
     }
-    /// Loads a `Drawing` from anything that implements the `Read` trait.
+    /// Loads a `Drawing` from anything that implements the `Read` trait.  Text is read with the encoding specified by
+    /// `$DWGCODEPAGE`, or Windows-1252 if it isn't recognized.
     pub fn load<'a, T>(reader: &mut T) -> DxfResult<Drawing>
     where
         T: Read + 'a + ?Sized,
     {
-        Drawing::load_with_encoding(reader, encoding_rs::WINDOWS_1252)
+        Drawing::load_with_options(reader, &LoadOptions::new()).map(|(drawing, _)| drawing)
     }
-    /// Loads a `Drawing` from anything that implements the `Read` trait using the specified text encoding.
+    /// Loads a `Drawing` from anything that implements the `Read` trait using the specified text encoding, regardless
+    /// of `$DWGCODEPAGE`.
     pub fn load_with_encoding<T>(reader: &mut T, encoding: &'static Encoding) -> DxfResult<Drawing>
     where
         T: Read + ?Sized,
     {
-        let first_line = match read_line(reader, true, encoding) {
-            Some(Ok(line)) => line,
-            Some(Err(e)) => return Err(e),
-            None => return Err(DxfError::UnexpectedEndOfInput),
-        };
+        let options = LoadOptions::new().encoding(encoding);
+        Drawing::load_with_options(reader, &options).map(|(drawing, _)| drawing)
+    }
+    /// Loads a `Drawing` from anything that implements the `Read` trait, keeping unsupported entities and objects as
+    /// `EntityType::Unknown` and `ObjectType::Unknown` so they are written back unchanged when the drawing is saved.
+    pub fn load_with_unknown_items<T>(reader: &mut T) -> DxfResult<Drawing>
+    where
+        T: Read + ?Sized,
+    {
+        let options = LoadOptions::new().preserve_unknown_items(true);
+        Drawing::load_with_options(reader, &options).map(|(drawing, _)| drawing)
+    }
+    /// Loads a `Drawing` from anything that implements the `Read` trait with the specified options.  Also returns the
+    /// problems that were recovered from, which is only ever non-empty for `LoadOptions::lenient()`.
+    pub fn load_with_options<T>(
+        reader: &mut T,
+        options: &LoadOptions,
+    ) -> DxfResult<(Drawing, Vec<LoadWarning>)>
+    where
+        T: Read + ?Sized,
+    {
+        let encoding = options.encoding;
+        let first_line =
+            match read_line(reader, true, encoding.unwrap_or(encoding_rs::WINDOWS_1252)) {
+                Some(Ok(line)) => line,
+                Some(Err(e)) => return Err(e),
+                None => return Err(DxfError::UnexpectedEndOfInput),
+            };
         match &*first_line {
             "AutoCAD DXB 1.0" => {
                 let mut reader = DxbReader::new(reader);
-                reader.load()
+                Ok((reader.load()?, vec![]))
             }
             _ => {
                 let iter = new_code_pair_iter_from_reader(reader, encoding, first_line)?;
-                Drawing::load_from_iter(iter)
+                Drawing::load_from_iter(iter, options)
             }
         }
     }
     /// Loads a `Drawing` from the specified `CodePairIter`.
-    pub(crate) fn load_from_iter(iter: Box<dyn CodePairIter>) -> DxfResult<Drawing> {
+    pub(crate) fn load_from_iter(
+        iter: Box<dyn CodePairIter>,
+        options: &LoadOptions,
+    ) -> DxfResult<(Drawing, Vec<LoadWarning>)> {
         let mut drawing = Drawing::new();
         drawing.clear();
         let mut iter = CodePairPutBack::from_code_pair_iter(iter);
-        Drawing::read_sections(&mut drawing, &mut iter)?;
-        match iter.next() {
-            Some(Ok(CodePair {
-                code: 0,
-                value: CodePairValue::Str(ref s),
-                ..
-            })) if s == "EOF" => Ok(drawing),
-            Some(Ok(pair)) => Err(DxfError::UnexpectedCodePair(
-                pair,
-                String::from("expected 0/EOF"),
-            )),
-            Some(Err(e)) => Err(e),
-            None => Ok(drawing),
+        iter.set_preserve_unknown_items(options.preserve_unknown_items);
+        iter.set_lenient(options.lenient);
+        Drawing::read_sections(&mut drawing, &mut iter, options)?;
+        Drawing::read_eof(&mut iter)?;
+        if options.decode_control_codes {
+            drawing.decode_control_codes();
         }
+
+        Ok((drawing, iter.take_warnings()))
     }
     /// Loads a `Drawing` from disk, using a `BufReader`.
     pub fn load_file(path: impl AsRef<Path>) -> DxfResult<Drawing> {
-        Drawing::load_file_with_encoding(path, encoding_rs::WINDOWS_1252)
+        let file = File::open(&path)?;
+        let mut buf_reader = BufReader::new(file);
+        Drawing::load(&mut buf_reader)
     }
-    /// Loads a `Drawing` from disk, using a `BufReader` with the specified text encoding.
+    /// Loads a `Drawing` from disk, using a `BufReader` with the specified text encoding, regardless of
+    /// `$DWGCODEPAGE`.
     pub fn load_file_with_encoding(
         path: impl AsRef<Path>,
         encoding: &'static Encoding,
@@ -164,6 +240,123 @@ impl Drawing {
         let mut buf_reader = BufReader::new(file);
         Drawing::load_with_encoding(&mut buf_reader, encoding)
     }
+    /// Loads a `Drawing` from disk, using a `BufReader`, keeping unsupported entities and objects so they are written
+    /// back unchanged when the drawing is saved.
+    pub fn load_file_with_unknown_items(path: impl AsRef<Path>) -> DxfResult<Drawing> {
+        let file = File::open(&path)?;
+        let mut buf_reader = BufReader::new(file);
+        Drawing::load_with_unknown_items(&mut buf_reader)
+    }
+    /// Loads a `Drawing` from disk, using a `BufReader` with the specified options.
+    pub fn load_file_with_options(
+        path: impl AsRef<Path>,
+        options: &LoadOptions,
+    ) -> DxfResult<(Drawing, Vec<LoadWarning>)> {
+        let file = File::open(&path)?;
+        let mut buf_reader = BufReader::new(file);
+        Drawing::load_with_options(&mut buf_reader, options)
+    }
+    /// Loads a `Drawing` from anything that implements the `AsyncRead` trait.  The input is parsed as it's received,
+    /// a section, entity, or object at a time, without blocking.  Requires the `async` feature.
+    #[cfg(feature = "async")]
+    pub async fn load_async<T>(reader: &mut T) -> DxfResult<Drawing>
+    where
+        T: AsyncRead + Unpin + ?Sized,
+    {
+        Drawing::load_async_with_options(reader, &LoadOptions::new())
+            .await
+            .map(|(drawing, _)| drawing)
+    }
+    /// Loads a `Drawing` from anything that implements the `AsyncRead` trait with the specified options.  Requires the
+    /// `async` feature.
+    #[cfg(feature = "async")]
+    pub async fn load_async_with_options<T>(
+        reader: &mut T,
+        options: &LoadOptions,
+    ) -> DxfResult<(Drawing, Vec<LoadWarning>)>
+    where
+        T: AsyncRead + Unpin + ?Sized,
+    {
+        let mut pairs = AsyncCodePairReader::new(reader, options.encoding).await?;
+        if pairs.is_dxb() {
+            let bytes = pairs.read_to_end().await?;
+            let mut reader = DxbReader::new(bytes.as_slice());
+            return Ok((reader.load()?, vec![]));
+        }
+
+        let mut drawing = Drawing::new();
+        drawing.clear();
+        let mut position = ReadPosition::Sections;
+        let mut warnings = vec![];
+        while !matches!(position, ReadPosition::End) {
+            if let ReadPosition::Sections = position {
+                // sections are added to the drawing as they're read, so they must not run out of code pairs
+                pairs
+                    .receive_section(|name| Drawing::reads_section_items(name, options))
+                    .await?;
+            }
+
+            let step = pairs.read_step(options, |iter| {
+                let mut next_position = position.clone();
+                let item = drawing.read_next(iter, options, &mut next_position);
+                (item, next_position, iter.take_warnings())
+            });
+            match step {
+                Some((item, next_position, step_warnings)) => {
+                    position = next_position;
+                    warnings.extend(step_warnings);
+                    if let Some(item) = item? {
+                        drawing.add_read_item(item, options);
+                    }
+                }
+                None => pairs.receive().await?,
+            }
+        }
+
+        loop {
+            match pairs.read_step(options, |iter| {
+                (Drawing::read_eof(iter), iter.take_warnings())
+            }) {
+                Some((result, step_warnings)) => {
+                    warnings.extend(step_warnings);
+                    result?;
+                    break;
+                }
+                None => pairs.receive().await?,
+            }
+        }
+
+        if options.decode_control_codes {
+            drawing.decode_control_codes();
+        }
+
+        Ok((drawing, warnings))
+    }
+    /// Reads the drawing from anything that implements the `Read` trait one item at a time instead of loading it all
+    /// into memory.  The returned `DrawingStream` yields the header, classes, table entries, blocks, entities, and
+    /// objects in the order they appear.  Wrapping `reader` in a `BufReader` is recommended.
+    pub fn stream<T>(reader: T) -> DxfResult<DrawingStream>
+    where
+        T: Read + 'static,
+    {
+        DrawingStream::new(reader, None)
+    }
+    /// Reads the drawing one item at a time, like `Drawing::stream`, using the specified text encoding regardless of
+    /// `$DWGCODEPAGE`.
+    pub fn stream_with_encoding<T>(
+        reader: T,
+        encoding: &'static Encoding,
+    ) -> DxfResult<DrawingStream>
+    where
+        T: Read + 'static,
+    {
+        DrawingStream::new(reader, Some(encoding))
+    }
+    /// Reads the drawing from disk one item at a time, using a `BufReader`.
+    pub fn stream_file(path: impl AsRef<Path>) -> DxfResult<DrawingStream> {
+        let file = File::open(&path)?;
+        Drawing::stream(BufReader::new(file))
+    }
     /// Writes a `Drawing` to anything that implements the `Write` trait.
     pub fn save<T>(&self, writer: &mut T) -> DxfResult<()>
     where
@@ -180,16 +373,9 @@ impl Drawing {
     }
     /// Gets all code pairs that will be written.
     pub(crate) fn code_pairs(&self) -> DxfResult<Vec<CodePair>> {
-        let write_handles = self.header.version >= AcadVersion::R13 || self.header.handles_enabled;
         let mut pairs = Vec::new();
-        self.header.add_code_pairs(&mut pairs);
-        self.add_classes_pairs(&mut pairs);
-        self.add_tables_pairs(&mut pairs, write_handles);
-        self.add_blocks_pairs(&mut pairs, write_handles);
-        self.add_entities_pairs(&mut pairs, write_handles);
-        self.add_objects_pairs(&mut pairs);
-        self.add_thumbnail_pairs(&mut pairs)?;
-        pairs.push(CodePair::new_str(0, "EOF"));
+        let mut producer = CodePairProducer::new(self);
+        while producer.add_next_pairs(&mut pairs)? {}
         Ok(pairs)
     }
     fn save_internal<T>(&self, writer: &mut T, as_ascii: bool) -> DxfResult<()>
@@ -206,6 +392,57 @@ impl Drawing {
         }
         Ok(())
     }
+    /// Writes a `Drawing` to anything that implements the `AsyncWrite` trait, flushing it when done.  Requires the
+    /// `async` feature.
+    #[cfg(feature = "async")]
+    pub async fn save_async<T>(&self, writer: &mut T) -> DxfResult<()>
+    where
+        T: AsyncWrite + Unpin + ?Sized,
+    {
+        self.save_async_internal(writer, true).await
+    }
+    /// Writes a `Drawing` as binary to anything that implements the `AsyncWrite` trait, flushing it when done.
+    /// Requires the `async` feature.
+    #[cfg(feature = "async")]
+    pub async fn save_binary_async<T>(&self, writer: &mut T) -> DxfResult<()>
+    where
+        T: AsyncWrite + Unpin + ?Sized,
+    {
+        self.save_async_internal(writer, false).await
+    }
+    #[cfg(feature = "async")]
+    async fn save_async_internal<T>(&self, writer: &mut T, as_ascii: bool) -> DxfResult<()>
+    where
+        T: AsyncWrite + Unpin + ?Sized,
+    {
+        let text_as_ascii = self.header.version <= AcadVersion::R2004;
+        let mut buffer = vec![];
+        CodePairWriter::new(&mut buffer, as_ascii, text_as_ascii, self.header.version)
+            .write_prelude()?;
+        let mut producer = CodePairProducer::new(self);
+        let mut pairs = vec![];
+        loop {
+            let has_more_pairs = producer.add_next_pairs(&mut pairs)?;
+            if pairs.len() >= ASYNC_WRITE_CHUNK_PAIRS || !has_more_pairs {
+                let mut code_pair_writer =
+                    CodePairWriter::new(&mut buffer, as_ascii, text_as_ascii, self.header.version);
+                for pair in &pairs {
+                    code_pair_writer.write_code_pair(pair)?;
+                }
+
+                writer.write_all(&buffer).await?;
+                buffer.clear();
+                pairs.clear();
+            }
+
+            if !has_more_pairs {
+                break;
+            }
+        }
+
+        writer.flush().await?;
+        Ok(())
+    }
     /// Writes a `Drawing` to disk, using a `BufWriter`.
     pub fn save_file(&self, path: impl AsRef<Path>) -> DxfResult<()> {
         self.save_file_internal(path, true)
@@ -413,28 +650,7 @@ impl Drawing {
     }
     /// Adds an entity to the `Drawing`.
     pub fn add_entity(&mut self, mut entity: Entity) -> &Entity {
-        entity.common.handle = self.next_handle();
-
-        // set child handles
-        match entity.specific {
-            EntityType::Insert(ref mut ins) => {
-                ins.__seqend_handle = self.next_handle();
-                for a in ins.__attributes_and_handles.iter_mut() {
-                    if a.1 == AUTO_REPLACE_HANDLE {
-                        a.1 = self.next_handle();
-                    }
-                }
-            }
-            EntityType::Polyline(ref mut poly) => {
-                poly.__seqend_handle = self.next_handle();
-                for v in poly.__vertices_and_handles.iter_mut() {
-                    if v.1 == AUTO_REPLACE_HANDLE {
-                        v.1 = self.next_handle();
-                    }
-                }
-            }
-            _ => (),
-        }
+        self.prepare_new_entity(&mut entity);
 
         // ensure invariants
         self.add_entity_no_handle_set(entity)
@@ -483,6 +699,304 @@ impl Drawing {
 
         self.header.next_available_handle = Handle(1);
     }
+    /// Removes all layers, line types, text styles, dimension styles, and blocks that aren't referenced by name or
+    /// handle from anywhere else in the `Drawing` and returns the number of items removed.  Items that AutoCAD
+    /// requires, e.g., layer `0` or the `*Model_Space` block, and the current items set in the header are kept.
+    pub fn purge(&mut self) -> usize {
+        // removing one item can leave another unused, e.g., a layer only used by a purged block
+        let mut removed = 0;
+        loop {
+            let pass = self.purge_blocks()
+                + self.purge_dim_styles()
+                + self.purge_layers()
+                + self.purge_line_types()
+                + self.purge_styles();
+            if pass == 0 {
+                return removed;
+            }
+            removed += pass;
+        }
+    }
+    /// Removes all blocks that aren't inserted, used by a dimension, or used as an arrowhead, along with their block
+    /// records, and returns the number of blocks removed.  Layout blocks and external references are kept.
+    pub fn purge_blocks(&mut self) -> usize {
+        let mut removed = 0;
+        loop {
+            let used = {
+                let index = self.build_handle_index();
+                let used_names = self.used_block_names();
+                self.blocks()
+                    .map(|b| {
+                        b.is_xref()
+                            || Drawing::is_layout_block_name(&b.name)
+                            || used_names.contains(&b.name.to_ascii_uppercase())
+                            || Drawing::is_referenced_by_handle(&index, b.handle)
+                            || self.block_records().any(|br| {
+                                br.name.eq_ignore_ascii_case(&b.name)
+                                    && Drawing::is_referenced_by_handle(&index, br.handle)
+                            })
+                    })
+                    .collect::<Vec<_>>()
+            };
+            let unused_names = self
+                .blocks()
+                .zip(&used)
+                .filter(|(_, used)| !**used)
+                .map(|(b, _)| b.name.clone())
+                .collect::<Vec<_>>();
+            if unused_names.is_empty() {
+                return removed;
+            }
+
+            // removing a block can leave the blocks it inserts unused
+            removed += Drawing::retain_used(&mut self.__blocks, used);
+            self.__block_records.retain(|br| {
+                !unused_names
+                    .iter()
+                    .any(|n| n.eq_ignore_ascii_case(&br.name))
+            });
+        }
+    }
+    /// Removes all dimension styles that aren't used by an entity and returns the number of styles removed.  The
+    /// `STANDARD` and `ANNOTATIVE` styles and the header's current style are kept.
+    pub fn purge_dim_styles(&mut self) -> usize {
+        let used = {
+            let index = self.build_handle_index();
+            let mut used_names = HashSet::new();
+            used_names.insert(String::from("STANDARD"));
+            used_names.insert(String::from("ANNOTATIVE"));
+            used_names.insert(self.header.dimension_style_name.to_ascii_uppercase());
+            for ent in self.all_entities() {
+                if let Some(name) = ent.specific.dimension_style_name() {
+                    used_names.insert(name.to_ascii_uppercase());
+                }
+            }
+            self.dim_styles()
+                .map(|ds| {
+                    used_names.contains(&ds.name.to_ascii_uppercase())
+                        || Drawing::is_referenced_by_handle(&index, ds.handle)
+                })
+                .collect::<Vec<_>>()
+        };
+        Drawing::retain_used(&mut self.__dim_styles, used)
+    }
+    /// Removes all layers that aren't used by an entity, block, or layer filter and returns the number of layers
+    /// removed.  Layer `0` and the header's current layer are kept.
+    pub fn purge_layers(&mut self) -> usize {
+        let used = {
+            let index = self.build_handle_index();
+            let mut used_names = HashSet::new();
+            used_names.insert(String::from("0"));
+            used_names.insert(self.header.current_layer.to_ascii_uppercase());
+            for ent in self.all_entities() {
+                used_names.insert(ent.common.layer.to_ascii_uppercase());
+            }
+            for block in self.blocks() {
+                used_names.insert(block.layer.to_ascii_uppercase());
+            }
+            for obj in self.objects() {
+                let layer_names = match obj.specific {
+                    ObjectType::LayerFilter(ref l) => &l.layer_names,
+                    ObjectType::LayerIndex(ref l) => &l.layer_names,
+                    _ => continue,
+                };
+                for layer_name in layer_names {
+                    used_names.insert(layer_name.to_ascii_uppercase());
+                }
+            }
+            self.layers()
+                .map(|l| {
+                    used_names.contains(&l.name.to_ascii_uppercase())
+                        || Drawing::is_referenced_by_handle(&index, l.handle)
+                })
+                .collect::<Vec<_>>()
+        };
+        Drawing::retain_used(&mut self.__layers, used)
+    }
+    /// Removes all line types that aren't used by an entity, layer, or multi-line style and returns the number of
+    /// line types removed.  The `BYLAYER`, `BYBLOCK`, and `CONTINUOUS` line types and the header's current line type
+    /// are kept.
+    pub fn purge_line_types(&mut self) -> usize {
+        let used = {
+            let index = self.build_handle_index();
+            let mut used_names = HashSet::new();
+            used_names.insert(String::from("BYLAYER"));
+            used_names.insert(String::from("BYBLOCK"));
+            used_names.insert(String::from("CONTINUOUS"));
+            used_names.insert(self.header.current_entity_line_type.to_ascii_uppercase());
+            for ent in self.all_entities() {
+                used_names.insert(ent.common.line_type_name.to_ascii_uppercase());
+            }
+            for layer in self.layers() {
+                used_names.insert(layer.line_type_name.to_ascii_uppercase());
+            }
+            for obj in self.objects() {
+                if let ObjectType::MLineStyle(ref style) = obj.specific {
+                    for element in &style.elements {
+                        used_names.insert(element.line_type.to_ascii_uppercase());
+                    }
+                }
+            }
+            self.line_types()
+                .map(|lt| {
+                    used_names.contains(&lt.name.to_ascii_uppercase())
+                        || Drawing::is_referenced_by_handle(&index, lt.handle)
+                })
+                .collect::<Vec<_>>()
+        };
+        Drawing::retain_used(&mut self.__line_types, used)
+    }
+    /// Removes all text styles that aren't used by an entity, attribute, or dimension style and returns the number of
+    /// styles removed.  The `STANDARD` and `ANNOTATIVE` styles, the header's current styles, and unnamed styles, which
+    /// register shape files, are kept.
+    pub fn purge_styles(&mut self) -> usize {
+        let used = {
+            let index = self.build_handle_index();
+            let mut used_names = HashSet::new();
+            used_names.insert(String::from("STANDARD"));
+            used_names.insert(String::from("ANNOTATIVE"));
+            used_names.insert(self.header.text_style.to_ascii_uppercase());
+            used_names.insert(self.header.dimension_text_style.to_ascii_uppercase());
+            for ent in self.all_entities() {
+                if let Some(name) = ent.specific.text_style_name() {
+                    used_names.insert(name.to_ascii_uppercase());
+                }
+                if let EntityType::Insert(ref ins) = ent.specific {
+                    for att in ins.attributes() {
+                        used_names.insert(att.text_style_name.to_ascii_uppercase());
+                    }
+                }
+            }
+            for ds in self.dim_styles() {
+                used_names.insert(ds.dimension_text_style.to_ascii_uppercase());
+            }
+            self.styles()
+                .map(|s| {
+                    s.name.is_empty()
+                        || used_names.contains(&s.name.to_ascii_uppercase())
+                        || Drawing::is_referenced_by_handle(&index, s.handle)
+                })
+                .collect::<Vec<_>>()
+        };
+        Drawing::retain_used(&mut self.__styles, used)
+    }
+    /// Renames the layer `old_name` to `new_name` and updates every entity, block, layer filter, dictionary entry, and
+    /// header value that refers to it.  Layer names are case-insensitive.  Returns `false` and does nothing if there is
+    /// no layer named `old_name` or if another layer is already named `new_name`.
+    pub fn rename_layer(&mut self, old_name: &str, new_name: &str) -> bool {
+        if new_name.is_empty()
+            || Drawing::is_name_taken(self.layers().map(|l| &*l.name), old_name, new_name)
+        {
+            return false;
+        }
+        let layer = match self
+            .__layers
+            .iter_mut()
+            .find(|l| l.name.eq_ignore_ascii_case(old_name))
+        {
+            Some(layer) => layer,
+            None => return false,
+        };
+        layer.name = String::from(new_name);
+        let layer_handle = layer.handle;
+
+        for ent in self.all_entities_mut() {
+            rename_if_matches(&mut ent.common.layer, old_name, new_name);
+        }
+        for block in &mut self.__blocks {
+            rename_if_matches(&mut block.layer, old_name, new_name);
+        }
+        for obj in &mut self.__objects {
+            let layer_names = match obj.specific {
+                ObjectType::LayerFilter(ref mut l) => &mut l.layer_names,
+                ObjectType::LayerIndex(ref mut l) => &mut l.layer_names,
+                _ => continue,
+            };
+            for layer_name in layer_names {
+                rename_if_matches(layer_name, old_name, new_name);
+            }
+        }
+        rename_if_matches(&mut self.header.current_layer, old_name, new_name);
+        self.rename_dictionary_keys(&[layer_handle], old_name, new_name);
+        true
+    }
+    /// Renames the block `old_name` to `new_name` and updates its block record and every insert, dimension, dimension
+    /// style, dictionary entry, and header value that refers to it.  Block names are case-insensitive.  Returns `false`
+    /// and does nothing if there is no block named `old_name` or if another block is already named `new_name`.
+    pub fn rename_block(&mut self, old_name: &str, new_name: &str) -> bool {
+        if new_name.is_empty()
+            || Drawing::is_name_taken(self.blocks().map(|b| &*b.name), old_name, new_name)
+            || Drawing::is_name_taken(self.block_records().map(|b| &*b.name), old_name, new_name)
+        {
+            return false;
+        }
+        let block = match self
+            .__blocks
+            .iter_mut()
+            .find(|b| b.name.eq_ignore_ascii_case(old_name))
+        {
+            Some(block) => block,
+            None => return false,
+        };
+        block.name = String::from(new_name);
+        let mut handles = vec![block.handle];
+        for block_record in &mut self.__block_records {
+            if block_record.name.eq_ignore_ascii_case(old_name) {
+                block_record.name = String::from(new_name);
+                handles.push(block_record.handle);
+            }
+        }
+
+        for ent in self.all_entities_mut() {
+            if let EntityType::Insert(ref mut ins) = ent.specific {
+                rename_if_matches(&mut ins.name, old_name, new_name);
+            }
+            if let Some(dim) = ent.specific.dimension_base_mut() {
+                rename_if_matches(&mut dim.block_name, old_name, new_name);
+            }
+        }
+        for ds in &mut self.__dim_styles {
+            rename_if_matches(&mut ds.arrow_block_name, old_name, new_name);
+            rename_if_matches(&mut ds.first_arrow_block_name, old_name, new_name);
+            rename_if_matches(&mut ds.second_arrow_block_name, old_name, new_name);
+            rename_if_matches(&mut ds.dimension_leader_block_name, old_name, new_name);
+        }
+        let header = &mut self.header;
+        rename_if_matches(&mut header.arrow_block_name, old_name, new_name);
+        rename_if_matches(&mut header.first_arrow_block_name, old_name, new_name);
+        rename_if_matches(&mut header.second_arrow_block_name, old_name, new_name);
+        rename_if_matches(&mut header.dimension_leader_block_name, old_name, new_name);
+        self.rename_dictionary_keys(&handles, old_name, new_name);
+        true
+    }
+    /// Returns world coordinate copies of the entities placed by the block reference.  The insertion point, scale,
+    /// rotation, extrusion direction, and row and column array of the reference are applied, nested block references
+    /// are expanded in turn, and the reference's attributes are included.  Non-constant attribute definitions in the
+    /// block are skipped and the copies have no handle.  Nothing is returned if the referenced block doesn't exist.
+    pub fn explode_insert<'a>(&'a self, insert: &Insert) -> impl Iterator<Item = Entity> + 'a {
+        ExplodedEntityIter::new(self, insert)
+    }
+    /// Computes the extents of the model space entities, including the contents of block references, or `None` if
+    /// there are no entities with extents.
+    pub fn compute_extents(&self) -> Option<BoundingBox> {
+        self.compute_entity_extents(false)
+    }
+    /// Computes the extents of the paper space entities, including the contents of block references, or `None` if
+    /// there are no entities with extents.
+    pub fn compute_paper_space_extents(&self) -> Option<BoundingBox> {
+        self.compute_entity_extents(true)
+    }
+    /// Sets the model and paper space extents in the header (`$EXTMIN`, `$EXTMAX`, `$PEXTMIN`, and `$PEXTMAX`) to the
+    /// computed extents.  Empty extents are written the same way AutoCAD does, with the minimum greater than the
+    /// maximum.
+    pub fn update_extents(&mut self) {
+        let (min, max) = Drawing::extents_or_empty(self.compute_extents());
+        self.header.minimum_drawing_extents = min;
+        self.header.maximum_drawing_extents = max;
+        let (min, max) = Drawing::extents_or_empty(self.compute_paper_space_extents());
+        self.header.paperspace_minimum_drawing_extents = min;
+        self.header.paperspace_maximum_drawing_extents = max;
+    }
     /// Normalizes the `Drawing` by ensuring expected items are present.
     pub fn normalize(&mut self) {
         // TODO: check for duplicates
@@ -492,6 +1006,7 @@ impl Drawing {
         self.normalize_objects();
         self.normalize_app_ids();
         self.normalize_block_records();
+        self.normalize_layouts();
         self.normalize_layers();
         self.normalize_text_styles();
         self.normalize_view_ports();
@@ -513,6 +1028,33 @@ impl Drawing {
         self.__views.sort_by(|a, b| a.name.cmp(&b.name));
         self.__view_ports.sort_by(|a, b| a.name.cmp(&b.name));
     }
+    /// Checks the `Drawing` for problems that can make it unreadable by other applications, e.g., duplicate handles,
+    /// pointers to missing items, or entities on missing layers.  Nothing is changed; see `audit_and_fix()`.
+    pub fn audit(&self) -> Vec<AuditIssue> {
+        audit::audit(self)
+    }
+    /// Same as `audit()`, but also fixes the problems that can be fixed without losing data.  The fixed issues have
+    /// `fixed` set.
+    pub fn audit_and_fix(&mut self) -> Vec<AuditIssue> {
+        audit::audit_and_fix(self)
+    }
+    /// Sets `$ACADVER` to `version` and lowers the entities that version can't hold to ones it can, so they aren't
+    /// dropped when saving, e.g., `LWPOLYLINE` becomes `POLYLINE`, `ELLIPSE` and `SPLINE` become polyline
+    /// approximations, and `MTEXT` becomes one `TEXT` per line.  True colors are replaced by the closest color index on
+    /// versions before R2004 and entities without an equivalent are removed.  Returns the changes that lost
+    /// information.
+    pub fn convert_to_version(&mut self, version: AcadVersion) -> Vec<LossyConversion> {
+        self.header.version = version;
+        let mut converter = VersionConverter::new(version, self.header.next_available_handle);
+        self.__entities = converter.convert(std::mem::take(&mut self.__entities));
+        for block in &mut self.__blocks {
+            block.entities = converter.convert(std::mem::take(&mut block.entities));
+        }
+
+        let (next_handle, losses) = converter.finish();
+        self.header.next_available_handle = next_handle;
+        losses
+    }
     /// Gets a `DrawingItem` with the appropriate handle or `None`.
     pub fn item_by_handle(&'_ self, handle: Handle) -> Option<DrawingItem<'_>> {
         for item in &self.__app_ids {
@@ -643,6 +1185,11 @@ impl Drawing {
 
         None
     }
+    /// Builds an index of every item's handle, allowing constant-time lookups by handle and queries for the items that
+    /// point at a given handle.  Prefer this over repeated calls to `item_by_handle()` when following many pointers.
+    pub fn build_handle_index(&self) -> HandleIndex<'_> {
+        HandleIndex::new(self)
+    }
     pub(crate) fn assign_and_get_handle(&mut self, item: &mut DrawingItemMut) -> Handle {
         if item.handle().is_empty() {
             item.set_handle(self.header.next_available_handle);
@@ -652,15 +1199,196 @@ impl Drawing {
 
         item.handle()
     }
+    /// Returns the block with the specified name; block names are case-insensitive.
+    pub(crate) fn block_by_name(&self, name: &str) -> Option<&Block> {
+        if name.is_empty() {
+            return None;
+        }
+        self.blocks().find(|b| b.name.eq_ignore_ascii_case(name))
+    }
+    /// Moves the classes, table entries, blocks, entities, and objects out of the drawing and into `items`, in that
+    /// order.  Only the entries of the tables accepted by `keep_table` are moved; the others are discarded.
+    pub(crate) fn take_stream_items<F>(
+        &mut self,
+        keep_table: F,
+        items: &mut VecDeque<DrawingStreamItem>,
+    ) where
+        F: Fn(&str) -> bool,
+    {
+        items.extend(self.classes.drain(..).map(DrawingStreamItem::Class));
+        macro_rules! take_table {
+            ($table_name:expr, $collection:ident, $variant:ident) => {
+                if keep_table($table_name) {
+                    items.extend(self.$collection.drain(..).map(DrawingStreamItem::$variant));
+                } else {
+                    self.$collection.clear();
+                }
+            };
+        }
+        take_table!("APPID", __app_ids, AppId);
+        take_table!("BLOCK_RECORD", __block_records, BlockRecord);
+        take_table!("DIMSTYLE", __dim_styles, DimStyle);
+        take_table!("LAYER", __layers, Layer);
+        take_table!("LTYPE", __line_types, LineType);
+        take_table!("STYLE", __styles, Style);
+        take_table!("UCS", __ucss, Ucs);
+        take_table!("VIEW", __views, View);
+        take_table!("VPORT", __view_ports, ViewPort);
+        items.extend(self.__blocks.drain(..).map(DrawingStreamItem::Block));
+        items.extend(self.__entities.drain(..).map(DrawingStreamItem::Entity));
+        items.extend(self.__objects.drain(..).map(DrawingStreamItem::Object));
+    }
 }
 
 // private implementation
 impl Drawing {
+    /// Decodes the control codes in the values of the text entities for `LoadOptions::decode_control_codes()`.
+    fn decode_control_codes(&mut self) {
+        fn decode(value: &mut String) {
+            *value = DecodedText::decode(value).plain_text();
+        }
+        let blocks = self.__blocks.iter_mut().flat_map(|b| b.entities.iter_mut());
+        for entity in self.__entities.iter_mut().chain(blocks) {
+            match entity.specific {
+                EntityType::Attribute(ref mut att) => decode(&mut att.value),
+                EntityType::AttributeDefinition(ref mut att) => {
+                    decode(&mut att.value);
+                    decode(&mut att.prompt);
+                }
+                EntityType::Insert(ref mut ins) => {
+                    for att in ins.attributes_mut() {
+                        decode(&mut att.value);
+                    }
+                }
+                EntityType::Text(ref mut text) => decode(&mut text.value),
+                _ => (),
+            }
+        }
+    }
+    /// Prepares an entity to be added by assigning new handles to it and its child items.
+    pub(crate) fn prepare_new_entity(&mut self, entity: &mut Entity) {
+        entity.common.handle = self.next_handle();
+
+        // set child handles
+        match entity.specific {
+            EntityType::Insert(ref mut ins) => {
+                ins.__seqend_handle = self.next_handle();
+                for a in ins.__attributes_and_handles.iter_mut() {
+                    if a.1 == AUTO_REPLACE_HANDLE {
+                        a.1 = self.next_handle();
+                    }
+                }
+            }
+            EntityType::Polyline(ref mut poly) => {
+                poly.__seqend_handle = self.next_handle();
+                for v in poly.__vertices_and_handles.iter_mut() {
+                    if v.1 == AUTO_REPLACE_HANDLE {
+                        v.1 = self.next_handle();
+                    }
+                }
+            }
+            EntityType::Viewport(_) => {
+                // viewports only ever live in paper space
+                entity.common.is_in_paper_space = true;
+            }
+            _ => (),
+        }
+    }
     pub(crate) fn next_handle(&mut self) -> Handle {
         let result = self.header.next_available_handle;
         self.header.next_available_handle = self.header.next_available_handle.next_handle_value();
         result
     }
+    /// Removes the items not flagged in `used` and returns the number of items removed.
+    fn retain_used<T>(collection: &mut Vec<T>, used: Vec<bool>) -> usize {
+        let count = collection.len();
+        let mut used = used.into_iter();
+        collection.retain(|_| used.next().unwrap_or(true));
+        count - collection.len()
+    }
+    /// Returns all entities, including those contained in blocks.
+    fn all_entities(&self) -> impl Iterator<Item = &Entity> {
+        self.__entities
+            .iter()
+            .chain(self.__blocks.iter().flat_map(|b| b.entities.iter()))
+    }
+    fn all_entities_mut(&mut self) -> impl Iterator<Item = &mut Entity> {
+        self.__entities
+            .iter_mut()
+            .chain(self.__blocks.iter_mut().flat_map(|b| b.entities.iter_mut()))
+    }
+    /// Returns the upper-case names of the blocks used by other items.  A block inserting itself doesn't count.
+    fn used_block_names(&self) -> HashSet<String> {
+        let mut names = HashSet::new();
+        let mut add_entity_names = |ent: &Entity, containing_block: Option<&str>| {
+            let name = match ent.specific {
+                EntityType::Insert(ref ins) => &ins.name,
+                _ => match ent.specific.dimension_base() {
+                    Some(dim) => &dim.block_name,
+                    None => return,
+                },
+            };
+            if !containing_block.is_some_and(|b| b.eq_ignore_ascii_case(name)) {
+                names.insert(name.to_ascii_uppercase());
+            }
+        };
+        for ent in self.entities() {
+            add_entity_names(ent, None);
+        }
+        for block in self.blocks() {
+            for ent in &block.entities {
+                add_entity_names(ent, Some(&block.name));
+            }
+        }
+        for ds in self.dim_styles() {
+            names.insert(ds.arrow_block_name.to_ascii_uppercase());
+            names.insert(ds.first_arrow_block_name.to_ascii_uppercase());
+            names.insert(ds.second_arrow_block_name.to_ascii_uppercase());
+            names.insert(ds.dimension_leader_block_name.to_ascii_uppercase());
+        }
+        names.insert(self.header.arrow_block_name.to_ascii_uppercase());
+        names.insert(self.header.first_arrow_block_name.to_ascii_uppercase());
+        names.insert(self.header.second_arrow_block_name.to_ascii_uppercase());
+        names.insert(self.header.dimension_leader_block_name.to_ascii_uppercase());
+        names
+    }
+    fn is_layout_block_name(name: &str) -> bool {
+        let name = name.to_ascii_uppercase();
+        name.starts_with("*MODEL_SPACE") || name.starts_with("*PAPER_SPACE")
+    }
+    /// Returns `true` if anything other than the item itself or the items it owns, e.g., its extension dictionary or
+    /// a block's entities, points to `handle`.
+    fn is_referenced_by_handle(index: &HandleIndex, handle: Handle) -> bool {
+        !handle.is_empty()
+            && index
+                .items_referencing(handle)
+                .any(|item| item.handle() != handle && item.owner_handle() != handle)
+    }
+    /// Returns `true` if `new_name` is in `names` and isn't just a different casing of `old_name`.
+    fn is_name_taken<'a, I>(mut names: I, old_name: &str, new_name: &str) -> bool
+    where
+        I: Iterator<Item = &'a str>,
+    {
+        !old_name.eq_ignore_ascii_case(new_name) && names.any(|n| n.eq_ignore_ascii_case(new_name))
+    }
+    /// Renames the dictionary entries named `old_name` that point to one of `handles`.
+    fn rename_dictionary_keys(&mut self, handles: &[Handle], old_name: &str, new_name: &str) {
+        for obj in &mut self.__objects {
+            let value_handles = match obj.specific {
+                ObjectType::Dictionary(ref mut d) => &mut d.value_handles,
+                ObjectType::DictionaryWithDefault(ref mut d) => &mut d.value_handles,
+                _ => continue,
+            };
+            let key = value_handles
+                .iter()
+                .find(|(k, h)| k.eq_ignore_ascii_case(old_name) && handles.contains(h))
+                .map(|(k, _)| k.clone());
+            if let Some(key) = key {
+                let handle = value_handles.remove(&key).unwrap();
+                value_handles.insert(String::from(new_name), handle);
+            }
+        }
+    }
     fn remove_item<T>(collection: &mut Vec<T>, index: usize) -> Option<T> {
         if index < collection.len() {
             Some(collection.remove(index))
@@ -690,6 +1418,7 @@ impl Drawing {
         self.ensure_line_type_is_present_for_object(&obj);
         self.ensure_text_style_is_present_for_object(&obj);
         self.ensure_view_is_present(&obj);
+        self.ensure_layout_is_linked_to_block_record(&obj);
         self.__objects.push(obj);
         self.__objects.last().unwrap()
     }
@@ -772,20 +1501,8 @@ impl Drawing {
     }
     fn ensure_dimension_style_is_present_for_entity(&mut self, entity: &Entity) {
         // ensure corresponding dimension style is present
-        let dim_style_name = match &entity.specific {
-            EntityType::RotatedDimension(ref d) => Some(&d.dimension_base.dimension_style_name),
-            EntityType::RadialDimension(ref d) => Some(&d.dimension_base.dimension_style_name),
-            EntityType::DiameterDimension(ref d) => Some(&d.dimension_base.dimension_style_name),
-            EntityType::AngularThreePointDimension(ref d) => {
-                Some(&d.dimension_base.dimension_style_name)
-            }
-            EntityType::OrdinateDimension(ref d) => Some(&d.dimension_base.dimension_style_name),
-            EntityType::Leader(ref l) => Some(&l.dimension_style_name),
-            EntityType::Tolerance(ref t) => Some(&t.dimension_style_name),
-            _ => None,
-        };
-        if let Some(dim_style_name) = dim_style_name {
-            self.ensure_dimension_style_is_present(&dim_style_name);
+        if let Some(dim_style_name) = entity.specific.dimension_style_name() {
+            self.ensure_dimension_style_is_present(dim_style_name);
         }
     }
     fn ensure_dimension_style_is_present(&mut self, dim_style_name: &str) {
@@ -855,16 +1572,8 @@ impl Drawing {
         }
     }
     fn ensure_text_style_is_present_for_entity(&mut self, entity: &Entity) {
-        let text_style_name = match &entity.specific {
-            EntityType::ArcAlignedText(ref e) => Some(&e.text_style_name),
-            EntityType::Attribute(ref e) => Some(&e.text_style_name),
-            EntityType::AttributeDefinition(ref e) => Some(&e.text_style_name),
-            EntityType::MText(ref e) => Some(&e.text_style_name),
-            EntityType::Text(ref e) => Some(&e.text_style_name),
-            _ => None,
-        };
-        if let Some(text_style_name) = text_style_name {
-            self.ensure_text_style_is_present(&text_style_name);
+        if let Some(text_style_name) = entity.specific.text_style_name() {
+            self.ensure_text_style_is_present(text_style_name);
         }
     }
     fn ensure_text_style_is_present_for_object(&mut self, obj: &Object) {
@@ -898,6 +1607,19 @@ impl Drawing {
             }
         }
     }
+    fn ensure_layout_is_linked_to_block_record(&mut self, obj: &Object) {
+        if let ObjectType::Layout(ref layout) = &obj.specific {
+            let layout_handle = obj.common.handle;
+            let block_record_handle = layout.__block_record_handle;
+            if let Some(block_record) = self
+                .__block_records
+                .iter_mut()
+                .find(|b| !b.handle.is_empty() && b.handle == block_record_handle)
+            {
+                block_record.__layout_handle = layout_handle;
+            }
+        }
+    }
     fn ensure_view_port_is_present(&mut self, name: &str) {
         if !self.view_ports().any(|v| v.name == name) {
             self.add_view_port(ViewPort {
@@ -938,26 +1660,6 @@ impl Drawing {
 
         pairs.push(CodePair::new_str(0, "ENDSEC"));
     }
-    pub(crate) fn add_entities_pairs(&self, pairs: &mut Vec<CodePair>, write_handles: bool) {
-        pairs.push(CodePair::new_str(0, "SECTION"));
-        pairs.push(CodePair::new_str(2, "ENTITIES"));
-        for e in &self.__entities {
-            e.add_code_pairs(pairs, self.header.version, write_handles);
-        }
-
-        pairs.push(CodePair::new_str(0, "ENDSEC"));
-    }
-    pub(crate) fn add_objects_pairs(&self, pairs: &mut Vec<CodePair>) {
-        if self.header.version >= AcadVersion::R13 {
-            pairs.push(CodePair::new_str(0, "SECTION"));
-            pairs.push(CodePair::new_str(2, "OBJECTS"));
-            for o in &self.__objects {
-                o.add_code_pairs(pairs, self.header.version);
-            }
-
-            pairs.push(CodePair::new_str(0, "ENDSEC"));
-        }
-    }
     pub(crate) fn add_thumbnail_pairs(&self, pairs: &mut Vec<CodePair>) -> DxfResult<()> {
         if self.header.version >= AcadVersion::R2000 {
             if let Some(ref img) = self.thumbnail {
@@ -976,83 +1678,212 @@ impl Drawing {
         }
         Ok(())
     }
-    fn read_sections(drawing: &mut Drawing, iter: &mut CodePairPutBack) -> DxfResult<()> {
+    fn read_sections(
+        drawing: &mut Drawing,
+        iter: &mut CodePairPutBack,
+        options: &LoadOptions,
+    ) -> DxfResult<()> {
+        let mut position = ReadPosition::Sections;
+        while !matches!(position, ReadPosition::End) {
+            if let Some(item) = drawing.read_next(iter, options, &mut position)? {
+                drawing.add_read_item(item, options);
+            }
+        }
+
+        Ok(())
+    }
+    /// Reads the next section, or the next entity or object in the middle of the `ENTITIES` or `OBJECTS` section.
+    /// Entities and objects are returned instead of added, so nothing is changed until they've been fully read.
+    fn read_next(
+        &mut self,
+        iter: &mut CodePairPutBack,
+        options: &LoadOptions,
+        position: &mut ReadPosition,
+    ) -> DxfResult<Option<ReadItem>> {
+        match position {
+            ReadPosition::Sections => self.read_next_section(iter, options, position)?,
+            ReadPosition::Entities(combiner) => {
+                let mut iter = EntityIter { iter };
+                let result =
+                    combiner.next(|| Ok(iter.next_of_type(|t| options.reads_entity_type(t))));
+                if let Ok(Some(entity)) = result {
+                    return Ok(Some(ReadItem::Entity(Box::new(entity))));
+                }
+
+                *position = ReadPosition::Sections;
+                Drawing::end_section(iter.iter, result.map(|_| ()))?;
+            }
+            ReadPosition::Objects => match (ObjectIter { iter }).next() {
+                Some(object) => return Ok(Some(ReadItem::Object(Box::new(object)))),
+                None => {
+                    *position = ReadPosition::Sections;
+                    Drawing::end_section(iter, Ok(()))?;
+                }
+            },
+            ReadPosition::End => (),
+        }
+
+        Ok(None)
+    }
+    fn read_next_section(
+        &mut self,
+        iter: &mut CodePairPutBack,
+        options: &LoadOptions,
+        position: &mut ReadPosition,
+    ) -> DxfResult<()> {
+        match iter.next() {
+            Some(Ok(pair @ CodePair { code: 0, .. })) => match &*pair.assert_string()? {
+                "EOF" => {
+                    iter.put_back(Ok(pair));
+                    *position = ReadPosition::End;
+                }
+                "SECTION" => match iter.next() {
+                    Some(Ok(CodePair {
+                        code: 2,
+                        value: CodePairValue::Str(s),
+                        ..
+                    })) => {
+                        let result = match &*s {
+                            _ if options.skips_section(&s) => Drawing::swallow_section(iter),
+                            "HEADER" => Header::read(iter).map(|header| self.header = header),
+                            "CLASSES" => Class::read_classes(self, iter),
+                            "TABLES" => self.read_section_item(iter, "TABLE", read_specific_table),
+                            "BLOCKS" => self.read_section_item(iter, "BLOCK", Block::read_block),
+                            "ENTITIES" => match self.read_all_entities(iter, options) {
+                                Ok(false) => {
+                                    *position = ReadPosition::Entities(Box::default());
+                                    return Ok(());
+                                }
+                                result => result.map(|_| ()),
+                            },
+                            "OBJECTS" => {
+                                *position = ReadPosition::Objects;
+                                return Ok(());
+                            }
+                            "THUMBNAILIMAGE" => thumbnail::read_thumbnail(iter)
+                                .map(|thumbnail| self.thumbnail = thumbnail),
+                            _ => Drawing::swallow_section(iter),
+                        };
+                        Drawing::end_section(iter, result)?;
+                    }
+                    Some(Ok(pair)) => {
+                        let error = DxfError::UnexpectedCodePair(
+                            pair,
+                            String::from("expected 2/<section-name>"),
+                        );
+                        iter.recover(error, SkippedContent::Section)?;
+                        Drawing::skip_rest_of_section(iter)?;
+                    }
+                    Some(Err(e)) => return Err(e),
+                    None => return Err(DxfError::UnexpectedEndOfInput),
+                },
+                _ => {
+                    let error =
+                        DxfError::UnexpectedCodePair(pair, String::from("expected 0/SECTION"));
+                    iter.recover(error, SkippedContent::Item)?;
+                    iter.skip_to_next_item()?;
+                }
+            },
+            Some(Ok(pair)) => iter.recover(
+                DxfError::UnexpectedCodePair(pair, String::from("expected 0/SECTION or 0/EOF")),
+                SkippedContent::Pair,
+            )?,
+            Some(Err(e)) => return Err(e),
+            None => *position = ReadPosition::End, // ideally should have been 0/EOF
+        }
+
+        Ok(())
+    }
+    /// Returns `true` if the items of the section `name` are read one at a time by `read_next()`.
+    #[cfg(feature = "async")]
+    fn reads_section_items(name: &str, options: &LoadOptions) -> bool {
+        (name == "ENTITIES" || name == "OBJECTS") && !options.skips_section(name)
+    }
+    /// Reads the `0/ENDSEC` after a section that was read with `result`, or skips the rest of the section if reading
+    /// it failed.
+    fn end_section(iter: &mut CodePairPutBack, result: DxfResult<()>) -> DxfResult<()> {
+        if let Err(e) = result {
+            iter.recover(e, SkippedContent::Section)?;
+            return Drawing::skip_rest_of_section(iter);
+        }
+
+        match iter.next() {
+            Some(Ok(CodePair {
+                code: 0,
+                value: CodePairValue::Str(ref s),
+                ..
+            })) if s == "ENDSEC" => (),
+            Some(Ok(pair)) => {
+                let error =
+                    DxfError::UnexpectedCodePair(pair.clone(), String::from("expected 0/ENDSEC"));
+                iter.recover(error, SkippedContent::Section)?;
+                iter.put_back(Ok(pair));
+                Drawing::skip_rest_of_section(iter)?;
+            }
+            Some(Err(e)) => return Err(e),
+            None => return Err(DxfError::UnexpectedEndOfInput),
+        }
+
+        Ok(())
+    }
+    fn read_eof(iter: &mut CodePairPutBack) -> DxfResult<()> {
+        match iter.next() {
+            Some(Ok(CodePair {
+                code: 0,
+                value: CodePairValue::Str(ref s),
+                ..
+            })) if s == "EOF" => (),
+            Some(Ok(pair)) => iter.recover(
+                DxfError::UnexpectedCodePair(pair, String::from("expected 0/EOF")),
+                SkippedContent::Section,
+            )?,
+            Some(Err(e)) => iter.recover(e, SkippedContent::Section)?,
+            None => (),
+        }
+
+        Ok(())
+    }
+    /// Skips the rest of a malformed section up to and including its `0/ENDSEC`, or up to the next section if the
+    /// `0/ENDSEC` is missing.
+    fn skip_rest_of_section(iter: &mut CodePairPutBack) -> DxfResult<()> {
         loop {
             match iter.next() {
                 Some(Ok(pair @ CodePair { code: 0, .. })) => match &*pair.assert_string()? {
-                    "EOF" => {
+                    "ENDSEC" => break,
+                    "SECTION" | "EOF" => {
                         iter.put_back(Ok(pair));
                         break;
                     }
-                    "SECTION" => match iter.next() {
-                        Some(Ok(CodePair {
-                            code: 2,
-                            value: CodePairValue::Str(s),
-                            ..
-                        })) => {
-                            match &*s {
-                                "HEADER" => drawing.header = Header::read(iter)?,
-                                "CLASSES" => Class::read_classes(drawing, iter)?,
-                                "TABLES" => {
-                                    drawing.read_section_item(iter, "TABLE", read_specific_table)?
-                                }
-                                "BLOCKS" => {
-                                    drawing.read_section_item(iter, "BLOCK", Block::read_block)?
-                                }
-                                "ENTITIES" => drawing.read_entities(iter)?,
-                                "OBJECTS" => drawing.read_objects(iter)?,
-                                "THUMBNAILIMAGE" => {
-                                    drawing.thumbnail = thumbnail::read_thumbnail(iter)?;
-                                }
-                                _ => Drawing::swallow_section(iter)?,
-                            }
+                    _ => (),
+                },
+                Some(Ok(_)) => (),
+                Some(Err(e)) => return Err(e),
+                None => break,
+            }
+        }
 
-                            match iter.next() {
-                                Some(Ok(CodePair {
-                                    code: 0,
-                                    value: CodePairValue::Str(ref s),
-                                    ..
-                                })) if s == "ENDSEC" => (),
-                                Some(Ok(pair)) => {
-                                    return Err(DxfError::UnexpectedCodePair(
-                                        pair,
-                                        String::from("expected 0/ENDSEC"),
-                                    ))
-                                }
-                                Some(Err(e)) => return Err(e),
-                                None => return Err(DxfError::UnexpectedEndOfInput),
-                            }
-                        }
-                        Some(Ok(pair)) => {
-                            return Err(DxfError::UnexpectedCodePair(
-                                pair,
-                                String::from("expected 2/<section-name>"),
-                            ))
-                        }
-                        Some(Err(e)) => return Err(e),
-                        None => return Err(DxfError::UnexpectedEndOfInput),
-                    },
-                    _ => {
-                        return Err(DxfError::UnexpectedCodePair(
-                            pair,
-                            String::from("expected 0/SECTION"),
-                        ))
+        Ok(())
+    }
+    /// Skips code pairs up to the next `0/<item_type>` or `0/ENDSEC`, which is put back.
+    fn skip_to_section_item(iter: &mut CodePairPutBack, item_type: &str) -> DxfResult<()> {
+        loop {
+            match iter.next() {
+                Some(Ok(pair @ CodePair { code: 0, .. })) => {
+                    let value = pair.assert_string()?;
+                    if value == item_type || value == "ENDSEC" {
+                        iter.put_back(Ok(pair));
+                        break;
                     }
-                },
-                Some(Ok(pair)) => {
-                    return Err(DxfError::UnexpectedCodePair(
-                        pair,
-                        String::from("expected 0/SECTION or 0/EOF"),
-                    ))
                 }
+                Some(Ok(_)) => (),
                 Some(Err(e)) => return Err(e),
-                None => break, // ideally should have been 0/EOF
+                None => break,
             }
         }
 
         Ok(())
     }
-    fn swallow_section(iter: &mut CodePairPutBack) -> DxfResult<()> {
+    pub(crate) fn swallow_section(iter: &mut CodePairPutBack) -> DxfResult<()> {
         loop {
             match iter.next() {
                 Some(Ok(pair)) => {
@@ -1068,30 +1899,58 @@ impl Drawing {
 
         Ok(())
     }
-    fn read_entities(&mut self, iter: &mut CodePairPutBack) -> DxfResult<()> {
-        let mut iter = EntityIter { iter };
-        let mut entities = vec![];
-        iter.read_entities_into_vec(&mut entities)?;
-        for e in entities {
-            if e.common.handle.is_empty() {
-                self.add_entity(e);
-            } else {
-                self.add_entity_no_handle_set(e);
+    /// Reads and adds all of the entities at once for `LoadOptions::parallel_entities()`.  Returns `false` if they
+    /// have to be read one at a time instead.
+    #[cfg(feature = "parallel")]
+    fn read_all_entities(
+        &mut self,
+        iter: &mut CodePairPutBack,
+        options: &LoadOptions,
+    ) -> DxfResult<bool> {
+        if options.parallel_entities {
+            if let Some(entities) = read_entities_in_parallel(iter, options)? {
+                let mut entities = entities.into_iter();
+                let mut combiner = EntityCombiner::default();
+                while let Some(entity) = combiner.next(|| Ok(entities.next()))? {
+                    self.add_read_item(ReadItem::Entity(Box::new(entity)), options);
+                }
+
+                return Ok(true);
             }
         }
-        Ok(())
+
+        Ok(false)
     }
-    fn read_objects(&mut self, iter: &mut CodePairPutBack) -> DxfResult<()> {
-        let iter = put_back(ObjectIter { iter });
-        for o in iter {
-            if o.common.handle.is_empty() {
-                self.add_object(o);
-            } else {
-                self.add_object_no_handle_set(o);
+    #[cfg(not(feature = "parallel"))]
+    fn read_all_entities(
+        &mut self,
+        _iter: &mut CodePairPutBack,
+        _options: &LoadOptions,
+    ) -> DxfResult<bool> {
+        Ok(false)
+    }
+    /// Adds an entity that passes the filters in `options`, or an object.
+    fn add_read_item(&mut self, item: ReadItem, options: &LoadOptions) {
+        match item {
+            ReadItem::Entity(e) => {
+                if !options.keeps_entity(&e) {
+                    return;
+                }
+
+                if e.common.handle.is_empty() {
+                    self.add_entity(*e);
+                } else {
+                    self.add_entity_no_handle_set(*e);
+                }
+            }
+            ReadItem::Object(o) => {
+                if o.common.handle.is_empty() {
+                    self.add_object(*o);
+                } else {
+                    self.add_object_no_handle_set(*o);
+                }
             }
         }
-
-        Ok(())
     }
     fn read_section_item<F>(
         &mut self,
@@ -1112,15 +1971,22 @@ impl Drawing {
                                 break;
                             }
                             val => {
-                                if val == item_type {
-                                    callback(self, iter)?;
+                                let result = if val == item_type {
+                                    callback(self, iter)
                                 } else {
-                                    return Err(DxfError::UnexpectedCodePair(pair, String::new()));
+                                    Err(DxfError::UnexpectedCodePair(pair, String::new()))
+                                };
+                                if let Err(e) = result {
+                                    iter.recover(e, SkippedContent::Item)?;
+                                    Drawing::skip_to_section_item(iter, item_type)?;
                                 }
                             }
                         }
                     } else {
-                        return Err(DxfError::UnexpectedCodePair(pair, String::new()));
+                        iter.recover(
+                            DxfError::UnexpectedCodePair(pair, String::new()),
+                            SkippedContent::Pair,
+                        )?;
                     }
                 }
                 Some(Err(e)) => return Err(e),
@@ -1151,6 +2017,21 @@ impl Drawing {
 
         Ok(())
     }
+    fn compute_entity_extents(&self, is_in_paper_space: bool) -> Option<BoundingBox> {
+        self.entities()
+            .filter(|e| e.common.is_in_paper_space == is_in_paper_space)
+            .map(|e| e.bounding_box_with_blocks(self))
+            .fold(None, BoundingBox::union_option)
+    }
+    fn extents_or_empty(extents: Option<BoundingBox>) -> (Point, Point) {
+        match extents {
+            Some(extents) => (extents.min, extents.max),
+            None => (
+                Point::new(1.0e20, 1.0e20, 1.0e20),
+                Point::new(-1.0e20, -1.0e20, -1.0e20),
+            ),
+        }
+    }
     fn normalize_blocks(&mut self) {
         for b in self.blocks_mut() {
             b.normalize();
@@ -1178,6 +2059,50 @@ impl Drawing {
         self.ensure_block_record_is_present("*MODEL_SPACE");
         self.ensure_block_record_is_present("*PAPER_SPACE");
     }
+    fn normalize_layouts(&mut self) {
+        // point each paper space block record back at its layout
+        let layouts = self
+            .objects()
+            .filter_map(|o| match o.specific {
+                ObjectType::Layout(ref layout) => {
+                    Some((o.common.handle, layout.__block_record_handle))
+                }
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        for &(layout_handle, block_record_handle) in &layouts {
+            if let Some(block_record) = self
+                .__block_records
+                .iter_mut()
+                .find(|b| !b.handle.is_empty() && b.handle == block_record_handle)
+            {
+                block_record.__layout_handle = layout_handle;
+            }
+        }
+
+        // drop links to layouts that no longer exist
+        let layout_handles = layouts.iter().map(|l| l.0).collect::<Vec<_>>();
+        for block_record in self.__block_records.iter_mut() {
+            if !layout_handles.contains(&block_record.__layout_handle) {
+                block_record.__layout_handle = Handle::empty();
+            }
+        }
+
+        // drop links to viewports that no longer exist; viewports of inactive layouts live in blocks
+        let viewport_handles = self
+            .entities()
+            .chain(self.blocks().flat_map(|b| b.entities.iter()))
+            .filter(|e| matches!(e.specific, EntityType::Viewport(_)))
+            .map(|e| e.common.handle)
+            .collect::<Vec<_>>();
+        for o in self.__objects.iter_mut() {
+            if let ObjectType::Layout(ref mut layout) = o.specific {
+                if !viewport_handles.contains(&layout.__viewport_handle) {
+                    layout.__viewport_handle = Handle::empty();
+                }
+            }
+        }
+    }
     fn normalize_layers(&mut self) {
         self.ensure_layer_is_present(&self.header.current_layer.clone());
         for l in self.layers_mut() {
@@ -1241,6 +2166,66 @@ impl Drawing {
     }
 }
 
+impl<'a> CodePairProducer<'a> {
+    fn new(drawing: &'a Drawing) -> Self {
+        CodePairProducer {
+            drawing,
+            write_handles: drawing.header.version >= AcadVersion::R13
+                || drawing.header.handles_enabled,
+            next_part: DrawingPart::Start,
+        }
+    }
+    /// Adds the code pairs of the next section, entity, or object.  Returns `false` once everything has been added.
+    fn add_next_pairs(&mut self, pairs: &mut Vec<CodePair>) -> DxfResult<bool> {
+        let drawing = self.drawing;
+        let version = drawing.header.version;
+        self.next_part = match self.next_part {
+            DrawingPart::Start => {
+                drawing.header.add_code_pairs(pairs);
+                drawing.add_classes_pairs(pairs);
+                drawing.add_tables_pairs(pairs, self.write_handles);
+                drawing.add_blocks_pairs(pairs, self.write_handles);
+                pairs.push(CodePair::new_str(0, "SECTION"));
+                pairs.push(CodePair::new_str(2, "ENTITIES"));
+                DrawingPart::Entity(0)
+            }
+            DrawingPart::Entity(index) => match drawing.__entities.get(index) {
+                Some(e) => {
+                    e.add_code_pairs(pairs, version, self.write_handles);
+                    DrawingPart::Entity(index + 1)
+                }
+                None => {
+                    pairs.push(CodePair::new_str(0, "ENDSEC"));
+                    DrawingPart::Objects
+                }
+            },
+            DrawingPart::Objects if version >= AcadVersion::R13 => {
+                pairs.push(CodePair::new_str(0, "SECTION"));
+                pairs.push(CodePair::new_str(2, "OBJECTS"));
+                DrawingPart::Object(0)
+            }
+            DrawingPart::Objects => DrawingPart::End,
+            DrawingPart::Object(index) => match drawing.__objects.get(index) {
+                Some(o) => {
+                    o.add_code_pairs(pairs, version);
+                    DrawingPart::Object(index + 1)
+                }
+                None => {
+                    pairs.push(CodePair::new_str(0, "ENDSEC"));
+                    DrawingPart::End
+                }
+            },
+            DrawingPart::End => {
+                drawing.add_thumbnail_pairs(pairs)?;
+                pairs.push(CodePair::new_str(0, "EOF"));
+                DrawingPart::Done
+            }
+            DrawingPart::Done => return Ok(false),
+        };
+        Ok(true)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::entities::*;
@@ -2101,4 +3086,283 @@ mod tests {
         let views = drawing.views().filter(|&v| v.name == "some-view");
         assert_eq!(1, views.count());
     }
+
+    fn layer_names(drawing: &Drawing) -> Vec<&str> {
+        drawing.layers().map(|l| &*l.name).collect()
+    }
+
+    fn line_on_layer(layer: &str) -> Entity {
+        let mut ent = Entity::new(EntityType::Line(Default::default()));
+        ent.common.layer = String::from(layer);
+        ent
+    }
+
+    fn insert_of(block_name: &str) -> Entity {
+        Entity::new(EntityType::Insert(Insert {
+            name: String::from(block_name),
+            ..Default::default()
+        }))
+    }
+
+    fn block_with(name: &str, entities: Vec<Entity>) -> Block {
+        Block {
+            name: String::from(name),
+            entities,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn purge_layers_removes_only_unused_layers() {
+        let mut drawing = Drawing::new();
+        drawing.add_layer(Layer {
+            name: String::from("unused"),
+            ..Default::default()
+        });
+        drawing.add_entity(line_on_layer("used-by-entity"));
+        drawing.add_block(block_with("b", vec![line_on_layer("used-by-block")]));
+        drawing.add_entity(insert_of("b"));
+        drawing.add_layer(Layer {
+            name: String::from("current"),
+            ..Default::default()
+        });
+        drawing.header.current_layer = String::from("CURRENT");
+
+        assert_eq!(1, drawing.purge_layers());
+        let layers = layer_names(&drawing);
+        assert!(layers.contains(&"0"));
+        assert!(layers.contains(&"used-by-entity"));
+        assert!(layers.contains(&"used-by-block"));
+        assert!(layers.contains(&"current"));
+        assert!(!layers.contains(&"unused"));
+    }
+
+    #[test]
+    fn purge_and_rename_layers_of_unknown_entities() {
+        let mut drawing = Drawing::new();
+        drawing.add_layer(Layer {
+            name: String::from("custom"),
+            ..Default::default()
+        });
+        let mut unknown = Entity::new(EntityType::Unknown {
+            type_string: String::from("UNSUPPORTED_ENTITY"),
+            pairs: vec![
+                CodePair::new_str(100, "AcDbEntity"),
+                CodePair::new_str(8, "custom"),
+                CodePair::new_str(1, "unsupported string"),
+            ],
+        });
+        unknown.common.layer = String::from("custom");
+        drawing.add_entity(unknown);
+        let mut drawing = drawing_with_unknown_items_from_pairs(drawing.code_pairs().unwrap());
+        assert_eq!("custom", drawing.entities().next().unwrap().common.layer);
+
+        assert_eq!(0, drawing.purge_layers());
+        assert!(layer_names(&drawing).contains(&"custom"));
+
+        assert!(drawing.rename_layer("custom", "renamed"));
+        assert_contains_pairs(
+            &drawing,
+            vec![
+                CodePair::new_str(100, "AcDbEntity"),
+                CodePair::new_str(8, "renamed"),
+                CodePair::new_str(1, "unsupported string"),
+            ],
+        );
+    }
+
+    #[test]
+    fn purge_layers_keeps_layers_referenced_by_handle() {
+        let mut drawing = Drawing::new();
+        let layer_handle = drawing
+            .add_layer(Layer {
+                name: String::from("frozen-in-viewport"),
+                ..Default::default()
+            })
+            .handle;
+        let mut viewport = Viewport::default();
+        viewport.__frozen_layers_handle.push(layer_handle);
+        drawing.add_entity(Entity::new(EntityType::Viewport(viewport)));
+
+        assert_eq!(0, drawing.purge_layers());
+        assert!(layer_names(&drawing).contains(&"frozen-in-viewport"));
+    }
+
+    #[test]
+    fn purge_blocks_removes_nested_unused_blocks_and_their_records() {
+        let mut drawing = Drawing::new();
+        drawing.add_block(block_with("inner", vec![line_on_layer("0")]));
+        drawing.add_block(block_with("outer", vec![insert_of("inner")]));
+        drawing.add_block(block_with("inserted", vec![insert_of("inserted")]));
+        drawing.add_entity(insert_of("INSERTED"));
+
+        assert_eq!(2, drawing.purge_blocks());
+        let blocks = drawing.blocks().map(|b| &*b.name).collect::<Vec<_>>();
+        assert_eq!(vec!["inserted"], blocks);
+        assert!(drawing
+            .block_records()
+            .all(|br| br.name != "inner" && br.name != "outer"));
+        assert!(drawing.block_records().any(|br| br.name == "inserted"));
+    }
+
+    #[test]
+    fn purge_removes_items_only_used_by_purged_blocks() {
+        let mut drawing = Drawing::new();
+        let mut line = line_on_layer("block-layer");
+        line.common.line_type_name = String::from("block-line-type");
+        drawing.add_block(block_with("unused", vec![line]));
+        drawing
+            .layers_mut()
+            .find(|l| l.name == "block-layer")
+            .unwrap()
+            .line_type_name = String::from("CONTINUOUS");
+
+        assert!(drawing.purge() >= 3);
+        assert!(drawing.blocks().next().is_none());
+        assert!(!layer_names(&drawing).contains(&"block-layer"));
+        assert!(drawing.line_types().all(|lt| lt.name != "block-line-type"));
+        assert!(drawing.line_types().any(|lt| lt.name == "CONTINUOUS"));
+        assert!(layer_names(&drawing).contains(&"0"));
+        assert_eq!(0, drawing.purge());
+    }
+
+    #[test]
+    fn purge_line_types_keeps_line_types_used_by_layers() {
+        let mut drawing = Drawing::new();
+        drawing.add_line_type(LineType {
+            name: String::from("dashed"),
+            ..Default::default()
+        });
+        drawing.add_line_type(LineType {
+            name: String::from("unused"),
+            ..Default::default()
+        });
+        drawing.add_layer(Layer {
+            name: String::from("dashed-layer"),
+            line_type_name: String::from("DASHED"),
+            ..Default::default()
+        });
+
+        assert_eq!(1, drawing.purge_line_types());
+        assert!(drawing.line_types().any(|lt| lt.name == "dashed"));
+        assert!(drawing.line_types().all(|lt| lt.name != "unused"));
+    }
+
+    #[test]
+    fn purge_styles_and_dim_styles() {
+        let mut drawing = Drawing::new();
+        drawing.add_entity(Entity::new(EntityType::Text(Text {
+            text_style_name: String::from("text-style"),
+            ..Default::default()
+        })));
+        drawing.add_dim_style(DimStyle {
+            name: String::from("unused-dim-style"),
+            dimension_text_style: String::from("dim-text-style"),
+            ..Default::default()
+        });
+        drawing.add_style(Style {
+            name: String::from("dim-text-style"),
+            ..Default::default()
+        });
+        drawing.add_style(Style {
+            name: String::from("unused-style"),
+            ..Default::default()
+        });
+
+        // the dimension style must be removed before its text style is unused
+        assert_eq!(1, drawing.purge_styles());
+        assert!(drawing.styles().any(|s| s.name == "dim-text-style"));
+        assert_eq!(1, drawing.purge_dim_styles());
+        assert_eq!(1, drawing.purge_styles());
+        let styles = drawing.styles().map(|s| &*s.name).collect::<Vec<_>>();
+        assert!(styles.contains(&"STANDARD"));
+        assert!(styles.contains(&"text-style"));
+        assert!(!styles.contains(&"dim-text-style"));
+        assert!(drawing.dim_styles().any(|ds| ds.name == "STANDARD"));
+    }
+
+    #[test]
+    fn rename_layer_updates_references() {
+        let mut drawing = Drawing::new();
+        let layer_handle = drawing
+            .add_layer(Layer {
+                name: String::from("old"),
+                ..Default::default()
+            })
+            .handle;
+        drawing.add_entity(line_on_layer("OLD"));
+        drawing.add_block(block_with("b", vec![line_on_layer("old")]));
+        drawing.header.current_layer = String::from("old");
+        let mut dict = Dictionary::default();
+        dict.value_handles.insert(String::from("old"), layer_handle);
+        dict.value_handles
+            .insert(String::from("unrelated"), layer_handle);
+        drawing.add_object(Object::new(ObjectType::Dictionary(dict)));
+
+        assert!(drawing.rename_layer("old", "new"));
+        let layers = layer_names(&drawing);
+        assert!(layers.contains(&"new"));
+        assert!(!layers.contains(&"old"));
+        assert!(drawing.entities().all(|e| e.common.layer != "OLD"));
+        assert_eq!(
+            "new",
+            drawing.blocks().next().unwrap().entities[0].common.layer
+        );
+        assert_eq!("new", drawing.header.current_layer);
+        match drawing.objects().last().unwrap().specific {
+            ObjectType::Dictionary(ref d) => {
+                assert_eq!(Some(&layer_handle), d.value_handles.get("new"));
+                assert!(!d.value_handles.contains_key("old"));
+                assert!(d.value_handles.contains_key("unrelated"));
+            }
+            _ => panic!("expected a dictionary"),
+        }
+    }
+
+    #[test]
+    fn rename_layer_rejects_missing_and_duplicate_names() {
+        let mut drawing = Drawing::new();
+        drawing.add_entity(line_on_layer("a"));
+        drawing.add_entity(line_on_layer("b"));
+        assert!(!drawing.rename_layer("missing", "c"));
+        assert!(!drawing.rename_layer("a", "B"));
+        assert!(!drawing.rename_layer("a", ""));
+        assert!(drawing.rename_layer("a", "A"));
+        assert!(layer_names(&drawing).contains(&"A"));
+    }
+
+    #[test]
+    fn rename_block_updates_references() {
+        let mut drawing = Drawing::new();
+        drawing.add_block(block_with("old", vec![]));
+        drawing.add_block(block_with("outer", vec![insert_of("old")]));
+        drawing.add_entity(insert_of("old"));
+        drawing.add_entity(Entity::new(EntityType::RotatedDimension(
+            RotatedDimension {
+                dimension_base: DimensionBase {
+                    block_name: String::from("old"),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        )));
+        drawing.header.arrow_block_name = String::from("old");
+
+        assert!(drawing.rename_block("old", "new"));
+        assert!(drawing.blocks().any(|b| b.name == "new"));
+        assert!(drawing.block_records().any(|br| br.name == "new"));
+        assert!(drawing.block_records().all(|br| br.name != "old"));
+        assert_eq!("new", drawing.header.arrow_block_name);
+        for ent in drawing.all_entities() {
+            match ent.specific {
+                EntityType::Insert(ref ins) => assert_eq!("new", ins.name),
+                EntityType::RotatedDimension(ref dim) => {
+                    assert_eq!("new", dim.dimension_base.block_name)
+                }
+                _ => panic!("unexpected entity"),
+            }
+        }
+        assert!(!drawing.rename_block("new", "outer"));
+        assert!(!drawing.rename_block("missing", "other"));
+    }
 }
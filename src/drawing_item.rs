@@ -3,7 +3,7 @@ use crate::objects::*;
 use crate::tables::*;
 use crate::{Block, Handle};
 
-#[derive(Debug)]
+#[derive(Clone, Copy, Debug)]
 pub enum DrawingItem<'a> {
     AppId(&'a AppId),
     Block(&'a Block),
@@ -36,6 +36,22 @@ impl<'a> DrawingItem<'a> {
             DrawingItem::ViewPort(ref v) => v.handle,
         }
     }
+    pub(crate) fn owner_handle(&self) -> Handle {
+        match self {
+            DrawingItem::AppId(app_id) => app_id.__owner_handle,
+            DrawingItem::Block(b) => b.__owner_handle,
+            DrawingItem::BlockRecord(br) => br.__owner_handle,
+            DrawingItem::DimStyle(ds) => ds.__owner_handle,
+            DrawingItem::Entity(ent) => ent.common.__owner_handle,
+            DrawingItem::Layer(l) => l.__owner_handle,
+            DrawingItem::LineType(l) => l.__owner_handle,
+            DrawingItem::Object(obj) => obj.common.__owner_handle,
+            DrawingItem::Style(s) => s.__owner_handle,
+            DrawingItem::Ucs(u) => u.__owner_handle,
+            DrawingItem::View(v) => v.__owner_handle,
+            DrawingItem::ViewPort(v) => v.__owner_handle,
+        }
+    }
 }
 
 pub enum DrawingItemMut<'a> {
@@ -0,0 +1,497 @@
+extern crate encoding_rs;
+use self::encoding_rs::Encoding;
+
+extern crate image;
+use self::image::DynamicImage;
+
+use crate::code_pair_iter::new_streaming_code_pair_iter_from_reader;
+use crate::code_pair_put_back::CodePairPutBack;
+use crate::dxb_reader::DxbReader;
+use crate::entities::*;
+use crate::entity_iter::EntityCombiner;
+use crate::helper_functions::*;
+use crate::objects::*;
+use crate::tables::*;
+use crate::thumbnail;
+
+use crate::{Block, Class, CodePair, CodePairValue, Drawing, DxfError, DxfResult, Header};
+
+use std::collections::VecDeque;
+use std::io::Read;
+
+/// An item read from a drawing by a `DrawingStream`.
+#[allow(clippy::large_enum_variant)] // entities and objects are the common case; don't box them
+#[derive(Debug)]
+pub enum DrawingStreamItem {
+    Header(Box<Header>),
+    Class(Class),
+    AppId(AppId),
+    BlockRecord(BlockRecord),
+    DimStyle(DimStyle),
+    Layer(Layer),
+    LineType(LineType),
+    Style(Style),
+    Ucs(Ucs),
+    View(View),
+    ViewPort(ViewPort),
+    Block(Block),
+    Entity(Entity),
+    Object(Object),
+    Thumbnail(DynamicImage),
+}
+
+/// Reads a drawing one item at a time, keeping only the item being read in memory.  Created with `Drawing::stream`.
+///
+/// Entities and objects are read individually; table entries are read one table at a time and blocks are read one
+/// block (with its entities) at a time.  Items without a handle are given one, continuing from `$HANDSEED` as
+/// `Drawing::load` would.  The stream ends after the first error.
+pub struct DrawingStream {
+    iter: CodePairPutBack,
+    section: Section,
+    drawing: Drawing,
+    pending: VecDeque<DrawingStreamItem>,
+    combiner: EntityCombiner,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Section {
+    None,
+    Tables,
+    Blocks,
+    Entities,
+    Objects,
+    Done,
+}
+
+// public implementation
+impl DrawingStream {
+    /// The header read so far, or the default header if the `HEADER` section hasn't been read yet.
+    pub fn header(&self) -> &Header {
+        &self.drawing.header
+    }
+}
+
+// internal visibility only
+impl DrawingStream {
+    pub(crate) fn new<T>(
+        mut reader: T,
+        encoding: Option<&'static Encoding>,
+    ) -> DxfResult<DrawingStream>
+    where
+        T: Read + 'static,
+    {
+        let first_line = match read_line(
+            &mut reader,
+            true,
+            encoding.unwrap_or(encoding_rs::WINDOWS_1252),
+        ) {
+            Some(Ok(line)) => line,
+            Some(Err(e)) => return Err(e),
+            None => return Err(DxfError::UnexpectedEndOfInput),
+        };
+        let mut drawing = Drawing::new();
+        drawing.clear();
+        let mut pending = VecDeque::new();
+        let section = if first_line == "AutoCAD DXB 1.0" {
+            // DXB files only contain simple geometry, so they're loaded all at once
+            let mut dxb = DxbReader::new(&mut reader).load()?;
+            pending.push_back(DrawingStreamItem::Header(Box::new(dxb.header.clone())));
+            dxb.take_stream_items(|_| true, &mut pending);
+            Section::Done
+        } else {
+            Section::None
+        };
+        let iter = new_streaming_code_pair_iter_from_reader(reader, encoding, first_line)?;
+        Ok(DrawingStream {
+            iter: CodePairPutBack::from_code_pair_iter(iter),
+            section,
+            drawing,
+            pending,
+            combiner: EntityCombiner::default(),
+        })
+    }
+}
+
+// private implementation
+impl DrawingStream {
+    fn read_item(&mut self) -> DxfResult<Option<DrawingStreamItem>> {
+        loop {
+            if let Some(item) = self.pending.pop_front() {
+                return Ok(Some(item));
+            }
+
+            match self.section {
+                Section::None => {
+                    if let Some(item) = self.read_section()? {
+                        return Ok(Some(item));
+                    }
+                }
+                Section::Tables => self.read_section_item("TABLE", |stream| {
+                    let table_name = match stream.iter.next() {
+                        Some(Ok(pair)) => {
+                            // an invalid table type is reported by `read_specific_table`
+                            let table_name = if pair.code == 2 {
+                                pair.assert_string()?
+                            } else {
+                                String::new()
+                            };
+                            stream.iter.put_back(Ok(pair));
+                            table_name
+                        }
+                        Some(Err(e)) => return Err(e),
+                        None => return Err(DxfError::UnexpectedEndOfInput),
+                    };
+                    read_specific_table(&mut stream.drawing, &mut stream.iter)?;
+                    stream
+                        .drawing
+                        .take_stream_items(|name| name == table_name, &mut stream.pending);
+                    Ok(())
+                })?,
+                Section::Blocks => self.read_section_item("BLOCK", |stream| {
+                    Block::read_block(&mut stream.drawing, &mut stream.iter)?;
+                    stream
+                        .drawing
+                        .take_stream_items(|_| false, &mut stream.pending);
+                    Ok(())
+                })?,
+                Section::Entities => {
+                    let iter = &mut self.iter;
+                    match self.combiner.next(|| Entity::read(iter))? {
+                        Some(mut entity) => {
+                            if entity.common.handle.is_empty() {
+                                self.drawing.prepare_new_entity(&mut entity);
+                            }
+
+                            return Ok(Some(DrawingStreamItem::Entity(entity)));
+                        }
+                        None => self.end_section()?,
+                    }
+                }
+                Section::Objects => match Object::read(&mut self.iter)? {
+                    Some(mut object) => {
+                        if object.common.handle.is_empty() {
+                            object.common.handle = self.drawing.next_handle();
+                        }
+
+                        return Ok(Some(DrawingStreamItem::Object(object)));
+                    }
+                    None => self.end_section()?,
+                },
+                Section::Done => return Ok(None),
+            }
+        }
+    }
+    /// Reads the start of the next section.  Sections that are read whole return their item immediately.
+    fn read_section(&mut self) -> DxfResult<Option<DrawingStreamItem>> {
+        match self.iter.next() {
+            Some(Ok(pair @ CodePair { code: 0, .. })) => match &*pair.assert_string()? {
+                "EOF" => {
+                    self.section = Section::Done;
+                    Ok(None)
+                }
+                "SECTION" => match self.iter.next() {
+                    Some(Ok(CodePair {
+                        code: 2,
+                        value: CodePairValue::Str(s),
+                        ..
+                    })) => match &*s {
+                        "HEADER" => {
+                            self.drawing.header = Header::read(&mut self.iter)?;
+                            self.end_section()?;
+                            Ok(Some(DrawingStreamItem::Header(Box::new(
+                                self.drawing.header.clone(),
+                            ))))
+                        }
+                        "CLASSES" => {
+                            Class::read_classes(&mut self.drawing, &mut self.iter)?;
+                            self.drawing.take_stream_items(|_| false, &mut self.pending);
+                            self.end_section()?;
+                            Ok(None)
+                        }
+                        "TABLES" => {
+                            self.section = Section::Tables;
+                            Ok(None)
+                        }
+                        "BLOCKS" => {
+                            self.section = Section::Blocks;
+                            Ok(None)
+                        }
+                        "ENTITIES" => {
+                            self.section = Section::Entities;
+                            Ok(None)
+                        }
+                        "OBJECTS" => {
+                            self.section = Section::Objects;
+                            Ok(None)
+                        }
+                        "THUMBNAILIMAGE" => {
+                            let thumbnail = thumbnail::read_thumbnail(&mut self.iter)?;
+                            self.end_section()?;
+                            Ok(thumbnail.map(DrawingStreamItem::Thumbnail))
+                        }
+                        _ => {
+                            Drawing::swallow_section(&mut self.iter)?;
+                            self.end_section()?;
+                            Ok(None)
+                        }
+                    },
+                    Some(Ok(pair)) => Err(DxfError::UnexpectedCodePair(
+                        pair,
+                        String::from("expected 2/<section-name>"),
+                    )),
+                    Some(Err(e)) => Err(e),
+                    None => Err(DxfError::UnexpectedEndOfInput),
+                },
+                _ => Err(DxfError::UnexpectedCodePair(
+                    pair,
+                    String::from("expected 0/SECTION"),
+                )),
+            },
+            Some(Ok(pair)) => Err(DxfError::UnexpectedCodePair(
+                pair,
+                String::from("expected 0/SECTION or 0/EOF"),
+            )),
+            Some(Err(e)) => Err(e),
+            None => {
+                // ideally should have been 0/EOF
+                self.section = Section::Done;
+                Ok(None)
+            }
+        }
+    }
+    /// Reads the next `item_type` in the current section with `callback`, or the end of the section.
+    fn read_section_item<F>(&mut self, item_type: &str, callback: F) -> DxfResult<()>
+    where
+        F: Fn(&mut DrawingStream) -> DxfResult<()>,
+    {
+        match self.iter.next() {
+            Some(Ok(pair @ CodePair { code: 0, .. })) => {
+                let val = pair.assert_string()?;
+                if val == "ENDSEC" {
+                    self.iter.put_back(Ok(pair));
+                    self.end_section()
+                } else if val == item_type {
+                    callback(self)
+                } else {
+                    Err(DxfError::UnexpectedCodePair(pair, String::new()))
+                }
+            }
+            Some(Ok(pair)) => Err(DxfError::UnexpectedCodePair(pair, String::new())),
+            Some(Err(e)) => Err(e),
+            None => Err(DxfError::UnexpectedEndOfInput),
+        }
+    }
+    fn end_section(&mut self) -> DxfResult<()> {
+        match self.iter.next() {
+            Some(Ok(CodePair {
+                code: 0,
+                value: CodePairValue::Str(ref s),
+                ..
+            })) if s == "ENDSEC" => {
+                self.section = Section::None;
+                Ok(())
+            }
+            Some(Ok(pair)) => Err(DxfError::UnexpectedCodePair(
+                pair,
+                String::from("expected 0/ENDSEC"),
+            )),
+            Some(Err(e)) => Err(e),
+            None => Err(DxfError::UnexpectedEndOfInput),
+        }
+    }
+}
+
+impl Iterator for DrawingStream {
+    type Item = DxfResult<DrawingStreamItem>;
+
+    fn next(&mut self) -> Option<DxfResult<DrawingStreamItem>> {
+        match self.read_item() {
+            Ok(Some(item)) => Some(Ok(item)),
+            Ok(None) => None,
+            Err(e) => {
+                self.section = Section::Done;
+                self.pending.clear();
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::entities::*;
+    use crate::enums::*;
+    use crate::helper_functions::tests::*;
+    use crate::objects::*;
+    use crate::tables::*;
+    use crate::*;
+    use std::io::Cursor;
+
+    fn stream_items(bytes: Vec<u8>) -> Vec<DrawingStreamItem> {
+        Drawing::stream(Cursor::new(bytes))
+            .unwrap()
+            .map(|item| item.unwrap())
+            .collect()
+    }
+
+    fn section_order(item: &DrawingStreamItem) -> i32 {
+        match item {
+            DrawingStreamItem::Header(_) => 0,
+            DrawingStreamItem::Class(_) => 1,
+            DrawingStreamItem::Block(_) => 3,
+            DrawingStreamItem::Entity(_) => 4,
+            DrawingStreamItem::Object(_) => 5,
+            DrawingStreamItem::Thumbnail(_) => 6,
+            _ => 2, // table entries
+        }
+    }
+
+    fn test_drawing() -> Drawing {
+        let mut drawing = Drawing::new();
+        drawing.header.version = AcadVersion::R2000;
+        drawing.add_layer(Layer {
+            name: String::from("streamed-layer"),
+            ..Default::default()
+        });
+        let mut block = Block {
+            name: String::from("streamed-block"),
+            ..Default::default()
+        };
+        block
+            .entities
+            .push(Entity::new(EntityType::Line(Line::default())));
+        drawing.add_block(block);
+        let mut poly = Polyline::default();
+        for x in 0..3 {
+            poly.add_vertex(
+                &mut drawing,
+                Vertex::new(Point::new(f64::from(x), 0.0, 0.0)),
+            );
+        }
+        drawing.add_entity(Entity::new(EntityType::Polyline(poly)));
+        drawing.add_entity(Entity::new(EntityType::Circle(Circle::default())));
+        drawing.add_object(Object::new(ObjectType::PlaceHolder(PlaceHolder::default())));
+        drawing
+    }
+
+    #[test]
+    fn stream_items_in_file_order() {
+        let drawing = test_drawing();
+        let items = stream_items(to_test_string(&drawing).into_bytes());
+        match items.first() {
+            Some(DrawingStreamItem::Header(header)) => {
+                assert_eq!(AcadVersion::R2000, header.version)
+            }
+            _ => panic!("expected the header first"),
+        }
+        let order = items.iter().map(section_order).collect::<Vec<_>>();
+        let mut sorted_order = order.clone();
+        sorted_order.sort();
+        assert_eq!(sorted_order, order);
+
+        assert!(items.iter().any(|item| match item {
+            DrawingStreamItem::Layer(layer) => layer.name == "streamed-layer",
+            _ => false,
+        }));
+        assert!(items.iter().any(|item| match item {
+            DrawingStreamItem::Block(block) =>
+                block.name == "streamed-block" && block.entities.len() == 1,
+            _ => false,
+        }));
+        assert!(items
+            .iter()
+            .any(|item| matches!(item, DrawingStreamItem::Object(_))));
+
+        // the streamed entities match the loaded ones, with vertices gathered into their polyline
+        let loaded = parse_drawing(&to_test_string(&drawing));
+        let streamed_entities = items
+            .iter()
+            .filter_map(|item| match item {
+                DrawingStreamItem::Entity(e) => Some(e),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        let loaded_entities = loaded.entities().collect::<Vec<_>>();
+        assert_eq!(loaded_entities.len(), streamed_entities.len());
+        for (loaded, streamed) in loaded_entities.iter().zip(streamed_entities.iter()) {
+            assert_eq!(loaded.common.handle, streamed.common.handle);
+        }
+        match &streamed_entities[0].specific {
+            EntityType::Polyline(poly) => assert_eq!(3, poly.vertices().count()),
+            _ => panic!("expected a polyline"),
+        }
+    }
+
+    #[test]
+    fn stream_binary_drawing() {
+        let items = stream_items(to_binary(&test_drawing()));
+        let entity_count = items
+            .iter()
+            .filter(|item| matches!(item, DrawingStreamItem::Entity(_)))
+            .count();
+        assert_eq!(2, entity_count);
+    }
+
+    #[test]
+    fn streamed_items_without_handles_are_given_one() {
+        let contents = vec![
+            "0",
+            "SECTION",
+            "2",
+            "HEADER",
+            "9",
+            "$HANDSEED",
+            "5",
+            "100",
+            "0",
+            "ENDSEC",
+            "0",
+            "SECTION",
+            "2",
+            "ENTITIES",
+            "0",
+            "LINE",
+            "0",
+            "LINE",
+            "0",
+            "ENDSEC",
+            "0",
+            "EOF",
+        ]
+        .join("\r\n");
+        let mut stream = Drawing::stream(Cursor::new(contents.into_bytes())).unwrap();
+        let handles = stream
+            .by_ref()
+            .filter_map(|item| match item.unwrap() {
+                DrawingStreamItem::Entity(e) => Some(e.common.handle),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(vec![Handle(0x100), Handle(0x101)], handles);
+        assert_eq!(Handle(0x102), stream.header().next_available_handle);
+    }
+
+    #[test]
+    fn stream_ends_after_an_error() {
+        let contents = vec![
+            "0",
+            "SECTION",
+            "2",
+            "ENTITIES",
+            "0",
+            "LINE",
+            "10",
+            "not-a-number",
+            "0",
+            "LINE",
+            "0",
+            "ENDSEC",
+            "0",
+            "EOF",
+        ]
+        .join("\r\n");
+        let mut stream = Drawing::stream(Cursor::new(contents.into_bytes())).unwrap();
+        assert!(stream.next().unwrap().is_err());
+        assert!(stream.next().is_none());
+    }
+}
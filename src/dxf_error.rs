@@ -37,6 +37,31 @@ impl From<::image::ImageError> for DxfError {
     }
 }
 
+impl DxfError {
+    /// Returns the line number or byte offset the error refers to, if it has one.
+    pub(crate) fn offset(&self) -> Option<usize> {
+        match *self {
+            DxfError::ParseFloatError(_, o)
+            | DxfError::ParseIntError(_, o)
+            | DxfError::ParseError(o)
+            | DxfError::UnexpectedCode(_, o)
+            | DxfError::UnexpectedByte(_, o)
+            | DxfError::UnexpectedEnumValue(o)
+            | DxfError::ExpectedTableType(o)
+            | DxfError::WrongValueType(o) => Some(o),
+            DxfError::UnexpectedCodePair(ref pair, _) => Some(pair.offset),
+            _ => None,
+        }
+    }
+    /// Returns `true` if reading can't continue after the error, no matter how lenient the reader is.
+    pub(crate) fn is_fatal(&self) -> bool {
+        matches!(
+            *self,
+            DxfError::IoError(_) | DxfError::UnexpectedEndOfInput | DxfError::InvalidBinaryFile
+        )
+    }
+}
+
 impl fmt::Display for DxfError {
     fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
         match *self {
@@ -2,12 +2,17 @@
 
 use enum_primitive::FromPrimitive;
 
-use crate::{CodePair, Color, DxfError, DxfResult, Handle, Point, Vector};
+use crate::{
+    CodePair, CodePairValue, Color, DrawingItem, DxfError, DxfResult, Handle, HatchBoundaryPath,
+    HatchPatternDefinitionLine, MLeaderContextData, MTextDocument, Point, TableCell,
+    TableCellRange, TransformationMatrix, Vector,
+};
 
 use crate::code_pair_put_back::CodePairPutBack;
 use crate::entities::*;
 use crate::enums::*;
 use crate::helper_functions::*;
+use crate::tables::BlockRecord;
 use crate::Drawing;
 
 //------------------------------------------------------------------------------
@@ -84,6 +89,28 @@ impl Face3D {
     }
 }
 
+//------------------------------------------------------------------------------
+//                                                            HatchGradientColor
+//------------------------------------------------------------------------------
+/// Represents a single color of a gradient `Hatch`.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct HatchGradientColor {
+    pub value: f64,
+    pub color: Color,
+    pub true_color: i32,
+}
+
+impl Default for HatchGradientColor {
+    fn default() -> Self {
+        HatchGradientColor {
+            value: 0.0,
+            color: Color::by_layer(),
+            true_color: 0,
+        }
+    }
+}
+
 //------------------------------------------------------------------------------
 //                                                                        Insert
 //------------------------------------------------------------------------------
@@ -98,6 +125,39 @@ impl Insert {
         let att_handle = drawing.next_handle();
         self.__attributes_and_handles.push((att, att_handle));
     }
+    /// Returns the transformation from block coordinates to world coordinates for each copy of the block placed by
+    /// this reference, in row-major order.
+    pub(crate) fn block_transformations(&self, base_point: &Point) -> Vec<TransformationMatrix> {
+        let to_world = TransformationMatrix::from_ocs(&self.extrusion_direction)
+            .multiply(&TransformationMatrix::translation(
+                &self.location.as_vector(),
+            ))
+            .multiply(&TransformationMatrix::rotation_about_z(self.rotation));
+        let from_block = TransformationMatrix::scale(
+            self.x_scale_factor,
+            self.y_scale_factor,
+            self.z_scale_factor,
+        )
+        .multiply(&TransformationMatrix::translation(
+            &base_point.as_vector().scaled(-1.0),
+        ));
+        let mut transformations = vec![];
+        for row in 0..self.row_count.max(1) {
+            for column in 0..self.column_count.max(1) {
+                let offset = Vector::new(
+                    f64::from(column) * self.column_spacing,
+                    f64::from(row) * self.row_spacing,
+                    0.0,
+                );
+                transformations.push(
+                    to_world
+                        .multiply(&TransformationMatrix::translation(&offset))
+                        .multiply(&from_block),
+                );
+            }
+        }
+        transformations
+    }
 }
 
 //------------------------------------------------------------------------------
@@ -128,6 +188,165 @@ pub struct LwPolylineVertex {
     pub bulge: f64,
 }
 
+//------------------------------------------------------------------------------
+//                                                      MLeaderArrowheadOverride
+//------------------------------------------------------------------------------
+/// Represents the arrowhead used by a single leader line of an `MLeader`.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct MLeaderArrowheadOverride {
+    pub index: i32,
+    #[doc(hidden)]
+    pub __arrowhead_handle: Handle,
+}
+
+impl Default for MLeaderArrowheadOverride {
+    fn default() -> Self {
+        MLeaderArrowheadOverride {
+            index: 0,
+            __arrowhead_handle: Handle::empty(),
+        }
+    }
+}
+
+impl MLeaderArrowheadOverride {
+    pub fn arrowhead<'a>(&self, drawing: &'a Drawing) -> Option<&'a BlockRecord> {
+        match drawing.item_by_handle(self.__arrowhead_handle) {
+            Some(DrawingItem::BlockRecord(val)) => Some(val),
+            _ => None,
+        }
+    }
+    pub fn set_arrowhead(&mut self, item: &BlockRecord) {
+        self.__arrowhead_handle = DrawingItem::BlockRecord(item).handle();
+    }
+}
+
+//------------------------------------------------------------------------------
+//                                                         MLeaderBlockAttribute
+//------------------------------------------------------------------------------
+/// Represents the value of a block attribute shown by an `MLeader` with block content.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct MLeaderBlockAttribute {
+    #[doc(hidden)]
+    pub __attribute_definition_handle: Handle,
+    pub index: i16,
+    pub width: f64,
+    pub text: String,
+}
+
+impl Default for MLeaderBlockAttribute {
+    fn default() -> Self {
+        MLeaderBlockAttribute {
+            __attribute_definition_handle: Handle::empty(),
+            index: 0,
+            width: 0.0,
+            text: String::new(),
+        }
+    }
+}
+
+impl MLeaderBlockAttribute {
+    pub fn attribute_definition<'a>(&self, drawing: &'a Drawing) -> Option<&'a Entity> {
+        match drawing.item_by_handle(self.__attribute_definition_handle) {
+            Some(DrawingItem::Entity(val)) => Some(val),
+            _ => None,
+        }
+    }
+    pub fn set_attribute_definition(&mut self, item: &Entity) {
+        self.__attribute_definition_handle = DrawingItem::Entity(item).handle();
+    }
+}
+
+//------------------------------------------------------------------------------
+//                                                                         MText
+//------------------------------------------------------------------------------
+/// The maximum number of characters written in each code 1 or code 3 pair of an `MText`.
+const MTEXT_CHUNK_LENGTH: usize = 250;
+
+impl MText {
+    /// Returns the contents, including the inline formatting codes, by joining `extended_text` and `text`.
+    pub fn full_text(&self) -> String {
+        self.extended_text
+            .iter()
+            .map(|s| s.as_str())
+            .chain(std::iter::once(self.text.as_str()))
+            .collect()
+    }
+    /// Sets the contents, including the inline formatting codes, splitting long values between `extended_text` and
+    /// `text`.
+    pub fn set_full_text(&mut self, text: &str) {
+        let mut chunks = split_text_chunks(text);
+        self.text = chunks.pop().unwrap_or_default();
+        self.extended_text = chunks;
+    }
+    /// Returns the contents parsed into paragraphs of formatted text.
+    pub fn formatted_text(&self) -> MTextDocument {
+        MTextDocument::parse(&self.full_text())
+    }
+    /// Sets the contents from paragraphs of formatted text.
+    pub fn set_formatted_text(&mut self, document: &MTextDocument) {
+        self.set_full_text(&document.to_string());
+    }
+    /// Returns the contents without formatting, with a line for each paragraph.
+    pub fn plain_text(&self) -> String {
+        self.formatted_text().plain_text()
+    }
+    /// The values written with code 3; the contents are re-split if any value is too long to be written as is.
+    pub(crate) fn extended_text_chunks(&self) -> Vec<String> {
+        let mut chunks = self.text_chunks();
+        chunks.pop();
+        chunks
+    }
+    /// The value written with code 1.
+    pub(crate) fn text_chunk(&self) -> String {
+        self.text_chunks().pop().unwrap_or_default()
+    }
+    fn text_chunks(&self) -> Vec<String> {
+        let is_too_long = |s: &String| s.chars().count() > MTEXT_CHUNK_LENGTH;
+        if is_too_long(&self.text) || self.extended_text.iter().any(is_too_long) {
+            split_text_chunks(&self.full_text())
+        } else {
+            let mut chunks = self.extended_text.clone();
+            chunks.push(self.text.clone());
+            chunks
+        }
+    }
+}
+
+/// Splits text into values of at most `MTEXT_CHUNK_LENGTH` characters; there's always at least one value.
+fn split_text_chunks(text: &str) -> Vec<String> {
+    let chars = text.chars().collect::<Vec<_>>();
+    if chars.is_empty() {
+        return vec![String::new()];
+    }
+    chars
+        .chunks(MTEXT_CHUNK_LENGTH)
+        .map(|chunk| chunk.iter().collect())
+        .collect()
+}
+
+//------------------------------------------------------------------------------
+//                                                         MeshSubentityOverride
+//------------------------------------------------------------------------------
+/// Represents the properties overridden for a single face, edge, or vertex of a `Mesh`.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct MeshSubentityOverride {
+    pub subentity_marker: i32,
+    pub properties: Vec<MeshPropertyOverride>,
+}
+
+/// Represents a single overridden property of a `Mesh` sub-entity.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct MeshPropertyOverride {
+    /// 0 = color, 1 = material, 2 = transparency, 3 = material mapper.
+    pub property_type: i32,
+    /// The code pairs of the property's value, e.g., `62` for a color, written back as they were read.
+    pub values: Vec<CodePair>,
+}
+
 //------------------------------------------------------------------------------
 //                                                                    ModelPoint
 //------------------------------------------------------------------------------
@@ -197,6 +416,80 @@ impl Solid {
     }
 }
 
+//------------------------------------------------------------------------------
+//                                                                         Table
+//------------------------------------------------------------------------------
+impl Table {
+    /// Creates a table of empty text cells with uniform row heights and column widths.
+    pub fn new(row_count: usize, column_count: usize, row_height: f64, column_width: f64) -> Self {
+        Table {
+            row_heights: vec![row_height; row_count],
+            column_widths: vec![column_width; column_count],
+            cells: vec![TableCell::default(); row_count * column_count],
+            ..Default::default()
+        }
+    }
+    pub fn row_count(&self) -> usize {
+        self.row_heights.len()
+    }
+    pub fn column_count(&self) -> usize {
+        self.column_widths.len()
+    }
+    pub fn cell(&self, row: usize, column: usize) -> Option<&TableCell> {
+        if column < self.column_count() {
+            self.cells.get(row * self.column_count() + column)
+        } else {
+            None
+        }
+    }
+    pub fn cell_mut(&mut self, row: usize, column: usize) -> Option<&mut TableCell> {
+        if column < self.column_count() {
+            let index = row * self.column_count() + column;
+            self.cells.get_mut(index)
+        } else {
+            None
+        }
+    }
+    /// Merges the cells in the specified range so the content of its top left cell spans the whole range.  Cells
+    /// outside of the table are ignored.
+    pub fn merge_cells(&mut self, range: TableCellRange) {
+        for row in range.top_row..=range.bottom_row {
+            for column in range.left_column..=range.right_column {
+                if let Some(cell) = self.cell_mut(row, column) {
+                    cell.is_merged = true;
+                    cell.merged_row_count = 1;
+                    cell.merged_column_count = 1;
+                }
+            }
+        }
+        if let Some(cell) = self.cell_mut(range.top_row, range.left_column) {
+            cell.merged_row_count = range.row_count() as i16;
+            cell.merged_column_count = range.column_count() as i16;
+        }
+    }
+    /// Returns the ranges of merged cells, as described by the top left cell of each range.
+    pub fn merged_ranges(&self) -> Vec<TableCellRange> {
+        let mut ranges = vec![];
+        for row in 0..self.row_count() {
+            for column in 0..self.column_count() {
+                if let Some(cell) = self.cell(row, column) {
+                    let row_span = cell.merged_row_count.max(1) as usize;
+                    let column_span = cell.merged_column_count.max(1) as usize;
+                    if cell.is_merged && (row_span > 1 || column_span > 1) {
+                        ranges.push(TableCellRange::new(
+                            row,
+                            column,
+                            row + row_span - 1,
+                            column + column_span - 1,
+                        ));
+                    }
+                }
+            }
+        }
+        ranges
+    }
+}
+
 //------------------------------------------------------------------------------
 //                                                                         Trace
 //------------------------------------------------------------------------------
@@ -233,6 +526,74 @@ impl Vertex {
 //                                                                    EntityType
 //------------------------------------------------------------------------------
 impl EntityType {
+    /// Returns the normal of the object coordinate system (OCS) the entity's coordinates are stored in, or `None` for
+    /// entities stored in world coordinates.  Only some of the points of dimensions, e.g., `text_mid_point`, are in
+    /// the OCS.
+    pub fn ocs_normal(&self) -> Option<&Vector> {
+        match self {
+            EntityType::Arc(ref arc) => Some(&arc.normal),
+            EntityType::ArcAlignedText(ref text) => Some(&text.extrusion_direction),
+            EntityType::Attribute(ref att) => Some(&att.normal),
+            EntityType::AttributeDefinition(ref att) => Some(&att.normal),
+            EntityType::Circle(ref circle) => Some(&circle.normal),
+            EntityType::Hatch(ref hatch) => Some(&hatch.extrusion_direction),
+            EntityType::Insert(ref insert) => Some(&insert.extrusion_direction),
+            EntityType::LwPolyline(ref poly) => Some(&poly.extrusion_direction),
+            EntityType::Polyline(ref poly)
+                if !poly.is_3d_polyline()
+                    && !poly.is_3d_polygon_mesh()
+                    && !poly.is_polyface_mesh() =>
+            {
+                Some(&poly.normal)
+            }
+            EntityType::RText(ref text) => Some(&text.extrusion_direction),
+            EntityType::Shape(ref shape) => Some(&shape.extrusion_direction),
+            EntityType::Solid(ref solid) => Some(&solid.extrusion_direction),
+            EntityType::Text(ref text) => Some(&text.normal),
+            EntityType::Trace(ref trace) => Some(&trace.extrusion_direction),
+            _ => self.dimension_base().map(|d| &d.normal),
+        }
+    }
+    pub(crate) fn dimension_base(&self) -> Option<&DimensionBase> {
+        match self {
+            EntityType::RotatedDimension(ref d) => Some(&d.dimension_base),
+            EntityType::RadialDimension(ref d) => Some(&d.dimension_base),
+            EntityType::DiameterDimension(ref d) => Some(&d.dimension_base),
+            EntityType::AngularThreePointDimension(ref d) => Some(&d.dimension_base),
+            EntityType::OrdinateDimension(ref d) => Some(&d.dimension_base),
+            _ => None,
+        }
+    }
+    pub(crate) fn dimension_base_mut(&mut self) -> Option<&mut DimensionBase> {
+        match self {
+            EntityType::RotatedDimension(ref mut d) => Some(&mut d.dimension_base),
+            EntityType::RadialDimension(ref mut d) => Some(&mut d.dimension_base),
+            EntityType::DiameterDimension(ref mut d) => Some(&mut d.dimension_base),
+            EntityType::AngularThreePointDimension(ref mut d) => Some(&mut d.dimension_base),
+            EntityType::OrdinateDimension(ref mut d) => Some(&mut d.dimension_base),
+            _ => None,
+        }
+    }
+    /// Returns the name of the dimension style used by dimensions, leaders, and tolerances.
+    pub(crate) fn dimension_style_name(&self) -> Option<&str> {
+        match self {
+            EntityType::Leader(ref l) => Some(&l.dimension_style_name),
+            EntityType::Tolerance(ref t) => Some(&t.dimension_style_name),
+            _ => self
+                .dimension_base()
+                .map(|d| d.dimension_style_name.as_str()),
+        }
+    }
+    pub(crate) fn text_style_name(&self) -> Option<&str> {
+        match self {
+            EntityType::ArcAlignedText(ref e) => Some(&e.text_style_name),
+            EntityType::Attribute(ref e) => Some(&e.text_style_name),
+            EntityType::AttributeDefinition(ref e) => Some(&e.text_style_name),
+            EntityType::MText(ref e) => Some(&e.text_style_name),
+            EntityType::Text(ref e) => Some(&e.text_style_name),
+            _ => None,
+        }
+    }
     fn apply_dimension_code_pair(&mut self, pair: &CodePair) -> DxfResult<bool> {
         match *self {
             EntityType::RotatedDimension(ref mut dim) => match pair.code {
@@ -405,6 +766,22 @@ impl Entity {
         self.common.normalize();
         // no entity-specific values to set
     }
+    /// Converts a point from the entity's object coordinate system (OCS) to world coordinates.  Points of entities
+    /// without an OCS (see `EntityType::ocs_normal()`) are returned unchanged.
+    pub fn ocs_to_wcs(&self, point: &Point) -> Point {
+        match self.specific.ocs_normal() {
+            Some(normal) => point.ocs_to_wcs(normal),
+            None => point.clone(),
+        }
+    }
+    /// Converts a point from world coordinates to the entity's object coordinate system (OCS).  Points of entities
+    /// without an OCS (see `EntityType::ocs_normal()`) are returned unchanged.
+    pub fn wcs_to_ocs(&self, point: &Point) -> Point {
+        match self.specific.ocs_normal() {
+            Some(normal) => point.wcs_to_ocs(normal),
+            None => point.clone(),
+        }
+    }
     pub(crate) fn read(iter: &mut CodePairPutBack) -> DxfResult<Option<Entity>> {
         'new_entity: loop {
             match iter.next() {
@@ -638,6 +1015,44 @@ impl Entity {
 
                                     return Ok(Some(entity));
                                 }
+                                None if iter.preserve_unknown_items() => {
+                                    // keep the unsupported entity's pairs so they can be written back
+                                    let mut common = EntityCommon::default();
+                                    let mut pairs = vec![];
+                                    loop {
+                                        match iter.next() {
+                                            Some(Ok(pair @ CodePair { code: 0, .. })) => {
+                                                // found another entity or ENDSEC
+                                                iter.put_back(Ok(pair));
+                                                break;
+                                            }
+                                            Some(Ok(pair)) => {
+                                                if pair.code == 5 && common.handle.is_empty() {
+                                                    common.handle = pair.as_handle()?;
+                                                } else {
+                                                    pairs.push(pair);
+                                                }
+                                            }
+                                            Some(Err(e)) => return Err(e),
+                                            None => return Err(DxfError::UnexpectedEndOfInput),
+                                        }
+                                    }
+
+                                    // the common values are also kept in `common` for filtering, purging, etc.
+                                    let (layer_index, owner_index) =
+                                        unknown_entity_common_pair_indices(&pairs);
+                                    if let Some(i) = layer_index {
+                                        common.layer = pairs[i].assert_string()?;
+                                    }
+                                    if let Some(i) = owner_index {
+                                        common.__owner_handle = pairs[i].as_handle()?;
+                                    }
+
+                                    return Ok(Some(Entity {
+                                        common,
+                                        specific: EntityType::Unknown { type_string, pairs },
+                                    }));
+                                }
                                 None => {
                                     // swallow unsupported entity
                                     loop {
@@ -793,12 +1208,36 @@ impl Entity {
             EntityType::AttributeDefinition(ref mut att) => {
                 Entity::apply_custom_reader_attributedefinition(&mut self.common, att, iter)
             }
+            EntityType::ExtrudedSurface(ref mut surface) => {
+                Entity::apply_custom_reader_extrudedsurface(&mut self.common, surface, iter)
+            }
+            EntityType::Hatch(ref mut hatch) => {
+                Entity::apply_custom_reader_hatch(&mut self.common, hatch, iter)
+            }
+            EntityType::LoftedSurface(ref mut surface) => {
+                Entity::apply_custom_reader_loftedsurface(&mut self.common, surface, iter)
+            }
             EntityType::LwPolyline(ref mut poly) => {
                 Entity::apply_custom_reader_lwpolyline(&mut self.common, poly, iter)
             }
+            EntityType::Mesh(ref mut mesh) => {
+                Entity::apply_custom_reader_mesh(&mut self.common, mesh, iter)
+            }
+            EntityType::MLeader(ref mut mleader) => {
+                Entity::apply_custom_reader_mleader(&mut self.common, mleader, iter)
+            }
             EntityType::MText(ref mut mtext) => {
                 Entity::apply_custom_reader_mtext(&mut self.common, mtext, iter)
             }
+            EntityType::RevolvedSurface(ref mut surface) => {
+                Entity::apply_custom_reader_revolvedsurface(&mut self.common, surface, iter)
+            }
+            EntityType::SweptSurface(ref mut surface) => {
+                Entity::apply_custom_reader_sweptsurface(&mut self.common, surface, iter)
+            }
+            EntityType::Table(ref mut table) => {
+                Entity::apply_custom_reader_table(&mut self.common, table, iter)
+            }
             _ => Ok(false), // no custom reader
         }
     }
@@ -1101,57 +1540,118 @@ impl Entity {
             }
         }
     }
-    //test
-    fn apply_custom_reader_lwpolyline(
+    fn apply_custom_reader_extrudedsurface(
         common: &mut EntityCommon,
-        poly: &mut LwPolyline,
+        surface: &mut ExtrudedSurface,
         iter: &mut CodePairPutBack,
     ) -> DxfResult<bool> {
+        let surface_text = "AcDbExtrudedSurface";
+        let mut last_subclass_marker = String::new();
+        let mut transformation_matrix = vec![];
+        let mut sweep_entity_transformation_matrix = vec![];
+        let mut path_entity_transformation_matrix = vec![];
         loop {
             let pair = next_pair!(iter);
             match pair.code {
-                // vertex-specific pairs
+                100 => {
+                    last_subclass_marker = pair.assert_string()?;
+                }
+                1 => {
+                    surface.custom_data.push(pair.assert_string()?);
+                }
+                3 => {
+                    surface.custom_data2.push(pair.assert_string()?);
+                }
+                70 => {
+                    if last_subclass_marker == surface_text {
+                        surface.sweep_alignment_flags = pair.assert_i16()?;
+                    } else {
+                        surface.format_version_number = pair.assert_i16()?;
+                    }
+                }
+                71 => {
+                    surface.u_isoline_count = pair.assert_i16()?;
+                }
+                72 => {
+                    surface.v_isoline_count = pair.assert_i16()?;
+                }
+                90 => {
+                    surface.class_id = pair.assert_i32()?;
+                }
                 10 => {
-                    // start a new vertex
-                    poly.vertices.push(LwPolylineVertex::default());
-                    vec_last!(poly.vertices).x = pair.assert_f64()?;
+                    surface.sweep_vector.x = pair.assert_f64()?;
                 }
                 20 => {
-                    vec_last!(poly.vertices).y = pair.assert_f64()?;
+                    surface.sweep_vector.y = pair.assert_f64()?;
                 }
-                38 => {
-                    poly.elevation = pair.assert_f64()?;
+                30 => {
+                    surface.sweep_vector.z = pair.assert_f64()?;
                 }
                 40 => {
-                    vec_last!(poly.vertices).starting_width = pair.assert_f64()?;
-                }
-                41 => {
-                    vec_last!(poly.vertices).ending_width = pair.assert_f64()?;
+                    transformation_matrix.push(pair.assert_f64()?);
+                    if transformation_matrix.len() == 16 {
+                        surface.transformation_matrix =
+                            TransformationMatrix::from_vec(&transformation_matrix);
+                    }
                 }
                 42 => {
-                    vec_last!(poly.vertices).bulge = pair.assert_f64()?;
+                    surface.draft_angle = pair.assert_f64()?;
                 }
-                91 => {
-                    vec_last!(poly.vertices).id = pair.assert_i32()?;
+                43 => {
+                    surface.draft_start_distance = pair.assert_f64()?;
                 }
-                // other pairs
-                39 => {
-                    poly.thickness = pair.assert_f64()?;
+                44 => {
+                    surface.draft_end_distance = pair.assert_f64()?;
                 }
-                43 => {
-                    poly.constant_width = pair.assert_f64()?;
+                45 => {
+                    surface.twist_angle = pair.assert_f64()?;
                 }
-                70 => {
-                    poly.flags = i32::from(pair.assert_i16()?);
+                46 => {
+                    sweep_entity_transformation_matrix.push(pair.assert_f64()?);
+                    if sweep_entity_transformation_matrix.len() == 16 {
+                        surface.sweep_entity_transformation_matrix =
+                            TransformationMatrix::from_vec(&sweep_entity_transformation_matrix);
+                    }
                 }
-                210 => {
-                    poly.extrusion_direction.x = pair.assert_f64()?;
+                47 => {
+                    path_entity_transformation_matrix.push(pair.assert_f64()?);
+                    if path_entity_transformation_matrix.len() == 16 {
+                        surface.path_entity_transformation_matrix =
+                            TransformationMatrix::from_vec(&path_entity_transformation_matrix);
+                    }
                 }
-                220 => {
-                    poly.extrusion_direction.y = pair.assert_f64()?;
+                48 if last_subclass_marker == surface_text => {
+                    surface.scale_factor = pair.assert_f64()?;
                 }
-                230 => {
-                    poly.extrusion_direction.z = pair.assert_f64()?;
+                49 => {
+                    surface.align_angle = pair.assert_f64()?;
+                }
+                290 => {
+                    surface.is_solid = pair.assert_bool()?;
+                }
+                292 => {
+                    surface.align_start = pair.assert_bool()?;
+                }
+                293 => {
+                    surface.bank = pair.assert_bool()?;
+                }
+                294 => {
+                    surface.base_point_set = pair.assert_bool()?;
+                }
+                295 => {
+                    surface.sweep_entity_transform_computed = pair.assert_bool()?;
+                }
+                296 => {
+                    surface.path_entity_transform_computed = pair.assert_bool()?;
+                }
+                11 => {
+                    surface.reference_vector_for_controlling_twist.x = pair.assert_f64()?;
+                }
+                21 => {
+                    surface.reference_vector_for_controlling_twist.y = pair.assert_f64()?;
+                }
+                31 => {
+                    surface.reference_vector_for_controlling_twist.z = pair.assert_f64()?;
                 }
                 _ => {
                     common.apply_individual_pair(&pair, iter)?;
@@ -1159,136 +1659,204 @@ impl Entity {
             }
         }
     }
-    fn apply_custom_reader_mtext(
+    fn apply_custom_reader_hatch(
         common: &mut EntityCommon,
-        mtext: &mut MText,
+        hatch: &mut Hatch,
         iter: &mut CodePairPutBack,
     ) -> DxfResult<bool> {
-        let mut reading_column_data = false;
-        let mut read_column_count = false;
+        let mut reading_seed_points = false;
         loop {
             let pair = next_pair!(iter);
             match pair.code {
+                2 => {
+                    hatch.pattern_name = pair.assert_string()?;
+                }
+                10 if reading_seed_points => {
+                    hatch
+                        .seed_points
+                        .push(Point::new(pair.assert_f64()?, 0.0, 0.0));
+                }
+                20 if reading_seed_points => {
+                    vec_last!(hatch.seed_points).y = pair.assert_f64()?;
+                }
                 10 => {
-                    mtext.insertion_point.x = pair.assert_f64()?;
+                    hatch.elevation_point.x = pair.assert_f64()?;
                 }
                 20 => {
-                    mtext.insertion_point.y = pair.assert_f64()?;
+                    hatch.elevation_point.y = pair.assert_f64()?;
                 }
                 30 => {
-                    mtext.insertion_point.z = pair.assert_f64()?;
-                }
-                40 => {
-                    mtext.initial_text_height = pair.assert_f64()?;
+                    hatch.elevation_point.z = pair.assert_f64()?;
                 }
                 41 => {
-                    mtext.reference_rectangle_width = pair.assert_f64()?;
+                    hatch.pattern_scale = pair.assert_f64()?;
+                }
+                47 => {
+                    hatch.pixel_size = pair.assert_f64()?;
+                }
+                52 => {
+                    hatch.pattern_angle = pair.assert_f64()?;
+                }
+                63 if !hatch.gradient_colors.is_empty() => {
+                    vec_last!(hatch.gradient_colors).color =
+                        Color::from_raw_value(pair.assert_i16()?);
+                }
+                70 => {
+                    hatch.is_solid_fill = as_bool(pair.assert_i16()?);
                 }
                 71 => {
-                    mtext.attachment_point =
-                        enum_from_number!(AttachmentPoint, TopLeft, from_i16, pair.assert_i16()?);
+                    hatch.is_associative = as_bool(pair.assert_i16()?);
                 }
-                72 => {
-                    mtext.drawing_direction = enum_from_number!(
-                        DrawingDirection,
-                        LeftToRight,
+                75 => {
+                    hatch.hatch_style =
+                        enum_from_number!(HatchStyle, OddParity, from_i16, pair.assert_i16()?);
+                }
+                76 => {
+                    hatch.pattern_type = enum_from_number!(
+                        HatchPatternType,
+                        Predefined,
                         from_i16,
                         pair.assert_i16()?
                     );
                 }
-                3 => {
-                    mtext.extended_text.push(pair.assert_string()?);
+                77 => {
+                    hatch.is_pattern_double = as_bool(pair.assert_i16()?);
                 }
-                1 => {
-                    mtext.text = pair.assert_string()?;
+                78 => {
+                    // pattern definition line count; the lines immediately follow
+                    while let Some(line) = HatchPatternDefinitionLine::read(iter)? {
+                        hatch.pattern_definition_lines.push(line);
+                    }
                 }
-                7 => {
-                    mtext.text_style_name = pair.assert_string()?;
+                91 => {
+                    // boundary path count; the paths immediately follow
+                    while let Some(path) = HatchBoundaryPath::read(iter)? {
+                        hatch.boundary_paths.push(path);
+                    }
+                }
+                98 => {
+                    // seed point count; the points immediately follow
+                    reading_seed_points = true;
                 }
                 210 => {
-                    mtext.extrusion_direction.x = pair.assert_f64()?;
+                    hatch.extrusion_direction.x = pair.assert_f64()?;
                 }
                 220 => {
-                    mtext.extrusion_direction.y = pair.assert_f64()?;
+                    hatch.extrusion_direction.y = pair.assert_f64()?;
                 }
                 230 => {
-                    mtext.extrusion_direction.z = pair.assert_f64()?;
+                    hatch.extrusion_direction.z = pair.assert_f64()?;
                 }
-                11 => {
-                    mtext.x_axis_direction.x = pair.assert_f64()?;
+                421 if !hatch.gradient_colors.is_empty() => {
+                    vec_last!(hatch.gradient_colors).true_color = pair.assert_i32()?;
                 }
-                21 => {
-                    mtext.x_axis_direction.y = pair.assert_f64()?;
+                450 => {
+                    hatch.is_gradient = pair.assert_i32()? != 0;
                 }
-                31 => {
-                    mtext.x_axis_direction.z = pair.assert_f64()?;
+                451 | 453 => (), // reserved value and gradient color count
+                452 => {
+                    hatch.is_single_color_gradient = pair.assert_i32()? != 0;
                 }
-                42 => {
-                    mtext.horizontal_width = pair.assert_f64()?;
+                460 => {
+                    hatch.gradient_angle = pair.assert_f64()?;
                 }
-                43 => {
-                    mtext.vertical_height = pair.assert_f64()?;
+                461 => {
+                    hatch.gradient_shift = pair.assert_f64()?;
                 }
-                50 => {
-                    if reading_column_data {
-                        if read_column_count {
-                            mtext.column_heights.push(pair.assert_f64()?);
-                        } else {
-                            mtext.column_count = pair.assert_f64()? as i32;
-                            read_column_count = true;
-                        }
+                462 => {
+                    hatch.gradient_tint = pair.assert_f64()?;
+                }
+                463 => {
+                    hatch.gradient_colors.push(HatchGradientColor {
+                        value: pair.assert_f64()?,
+                        ..Default::default()
+                    });
+                }
+                470 => {
+                    hatch.gradient_name = pair.assert_string()?;
+                }
+                _ => {
+                    common.apply_individual_pair(&pair, iter)?;
+                }
+            }
+        }
+    }
+    //test
+    fn apply_custom_reader_loftedsurface(
+        common: &mut EntityCommon,
+        surface: &mut LoftedSurface,
+        iter: &mut CodePairPutBack,
+    ) -> DxfResult<bool> {
+        let surface_text = "AcDbLoftedSurface";
+        let mut last_subclass_marker = String::new();
+        let mut transformation_matrix = vec![];
+        loop {
+            let pair = next_pair!(iter);
+            match pair.code {
+                100 => {
+                    last_subclass_marker = pair.assert_string()?;
+                }
+                1 => {
+                    surface.custom_data.push(pair.assert_string()?);
+                }
+                3 => {
+                    surface.custom_data2.push(pair.assert_string()?);
+                }
+                70 => {
+                    if last_subclass_marker == surface_text {
+                        surface.plane_normal_lofting_type = pair.assert_i16()?;
                     } else {
-                        mtext.rotation_angle = pair.assert_f64()?;
+                        surface.format_version_number = pair.assert_i16()?;
                     }
                 }
-                73 => {
-                    mtext.line_spacing_style = enum_from_number!(
-                        MTextLineSpacingStyle,
-                        AtLeast,
-                        from_i16,
-                        pair.assert_i16()?
-                    );
+                71 => {
+                    surface.u_isoline_count = pair.assert_i16()?;
                 }
-                44 => {
-                    mtext.line_spacing_factor = pair.assert_f64()?;
+                72 => {
+                    surface.v_isoline_count = pair.assert_i16()?;
                 }
-                90 => {
-                    mtext.background_fill_setting =
-                        enum_from_number!(BackgroundFillSetting, Off, from_i32, pair.assert_i32()?);
+                40 => {
+                    transformation_matrix.push(pair.assert_f64()?);
+                    if transformation_matrix.len() == 16 {
+                        surface.transformation_matrix =
+                            TransformationMatrix::from_vec(&transformation_matrix);
+                    }
                 }
-                420 => {
-                    mtext.background_color_rgb = pair.assert_i32()?;
+                41 => {
+                    surface.start_draft_angle = pair.assert_f64()?;
                 }
-                430 => {
-                    mtext.background_color_name = pair.assert_string()?;
+                42 => {
+                    surface.end_draft_angle = pair.assert_f64()?;
                 }
-                45 => {
-                    mtext.fill_box_scale = pair.assert_f64()?;
+                43 => {
+                    surface.start_draft_magnitude = pair.assert_f64()?;
                 }
-                63 => {
-                    mtext.background_fill_color = Color::from_raw_value(pair.assert_i16()?);
+                44 => {
+                    surface.end_draft_magnitude = pair.assert_f64()?;
                 }
-                441 => {
-                    mtext.background_fill_color_transparency = pair.assert_i32()?;
+                290 => {
+                    surface.arc_length_parameterization = pair.assert_bool()?;
                 }
-                75 => {
-                    mtext.column_type = pair.assert_i16()?;
-                    reading_column_data = true;
+                291 => {
+                    surface.no_twist = pair.assert_bool()?;
                 }
-                76 => {
-                    mtext.column_count = i32::from(pair.assert_i16()?);
+                292 => {
+                    surface.align_direction = pair.assert_bool()?;
                 }
-                78 => {
-                    mtext.is_column_flow_reversed = as_bool(pair.assert_i16()?);
+                293 => {
+                    surface.create_simple_surfaces = pair.assert_bool()?;
                 }
-                79 => {
-                    mtext.is_column_auto_height = as_bool(pair.assert_i16()?);
+                294 => {
+                    surface.create_closed_surface = pair.assert_bool()?;
                 }
-                48 => {
-                    mtext.column_width = pair.assert_f64()?;
+                295 => {
+                    surface.is_solid = pair.assert_bool()?;
                 }
-                49 => {
-                    mtext.column_gutter = pair.assert_f64()?;
+                296 => {
+                    surface.create_ruled_surface = pair.assert_bool()?;
+                }
+                297 => {
+                    surface.virtual_guide = pair.assert_bool()?;
                 }
                 _ => {
                     common.apply_individual_pair(&pair, iter)?;
@@ -1296,96 +1864,915 @@ impl Entity {
             }
         }
     }
-    pub(crate) fn add_code_pairs(
-        &self,
-        pairs: &mut Vec<CodePair>,
-        version: AcadVersion,
-        write_handles: bool,
-    ) {
-        if self.specific.is_supported_on_version(version) {
-            pairs.push(CodePair::new_str(0, self.specific.to_type_string()));
-            self.common.add_code_pairs(pairs, version, write_handles);
-            if !self.add_custom_code_pairs(pairs, version) {
-                self.specific.add_code_pairs(pairs, &self.common, version);
-            }
-
-            self.add_post_code_pairs(pairs, version, write_handles);
-            for x in &self.common.x_data {
-                x.add_code_pairs(pairs, version);
+    fn apply_custom_reader_lwpolyline(
+        common: &mut EntityCommon,
+        poly: &mut LwPolyline,
+        iter: &mut CodePairPutBack,
+    ) -> DxfResult<bool> {
+        loop {
+            let pair = next_pair!(iter);
+            match pair.code {
+                // vertex-specific pairs
+                10 => {
+                    // start a new vertex
+                    poly.vertices.push(LwPolylineVertex::default());
+                    vec_last!(poly.vertices).x = pair.assert_f64()?;
+                }
+                20 => {
+                    vec_last!(poly.vertices).y = pair.assert_f64()?;
+                }
+                38 => {
+                    poly.elevation = pair.assert_f64()?;
+                }
+                40 => {
+                    vec_last!(poly.vertices).starting_width = pair.assert_f64()?;
+                }
+                41 => {
+                    vec_last!(poly.vertices).ending_width = pair.assert_f64()?;
+                }
+                42 => {
+                    vec_last!(poly.vertices).bulge = pair.assert_f64()?;
+                }
+                91 => {
+                    vec_last!(poly.vertices).id = pair.assert_i32()?;
+                }
+                // other pairs
+                39 => {
+                    poly.thickness = pair.assert_f64()?;
+                }
+                43 => {
+                    poly.constant_width = pair.assert_f64()?;
+                }
+                70 => {
+                    poly.flags = i32::from(pair.assert_i16()?);
+                }
+                210 => {
+                    poly.extrusion_direction.x = pair.assert_f64()?;
+                }
+                220 => {
+                    poly.extrusion_direction.y = pair.assert_f64()?;
+                }
+                230 => {
+                    poly.extrusion_direction.z = pair.assert_f64()?;
+                }
+                _ => {
+                    common.apply_individual_pair(&pair, iter)?;
+                }
             }
         }
     }
-    fn add_custom_code_pairs(&self, pairs: &mut Vec<CodePair>, version: AcadVersion) -> bool {
-        match self.specific {
-            EntityType::RotatedDimension(ref dim) => {
-                Entity::add_custom_code_pairs_rotateddimension(pairs, dim, version);
-            }
-            EntityType::RadialDimension(ref dim) => {
-                Entity::add_custom_code_pairs_radialdimension(pairs, dim, version);
-            }
-            EntityType::DiameterDimension(ref dim) => {
-                Entity::add_custom_code_pairs_diameterdimension(pairs, dim, version);
-            }
-            EntityType::AngularThreePointDimension(ref dim) => {
-                Entity::add_custom_code_pairs_angularthreepointdimension(pairs, dim, version);
-            }
-            EntityType::OrdinateDimension(ref dim) => {
-                Entity::add_custom_code_pairs_ordinatedimension(pairs, dim, version);
-            }
-            EntityType::Polyline(ref poly) => {
-                Entity::add_custom_code_pairs_polyline(pairs, poly, version);
+    fn apply_custom_reader_mesh(
+        common: &mut EntityCommon,
+        mesh: &mut Mesh,
+        iter: &mut CodePairPutBack,
+    ) -> DxfResult<bool> {
+        let mesh_text = "AcDbSubDMesh";
+        let mut last_subclass_marker = String::new();
+        // the count code (92-95) that introduced the current array, or 90 once the property overrides start
+        let mut current_array_code = 0;
+        let mut remaining_face_indices = 0;
+        let mut edge_start = None;
+        loop {
+            let pair = next_pair!(iter);
+            if current_array_code == 90 && pair.code != 1001 {
+                match pair.code {
+                    91 => mesh.subentity_overrides.push(MeshSubentityOverride {
+                        subentity_marker: pair.assert_i32()?,
+                        properties: vec![],
+                    }),
+                    92 => (), // property count
+                    90 => {
+                        if let Some(subentity) = mesh.subentity_overrides.last_mut() {
+                            subentity.properties.push(MeshPropertyOverride {
+                                property_type: pair.assert_i32()?,
+                                values: vec![],
+                            });
+                        }
+                    }
+                    _ => {
+                        if let Some(property) = mesh
+                            .subentity_overrides
+                            .last_mut()
+                            .and_then(|s| s.properties.last_mut())
+                        {
+                            property.values.push(pair);
+                        }
+                    }
+                }
+                continue;
             }
-            EntityType::Vertex(ref v) => {
-                Entity::add_custom_code_pairs_vertex(pairs, v, version);
+            match pair.code {
+                100 => {
+                    last_subclass_marker = pair.assert_string()?;
+                }
+                71 => {
+                    mesh.version = pair.assert_i16()?;
+                }
+                72 => {
+                    mesh.is_blend_creased = as_bool(pair.assert_i16()?);
+                }
+                91 => {
+                    mesh.subdivision_level = pair.assert_i32()?;
+                }
+                92..=95 if last_subclass_marker == mesh_text => {
+                    current_array_code = pair.code;
+                }
+                10 => {
+                    mesh.vertices.push(Point::new(pair.assert_f64()?, 0.0, 0.0));
+                }
+                20 => {
+                    vec_last!(mesh.vertices).y = pair.assert_f64()?;
+                }
+                30 => {
+                    vec_last!(mesh.vertices).z = pair.assert_f64()?;
+                }
+                90 => {
+                    let value = pair.assert_i32()?;
+                    match current_array_code {
+                        93 => {
+                            if remaining_face_indices == 0 {
+                                mesh.faces.push(vec![]);
+                                remaining_face_indices = value;
+                            } else {
+                                vec_last!(mesh.faces).push(value);
+                                remaining_face_indices -= 1;
+                            }
+                        }
+                        94 => match edge_start.take() {
+                            Some(start) => mesh.edges.push((start, value)),
+                            None => edge_start = Some(value),
+                        },
+                        95 => {
+                            // property override count
+                            current_array_code = 90;
+                        }
+                        _ => (),
+                    }
+                }
+                140 => {
+                    mesh.edge_creases.push(pair.assert_f64()?);
+                }
+                _ => {
+                    common.apply_individual_pair(&pair, iter)?;
+                }
             }
-            _ => return false, // no custom code pairs
-        }
-
-        true
-    }
-    fn add_custom_code_pairs_rotateddimension(
-        pairs: &mut Vec<CodePair>,
-        dim: &RotatedDimension,
-        version: AcadVersion,
-    ) -> bool {
-        dim.dimension_base.add_code_pairs(pairs, version);
-        if version >= AcadVersion::R13 {
-            pairs.push(CodePair::new_str(100, "AcDbAlignedDimension"));
-        }
-        pairs.push(CodePair::new_f64(12, dim.insertion_point.x));
-        pairs.push(CodePair::new_f64(22, dim.insertion_point.y));
-        pairs.push(CodePair::new_f64(32, dim.insertion_point.z));
-        pairs.push(CodePair::new_f64(13, dim.definition_point_2.x));
-        pairs.push(CodePair::new_f64(23, dim.definition_point_2.y));
-        pairs.push(CodePair::new_f64(33, dim.definition_point_2.z));
-        pairs.push(CodePair::new_f64(14, dim.definition_point_3.x));
-        pairs.push(CodePair::new_f64(24, dim.definition_point_3.y));
-        pairs.push(CodePair::new_f64(34, dim.definition_point_3.z));
-        pairs.push(CodePair::new_f64(50, dim.rotation_angle));
-        pairs.push(CodePair::new_f64(52, dim.extension_line_angle));
-        if version >= AcadVersion::R13 {
-            pairs.push(CodePair::new_str(100, "AcDbRotatedDimension"));
         }
-        true
-    }
-    fn add_custom_code_pairs_radialdimension(
-        pairs: &mut Vec<CodePair>,
-        dim: &RadialDimension,
-        version: AcadVersion,
-    ) -> bool {
-        dim.dimension_base.add_code_pairs(pairs, version);
-        pairs.push(CodePair::new_str(100, "AcDbRadialDimension"));
-        pairs.push(CodePair::new_f64(15, dim.definition_point_2.x));
-        pairs.push(CodePair::new_f64(25, dim.definition_point_2.y));
-        pairs.push(CodePair::new_f64(35, dim.definition_point_2.z));
-        pairs.push(CodePair::new_f64(40, dim.leader_length));
-        true
     }
-    fn add_custom_code_pairs_diameterdimension(
-        pairs: &mut Vec<CodePair>,
-        dim: &DiameterDimension,
-        version: AcadVersion,
-    ) -> bool {
+    fn apply_custom_reader_mleader(
+        common: &mut EntityCommon,
+        mleader: &mut MLeader,
+        iter: &mut CodePairPutBack,
+    ) -> DxfResult<bool> {
+        // code 330 is the owner handle until the subclass marker, then it starts a block attribute
+        let mut seen_subclass_marker = false;
+        loop {
+            let pair = next_pair!(iter);
+            match pair.code {
+                100 if pair.assert_string()? == "AcDbMLeader" => {
+                    seen_subclass_marker = true;
+                }
+                10 => {
+                    mleader.block_content_scale.x = pair.assert_f64()?;
+                }
+                20 => {
+                    mleader.block_content_scale.y = pair.assert_f64()?;
+                }
+                30 => {
+                    mleader.block_content_scale.z = pair.assert_f64()?;
+                }
+                41 => {
+                    mleader.dogleg_length = pair.assert_f64()?;
+                }
+                42 => {
+                    mleader.arrowhead_size = pair.assert_f64()?;
+                }
+                43 => {
+                    mleader.block_content_rotation = pair.assert_f64()?;
+                }
+                44 if !mleader.block_attributes.is_empty() => {
+                    vec_last!(mleader.block_attributes).width = pair.assert_f64()?;
+                }
+                90 => {
+                    mleader.property_override_flags = pair.assert_i32()?;
+                }
+                91 => {
+                    mleader.leader_line_color = pair.assert_i32()?;
+                }
+                92 => {
+                    mleader.text_color = pair.assert_i32()?;
+                }
+                93 => {
+                    mleader.block_content_color = pair.assert_i32()?;
+                }
+                94 => {
+                    mleader.arrowhead_overrides.push(MLeaderArrowheadOverride {
+                        index: pair.assert_i32()?,
+                        ..Default::default()
+                    });
+                }
+                95 => {
+                    mleader.text_right_attachment_type = pair.assert_i32()?;
+                }
+                170 => {
+                    mleader.leader_line_type = pair.assert_i16()?;
+                }
+                171 => {
+                    mleader.leader_line_weight = pair.assert_i16()?;
+                }
+                172 => {
+                    mleader.content_type = pair.assert_i16()?;
+                }
+                173 => {
+                    mleader.text_left_attachment_type = pair.assert_i16()?;
+                }
+                174 => {
+                    mleader.text_angle_type = pair.assert_i16()?;
+                }
+                175 => {
+                    mleader.text_alignment_type = pair.assert_i16()?;
+                }
+                176 => {
+                    mleader.block_content_connection_type = pair.assert_i16()?;
+                }
+                177 if !mleader.block_attributes.is_empty() => {
+                    vec_last!(mleader.block_attributes).index = pair.assert_i16()?;
+                }
+                178 => {
+                    mleader.text_align_in_ipe = pair.assert_i16()?;
+                }
+                179 => {
+                    mleader.text_attachment_point = pair.assert_i16()?;
+                }
+                270 => {
+                    mleader.version = pair.assert_i16()?;
+                }
+                271 => {
+                    mleader.text_attachment_direction = enum_from_number!(
+                        TextAttachmentDirection,
+                        Horizontal,
+                        from_i16,
+                        pair.assert_i16()?
+                    );
+                }
+                272 => {
+                    mleader.bottom_text_attachment_direction = enum_from_number!(
+                        BottomTextAttachmentDirection,
+                        Center,
+                        from_i16,
+                        pair.assert_i16()?
+                    );
+                }
+                273 => {
+                    mleader.top_text_attachment_direction = enum_from_number!(
+                        TopTextAttachmentDirection,
+                        Center,
+                        from_i16,
+                        pair.assert_i16()?
+                    );
+                }
+                290 => {
+                    mleader.enable_landing = pair.assert_bool()?;
+                }
+                291 => {
+                    mleader.enable_dogleg = pair.assert_bool()?;
+                }
+                292 => {
+                    mleader.enable_frame_text = pair.assert_bool()?;
+                }
+                293 => {
+                    mleader.enable_annotation_scale = pair.assert_bool()?;
+                }
+                294 => {
+                    mleader.is_text_direction_negative = pair.assert_bool()?;
+                }
+                300 => {
+                    // CONTEXT_DATA{
+                    mleader.context_data = MLeaderContextData::read(iter)?;
+                }
+                302 if !mleader.block_attributes.is_empty() => {
+                    vec_last!(mleader.block_attributes).text = pair.assert_string()?;
+                }
+                330 if seen_subclass_marker => {
+                    mleader.block_attributes.push(MLeaderBlockAttribute {
+                        __attribute_definition_handle: pair.as_handle()?,
+                        ..Default::default()
+                    });
+                }
+                340 => {
+                    mleader.__leader_style_handle = pair.as_handle()?;
+                }
+                341 => {
+                    mleader.__line_type_handle = pair.as_handle()?;
+                }
+                342 => {
+                    mleader.__arrowhead_handle = pair.as_handle()?;
+                }
+                343 => {
+                    mleader.__text_style_handle = pair.as_handle()?;
+                }
+                344 => {
+                    mleader.__block_content_handle = pair.as_handle()?;
+                }
+                345 if !mleader.arrowhead_overrides.is_empty() => {
+                    vec_last!(mleader.arrowhead_overrides).__arrowhead_handle = pair.as_handle()?;
+                }
+                _ => {
+                    common.apply_individual_pair(&pair, iter)?;
+                }
+            }
+        }
+    }
+    fn apply_custom_reader_mtext(
+        common: &mut EntityCommon,
+        mtext: &mut MText,
+        iter: &mut CodePairPutBack,
+    ) -> DxfResult<bool> {
+        let mut reading_column_data = false;
+        let mut read_column_count = false;
+        loop {
+            let pair = next_pair!(iter);
+            match pair.code {
+                10 => {
+                    mtext.insertion_point.x = pair.assert_f64()?;
+                }
+                20 => {
+                    mtext.insertion_point.y = pair.assert_f64()?;
+                }
+                30 => {
+                    mtext.insertion_point.z = pair.assert_f64()?;
+                }
+                40 => {
+                    mtext.initial_text_height = pair.assert_f64()?;
+                }
+                41 => {
+                    mtext.reference_rectangle_width = pair.assert_f64()?;
+                }
+                71 => {
+                    mtext.attachment_point =
+                        enum_from_number!(AttachmentPoint, TopLeft, from_i16, pair.assert_i16()?);
+                }
+                72 => {
+                    mtext.drawing_direction = enum_from_number!(
+                        DrawingDirection,
+                        LeftToRight,
+                        from_i16,
+                        pair.assert_i16()?
+                    );
+                }
+                3 => {
+                    mtext.extended_text.push(pair.assert_string()?);
+                }
+                1 => {
+                    mtext.text = pair.assert_string()?;
+                }
+                7 => {
+                    mtext.text_style_name = pair.assert_string()?;
+                }
+                210 => {
+                    mtext.extrusion_direction.x = pair.assert_f64()?;
+                }
+                220 => {
+                    mtext.extrusion_direction.y = pair.assert_f64()?;
+                }
+                230 => {
+                    mtext.extrusion_direction.z = pair.assert_f64()?;
+                }
+                11 => {
+                    mtext.x_axis_direction.x = pair.assert_f64()?;
+                }
+                21 => {
+                    mtext.x_axis_direction.y = pair.assert_f64()?;
+                }
+                31 => {
+                    mtext.x_axis_direction.z = pair.assert_f64()?;
+                }
+                42 => {
+                    mtext.horizontal_width = pair.assert_f64()?;
+                }
+                43 => {
+                    mtext.vertical_height = pair.assert_f64()?;
+                }
+                50 => {
+                    if reading_column_data {
+                        if read_column_count {
+                            mtext.column_heights.push(pair.assert_f64()?);
+                        } else {
+                            mtext.column_count = pair.assert_f64()? as i32;
+                            read_column_count = true;
+                        }
+                    } else {
+                        mtext.rotation_angle = pair.assert_f64()?;
+                    }
+                }
+                73 => {
+                    mtext.line_spacing_style = enum_from_number!(
+                        MTextLineSpacingStyle,
+                        AtLeast,
+                        from_i16,
+                        pair.assert_i16()?
+                    );
+                }
+                44 => {
+                    mtext.line_spacing_factor = pair.assert_f64()?;
+                }
+                90 => {
+                    mtext.background_fill_setting =
+                        enum_from_number!(BackgroundFillSetting, Off, from_i32, pair.assert_i32()?);
+                }
+                420 => {
+                    mtext.background_color_rgb = pair.assert_i32()?;
+                }
+                430 => {
+                    mtext.background_color_name = pair.assert_string()?;
+                }
+                45 => {
+                    mtext.fill_box_scale = pair.assert_f64()?;
+                }
+                63 => {
+                    mtext.background_fill_color = Color::from_raw_value(pair.assert_i16()?);
+                }
+                441 => {
+                    mtext.background_fill_color_transparency = pair.assert_i32()?;
+                }
+                75 => {
+                    mtext.column_type = pair.assert_i16()?;
+                    reading_column_data = true;
+                }
+                76 => {
+                    mtext.column_count = i32::from(pair.assert_i16()?);
+                }
+                78 => {
+                    mtext.is_column_flow_reversed = as_bool(pair.assert_i16()?);
+                }
+                79 => {
+                    mtext.is_column_auto_height = as_bool(pair.assert_i16()?);
+                }
+                48 => {
+                    mtext.column_width = pair.assert_f64()?;
+                }
+                49 => {
+                    mtext.column_gutter = pair.assert_f64()?;
+                }
+                _ => {
+                    common.apply_individual_pair(&pair, iter)?;
+                }
+            }
+        }
+    }
+    pub(crate) fn add_code_pairs(
+        &self,
+        pairs: &mut Vec<CodePair>,
+        version: AcadVersion,
+        write_handles: bool,
+    ) {
+        if let EntityType::Unknown {
+            ref type_string,
+            pairs: ref unknown_pairs,
+        } = self.specific
+        {
+            // unsupported entities are written back as they were read, with the common values that may have changed
+            pairs.push(CodePair::new_str(0, type_string));
+            if write_handles && !self.common.handle.is_empty() {
+                pairs.push(CodePair::new_string(5, &self.common.handle.as_string()));
+            }
+            let (layer_index, owner_index) = unknown_entity_common_pair_indices(unknown_pairs);
+            for (i, pair) in unknown_pairs.iter().enumerate() {
+                if Some(i) == layer_index {
+                    pairs.push(CodePair::new_string(8, &self.common.layer));
+                } else if Some(i) == owner_index {
+                    pairs.push(CodePair::new_string(
+                        330,
+                        &self.common.__owner_handle.as_string(),
+                    ));
+                } else {
+                    pairs.push(pair.clone());
+                }
+            }
+        } else if self.specific.is_supported_on_version(version) {
+            pairs.push(CodePair::new_str(0, self.specific.to_type_string()));
+            self.common.add_code_pairs(pairs, version, write_handles);
+            if !self.add_custom_code_pairs(pairs, version) {
+                self.specific.add_code_pairs(pairs, &self.common, version);
+            }
+
+            self.add_post_code_pairs(pairs, version, write_handles);
+            for x in &self.common.x_data {
+                x.add_code_pairs(pairs, version);
+            }
+        }
+    }
+    fn apply_custom_reader_revolvedsurface(
+        common: &mut EntityCommon,
+        surface: &mut RevolvedSurface,
+        iter: &mut CodePairPutBack,
+    ) -> DxfResult<bool> {
+        let mut transformation_matrix = vec![];
+        loop {
+            let pair = next_pair!(iter);
+            match pair.code {
+                1 => {
+                    surface.custom_data.push(pair.assert_string()?);
+                }
+                3 => {
+                    surface.custom_data2.push(pair.assert_string()?);
+                }
+                70 => {
+                    surface.format_version_number = pair.assert_i16()?;
+                }
+                71 => {
+                    surface.u_isoline_count = pair.assert_i16()?;
+                }
+                72 => {
+                    surface.v_isoline_count = pair.assert_i16()?;
+                }
+                90 => {
+                    surface.class_id = pair.assert_i32()?;
+                }
+                10 => {
+                    surface.axis_point.x = pair.assert_f64()?;
+                }
+                20 => {
+                    surface.axis_point.y = pair.assert_f64()?;
+                }
+                30 => {
+                    surface.axis_point.z = pair.assert_f64()?;
+                }
+                11 => {
+                    surface.axis_vector.x = pair.assert_f64()?;
+                }
+                21 => {
+                    surface.axis_vector.y = pair.assert_f64()?;
+                }
+                31 => {
+                    surface.axis_vector.z = pair.assert_f64()?;
+                }
+                40 => {
+                    surface.revolve_angle = pair.assert_f64()?;
+                }
+                41 => {
+                    surface.start_angle = pair.assert_f64()?;
+                }
+                42 => {
+                    transformation_matrix.push(pair.assert_f64()?);
+                    if transformation_matrix.len() == 16 {
+                        surface.transformation_matrix =
+                            TransformationMatrix::from_vec(&transformation_matrix);
+                    }
+                }
+                43 => {
+                    surface.draft_angle = pair.assert_f64()?;
+                }
+                44 => {
+                    surface.start_draft_distance = pair.assert_f64()?;
+                }
+                45 => {
+                    surface.end_draft_distance = pair.assert_f64()?;
+                }
+                46 => {
+                    surface.twist_angle = pair.assert_f64()?;
+                }
+                290 => {
+                    surface.is_solid = pair.assert_bool()?;
+                }
+                291 => {
+                    surface.close_to_axis = pair.assert_bool()?;
+                }
+                _ => {
+                    common.apply_individual_pair(&pair, iter)?;
+                }
+            }
+        }
+    }
+    fn apply_custom_reader_sweptsurface(
+        common: &mut EntityCommon,
+        surface: &mut SweptSurface,
+        iter: &mut CodePairPutBack,
+    ) -> DxfResult<bool> {
+        let surface_text = "AcDbSweptSurface";
+        let mut last_subclass_marker = String::new();
+        // the swept and path entities are each written as an id, a byte count, and 310 data chunks
+        let mut code_90_count = 0;
+        let mut transformation_matrix = vec![];
+        let mut path_transformation_matrix = vec![];
+        let mut sweep_entity_transformation_matrix = vec![];
+        let mut path_entity_transformation_matrix = vec![];
+        loop {
+            let pair = next_pair!(iter);
+            match pair.code {
+                100 => {
+                    last_subclass_marker = pair.assert_string()?;
+                }
+                1 => {
+                    surface.custom_data.push(pair.assert_string()?);
+                }
+                3 => {
+                    surface.custom_data2.push(pair.assert_string()?);
+                }
+                70 => {
+                    if last_subclass_marker == surface_text {
+                        surface.sweep_alignment_flags = pair.assert_i16()?;
+                    } else {
+                        surface.format_version_number = pair.assert_i16()?;
+                    }
+                }
+                71 => {
+                    surface.u_isoline_count = pair.assert_i16()?;
+                }
+                72 => {
+                    surface.v_isoline_count = pair.assert_i16()?;
+                }
+                90 => {
+                    match code_90_count {
+                        0 => surface.swept_entity_id = pair.assert_i32()?,
+                        2 => surface.path_entity_id = pair.assert_i32()?,
+                        _ => (), // byte counts are recomputed when writing
+                    }
+                    code_90_count += 1;
+                }
+                310 if last_subclass_marker == surface_text => {
+                    if code_90_count <= 2 {
+                        surface.swept_entity_data.push(pair.assert_binary()?);
+                    } else {
+                        surface.path_entity_data.push(pair.assert_binary()?);
+                    }
+                }
+                40 => {
+                    transformation_matrix.push(pair.assert_f64()?);
+                    if transformation_matrix.len() == 16 {
+                        surface.transformation_matrix =
+                            TransformationMatrix::from_vec(&transformation_matrix);
+                    }
+                }
+                41 => {
+                    path_transformation_matrix.push(pair.assert_f64()?);
+                    if path_transformation_matrix.len() == 16 {
+                        surface.path_transformation_matrix =
+                            TransformationMatrix::from_vec(&path_transformation_matrix);
+                    }
+                }
+                42 => {
+                    surface.draft_angle = pair.assert_f64()?;
+                }
+                43 => {
+                    surface.draft_start_distance = pair.assert_f64()?;
+                }
+                44 => {
+                    surface.draft_end_distance = pair.assert_f64()?;
+                }
+                45 => {
+                    surface.twist_angle = pair.assert_f64()?;
+                }
+                46 => {
+                    sweep_entity_transformation_matrix.push(pair.assert_f64()?);
+                    if sweep_entity_transformation_matrix.len() == 16 {
+                        surface.sweep_entity_transformation_matrix =
+                            TransformationMatrix::from_vec(&sweep_entity_transformation_matrix);
+                    }
+                }
+                47 => {
+                    path_entity_transformation_matrix.push(pair.assert_f64()?);
+                    if path_entity_transformation_matrix.len() == 16 {
+                        surface.path_entity_transformation_matrix =
+                            TransformationMatrix::from_vec(&path_entity_transformation_matrix);
+                    }
+                }
+                48 if last_subclass_marker == surface_text => {
+                    surface.scale_factor = pair.assert_f64()?;
+                }
+                49 => {
+                    surface.align_angle = pair.assert_f64()?;
+                }
+                290 => {
+                    surface.is_solid = pair.assert_bool()?;
+                }
+                292 => {
+                    surface.align_start = pair.assert_bool()?;
+                }
+                293 => {
+                    surface.bank = pair.assert_bool()?;
+                }
+                294 => {
+                    surface.base_point_set = pair.assert_bool()?;
+                }
+                295 => {
+                    surface.sweep_entity_transform_computed = pair.assert_bool()?;
+                }
+                296 => {
+                    surface.path_entity_transform_computed = pair.assert_bool()?;
+                }
+                11 => {
+                    surface.reference_vector_for_controlling_twist.x = pair.assert_f64()?;
+                }
+                21 => {
+                    surface.reference_vector_for_controlling_twist.y = pair.assert_f64()?;
+                }
+                31 => {
+                    surface.reference_vector_for_controlling_twist.z = pair.assert_f64()?;
+                }
+                _ => {
+                    common.apply_individual_pair(&pair, iter)?;
+                }
+            }
+        }
+    }
+    fn apply_custom_reader_table(
+        common: &mut EntityCommon,
+        table: &mut Table,
+        iter: &mut CodePairPutBack,
+    ) -> DxfResult<bool> {
+        let table_text = "AcDbTable";
+        let mut last_subclass_marker = String::new();
+        // code 280 is the version until the rows and cells have been read, then it's a table override
+        let mut seen_cells = false;
+        loop {
+            let pair = next_pair!(iter);
+            match pair.code {
+                100 => {
+                    last_subclass_marker = pair.assert_string()?;
+                }
+                2 => {
+                    table.block_name = pair.assert_string()?;
+                }
+                10 => {
+                    table.insertion_point.x = pair.assert_f64()?;
+                }
+                20 => {
+                    table.insertion_point.y = pair.assert_f64()?;
+                }
+                30 => {
+                    table.insertion_point.z = pair.assert_f64()?;
+                }
+                280 => {
+                    if seen_cells {
+                        table.is_title_suppressed_override = Some(as_bool(pair.assert_i16()?));
+                    } else {
+                        table.version = pair.assert_i16()?;
+                    }
+                }
+                342 => {
+                    table.__table_style_handle = pair.as_handle()?;
+                }
+                343 => {
+                    table.__block_record_handle = pair.as_handle()?;
+                }
+                11 => {
+                    table.horizontal_direction.x = pair.assert_f64()?;
+                }
+                21 => {
+                    table.horizontal_direction.y = pair.assert_f64()?;
+                }
+                31 => {
+                    table.horizontal_direction.z = pair.assert_f64()?;
+                }
+                90 => {
+                    table.table_value_flags = pair.assert_i32()?;
+                }
+                91 | 92 if last_subclass_marker == table_text => (), // row and column counts; recomputed when writing
+                93 => {
+                    table.override_flags = pair.assert_i32()?;
+                }
+                94 => {
+                    table.border_color_override_flags = pair.assert_i32()?;
+                }
+                95 => {
+                    table.border_lineweight_override_flags = pair.assert_i32()?;
+                }
+                96 => {
+                    table.border_visibility_override_flags = pair.assert_i32()?;
+                }
+                141 => {
+                    seen_cells = true;
+                    table.row_heights.push(pair.assert_f64()?);
+                }
+                142 => {
+                    seen_cells = true;
+                    table.column_widths.push(pair.assert_f64()?);
+                }
+                171 => {
+                    seen_cells = true;
+                    iter.put_back(Ok(pair));
+                    if let Some(cell) = TableCell::read(iter)? {
+                        table.cells.push(cell);
+                    }
+                }
+                281 => {
+                    table.is_column_heading_suppressed_override = Some(as_bool(pair.assert_i16()?));
+                }
+                70 => {
+                    table.flow_direction_override = Some(enum_from_number!(
+                        FlowDirection,
+                        Down,
+                        from_i16,
+                        pair.assert_i16()?
+                    ));
+                }
+                40 => {
+                    table.horizontal_cell_margin_override = Some(pair.assert_f64()?);
+                }
+                41 => {
+                    table.vertical_cell_margin_override = Some(pair.assert_f64()?);
+                }
+                _ => {
+                    common.apply_individual_pair(&pair, iter)?;
+                }
+            }
+        }
+    }
+    fn add_custom_code_pairs(&self, pairs: &mut Vec<CodePair>, version: AcadVersion) -> bool {
+        match self.specific {
+            EntityType::RotatedDimension(ref dim) => {
+                Entity::add_custom_code_pairs_rotateddimension(pairs, dim, version);
+            }
+            EntityType::RadialDimension(ref dim) => {
+                Entity::add_custom_code_pairs_radialdimension(pairs, dim, version);
+            }
+            EntityType::DiameterDimension(ref dim) => {
+                Entity::add_custom_code_pairs_diameterdimension(pairs, dim, version);
+            }
+            EntityType::AngularThreePointDimension(ref dim) => {
+                Entity::add_custom_code_pairs_angularthreepointdimension(pairs, dim, version);
+            }
+            EntityType::OrdinateDimension(ref dim) => {
+                Entity::add_custom_code_pairs_ordinatedimension(pairs, dim, version);
+            }
+            EntityType::ExtrudedSurface(ref surface) => {
+                Entity::add_custom_code_pairs_extrudedsurface(pairs, surface);
+            }
+            EntityType::Hatch(ref hatch) => {
+                Entity::add_custom_code_pairs_hatch(pairs, hatch, version);
+            }
+            EntityType::LoftedSurface(ref surface) => {
+                Entity::add_custom_code_pairs_loftedsurface(pairs, surface);
+            }
+            EntityType::Mesh(ref mesh) => {
+                Entity::add_custom_code_pairs_mesh(pairs, mesh);
+            }
+            EntityType::MLeader(ref mleader) => {
+                Entity::add_custom_code_pairs_mleader(pairs, mleader, version);
+            }
+            EntityType::Polyline(ref poly) => {
+                Entity::add_custom_code_pairs_polyline(pairs, poly, version);
+            }
+            EntityType::RevolvedSurface(ref surface) => {
+                Entity::add_custom_code_pairs_revolvedsurface(pairs, surface);
+            }
+            EntityType::SweptSurface(ref surface) => {
+                Entity::add_custom_code_pairs_sweptsurface(pairs, surface);
+            }
+            EntityType::Table(ref table) => {
+                Entity::add_custom_code_pairs_table(pairs, table);
+            }
+            EntityType::Vertex(ref v) => {
+                Entity::add_custom_code_pairs_vertex(pairs, v, version);
+            }
+            _ => return false, // no custom code pairs
+        }
+
+        true
+    }
+    fn add_custom_code_pairs_rotateddimension(
+        pairs: &mut Vec<CodePair>,
+        dim: &RotatedDimension,
+        version: AcadVersion,
+    ) -> bool {
+        dim.dimension_base.add_code_pairs(pairs, version);
+        if version >= AcadVersion::R13 {
+            pairs.push(CodePair::new_str(100, "AcDbAlignedDimension"));
+        }
+        pairs.push(CodePair::new_f64(12, dim.insertion_point.x));
+        pairs.push(CodePair::new_f64(22, dim.insertion_point.y));
+        pairs.push(CodePair::new_f64(32, dim.insertion_point.z));
+        pairs.push(CodePair::new_f64(13, dim.definition_point_2.x));
+        pairs.push(CodePair::new_f64(23, dim.definition_point_2.y));
+        pairs.push(CodePair::new_f64(33, dim.definition_point_2.z));
+        pairs.push(CodePair::new_f64(14, dim.definition_point_3.x));
+        pairs.push(CodePair::new_f64(24, dim.definition_point_3.y));
+        pairs.push(CodePair::new_f64(34, dim.definition_point_3.z));
+        pairs.push(CodePair::new_f64(50, dim.rotation_angle));
+        pairs.push(CodePair::new_f64(52, dim.extension_line_angle));
+        if version >= AcadVersion::R13 {
+            pairs.push(CodePair::new_str(100, "AcDbRotatedDimension"));
+        }
+        true
+    }
+    fn add_custom_code_pairs_radialdimension(
+        pairs: &mut Vec<CodePair>,
+        dim: &RadialDimension,
+        version: AcadVersion,
+    ) -> bool {
+        dim.dimension_base.add_code_pairs(pairs, version);
+        pairs.push(CodePair::new_str(100, "AcDbRadialDimension"));
+        pairs.push(CodePair::new_f64(15, dim.definition_point_2.x));
+        pairs.push(CodePair::new_f64(25, dim.definition_point_2.y));
+        pairs.push(CodePair::new_f64(35, dim.definition_point_2.z));
+        pairs.push(CodePair::new_f64(40, dim.leader_length));
+        true
+    }
+    fn add_custom_code_pairs_diameterdimension(
+        pairs: &mut Vec<CodePair>,
+        dim: &DiameterDimension,
+        version: AcadVersion,
+    ) -> bool {
         dim.dimension_base.add_code_pairs(pairs, version);
         pairs.push(CodePair::new_str(100, "AcDbDiametricDimension"));
         pairs.push(CodePair::new_f64(15, dim.definition_point_2.x));
@@ -1415,19 +2802,209 @@ impl Entity {
         pairs.push(CodePair::new_f64(36, dim.definition_point_5.z));
         true
     }
-    fn add_custom_code_pairs_ordinatedimension(
+    fn add_custom_code_pairs_ordinatedimension(
+        pairs: &mut Vec<CodePair>,
+        dim: &OrdinateDimension,
+        version: AcadVersion,
+    ) -> bool {
+        dim.dimension_base.add_code_pairs(pairs, version);
+        pairs.push(CodePair::new_str(100, "AcDbOrdinateDimension"));
+        pairs.push(CodePair::new_f64(13, dim.definition_point_2.x));
+        pairs.push(CodePair::new_f64(23, dim.definition_point_2.y));
+        pairs.push(CodePair::new_f64(33, dim.definition_point_2.z));
+        pairs.push(CodePair::new_f64(14, dim.definition_point_3.x));
+        pairs.push(CodePair::new_f64(24, dim.definition_point_3.y));
+        pairs.push(CodePair::new_f64(34, dim.definition_point_3.z));
+        true
+    }
+    fn add_custom_code_pairs_hatch(
+        pairs: &mut Vec<CodePair>,
+        hatch: &Hatch,
+        version: AcadVersion,
+    ) -> bool {
+        pairs.push(CodePair::new_str(100, "AcDbHatch"));
+        pairs.push(CodePair::new_f64(10, hatch.elevation_point.x));
+        pairs.push(CodePair::new_f64(20, hatch.elevation_point.y));
+        pairs.push(CodePair::new_f64(30, hatch.elevation_point.z));
+        pairs.push(CodePair::new_f64(210, hatch.extrusion_direction.x));
+        pairs.push(CodePair::new_f64(220, hatch.extrusion_direction.y));
+        pairs.push(CodePair::new_f64(230, hatch.extrusion_direction.z));
+        pairs.push(CodePair::new_string(2, &hatch.pattern_name));
+        pairs.push(CodePair::new_i16(70, as_i16(hatch.is_solid_fill)));
+        pairs.push(CodePair::new_i16(71, as_i16(hatch.is_associative)));
+        pairs.push(CodePair::new_i32(91, hatch.boundary_paths.len() as i32));
+        for path in &hatch.boundary_paths {
+            path.add_code_pairs(pairs, version);
+        }
+        pairs.push(CodePair::new_i16(75, hatch.hatch_style as i16));
+        pairs.push(CodePair::new_i16(76, hatch.pattern_type as i16));
+        if !hatch.is_solid_fill {
+            pairs.push(CodePair::new_f64(52, hatch.pattern_angle));
+            pairs.push(CodePair::new_f64(41, hatch.pattern_scale));
+            pairs.push(CodePair::new_i16(77, as_i16(hatch.is_pattern_double)));
+            pairs.push(CodePair::new_i16(
+                78,
+                hatch.pattern_definition_lines.len() as i16,
+            ));
+            for line in &hatch.pattern_definition_lines {
+                line.add_code_pairs(pairs);
+            }
+        }
+        if hatch.pixel_size != 0.0 {
+            pairs.push(CodePair::new_f64(47, hatch.pixel_size));
+        }
+        pairs.push(CodePair::new_i32(98, hatch.seed_points.len() as i32));
+        for seed_point in &hatch.seed_points {
+            pairs.push(CodePair::new_f64(10, seed_point.x));
+            pairs.push(CodePair::new_f64(20, seed_point.y));
+        }
+        if version >= AcadVersion::R2004 && hatch.is_gradient {
+            pairs.push(CodePair::new_i32(450, 1));
+            pairs.push(CodePair::new_i32(451, 0));
+            pairs.push(CodePair::new_i32(
+                452,
+                i32::from(as_i16(hatch.is_single_color_gradient)),
+            ));
+            pairs.push(CodePair::new_i32(453, hatch.gradient_colors.len() as i32));
+            for gradient_color in &hatch.gradient_colors {
+                pairs.push(CodePair::new_f64(463, gradient_color.value));
+                if gradient_color.color.is_index() {
+                    pairs.push(CodePair::new_i16(63, gradient_color.color.raw_value()));
+                }
+                pairs.push(CodePair::new_i32(421, gradient_color.true_color));
+            }
+            pairs.push(CodePair::new_f64(460, hatch.gradient_angle));
+            pairs.push(CodePair::new_f64(461, hatch.gradient_shift));
+            pairs.push(CodePair::new_f64(462, hatch.gradient_tint));
+            pairs.push(CodePair::new_string(470, &hatch.gradient_name));
+        }
+        true
+    }
+    fn add_custom_code_pairs_mleader(
         pairs: &mut Vec<CodePair>,
-        dim: &OrdinateDimension,
+        mleader: &MLeader,
         version: AcadVersion,
     ) -> bool {
-        dim.dimension_base.add_code_pairs(pairs, version);
-        pairs.push(CodePair::new_str(100, "AcDbOrdinateDimension"));
-        pairs.push(CodePair::new_f64(13, dim.definition_point_2.x));
-        pairs.push(CodePair::new_f64(23, dim.definition_point_2.y));
-        pairs.push(CodePair::new_f64(33, dim.definition_point_2.z));
-        pairs.push(CodePair::new_f64(14, dim.definition_point_3.x));
-        pairs.push(CodePair::new_f64(24, dim.definition_point_3.y));
-        pairs.push(CodePair::new_f64(34, dim.definition_point_3.z));
+        pairs.push(CodePair::new_str(100, "AcDbMLeader"));
+        pairs.push(CodePair::new_i16(270, mleader.version));
+        mleader.context_data.add_code_pairs(pairs, version);
+        pairs.push(CodePair::new_string(
+            340,
+            &mleader.__leader_style_handle.as_string(),
+        ));
+        pairs.push(CodePair::new_i32(90, mleader.property_override_flags));
+        pairs.push(CodePair::new_i16(170, mleader.leader_line_type));
+        pairs.push(CodePair::new_i32(91, mleader.leader_line_color));
+        pairs.push(CodePair::new_string(
+            341,
+            &mleader.__line_type_handle.as_string(),
+        ));
+        pairs.push(CodePair::new_i16(171, mleader.leader_line_weight));
+        pairs.push(CodePair::new_bool(290, mleader.enable_landing));
+        pairs.push(CodePair::new_bool(291, mleader.enable_dogleg));
+        pairs.push(CodePair::new_f64(41, mleader.dogleg_length));
+        pairs.push(CodePair::new_string(
+            342,
+            &mleader.__arrowhead_handle.as_string(),
+        ));
+        pairs.push(CodePair::new_f64(42, mleader.arrowhead_size));
+        pairs.push(CodePair::new_i16(172, mleader.content_type));
+        pairs.push(CodePair::new_string(
+            343,
+            &mleader.__text_style_handle.as_string(),
+        ));
+        pairs.push(CodePair::new_i16(173, mleader.text_left_attachment_type));
+        pairs.push(CodePair::new_i32(95, mleader.text_right_attachment_type));
+        pairs.push(CodePair::new_i16(174, mleader.text_angle_type));
+        pairs.push(CodePair::new_i16(175, mleader.text_alignment_type));
+        pairs.push(CodePair::new_i32(92, mleader.text_color));
+        pairs.push(CodePair::new_bool(292, mleader.enable_frame_text));
+        pairs.push(CodePair::new_string(
+            344,
+            &mleader.__block_content_handle.as_string(),
+        ));
+        pairs.push(CodePair::new_i32(93, mleader.block_content_color));
+        pairs.push(CodePair::new_f64(10, mleader.block_content_scale.x));
+        pairs.push(CodePair::new_f64(20, mleader.block_content_scale.y));
+        pairs.push(CodePair::new_f64(30, mleader.block_content_scale.z));
+        pairs.push(CodePair::new_f64(43, mleader.block_content_rotation));
+        pairs.push(CodePair::new_i16(
+            176,
+            mleader.block_content_connection_type,
+        ));
+        pairs.push(CodePair::new_bool(293, mleader.enable_annotation_scale));
+        for arrowhead_override in &mleader.arrowhead_overrides {
+            pairs.push(CodePair::new_i32(94, arrowhead_override.index));
+            pairs.push(CodePair::new_string(
+                345,
+                &arrowhead_override.__arrowhead_handle.as_string(),
+            ));
+        }
+        for attribute in &mleader.block_attributes {
+            pairs.push(CodePair::new_string(
+                330,
+                &attribute.__attribute_definition_handle.as_string(),
+            ));
+            pairs.push(CodePair::new_i16(177, attribute.index));
+            pairs.push(CodePair::new_f64(44, attribute.width));
+            pairs.push(CodePair::new_string(302, &attribute.text));
+        }
+        pairs.push(CodePair::new_bool(294, mleader.is_text_direction_negative));
+        pairs.push(CodePair::new_i16(178, mleader.text_align_in_ipe));
+        pairs.push(CodePair::new_i16(179, mleader.text_attachment_point));
+        if version >= AcadVersion::R2010 {
+            pairs.push(CodePair::new_i16(
+                271,
+                mleader.text_attachment_direction as i16,
+            ));
+            pairs.push(CodePair::new_i16(
+                272,
+                mleader.bottom_text_attachment_direction as i16,
+            ));
+            pairs.push(CodePair::new_i16(
+                273,
+                mleader.top_text_attachment_direction as i16,
+            ));
+        }
+        true
+    }
+    fn add_custom_code_pairs_mesh(pairs: &mut Vec<CodePair>, mesh: &Mesh) -> bool {
+        pairs.push(CodePair::new_str(100, "AcDbSubDMesh"));
+        pairs.push(CodePair::new_i16(71, mesh.version));
+        pairs.push(CodePair::new_i16(72, as_i16(mesh.is_blend_creased)));
+        pairs.push(CodePair::new_i32(91, mesh.subdivision_level));
+        pairs.push(CodePair::new_i32(92, mesh.vertices.len() as i32));
+        for vertex in &mesh.vertices {
+            pairs.push(CodePair::new_f64(10, vertex.x));
+            pairs.push(CodePair::new_f64(20, vertex.y));
+            pairs.push(CodePair::new_f64(30, vertex.z));
+        }
+        let face_list_size: usize = mesh.faces.iter().map(|f| f.len() + 1).sum();
+        pairs.push(CodePair::new_i32(93, face_list_size as i32));
+        for face in &mesh.faces {
+            pairs.push(CodePair::new_i32(90, face.len() as i32));
+            for index in face {
+                pairs.push(CodePair::new_i32(90, *index));
+            }
+        }
+        pairs.push(CodePair::new_i32(94, mesh.edges.len() as i32));
+        for (start, end) in &mesh.edges {
+            pairs.push(CodePair::new_i32(90, *start));
+            pairs.push(CodePair::new_i32(90, *end));
+        }
+        pairs.push(CodePair::new_i32(95, mesh.edge_creases.len() as i32));
+        for crease in &mesh.edge_creases {
+            pairs.push(CodePair::new_f64(140, *crease));
+        }
+        pairs.push(CodePair::new_i32(90, mesh.subentity_overrides.len() as i32));
+        for subentity in &mesh.subentity_overrides {
+            pairs.push(CodePair::new_i32(91, subentity.subentity_marker));
+            pairs.push(CodePair::new_i32(92, subentity.properties.len() as i32));
+            for property in &subentity.properties {
+                pairs.push(CodePair::new_i32(90, property.property_type));
+                pairs.extend(property.values.iter().cloned());
+            }
+        }
         true
     }
     fn add_custom_code_pairs_polyline(
@@ -1460,1502 +3037,3340 @@ impl Entity {
         if poly.default_starting_width != 0.0 {
             pairs.push(CodePair::new_f64(40, poly.default_starting_width));
         }
-        if poly.default_ending_width != 0.0 {
-            pairs.push(CodePair::new_f64(41, poly.default_ending_width));
+        if poly.default_ending_width != 0.0 {
+            pairs.push(CodePair::new_f64(41, poly.default_ending_width));
+        }
+        if poly.polygon_mesh_m_vertex_count != 0 {
+            pairs.push(CodePair::new_i16(
+                71,
+                poly.polygon_mesh_m_vertex_count as i16,
+            ));
+        }
+        if poly.polygon_mesh_n_vertex_count != 0 {
+            pairs.push(CodePair::new_i16(
+                72,
+                poly.polygon_mesh_n_vertex_count as i16,
+            ));
+        }
+        if poly.smooth_surface_m_density != 0 {
+            pairs.push(CodePair::new_i16(73, poly.smooth_surface_m_density as i16));
+        }
+        if poly.smooth_surface_n_density != 0 {
+            pairs.push(CodePair::new_i16(74, poly.smooth_surface_n_density as i16));
+        }
+        if poly.surface_type != PolylineCurvedAndSmoothSurfaceType::None {
+            pairs.push(CodePair::new_i16(75, poly.surface_type as i16));
+        }
+        if poly.normal != Vector::z_axis() {
+            pairs.push(CodePair::new_f64(210, poly.normal.x));
+            pairs.push(CodePair::new_f64(220, poly.normal.y));
+            pairs.push(CodePair::new_f64(230, poly.normal.z));
+        }
+        true
+    }
+    fn add_custom_code_pairs_vertex(
+        pairs: &mut Vec<CodePair>,
+        v: &Vertex,
+        version: AcadVersion,
+    ) -> bool {
+        pairs.push(CodePair::new_str(100, "AcDbVertex"));
+        let subclass_marker = if v.is_3d_polyline_vertex() || v.is_3d_polygon_mesh() {
+            "AcDb3dPolylineVertex"
+        } else {
+            "AcDb2dVertex"
+        };
+        if version >= AcadVersion::R13 {
+            pairs.push(CodePair::new_str(100, subclass_marker));
+        }
+        pairs.push(CodePair::new_f64(10, v.location.x));
+        pairs.push(CodePair::new_f64(20, v.location.y));
+        pairs.push(CodePair::new_f64(30, v.location.z));
+        if v.starting_width != 0.0 {
+            pairs.push(CodePair::new_f64(40, v.starting_width));
+        }
+        if v.ending_width != 0.0 {
+            pairs.push(CodePair::new_f64(41, v.ending_width));
+        }
+        if v.bulge != 0.0 {
+            pairs.push(CodePair::new_f64(42, v.bulge));
+        }
+        pairs.push(CodePair::new_i16(70, v.flags as i16));
+        pairs.push(CodePair::new_f64(50, v.curve_fit_tangent_direction));
+        if version >= AcadVersion::R13 {
+            if v.polyface_mesh_vertex_index1 != 0 {
+                pairs.push(CodePair::new_i16(71, v.polyface_mesh_vertex_index1 as i16));
+            }
+            if v.polyface_mesh_vertex_index2 != 0 {
+                pairs.push(CodePair::new_i16(72, v.polyface_mesh_vertex_index2 as i16));
+            }
+            if v.polyface_mesh_vertex_index3 != 0 {
+                pairs.push(CodePair::new_i16(73, v.polyface_mesh_vertex_index3 as i16));
+            }
+            if v.polyface_mesh_vertex_index4 != 0 {
+                pairs.push(CodePair::new_i16(74, v.polyface_mesh_vertex_index4 as i16));
+            }
+        }
+        if version >= AcadVersion::R2010 {
+            pairs.push(CodePair::new_i32(91, v.identifier));
+        }
+        true
+    }
+    fn add_custom_code_pairs_extrudedsurface(
+        pairs: &mut Vec<CodePair>,
+        surface: &ExtrudedSurface,
+    ) -> bool {
+        Entity::add_modeler_geometry_code_pairs(
+            pairs,
+            surface.format_version_number,
+            &surface.custom_data,
+            &surface.custom_data2,
+            surface.u_isoline_count,
+            surface.v_isoline_count,
+        );
+        pairs.push(CodePair::new_str(100, "AcDbExtrudedSurface"));
+        pairs.push(CodePair::new_i32(90, surface.class_id));
+        pairs.push(CodePair::new_f64(10, surface.sweep_vector.x));
+        pairs.push(CodePair::new_f64(20, surface.sweep_vector.y));
+        pairs.push(CodePair::new_f64(30, surface.sweep_vector.z));
+        Entity::add_transformation_matrix_code_pairs(pairs, 40, &surface.transformation_matrix);
+        pairs.push(CodePair::new_f64(42, surface.draft_angle));
+        pairs.push(CodePair::new_f64(43, surface.draft_start_distance));
+        pairs.push(CodePair::new_f64(44, surface.draft_end_distance));
+        pairs.push(CodePair::new_f64(45, surface.twist_angle));
+        pairs.push(CodePair::new_f64(48, surface.scale_factor));
+        pairs.push(CodePair::new_f64(49, surface.align_angle));
+        Entity::add_transformation_matrix_code_pairs(
+            pairs,
+            46,
+            &surface.sweep_entity_transformation_matrix,
+        );
+        Entity::add_transformation_matrix_code_pairs(
+            pairs,
+            47,
+            &surface.path_entity_transformation_matrix,
+        );
+        pairs.push(CodePair::new_bool(290, surface.is_solid));
+        pairs.push(CodePair::new_i16(70, surface.sweep_alignment_flags));
+        pairs.push(CodePair::new_bool(292, surface.align_start));
+        pairs.push(CodePair::new_bool(293, surface.bank));
+        pairs.push(CodePair::new_bool(294, surface.base_point_set));
+        pairs.push(CodePair::new_bool(
+            295,
+            surface.sweep_entity_transform_computed,
+        ));
+        pairs.push(CodePair::new_bool(
+            296,
+            surface.path_entity_transform_computed,
+        ));
+        pairs.push(CodePair::new_f64(
+            11,
+            surface.reference_vector_for_controlling_twist.x,
+        ));
+        pairs.push(CodePair::new_f64(
+            21,
+            surface.reference_vector_for_controlling_twist.y,
+        ));
+        pairs.push(CodePair::new_f64(
+            31,
+            surface.reference_vector_for_controlling_twist.z,
+        ));
+        true
+    }
+    fn add_custom_code_pairs_loftedsurface(
+        pairs: &mut Vec<CodePair>,
+        surface: &LoftedSurface,
+    ) -> bool {
+        Entity::add_modeler_geometry_code_pairs(
+            pairs,
+            surface.format_version_number,
+            &surface.custom_data,
+            &surface.custom_data2,
+            surface.u_isoline_count,
+            surface.v_isoline_count,
+        );
+        pairs.push(CodePair::new_str(100, "AcDbLoftedSurface"));
+        Entity::add_transformation_matrix_code_pairs(pairs, 40, &surface.transformation_matrix);
+        pairs.push(CodePair::new_i16(70, surface.plane_normal_lofting_type));
+        pairs.push(CodePair::new_f64(41, surface.start_draft_angle));
+        pairs.push(CodePair::new_f64(42, surface.end_draft_angle));
+        pairs.push(CodePair::new_f64(43, surface.start_draft_magnitude));
+        pairs.push(CodePair::new_f64(44, surface.end_draft_magnitude));
+        pairs.push(CodePair::new_bool(290, surface.arc_length_parameterization));
+        pairs.push(CodePair::new_bool(291, surface.no_twist));
+        pairs.push(CodePair::new_bool(292, surface.align_direction));
+        pairs.push(CodePair::new_bool(293, surface.create_simple_surfaces));
+        pairs.push(CodePair::new_bool(294, surface.create_closed_surface));
+        pairs.push(CodePair::new_bool(295, surface.is_solid));
+        pairs.push(CodePair::new_bool(296, surface.create_ruled_surface));
+        pairs.push(CodePair::new_bool(297, surface.virtual_guide));
+        true
+    }
+    fn add_custom_code_pairs_revolvedsurface(
+        pairs: &mut Vec<CodePair>,
+        surface: &RevolvedSurface,
+    ) -> bool {
+        Entity::add_modeler_geometry_code_pairs(
+            pairs,
+            surface.format_version_number,
+            &surface.custom_data,
+            &surface.custom_data2,
+            surface.u_isoline_count,
+            surface.v_isoline_count,
+        );
+        pairs.push(CodePair::new_str(100, "AcDbRevolvedSurface"));
+        pairs.push(CodePair::new_i32(90, surface.class_id));
+        pairs.push(CodePair::new_f64(10, surface.axis_point.x));
+        pairs.push(CodePair::new_f64(20, surface.axis_point.y));
+        pairs.push(CodePair::new_f64(30, surface.axis_point.z));
+        pairs.push(CodePair::new_f64(11, surface.axis_vector.x));
+        pairs.push(CodePair::new_f64(21, surface.axis_vector.y));
+        pairs.push(CodePair::new_f64(31, surface.axis_vector.z));
+        pairs.push(CodePair::new_f64(40, surface.revolve_angle));
+        pairs.push(CodePair::new_f64(41, surface.start_angle));
+        Entity::add_transformation_matrix_code_pairs(pairs, 42, &surface.transformation_matrix);
+        pairs.push(CodePair::new_f64(43, surface.draft_angle));
+        pairs.push(CodePair::new_f64(44, surface.start_draft_distance));
+        pairs.push(CodePair::new_f64(45, surface.end_draft_distance));
+        pairs.push(CodePair::new_f64(46, surface.twist_angle));
+        pairs.push(CodePair::new_bool(290, surface.is_solid));
+        pairs.push(CodePair::new_bool(291, surface.close_to_axis));
+        true
+    }
+    fn add_custom_code_pairs_sweptsurface(
+        pairs: &mut Vec<CodePair>,
+        surface: &SweptSurface,
+    ) -> bool {
+        Entity::add_modeler_geometry_code_pairs(
+            pairs,
+            surface.format_version_number,
+            &surface.custom_data,
+            &surface.custom_data2,
+            surface.u_isoline_count,
+            surface.v_isoline_count,
+        );
+        pairs.push(CodePair::new_str(100, "AcDbSweptSurface"));
+        pairs.push(CodePair::new_i32(90, surface.swept_entity_id));
+        Entity::add_binary_data_code_pairs(pairs, &surface.swept_entity_data);
+        pairs.push(CodePair::new_i32(90, surface.path_entity_id));
+        Entity::add_binary_data_code_pairs(pairs, &surface.path_entity_data);
+        Entity::add_transformation_matrix_code_pairs(pairs, 40, &surface.transformation_matrix);
+        Entity::add_transformation_matrix_code_pairs(
+            pairs,
+            41,
+            &surface.path_transformation_matrix,
+        );
+        pairs.push(CodePair::new_f64(42, surface.draft_angle));
+        pairs.push(CodePair::new_f64(43, surface.draft_start_distance));
+        pairs.push(CodePair::new_f64(44, surface.draft_end_distance));
+        pairs.push(CodePair::new_f64(45, surface.twist_angle));
+        pairs.push(CodePair::new_f64(48, surface.scale_factor));
+        pairs.push(CodePair::new_f64(49, surface.align_angle));
+        Entity::add_transformation_matrix_code_pairs(
+            pairs,
+            46,
+            &surface.sweep_entity_transformation_matrix,
+        );
+        Entity::add_transformation_matrix_code_pairs(
+            pairs,
+            47,
+            &surface.path_entity_transformation_matrix,
+        );
+        pairs.push(CodePair::new_bool(290, surface.is_solid));
+        pairs.push(CodePair::new_i16(70, surface.sweep_alignment_flags));
+        pairs.push(CodePair::new_bool(292, surface.align_start));
+        pairs.push(CodePair::new_bool(293, surface.bank));
+        pairs.push(CodePair::new_bool(294, surface.base_point_set));
+        pairs.push(CodePair::new_bool(
+            295,
+            surface.sweep_entity_transform_computed,
+        ));
+        pairs.push(CodePair::new_bool(
+            296,
+            surface.path_entity_transform_computed,
+        ));
+        pairs.push(CodePair::new_f64(
+            11,
+            surface.reference_vector_for_controlling_twist.x,
+        ));
+        pairs.push(CodePair::new_f64(
+            21,
+            surface.reference_vector_for_controlling_twist.y,
+        ));
+        pairs.push(CodePair::new_f64(
+            31,
+            surface.reference_vector_for_controlling_twist.z,
+        ));
+        true
+    }
+    fn add_custom_code_pairs_table(pairs: &mut Vec<CodePair>, table: &Table) -> bool {
+        pairs.push(CodePair::new_str(100, "AcDbBlockReference"));
+        pairs.push(CodePair::new_string(2, &table.block_name));
+        pairs.push(CodePair::new_f64(10, table.insertion_point.x));
+        pairs.push(CodePair::new_f64(20, table.insertion_point.y));
+        pairs.push(CodePair::new_f64(30, table.insertion_point.z));
+        pairs.push(CodePair::new_str(100, "AcDbTable"));
+        pairs.push(CodePair::new_i16(280, table.version));
+        pairs.push(CodePair::new_string(
+            342,
+            &table.__table_style_handle.as_string(),
+        ));
+        pairs.push(CodePair::new_string(
+            343,
+            &table.__block_record_handle.as_string(),
+        ));
+        pairs.push(CodePair::new_f64(11, table.horizontal_direction.x));
+        pairs.push(CodePair::new_f64(21, table.horizontal_direction.y));
+        pairs.push(CodePair::new_f64(31, table.horizontal_direction.z));
+        pairs.push(CodePair::new_i32(90, table.table_value_flags));
+        pairs.push(CodePair::new_i32(91, table.row_count() as i32));
+        pairs.push(CodePair::new_i32(92, table.column_count() as i32));
+        pairs.push(CodePair::new_i32(93, table.override_flags));
+        pairs.push(CodePair::new_i32(94, table.border_color_override_flags));
+        pairs.push(CodePair::new_i32(
+            95,
+            table.border_lineweight_override_flags,
+        ));
+        pairs.push(CodePair::new_i32(
+            96,
+            table.border_visibility_override_flags,
+        ));
+        for row_height in &table.row_heights {
+            pairs.push(CodePair::new_f64(141, *row_height));
+        }
+        for column_width in &table.column_widths {
+            pairs.push(CodePair::new_f64(142, *column_width));
+        }
+        for cell in &table.cells {
+            cell.add_code_pairs(pairs);
+        }
+        if let Some(is_title_suppressed) = table.is_title_suppressed_override {
+            pairs.push(CodePair::new_i16(280, as_i16(is_title_suppressed)));
+        }
+        if let Some(is_column_heading_suppressed) = table.is_column_heading_suppressed_override {
+            pairs.push(CodePair::new_i16(281, as_i16(is_column_heading_suppressed)));
+        }
+        if let Some(flow_direction) = table.flow_direction_override {
+            pairs.push(CodePair::new_i16(70, flow_direction as i16));
+        }
+        if let Some(margin) = table.horizontal_cell_margin_override {
+            pairs.push(CodePair::new_f64(40, margin));
+        }
+        if let Some(margin) = table.vertical_cell_margin_override {
+            pairs.push(CodePair::new_f64(41, margin));
+        }
+        true
+    }
+    fn add_modeler_geometry_code_pairs(
+        pairs: &mut Vec<CodePair>,
+        format_version_number: i16,
+        custom_data: &[String],
+        custom_data2: &[String],
+        u_isoline_count: i16,
+        v_isoline_count: i16,
+    ) {
+        pairs.push(CodePair::new_str(100, "AcDbModelerGeometry"));
+        pairs.push(CodePair::new_i16(70, format_version_number));
+        for data in custom_data {
+            pairs.push(CodePair::new_string(1, data));
+        }
+        for data in custom_data2 {
+            pairs.push(CodePair::new_string(3, data));
+        }
+        pairs.push(CodePair::new_str(100, "AcDbSurface"));
+        pairs.push(CodePair::new_i16(71, u_isoline_count));
+        pairs.push(CodePair::new_i16(72, v_isoline_count));
+    }
+    fn add_transformation_matrix_code_pairs(
+        pairs: &mut Vec<CodePair>,
+        code: i32,
+        matrix: &TransformationMatrix,
+    ) {
+        for value in matrix.values() {
+            pairs.push(CodePair::new_f64(code, value));
+        }
+    }
+    fn add_binary_data_code_pairs(pairs: &mut Vec<CodePair>, data: &[Vec<u8>]) {
+        let byte_count: usize = data.iter().map(|d| d.len()).sum();
+        pairs.push(CodePair::new_i32(90, byte_count as i32));
+        for chunk in data {
+            pairs.push(CodePair::new_binary(310, chunk.clone()));
+        }
+    }
+    fn add_post_code_pairs(
+        &self,
+        pairs: &mut Vec<CodePair>,
+        version: AcadVersion,
+        write_handles: bool,
+    ) {
+        match self.specific {
+            EntityType::Attribute(ref att) => self.add_code_pairs_attribute_m_text(
+                pairs,
+                att.m_text.clone(),
+                version,
+                write_handles,
+            ),
+            EntityType::AttributeDefinition(ref att) => self.add_code_pairs_attribute_m_text(
+                pairs,
+                att.m_text.clone(),
+                version,
+                write_handles,
+            ),
+            EntityType::Insert(ref ins) => {
+                for (a, att_handle) in &ins.__attributes_and_handles {
+                    let a = Entity {
+                        common: EntityCommon {
+                            handle: *att_handle,
+                            ..Default::default()
+                        },
+                        specific: EntityType::Attribute(a.clone()),
+                    };
+                    a.add_code_pairs(pairs, version, write_handles);
+                }
+                if ins.__attributes_and_handles.len() > 0 {
+                    Entity::add_code_pairs_seqend(pairs, &ins.__seqend_handle, write_handles);
+                }
+            }
+            EntityType::Polyline(ref poly) => {
+                for (v, vertex_handle) in &poly.__vertices_and_handles {
+                    let mut v = v.clone();
+                    v.set_is_3d_polyline_vertex(poly.is_3d_polyline());
+                    v.set_is_3d_polygon_mesh(poly.is_3d_polygon_mesh());
+                    let v = Entity {
+                        common: EntityCommon {
+                            handle: *vertex_handle,
+                            ..Default::default()
+                        },
+                        specific: EntityType::Vertex(v),
+                    };
+                    v.add_code_pairs(pairs, version, write_handles);
+                }
+                Entity::add_code_pairs_seqend(pairs, &poly.__seqend_handle, write_handles);
+            }
+            _ => (),
+        }
+    }
+    fn add_code_pairs_attribute_m_text(
+        &self,
+        pairs: &mut Vec<CodePair>,
+        m_text: MText,
+        version: AcadVersion,
+        write_handles: bool,
+    ) {
+        let m_text_common = EntityCommon {
+            handle: Handle::empty(), // TODO: set handle
+            __owner_handle: self.common.handle,
+            is_in_paper_space: self.common.is_in_paper_space,
+            layer: self.common.layer.clone(),
+            ..Default::default()
+        };
+        let m_text = Entity {
+            common: m_text_common,
+            specific: EntityType::MText(m_text),
+        };
+        m_text.add_code_pairs(pairs, version, write_handles);
+    }
+    fn add_code_pairs_seqend(pairs: &mut Vec<CodePair>, handle: &Handle, write_handles: bool) {
+        pairs.push(CodePair::new_str(0, "SEQEND"));
+        if write_handles {
+            pairs.push(CodePair::new_string(5, &handle.as_string()));
+        }
+    }
+}
+
+/// Returns the indices of the layer, `8`, and owner, `330`, code pairs of an unsupported entity.  The owner is the
+/// first `330` before the subclass markers, outside of `102` groups such as the reactors.
+fn unknown_entity_common_pair_indices(pairs: &[CodePair]) -> (Option<usize>, Option<usize>) {
+    let mut layer_index = None;
+    let mut owner_index = None;
+    let mut is_in_group = false;
+    let mut is_in_subclass = false;
+    for (i, pair) in pairs.iter().enumerate() {
+        match pair.code {
+            102 => {
+                is_in_group = match pair.value {
+                    CodePairValue::Str(ref s) => s.starts_with('{'),
+                    _ => false,
+                }
+            }
+            100 => is_in_subclass = true,
+            8 if !is_in_group && layer_index.is_none() => layer_index = Some(i),
+            330 if !is_in_group && !is_in_subclass && owner_index.is_none() => {
+                owner_index = Some(i)
+            }
+            _ => (),
+        }
+    }
+    (layer_index, owner_index)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::entities::*;
+    use crate::enums::*;
+    use crate::helper_functions::tests::*;
+    use crate::objects::*;
+    use crate::tables::*;
+    use crate::*;
+
+    fn read_entity(entity_type: &str, body: Vec<CodePair>) -> Entity {
+        let mut pairs = vec![CodePair::new_str(0, entity_type)];
+        for pair in body {
+            pairs.push(pair);
+        }
+        let drawing = from_section("ENTITIES", pairs);
+        let entities = drawing.entities().collect::<Vec<_>>();
+        assert_eq!(1, entities.len());
+        entities[0].clone()
+    }
+
+    /// Writes `drawing` as text and as binary, and calls `check` with each drawing read back and its only entity.
+    fn assert_round_trips<F>(drawing: &Drawing, check: F)
+    where
+        F: Fn(&Drawing, &Entity),
+    {
+        let bytes = to_binary(drawing);
+        let round_tripped = vec![
+            parse_drawing(&to_test_string(drawing)),
+            unwrap_drawing(Drawing::load(&mut bytes.as_slice())),
+        ];
+        for drawing in &round_tripped {
+            let entities = drawing.entities().collect::<Vec<_>>();
+            assert_eq!(1, entities.len());
+            check(drawing, entities[0]);
         }
-        if poly.polygon_mesh_m_vertex_count != 0 {
-            pairs.push(CodePair::new_i16(
-                71,
-                poly.polygon_mesh_m_vertex_count as i16,
-            ));
+    }
+
+    /// Asserts that `specific` is unchanged after being written as text and as binary for `version`.
+    fn assert_entity_round_trips(version: AcadVersion, specific: EntityType) {
+        let mut drawing = Drawing::new();
+        drawing.header.version = version;
+        drawing.add_entity(Entity::new(specific.clone()));
+        assert_round_trips(&drawing, |_, entity| assert_eq!(specific, entity.specific));
+    }
+
+    #[test]
+    fn read_empty_entities_section() {
+        let drawing = drawing_from_pairs(vec![
+            CodePair::new_str(0, "SECTION"),
+            CodePair::new_str(2, "ENTITIES"),
+            CodePair::new_str(0, "ENDSEC"),
+            CodePair::new_str(0, "EOF"),
+        ]);
+        let entities = drawing.entities();
+        assert_eq!(0, entities.count());
+    }
+
+    #[test]
+    fn read_unsupported_entity() {
+        let drawing = drawing_from_pairs(vec![
+            CodePair::new_str(0, "SECTION"),
+            CodePair::new_str(2, "ENTITIES"),
+            CodePair::new_str(0, "UNSUPPORTED_ENTITY"),
+            CodePair::new_str(1, "unsupported string"),
+            CodePair::new_str(0, "ENDSEC"),
+            CodePair::new_str(0, "EOF"),
+        ]);
+        let entities = drawing.entities();
+        assert_eq!(0, entities.count());
+    }
+
+    #[test]
+    fn read_unsupported_entity_as_unknown() {
+        let drawing = drawing_with_unknown_items_from_pairs(vec![
+            CodePair::new_str(0, "SECTION"),
+            CodePair::new_str(2, "ENTITIES"),
+            CodePair::new_str(0, "UNSUPPORTED_ENTITY"),
+            CodePair::new_str(5, "A1"),
+            CodePair::new_str(100, "AcDbEntity"),
+            CodePair::new_str(8, "some-layer"),
+            CodePair::new_str(1, "unsupported string"),
+            CodePair::new_str(1001, "APP"),
+            CodePair::new_i16(1070, 42),
+            CodePair::new_str(0, "LINE"),
+            CodePair::new_str(0, "ENDSEC"),
+            CodePair::new_str(0, "EOF"),
+        ]);
+        let entities = drawing.entities().collect::<Vec<_>>();
+        assert_eq!(2, entities.len());
+        assert_eq!(Handle(0xa1), entities[0].common.handle);
+        assert_eq!("some-layer", entities[0].common.layer);
+        match entities[0].specific {
+            EntityType::Unknown {
+                ref type_string,
+                ref pairs,
+            } => {
+                assert_eq!("UNSUPPORTED_ENTITY", type_string);
+                assert_eq!(
+                    &vec![
+                        CodePair::new_str(100, "AcDbEntity"),
+                        CodePair::new_str(8, "some-layer"),
+                        CodePair::new_str(1, "unsupported string"),
+                        CodePair::new_str(1001, "APP"),
+                        CodePair::new_i16(1070, 42),
+                    ],
+                    pairs
+                );
+            }
+            _ => panic!("expected an unknown entity"),
         }
-        if poly.polygon_mesh_n_vertex_count != 0 {
-            pairs.push(CodePair::new_i16(
-                72,
-                poly.polygon_mesh_n_vertex_count as i16,
-            ));
+        match entities[1].specific {
+            EntityType::Line(_) => (),
+            _ => panic!("expected a line"),
         }
-        if poly.smooth_surface_m_density != 0 {
-            pairs.push(CodePair::new_i16(73, poly.smooth_surface_m_density as i16));
+    }
+
+    #[test]
+    fn read_and_write_unknown_entity_common_values() {
+        let drawing = drawing_with_unknown_items_from_pairs(vec![
+            CodePair::new_str(0, "SECTION"),
+            CodePair::new_str(2, "ENTITIES"),
+            CodePair::new_str(0, "UNSUPPORTED_ENTITY"),
+            CodePair::new_str(102, "{ACAD_REACTORS"),
+            CodePair::new_str(330, "B1"),
+            CodePair::new_str(102, "}"),
+            CodePair::new_str(330, "A2"),
+            CodePair::new_str(100, "AcDbEntity"),
+            CodePair::new_str(8, "some-layer"),
+            CodePair::new_str(100, "AcDbUnsupported"),
+            CodePair::new_str(330, "C3"),
+            CodePair::new_str(8, "not-the-layer"),
+            CodePair::new_str(0, "ENDSEC"),
+            CodePair::new_str(0, "EOF"),
+        ]);
+        let mut entities = drawing.entities().cloned().collect::<Vec<_>>();
+        assert_eq!(1, entities.len());
+        assert_eq!("some-layer", entities[0].common.layer);
+        assert_eq!(Handle(0xa2), entities[0].common.__owner_handle);
+
+        let mut drawing = Drawing::new();
+        entities[0].common.layer = String::from("other-layer");
+        entities[0].common.__owner_handle = Handle(0xa3);
+        drawing.add_entity(entities.remove(0));
+        assert_contains_pairs(
+            &drawing,
+            vec![
+                CodePair::new_str(102, "{ACAD_REACTORS"),
+                CodePair::new_str(330, "B1"),
+                CodePair::new_str(102, "}"),
+                CodePair::new_str(330, "A3"),
+                CodePair::new_str(100, "AcDbEntity"),
+                CodePair::new_str(8, "other-layer"),
+                CodePair::new_str(100, "AcDbUnsupported"),
+                CodePair::new_str(330, "C3"),
+                CodePair::new_str(8, "not-the-layer"),
+            ],
+        );
+    }
+
+    #[test]
+    fn write_unknown_entity_verbatim() {
+        let mut drawing = Drawing::new();
+        drawing.add_entity(Entity::new(EntityType::Unknown {
+            type_string: String::from("UNSUPPORTED_ENTITY"),
+            pairs: vec![
+                CodePair::new_str(100, "AcDbEntity"),
+                CodePair::new_str(1, "unsupported string"),
+                CodePair::new_f64(40, 1.5),
+            ],
+        }));
+        let handle = drawing.entities().next().unwrap().common.handle;
+        assert_contains_pairs(
+            &drawing,
+            vec![
+                CodePair::new_str(0, "UNSUPPORTED_ENTITY"),
+                CodePair::new_string(5, &handle.as_string()),
+                CodePair::new_str(100, "AcDbEntity"),
+                CodePair::new_str(1, "unsupported string"),
+                CodePair::new_f64(40, 1.5),
+                CodePair::new_str(0, "ENDSEC"),
+            ],
+        );
+    }
+
+    #[test]
+    fn round_trip_unknown_entity_in_block() {
+        let mut drawing = Drawing::new();
+        let mut block = Block {
+            name: String::from("some-block"),
+            ..Default::default()
+        };
+        block.entities.push(Entity::new(EntityType::Unknown {
+            type_string: String::from("UNSUPPORTED_ENTITY"),
+            pairs: vec![CodePair::new_str(1, "unsupported string")],
+        }));
+        drawing.add_block(block);
+
+        let round_tripped = drawing_with_unknown_items_from_pairs(drawing.code_pairs().unwrap());
+        let block = round_tripped
+            .blocks()
+            .find(|b| b.name == "some-block")
+            .unwrap();
+        assert_eq!(1, block.entities.len());
+        assert_eq!(
+            EntityType::Unknown {
+                type_string: String::from("UNSUPPORTED_ENTITY"),
+                pairs: vec![CodePair::new_str(1, "unsupported string")],
+            },
+            block.entities[0].specific
+        );
+    }
+
+    #[test]
+    fn read_unsupported_entity_between_supported_entities() {
+        let drawing = drawing_from_pairs(vec![
+            CodePair::new_str(0, "SECTION"),
+            CodePair::new_str(2, "ENTITIES"),
+            CodePair::new_str(0, "LINE"),
+            CodePair::new_str(0, "UNSUPPORTED_ENTITY"),
+            CodePair::new_str(1, "unsupported string"),
+            CodePair::new_str(0, "CIRCLE"),
+            CodePair::new_str(0, "ENDSEC"),
+            CodePair::new_str(0, "EOF"),
+        ]);
+        let entities = drawing.entities().collect::<Vec<_>>();
+        assert_eq!(2, entities.len());
+        match entities[0].specific {
+            EntityType::Line(_) => (),
+            _ => panic!("expected a line"),
         }
-        if poly.smooth_surface_n_density != 0 {
-            pairs.push(CodePair::new_i16(74, poly.smooth_surface_n_density as i16));
+        match entities[1].specific {
+            EntityType::Circle(_) => (),
+            _ => panic!("expected a circle"),
         }
-        if poly.surface_type != PolylineCurvedAndSmoothSurfaceType::None {
-            pairs.push(CodePair::new_i16(75, poly.surface_type as i16));
+    }
+
+    #[test]
+    fn read_entity_with_no_values() {
+        let drawing = drawing_from_pairs(vec![
+            CodePair::new_str(0, "SECTION"),
+            CodePair::new_str(2, "ENTITIES"),
+            CodePair::new_str(0, "LINE"),
+            CodePair::new_str(0, "ENDSEC"),
+            CodePair::new_str(0, "EOF"),
+        ]);
+        let entities = drawing.entities().collect::<Vec<_>>();
+        assert_eq!(1, entities.len());
+        match entities[0].specific {
+            EntityType::Line(_) => (),
+            _ => panic!("expected a line"),
         }
-        if poly.normal != Vector::z_axis() {
-            pairs.push(CodePair::new_f64(210, poly.normal.x));
-            pairs.push(CodePair::new_f64(220, poly.normal.y));
-            pairs.push(CodePair::new_f64(230, poly.normal.z));
+    }
+
+    #[test]
+    fn read_common_entity_fields() {
+        let ent = read_entity("LINE", vec![CodePair::new_str(8, "layer")]);
+        assert_eq!("layer", ent.common.layer);
+    }
+
+    #[test]
+    fn read_line() {
+        let ent = read_entity(
+            "LINE",
+            vec![
+                CodePair::new_f64(10, 1.1), // p1
+                CodePair::new_f64(20, 2.2),
+                CodePair::new_f64(30, 3.3),
+                CodePair::new_f64(11, 4.4), // p2
+                CodePair::new_f64(21, 5.5),
+                CodePair::new_f64(31, 6.6),
+            ],
+        );
+        match ent.specific {
+            EntityType::Line(ref line) => {
+                assert_eq!(Point::new(1.1, 2.2, 3.3), line.p1);
+                assert_eq!(Point::new(4.4, 5.5, 6.6), line.p2);
+            }
+            _ => panic!("expected a line"),
         }
-        true
     }
-    fn add_custom_code_pairs_vertex(
-        pairs: &mut Vec<CodePair>,
-        v: &Vertex,
-        version: AcadVersion,
-    ) -> bool {
-        pairs.push(CodePair::new_str(100, "AcDbVertex"));
-        let subclass_marker = if v.is_3d_polyline_vertex() || v.is_3d_polygon_mesh() {
-            "AcDb3dPolylineVertex"
-        } else {
-            "AcDb2dVertex"
-        };
-        if version >= AcadVersion::R13 {
-            pairs.push(CodePair::new_str(100, subclass_marker));
-        }
-        pairs.push(CodePair::new_f64(10, v.location.x));
-        pairs.push(CodePair::new_f64(20, v.location.y));
-        pairs.push(CodePair::new_f64(30, v.location.z));
-        if v.starting_width != 0.0 {
-            pairs.push(CodePair::new_f64(40, v.starting_width));
-        }
-        if v.ending_width != 0.0 {
-            pairs.push(CodePair::new_f64(41, v.ending_width));
-        }
-        if v.bulge != 0.0 {
-            pairs.push(CodePair::new_f64(42, v.bulge));
-        }
-        pairs.push(CodePair::new_i16(70, v.flags as i16));
-        pairs.push(CodePair::new_f64(50, v.curve_fit_tangent_direction));
-        if version >= AcadVersion::R13 {
-            if v.polyface_mesh_vertex_index1 != 0 {
-                pairs.push(CodePair::new_i16(71, v.polyface_mesh_vertex_index1 as i16));
-            }
-            if v.polyface_mesh_vertex_index2 != 0 {
-                pairs.push(CodePair::new_i16(72, v.polyface_mesh_vertex_index2 as i16));
-            }
-            if v.polyface_mesh_vertex_index3 != 0 {
-                pairs.push(CodePair::new_i16(73, v.polyface_mesh_vertex_index3 as i16));
+
+    #[test]
+    fn write_common_entity_fields_r12() {
+        let mut drawing = Drawing::new();
+        drawing.header.version = AcadVersion::R12;
+        let mut ent = Entity {
+            common: Default::default(),
+            specific: EntityType::Line(Default::default()),
+        };
+        ent.common.layer = "some-layer".to_owned();
+        drawing.add_entity(ent);
+        assert_contains_pairs(
+            &drawing,
+            vec![
+                CodePair::new_str(0, "LINE"),
+                CodePair::new_str(5, "10"),
+                CodePair::new_str(8, "some-layer"),
+                CodePair::new_f64(10, 0.0),
+            ],
+        );
+    }
+
+    #[test]
+    fn write_common_entity_fields_r13() {
+        let mut drawing = Drawing::new();
+        drawing.header.version = AcadVersion::R13;
+        let mut ent = Entity {
+            common: Default::default(),
+            specific: EntityType::Line(Default::default()),
+        };
+        ent.common.layer = "some-layer".to_owned();
+        drawing.add_entity(ent);
+        assert_contains_pairs(
+            &drawing,
+            vec![
+                CodePair::new_str(0, "LINE"),
+                CodePair::new_str(5, "10"),
+                CodePair::new_str(100, "AcDbEntity"),
+                CodePair::new_str(8, "some-layer"),
+                CodePair::new_str(100, "AcDbLine"),
+                CodePair::new_f64(10, 0.0),
+            ],
+        );
+    }
+
+    #[test]
+    fn write_specific_entity_fields() {
+        let mut drawing = Drawing::new();
+        drawing.header.version = AcadVersion::R13;
+        let line = Line {
+            p1: Point::new(1.1, 2.2, 3.3),
+            p2: Point::new(4.4, 5.5, 6.6),
+            ..Default::default()
+        };
+        drawing.add_entity(Entity::new(EntityType::Line(line)));
+        assert_contains_pairs(
+            &drawing,
+            vec![
+                CodePair::new_str(100, "AcDbLine"),
+                CodePair::new_f64(10, 1.1),
+                CodePair::new_f64(20, 2.2),
+                CodePair::new_f64(30, 3.3),
+                CodePair::new_f64(11, 4.4),
+                CodePair::new_f64(21, 5.5),
+                CodePair::new_f64(31, 6.6),
+            ],
+        );
+    }
+
+    #[test]
+    fn read_multiple_entities() {
+        let drawing = from_section(
+            "ENTITIES",
+            vec![
+                CodePair::new_str(0, "CIRCLE"),
+                CodePair::new_f64(10, 1.1), // center
+                CodePair::new_f64(20, 2.2),
+                CodePair::new_f64(30, 3.3),
+                CodePair::new_f64(40, 4.4), // radius
+                CodePair::new_str(0, "LINE"),
+                CodePair::new_f64(10, 5.5), // p1
+                CodePair::new_f64(20, 6.6),
+                CodePair::new_f64(30, 7.7),
+                CodePair::new_f64(11, 8.8), // p2
+                CodePair::new_f64(21, 9.9),
+                CodePair::new_f64(31, 10.1),
+            ],
+        );
+        let entities = drawing.entities().collect::<Vec<_>>();
+        assert_eq!(2, entities.len());
+
+        // verify circle
+        match entities[0].specific {
+            EntityType::Circle(ref circle) => {
+                assert_eq!(Point::new(1.1, 2.2, 3.3), circle.center);
+                assert!(approx_eq!(f64, 4.4, circle.radius));
             }
-            if v.polyface_mesh_vertex_index4 != 0 {
-                pairs.push(CodePair::new_i16(74, v.polyface_mesh_vertex_index4 as i16));
+            _ => panic!("expected a line"),
+        }
+
+        // verify line
+        match entities[1].specific {
+            EntityType::Line(ref line) => {
+                assert_eq!(Point::new(5.5, 6.6, 7.7), line.p1);
+                assert_eq!(Point::new(8.8, 9.9, 10.1), line.p2);
             }
+            _ => panic!("expected a line"),
         }
-        if version >= AcadVersion::R2010 {
-            pairs.push(CodePair::new_i32(91, v.identifier));
+    }
+
+    #[test]
+    fn read_field_with_multiples_common() {
+        let ent = read_entity(
+            "LINE",
+            vec![
+                CodePair::new_binary(310, vec![0x01, 0x02]),
+                CodePair::new_binary(310, vec![0x03, 0x04]),
+            ],
+        );
+        assert_eq!(
+            vec![vec![0x01, 0x02], vec![0x03, 0x04]],
+            ent.common.preview_image_data
+        );
+    }
+
+    #[test]
+    fn write_field_with_multiples_common() {
+        let mut drawing = Drawing::new();
+        drawing.header.version = AcadVersion::R2000;
+        drawing.add_entity(Entity {
+            common: EntityCommon {
+                preview_image_data: vec![vec![0x01, 0x02], vec![0x03, 0x04]],
+                ..Default::default()
+            },
+            specific: EntityType::Line(Default::default()),
+        });
+        assert_contains_pairs(
+            &drawing,
+            vec![
+                CodePair::new_binary(310, vec![0x01, 0x02]),
+                CodePair::new_binary(310, vec![0x03, 0x04]),
+            ],
+        );
+    }
+
+    #[test]
+    fn read_field_with_multiples_specific() {
+        let ent = read_entity(
+            "3DSOLID",
+            vec![
+                CodePair::new_str(1, "one-1"),
+                CodePair::new_str(1, "one-2"),
+                CodePair::new_str(3, "three-1"),
+                CodePair::new_str(3, "three-2"),
+            ],
+        );
+        match ent.specific {
+            EntityType::Solid3D(ref solid3d) => {
+                assert_eq!(vec!["one-1", "one-2"], solid3d.custom_data);
+                assert_eq!(vec!["three-1", "three-2"], solid3d.custom_data2);
+            }
+            _ => panic!("expected a 3DSOLID"),
         }
-        true
     }
-    fn add_post_code_pairs(
-        &self,
-        pairs: &mut Vec<CodePair>,
-        version: AcadVersion,
-        write_handles: bool,
-    ) {
-        match self.specific {
-            EntityType::Attribute(ref att) => self.add_code_pairs_attribute_m_text(
-                pairs,
-                att.m_text.clone(),
-                version,
-                write_handles,
-            ),
-            EntityType::AttributeDefinition(ref att) => self.add_code_pairs_attribute_m_text(
-                pairs,
-                att.m_text.clone(),
-                version,
-                write_handles,
-            ),
-            EntityType::Insert(ref ins) => {
-                for (a, att_handle) in &ins.__attributes_and_handles {
-                    let a = Entity {
-                        common: EntityCommon {
-                            handle: *att_handle,
-                            ..Default::default()
-                        },
-                        specific: EntityType::Attribute(a.clone()),
-                    };
-                    a.add_code_pairs(pairs, version, write_handles);
-                }
-                if ins.__attributes_and_handles.len() > 0 {
-                    Entity::add_code_pairs_seqend(pairs, &ins.__seqend_handle, write_handles);
-                }
+
+    #[test]
+    fn write_field_with_multiples_specific() {
+        let mut drawing = Drawing::new();
+        drawing.header.version = AcadVersion::R13; // 3DSOLID is only supported on R13+
+        drawing.add_entity(Entity {
+            common: Default::default(),
+            specific: EntityType::Solid3D(Solid3D {
+                custom_data: vec![String::from("one-1"), String::from("one-2")],
+                custom_data2: vec![String::from("three-1"), String::from("three-2")],
+                ..Default::default()
+            }),
+        });
+        assert_contains_pairs(
+            &drawing,
+            vec![
+                CodePair::new_str(1, "one-1"),
+                CodePair::new_str(1, "one-2"),
+                CodePair::new_str(3, "three-1"),
+                CodePair::new_str(3, "three-2"),
+            ],
+        );
+    }
+
+    #[test]
+    fn read_entity_with_post_parse() {
+        let ent = read_entity(
+            "IMAGE",
+            vec![
+                CodePair::new_f64(14, 1.1), // clipping vertices[0]
+                CodePair::new_f64(24, 2.2),
+                CodePair::new_f64(14, 3.3), // clipping vertices[1]
+                CodePair::new_f64(24, 4.4),
+                CodePair::new_f64(14, 5.5), // clipping vertices[2]
+                CodePair::new_f64(24, 6.6),
+            ],
+        );
+        match ent.specific {
+            EntityType::Image(ref image) => {
+                assert_eq!(3, image.clipping_vertices.len());
+                assert_eq!(Point::new(1.1, 2.2, 0.0), image.clipping_vertices[0]);
+                assert_eq!(Point::new(3.3, 4.4, 0.0), image.clipping_vertices[1]);
+                assert_eq!(Point::new(5.5, 6.6, 0.0), image.clipping_vertices[2]);
             }
-            EntityType::Polyline(ref poly) => {
-                for (v, vertex_handle) in &poly.__vertices_and_handles {
-                    let mut v = v.clone();
-                    v.set_is_3d_polyline_vertex(poly.is_3d_polyline());
-                    v.set_is_3d_polygon_mesh(poly.is_3d_polygon_mesh());
-                    let v = Entity {
-                        common: EntityCommon {
-                            handle: *vertex_handle,
-                            ..Default::default()
-                        },
-                        specific: EntityType::Vertex(v),
-                    };
-                    v.add_code_pairs(pairs, version, write_handles);
-                }
-                Entity::add_code_pairs_seqend(pairs, &poly.__seqend_handle, write_handles);
+            _ => panic!("expected an IMAGE"),
+        }
+    }
+
+    #[test]
+    fn write_entity_with_write_order() {
+        let mut drawing = Drawing::new();
+        drawing.header.version = AcadVersion::R14; // IMAGE is only supported on R14+
+        drawing.add_entity(Entity {
+            common: Default::default(),
+            specific: EntityType::Image(Image {
+                clipping_vertices: vec![
+                    Point::new(1.1, 2.2, 0.0),
+                    Point::new(3.3, 4.4, 0.0),
+                    Point::new(5.5, 6.6, 0.0),
+                ],
+                ..Default::default()
+            }),
+        });
+        assert_contains_pairs(
+            &drawing,
+            vec![
+                CodePair::new_i32(91, 3),
+                CodePair::new_f64(14, 1.1),
+                CodePair::new_f64(24, 2.2),
+                CodePair::new_f64(14, 3.3),
+                CodePair::new_f64(24, 4.4),
+                CodePair::new_f64(14, 5.5),
+                CodePair::new_f64(24, 6.6),
+            ],
+        );
+    }
+
+    #[test]
+    fn read_entity_with_custom_reader_mtext() {
+        let ent = read_entity(
+            "MTEXT",
+            vec![
+                CodePair::new_f64(50, 1.1),  // rotation angle
+                CodePair::new_i16(75, 7),    // column type
+                CodePair::new_f64(50, 3.0),  // column count
+                CodePair::new_f64(50, 10.0), // column values
+                CodePair::new_f64(50, 20.0),
+                CodePair::new_f64(50, 30.0),
+            ],
+        );
+        match ent.specific {
+            EntityType::MText(ref mtext) => {
+                assert!(approx_eq!(f64, 1.1, mtext.rotation_angle));
+                assert_eq!(7, mtext.column_type);
+                assert_eq!(3, mtext.column_count);
+                assert_eq!(3, mtext.column_heights.len());
+                assert!(approx_eq!(f64, 10.0, mtext.column_heights[0]));
+                assert!(approx_eq!(f64, 20.0, mtext.column_heights[1]));
+                assert!(approx_eq!(f64, 30.0, mtext.column_heights[2]));
             }
-            _ => (),
+            _ => panic!("expected an MTEXT"),
         }
     }
-    fn add_code_pairs_attribute_m_text(
-        &self,
-        pairs: &mut Vec<CodePair>,
-        m_text: MText,
-        version: AcadVersion,
-        write_handles: bool,
-    ) {
-        let m_text_common = EntityCommon {
-            handle: Handle::empty(), // TODO: set handle
-            __owner_handle: self.common.handle,
-            is_in_paper_space: self.common.is_in_paper_space,
-            layer: self.common.layer.clone(),
+
+    #[test]
+    fn write_long_mtext_in_chunks() {
+        let mut drawing = Drawing::new();
+        drawing.header.version = AcadVersion::R13; // MTEXT is only written on R13+
+        let text = format!("{}{}{}", "a".repeat(250), "b".repeat(250), "c".repeat(10));
+        let mtext = MText {
+            text: text.clone(),
             ..Default::default()
         };
-        let m_text = Entity {
-            common: m_text_common,
-            specific: EntityType::MText(m_text),
+        drawing.add_entity(Entity::new(EntityType::MText(mtext)));
+        assert_contains_pairs(
+            &drawing,
+            vec![
+                CodePair::new_string(3, &"a".repeat(250)),
+                CodePair::new_string(3, &"b".repeat(250)),
+                CodePair::new_string(1, &"c".repeat(10)),
+            ],
+        );
+
+        let drawing = drawing_from_pairs(drawing.code_pairs().unwrap());
+        let entities = drawing.entities().collect::<Vec<_>>();
+        match entities[0].specific {
+            EntityType::MText(ref mtext) => {
+                assert_eq!(2, mtext.extended_text.len());
+                assert_eq!(text, mtext.full_text());
+            }
+            _ => panic!("expected an MTEXT"),
+        }
+    }
+
+    #[test]
+    fn write_short_mtext_chunks_as_is() {
+        let mut drawing = Drawing::new();
+        drawing.header.version = AcadVersion::R13; // MTEXT is only written on R13+
+        let mtext = MText {
+            extended_text: vec![String::from("abc")],
+            text: String::from("def"),
+            ..Default::default()
         };
-        m_text.add_code_pairs(pairs, version, write_handles);
+        drawing.add_entity(Entity::new(EntityType::MText(mtext)));
+        assert_contains_pairs(
+            &drawing,
+            vec![CodePair::new_str(3, "abc"), CodePair::new_str(1, "def")],
+        );
     }
-    fn add_code_pairs_seqend(pairs: &mut Vec<CodePair>, handle: &Handle, write_handles: bool) {
-        pairs.push(CodePair::new_str(0, "SEQEND"));
-        if write_handles {
-            pairs.push(CodePair::new_string(5, &handle.as_string()));
+
+    #[test]
+    fn mtext_formatted_text() {
+        let mut mtext = MText::default();
+        mtext.set_full_text(&format!("{{\\C1;{}}}\\Pline 2", "x".repeat(300)));
+        assert_eq!(1, mtext.extended_text.len());
+        assert_eq!(250, mtext.extended_text[0].chars().count());
+        assert_eq!(format!("{}\nline 2", "x".repeat(300)), mtext.plain_text());
+
+        let mut document = mtext.formatted_text();
+        assert_eq!(2, document.paragraphs.len());
+        assert_eq!(
+            Some(MTextColor::Indexed(Color::from_index(1))),
+            document.paragraphs[0].runs[0].format.color
+        );
+        document.paragraphs.pop();
+        mtext.set_formatted_text(&document);
+        assert_eq!(format!("{{\\C1;{}}}", "x".repeat(300)), mtext.full_text());
+    }
+
+    #[test]
+    fn read_entity_after_entity_with_custom_reader() {
+        let drawing = from_section(
+            "ENTITIES",
+            vec![
+                CodePair::new_str(0, "MTEXT"), // has a custom reader
+                CodePair::new_str(0, "LINE"),  // uses the auto-generated reader
+            ],
+        );
+        let entities = drawing.entities().collect::<Vec<_>>();
+        assert_eq!(2, entities.len());
+        match entities[0].specific {
+            EntityType::MText(_) => {}
+            _ => panic!("expected an mtext"),
+        }
+        match entities[1].specific {
+            EntityType::Line(_) => {}
+            _ => panic!("expected a line"),
         }
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use crate::entities::*;
-    use crate::enums::*;
-    use crate::helper_functions::tests::*;
-    use crate::objects::*;
-    use crate::*;
+    #[test]
+    fn read_entity_with_flags() {
+        let ent = read_entity("IMAGE", vec![CodePair::new_i16(70, 5)]);
+        match ent.specific {
+            EntityType::Image(ref image) => {
+                assert!(image.show_image());
+                assert!(image.use_clipping_boundary());
+            }
+            _ => panic!("expected an IMAGE"),
+        }
+    }
 
-    fn read_entity(entity_type: &str, body: Vec<CodePair>) -> Entity {
-        let mut pairs = vec![CodePair::new_str(0, entity_type)];
-        for pair in body {
-            pairs.push(pair);
+    #[test]
+    fn write_entity_with_flags() {
+        let mut drawing = Drawing::new();
+        drawing.header.version = AcadVersion::R14; // IMAGE is only supported on R14+
+        let mut image = Image::default();
+        assert_eq!(0, image.display_options_flags);
+        image.set_show_image(true);
+        image.set_use_clipping_boundary(true);
+        drawing.add_entity(Entity {
+            common: Default::default(),
+            specific: EntityType::Image(image),
+        });
+        assert_contains_pairs(
+            &drawing,
+            vec![
+                CodePair::new_i16(70, 5),  // flags
+                CodePair::new_i16(280, 1), // sentinels to make sure we're not reading a header value
+                CodePair::new_i16(281, 50),
+            ],
+        );
+    }
+
+    #[test]
+    fn read_entity_with_handle_and_pointer() {
+        let ent = read_entity(
+            "3DSOLID",
+            vec![
+                CodePair::new_str(5, "A1"),   // handle
+                CodePair::new_str(330, "A2"), // owner handle
+                CodePair::new_str(350, "A3"), // history_object pointer
+            ],
+        );
+        assert_eq!(Handle(0xa1), ent.common.handle);
+        assert_eq!(Handle(0xa2), ent.common.__owner_handle);
+        match ent.specific {
+            EntityType::Solid3D(ref solid) => {
+                assert_eq!(Handle(0xa3), solid.__history_object_handle)
+            }
+            _ => panic!("expected a 3DSOLID entity"),
+        }
+    }
+
+    #[test]
+    fn write_entity_with_handle_and_pointer() {
+        let mut drawing = Drawing::new();
+        drawing.header.version = AcadVersion::R2000;
+        drawing.add_entity(Entity {
+            common: EntityCommon {
+                __owner_handle: Handle(0xa2),
+                ..Default::default()
+            },
+            specific: EntityType::Line(Default::default()),
+        });
+        assert_contains_pairs(
+            &drawing,
+            vec![
+                CodePair::new_str(0, "LINE"),
+                CodePair::new_str(5, "10"),
+                CodePair::new_str(330, "A2"),
+            ],
+        );
+    }
+
+    #[test]
+    fn write_version_specific_entity() {
+        let mut drawing = Drawing::new();
+        drawing.add_entity(Entity {
+            common: Default::default(),
+            specific: EntityType::Solid3D(Default::default()),
+        });
+
+        // 3DSOLID not supported in R12 and below
+        drawing.header.version = AcadVersion::R12;
+        assert_contains_pairs(
+            &drawing,
+            vec![
+                CodePair::new_str(0, "SECTION"),
+                CodePair::new_str(2, "ENTITIES"),
+                CodePair::new_str(0, "ENDSEC"),
+            ],
+        );
+
+        // but it is in R13 and above
+        drawing.header.version = AcadVersion::R13;
+        assert_contains_pairs(
+            &drawing,
+            vec![
+                CodePair::new_str(0, "SECTION"),
+                CodePair::new_str(2, "ENTITIES"),
+                CodePair::new_str(0, "3DSOLID"),
+            ],
+        );
+    }
+
+    #[test]
+    fn read_polyline() {
+        let drawing = from_section(
+            "ENTITIES",
+            vec![
+                CodePair::new_str(0, "POLYLINE"), // polyline sentinel
+                CodePair::new_str(0, "VERTEX"),   // vertex 1
+                CodePair::new_f64(10, 1.1),
+                CodePair::new_f64(20, 2.1),
+                CodePair::new_f64(30, 3.1),
+                CodePair::new_str(0, "VERTEX"), // vertex 2
+                CodePair::new_f64(10, 1.2),
+                CodePair::new_f64(20, 2.2),
+                CodePair::new_f64(30, 3.2),
+                CodePair::new_str(0, "VERTEX"), // vertex 3
+                CodePair::new_f64(10, 1.3),
+                CodePair::new_f64(20, 2.3),
+                CodePair::new_f64(30, 3.3),
+                CodePair::new_str(0, "SEQEND"), // end sequence
+            ],
+        );
+        let entities = drawing.entities().collect::<Vec<_>>();
+        assert_eq!(1, entities.len());
+        match entities[0].specific {
+            EntityType::Polyline(ref poly) => {
+                assert_eq!(
+                    vec![
+                        Vertex {
+                            location: Point::new(1.1, 2.1, 3.1),
+                            ..Default::default()
+                        },
+                        Vertex {
+                            location: Point::new(1.2, 2.2, 3.2),
+                            ..Default::default()
+                        },
+                        Vertex {
+                            location: Point::new(1.3, 2.3, 3.3),
+                            ..Default::default()
+                        },
+                    ],
+                    poly.vertices().cloned().collect::<Vec<_>>()
+                );
+            }
+            _ => panic!("expected a POLYLINE"),
         }
-        let drawing = from_section("ENTITIES", pairs);
-        let entities = drawing.entities().collect::<Vec<_>>();
-        assert_eq!(1, entities.len());
-        entities[0].clone()
-    }
-
-    #[test]
-    fn read_empty_entities_section() {
-        let drawing = drawing_from_pairs(vec![
-            CodePair::new_str(0, "SECTION"),
-            CodePair::new_str(2, "ENTITIES"),
-            CodePair::new_str(0, "ENDSEC"),
-            CodePair::new_str(0, "EOF"),
-        ]);
-        let entities = drawing.entities();
-        assert_eq!(0, entities.count());
-    }
-
-    #[test]
-    fn read_unsupported_entity() {
-        let drawing = drawing_from_pairs(vec![
-            CodePair::new_str(0, "SECTION"),
-            CodePair::new_str(2, "ENTITIES"),
-            CodePair::new_str(0, "UNSUPPORTED_ENTITY"),
-            CodePair::new_str(1, "unsupported string"),
-            CodePair::new_str(0, "ENDSEC"),
-            CodePair::new_str(0, "EOF"),
-        ]);
-        let entities = drawing.entities();
-        assert_eq!(0, entities.count());
     }
 
     #[test]
-    fn read_unsupported_entity_between_supported_entities() {
-        let drawing = drawing_from_pairs(vec![
-            CodePair::new_str(0, "SECTION"),
-            CodePair::new_str(2, "ENTITIES"),
-            CodePair::new_str(0, "LINE"),
-            CodePair::new_str(0, "UNSUPPORTED_ENTITY"),
-            CodePair::new_str(1, "unsupported string"),
-            CodePair::new_str(0, "CIRCLE"),
-            CodePair::new_str(0, "ENDSEC"),
-            CodePair::new_str(0, "EOF"),
-        ]);
+    fn read_polyline_without_seqend() {
+        let drawing = from_section(
+            "ENTITIES",
+            vec![
+                CodePair::new_str(0, "POLYLINE"), // polyline sentinel
+                CodePair::new_str(0, "VERTEX"),   // vertex 1
+                CodePair::new_f64(10, 1.1),
+                CodePair::new_f64(20, 2.1),
+                CodePair::new_f64(30, 3.1),
+                CodePair::new_str(0, "VERTEX"), // vertex 2
+                CodePair::new_f64(10, 1.2),
+                CodePair::new_f64(20, 2.2),
+                CodePair::new_f64(30, 3.2),
+                CodePair::new_str(0, "VERTEX"), // vertex 3
+                CodePair::new_f64(10, 1.3),
+                CodePair::new_f64(20, 2.3),
+                CodePair::new_f64(30, 3.3),
+                // no end sequence
+            ],
+        );
         let entities = drawing.entities().collect::<Vec<_>>();
-        assert_eq!(2, entities.len());
+        assert_eq!(1, entities.len());
         match entities[0].specific {
-            EntityType::Line(_) => (),
-            _ => panic!("expected a line"),
-        }
-        match entities[1].specific {
-            EntityType::Circle(_) => (),
-            _ => panic!("expected a circle"),
+            EntityType::Polyline(ref poly) => {
+                assert_eq!(
+                    vec![
+                        Vertex {
+                            location: Point::new(1.1, 2.1, 3.1),
+                            ..Default::default()
+                        },
+                        Vertex {
+                            location: Point::new(1.2, 2.2, 3.2),
+                            ..Default::default()
+                        },
+                        Vertex {
+                            location: Point::new(1.3, 2.3, 3.3),
+                            ..Default::default()
+                        },
+                    ],
+                    poly.vertices().cloned().collect::<Vec<_>>()
+                );
+            }
+            _ => panic!("expected a POLYLINE"),
         }
     }
 
     #[test]
-    fn read_entity_with_no_values() {
-        let drawing = drawing_from_pairs(vec![
-            CodePair::new_str(0, "SECTION"),
-            CodePair::new_str(2, "ENTITIES"),
-            CodePair::new_str(0, "LINE"),
-            CodePair::new_str(0, "ENDSEC"),
-            CodePair::new_str(0, "EOF"),
-        ]);
+    fn read_empty_polyline() {
+        let drawing = from_section(
+            "ENTITIES",
+            vec![
+                CodePair::new_str(0, "POLYLINE"),
+                CodePair::new_str(0, "SEQEND"),
+            ],
+        );
         let entities = drawing.entities().collect::<Vec<_>>();
         assert_eq!(1, entities.len());
         match entities[0].specific {
-            EntityType::Line(_) => (),
-            _ => panic!("expected a line"),
+            EntityType::Polyline(ref poly) => assert_eq!(0, poly.vertices().count()),
+            _ => panic!("expected a POLYLINE"),
         }
     }
 
     #[test]
-    fn read_common_entity_fields() {
-        let ent = read_entity("LINE", vec![CodePair::new_str(8, "layer")]);
-        assert_eq!("layer", ent.common.layer);
+    fn read_empty_polyline_without_seqend() {
+        let drawing = from_section("ENTITIES", vec![CodePair::new_str(0, "POLYLINE")]);
+        let entities = drawing.entities().collect::<Vec<_>>();
+        assert_eq!(1, entities.len());
+        match entities[0].specific {
+            EntityType::Polyline(ref poly) => assert_eq!(0, poly.vertices().count()),
+            _ => panic!("expected a POLYLINE"),
+        }
     }
 
     #[test]
-    fn read_line() {
-        let ent = read_entity(
-            "LINE",
+    fn read_polyline_with_trailing_entity() {
+        let drawing = from_section(
+            "ENTITIES",
             vec![
-                CodePair::new_f64(10, 1.1), // p1
+                CodePair::new_str(0, "POLYLINE"), // polyline sentinel
+                CodePair::new_str(0, "VERTEX"),   // vertex 1
+                CodePair::new_f64(10, 1.1),
+                CodePair::new_f64(20, 2.1),
+                CodePair::new_f64(30, 3.1),
+                CodePair::new_str(0, "VERTEX"), // vertex 2
+                CodePair::new_f64(10, 1.2),
                 CodePair::new_f64(20, 2.2),
+                CodePair::new_f64(30, 3.2),
+                CodePair::new_str(0, "VERTEX"), // vertex 3
+                CodePair::new_f64(10, 1.3),
+                CodePair::new_f64(20, 2.3),
                 CodePair::new_f64(30, 3.3),
-                CodePair::new_f64(11, 4.4), // p2
-                CodePair::new_f64(21, 5.5),
-                CodePair::new_f64(31, 6.6),
+                CodePair::new_str(0, "SEQEND"), // end sequence
+                CodePair::new_str(0, "LINE"),   // trailing entity
             ],
         );
-        match ent.specific {
-            EntityType::Line(ref line) => {
-                assert_eq!(Point::new(1.1, 2.2, 3.3), line.p1);
-                assert_eq!(Point::new(4.4, 5.5, 6.6), line.p2);
+        let entities = drawing.entities().collect::<Vec<_>>();
+        assert_eq!(2, entities.len());
+        match entities[0].specific {
+            EntityType::Polyline(ref poly) => {
+                assert_eq!(
+                    vec![
+                        Vertex {
+                            location: Point::new(1.1, 2.1, 3.1),
+                            ..Default::default()
+                        },
+                        Vertex {
+                            location: Point::new(1.2, 2.2, 3.2),
+                            ..Default::default()
+                        },
+                        Vertex {
+                            location: Point::new(1.3, 2.3, 3.3),
+                            ..Default::default()
+                        },
+                    ],
+                    poly.vertices().cloned().collect::<Vec<_>>()
+                );
             }
-            _ => panic!("expected a line"),
+            _ => panic!("expected a POLYLINE"),
         }
-    }
 
-    #[test]
-    fn write_common_entity_fields_r12() {
-        let mut drawing = Drawing::new();
-        drawing.header.version = AcadVersion::R12;
-        let mut ent = Entity {
-            common: Default::default(),
-            specific: EntityType::Line(Default::default()),
-        };
-        ent.common.layer = "some-layer".to_owned();
-        drawing.add_entity(ent);
-        assert_contains_pairs(
-            &drawing,
-            vec![
-                CodePair::new_str(0, "LINE"),
-                CodePair::new_str(5, "10"),
-                CodePair::new_str(8, "some-layer"),
-                CodePair::new_f64(10, 0.0),
-            ],
-        );
+        match entities[1].specific {
+            EntityType::Line(_) => (),
+            _ => panic!("expected a LINE"),
+        }
     }
 
     #[test]
-    fn write_common_entity_fields_r13() {
-        let mut drawing = Drawing::new();
-        drawing.header.version = AcadVersion::R13;
-        let mut ent = Entity {
-            common: Default::default(),
-            specific: EntityType::Line(Default::default()),
-        };
-        ent.common.layer = "some-layer".to_owned();
-        drawing.add_entity(ent);
-        assert_contains_pairs(
-            &drawing,
+    fn read_polyline_without_seqend_with_trailing_entity() {
+        let drawing = from_section(
+            "ENTITIES",
             vec![
-                CodePair::new_str(0, "LINE"),
-                CodePair::new_str(5, "10"),
-                CodePair::new_str(100, "AcDbEntity"),
-                CodePair::new_str(8, "some-layer"),
-                CodePair::new_str(100, "AcDbLine"),
-                CodePair::new_f64(10, 0.0),
+                CodePair::new_str(0, "POLYLINE"), // polyline sentinel
+                CodePair::new_str(0, "VERTEX"),   // vertex 1
+                CodePair::new_f64(10, 1.1),
+                CodePair::new_f64(20, 2.1),
+                CodePair::new_f64(30, 3.1),
+                CodePair::new_str(0, "VERTEX"), // vertex 2
+                CodePair::new_f64(10, 1.2),
+                CodePair::new_f64(20, 2.2),
+                CodePair::new_f64(30, 3.2),
+                CodePair::new_str(0, "VERTEX"), // vertex 3
+                CodePair::new_f64(10, 1.3),
+                CodePair::new_f64(20, 2.3),
+                CodePair::new_f64(30, 3.3),
+                // no end sequence
+                CodePair::new_str(0, "LINE"), // trailing entity
             ],
         );
+        let entities = drawing.entities().collect::<Vec<_>>();
+        assert_eq!(2, entities.len());
+        match entities[0].specific {
+            EntityType::Polyline(ref poly) => {
+                assert_eq!(
+                    vec![
+                        Vertex {
+                            location: Point::new(1.1, 2.1, 3.1),
+                            ..Default::default()
+                        },
+                        Vertex {
+                            location: Point::new(1.2, 2.2, 3.2),
+                            ..Default::default()
+                        },
+                        Vertex {
+                            location: Point::new(1.3, 2.3, 3.3),
+                            ..Default::default()
+                        },
+                    ],
+                    poly.vertices().cloned().collect::<Vec<_>>()
+                );
+            }
+            _ => panic!("expected a POLYLINE"),
+        }
+
+        match entities[1].specific {
+            EntityType::Line(_) => (),
+            _ => panic!("expected a LINE"),
+        }
     }
 
     #[test]
-    fn write_specific_entity_fields() {
-        let mut drawing = Drawing::new();
-        drawing.header.version = AcadVersion::R13;
-        let line = Line {
-            p1: Point::new(1.1, 2.2, 3.3),
-            p2: Point::new(4.4, 5.5, 6.6),
-            ..Default::default()
-        };
-        drawing.add_entity(Entity::new(EntityType::Line(line)));
-        assert_contains_pairs(
-            &drawing,
+    fn read_empty_polyline_with_trailing_entity() {
+        let drawing = from_section(
+            "ENTITIES",
             vec![
-                CodePair::new_str(100, "AcDbLine"),
-                CodePair::new_f64(10, 1.1),
-                CodePair::new_f64(20, 2.2),
-                CodePair::new_f64(30, 3.3),
-                CodePair::new_f64(11, 4.4),
-                CodePair::new_f64(21, 5.5),
-                CodePair::new_f64(31, 6.6),
+                CodePair::new_str(0, "POLYLINE"), // polyline sentinel
+                CodePair::new_str(0, "SEQEND"),   // end sequence
+                CodePair::new_str(0, "LINE"),     // trailing entity
             ],
         );
+        let entities = drawing.entities().collect::<Vec<_>>();
+        assert_eq!(2, entities.len());
+        match entities[0].specific {
+            EntityType::Polyline(ref poly) => assert_eq!(0, poly.vertices().count()),
+            _ => panic!("expected a POLYLINE"),
+        }
+        match entities[1].specific {
+            EntityType::Line(_) => (),
+            _ => panic!("expected a LINE"),
+        }
     }
 
     #[test]
-    fn read_multiple_entities() {
+    fn read_empty_polyline_without_seqend_with_trailing_entity() {
         let drawing = from_section(
             "ENTITIES",
             vec![
-                CodePair::new_str(0, "CIRCLE"),
-                CodePair::new_f64(10, 1.1), // center
-                CodePair::new_f64(20, 2.2),
-                CodePair::new_f64(30, 3.3),
-                CodePair::new_f64(40, 4.4), // radius
-                CodePair::new_str(0, "LINE"),
-                CodePair::new_f64(10, 5.5), // p1
-                CodePair::new_f64(20, 6.6),
-                CodePair::new_f64(30, 7.7),
-                CodePair::new_f64(11, 8.8), // p2
-                CodePair::new_f64(21, 9.9),
-                CodePair::new_f64(31, 10.1),
+                CodePair::new_str(0, "POLYLINE"), // polyline sentinel
+                CodePair::new_str(0, "LINE"),     // trailing entity
             ],
         );
         let entities = drawing.entities().collect::<Vec<_>>();
         assert_eq!(2, entities.len());
-
-        // verify circle
         match entities[0].specific {
-            EntityType::Circle(ref circle) => {
-                assert_eq!(Point::new(1.1, 2.2, 3.3), circle.center);
-                assert!(approx_eq!(f64, 4.4, circle.radius));
-            }
-            _ => panic!("expected a line"),
+            EntityType::Polyline(ref poly) => assert_eq!(0, poly.vertices().count()),
+            _ => panic!("expected a POLYLINE"),
         }
-
-        // verify line
         match entities[1].specific {
-            EntityType::Line(ref line) => {
-                assert_eq!(Point::new(5.5, 6.6, 7.7), line.p1);
-                assert_eq!(Point::new(8.8, 9.9, 10.1), line.p2);
-            }
-            _ => panic!("expected a line"),
+            EntityType::Line(_) => (),
+            _ => panic!("expected a LINE"),
         }
     }
 
     #[test]
-    fn read_field_with_multiples_common() {
-        let ent = read_entity(
-            "LINE",
+    fn write_2d_polyline() {
+        let mut drawing = Drawing::new();
+        drawing.header.version = AcadVersion::R13;
+        let mut poly = Polyline::default();
+        poly.add_vertex(
+            &mut drawing,
+            Vertex {
+                location: Point::new(1.1, 2.1, 3.1),
+                ..Default::default()
+            },
+        );
+        poly.add_vertex(
+            &mut drawing,
+            Vertex {
+                location: Point::new(1.2, 2.2, 3.2),
+                ..Default::default()
+            },
+        );
+        poly.add_vertex(
+            &mut drawing,
+            Vertex {
+                location: Point::new(1.3, 2.3, 3.3),
+                ..Default::default()
+            },
+        );
+        drawing.add_entity(Entity {
+            common: Default::default(),
+            specific: EntityType::Polyline(poly),
+        });
+        // TODO
+        assert_contains_pairs(
+            &drawing,
             vec![
-                CodePair::new_binary(310, vec![0x01, 0x02]),
-                CodePair::new_binary(310, vec![0x03, 0x04]),
+                CodePair::new_str(0, "POLYLINE"), // polyline
+                CodePair::new_str(5, "13"),
+                CodePair::new_str(100, "AcDbEntity"),
+                CodePair::new_str(8, "0"),
+                CodePair::new_str(100, "AcDb2dPolyline"),
+                CodePair::new_i16(66, 1),
+                CodePair::new_f64(10, 0.0),
+                CodePair::new_f64(20, 0.0),
+                CodePair::new_f64(30, 0.0),
+                CodePair::new_str(0, "VERTEX"), // vertex 1
+                CodePair::new_str(5, "10"),
+                CodePair::new_str(100, "AcDbEntity"),
+                CodePair::new_str(8, "0"),
+                CodePair::new_str(100, "AcDbVertex"),
+                CodePair::new_str(100, "AcDb2dVertex"),
+                CodePair::new_f64(10, 1.1),
+                CodePair::new_f64(20, 2.1),
+                CodePair::new_f64(30, 3.1),
+                CodePair::new_i16(70, 0),
+                CodePair::new_f64(50, 0.0),
+                CodePair::new_str(0, "VERTEX"), // vertex 2
+                CodePair::new_str(5, "11"),
+                CodePair::new_str(100, "AcDbEntity"),
+                CodePair::new_str(8, "0"),
+                CodePair::new_str(100, "AcDbVertex"),
+                CodePair::new_str(100, "AcDb2dVertex"),
+                CodePair::new_f64(10, 1.2),
+                CodePair::new_f64(20, 2.2),
+                CodePair::new_f64(30, 3.2),
+                CodePair::new_i16(70, 0),
+                CodePair::new_f64(50, 0.0),
+                CodePair::new_str(0, "VERTEX"), // vertex 3
+                CodePair::new_str(5, "12"),
+                CodePair::new_str(100, "AcDbEntity"),
+                CodePair::new_str(8, "0"),
+                CodePair::new_str(100, "AcDbVertex"),
+                CodePair::new_str(100, "AcDb2dVertex"),
+                CodePair::new_f64(10, 1.3),
+                CodePair::new_f64(20, 2.3),
+                CodePair::new_f64(30, 3.3),
+                CodePair::new_i16(70, 0),
+                CodePair::new_f64(50, 0.0),
+                CodePair::new_str(0, "SEQEND"), // end sequence
             ],
         );
-        assert_eq!(
-            vec![vec![0x01, 0x02], vec![0x03, 0x04]],
-            ent.common.preview_image_data
-        );
     }
 
     #[test]
-    fn write_field_with_multiples_common() {
+    fn write_3d_polyline() {
         let mut drawing = Drawing::new();
-        drawing.header.version = AcadVersion::R2000;
-        drawing.add_entity(Entity {
-            common: EntityCommon {
-                preview_image_data: vec![vec![0x01, 0x02], vec![0x03, 0x04]],
+        drawing.header.version = AcadVersion::R13;
+        let mut poly = Polyline::default();
+        poly.add_vertex(
+            &mut drawing,
+            Vertex {
+                location: Point::new(1.1, 2.1, 3.1),
                 ..Default::default()
             },
-            specific: EntityType::Line(Default::default()),
+        );
+        poly.set_is_3d_polyline(true);
+        drawing.add_entity(Entity {
+            common: Default::default(),
+            specific: EntityType::Polyline(poly),
         });
         assert_contains_pairs(
             &drawing,
             vec![
-                CodePair::new_binary(310, vec![0x01, 0x02]),
-                CodePair::new_binary(310, vec![0x03, 0x04]),
+                CodePair::new_str(0, "POLYLINE"), // polyline
+                CodePair::new_str(5, "11"),
+                CodePair::new_str(100, "AcDbEntity"),
+                CodePair::new_str(8, "0"),
+                CodePair::new_str(100, "AcDb3dPolyline"), // 3d = true
+                CodePair::new_i16(66, 1),
+                CodePair::new_f64(10, 0.0),
+                CodePair::new_f64(20, 0.0),
+                CodePair::new_f64(30, 0.0),
+                CodePair::new_i16(70, 8),       // 3d = true
+                CodePair::new_str(0, "VERTEX"), // vertex 1
+                CodePair::new_str(5, "10"),
+                CodePair::new_str(100, "AcDbEntity"),
+                CodePair::new_str(8, "0"),
+                CodePair::new_str(100, "AcDbVertex"),
+                CodePair::new_str(100, "AcDb3dPolylineVertex"), // 3d = true
+                CodePair::new_f64(10, 1.1),
+                CodePair::new_f64(20, 2.1),
+                CodePair::new_f64(30, 3.1),
+                CodePair::new_i16(70, 32), // 3d = true
             ],
         );
     }
 
     #[test]
-    fn read_field_with_multiples_specific() {
-        let ent = read_entity(
-            "3DSOLID",
+    fn polyline_seqend_handle_is_assigned_when_added_to_drawing() {
+        let mut drawing = Drawing::new();
+        drawing.add_entity(Entity {
+            common: Default::default(),
+            specific: EntityType::Polyline(Polyline::default()),
+        });
+        assert_not_contains_pairs(
+            &drawing,
+            vec![CodePair::new_str(0, "SEQEND"), CodePair::new_str(5, "0")],
+        );
+    }
+
+    #[test]
+    fn read_lw_polyline_with_no_vertices() {
+        let drawing = from_section(
+            "ENTITIES",
             vec![
-                CodePair::new_str(1, "one-1"),
-                CodePair::new_str(1, "one-2"),
-                CodePair::new_str(3, "three-1"),
-                CodePair::new_str(3, "three-2"),
+                CodePair::new_str(0, "LWPOLYLINE"),
+                CodePair::new_f64(43, 43.0),
             ],
         );
-        match ent.specific {
-            EntityType::Solid3D(ref solid3d) => {
-                assert_eq!(vec!["one-1", "one-2"], solid3d.custom_data);
-                assert_eq!(vec!["three-1", "three-2"], solid3d.custom_data2);
+        let entities = drawing.entities().collect::<Vec<_>>();
+        assert_eq!(1, entities.len());
+        match entities[0].specific {
+            EntityType::LwPolyline(ref poly) => {
+                assert!(approx_eq!(f64, 43.0, poly.constant_width));
+                assert_eq!(0, poly.vertices.len());
             }
-            _ => panic!("expected a 3DSOLID"),
+            _ => panic!("expected an LWPOLYLINE"),
         }
     }
 
     #[test]
-    fn write_field_with_multiples_specific() {
-        let mut drawing = Drawing::new();
-        drawing.header.version = AcadVersion::R13; // 3DSOLID is only supported on R13+
-        drawing.add_entity(Entity {
-            common: Default::default(),
-            specific: EntityType::Solid3D(Solid3D {
-                custom_data: vec![String::from("one-1"), String::from("one-2")],
-                custom_data2: vec![String::from("three-1"), String::from("three-2")],
-                ..Default::default()
-            }),
-        });
-        assert_contains_pairs(
-            &drawing,
+    fn read_lw_polyline_with_one_vertex() {
+        let drawing = from_section(
+            "ENTITIES",
             vec![
-                CodePair::new_str(1, "one-1"),
-                CodePair::new_str(1, "one-2"),
-                CodePair::new_str(3, "three-1"),
-                CodePair::new_str(3, "three-2"),
+                CodePair::new_str(0, "LWPOLYLINE"),
+                CodePair::new_f64(43, 43.0), // constant width
+                CodePair::new_f64(10, 1.1),  // vertex 1
+                CodePair::new_f64(20, 2.1),
+                CodePair::new_f64(40, 40.1), // starting width
+                CodePair::new_f64(41, 41.1), // ending width
+                CodePair::new_f64(42, 42.1), // bulge
+                CodePair::new_i32(91, 91),   // id
             ],
         );
+        let entities = drawing.entities().collect::<Vec<_>>();
+        assert_eq!(1, entities.len());
+        match entities[0].specific {
+            EntityType::LwPolyline(ref poly) => {
+                assert!(approx_eq!(f64, 43.0, poly.constant_width));
+                assert_eq!(1, poly.vertices.len());
+                // vertex 1
+                assert!(approx_eq!(f64, 1.1, poly.vertices[0].x));
+                assert!(approx_eq!(f64, 2.1, poly.vertices[0].y));
+                assert!(approx_eq!(f64, 40.1, poly.vertices[0].starting_width));
+                assert!(approx_eq!(f64, 41.1, poly.vertices[0].ending_width));
+                assert!(approx_eq!(f64, 42.1, poly.vertices[0].bulge));
+                assert_eq!(91, poly.vertices[0].id);
+            }
+            _ => panic!("expected an LWPOLYLINE"),
+        }
     }
 
     #[test]
-    fn read_entity_with_post_parse() {
-        let ent = read_entity(
-            "IMAGE",
+    fn read_lw_polyline_with_multiple_vertices() {
+        let drawing = from_section(
+            "ENTITIES",
             vec![
-                CodePair::new_f64(14, 1.1), // clipping vertices[0]
-                CodePair::new_f64(24, 2.2),
-                CodePair::new_f64(14, 3.3), // clipping vertices[1]
-                CodePair::new_f64(24, 4.4),
-                CodePair::new_f64(14, 5.5), // clipping vertices[2]
-                CodePair::new_f64(24, 6.6),
+                CodePair::new_str(0, "LWPOLYLINE"),
+                CodePair::new_f64(43, 43.0), // constant width
+                CodePair::new_f64(10, 1.1),  // vertex 1
+                CodePair::new_f64(20, 2.1),
+                CodePair::new_f64(40, 40.1), // starting width
+                CodePair::new_f64(41, 41.1), // ending width
+                CodePair::new_f64(42, 42.1), // bulge
+                CodePair::new_i32(91, 91),   // id
+                CodePair::new_f64(10, 1.2),  // vertex 1
+                CodePair::new_f64(20, 2.2),
+                CodePair::new_f64(40, 40.2), // starting width
+                CodePair::new_f64(41, 41.2), // ending width
+                CodePair::new_f64(42, 42.2), // bulge
+                CodePair::new_i32(91, 92),   // id
             ],
         );
-        match ent.specific {
-            EntityType::Image(ref image) => {
-                assert_eq!(3, image.clipping_vertices.len());
-                assert_eq!(Point::new(1.1, 2.2, 0.0), image.clipping_vertices[0]);
-                assert_eq!(Point::new(3.3, 4.4, 0.0), image.clipping_vertices[1]);
-                assert_eq!(Point::new(5.5, 6.6, 0.0), image.clipping_vertices[2]);
+        let entities = drawing.entities().collect::<Vec<_>>();
+        assert_eq!(1, entities.len());
+        match entities[0].specific {
+            EntityType::LwPolyline(ref poly) => {
+                assert!(approx_eq!(f64, 43.0, poly.constant_width));
+                assert_eq!(2, poly.vertices.len());
+                // vertex 1
+                assert!(approx_eq!(f64, 1.1, poly.vertices[0].x));
+                assert!(approx_eq!(f64, 2.1, poly.vertices[0].y));
+                assert!(approx_eq!(f64, 40.1, poly.vertices[0].starting_width));
+                assert!(approx_eq!(f64, 41.1, poly.vertices[0].ending_width));
+                assert!(approx_eq!(f64, 42.1, poly.vertices[0].bulge));
+                assert_eq!(91, poly.vertices[0].id);
+                // vertex 2
+                assert!(approx_eq!(f64, 1.2, poly.vertices[1].x));
+                assert!(approx_eq!(f64, 2.2, poly.vertices[1].y));
+                assert!(approx_eq!(f64, 40.2, poly.vertices[1].starting_width));
+                assert!(approx_eq!(f64, 41.2, poly.vertices[1].ending_width));
+                assert!(approx_eq!(f64, 42.2, poly.vertices[1].bulge));
+                assert_eq!(92, poly.vertices[1].id);
             }
-            _ => panic!("expected an IMAGE"),
+            _ => panic!("expected an LWPOLYLINE"),
         }
     }
 
     #[test]
-    fn write_entity_with_write_order() {
+    fn write_lw_polyline() {
         let mut drawing = Drawing::new();
-        drawing.header.version = AcadVersion::R14; // IMAGE is only supported on R14+
-        drawing.add_entity(Entity {
-            common: Default::default(),
-            specific: EntityType::Image(Image {
-                clipping_vertices: vec![
-                    Point::new(1.1, 2.2, 0.0),
-                    Point::new(3.3, 4.4, 0.0),
-                    Point::new(5.5, 6.6, 0.0),
-                ],
-                ..Default::default()
-            }),
+        drawing.header.version = AcadVersion::R2013;
+        let mut poly = LwPolyline::default();
+        poly.constant_width = 43.0;
+        poly.vertices.push(LwPolylineVertex {
+            x: 1.1,
+            y: 2.1,
+            ..Default::default()
+        });
+        poly.vertices.push(LwPolylineVertex {
+            x: 1.2,
+            y: 2.2,
+            starting_width: 40.2,
+            ending_width: 41.2,
+            bulge: 42.2,
+            id: 92,
         });
+        drawing.add_entity(Entity::new(EntityType::LwPolyline(poly)));
         assert_contains_pairs(
             &drawing,
             vec![
-                CodePair::new_i32(91, 3),
-                CodePair::new_f64(14, 1.1),
-                CodePair::new_f64(24, 2.2),
-                CodePair::new_f64(14, 3.3),
-                CodePair::new_f64(24, 4.4),
-                CodePair::new_f64(14, 5.5),
-                CodePair::new_f64(24, 6.6),
+                CodePair::new_str(100, "AcDbPolyline"),
+                CodePair::new_i32(90, 2), // number of vertices
+                CodePair::new_i16(70, 0),
+                CodePair::new_f64(43, 43.0), // constant width
+                CodePair::new_f64(10, 1.1),  // vertex 1
+                CodePair::new_f64(20, 2.1),
+                CodePair::new_i32(91, 0),
+                CodePair::new_f64(10, 1.2), // vertex 2
+                CodePair::new_f64(20, 2.2),
+                CodePair::new_i32(91, 92),
+                CodePair::new_f64(40, 40.2), // starting width
+                CodePair::new_f64(41, 41.2), // ending width
+                CodePair::new_f64(42, 42.2), // bulge
             ],
         );
     }
 
     #[test]
-    fn read_entity_with_custom_reader_mtext() {
+    fn read_dimension() {
         let ent = read_entity(
-            "MTEXT",
+            "DIMENSION",
             vec![
-                CodePair::new_f64(50, 1.1),  // rotation angle
-                CodePair::new_i16(75, 7),    // column type
-                CodePair::new_f64(50, 3.0),  // column count
-                CodePair::new_f64(50, 10.0), // column values
-                CodePair::new_f64(50, 20.0),
-                CodePair::new_f64(50, 30.0),
+                CodePair::new_str(1, "text"),
+                CodePair::new_str(100, "AcDbOrdinateDimension"),
+                CodePair::new_f64(13, 1.1), // definition_point_2
+                CodePair::new_f64(23, 2.2),
+                CodePair::new_f64(33, 3.3),
+                CodePair::new_f64(14, 4.4), // definition_point_3
+                CodePair::new_f64(24, 5.5),
+                CodePair::new_f64(34, 6.6),
             ],
         );
         match ent.specific {
-            EntityType::MText(ref mtext) => {
-                assert!(approx_eq!(f64, 1.1, mtext.rotation_angle));
-                assert_eq!(7, mtext.column_type);
-                assert_eq!(3, mtext.column_count);
-                assert_eq!(3, mtext.column_heights.len());
-                assert!(approx_eq!(f64, 10.0, mtext.column_heights[0]));
-                assert!(approx_eq!(f64, 20.0, mtext.column_heights[1]));
-                assert!(approx_eq!(f64, 30.0, mtext.column_heights[2]));
+            EntityType::OrdinateDimension(ref dim) => {
+                assert_eq!("text", dim.dimension_base.text);
+                assert_eq!(Point::new(1.1, 2.2, 3.3), dim.definition_point_2);
+                assert_eq!(Point::new(4.4, 5.5, 6.6), dim.definition_point_3);
             }
-            _ => panic!("expected an MTEXT"),
+            _ => panic!("expected an ordinate dimension"),
         }
     }
 
     #[test]
-    fn read_entity_after_entity_with_custom_reader() {
+    fn read_entity_after_unsupported_dimension() {
         let drawing = from_section(
             "ENTITIES",
             vec![
-                CodePair::new_str(0, "MTEXT"), // has a custom reader
-                CodePair::new_str(0, "LINE"),  // uses the auto-generated reader
+                CodePair::new_str(0, "DIMENSION"),
+                CodePair::new_str(1, "text"),
+                CodePair::new_str(100, "AcDbSomeUnsupportedDimensionType"),
+                CodePair::new_f64(10, 1.1),
+                CodePair::new_f64(20, 2.2),
+                CodePair::new_f64(30, 3.3),
+                CodePair::new_str(0, "LINE"),
             ],
         );
         let entities = drawing.entities().collect::<Vec<_>>();
-        assert_eq!(2, entities.len());
+        assert_eq!(1, entities.len());
         match entities[0].specific {
-            EntityType::MText(_) => {}
-            _ => panic!("expected an mtext"),
-        }
-        match entities[1].specific {
             EntityType::Line(_) => {}
             _ => panic!("expected a line"),
         }
     }
 
     #[test]
-    fn read_entity_with_flags() {
-        let ent = read_entity("IMAGE", vec![CodePair::new_i16(70, 5)]);
-        match ent.specific {
-            EntityType::Image(ref image) => {
-                assert!(image.show_image());
-                assert!(image.use_clipping_boundary());
-            }
-            _ => panic!("expected an IMAGE"),
-        }
+    fn write_arc() {
+        let arc = Arc::new(Point::new(1.0, 2.0, 3.0), 4.0, 90.0, 180.0);
+        let ent = Entity::new(EntityType::Arc(arc));
+        let mut drawing = Drawing::new();
+        drawing.add_entity(ent);
+        assert_contains_pairs(
+            &drawing,
+            vec![
+                CodePair::new_str(100, "AcDbCircle"),
+                CodePair::new_f64(10, 1.0),
+                CodePair::new_f64(20, 2.0),
+                CodePair::new_f64(30, 3.0),
+                CodePair::new_f64(40, 4.0),
+                CodePair::new_str(100, "AcDbArc"),
+                CodePair::new_f64(50, 90.0),
+                CodePair::new_f64(51, 180.0),
+            ],
+        );
     }
 
     #[test]
-    fn write_entity_with_flags() {
+    fn write_dimension() {
+        let dim = RadialDimension {
+            dimension_base: DimensionBase {
+                text: String::from("some-text"),
+                ..Default::default()
+            },
+            definition_point_2: Point::new(1.1, 2.2, 3.3),
+            ..Default::default()
+        };
+        let ent = Entity::new(EntityType::RadialDimension(dim));
         let mut drawing = Drawing::new();
-        drawing.header.version = AcadVersion::R14; // IMAGE is only supported on R14+
-        let mut image = Image::default();
-        assert_eq!(0, image.display_options_flags);
-        image.set_show_image(true);
-        image.set_use_clipping_boundary(true);
-        drawing.add_entity(Entity {
-            common: Default::default(),
-            specific: EntityType::Image(image),
-        });
+        drawing.add_entity(ent);
+        assert_contains_pairs(&drawing, vec![CodePair::new_str(0, "DIMENSION")]);
+        assert_contains_pairs(&drawing, vec![CodePair::new_str(1, "some-text")]);
         assert_contains_pairs(
             &drawing,
             vec![
-                CodePair::new_i16(70, 5),  // flags
-                CodePair::new_i16(280, 1), // sentinels to make sure we're not reading a header value
-                CodePair::new_i16(281, 50),
+                CodePair::new_str(100, "AcDbRadialDimension"),
+                CodePair::new_f64(15, 1.1), // definition_point_2
+                CodePair::new_f64(25, 2.2),
+                CodePair::new_f64(35, 3.3),
+                CodePair::new_f64(40, 0.0), // leader_length
+            ],
+        );
+    }
+
+    #[test]
+    fn read_insert_with_separate_attributes() {
+        let file = from_section(
+            "ENTITIES",
+            vec![
+                CodePair::new_str(0, "INSERT"),
+                CodePair::new_i16(66, 0),       // no attributes
+                CodePair::new_str(0, "ATTRIB"), // this is a separate attribute, not tiee to the `INSERT` entity
+                CodePair::new_str(0, "SEQEND"), // this is a separate `SEQEND` entity, not tiee to the `INSERT` entity
             ],
         );
+        let entities = file.entities().collect::<Vec<_>>();
+        assert_eq!(3, entities.len());
+        match entities[0].specific {
+            EntityType::Insert(_) => (),
+            _ => panic!("expected an INSERT"),
+        }
+        match entities[1].specific {
+            EntityType::Attribute(_) => (),
+            _ => panic!("expected an ATTRIB"),
+        }
+        match entities[2].specific {
+            EntityType::Seqend(_) => (),
+            _ => panic!("expected a SEQEND"),
+        }
     }
 
     #[test]
-    fn read_entity_with_handle_and_pointer() {
-        let ent = read_entity(
-            "3DSOLID",
+    fn read_insert_with_embedded_attributes() {
+        let file = from_section(
+            "ENTITIES",
             vec![
-                CodePair::new_str(5, "A1"),   // handle
-                CodePair::new_str(330, "A2"), // owner handle
-                CodePair::new_str(350, "A3"), // history_object pointer
+                CodePair::new_str(0, "INSERT"),
+                CodePair::new_i16(66, 1),       // includes attributes
+                CodePair::new_str(0, "ATTRIB"), // these are embedded attributes tied to the `INSERT` enttiy
+                CodePair::new_str(0, "ATTRIB"),
+                CodePair::new_str(0, "SEQEND"), // this is an embedded `SEQEND` entity tied to the `INSERT` entity
             ],
         );
-        assert_eq!(Handle(0xa1), ent.common.handle);
-        assert_eq!(Handle(0xa2), ent.common.__owner_handle);
-        match ent.specific {
-            EntityType::Solid3D(ref solid) => {
-                assert_eq!(Handle(0xa3), solid.__history_object_handle)
-            }
-            _ => panic!("expected a 3DSOLID entity"),
+        let entities = file.entities().collect::<Vec<_>>();
+        assert_eq!(1, entities.len());
+        match entities[0].specific {
+            EntityType::Insert(ref ins) => assert_eq!(2, ins.attributes().count()),
+            _ => panic!("exepcted an INSERT"),
         }
     }
 
     #[test]
-    fn write_entity_with_handle_and_pointer() {
+    fn write_insert_no_embedded_attributes() {
         let mut drawing = Drawing::new();
-        drawing.header.version = AcadVersion::R2000;
-        drawing.add_entity(Entity {
-            common: EntityCommon {
-                __owner_handle: Handle(0xa2),
-                ..Default::default()
-            },
-            specific: EntityType::Line(Default::default()),
-        });
-        assert_contains_pairs(
+        let mut ins = Insert::default();
+        ins.name = "insert-name".to_string();
+        let ent = Entity::new(EntityType::Insert(ins));
+        drawing.add_entity(ent);
+        assert_not_contains_pairs(
             &drawing,
             vec![
-                CodePair::new_str(0, "LINE"),
-                CodePair::new_str(5, "10"),
-                CodePair::new_str(330, "A2"),
+                CodePair::new_i16(66, 0),            // contains no attributes
+                CodePair::new_str(2, "insert-name"), // sentinel to ensure we're reading at the correct location
             ],
         );
+        assert_not_contains_pairs(&drawing, vec![CodePair::new_str(0, "SEQEND")]);
     }
 
     #[test]
-    fn write_version_specific_entity() {
+    fn write_insert_with_embedded_attributes() {
         let mut drawing = Drawing::new();
-        drawing.add_entity(Entity {
-            common: Default::default(),
-            specific: EntityType::Solid3D(Default::default()),
-        });
-
-        // 3DSOLID not supported in R12 and below
-        drawing.header.version = AcadVersion::R12;
+        drawing.header.version = AcadVersion::R13;
+        let mut ins = Insert::default();
+        ins.add_attribute(&mut drawing, Attribute::default());
+        let ent = Entity::new(EntityType::Insert(ins));
+        drawing.add_entity(ent);
+        assert_contains_pairs(&drawing, vec![CodePair::new_str(0, "INSERT")]);
         assert_contains_pairs(
             &drawing,
             vec![
-                CodePair::new_str(0, "SECTION"),
-                CodePair::new_str(2, "ENTITIES"),
-                CodePair::new_str(0, "ENDSEC"),
+                CodePair::new_str(100, "AcDbBlockReference"),
+                CodePair::new_i16(66, 1), // contains attributes
             ],
         );
+        assert_contains_pairs(&drawing, vec![CodePair::new_str(0, "ATTRIB")]);
+        assert_contains_pairs(&drawing, vec![CodePair::new_str(0, "SEQEND")]);
+    }
 
-        // but it is in R13 and above
-        drawing.header.version = AcadVersion::R13;
-        assert_contains_pairs(
+    #[test]
+    fn write_insert_no_extrusion_on_r12() {
+        let mut drawing = Drawing::new();
+        drawing.header.version = AcadVersion::R12;
+        let mut ins = Insert::default();
+        ins.extrusion_direction = Vector::new(1.0, 2.0, 3.0);
+        let ent = Entity::new(EntityType::Insert(ins));
+        drawing.add_entity(ent);
+        assert_not_contains_pairs(
             &drawing,
             vec![
-                CodePair::new_str(0, "SECTION"),
-                CodePair::new_str(2, "ENTITIES"),
-                CodePair::new_str(0, "3DSOLID"),
+                CodePair::new_f64(210, 1.0),
+                CodePair::new_f64(220, 2.0),
+                CodePair::new_f64(230, 3.0),
             ],
         );
     }
 
     #[test]
-    fn read_polyline() {
-        let drawing = from_section(
-            "ENTITIES",
+    fn write_insert_extrusion_on_r13() {
+        let mut drawing = Drawing::new();
+        drawing.header.version = AcadVersion::R13;
+        let mut ins = Insert::default();
+        ins.extrusion_direction = Vector::new(1.0, 2.0, 3.0);
+        let ent = Entity::new(EntityType::Insert(ins));
+        drawing.add_entity(ent);
+        assert_contains_pairs(
+            &drawing,
             vec![
-                CodePair::new_str(0, "POLYLINE"), // polyline sentinel
-                CodePair::new_str(0, "VERTEX"),   // vertex 1
-                CodePair::new_f64(10, 1.1),
-                CodePair::new_f64(20, 2.1),
-                CodePair::new_f64(30, 3.1),
-                CodePair::new_str(0, "VERTEX"), // vertex 2
-                CodePair::new_f64(10, 1.2),
-                CodePair::new_f64(20, 2.2),
-                CodePair::new_f64(30, 3.2),
-                CodePair::new_str(0, "VERTEX"), // vertex 3
-                CodePair::new_f64(10, 1.3),
-                CodePair::new_f64(20, 2.3),
-                CodePair::new_f64(30, 3.3),
-                CodePair::new_str(0, "SEQEND"), // end sequence
+                CodePair::new_f64(210, 1.0),
+                CodePair::new_f64(220, 2.0),
+                CodePair::new_f64(230, 3.0),
             ],
         );
-        let entities = drawing.entities().collect::<Vec<_>>();
-        assert_eq!(1, entities.len());
-        match entities[0].specific {
-            EntityType::Polyline(ref poly) => {
-                assert_eq!(
-                    vec![
-                        Vertex {
-                            location: Point::new(1.1, 2.1, 3.1),
-                            ..Default::default()
-                        },
-                        Vertex {
-                            location: Point::new(1.2, 2.2, 3.2),
-                            ..Default::default()
-                        },
-                        Vertex {
-                            location: Point::new(1.3, 2.3, 3.3),
-                            ..Default::default()
-                        },
-                    ],
-                    poly.vertices().cloned().collect::<Vec<_>>()
-                );
-            }
-            _ => panic!("expected a POLYLINE"),
-        }
     }
 
     #[test]
-    fn read_polyline_without_seqend() {
-        let drawing = from_section(
-            "ENTITIES",
-            vec![
-                CodePair::new_str(0, "POLYLINE"), // polyline sentinel
-                CodePair::new_str(0, "VERTEX"),   // vertex 1
-                CodePair::new_f64(10, 1.1),
-                CodePair::new_f64(20, 2.1),
-                CodePair::new_f64(30, 3.1),
-                CodePair::new_str(0, "VERTEX"), // vertex 2
-                CodePair::new_f64(10, 1.2),
-                CodePair::new_f64(20, 2.2),
-                CodePair::new_f64(30, 3.2),
-                CodePair::new_str(0, "VERTEX"), // vertex 3
-                CodePair::new_f64(10, 1.3),
-                CodePair::new_f64(20, 2.3),
-                CodePair::new_f64(30, 3.3),
-                // no end sequence
-            ],
-        );
+    fn round_trip_insert_with_attributes() {
+        let mut drawing = Drawing::new();
+        let mut ins = Insert::default();
+        ins.add_attribute(&mut drawing, Attribute::default());
+        let ent = Entity::new(EntityType::Insert(ins));
+        drawing.add_entity(ent);
+
+        let drawing = drawing_from_pairs(drawing.code_pairs().unwrap());
+
         let entities = drawing.entities().collect::<Vec<_>>();
         assert_eq!(1, entities.len());
         match entities[0].specific {
-            EntityType::Polyline(ref poly) => {
-                assert_eq!(
-                    vec![
-                        Vertex {
-                            location: Point::new(1.1, 2.1, 3.1),
-                            ..Default::default()
-                        },
-                        Vertex {
-                            location: Point::new(1.2, 2.2, 3.2),
-                            ..Default::default()
-                        },
-                        Vertex {
-                            location: Point::new(1.3, 2.3, 3.3),
-                            ..Default::default()
-                        },
-                    ],
-                    poly.vertices().cloned().collect::<Vec<_>>()
-                );
-            }
-            _ => panic!("expected a POLYLINE"),
+            EntityType::Insert(ref ins) => assert_eq!(1, ins.attributes().count()),
+            _ => panic!("expected an INSERT"),
         }
     }
 
     #[test]
-    fn read_empty_polyline() {
-        let drawing = from_section(
+    fn read_attribute_with_attached_mtext() {
+        let file = from_section(
             "ENTITIES",
             vec![
-                CodePair::new_str(0, "POLYLINE"),
-                CodePair::new_str(0, "SEQEND"),
+                CodePair::new_str(0, "ATTRIB"),
+                CodePair::new_str(0, "MTEXT"),
+                CodePair::new_str(1, "m_text"),
             ],
         );
-        let entities = drawing.entities().collect::<Vec<_>>();
+        let entities = file.entities().collect::<Vec<_>>();
         assert_eq!(1, entities.len());
         match entities[0].specific {
-            EntityType::Polyline(ref poly) => assert_eq!(0, poly.vertices().count()),
-            _ => panic!("expected a POLYLINE"),
+            EntityType::Attribute(ref att) => assert_eq!("m_text", att.m_text.text),
+            _ => panic!("exepcted an attribute"),
         }
     }
 
     #[test]
-    fn read_empty_polyline_without_seqend() {
-        let drawing = from_section("ENTITIES", vec![CodePair::new_str(0, "POLYLINE")]);
-        let entities = drawing.entities().collect::<Vec<_>>();
-        assert_eq!(1, entities.len());
-        match entities[0].specific {
-            EntityType::Polyline(ref poly) => assert_eq!(0, poly.vertices().count()),
-            _ => panic!("expected a POLYLINE"),
-        }
+    fn write_attribute_with_attached_mtext() {
+        let mut drawing = Drawing::new();
+        drawing.header.version = AcadVersion::R13; // MTEXT is only written on R13+
+        drawing.add_entity(Entity::new(EntityType::Attribute(Default::default())));
+        assert_contains_pairs(&drawing, vec![CodePair::new_str(0, "ATTRIB")]);
+        assert_contains_pairs(&drawing, vec![CodePair::new_str(0, "MTEXT")]);
     }
 
     #[test]
-    fn read_polyline_with_trailing_entity() {
-        let drawing = from_section(
-            "ENTITIES",
-            vec![
-                CodePair::new_str(0, "POLYLINE"), // polyline sentinel
-                CodePair::new_str(0, "VERTEX"),   // vertex 1
-                CodePair::new_f64(10, 1.1),
-                CodePair::new_f64(20, 2.1),
-                CodePair::new_f64(30, 3.1),
-                CodePair::new_str(0, "VERTEX"), // vertex 2
-                CodePair::new_f64(10, 1.2),
-                CodePair::new_f64(20, 2.2),
-                CodePair::new_f64(30, 3.2),
-                CodePair::new_str(0, "VERTEX"), // vertex 3
-                CodePair::new_f64(10, 1.3),
-                CodePair::new_f64(20, 2.3),
-                CodePair::new_f64(30, 3.3),
-                CodePair::new_str(0, "SEQEND"), // end sequence
-                CodePair::new_str(0, "LINE"),   // trailing entity
-            ],
-        );
+    fn round_trip_attribute_with_attached_mtext() {
+        let att = Attribute {
+            m_text: MText {
+                text: String::from("m_text"),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let mut drawing = Drawing::new();
+        drawing.header.version = AcadVersion::R13; // MTEXT is only written on R13+
+        drawing.add_entity(Entity::new(EntityType::Attribute(att)));
+
+        let drawing = drawing_from_pairs(drawing.code_pairs().unwrap());
+
         let entities = drawing.entities().collect::<Vec<_>>();
-        assert_eq!(2, entities.len());
+        assert_eq!(1, entities.len());
         match entities[0].specific {
-            EntityType::Polyline(ref poly) => {
-                assert_eq!(
-                    vec![
-                        Vertex {
-                            location: Point::new(1.1, 2.1, 3.1),
-                            ..Default::default()
-                        },
-                        Vertex {
-                            location: Point::new(1.2, 2.2, 3.2),
-                            ..Default::default()
-                        },
-                        Vertex {
-                            location: Point::new(1.3, 2.3, 3.3),
-                            ..Default::default()
-                        },
-                    ],
-                    poly.vertices().cloned().collect::<Vec<_>>()
-                );
-            }
-            _ => panic!("expected a POLYLINE"),
+            EntityType::Attribute(ref att) => assert_eq!("m_text", att.m_text.text),
+            _ => panic!("expected a attribute"),
         }
+    }
 
-        match entities[1].specific {
-            EntityType::Line(_) => (),
-            _ => panic!("expected a LINE"),
+    #[test]
+    fn read_hatch_with_polyline_boundary() {
+        let ent = read_entity(
+            "HATCH",
+            vec![
+                CodePair::new_str(100, "AcDbHatch"),
+                CodePair::new_f64(10, 0.0), // elevation point
+                CodePair::new_f64(20, 0.0),
+                CodePair::new_f64(30, 3.0),
+                CodePair::new_str(2, "SOLID"),
+                CodePair::new_i16(70, 1),
+                CodePair::new_i16(71, 1),
+                CodePair::new_i32(91, 1), // boundary path count
+                CodePair::new_i32(92, 3), // external polyline path
+                CodePair::new_i16(72, 1), // has bulge
+                CodePair::new_i16(73, 1), // is closed
+                CodePair::new_i32(93, 2), // vertex count
+                CodePair::new_f64(10, 1.0),
+                CodePair::new_f64(20, 2.0),
+                CodePair::new_f64(42, 0.5),
+                CodePair::new_f64(10, 3.0),
+                CodePair::new_f64(20, 4.0),
+                CodePair::new_f64(42, 0.0),
+                CodePair::new_i32(97, 1), // source boundary object count
+                CodePair::new_str(330, "ABC"),
+                CodePair::new_i16(75, 1),
+                CodePair::new_i16(76, 1),
+                CodePair::new_i32(98, 1), // seed point count
+                CodePair::new_f64(10, 5.0),
+                CodePair::new_f64(20, 6.0),
+            ],
+        );
+        match ent.specific {
+            EntityType::Hatch(ref hatch) => {
+                assert_eq!(Point::new(0.0, 0.0, 3.0), hatch.elevation_point);
+                assert_eq!("SOLID", hatch.pattern_name);
+                assert!(hatch.is_solid_fill);
+                assert!(hatch.is_associative);
+                assert_eq!(1, hatch.boundary_paths.len());
+                let path = &hatch.boundary_paths[0];
+                assert!(path.is_external());
+                assert!(path.is_polyline());
+                assert!(path.is_closed);
+                assert_eq!(2, path.vertices.len());
+                assert!(approx_eq!(f64, 1.0, path.vertices[0].x));
+                assert!(approx_eq!(f64, 2.0, path.vertices[0].y));
+                assert!(approx_eq!(f64, 0.5, path.vertices[0].bulge));
+                assert!(approx_eq!(f64, 3.0, path.vertices[1].x));
+                assert!(approx_eq!(f64, 4.0, path.vertices[1].y));
+                assert_eq!(vec![Handle(0xABC)], path.__source_boundary_objects_handle);
+                assert_eq!(HatchStyle::OutermostAreaOnly, hatch.hatch_style);
+                assert_eq!(HatchPatternType::Predefined, hatch.pattern_type);
+                assert_eq!(vec![Point::new(5.0, 6.0, 0.0)], hatch.seed_points);
+            }
+            _ => panic!("expected a HATCH"),
         }
     }
 
     #[test]
-    fn read_polyline_without_seqend_with_trailing_entity() {
-        let drawing = from_section(
-            "ENTITIES",
+    fn read_hatch_with_edge_boundary() {
+        let ent = read_entity(
+            "HATCH",
             vec![
-                CodePair::new_str(0, "POLYLINE"), // polyline sentinel
-                CodePair::new_str(0, "VERTEX"),   // vertex 1
-                CodePair::new_f64(10, 1.1),
-                CodePair::new_f64(20, 2.1),
-                CodePair::new_f64(30, 3.1),
-                CodePair::new_str(0, "VERTEX"), // vertex 2
-                CodePair::new_f64(10, 1.2),
-                CodePair::new_f64(20, 2.2),
-                CodePair::new_f64(30, 3.2),
-                CodePair::new_str(0, "VERTEX"), // vertex 3
-                CodePair::new_f64(10, 1.3),
-                CodePair::new_f64(20, 2.3),
-                CodePair::new_f64(30, 3.3),
-                // no end sequence
-                CodePair::new_str(0, "LINE"), // trailing entity
+                CodePair::new_str(100, "AcDbHatch"),
+                CodePair::new_i32(91, 1), // boundary path count
+                CodePair::new_i32(92, 1), // external path
+                CodePair::new_i32(93, 4), // edge count
+                CodePair::new_i16(72, 1), // line
+                CodePair::new_f64(10, 1.0),
+                CodePair::new_f64(20, 2.0),
+                CodePair::new_f64(11, 3.0),
+                CodePair::new_f64(21, 4.0),
+                CodePair::new_i16(72, 2), // circular arc
+                CodePair::new_f64(10, 5.0),
+                CodePair::new_f64(20, 6.0),
+                CodePair::new_f64(40, 7.0),
+                CodePair::new_f64(50, 0.0),
+                CodePair::new_f64(51, 90.0),
+                CodePair::new_i16(73, 0),
+                CodePair::new_i16(72, 3), // elliptic arc
+                CodePair::new_f64(10, 8.0),
+                CodePair::new_f64(20, 9.0),
+                CodePair::new_f64(11, 10.0),
+                CodePair::new_f64(21, 0.0),
+                CodePair::new_f64(40, 0.5),
+                CodePair::new_f64(50, 0.0),
+                CodePair::new_f64(51, 180.0),
+                CodePair::new_i16(73, 1),
+                CodePair::new_i16(72, 4), // spline
+                CodePair::new_i32(94, 3),
+                CodePair::new_i16(73, 1),
+                CodePair::new_i16(74, 0),
+                CodePair::new_i32(95, 2),
+                CodePair::new_i32(96, 2),
+                CodePair::new_f64(40, 0.0),
+                CodePair::new_f64(40, 1.0),
+                CodePair::new_f64(10, 11.0),
+                CodePair::new_f64(20, 12.0),
+                CodePair::new_f64(42, 0.25),
+                CodePair::new_f64(10, 13.0),
+                CodePair::new_f64(20, 14.0),
+                CodePair::new_f64(42, 0.75),
+                CodePair::new_i32(97, 1), // fit point count
+                CodePair::new_f64(11, 15.0),
+                CodePair::new_f64(21, 16.0),
+                CodePair::new_f64(12, 1.0),
+                CodePair::new_f64(22, 0.0),
+                CodePair::new_f64(13, 0.0),
+                CodePair::new_f64(23, 1.0),
+                CodePair::new_i32(97, 0), // source boundary object count
+                CodePair::new_i16(75, 0),
+                CodePair::new_i16(76, 1),
             ],
         );
-        let entities = drawing.entities().collect::<Vec<_>>();
-        assert_eq!(2, entities.len());
-        match entities[0].specific {
-            EntityType::Polyline(ref poly) => {
+        match ent.specific {
+            EntityType::Hatch(ref hatch) => {
+                assert_eq!(1, hatch.boundary_paths.len());
+                let path = &hatch.boundary_paths[0];
+                assert!(!path.is_polyline());
                 assert_eq!(
                     vec![
-                        Vertex {
-                            location: Point::new(1.1, 2.1, 3.1),
-                            ..Default::default()
+                        HatchEdge::Line {
+                            start: Point::new(1.0, 2.0, 0.0),
+                            end: Point::new(3.0, 4.0, 0.0),
                         },
-                        Vertex {
-                            location: Point::new(1.2, 2.2, 3.2),
-                            ..Default::default()
+                        HatchEdge::CircularArc {
+                            center: Point::new(5.0, 6.0, 0.0),
+                            radius: 7.0,
+                            start_angle: 0.0,
+                            end_angle: 90.0,
+                            is_counter_clockwise: false,
                         },
-                        Vertex {
-                            location: Point::new(1.3, 2.3, 3.3),
-                            ..Default::default()
+                        HatchEdge::EllipticArc {
+                            center: Point::new(8.0, 9.0, 0.0),
+                            major_axis: Vector::new(10.0, 0.0, 0.0),
+                            minor_axis_ratio: 0.5,
+                            start_angle: 0.0,
+                            end_angle: 180.0,
+                            is_counter_clockwise: true,
+                        },
+                        HatchEdge::Spline {
+                            degree_of_curve: 3,
+                            is_rational: true,
+                            is_periodic: false,
+                            knot_values: vec![0.0, 1.0],
+                            control_points: vec![
+                                Point::new(11.0, 12.0, 0.0),
+                                Point::new(13.0, 14.0, 0.0),
+                            ],
+                            weights: vec![0.25, 0.75],
+                            fit_points: vec![Point::new(15.0, 16.0, 0.0)],
+                            start_tangent: Vector::new(1.0, 0.0, 0.0),
+                            end_tangent: Vector::new(0.0, 1.0, 0.0),
                         },
                     ],
-                    poly.vertices().cloned().collect::<Vec<_>>()
+                    path.edges
                 );
+                assert_eq!(HatchStyle::OddParity, hatch.hatch_style);
             }
-            _ => panic!("expected a POLYLINE"),
-        }
-
-        match entities[1].specific {
-            EntityType::Line(_) => (),
-            _ => panic!("expected a LINE"),
-        }
-    }
-
-    #[test]
-    fn read_empty_polyline_with_trailing_entity() {
-        let drawing = from_section(
-            "ENTITIES",
-            vec![
-                CodePair::new_str(0, "POLYLINE"), // polyline sentinel
-                CodePair::new_str(0, "SEQEND"),   // end sequence
-                CodePair::new_str(0, "LINE"),     // trailing entity
-            ],
-        );
-        let entities = drawing.entities().collect::<Vec<_>>();
-        assert_eq!(2, entities.len());
-        match entities[0].specific {
-            EntityType::Polyline(ref poly) => assert_eq!(0, poly.vertices().count()),
-            _ => panic!("expected a POLYLINE"),
-        }
-        match entities[1].specific {
-            EntityType::Line(_) => (),
-            _ => panic!("expected a LINE"),
+            _ => panic!("expected a HATCH"),
         }
     }
 
     #[test]
-    fn read_empty_polyline_without_seqend_with_trailing_entity() {
-        let drawing = from_section(
-            "ENTITIES",
+    fn read_hatch_with_spline_edge_without_fit_data() {
+        // pre-R2010 files don't write the fit data so the next code 97 is the source boundary object count
+        let ent = read_entity(
+            "HATCH",
             vec![
-                CodePair::new_str(0, "POLYLINE"), // polyline sentinel
-                CodePair::new_str(0, "LINE"),     // trailing entity
+                CodePair::new_i32(91, 1),
+                CodePair::new_i32(92, 1),
+                CodePair::new_i32(93, 1),
+                CodePair::new_i16(72, 4),
+                CodePair::new_i32(94, 3),
+                CodePair::new_f64(10, 1.0),
+                CodePair::new_f64(20, 2.0),
+                CodePair::new_i32(97, 1),
+                CodePair::new_str(330, "ABC"),
+                CodePair::new_i16(75, 0),
             ],
         );
-        let entities = drawing.entities().collect::<Vec<_>>();
-        assert_eq!(2, entities.len());
-        match entities[0].specific {
-            EntityType::Polyline(ref poly) => assert_eq!(0, poly.vertices().count()),
-            _ => panic!("expected a POLYLINE"),
-        }
-        match entities[1].specific {
-            EntityType::Line(_) => (),
-            _ => panic!("expected a LINE"),
+        match ent.specific {
+            EntityType::Hatch(ref hatch) => {
+                let path = &hatch.boundary_paths[0];
+                assert_eq!(1, path.edges.len());
+                assert_eq!(vec![Handle(0xABC)], path.__source_boundary_objects_handle);
+            }
+            _ => panic!("expected a HATCH"),
         }
     }
 
     #[test]
-    fn write_2d_polyline() {
-        let mut drawing = Drawing::new();
-        drawing.header.version = AcadVersion::R13;
-        let mut poly = Polyline::default();
-        poly.add_vertex(
-            &mut drawing,
-            Vertex {
-                location: Point::new(1.1, 2.1, 3.1),
-                ..Default::default()
-            },
-        );
-        poly.add_vertex(
-            &mut drawing,
-            Vertex {
-                location: Point::new(1.2, 2.2, 3.2),
-                ..Default::default()
-            },
-        );
-        poly.add_vertex(
-            &mut drawing,
-            Vertex {
-                location: Point::new(1.3, 2.3, 3.3),
-                ..Default::default()
-            },
-        );
-        drawing.add_entity(Entity {
-            common: Default::default(),
-            specific: EntityType::Polyline(poly),
-        });
-        // TODO
-        assert_contains_pairs(
-            &drawing,
+    fn read_hatch_with_pattern_and_gradient() {
+        let ent = read_entity(
+            "HATCH",
             vec![
-                CodePair::new_str(0, "POLYLINE"), // polyline
-                CodePair::new_str(5, "13"),
-                CodePair::new_str(100, "AcDbEntity"),
-                CodePair::new_str(8, "0"),
-                CodePair::new_str(100, "AcDb2dPolyline"),
-                CodePair::new_i16(66, 1),
-                CodePair::new_f64(10, 0.0),
-                CodePair::new_f64(20, 0.0),
-                CodePair::new_f64(30, 0.0),
-                CodePair::new_str(0, "VERTEX"), // vertex 1
-                CodePair::new_str(5, "10"),
-                CodePair::new_str(100, "AcDbEntity"),
-                CodePair::new_str(8, "0"),
-                CodePair::new_str(100, "AcDbVertex"),
-                CodePair::new_str(100, "AcDb2dVertex"),
-                CodePair::new_f64(10, 1.1),
-                CodePair::new_f64(20, 2.1),
-                CodePair::new_f64(30, 3.1),
-                CodePair::new_i16(70, 0),
-                CodePair::new_f64(50, 0.0),
-                CodePair::new_str(0, "VERTEX"), // vertex 2
-                CodePair::new_str(5, "11"),
-                CodePair::new_str(100, "AcDbEntity"),
-                CodePair::new_str(8, "0"),
-                CodePair::new_str(100, "AcDbVertex"),
-                CodePair::new_str(100, "AcDb2dVertex"),
-                CodePair::new_f64(10, 1.2),
-                CodePair::new_f64(20, 2.2),
-                CodePair::new_f64(30, 3.2),
-                CodePair::new_i16(70, 0),
-                CodePair::new_f64(50, 0.0),
-                CodePair::new_str(0, "VERTEX"), // vertex 3
-                CodePair::new_str(5, "12"),
-                CodePair::new_str(100, "AcDbEntity"),
-                CodePair::new_str(8, "0"),
-                CodePair::new_str(100, "AcDbVertex"),
-                CodePair::new_str(100, "AcDb2dVertex"),
-                CodePair::new_f64(10, 1.3),
-                CodePair::new_f64(20, 2.3),
-                CodePair::new_f64(30, 3.3),
+                CodePair::new_str(2, "ANSI31"),
                 CodePair::new_i16(70, 0),
-                CodePair::new_f64(50, 0.0),
-                CodePair::new_str(0, "SEQEND"), // end sequence
+                CodePair::new_i32(91, 0),
+                CodePair::new_i16(75, 0),
+                CodePair::new_i16(76, 1),
+                CodePair::new_f64(52, 45.0),
+                CodePair::new_f64(41, 2.0),
+                CodePair::new_i16(77, 0),
+                CodePair::new_i16(78, 2), // pattern definition line count
+                CodePair::new_f64(53, 45.0),
+                CodePair::new_f64(43, 0.0),
+                CodePair::new_f64(44, 0.0),
+                CodePair::new_f64(45, -0.5),
+                CodePair::new_f64(46, 0.5),
+                CodePair::new_i16(79, 2),
+                CodePair::new_f64(49, 0.25),
+                CodePair::new_f64(49, -0.125),
+                CodePair::new_f64(53, 135.0),
+                CodePair::new_f64(43, 1.0),
+                CodePair::new_f64(44, 2.0),
+                CodePair::new_f64(45, 3.0),
+                CodePair::new_f64(46, 4.0),
+                CodePair::new_i16(79, 0),
+                CodePair::new_f64(47, 0.01),
+                CodePair::new_i32(98, 0),
+                CodePair::new_i32(450, 1),
+                CodePair::new_i32(451, 0),
+                CodePair::new_i32(452, 0),
+                CodePair::new_i32(453, 2),
+                CodePair::new_f64(463, 0.0),
+                CodePair::new_i16(63, 5),
+                CodePair::new_i32(421, 255),
+                CodePair::new_f64(463, 1.0),
+                CodePair::new_i32(421, 16_776_960),
+                CodePair::new_f64(460, 1.5),
+                CodePair::new_f64(461, 0.0),
+                CodePair::new_f64(462, 1.0),
+                CodePair::new_str(470, "LINEAR"),
             ],
         );
+        match ent.specific {
+            EntityType::Hatch(ref hatch) => {
+                assert_eq!("ANSI31", hatch.pattern_name);
+                assert!(!hatch.is_solid_fill);
+                assert!(approx_eq!(f64, 45.0, hatch.pattern_angle));
+                assert!(approx_eq!(f64, 2.0, hatch.pattern_scale));
+                assert_eq!(
+                    vec![
+                        HatchPatternDefinitionLine {
+                            angle: 45.0,
+                            base_point: Point::origin(),
+                            offset: Vector::new(-0.5, 0.5, 0.0),
+                            dash_lengths: vec![0.25, -0.125],
+                        },
+                        HatchPatternDefinitionLine {
+                            angle: 135.0,
+                            base_point: Point::new(1.0, 2.0, 0.0),
+                            offset: Vector::new(3.0, 4.0, 0.0),
+                            dash_lengths: vec![],
+                        },
+                    ],
+                    hatch.pattern_definition_lines
+                );
+                assert!(approx_eq!(f64, 0.01, hatch.pixel_size));
+                assert!(hatch.is_gradient);
+                assert!(!hatch.is_single_color_gradient);
+                assert_eq!(
+                    vec![
+                        HatchGradientColor {
+                            value: 0.0,
+                            color: Color::from_index(5),
+                            true_color: 255,
+                        },
+                        HatchGradientColor {
+                            value: 1.0,
+                            color: Color::by_layer(),
+                            true_color: 16_776_960,
+                        },
+                    ],
+                    hatch.gradient_colors
+                );
+                assert!(approx_eq!(f64, 1.5, hatch.gradient_angle));
+                assert!(approx_eq!(f64, 1.0, hatch.gradient_tint));
+                assert_eq!("LINEAR", hatch.gradient_name);
+            }
+            _ => panic!("expected a HATCH"),
+        }
     }
 
     #[test]
-    fn write_3d_polyline() {
+    fn write_hatch() {
+        let mut hatch = Hatch::default();
+        hatch
+            .boundary_paths
+            .push(HatchBoundaryPath::from_vertices(vec![
+                LwPolylineVertex {
+                    x: 1.0,
+                    y: 2.0,
+                    ..Default::default()
+                },
+                LwPolylineVertex {
+                    x: 3.0,
+                    y: 4.0,
+                    ..Default::default()
+                },
+            ]));
         let mut drawing = Drawing::new();
-        drawing.header.version = AcadVersion::R13;
-        let mut poly = Polyline::default();
-        poly.add_vertex(
-            &mut drawing,
-            Vertex {
-                location: Point::new(1.1, 2.1, 3.1),
-                ..Default::default()
-            },
-        );
-        poly.set_is_3d_polyline(true);
-        drawing.add_entity(Entity {
-            common: Default::default(),
-            specific: EntityType::Polyline(poly),
-        });
+        drawing.header.version = AcadVersion::R2000;
+        drawing.add_entity(Entity::new(EntityType::Hatch(hatch)));
         assert_contains_pairs(
             &drawing,
             vec![
-                CodePair::new_str(0, "POLYLINE"), // polyline
-                CodePair::new_str(5, "11"),
-                CodePair::new_str(100, "AcDbEntity"),
-                CodePair::new_str(8, "0"),
-                CodePair::new_str(100, "AcDb3dPolyline"), // 3d = true
-                CodePair::new_i16(66, 1),
-                CodePair::new_f64(10, 0.0),
-                CodePair::new_f64(20, 0.0),
-                CodePair::new_f64(30, 0.0),
-                CodePair::new_i16(70, 8),       // 3d = true
-                CodePair::new_str(0, "VERTEX"), // vertex 1
-                CodePair::new_str(5, "10"),
-                CodePair::new_str(100, "AcDbEntity"),
-                CodePair::new_str(8, "0"),
-                CodePair::new_str(100, "AcDbVertex"),
-                CodePair::new_str(100, "AcDb3dPolylineVertex"), // 3d = true
-                CodePair::new_f64(10, 1.1),
-                CodePair::new_f64(20, 2.1),
-                CodePair::new_f64(30, 3.1),
-                CodePair::new_i16(70, 32), // 3d = true
+                CodePair::new_str(100, "AcDbHatch"),
+                CodePair::new_f64(10, 0.0),
+                CodePair::new_f64(20, 0.0),
+                CodePair::new_f64(30, 0.0),
+                CodePair::new_f64(210, 0.0),
+                CodePair::new_f64(220, 0.0),
+                CodePair::new_f64(230, 1.0),
+                CodePair::new_str(2, "SOLID"),
+                CodePair::new_i16(70, 1),
+                CodePair::new_i16(71, 0),
+                CodePair::new_i32(91, 1),
+                CodePair::new_i32(92, 3),
+                CodePair::new_i16(72, 0),
+                CodePair::new_i16(73, 1),
+                CodePair::new_i32(93, 2),
+                CodePair::new_f64(10, 1.0),
+                CodePair::new_f64(20, 2.0),
+                CodePair::new_f64(10, 3.0),
+                CodePair::new_f64(20, 4.0),
+                CodePair::new_i32(97, 0),
+                CodePair::new_i16(75, 0),
+                CodePair::new_i16(76, 1),
+                CodePair::new_i32(98, 0),
             ],
         );
     }
 
     #[test]
-    fn polyline_seqend_handle_is_assigned_when_added_to_drawing() {
+    fn write_hatch_gradient_only_on_r2004_and_later() {
+        let hatch = Hatch {
+            is_gradient: true,
+            ..Default::default()
+        };
         let mut drawing = Drawing::new();
-        drawing.add_entity(Entity {
-            common: Default::default(),
-            specific: EntityType::Polyline(Polyline::default()),
-        });
-        assert_not_contains_pairs(
-            &drawing,
-            vec![CodePair::new_str(0, "SEQEND"), CodePair::new_str(5, "0")],
-        );
+        drawing.add_entity(Entity::new(EntityType::Hatch(hatch)));
+
+        drawing.header.version = AcadVersion::R2000;
+        assert_not_contains_pairs(&drawing, vec![CodePair::new_i32(450, 1)]);
+
+        drawing.header.version = AcadVersion::R2004;
+        assert_contains_pairs(&drawing, vec![CodePair::new_i32(450, 1)]);
     }
 
-    #[test]
-    fn read_lw_polyline_with_no_vertices() {
-        let drawing = from_section(
-            "ENTITIES",
-            vec![
-                CodePair::new_str(0, "LWPOLYLINE"),
-                CodePair::new_f64(43, 43.0),
+    fn hatch_for_round_trip() -> Hatch {
+        let mut polyline_path = HatchBoundaryPath::from_vertices(vec![
+            LwPolylineVertex {
+                x: 1.0,
+                y: 2.0,
+                bulge: 0.5,
+                ..Default::default()
+            },
+            LwPolylineVertex {
+                x: 3.0,
+                y: 4.0,
+                ..Default::default()
+            },
+        ]);
+        polyline_path.set_is_outermost(true);
+        Hatch {
+            pattern_name: String::from("ANSI31"),
+            is_solid_fill: false,
+            boundary_paths: vec![
+                polyline_path,
+                HatchBoundaryPath::from_edges(vec![
+                    HatchEdge::Line {
+                        start: Point::new(1.0, 2.0, 0.0),
+                        end: Point::new(3.0, 4.0, 0.0),
+                    },
+                    HatchEdge::CircularArc {
+                        center: Point::new(5.0, 6.0, 0.0),
+                        radius: 7.0,
+                        start_angle: 0.0,
+                        end_angle: 90.0,
+                        is_counter_clockwise: true,
+                    },
+                    HatchEdge::EllipticArc {
+                        center: Point::new(8.0, 9.0, 0.0),
+                        major_axis: Vector::new(10.0, 0.0, 0.0),
+                        minor_axis_ratio: 0.5,
+                        start_angle: 0.0,
+                        end_angle: 180.0,
+                        is_counter_clockwise: false,
+                    },
+                    HatchEdge::Spline {
+                        degree_of_curve: 3,
+                        is_rational: true,
+                        is_periodic: false,
+                        knot_values: vec![0.0, 0.0, 1.0, 1.0],
+                        control_points: vec![
+                            Point::new(11.0, 12.0, 0.0),
+                            Point::new(13.0, 14.0, 0.0),
+                        ],
+                        weights: vec![0.25, 0.75],
+                        fit_points: vec![Point::new(15.0, 16.0, 0.0)],
+                        start_tangent: Vector::new(1.0, 0.0, 0.0),
+                        end_tangent: Vector::new(0.0, 1.0, 0.0),
+                    },
+                ]),
             ],
-        );
-        let entities = drawing.entities().collect::<Vec<_>>();
-        assert_eq!(1, entities.len());
-        match entities[0].specific {
-            EntityType::LwPolyline(ref poly) => {
-                assert!(approx_eq!(f64, 43.0, poly.constant_width));
-                assert_eq!(0, poly.vertices.len());
-            }
-            _ => panic!("expected an LWPOLYLINE"),
+            pattern_angle: 45.0,
+            pattern_scale: 2.0,
+            pattern_definition_lines: vec![HatchPatternDefinitionLine {
+                angle: 45.0,
+                base_point: Point::new(1.0, 2.0, 0.0),
+                offset: Vector::new(-0.5, 0.5, 0.0),
+                dash_lengths: vec![0.25, -0.125],
+            }],
+            seed_points: vec![Point::new(5.0, 6.0, 0.0)],
+            is_gradient: true,
+            gradient_colors: vec![
+                HatchGradientColor {
+                    value: 0.0,
+                    color: Color::from_index(5),
+                    true_color: 255,
+                },
+                HatchGradientColor {
+                    value: 1.0,
+                    color: Color::by_layer(),
+                    true_color: 16_776_960,
+                },
+            ],
+            gradient_angle: 1.5,
+            gradient_name: String::from("LINEAR"),
+            ..Default::default()
         }
     }
 
     #[test]
-    fn read_lw_polyline_with_one_vertex() {
-        let drawing = from_section(
-            "ENTITIES",
+    fn round_trip_hatch() {
+        assert_entity_round_trips(
+            AcadVersion::R2010,
+            EntityType::Hatch(hatch_for_round_trip()),
+        );
+    }
+
+    #[test]
+    fn read_mleader_with_nested_groups() {
+        let ent = read_entity(
+            "MULTILEADER",
             vec![
-                CodePair::new_str(0, "LWPOLYLINE"),
-                CodePair::new_f64(43, 43.0), // constant width
-                CodePair::new_f64(10, 1.1),  // vertex 1
-                CodePair::new_f64(20, 2.1),
-                CodePair::new_f64(40, 40.1), // starting width
-                CodePair::new_f64(41, 41.1), // ending width
-                CodePair::new_f64(42, 42.1), // bulge
-                CodePair::new_i32(91, 91),   // id
+                CodePair::new_str(330, "A1"), // owner handle
+                CodePair::new_str(100, "AcDbMLeader"),
+                CodePair::new_i16(270, 2),
+                CodePair::new_str(300, "CONTEXT_DATA{"),
+                CodePair::new_f64(40, 2.0),
+                CodePair::new_f64(41, 0.25),
+                CodePair::new_bool(290, true),
+                CodePair::new_str(304, "leader text"),
+                CodePair::new_str(340, "A2"), // text style
+                CodePair::new_f64(12, 3.0),
+                CodePair::new_f64(22, 4.0),
+                CodePair::new_f64(32, 0.0),
+                CodePair::new_bool(296, false),
+                CodePair::new_str(302, "LEADER{"),
+                CodePair::new_bool(290, true),
+                CodePair::new_bool(291, true),
+                CodePair::new_f64(10, 2.5),
+                CodePair::new_f64(20, 4.0),
+                CodePair::new_f64(30, 0.0),
+                CodePair::new_f64(40, 0.5),
+                CodePair::new_str(304, "LEADER_LINE{"),
+                CodePair::new_f64(10, 0.0),
+                CodePair::new_f64(20, 0.0),
+                CodePair::new_f64(30, 0.0),
+                CodePair::new_f64(10, 1.0),
+                CodePair::new_f64(20, 2.0),
+                CodePair::new_f64(30, 0.0),
+                CodePair::new_i32(91, 7),
+                CodePair::new_str(305, "}"),
+                CodePair::new_i16(271, 1),
+                CodePair::new_str(303, "}"),
+                CodePair::new_i16(272, 10),
+                CodePair::new_str(301, "}"),
+                CodePair::new_str(340, "A3"), // leader style
+                CodePair::new_bool(290, false),
+                CodePair::new_f64(41, 0.75),
+                CodePair::new_i16(172, 2),
+                CodePair::new_i32(94, 0),
+                CodePair::new_str(345, "A4"),
+                CodePair::new_str(330, "A5"), // block attribute definition
+                CodePair::new_i16(177, 1),
+                CodePair::new_f64(44, 1.5),
+                CodePair::new_str(302, "attribute value"),
+                CodePair::new_i16(179, 2),
             ],
         );
-        let entities = drawing.entities().collect::<Vec<_>>();
-        assert_eq!(1, entities.len());
-        match entities[0].specific {
-            EntityType::LwPolyline(ref poly) => {
-                assert!(approx_eq!(f64, 43.0, poly.constant_width));
-                assert_eq!(1, poly.vertices.len());
-                // vertex 1
-                assert!(approx_eq!(f64, 1.1, poly.vertices[0].x));
-                assert!(approx_eq!(f64, 2.1, poly.vertices[0].y));
-                assert!(approx_eq!(f64, 40.1, poly.vertices[0].starting_width));
-                assert!(approx_eq!(f64, 41.1, poly.vertices[0].ending_width));
-                assert!(approx_eq!(f64, 42.1, poly.vertices[0].bulge));
-                assert_eq!(91, poly.vertices[0].id);
+        assert_eq!(Handle(0xa1), ent.common.__owner_handle);
+        match ent.specific {
+            EntityType::MLeader(ref mleader) => {
+                assert_eq!(2, mleader.version);
+                assert_eq!(Handle(0xa3), mleader.__leader_style_handle);
+                assert!(!mleader.enable_landing);
+                assert!(approx_eq!(f64, 0.75, mleader.dogleg_length));
+                assert_eq!(2, mleader.text_attachment_point);
+
+                let context = &mleader.context_data;
+                assert!(approx_eq!(f64, 2.0, context.content_scale));
+                assert!(approx_eq!(f64, 0.25, context.text_height));
+                assert!(context.has_m_text_content);
+                assert_eq!("leader text", context.text);
+                assert_eq!(Handle(0xa2), context.__text_style_handle);
+                assert_eq!(Point::new(3.0, 4.0, 0.0), context.text_location);
+                assert!(!context.has_block_content);
+                assert_eq!(
+                    BottomTextAttachmentDirection::UnderlineAndCenter,
+                    context.bottom_text_attachment_direction
+                );
+
+                assert_eq!(1, context.leaders.len());
+                let leader = &context.leaders[0];
+                assert_eq!(Point::new(2.5, 4.0, 0.0), leader.last_leader_line_point);
+                assert!(approx_eq!(f64, 0.5, leader.dogleg_length));
+                assert_eq!(
+                    TextAttachmentDirection::Vertical,
+                    leader.attachment_direction
+                );
+                assert_eq!(1, leader.leader_lines.len());
+                let leader_line = &leader.leader_lines[0];
+                assert_eq!(
+                    vec![Point::new(0.0, 0.0, 0.0), Point::new(1.0, 2.0, 0.0)],
+                    leader_line.vertices
+                );
+                assert_eq!(7, leader_line.leader_line_index);
+
+                assert_eq!(1, mleader.arrowhead_overrides.len());
+                assert_eq!(
+                    Handle(0xa4),
+                    mleader.arrowhead_overrides[0].__arrowhead_handle
+                );
+                assert_eq!(1, mleader.block_attributes.len());
+                let attribute = &mleader.block_attributes[0];
+                assert_eq!(Handle(0xa5), attribute.__attribute_definition_handle);
+                assert_eq!(1, attribute.index);
+                assert!(approx_eq!(f64, 1.5, attribute.width));
+                assert_eq!("attribute value", attribute.text);
             }
-            _ => panic!("expected an LWPOLYLINE"),
+            _ => panic!("expected a MULTILEADER"),
         }
     }
 
     #[test]
-    fn read_lw_polyline_with_multiple_vertices() {
+    fn read_entity_after_mleader() {
         let drawing = from_section(
             "ENTITIES",
             vec![
-                CodePair::new_str(0, "LWPOLYLINE"),
-                CodePair::new_f64(43, 43.0), // constant width
-                CodePair::new_f64(10, 1.1),  // vertex 1
-                CodePair::new_f64(20, 2.1),
-                CodePair::new_f64(40, 40.1), // starting width
-                CodePair::new_f64(41, 41.1), // ending width
-                CodePair::new_f64(42, 42.1), // bulge
-                CodePair::new_i32(91, 91),   // id
-                CodePair::new_f64(10, 1.2),  // vertex 1
-                CodePair::new_f64(20, 2.2),
-                CodePair::new_f64(40, 40.2), // starting width
-                CodePair::new_f64(41, 41.2), // ending width
-                CodePair::new_f64(42, 42.2), // bulge
-                CodePair::new_i32(91, 92),   // id
+                CodePair::new_str(0, "MULTILEADER"),
+                CodePair::new_str(100, "AcDbMLeader"),
+                CodePair::new_str(300, "CONTEXT_DATA{"),
+                CodePair::new_str(302, "LEADER{"),
+                CodePair::new_str(0, "LINE"), // unterminated groups
             ],
         );
         let entities = drawing.entities().collect::<Vec<_>>();
-        assert_eq!(1, entities.len());
+        assert_eq!(2, entities.len());
         match entities[0].specific {
-            EntityType::LwPolyline(ref poly) => {
-                assert!(approx_eq!(f64, 43.0, poly.constant_width));
-                assert_eq!(2, poly.vertices.len());
-                // vertex 1
-                assert!(approx_eq!(f64, 1.1, poly.vertices[0].x));
-                assert!(approx_eq!(f64, 2.1, poly.vertices[0].y));
-                assert!(approx_eq!(f64, 40.1, poly.vertices[0].starting_width));
-                assert!(approx_eq!(f64, 41.1, poly.vertices[0].ending_width));
-                assert!(approx_eq!(f64, 42.1, poly.vertices[0].bulge));
-                assert_eq!(91, poly.vertices[0].id);
-                // vertex 2
-                assert!(approx_eq!(f64, 1.2, poly.vertices[1].x));
-                assert!(approx_eq!(f64, 2.2, poly.vertices[1].y));
-                assert!(approx_eq!(f64, 40.2, poly.vertices[1].starting_width));
-                assert!(approx_eq!(f64, 41.2, poly.vertices[1].ending_width));
-                assert!(approx_eq!(f64, 42.2, poly.vertices[1].bulge));
-                assert_eq!(92, poly.vertices[1].id);
+            EntityType::MLeader(ref mleader) => {
+                assert_eq!(1, mleader.context_data.leaders.len())
             }
-            _ => panic!("expected an LWPOLYLINE"),
+            _ => panic!("expected a MULTILEADER"),
+        }
+        match entities[1].specific {
+            EntityType::Line(_) => (),
+            _ => panic!("expected a line"),
         }
     }
 
     #[test]
-    fn write_lw_polyline() {
-        let mut drawing = Drawing::new();
-        drawing.header.version = AcadVersion::R2013;
-        let mut poly = LwPolyline::default();
-        poly.constant_width = 43.0;
-        poly.vertices.push(LwPolylineVertex {
-            x: 1.1,
-            y: 2.1,
+    fn write_mleader_nested_groups() {
+        let mut mleader = MLeader::default();
+        mleader.context_data.text = String::from("leader text");
+        mleader.context_data.leaders.push(MLeaderLeader {
+            leader_lines: vec![MLeaderLeaderLine {
+                vertices: vec![Point::new(1.0, 2.0, 0.0)],
+                ..Default::default()
+            }],
             ..Default::default()
         });
-        poly.vertices.push(LwPolylineVertex {
-            x: 1.2,
-            y: 2.2,
-            starting_width: 40.2,
-            ending_width: 41.2,
-            bulge: 42.2,
-            id: 92,
-        });
-        drawing.add_entity(Entity::new(EntityType::LwPolyline(poly)));
+        let mut drawing = Drawing::new();
+        drawing.header.version = AcadVersion::R2007;
+        drawing.add_entity(Entity::new(EntityType::MLeader(mleader)));
         assert_contains_pairs(
             &drawing,
             vec![
-                CodePair::new_str(100, "AcDbPolyline"),
-                CodePair::new_i32(90, 2), // number of vertices
-                CodePair::new_i16(70, 0),
-                CodePair::new_f64(43, 43.0), // constant width
-                CodePair::new_f64(10, 1.1),  // vertex 1
-                CodePair::new_f64(20, 2.1),
+                CodePair::new_str(100, "AcDbMLeader"),
+                CodePair::new_i16(270, 2),
+                CodePair::new_str(300, "CONTEXT_DATA{"),
+            ],
+        );
+        assert_contains_pairs(
+            &drawing,
+            vec![
+                CodePair::new_bool(290, true),
+                CodePair::new_str(304, "leader text"),
+            ],
+        );
+        assert_contains_pairs(
+            &drawing,
+            vec![
+                CodePair::new_str(304, "LEADER_LINE{"),
+                CodePair::new_f64(10, 1.0),
+                CodePair::new_f64(20, 2.0),
+                CodePair::new_f64(30, 0.0),
                 CodePair::new_i32(91, 0),
-                CodePair::new_f64(10, 1.2), // vertex 2
-                CodePair::new_f64(20, 2.2),
-                CodePair::new_i32(91, 92),
-                CodePair::new_f64(40, 40.2), // starting width
-                CodePair::new_f64(41, 41.2), // ending width
-                CodePair::new_f64(42, 42.2), // bulge
+                CodePair::new_str(305, "}"),
+                CodePair::new_str(303, "}"),
+                CodePair::new_str(301, "}"),
             ],
         );
     }
 
     #[test]
-    fn read_dimension() {
-        let ent = read_entity(
-            "DIMENSION",
+    fn write_mleader_attachment_directions_only_on_r2010_and_later() {
+        let mut drawing = Drawing::new();
+        drawing.add_entity(Entity::new(EntityType::MLeader(MLeader::default())));
+
+        drawing.header.version = AcadVersion::R2007;
+        assert_not_contains_pairs(
+            &drawing,
             vec![
-                CodePair::new_str(1, "text"),
-                CodePair::new_str(100, "AcDbOrdinateDimension"),
-                CodePair::new_f64(13, 1.1), // definition_point_2
-                CodePair::new_f64(23, 2.2),
-                CodePair::new_f64(33, 3.3),
-                CodePair::new_f64(14, 4.4), // definition_point_3
-                CodePair::new_f64(24, 5.5),
-                CodePair::new_f64(34, 6.6),
+                CodePair::new_i16(271, 0),
+                CodePair::new_i16(272, 9),
+                CodePair::new_i16(273, 9),
             ],
         );
-        match ent.specific {
-            EntityType::OrdinateDimension(ref dim) => {
-                assert_eq!("text", dim.dimension_base.text);
-                assert_eq!(Point::new(1.1, 2.2, 3.3), dim.definition_point_2);
-                assert_eq!(Point::new(4.4, 5.5, 6.6), dim.definition_point_3);
-            }
-            _ => panic!("expected an ordinate dimension"),
-        }
+
+        drawing.header.version = AcadVersion::R2010;
+        assert_contains_pairs(
+            &drawing,
+            vec![
+                CodePair::new_i16(271, 0),
+                CodePair::new_i16(272, 9),
+                CodePair::new_i16(273, 9),
+            ],
+        );
+    }
+
+    fn mleader_for_round_trip() -> MLeader {
+        let mut mleader = MLeader {
+            content_type: 1,
+            dogleg_length: 0.5,
+            block_content_scale: Vector::new(2.0, 2.0, 1.0),
+            arrowhead_overrides: vec![MLeaderArrowheadOverride {
+                index: 1,
+                __arrowhead_handle: Handle(0xa4),
+            }],
+            block_attributes: vec![MLeaderBlockAttribute {
+                __attribute_definition_handle: Handle(0xa5),
+                index: 2,
+                width: 1.5,
+                text: String::from("attribute value"),
+            }],
+            text_attachment_direction: TextAttachmentDirection::Vertical,
+            ..Default::default()
+        };
+        let context = &mut mleader.context_data;
+        context.has_m_text_content = false;
+        context.has_block_content = true;
+        context.block_content_location = Point::new(5.0, 6.0, 0.0);
+        context.block_content_rotation = 0.25;
+        context.block_content_transformation_matrix = TransformationMatrix::from_vec(&[
+            2.0, 0.0, 0.0, 5.0, 0.0, 2.0, 0.0, 6.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0,
+        ]);
+        context.top_text_attachment_direction = TopTextAttachmentDirection::OverlineAndCenter;
+        context.leaders.push(MLeaderLeader {
+            last_leader_line_point: Point::new(4.0, 6.0, 0.0),
+            break_start_points: vec![Point::new(1.0, 1.0, 0.0)],
+            break_end_points: vec![Point::new(2.0, 2.0, 0.0)],
+            leader_lines: vec![MLeaderLeaderLine {
+                vertices: vec![Point::new(0.0, 0.0, 0.0), Point::new(3.0, 4.0, 0.0)],
+                break_point_indices: vec![0],
+                break_start_points: vec![Point::new(0.5, 0.5, 0.0)],
+                break_end_points: vec![Point::new(1.0, 1.5, 0.0)],
+                leader_line_index: 3,
+                leader_line_color: 256,
+                __arrowhead_handle: Handle(0xa6),
+                override_flags: 1,
+                ..Default::default()
+            }],
+            attachment_direction: TextAttachmentDirection::Vertical,
+            ..Default::default()
+        });
+        mleader
     }
 
     #[test]
-    fn read_entity_after_unsupported_dimension() {
-        let drawing = from_section(
-            "ENTITIES",
+    fn round_trip_mleader() {
+        assert_entity_round_trips(
+            AcadVersion::R2010,
+            EntityType::MLeader(mleader_for_round_trip()),
+        );
+    }
+
+    #[test]
+    fn read_viewport() {
+        let ent = read_entity(
+            "VIEWPORT",
             vec![
-                CodePair::new_str(0, "DIMENSION"),
-                CodePair::new_str(1, "text"),
-                CodePair::new_str(100, "AcDbSomeUnsupportedDimensionType"),
-                CodePair::new_f64(10, 1.1),
-                CodePair::new_f64(20, 2.2),
-                CodePair::new_f64(30, 3.3),
-                CodePair::new_str(0, "LINE"),
+                CodePair::new_i16(67, 1),
+                CodePair::new_str(100, "AcDbViewport"),
+                CodePair::new_f64(10, 1.0),
+                CodePair::new_f64(20, 2.0),
+                CodePair::new_f64(30, 0.0),
+                CodePair::new_f64(40, 3.0),
+                CodePair::new_f64(41, 4.0),
+                CodePair::new_i16(68, 2),
+                CodePair::new_i16(69, 5),
+                CodePair::new_f64(17, 6.0),
+                CodePair::new_f64(27, 7.0),
+                CodePair::new_f64(37, 8.0),
+                CodePair::new_f64(51, 0.5),
+                CodePair::new_str(331, "A1"),
+                CodePair::new_str(331, "A2"),
+                CodePair::new_i32(90, 65537),
+                CodePair::new_str(340, "A3"),
+                CodePair::new_i16(281, 4),
+                CodePair::new_str(345, "A4"),
             ],
         );
-        let entities = drawing.entities().collect::<Vec<_>>();
-        assert_eq!(1, entities.len());
-        match entities[0].specific {
-            EntityType::Line(_) => {}
-            _ => panic!("expected a line"),
+        assert!(ent.common.is_in_paper_space);
+        match ent.specific {
+            EntityType::Viewport(ref vp) => {
+                assert_eq!(Point::new(1.0, 2.0, 0.0), vp.center);
+                assert!(approx_eq!(f64, 3.0, vp.width));
+                assert!(approx_eq!(f64, 4.0, vp.height));
+                assert_eq!(2, vp.status);
+                assert_eq!(5, vp.id);
+                assert_eq!(Point::new(6.0, 7.0, 8.0), vp.view_target_point);
+                assert!(approx_eq!(f64, 0.5, vp.twist_angle));
+                assert_eq!(vec![Handle(0xa1), Handle(0xa2)], vp.__frozen_layers_handle);
+                assert!(vp.is_perspective_view_active());
+                assert!(vp.is_non_rectangular_clipping_on());
+                assert_eq!(Handle(0xa3), vp.__clipping_boundary_handle);
+                assert_eq!(ViewRenderMode::GouraudShaded, vp.render_mode);
+                assert_eq!(Handle(0xa4), vp.__ucs_handle);
+            }
+            _ => panic!("expected a VIEWPORT"),
         }
     }
 
     #[test]
-    fn write_arc() {
-        let arc = Arc::new(Point::new(1.0, 2.0, 3.0), 4.0, 90.0, 180.0);
-        let ent = Entity::new(EntityType::Arc(arc));
+    fn write_viewport() {
         let mut drawing = Drawing::new();
-        drawing.add_entity(ent);
+        drawing.header.version = AcadVersion::R2000;
+        let layer_handle = drawing
+            .add_layer(Layer {
+                name: String::from("frozen"),
+                ..Default::default()
+            })
+            .handle;
+        let viewport = Viewport {
+            center: Point::new(1.0, 2.0, 0.0),
+            width: 3.0,
+            height: 4.0,
+            __frozen_layers_handle: vec![layer_handle],
+            ..Default::default()
+        };
+        let viewport = drawing.add_entity(Entity::new(EntityType::Viewport(viewport)));
+        assert!(viewport.common.is_in_paper_space);
         assert_contains_pairs(
             &drawing,
             vec![
-                CodePair::new_str(100, "AcDbCircle"),
+                CodePair::new_str(100, "AcDbViewport"),
                 CodePair::new_f64(10, 1.0),
                 CodePair::new_f64(20, 2.0),
-                CodePair::new_f64(30, 3.0),
-                CodePair::new_f64(40, 4.0),
-                CodePair::new_str(100, "AcDbArc"),
-                CodePair::new_f64(50, 90.0),
-                CodePair::new_f64(51, 180.0),
+                CodePair::new_f64(30, 0.0),
+                CodePair::new_f64(40, 3.0),
+                CodePair::new_f64(41, 4.0),
+                CodePair::new_i16(68, 1),
+                CodePair::new_i16(69, 2),
+                CodePair::new_f64(12, 0.0),
+            ],
+        );
+        assert_contains_pairs(
+            &drawing,
+            vec![
+                CodePair::new_i16(72, 1000),
+                CodePair::new_string(331, &layer_handle.as_string()),
+                CodePair::new_i32(90, 0),
             ],
         );
     }
 
     #[test]
-    fn write_dimension() {
-        let dim = RadialDimension {
-            dimension_base: DimensionBase {
-                text: String::from("some-text"),
-                ..Default::default()
-            },
-            definition_point_2: Point::new(1.1, 2.2, 3.3),
-            ..Default::default()
-        };
-        let ent = Entity::new(EntityType::RadialDimension(dim));
+    fn write_viewport_r12() {
         let mut drawing = Drawing::new();
-        drawing.add_entity(ent);
-        assert_contains_pairs(&drawing, vec![CodePair::new_str(0, "DIMENSION")]);
-        assert_contains_pairs(&drawing, vec![CodePair::new_str(1, "some-text")]);
+        drawing.header.version = AcadVersion::R12;
+        drawing.add_entity(Entity::new(EntityType::Viewport(Viewport::default())));
         assert_contains_pairs(
             &drawing,
             vec![
-                CodePair::new_str(100, "AcDbRadialDimension"),
-                CodePair::new_f64(15, 1.1), // definition_point_2
-                CodePair::new_f64(25, 2.2),
-                CodePair::new_f64(35, 3.3),
-                CodePair::new_f64(40, 0.0), // leader_length
+                CodePair::new_str(0, "VIEWPORT"),
+                CodePair::new_str(5, "10"),
+                CodePair::new_i16(67, 1),
+                CodePair::new_str(8, "0"),
+                CodePair::new_f64(10, 0.0),
+            ],
+        );
+        assert_not_contains_pairs(&drawing, vec![CodePair::new_str(100, "AcDbViewport")]);
+        assert_contains_pairs(
+            &drawing,
+            vec![
+                CodePair::new_i16(68, 1),
+                CodePair::new_i16(69, 2),
+                CodePair::new_str(0, "ENDSEC"),
             ],
         );
     }
 
     #[test]
-    fn read_insert_with_separate_attributes() {
-        let file = from_section(
-            "ENTITIES",
+    fn read_mesh() {
+        let ent = read_entity(
+            "MESH",
             vec![
-                CodePair::new_str(0, "INSERT"),
-                CodePair::new_i16(66, 0),       // no attributes
-                CodePair::new_str(0, "ATTRIB"), // this is a separate attribute, not tiee to the `INSERT` entity
-                CodePair::new_str(0, "SEQEND"), // this is a separate `SEQEND` entity, not tiee to the `INSERT` entity
+                CodePair::new_str(100, "AcDbEntity"),
+                CodePair::new_str(100, "AcDbSubDMesh"),
+                CodePair::new_i16(71, 2),
+                CodePair::new_i16(72, 1),
+                CodePair::new_i32(91, 3),
+                CodePair::new_i32(92, 3), // vertex count
+                CodePair::new_f64(10, 0.0),
+                CodePair::new_f64(20, 0.0),
+                CodePair::new_f64(30, 0.0),
+                CodePair::new_f64(10, 1.0),
+                CodePair::new_f64(20, 0.0),
+                CodePair::new_f64(30, 0.0),
+                CodePair::new_f64(10, 0.0),
+                CodePair::new_f64(20, 1.0),
+                CodePair::new_f64(30, 2.0),
+                CodePair::new_i32(93, 4), // face list size
+                CodePair::new_i32(90, 3),
+                CodePair::new_i32(90, 0),
+                CodePair::new_i32(90, 1),
+                CodePair::new_i32(90, 2),
+                CodePair::new_i32(94, 2), // edge count
+                CodePair::new_i32(90, 0),
+                CodePair::new_i32(90, 1),
+                CodePair::new_i32(90, 1),
+                CodePair::new_i32(90, 2),
+                CodePair::new_i32(95, 2), // crease count
+                CodePair::new_f64(140, 0.5),
+                CodePair::new_f64(140, 1.5),
+                CodePair::new_i32(90, 1), // property override count
+                CodePair::new_i32(91, 0),
+                CodePair::new_i32(92, 1),
+                CodePair::new_i32(90, 0),
+                CodePair::new_i16(62, 1),
             ],
         );
-        let entities = file.entities().collect::<Vec<_>>();
-        assert_eq!(3, entities.len());
-        match entities[0].specific {
-            EntityType::Insert(_) => (),
-            _ => panic!("expected an INSERT"),
-        }
-        match entities[1].specific {
-            EntityType::Attribute(_) => (),
-            _ => panic!("expected an ATTRIB"),
-        }
-        match entities[2].specific {
-            EntityType::Seqend(_) => (),
-            _ => panic!("expected a SEQEND"),
+        assert_eq!(Color::by_layer(), ent.common.color);
+        match ent.specific {
+            EntityType::Mesh(ref mesh) => {
+                assert_eq!(2, mesh.version);
+                assert!(mesh.is_blend_creased);
+                assert_eq!(3, mesh.subdivision_level);
+                assert_eq!(
+                    vec![
+                        Point::new(0.0, 0.0, 0.0),
+                        Point::new(1.0, 0.0, 0.0),
+                        Point::new(0.0, 1.0, 2.0),
+                    ],
+                    mesh.vertices
+                );
+                assert_eq!(vec![vec![0, 1, 2]], mesh.faces);
+                assert_eq!(vec![(0, 1), (1, 2)], mesh.edges);
+                assert_eq!(vec![0.5, 1.5], mesh.edge_creases);
+                assert_eq!(
+                    vec![MeshSubentityOverride {
+                        subentity_marker: 0,
+                        properties: vec![MeshPropertyOverride {
+                            property_type: 0,
+                            values: vec![CodePair::new_i16(62, 1)],
+                        }],
+                    }],
+                    mesh.subentity_overrides
+                );
+            }
+            _ => panic!("expected a MESH"),
         }
     }
 
     #[test]
-    fn read_insert_with_embedded_attributes() {
-        let file = from_section(
-            "ENTITIES",
-            vec![
-                CodePair::new_str(0, "INSERT"),
-                CodePair::new_i16(66, 1),       // includes attributes
-                CodePair::new_str(0, "ATTRIB"), // these are embedded attributes tied to the `INSERT` enttiy
-                CodePair::new_str(0, "ATTRIB"),
-                CodePair::new_str(0, "SEQEND"), // this is an embedded `SEQEND` entity tied to the `INSERT` entity
+    fn round_trip_mesh_with_subentity_overrides() {
+        let mesh = Mesh {
+            vertices: vec![
+                Point::new(0.0, 0.0, 0.0),
+                Point::new(1.0, 0.0, 0.0),
+                Point::new(0.0, 1.0, 0.0),
             ],
-        );
-        let entities = file.entities().collect::<Vec<_>>();
-        assert_eq!(1, entities.len());
-        match entities[0].specific {
-            EntityType::Insert(ref ins) => assert_eq!(2, ins.attributes().count()),
-            _ => panic!("exepcted an INSERT"),
-        }
+            faces: vec![vec![0, 1, 2]],
+            edges: vec![(0, 1), (1, 2)],
+            edge_creases: vec![0.5, 1.5],
+            subentity_overrides: vec![
+                MeshSubentityOverride {
+                    subentity_marker: 0,
+                    properties: vec![
+                        MeshPropertyOverride {
+                            property_type: 0,
+                            values: vec![CodePair::new_i16(62, 1)],
+                        },
+                        MeshPropertyOverride {
+                            property_type: 2,
+                            values: vec![CodePair::new_i32(440, 0x0200_0080)],
+                        },
+                    ],
+                },
+                MeshSubentityOverride {
+                    subentity_marker: 1,
+                    properties: vec![MeshPropertyOverride {
+                        property_type: 1,
+                        values: vec![CodePair::new_str(347, "A1")],
+                    }],
+                },
+            ],
+            ..Default::default()
+        };
+        assert_entity_round_trips(AcadVersion::R2010, EntityType::Mesh(mesh));
     }
 
     #[test]
-    fn write_insert_no_embedded_attributes() {
+    fn write_mesh() {
         let mut drawing = Drawing::new();
-        let mut ins = Insert::default();
-        ins.name = "insert-name".to_string();
-        let ent = Entity::new(EntityType::Insert(ins));
-        drawing.add_entity(ent);
-        assert_not_contains_pairs(
+        drawing.header.version = AcadVersion::R2010;
+        let mesh = Mesh {
+            vertices: vec![
+                Point::new(0.0, 0.0, 0.0),
+                Point::new(1.0, 0.0, 0.0),
+                Point::new(0.0, 1.0, 0.0),
+            ],
+            faces: vec![vec![0, 1, 2]],
+            edges: vec![(0, 1)],
+            edge_creases: vec![0.5],
+            ..Default::default()
+        };
+        drawing.add_entity(Entity::new(EntityType::Mesh(mesh)));
+        assert_contains_pairs(
             &drawing,
             vec![
-                CodePair::new_i16(66, 0),            // contains no attributes
-                CodePair::new_str(2, "insert-name"), // sentinel to ensure we're reading at the correct location
+                CodePair::new_str(100, "AcDbSubDMesh"),
+                CodePair::new_i16(71, 2),
+                CodePair::new_i16(72, 0),
+                CodePair::new_i32(91, 0),
+                CodePair::new_i32(92, 3),
             ],
         );
-        assert_not_contains_pairs(&drawing, vec![CodePair::new_str(0, "SEQEND")]);
-    }
-
-    #[test]
-    fn write_insert_with_embedded_attributes() {
-        let mut drawing = Drawing::new();
-        drawing.header.version = AcadVersion::R13;
-        let mut ins = Insert::default();
-        ins.add_attribute(&mut drawing, Attribute::default());
-        let ent = Entity::new(EntityType::Insert(ins));
-        drawing.add_entity(ent);
-        assert_contains_pairs(&drawing, vec![CodePair::new_str(0, "INSERT")]);
         assert_contains_pairs(
             &drawing,
             vec![
-                CodePair::new_str(100, "AcDbBlockReference"),
-                CodePair::new_i16(66, 1), // contains attributes
+                CodePair::new_f64(30, 0.0),
+                CodePair::new_i32(93, 4),
+                CodePair::new_i32(90, 3),
+                CodePair::new_i32(90, 0),
+                CodePair::new_i32(90, 1),
+                CodePair::new_i32(90, 2),
+                CodePair::new_i32(94, 1),
+                CodePair::new_i32(90, 0),
+                CodePair::new_i32(90, 1),
+                CodePair::new_i32(95, 1),
+                CodePair::new_f64(140, 0.5),
+                CodePair::new_i32(90, 0),
             ],
         );
-        assert_contains_pairs(&drawing, vec![CodePair::new_str(0, "ATTRIB")]);
-        assert_contains_pairs(&drawing, vec![CodePair::new_str(0, "SEQEND")]);
     }
 
     #[test]
-    fn write_insert_no_extrusion_on_r12() {
+    fn dont_write_mesh_before_r2010() {
         let mut drawing = Drawing::new();
-        drawing.header.version = AcadVersion::R12;
-        let mut ins = Insert::default();
-        ins.extrusion_direction = Vector::new(1.0, 2.0, 3.0);
-        let ent = Entity::new(EntityType::Insert(ins));
-        drawing.add_entity(ent);
-        assert_not_contains_pairs(
-            &drawing,
-            vec![
-                CodePair::new_f64(210, 1.0),
-                CodePair::new_f64(220, 2.0),
-                CodePair::new_f64(230, 3.0),
-            ],
-        );
+        drawing.header.version = AcadVersion::R2007;
+        drawing.add_entity(Entity::new(EntityType::Mesh(Mesh::default())));
+        assert_not_contains_pairs(&drawing, vec![CodePair::new_str(0, "MESH")]);
     }
 
     #[test]
-    fn write_insert_extrusion_on_r13() {
+    fn read_extruded_surface() {
+        let mut body = vec![
+            CodePair::new_str(100, "AcDbEntity"),
+            CodePair::new_f64(48, 2.0), // line type scale
+            CodePair::new_str(100, "AcDbModelerGeometry"),
+            CodePair::new_i16(70, 1),
+            CodePair::new_str(1, "acis line 1"),
+            CodePair::new_str(1, "acis line 2"),
+            CodePair::new_str(100, "AcDbSurface"),
+            CodePair::new_i16(71, 4),
+            CodePair::new_i16(72, 5),
+            CodePair::new_str(100, "AcDbExtrudedSurface"),
+            CodePair::new_i32(90, 6),
+            CodePair::new_f64(10, 0.0),
+            CodePair::new_f64(20, 0.0),
+            CodePair::new_f64(30, 7.0),
+        ];
+        for i in 0..16 {
+            body.push(CodePair::new_f64(40, f64::from(i)));
+        }
+        body.push(CodePair::new_f64(48, 3.0)); // scale factor
+        body.push(CodePair::new_i16(70, 2)); // sweep alignment flags
+        let ent = read_entity("EXTRUDEDSURFACE", body);
+        assert!(approx_eq!(f64, 2.0, ent.common.line_type_scale));
+        match ent.specific {
+            EntityType::ExtrudedSurface(ref surface) => {
+                assert_eq!(1, surface.format_version_number);
+                assert_eq!(vec!["acis line 1", "acis line 2"], surface.custom_data);
+                assert_eq!(4, surface.u_isoline_count);
+                assert_eq!(5, surface.v_isoline_count);
+                assert_eq!(6, surface.class_id);
+                assert_eq!(Vector::new(0.0, 0.0, 7.0), surface.sweep_vector);
+                assert!(approx_eq!(f64, 1.0, surface.transformation_matrix.m12));
+                assert!(approx_eq!(f64, 15.0, surface.transformation_matrix.m44));
+                assert!(approx_eq!(f64, 3.0, surface.scale_factor));
+                assert_eq!(2, surface.sweep_alignment_flags);
+            }
+            _ => panic!("expected an EXTRUDEDSURFACE"),
+        }
+    }
+
+    fn swept_surface_for_round_trip() -> SweptSurface {
+        let mut transformation_matrix = TransformationMatrix::identity();
+        transformation_matrix.m14 = 5.0;
+        SweptSurface {
+            custom_data: vec![String::from("acis data")],
+            u_isoline_count: 2,
+            swept_entity_id: 1,
+            swept_entity_data: vec![vec![0x01, 0x02], vec![0x03]],
+            path_entity_id: 2,
+            path_entity_data: vec![vec![0x04, 0x05, 0x06]],
+            transformation_matrix,
+            scale_factor: 2.5,
+            sweep_alignment_flags: 1,
+            bank: true,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn write_swept_surface_binary_data() {
         let mut drawing = Drawing::new();
-        drawing.header.version = AcadVersion::R13;
-        let mut ins = Insert::default();
-        ins.extrusion_direction = Vector::new(1.0, 2.0, 3.0);
-        let ent = Entity::new(EntityType::Insert(ins));
-        drawing.add_entity(ent);
+        drawing.header.version = AcadVersion::R2007;
+        drawing.add_entity(Entity::new(EntityType::SweptSurface(
+            swept_surface_for_round_trip(),
+        )));
         assert_contains_pairs(
             &drawing,
             vec![
-                CodePair::new_f64(210, 1.0),
-                CodePair::new_f64(220, 2.0),
-                CodePair::new_f64(230, 3.0),
+                CodePair::new_str(100, "AcDbSweptSurface"),
+                CodePair::new_i32(90, 1),
+                CodePair::new_i32(90, 3),
+                CodePair::new_binary(310, vec![0x01, 0x02]),
+                CodePair::new_binary(310, vec![0x03]),
+                CodePair::new_i32(90, 2),
+                CodePair::new_i32(90, 3),
+                CodePair::new_binary(310, vec![0x04, 0x05, 0x06]),
+                CodePair::new_f64(40, 1.0),
             ],
         );
     }
 
     #[test]
-    fn round_trip_insert_with_attributes() {
+    fn round_trip_swept_surface() {
         let mut drawing = Drawing::new();
-        let mut ins = Insert::default();
-        ins.add_attribute(&mut drawing, Attribute::default());
-        let ent = Entity::new(EntityType::Insert(ins));
-        drawing.add_entity(ent);
+        drawing.header.version = AcadVersion::R2007;
+        drawing.add_entity(Entity::new(EntityType::SweptSurface(
+            swept_surface_for_round_trip(),
+        )));
+        assert_round_trips(&drawing, |_, entity| {
+            match entity.specific {
+                EntityType::SweptSurface(ref surface) => {
+                    assert_eq!(&swept_surface_for_round_trip(), surface);
+                }
+                _ => panic!("expected a SWEPTSURFACE"),
+            }
+            assert!(entity.common.preview_image_data.is_empty());
+        });
+    }
 
-        let drawing = drawing_from_pairs(drawing.code_pairs().unwrap());
+    #[test]
+    fn round_trip_revolved_and_lofted_surfaces() {
+        let mut drawing = Drawing::new();
+        drawing.header.version = AcadVersion::R2007;
+        let revolved = RevolvedSurface {
+            axis_point: Point::new(1.0, 2.0, 3.0),
+            revolve_angle: 1.5,
+            draft_angle: 0.25,
+            close_to_axis: true,
+            ..Default::default()
+        };
+        let lofted = LoftedSurface {
+            plane_normal_lofting_type: 3,
+            end_draft_magnitude: 4.0,
+            virtual_guide: true,
+            ..Default::default()
+        };
+        drawing.add_entity(Entity::new(EntityType::RevolvedSurface(revolved.clone())));
+        drawing.add_entity(Entity::new(EntityType::LoftedSurface(lofted.clone())));
 
-        let entities = drawing.entities().collect::<Vec<_>>();
-        assert_eq!(1, entities.len());
+        let round_tripped = parse_drawing(&to_test_string(&drawing));
+        let entities = round_tripped.entities().collect::<Vec<_>>();
+        assert_eq!(2, entities.len());
         match entities[0].specific {
-            EntityType::Insert(ref ins) => assert_eq!(1, ins.attributes().count()),
-            _ => panic!("expected an INSERT"),
+            EntityType::RevolvedSurface(ref surface) => assert_eq!(&revolved, surface),
+            _ => panic!("expected a REVOLVEDSURFACE"),
+        }
+        match entities[1].specific {
+            EntityType::LoftedSurface(ref surface) => assert_eq!(&lofted, surface),
+            _ => panic!("expected a LOFTEDSURFACE"),
         }
     }
 
     #[test]
-    fn read_attribute_with_attached_mtext() {
-        let file = from_section(
-            "ENTITIES",
+    fn read_table() {
+        let ent = read_entity(
+            "ACAD_TABLE",
             vec![
-                CodePair::new_str(0, "ATTRIB"),
-                CodePair::new_str(0, "MTEXT"),
-                CodePair::new_str(1, "m_text"),
+                CodePair::new_str(100, "AcDbEntity"),
+                CodePair::new_str(100, "AcDbBlockReference"),
+                CodePair::new_str(2, "*T1"),
+                CodePair::new_f64(10, 1.0),
+                CodePair::new_f64(20, 2.0),
+                CodePair::new_f64(30, 0.0),
+                CodePair::new_str(100, "AcDbTable"),
+                CodePair::new_i16(280, 0),
+                CodePair::new_str(342, "A1"),
+                CodePair::new_str(343, "A2"),
+                CodePair::new_i32(91, 1),
+                CodePair::new_i32(92, 2),
+                CodePair::new_f64(141, 0.5),
+                CodePair::new_f64(142, 3.0),
+                CodePair::new_f64(142, 4.0),
+                // text cell
+                CodePair::new_i16(171, 1),
+                CodePair::new_i16(173, 1),
+                CodePair::new_i16(175, 2),
+                CodePair::new_i16(176, 1),
+                CodePair::new_i32(91, 0),
+                CodePair::new_str(2, "first "),
+                CodePair::new_str(1, "second"),
+                CodePair::new_str(7, "text-style"),
+                CodePair::new_i16(63, 5),
+                // block cell
+                CodePair::new_i16(171, 2),
+                CodePair::new_i16(173, 1),
+                CodePair::new_str(340, "A3"),
+                CodePair::new_f64(144, 2.0),
+                CodePair::new_i16(179, 1),
+                CodePair::new_str(331, "A4"),
+                CodePair::new_str(300, "attribute-value"),
+                // table overrides
+                CodePair::new_i16(280, 1),
+                CodePair::new_f64(40, 0.25),
             ],
         );
-        let entities = file.entities().collect::<Vec<_>>();
-        assert_eq!(1, entities.len());
-        match entities[0].specific {
-            EntityType::Attribute(ref att) => assert_eq!("m_text", att.m_text.text),
-            _ => panic!("exepcted an attribute"),
+        match ent.specific {
+            EntityType::Table(ref table) => {
+                assert_eq!("*T1", table.block_name);
+                assert_eq!(Point::new(1.0, 2.0, 0.0), table.insertion_point);
+                assert_eq!(0, table.version);
+                assert_eq!(Handle(0xa1), table.__table_style_handle);
+                assert_eq!(Handle(0xa2), table.__block_record_handle);
+                assert_eq!(1, table.row_count());
+                assert_eq!(2, table.column_count());
+                assert_eq!(2, table.cells.len());
+
+                let text_cell = table.cell(0, 0).unwrap();
+                assert_eq!(TableCellType::Text, text_cell.cell_type);
+                assert_eq!("first second", text_cell.text);
+                assert_eq!(
+                    Some(String::from("text-style")),
+                    text_cell.text_style_name_override
+                );
+                assert_eq!(Some(Color::from_index(5)), text_cell.fill_color_override);
+                assert_eq!(None, text_cell.text_height_override);
+
+                let block_cell = table.cell(0, 1).unwrap();
+                assert_eq!(TableCellType::Block, block_cell.cell_type);
+                assert_eq!(Handle(0xa3), block_cell.__block_record_handle);
+                assert!(approx_eq!(f64, 2.0, block_cell.block_scale));
+                assert_eq!(1, block_cell.attributes.len());
+                assert_eq!(
+                    Handle(0xa4),
+                    block_cell.attributes[0].__attribute_definition_handle
+                );
+                assert_eq!("attribute-value", block_cell.attributes[0].value);
+
+                assert_eq!(vec![TableCellRange::new(0, 0, 0, 1)], table.merged_ranges());
+                assert_eq!(Some(true), table.is_title_suppressed_override);
+                assert_eq!(Some(0.25), table.horizontal_cell_margin_override);
+                assert_eq!(None, table.vertical_cell_margin_override);
+            }
+            _ => panic!("expected an ACAD_TABLE"),
         }
     }
 
     #[test]
-    fn write_attribute_with_attached_mtext() {
+    fn write_table() {
         let mut drawing = Drawing::new();
-        drawing.header.version = AcadVersion::R13; // MTEXT is only written on R13+
-        drawing.add_entity(Entity::new(EntityType::Attribute(Default::default())));
-        assert_contains_pairs(&drawing, vec![CodePair::new_str(0, "ATTRIB")]);
-        assert_contains_pairs(&drawing, vec![CodePair::new_str(0, "MTEXT")]);
+        drawing.header.version = AcadVersion::R2007;
+        let mut table = Table::new(1, 1, 0.5, 2.0);
+        table.cell_mut(0, 0).unwrap().text = "x".repeat(260);
+        table.cell_mut(0, 0).unwrap().text_height_override = Some(0.2);
+        drawing.add_entity(Entity::new(EntityType::Table(table)));
+        assert_contains_pairs(
+            &drawing,
+            vec![
+                CodePair::new_i32(90, 0),
+                CodePair::new_i32(91, 1),
+                CodePair::new_i32(92, 1),
+                CodePair::new_i32(93, 0),
+                CodePair::new_i32(94, 0),
+                CodePair::new_i32(95, 0),
+                CodePair::new_i32(96, 0),
+                CodePair::new_f64(141, 0.5),
+                CodePair::new_f64(142, 2.0),
+                CodePair::new_i16(171, 1),
+                CodePair::new_i16(172, 0),
+                CodePair::new_i16(173, 0),
+                CodePair::new_i16(174, 0),
+                CodePair::new_i16(175, 1),
+                CodePair::new_i16(176, 1),
+                CodePair::new_i32(91, 0),
+                CodePair::new_i16(178, 0),
+                CodePair::new_f64(145, 0.0),
+                CodePair::new_string(2, &"x".repeat(250)),
+                CodePair::new_string(1, &"x".repeat(10)),
+                CodePair::new_f64(140, 0.2),
+            ],
+        );
     }
 
     #[test]
-    fn round_trip_attribute_with_attached_mtext() {
-        let att = Attribute {
-            m_text: MText {
-                text: String::from("m_text"),
-                ..Default::default()
-            },
-            ..Default::default()
-        };
+    fn dont_write_table_before_r2007() {
         let mut drawing = Drawing::new();
-        drawing.header.version = AcadVersion::R13; // MTEXT is only written on R13+
-        drawing.add_entity(Entity::new(EntityType::Attribute(att)));
+        drawing.header.version = AcadVersion::R2004;
+        drawing.add_entity(Entity::new(EntityType::Table(Table::new(1, 1, 1.0, 1.0))));
+        assert_not_contains_pairs(&drawing, vec![CodePair::new_str(0, "ACAD_TABLE")]);
+    }
 
-        let drawing = drawing_from_pairs(drawing.code_pairs().unwrap());
+    #[test]
+    fn merge_table_cells() {
+        let mut table = Table::new(3, 3, 1.0, 1.0);
+        table.merge_cells(TableCellRange::new(1, 1, 2, 2));
+        assert_eq!(vec![TableCellRange::new(1, 1, 2, 2)], table.merged_ranges());
+        assert!(table.cell(2, 2).unwrap().is_merged);
+        assert!(!table.cell(0, 0).unwrap().is_merged);
+        assert_eq!(2, table.cell(1, 1).unwrap().merged_row_count);
+        assert_eq!(2, table.cell(1, 1).unwrap().merged_column_count);
+        assert!(table.cell(0, 3).is_none());
+        assert!(table.cell(3, 0).is_none());
+    }
 
-        let entities = drawing.entities().collect::<Vec<_>>();
-        assert_eq!(1, entities.len());
-        match entities[0].specific {
-            EntityType::Attribute(ref att) => assert_eq!("m_text", att.m_text.text),
-            _ => panic!("expected a attribute"),
-        }
+    fn table_for_round_trip(drawing: &mut Drawing) -> Table {
+        let table_style =
+            drawing.add_object(Object::new(ObjectType::TableStyle(TableStyle::default())));
+        let mut table = Table::new(2, 2, 0.5, 3.0);
+        table.set_table_style(table_style).unwrap();
+        table.cell_mut(0, 0).unwrap().text = String::from("Part");
+        table.cell_mut(0, 1).unwrap().text = String::from("Quantity");
+        table.cell_mut(1, 0).unwrap().text = String::from("Bolt");
+        table.cell_mut(1, 0).unwrap().alignment_override = Some(5);
+        table.cell_mut(1, 1).unwrap().cell_type = TableCellType::Block;
+        table.cell_mut(1, 1).unwrap().block_scale = 0.5;
+        table
+            .cell_mut(1, 1)
+            .unwrap()
+            .attributes
+            .push(TableCellAttribute {
+                value: String::from("12"),
+                ..Default::default()
+            });
+        table.merge_cells(TableCellRange::new(0, 0, 0, 1));
+        table.flow_direction_override = Some(FlowDirection::Up);
+        table
+    }
+
+    #[test]
+    fn round_trip_table() {
+        let mut drawing = Drawing::new();
+        drawing.header.version = AcadVersion::R2007;
+        let expected = table_for_round_trip(&mut drawing);
+        drawing.add_entity(Entity::new(EntityType::Table(expected.clone())));
+        assert_round_trips(&drawing, |drawing, entity| match entity.specific {
+            EntityType::Table(ref table) => {
+                assert_eq!(&expected, table);
+                match table.table_style(drawing) {
+                    Some(Object {
+                        specific: ObjectType::TableStyle(_),
+                        ..
+                    }) => (),
+                    _ => panic!("expected a table style"),
+                }
+            }
+            _ => panic!("expected an ACAD_TABLE"),
+        });
     }
 
     #[test]
@@ -3167,11 +6582,11 @@ mod tests {
                 common,
                 specific: expected_type,
             });
-            // 3DLINE writes as a LINE
-            let type_string = if type_string == "3DLINE" {
-                "LINE"
-            } else {
-                type_string
+            // 3DLINE writes as a LINE and MLEADER writes as a MULTILEADER
+            let type_string = match type_string {
+                "3DLINE" => "LINE",
+                "MLEADER" => "MULTILEADER",
+                _ => type_string,
             };
             assert_contains_pairs(&drawing, vec![CodePair::new_str(0, type_string)]);
             if max_version >= AcadVersion::R14 {
@@ -3235,4 +6650,31 @@ mod tests {
         assert_eq!("STANDARD", dim_styles[1].name);
         assert_eq!("style name", dim_styles[2].name);
     }
+
+    #[test]
+    fn entity_ocs_to_wcs_uses_normal() {
+        let circle = Circle {
+            center: Point::new(1.0, 2.0, 3.0),
+            normal: Vector::new(0.0, 0.0, -1.0),
+            ..Default::default()
+        };
+        let entity = Entity::new(EntityType::Circle(circle));
+        let wcs = entity.ocs_to_wcs(&Point::new(1.0, 2.0, 3.0));
+        assert_eq!(Point::new(-1.0, 2.0, -3.0), wcs);
+        assert_eq!(Point::new(1.0, 2.0, 3.0), entity.wcs_to_ocs(&wcs));
+    }
+
+    #[test]
+    fn entity_ocs_to_wcs_without_ocs() {
+        let line = Entity::new(EntityType::Line(Line::default()));
+        assert_eq!(None, line.specific.ocs_normal());
+        assert_eq!(
+            Point::new(1.0, 2.0, 3.0),
+            line.ocs_to_wcs(&Point::new(1.0, 2.0, 3.0))
+        );
+
+        let mut poly = Polyline::default();
+        poly.set_is_3d_polyline(true);
+        assert_eq!(None, EntityType::Polyline(poly).ocs_normal());
+    }
 }
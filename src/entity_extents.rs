@@ -0,0 +1,907 @@
+// `Entity::bounding_box()`; other implementation is in `entity.rs` and `generated/entities.rs`
+
+use std::f64::consts::PI;
+
+use crate::{
+    BoundingBox, Drawing, HatchBoundaryPath, HatchEdge, LwPolylineVertex, Point,
+    TransformationMatrix, Vector,
+};
+
+use crate::entities::*;
+use crate::enums::*;
+use crate::exploded_entity_iter::MAX_BLOCK_DEPTH;
+
+/// The width of a single character relative to the text height, used to estimate the extents of text.
+const CHARACTER_WIDTH_FACTOR: f64 = 1.0;
+
+impl Entity {
+    /// Returns the world coordinate extents of the entity, or `None` if it has no extents that can be computed.
+    ///
+    /// Arcs, ellipses, and polyline bulges are bounded exactly and splines are bounded by their control points.  Text
+    /// extents are estimated from the text height and character count.  Block references (`Insert`) need the
+    /// drawing to resolve their block and return `None`; use `bounding_box_with_blocks()` for those.  Infinite
+    /// entities (`Ray`, `XLine`) and entities whose geometry is stored in opaque data also return `None`.
+    pub fn bounding_box(&self) -> Option<BoundingBox> {
+        let mut bounds = Bounds::default();
+        bounds.add_entity(&self.specific);
+        bounds.result
+    }
+    /// Returns the world coordinate extents of the entity, including the contents of any block it references.
+    pub fn bounding_box_with_blocks(&self, drawing: &Drawing) -> Option<BoundingBox> {
+        self.bounding_box_in_drawing(drawing, &TransformationMatrix::identity(), 0)
+    }
+}
+
+// internal visibility only
+impl Entity {
+    pub(crate) fn bounding_box_in_drawing(
+        &self,
+        drawing: &Drawing,
+        matrix: &TransformationMatrix,
+        depth: usize,
+    ) -> Option<BoundingBox> {
+        match self.specific {
+            EntityType::Insert(ref insert) => {
+                let mut result = None;
+                if depth < MAX_BLOCK_DEPTH {
+                    if let Some(block) = drawing.block_by_name(&insert.name) {
+                        for transformation in insert.block_transformations(&block.base_point) {
+                            let transformation = matrix.multiply(&transformation);
+                            for entity in &block.entities {
+                                result = BoundingBox::union_option(
+                                    result,
+                                    entity.bounding_box_in_drawing(
+                                        drawing,
+                                        &transformation,
+                                        depth + 1,
+                                    ),
+                                );
+                            }
+                        }
+                    }
+                }
+                for att in insert.attributes() {
+                    let att = Entity::new(EntityType::Attribute(att.clone()));
+                    result = BoundingBox::union_option(
+                        result,
+                        att.bounding_box_in_drawing(drawing, matrix, depth),
+                    );
+                }
+                result
+            }
+            _ => {
+                let own = if *matrix == TransformationMatrix::identity() {
+                    self.bounding_box()
+                } else {
+                    let mut transformed = self.clone();
+                    transformed.transform(matrix);
+                    transformed.bounding_box()
+                };
+                let block_name = match self.specific {
+                    EntityType::RotatedDimension(ref dim) => Some(&dim.dimension_base.block_name),
+                    EntityType::RadialDimension(ref dim) => Some(&dim.dimension_base.block_name),
+                    EntityType::DiameterDimension(ref dim) => Some(&dim.dimension_base.block_name),
+                    EntityType::AngularThreePointDimension(ref dim) => {
+                        Some(&dim.dimension_base.block_name)
+                    }
+                    EntityType::OrdinateDimension(ref dim) => Some(&dim.dimension_base.block_name),
+                    _ => None,
+                };
+                // the anonymous block of a dimension holds its rendered geometry in world coordinates
+                let rendered = match block_name.and_then(|name| drawing.block_by_name(name)) {
+                    Some(block) if depth < MAX_BLOCK_DEPTH => block
+                        .entities
+                        .iter()
+                        .map(|e| e.bounding_box_in_drawing(drawing, matrix, depth + 1))
+                        .fold(None, BoundingBox::union_option),
+                    _ => None,
+                };
+                BoundingBox::union_option(own, rendered)
+            }
+        }
+    }
+}
+
+/// Accumulates world coordinate extents.
+#[derive(Default)]
+struct Bounds {
+    result: Option<BoundingBox>,
+}
+
+impl Bounds {
+    fn add_point(&mut self, point: &Point) {
+        match self.result {
+            Some(ref mut bounding_box) => bounding_box.add_point(point),
+            None => self.result = Some(BoundingBox::from_point(point)),
+        }
+    }
+    fn add_points<'a, I>(&mut self, points: I)
+    where
+        I: IntoIterator<Item = &'a Point>,
+    {
+        for point in points {
+            self.add_point(point);
+        }
+    }
+    fn add_ocs_point(&mut self, point: &Point, normal: &Vector) {
+        self.add_point(&point.ocs_to_wcs(normal));
+    }
+    /// Adds the points at `center + u * cos(t) + v * sin(t)` for `t` from `start` through `start + sweep` radians.
+    fn add_elliptic_arc(&mut self, center: &Point, u: &Vector, v: &Vector, start: f64, sweep: f64) {
+        let point_at = |t: f64| {
+            let (sin, cos) = t.sin_cos();
+            let offset = u.scaled(cos).plus(&v.scaled(sin));
+            Point::new(
+                center.x + offset.x,
+                center.y + offset.y,
+                center.z + offset.z,
+            )
+        };
+        self.add_point(&point_at(start));
+        self.add_point(&point_at(start + sweep));
+        // each coordinate is extreme where its derivative `-u * sin(t) + v * cos(t)` is zero
+        for (u_value, v_value) in &[(u.x, v.x), (u.y, v.y), (u.z, v.z)] {
+            let t = v_value.atan2(*u_value);
+            for candidate in &[t, t + PI] {
+                if (candidate - start).rem_euclid(PI * 2.0) <= sweep {
+                    self.add_point(&point_at(*candidate));
+                }
+            }
+        }
+    }
+    /// Adds a circular arc in the OCS with the specified normal; angles are in degrees.
+    fn add_ocs_arc(&mut self, center: &Point, radius: f64, start: f64, end: f64, normal: &Vector) {
+        let (x_axis, y_axis) = normal.arbitrary_axes();
+        let mut sweep = (end - start).rem_euclid(360.0);
+        if sweep == 0.0 {
+            sweep = 360.0;
+        }
+        self.add_elliptic_arc(
+            &center.ocs_to_wcs(normal),
+            &x_axis.scaled(radius),
+            &y_axis.scaled(radius),
+            start.to_radians(),
+            sweep.to_radians(),
+        );
+    }
+    /// Adds a polyline in the OCS, including the arcs described by the bulges.
+    fn add_ocs_polyline(&mut self, vertices: &[(Point, f64)], is_closed: bool, normal: &Vector) {
+        for (i, (point, bulge)) in vertices.iter().enumerate() {
+            self.add_ocs_point(point, normal);
+            let next = if i + 1 < vertices.len() {
+                &vertices[i + 1].0
+            } else if is_closed && vertices.len() > 1 {
+                &vertices[0].0
+            } else {
+                continue;
+            };
+            if *bulge != 0.0 {
+                self.add_bulge(point, next, *bulge, normal);
+            }
+        }
+    }
+    fn add_bulge(&mut self, start: &Point, end: &Point, bulge: f64, normal: &Vector) {
+        let (dx, dy) = (end.x - start.x, end.y - start.y);
+        let chord = (dx * dx + dy * dy).sqrt();
+        if chord == 0.0 {
+            return;
+        }
+        let included_angle = 4.0 * bulge.atan();
+        // the center lies on the perpendicular bisector of the chord, to the left for counter-clockwise arcs
+        let distance = (chord / 2.0) / (included_angle / 2.0).tan();
+        let center = Point::new(
+            (start.x + end.x) / 2.0 - dy / chord * distance,
+            (start.y + end.y) / 2.0 + dx / chord * distance,
+            start.z,
+        );
+        let radius = ((start.x - center.x).powi(2) + (start.y - center.y).powi(2)).sqrt();
+        let start_angle = (start.y - center.y).atan2(start.x - center.x).to_degrees();
+        let end_angle = (end.y - center.y).atan2(end.x - center.x).to_degrees();
+        if bulge > 0.0 {
+            self.add_ocs_arc(&center, radius, start_angle, end_angle, normal);
+        } else {
+            self.add_ocs_arc(&center, radius, end_angle, start_angle, normal);
+        }
+    }
+    /// Adds a rectangle in the OCS with one corner at `origin`, rotated by `rotation` degrees.
+    fn add_ocs_rectangle(
+        &mut self,
+        origin: &Point,
+        rotation: f64,
+        x: (f64, f64),
+        y: (f64, f64),
+        normal: &Vector,
+    ) {
+        let (sin, cos) = rotation.to_radians().sin_cos();
+        for dx in &[x.0, x.1] {
+            for dy in &[y.0, y.1] {
+                let corner = Point::new(
+                    origin.x + dx * cos - dy * sin,
+                    origin.y + dx * sin + dy * cos,
+                    origin.z,
+                );
+                self.add_ocs_point(&corner, normal);
+            }
+        }
+    }
+    #[allow(clippy::too_many_arguments)]
+    fn add_text(
+        &mut self,
+        location: &Point,
+        second_alignment_point: &Point,
+        value: &str,
+        height: f64,
+        rotation: f64,
+        x_scale_factor: f64,
+        horizontal_justification: HorizontalTextJustification,
+        vertical_justification: VerticalTextJustification,
+        normal: &Vector,
+    ) {
+        let width = value.chars().count() as f64 * height * x_scale_factor * CHARACTER_WIDTH_FACTOR;
+        let uses_second_point = horizontal_justification != HorizontalTextJustification::Left
+            || vertical_justification != VerticalTextJustification::Baseline;
+        let (origin, x) = if uses_second_point {
+            match horizontal_justification {
+                HorizontalTextJustification::Right => (second_alignment_point, (-width, 0.0)),
+                HorizontalTextJustification::Aligned | HorizontalTextJustification::Fit => {
+                    self.add_ocs_point(second_alignment_point, normal);
+                    (location, (0.0, width))
+                }
+                HorizontalTextJustification::Left => (second_alignment_point, (0.0, width)),
+                _ => (second_alignment_point, (-width / 2.0, width / 2.0)),
+            }
+        } else {
+            (location, (0.0, width))
+        };
+        let y = match vertical_justification {
+            VerticalTextJustification::Top => (-height, 0.0),
+            VerticalTextJustification::Middle => (-height / 2.0, height / 2.0),
+            _ => (0.0, height),
+        };
+        self.add_ocs_rectangle(origin, rotation, x, y, normal);
+    }
+    fn add_m_text(&mut self, mtext: &MText) {
+        let normal = &mtext.extrusion_direction;
+        let (x_axis, y_axis) = normal.arbitrary_axes();
+        let direction = if mtext.x_axis_direction.length() == 0.0 {
+            let (sin, cos) = mtext.rotation_angle.sin_cos();
+            x_axis.scaled(cos).plus(&y_axis.scaled(sin))
+        } else {
+            mtext.x_axis_direction.normalized()
+        };
+        let up = normal.normalized().cross(&direction);
+        let lines = mtext.full_text();
+        let line_count = lines.matches("\\P").count() + 1;
+        let longest_line = lines
+            .split("\\P")
+            .map(|line| line.chars().count())
+            .max()
+            .unwrap_or(0);
+        let width = if mtext.reference_rectangle_width > 0.0 {
+            mtext.reference_rectangle_width
+        } else if mtext.horizontal_width > 0.0 {
+            mtext.horizontal_width
+        } else {
+            longest_line as f64 * mtext.initial_text_height * CHARACTER_WIDTH_FACTOR
+        };
+        let height = if mtext.vertical_height > 0.0 {
+            mtext.vertical_height
+        } else {
+            line_count as f64 * mtext.initial_text_height * mtext.line_spacing_factor.max(1.0)
+        };
+        let attachment = mtext.attachment_point as i32 - 1;
+        let x_offset = match attachment % 3 {
+            1 => -width / 2.0,
+            2 => -width,
+            _ => 0.0,
+        };
+        let y_offset = match attachment / 3 {
+            0 => -height,
+            1 => -height / 2.0,
+            _ => 0.0,
+        };
+        for dx in &[x_offset, x_offset + width] {
+            for dy in &[y_offset, y_offset + height] {
+                let offset = direction.scaled(*dx).plus(&up.scaled(*dy));
+                self.add_point(&Point::new(
+                    mtext.insertion_point.x + offset.x,
+                    mtext.insertion_point.y + offset.y,
+                    mtext.insertion_point.z + offset.z,
+                ));
+            }
+        }
+    }
+    fn add_hatch_boundary_path(
+        &mut self,
+        path: &HatchBoundaryPath,
+        elevation: f64,
+        normal: &Vector,
+    ) {
+        let at_elevation = |p: &Point| Point::new(p.x, p.y, elevation);
+        let vertices = path
+            .vertices
+            .iter()
+            .map(|v: &LwPolylineVertex| (Point::new(v.x, v.y, elevation), v.bulge))
+            .collect::<Vec<_>>();
+        self.add_ocs_polyline(&vertices, path.is_closed, normal);
+        for edge in &path.edges {
+            match edge {
+                HatchEdge::Line { start, end } => {
+                    self.add_ocs_point(&at_elevation(start), normal);
+                    self.add_ocs_point(&at_elevation(end), normal);
+                }
+                HatchEdge::CircularArc {
+                    center,
+                    radius,
+                    start_angle,
+                    end_angle,
+                    is_counter_clockwise,
+                } => {
+                    // clockwise edges store their angles mirrored
+                    let (start, end) = if *is_counter_clockwise {
+                        (*start_angle, *end_angle)
+                    } else {
+                        (360.0 - *end_angle, 360.0 - *start_angle)
+                    };
+                    self.add_ocs_arc(&at_elevation(center), *radius, start, end, normal);
+                }
+                HatchEdge::EllipticArc {
+                    center,
+                    major_axis,
+                    minor_axis_ratio,
+                    start_angle,
+                    end_angle,
+                    is_counter_clockwise,
+                } => {
+                    let (start, end) = if *is_counter_clockwise {
+                        (*start_angle, *end_angle)
+                    } else {
+                        (360.0 - *end_angle, 360.0 - *start_angle)
+                    };
+                    let (x_axis, y_axis) = normal.arbitrary_axes();
+                    let u = x_axis
+                        .scaled(major_axis.x)
+                        .plus(&y_axis.scaled(major_axis.y));
+                    let v = normal.normalized().cross(&u).scaled(*minor_axis_ratio);
+                    let mut sweep = (end - start).rem_euclid(360.0);
+                    if sweep == 0.0 {
+                        sweep = 360.0;
+                    }
+                    self.add_elliptic_arc(
+                        &at_elevation(center).ocs_to_wcs(normal),
+                        &u,
+                        &v,
+                        start.to_radians(),
+                        sweep.to_radians(),
+                    );
+                }
+                HatchEdge::Spline {
+                    control_points,
+                    fit_points,
+                    ..
+                } => {
+                    for point in control_points.iter().chain(fit_points.iter()) {
+                        self.add_ocs_point(&at_elevation(point), normal);
+                    }
+                }
+            }
+        }
+    }
+    fn add_entity(&mut self, specific: &EntityType) {
+        match specific {
+            EntityType::Face3D(face) => self.add_points(vec![
+                &face.first_corner,
+                &face.second_corner,
+                &face.third_corner,
+                &face.fourth_corner,
+            ]),
+            EntityType::Arc(arc) => self.add_ocs_arc(
+                &arc.center,
+                arc.radius,
+                arc.start_angle,
+                arc.end_angle,
+                &arc.normal,
+            ),
+            EntityType::ArcAlignedText(text) => {
+                let radius = text.arc_radius + text.offset_from_arc + text.text_height;
+                self.add_ocs_arc(
+                    &text.center_point,
+                    radius,
+                    0.0,
+                    360.0,
+                    &text.extrusion_direction,
+                );
+            }
+            EntityType::AttributeDefinition(att) => self.add_text(
+                &att.location,
+                &att.second_alignment_point,
+                &att.value,
+                att.text_height,
+                att.rotation,
+                att.relative_x_scale_factor,
+                att.horizontal_text_justification,
+                att.vertical_text_justification,
+                &att.normal,
+            ),
+            EntityType::Attribute(att) => self.add_text(
+                &att.location,
+                &att.second_alignment_point,
+                &att.value,
+                att.text_height,
+                att.rotation,
+                att.relative_x_scale_factor,
+                att.horizontal_text_justification,
+                att.vertical_text_justification,
+                &att.normal,
+            ),
+            EntityType::Circle(circle) => {
+                self.add_ocs_arc(&circle.center, circle.radius, 0.0, 360.0, &circle.normal)
+            }
+            EntityType::RotatedDimension(dim) => {
+                self.add_dimension_base(&dim.dimension_base);
+                self.add_ocs_point(&dim.insertion_point, &dim.dimension_base.normal);
+                self.add_points(vec![&dim.definition_point_2, &dim.definition_point_3]);
+            }
+            EntityType::RadialDimension(dim) => {
+                self.add_dimension_base(&dim.dimension_base);
+                self.add_point(&dim.definition_point_2);
+            }
+            EntityType::DiameterDimension(dim) => {
+                self.add_dimension_base(&dim.dimension_base);
+                self.add_point(&dim.definition_point_2);
+            }
+            EntityType::AngularThreePointDimension(dim) => {
+                self.add_dimension_base(&dim.dimension_base);
+                self.add_points(vec![
+                    &dim.definition_point_2,
+                    &dim.definition_point_3,
+                    &dim.definition_point_4,
+                ]);
+                self.add_ocs_point(&dim.definition_point_5, &dim.dimension_base.normal);
+            }
+            EntityType::OrdinateDimension(dim) => {
+                self.add_dimension_base(&dim.dimension_base);
+                self.add_points(vec![&dim.definition_point_2, &dim.definition_point_3]);
+            }
+            EntityType::Ellipse(ellipse) => {
+                let v = ellipse
+                    .normal
+                    .normalized()
+                    .cross(&ellipse.major_axis)
+                    .scaled(ellipse.minor_axis_ratio);
+                let mut sweep =
+                    (ellipse.end_parameter - ellipse.start_parameter).rem_euclid(PI * 2.0);
+                if sweep == 0.0 {
+                    sweep = PI * 2.0;
+                }
+                self.add_elliptic_arc(
+                    &ellipse.center,
+                    &ellipse.major_axis,
+                    &v,
+                    ellipse.start_parameter,
+                    sweep,
+                );
+            }
+            EntityType::Hatch(hatch) => {
+                for path in &hatch.boundary_paths {
+                    self.add_hatch_boundary_path(
+                        path,
+                        hatch.elevation_point.z,
+                        &hatch.extrusion_direction,
+                    );
+                }
+            }
+            EntityType::Helix(helix) => {
+                let axis = helix.axis_vector.normalized();
+                let offset = Vector::new(
+                    helix.start_point.x - helix.axis_base_point.x,
+                    helix.start_point.y - helix.axis_base_point.y,
+                    helix.start_point.z - helix.axis_base_point.z,
+                );
+                let start_height = offset.dot(&axis);
+                let end_height = start_height + helix.number_of_turns * helix.turn_height;
+                let (u, v) = axis.arbitrary_axes();
+                for height in &[start_height, end_height] {
+                    let center = axis.scaled(*height);
+                    let center = Point::new(
+                        helix.axis_base_point.x + center.x,
+                        helix.axis_base_point.y + center.y,
+                        helix.axis_base_point.z + center.z,
+                    );
+                    self.add_elliptic_arc(
+                        &center,
+                        &u.scaled(helix.radius),
+                        &v.scaled(helix.radius),
+                        0.0,
+                        PI * 2.0,
+                    );
+                }
+            }
+            EntityType::Image(image) => self.add_image(
+                &image.location,
+                &image.u_vector,
+                &image.v_vector,
+                &image.image_size,
+            ),
+            EntityType::Insert(_) => (), // requires the drawing
+            EntityType::Leader(leader) => self.add_points(&leader.vertices),
+            EntityType::Light(light) => self.add_point(&light.position),
+            EntityType::Line(line) => self.add_points(vec![&line.p1, &line.p2]),
+            EntityType::LwPolyline(poly) => {
+                let vertices = poly
+                    .vertices
+                    .iter()
+                    .map(|v| (Point::new(v.x, v.y, poly.elevation), v.bulge))
+                    .collect::<Vec<_>>();
+                self.add_ocs_polyline(&vertices, poly.is_closed(), &poly.extrusion_direction);
+            }
+            EntityType::Mesh(mesh) => self.add_points(&mesh.vertices),
+            EntityType::MLine(mline) => {
+                self.add_point(&mline.start_point);
+                self.add_points(&mline.vertices);
+            }
+            EntityType::MLeader(mleader) => {
+                let data = &mleader.context_data;
+                for leader in &data.leaders {
+                    self.add_point(&leader.last_leader_line_point);
+                    for line in &leader.leader_lines {
+                        self.add_points(&line.vertices);
+                    }
+                }
+                if data.has_m_text_content {
+                    self.add_point(&data.text_location);
+                }
+                if data.has_block_content {
+                    self.add_point(&data.block_content_location);
+                }
+            }
+            EntityType::MText(mtext) => self.add_m_text(mtext),
+            EntityType::Ole2Frame(frame) => {
+                self.add_points(vec![&frame.upper_left_corner, &frame.lower_right_corner])
+            }
+            EntityType::ModelPoint(point) => self.add_point(&point.location),
+            EntityType::Polyline(poly) => {
+                if poly.is_3d_polyline() || poly.is_3d_polygon_mesh() || poly.is_polyface_mesh() {
+                    for vertex in poly.vertices() {
+                        let is_face_record =
+                            vertex.is_polyface_mesh_vertex() && !vertex.is_3d_polygon_mesh();
+                        if !is_face_record {
+                            self.add_point(&vertex.location);
+                        }
+                    }
+                } else {
+                    let vertices = poly
+                        .vertices()
+                        .map(|v| {
+                            (
+                                Point::new(v.location.x, v.location.y, poly.location.z),
+                                v.bulge,
+                            )
+                        })
+                        .collect::<Vec<_>>();
+                    self.add_ocs_polyline(&vertices, poly.is_closed(), &poly.normal);
+                }
+            }
+            EntityType::RText(text) => self.add_point(&text.insertion_point),
+            EntityType::Section(section) => self.add_points(&section.vertices),
+            EntityType::Shape(shape) => self.add_ocs_rectangle(
+                &shape.location,
+                shape.rotation_angle,
+                (0.0, shape.size * shape.relative_x_scale_factor),
+                (0.0, shape.size),
+                &shape.extrusion_direction,
+            ),
+            EntityType::Solid(solid) => {
+                for corner in &[
+                    &solid.first_corner,
+                    &solid.second_corner,
+                    &solid.third_corner,
+                    &solid.fourth_corner,
+                ] {
+                    self.add_ocs_point(corner, &solid.extrusion_direction);
+                }
+            }
+            EntityType::Spline(spline) => {
+                // a spline lies within the convex hull of its control points
+                self.add_points(&spline.control_points);
+                self.add_points(&spline.fit_points);
+            }
+            EntityType::Table(table) => {
+                let direction = table.horizontal_direction.normalized();
+                let down = direction.cross(&Vector::z_axis());
+                let width = table.column_widths.iter().sum::<f64>();
+                let height = table.row_heights.iter().sum::<f64>();
+                for dx in &[0.0, width] {
+                    for dy in &[0.0, height] {
+                        let offset = direction.scaled(*dx).plus(&down.scaled(*dy));
+                        self.add_point(&Point::new(
+                            table.insertion_point.x + offset.x,
+                            table.insertion_point.y + offset.y,
+                            table.insertion_point.z + offset.z,
+                        ));
+                    }
+                }
+            }
+            EntityType::Text(text) => self.add_text(
+                &text.location,
+                &text.second_alignment_point,
+                &text.value,
+                text.text_height,
+                text.rotation,
+                text.relative_x_scale_factor,
+                text.horizontal_text_justification,
+                text.vertical_text_justification,
+                &text.normal,
+            ),
+            EntityType::Tolerance(tolerance) => self.add_point(&tolerance.insertion_point),
+            EntityType::Trace(trace) => {
+                for corner in &[
+                    &trace.first_corner,
+                    &trace.second_corner,
+                    &trace.third_corner,
+                    &trace.fourth_corner,
+                ] {
+                    self.add_ocs_point(corner, &trace.extrusion_direction);
+                }
+            }
+            EntityType::DgnUnderlay(underlay) => self.add_point(&underlay.insertion_point),
+            EntityType::DwfUnderlay(underlay) => self.add_point(&underlay.insertion_point),
+            EntityType::PdfUnderlay(underlay) => self.add_point(&underlay.insertion_point),
+            EntityType::Vertex(vertex) => self.add_point(&vertex.location),
+            EntityType::Viewport(viewport) => {
+                let half_size = Vector::new(viewport.width / 2.0, viewport.height / 2.0, 0.0);
+                self.add_point(&Point::new(
+                    viewport.center.x - half_size.x,
+                    viewport.center.y - half_size.y,
+                    viewport.center.z,
+                ));
+                self.add_point(&Point::new(
+                    viewport.center.x + half_size.x,
+                    viewport.center.y + half_size.y,
+                    viewport.center.z,
+                ));
+            }
+            EntityType::Wipeout(wipeout) => self.add_image(
+                &wipeout.location,
+                &wipeout.u_vector,
+                &wipeout.v_vector,
+                &wipeout.image_size,
+            ),
+            // infinite
+            EntityType::Ray(_) | EntityType::XLine(_) => (),
+            // geometry is stored in opaque modeler, proxy, or OLE data
+            EntityType::Solid3D(_)
+            | EntityType::ProxyEntity(_)
+            | EntityType::Body(_)
+            | EntityType::OleFrame(_)
+            | EntityType::Region(_)
+            | EntityType::ExtrudedSurface(_)
+            | EntityType::LoftedSurface(_)
+            | EntityType::NurbSurface(_)
+            | EntityType::PlaneSurface(_)
+            | EntityType::RevolvedSurface(_)
+            | EntityType::SweptSurface(_) => (),
+            EntityType::Seqend(_) | EntityType::Unknown { .. } => (),
+        }
+    }
+    fn add_dimension_base(&mut self, dim: &DimensionBase) {
+        self.add_point(&dim.definition_point_1);
+        self.add_ocs_point(&dim.text_mid_point, &dim.normal);
+    }
+    /// Adds the corners of an image whose pixels are spanned by `u` and `v`.
+    fn add_image(&mut self, location: &Point, u: &Vector, v: &Vector, size: &Vector) {
+        for dx in &[0.0, size.x] {
+            for dy in &[0.0, size.y] {
+                let offset = u.scaled(*dx).plus(&v.scaled(*dy));
+                self.add_point(&Point::new(
+                    location.x + offset.x,
+                    location.y + offset.y,
+                    location.z + offset.z,
+                ));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::entities::*;
+    use crate::*;
+
+    fn assert_box_close(expected: (Point, Point), actual: Option<BoundingBox>) {
+        let actual = actual.expect("expected a bounding box");
+        for (e, a) in &[(&expected.0, &actual.min), (&expected.1, &actual.max)] {
+            assert!(
+                (e.x - a.x).abs() < 1e-9 && (e.y - a.y).abs() < 1e-9 && (e.z - a.z).abs() < 1e-9,
+                "expected {:?} but was {:?}",
+                expected,
+                actual
+            );
+        }
+    }
+
+    fn bounding_box(specific: EntityType) -> Option<BoundingBox> {
+        Entity::new(specific).bounding_box()
+    }
+
+    #[test]
+    fn arc_bounding_box() {
+        assert_box_close(
+            (Point::new(0.0, 0.0, 0.0), Point::new(1.0, 1.0, 0.0)),
+            bounding_box(EntityType::Arc(Arc::new(Point::origin(), 1.0, 0.0, 90.0))),
+        );
+        // crossing zero degrees
+        assert_box_close(
+            (Point::new(0.0, -1.0, 0.0), Point::new(1.0, 1.0, 0.0)),
+            bounding_box(EntityType::Arc(Arc::new(Point::origin(), 1.0, 270.0, 90.0))),
+        );
+    }
+
+    #[test]
+    fn circle_with_normal_bounding_box() {
+        let circle = Circle {
+            center: Point::origin(),
+            radius: 2.0,
+            normal: Vector::x_axis(),
+            ..Default::default()
+        };
+        assert_box_close(
+            (Point::new(0.0, -2.0, -2.0), Point::new(0.0, 2.0, 2.0)),
+            bounding_box(EntityType::Circle(circle)),
+        );
+    }
+
+    #[test]
+    fn ellipse_bounding_box() {
+        let ellipse = Ellipse {
+            center: Point::new(1.0, 1.0, 0.0),
+            major_axis: Vector::new(0.0, 2.0, 0.0),
+            normal: Vector::z_axis(),
+            minor_axis_ratio: 0.5,
+            start_parameter: 0.0,
+            end_parameter: std::f64::consts::PI,
+        };
+        // the upper half of the major axis, sweeping through the negative X side
+        assert_box_close(
+            (Point::new(0.0, -1.0, 0.0), Point::new(1.0, 3.0, 0.0)),
+            bounding_box(EntityType::Ellipse(ellipse)),
+        );
+    }
+
+    #[test]
+    fn lw_polyline_bulge_bounding_box() {
+        let mut poly = LwPolyline::default();
+        poly.vertices.push(LwPolylineVertex {
+            x: 0.0,
+            y: 0.0,
+            bulge: 1.0,
+            ..Default::default()
+        });
+        poly.vertices.push(LwPolylineVertex {
+            x: 2.0,
+            y: 0.0,
+            ..Default::default()
+        });
+        // a counter-clockwise half circle below the chord
+        assert_box_close(
+            (Point::new(0.0, -1.0, 0.0), Point::new(2.0, 0.0, 0.0)),
+            bounding_box(EntityType::LwPolyline(poly)),
+        );
+    }
+
+    #[test]
+    fn infinite_entities_have_no_bounding_box() {
+        assert_eq!(None, bounding_box(EntityType::XLine(Default::default())));
+        assert_eq!(None, bounding_box(EntityType::Insert(Default::default())));
+    }
+
+    fn drawing_with_block() -> Drawing {
+        let mut drawing = Drawing::new();
+        let mut block = Block {
+            name: String::from("b"),
+            ..Default::default()
+        };
+        block.entities.push(Entity::new(EntityType::Line(Line::new(
+            Point::new(0.0, 0.0, 0.0),
+            Point::new(1.0, 1.0, 0.0),
+        ))));
+        drawing.add_block(block);
+        let mut nested = Block {
+            name: String::from("nested"),
+            base_point: Point::new(1.0, 0.0, 0.0),
+            ..Default::default()
+        };
+        nested.entities.push(Entity::new(EntityType::Insert(Insert {
+            name: String::from("b"),
+            ..Default::default()
+        })));
+        drawing.add_block(nested);
+        drawing
+    }
+
+    #[test]
+    fn insert_bounding_box_with_blocks() {
+        let drawing = drawing_with_block();
+        let insert = Entity::new(EntityType::Insert(Insert {
+            name: String::from("b"),
+            location: Point::new(10.0, 10.0, 0.0),
+            x_scale_factor: 2.0,
+            y_scale_factor: 2.0,
+            rotation: 90.0,
+            ..Default::default()
+        }));
+        assert_box_close(
+            (Point::new(8.0, 10.0, 0.0), Point::new(10.0, 12.0, 0.0)),
+            insert.bounding_box_with_blocks(&drawing),
+        );
+    }
+
+    #[test]
+    fn nested_and_arrayed_insert_bounding_box() {
+        let drawing = drawing_with_block();
+        let insert = Entity::new(EntityType::Insert(Insert {
+            name: String::from("nested"),
+            column_count: 2,
+            column_spacing: 5.0,
+            ..Default::default()
+        }));
+        // the nested block's base point moves its contents one unit to the left
+        assert_box_close(
+            (Point::new(-1.0, 0.0, 0.0), Point::new(5.0, 1.0, 0.0)),
+            insert.bounding_box_with_blocks(&drawing),
+        );
+    }
+
+    #[test]
+    fn compute_drawing_extents() {
+        let mut drawing = drawing_with_block();
+        assert_eq!(None, drawing.compute_extents());
+        drawing.add_entity(Entity::new(EntityType::Insert(Insert {
+            name: String::from("b"),
+            location: Point::new(-2.0, 0.0, 0.0),
+            ..Default::default()
+        })));
+        drawing.add_entity(Entity::new(EntityType::Circle(Circle::new(
+            Point::new(5.0, 5.0, 0.0),
+            1.0,
+        ))));
+        let mut paper_space = Entity::new(EntityType::Line(Line::new(
+            Point::new(100.0, 100.0, 0.0),
+            Point::new(200.0, 200.0, 0.0),
+        )));
+        paper_space.common.is_in_paper_space = true;
+        drawing.add_entity(paper_space);
+        assert_box_close(
+            (Point::new(-2.0, 0.0, 0.0), Point::new(6.0, 6.0, 0.0)),
+            drawing.compute_extents(),
+        );
+
+        drawing.update_extents();
+        assert_eq!(
+            Point::new(-2.0, 0.0, 0.0),
+            drawing.header.minimum_drawing_extents
+        );
+        assert_eq!(
+            Point::new(6.0, 6.0, 0.0),
+            drawing.header.maximum_drawing_extents
+        );
+        assert_eq!(
+            Point::new(200.0, 200.0, 0.0),
+            drawing.header.paperspace_maximum_drawing_extents
+        );
+    }
+
+    #[test]
+    fn update_extents_of_empty_drawing() {
+        let mut drawing = Drawing::new();
+        drawing.update_extents();
+        assert_eq!(
+            Point::new(1.0e20, 1.0e20, 1.0e20),
+            drawing.header.minimum_drawing_extents
+        );
+        assert_eq!(
+            Point::new(-1.0e20, -1.0e20, -1.0e20),
+            drawing.header.maximum_drawing_extents
+        );
+    }
+}
@@ -1,9 +1,7 @@
 use crate::code_pair_put_back::CodePairPutBack;
 use crate::drawing::AUTO_REPLACE_HANDLE;
 use crate::entities::*;
-use crate::DxfResult;
-
-use itertools::{put_back, PutBack};
+use crate::{CodePair, CodePairValue, DxfResult, Handle, SkippedContent};
 
 pub(crate) struct EntityIter<'a> {
     pub iter: &'a mut CodePairPutBack,
@@ -13,14 +11,60 @@ impl<'a> Iterator for EntityIter<'a> {
     type Item = Entity;
 
     fn next(&mut self) -> Option<Entity> {
-        match Entity::read(self.iter) {
-            Ok(Some(e)) => Some(e),
-            Ok(None) | Err(_) => None,
+        loop {
+            match Entity::read(self.iter) {
+                Ok(Some(e)) => return Some(e),
+                Ok(None) => return None,
+                Err(e) => {
+                    // when lenient, drop the malformed entity and continue with the next one
+                    let result = self
+                        .iter
+                        .recover(e, SkippedContent::Item)
+                        .and_then(|_| self.iter.skip_to_next_item());
+                    if let Err(e) = result {
+                        // leave the error for the section reader instead of silently ending the section
+                        self.iter.put_back(Err(e));
+                        return None;
+                    }
+                }
+            }
         }
     }
 }
 
 impl<'a> EntityIter<'a> {
+    /// Same as `next()`, but entities whose type string is rejected by `reads_type` are skipped without being read.
+    pub(crate) fn next_of_type<F>(&mut self, reads_type: F) -> Option<Entity>
+    where
+        F: Fn(&str) -> bool,
+    {
+        loop {
+            match self.iter.next() {
+                Some(Ok(pair @ CodePair { code: 0, .. })) => {
+                    let is_skipped = match pair.value {
+                        CodePairValue::Str(ref s) => {
+                            s != "ENDSEC" && s != "ENDBLK" && !reads_type(s)
+                        }
+                        _ => false,
+                    };
+                    if !is_skipped {
+                        self.iter.put_back(Ok(pair));
+                        return self.next();
+                    }
+
+                    if let Err(e) = self.iter.skip_to_next_item() {
+                        self.iter.put_back(Err(e));
+                        return None;
+                    }
+                }
+                Some(pair) => {
+                    self.iter.put_back(pair);
+                    return self.next();
+                }
+                None => return None,
+            }
+        }
+    }
     pub(crate) fn read_entities_into_vec(&mut self, entities: &mut Vec<Entity>) -> DxfResult<()> {
         collect_entities(self, entities)
     }
@@ -30,140 +74,145 @@ pub(crate) fn collect_entities<I>(iter: &mut I, entities: &mut Vec<Entity>) -> D
 where
     I: Iterator<Item = Entity>,
 {
-    fn swallow_seqend<I>(iter: &mut PutBack<I>) -> DxfResult<()>
-    where
-        I: Iterator<Item = Entity>,
-    {
-        match iter.next() {
-            Some(Entity {
-                specific: EntityType::Seqend(_),
-                ..
-            }) => (),
-            Some(ent) => iter.put_back(ent),
-            None => (),
-        }
-
-        Ok(())
+    let mut combiner = EntityCombiner::default();
+    while let Some(entity) = combiner.next(|| Ok(iter.next()))? {
+        entities.push(entity);
     }
 
-    fn mtext<I>(iter: &mut PutBack<I>) -> DxfResult<Option<MText>>
+    Ok(())
+}
+
+/// Gathers the entities that trail an entity in the file (e.g., the `ATTRIB`s of an `INSERT`, the `VERTEX`es of a
+/// `POLYLINE`, and their `SEQEND`) into the entity that owns them, one entity at a time.
+#[derive(Clone, Default)]
+pub(crate) struct EntityCombiner {
+    put_back: Option<Entity>,
+}
+
+impl EntityCombiner {
+    /// Returns the next complete entity, using `read_entity` to get the raw entities in file order.
+    pub(crate) fn next<F>(&mut self, mut read_entity: F) -> DxfResult<Option<Entity>>
     where
-        I: Iterator<Item = Entity>,
+        F: FnMut() -> DxfResult<Option<Entity>>,
     {
-        let m_text = match iter.next() {
-            Some(Entity {
-                specific: EntityType::MText(m),
-                ..
-            }) => Some(m),
-            Some(ent) => {
-                iter.put_back(ent);
-                None
-            }
-            None => None,
+        let entity = match self.read(&mut read_entity)? {
+            Some(entity) => entity,
+            None => return Ok(None),
         };
-
-        Ok(m_text)
-    }
-
-    let mut iter = put_back(iter);
-    loop {
-        match iter.next() {
-            Some(Entity {
-                ref common,
-                specific: EntityType::Attribute(ref att),
-            }) => {
-                let mut att = att.clone(); // 27 fields
-                match mtext(&mut iter) {
-                    Ok(Some(m_text)) => att.m_text = m_text,
-                    Ok(None) => (),
-                    Err(e) => return Err(e),
+        let Entity { common, specific } = entity;
+        let specific = match specific {
+            EntityType::Attribute(mut att) => {
+                if let Some(m_text) = self.m_text(&mut read_entity)? {
+                    att.m_text = m_text;
                 }
 
-                entities.push(Entity {
-                    common: common.clone(), // 18 fields
-                    specific: EntityType::Attribute(att),
-                });
+                EntityType::Attribute(att)
             }
-            Some(Entity {
-                ref common,
-                specific: EntityType::AttributeDefinition(ref att),
-            }) => {
-                let mut att = att.clone(); // 27 fields
-                match mtext(&mut iter) {
-                    Ok(Some(m_text)) => att.m_text = m_text,
-                    Ok(None) => (),
-                    Err(e) => return Err(e),
+            EntityType::AttributeDefinition(mut att) => {
+                if let Some(m_text) = self.m_text(&mut read_entity)? {
+                    att.m_text = m_text;
                 }
 
-                entities.push(Entity {
-                    common: common.clone(), // 18 fields
-                    specific: EntityType::AttributeDefinition(att),
-                });
+                EntityType::AttributeDefinition(att)
             }
-            Some(Entity {
-                ref common,
-                specific: EntityType::Insert(ref ins),
-            }) if ins.__has_attributes => {
-                let mut ins = ins.clone(); // 12 fields
+            EntityType::Insert(mut ins) if ins.__has_attributes => {
                 loop {
-                    match iter.next() {
+                    match self.read(&mut read_entity)? {
                         Some(Entity {
+                            common: child,
                             specific: EntityType::Attribute(att),
-                            ..
                         }) => ins
                             .__attributes_and_handles
-                            .push((att, AUTO_REPLACE_HANDLE)),
+                            .push((att, child_handle(child.handle))),
                         Some(ent) => {
                             // stop gathering on any non-ATTRIBUTE
-                            iter.put_back(ent);
+                            self.put_back = Some(ent);
                             break;
                         }
                         None => break,
                     }
                 }
 
-                swallow_seqend(&mut iter)?;
-
-                // and finally keep the INSERT
-                entities.push(Entity {
-                    common: common.clone(), // 18 fields
-                    specific: EntityType::Insert(ins),
-                });
+                ins.__seqend_handle = self.swallow_seqend(&mut read_entity)?;
+                EntityType::Insert(ins)
             }
-            Some(Entity {
-                common,
-                specific: EntityType::Polyline(poly),
-            }) => {
-                let mut poly = poly.clone(); // 13 fields
+            EntityType::Polyline(mut poly) => {
                 loop {
-                    match iter.next() {
+                    match self.read(&mut read_entity)? {
                         Some(Entity {
+                            common: child,
                             specific: EntityType::Vertex(vertex),
-                            ..
                         }) => poly
                             .__vertices_and_handles
-                            .push((vertex, AUTO_REPLACE_HANDLE)),
+                            .push((vertex, child_handle(child.handle))),
                         Some(ent) => {
                             // stop gathering on any non-VERTEX
-                            iter.put_back(ent);
+                            self.put_back = Some(ent);
                             break;
                         }
                         None => break,
                     }
                 }
 
-                swallow_seqend(&mut iter)?;
+                poly.__seqend_handle = self.swallow_seqend(&mut read_entity)?;
+                EntityType::Polyline(poly)
+            }
+            specific => specific,
+        };
+
+        Ok(Some(Entity { common, specific }))
+    }
+}
+
+impl EntityCombiner {
+    fn read<F>(&mut self, read_entity: &mut F) -> DxfResult<Option<Entity>>
+    where
+        F: FnMut() -> DxfResult<Option<Entity>>,
+    {
+        match self.put_back.take() {
+            Some(entity) => Ok(Some(entity)),
+            None => read_entity(),
+        }
+    }
+    /// Reads the `SEQEND` ending a sequence, if present, and returns its handle.
+    fn swallow_seqend<F>(&mut self, read_entity: &mut F) -> DxfResult<Handle>
+    where
+        F: FnMut() -> DxfResult<Option<Entity>>,
+    {
+        match self.read(read_entity)? {
+            Some(Entity {
+                common,
+                specific: EntityType::Seqend(_),
+            }) => return Ok(common.handle),
+            Some(ent) => self.put_back = Some(ent),
+            None => (),
+        }
 
-                // and finally keep the POLYLINE
-                entities.push(Entity {
-                    common: common.clone(), // 18 fields
-                    specific: EntityType::Polyline(poly),
-                });
+        Ok(Handle::empty())
+    }
+    fn m_text<F>(&mut self, read_entity: &mut F) -> DxfResult<Option<MText>>
+    where
+        F: FnMut() -> DxfResult<Option<Entity>>,
+    {
+        match self.read(read_entity)? {
+            Some(Entity {
+                specific: EntityType::MText(m),
+                ..
+            }) => Ok(Some(m)),
+            Some(ent) => {
+                self.put_back = Some(ent);
+                Ok(None)
             }
-            Some(entity) => entities.push(entity),
-            None => break,
+            None => Ok(None),
         }
     }
+}
 
-    Ok(())
+/// Child items without a handle are given one when their owner is added to the drawing.
+fn child_handle(handle: Handle) -> Handle {
+    if handle.is_empty() {
+        AUTO_REPLACE_HANDLE
+    } else {
+        handle
+    }
 }
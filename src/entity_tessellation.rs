@@ -0,0 +1,675 @@
+// `Entity::tessellate()`; other implementation is in `entity.rs` and `generated/entities.rs`
+
+use std::f64::consts::PI;
+
+use crate::{Point, Vector};
+
+use crate::entities::*;
+use crate::enums::*;
+
+/// The largest angle in radians a single segment may turn through, regardless of the tolerances.
+const MAX_SEGMENT_ANGLE: f64 = PI / 2.0;
+
+/// The most segments a single arc is divided into.
+const MAX_ARC_SEGMENTS: usize = 4096;
+
+/// How many times a piece of a spline may be halved while looking for segments within the tolerances.
+const MAX_SUBDIVISION_DEPTH: usize = 12;
+
+impl Entity {
+    /// Returns the entity as polylines of world coordinate points whose segments are no further than `tolerance`
+    /// from the curve they approximate.
+    ///
+    /// `Arc`, `Circle`, `Ellipse`, `Helix`, `Line`, `LwPolyline` (including bulges), `Polyline`, and `Spline` are
+    /// supported; other entities return no polylines.  The last point of a closed curve repeats its first.  Segments
+    /// never turn through more than a quarter turn, so a non-positive `tolerance` still gives a coarse outline.
+    /// Splines fall back to their fit points, or their control points, if their knot values are invalid.  The spline
+    /// frame control points of a spline-fit `Polyline` are used as a B-spline when it has no fit vertices; polygon and
+    /// polyface meshes return no polylines.
+    pub fn tessellate(&self, tolerance: f64) -> Vec<Vec<Point>> {
+        self.tessellate_with_angle_tolerance(tolerance, 0.0)
+    }
+    /// Returns the entity as polylines, like `tessellate()`, where each segment also turns by at most
+    /// `angle_tolerance` degrees from the curve's direction.  A non-positive value doesn't limit the angle.
+    pub fn tessellate_with_angle_tolerance(
+        &self,
+        tolerance: f64,
+        angle_tolerance: f64,
+    ) -> Vec<Vec<Point>> {
+        let tessellator = Tessellator {
+            tolerance,
+            max_angle: if angle_tolerance > 0.0 {
+                angle_tolerance.to_radians().min(MAX_SEGMENT_ANGLE)
+            } else {
+                MAX_SEGMENT_ANGLE
+            },
+        };
+        tessellator
+            .tessellate(&self.specific)
+            .into_iter()
+            .filter(|points| points.len() > 1)
+            .collect()
+    }
+}
+
+struct Tessellator {
+    /// The maximum chordal deviation; ignored if non-positive.
+    tolerance: f64,
+    /// The largest angle in radians a segment may turn through.
+    max_angle: f64,
+}
+
+impl Tessellator {
+    fn tessellate(&self, specific: &EntityType) -> Vec<Vec<Point>> {
+        match specific {
+            EntityType::Arc(arc) => {
+                let mut sweep = (arc.end_angle - arc.start_angle).rem_euclid(360.0);
+                if sweep == 0.0 {
+                    sweep = 360.0;
+                }
+                vec![self.ocs_arc(
+                    &arc.center,
+                    arc.radius,
+                    arc.start_angle.to_radians(),
+                    sweep.to_radians(),
+                    &arc.normal,
+                )]
+            }
+            EntityType::Circle(circle) => vec![closed(self.ocs_arc(
+                &circle.center,
+                circle.radius,
+                0.0,
+                PI * 2.0,
+                &circle.normal,
+            ))],
+            EntityType::Ellipse(ellipse) => {
+                let v = ellipse.normal.normalized().cross(&ellipse.major_axis)
+                    * ellipse.minor_axis_ratio;
+                let sweep = (ellipse.end_parameter - ellipse.start_parameter).rem_euclid(PI * 2.0);
+                let points = if sweep == 0.0 {
+                    closed(self.elliptic_arc(
+                        &ellipse.center,
+                        &ellipse.major_axis,
+                        &v,
+                        ellipse.start_parameter,
+                        PI * 2.0,
+                    ))
+                } else {
+                    self.elliptic_arc(
+                        &ellipse.center,
+                        &ellipse.major_axis,
+                        &v,
+                        ellipse.start_parameter,
+                        sweep,
+                    )
+                };
+                vec![points]
+            }
+            EntityType::Helix(helix) => vec![self.helix(helix)],
+            EntityType::Line(line) => vec![vec![line.p1.clone(), line.p2.clone()]],
+            EntityType::LwPolyline(poly) => {
+                let vertices = poly
+                    .vertices
+                    .iter()
+                    .map(|v| (Point::new(v.x, v.y, poly.elevation), v.bulge))
+                    .collect::<Vec<_>>();
+                vec![self.ocs_polyline(&vertices, poly.is_closed(), &poly.extrusion_direction)]
+            }
+            EntityType::Polyline(poly) => self.polyline(poly),
+            EntityType::Spline(spline) => vec![self.spline(spline)],
+            _ => vec![],
+        }
+    }
+    /// Returns the number of segments that keep an arc with the specified radius and sweep in radians within the
+    /// tolerances.
+    fn arc_segment_count(&self, radius: f64, sweep: f64) -> usize {
+        let mut step = self.max_angle;
+        if self.tolerance > 0.0 && self.tolerance < radius {
+            // the sagitta of a segment spanning `step` is `radius * (1 - cos(step / 2))`
+            step = step.min(2.0 * (1.0 - self.tolerance / radius).acos());
+        }
+        ((sweep.abs() / step).ceil() as usize).clamp(1, MAX_ARC_SEGMENTS)
+    }
+    /// Returns the points at `center + u * cos(t) + v * sin(t)` for `t` from `start` through `start + sweep` radians.
+    fn elliptic_arc(
+        &self,
+        center: &Point,
+        u: &Vector,
+        v: &Vector,
+        start: f64,
+        sweep: f64,
+    ) -> Vec<Point> {
+        // stepping the parameter evenly, the segments deviate no more than those of the circle around the major axis
+        let radius = u.length().max(v.length());
+        let count = self.arc_segment_count(radius, sweep);
+        (0..=count)
+            .map(|i| {
+                let (sin, cos) = (start + sweep * i as f64 / count as f64).sin_cos();
+                center + u * cos + v * sin
+            })
+            .collect()
+    }
+    /// Returns the points of a circular arc in the OCS with the specified normal; angles are in radians and a
+    /// negative sweep is clockwise.
+    fn ocs_arc(
+        &self,
+        center: &Point,
+        radius: f64,
+        start: f64,
+        sweep: f64,
+        normal: &Vector,
+    ) -> Vec<Point> {
+        let (x_axis, y_axis) = normal.arbitrary_axes();
+        self.elliptic_arc(
+            &center.ocs_to_wcs(normal),
+            &(x_axis * radius),
+            &(y_axis * radius),
+            start,
+            sweep,
+        )
+    }
+    /// Returns the points of a polyline in the OCS, including the arcs described by the bulges.
+    fn ocs_polyline(
+        &self,
+        vertices: &[(Point, f64)],
+        is_closed: bool,
+        normal: &Vector,
+    ) -> Vec<Point> {
+        let mut points = match vertices.first() {
+            Some((first, _)) => vec![first.ocs_to_wcs(normal)],
+            None => return vec![],
+        };
+        let segment_count = if is_closed && vertices.len() > 1 {
+            vertices.len()
+        } else {
+            vertices.len() - 1
+        };
+        for i in 0..segment_count {
+            let (start, bulge) = &vertices[i];
+            let end = &vertices[(i + 1) % vertices.len()].0;
+            match self.bulge(start, end, *bulge, normal) {
+                // the arc starts at the previous point
+                Some(arc) => points.extend(arc.into_iter().skip(1)),
+                None => points.push(end.ocs_to_wcs(normal)),
+            }
+        }
+
+        if is_closed {
+            closed(points)
+        } else {
+            points
+        }
+    }
+    /// Returns the points of the arc described by a bulge, or `None` if the segment is straight.
+    fn bulge(&self, start: &Point, end: &Point, bulge: f64, normal: &Vector) -> Option<Vec<Point>> {
+        let (dx, dy) = (end.x - start.x, end.y - start.y);
+        let chord = (dx * dx + dy * dy).sqrt();
+        if bulge == 0.0 || chord == 0.0 {
+            return None;
+        }
+        let included_angle = 4.0 * bulge.atan();
+        // the center lies on the perpendicular bisector of the chord, to the left for counter-clockwise arcs
+        let distance = (chord / 2.0) / (included_angle / 2.0).tan();
+        let center = Point::new(
+            (start.x + end.x) / 2.0 - dy / chord * distance,
+            (start.y + end.y) / 2.0 + dx / chord * distance,
+            start.z,
+        );
+        let radius = ((start.x - center.x).powi(2) + (start.y - center.y).powi(2)).sqrt();
+        let start_angle = (start.y - center.y).atan2(start.x - center.x);
+        let mut points = self.ocs_arc(&center, radius, start_angle, included_angle, normal);
+        // land exactly on the next vertex
+        if let Some(last) = points.last_mut() {
+            *last = end.ocs_to_wcs(normal);
+        }
+        Some(points)
+    }
+    fn helix(&self, helix: &Helix) -> Vec<Point> {
+        let axis = helix.axis_vector.normalized();
+        let offset = &helix.start_point - &helix.axis_base_point;
+        let start_height = offset.dot(&axis);
+        let radial = offset - &axis * start_height;
+        let x_axis = if radial.is_zero() {
+            axis.arbitrary_axes().0
+        } else {
+            radial.normalized()
+        };
+        let y_axis = if helix.is_right_handed {
+            axis.cross(&x_axis)
+        } else {
+            x_axis.cross(&axis)
+        };
+        let sweep = helix.number_of_turns * PI * 2.0;
+        // the segments deviate from the helix as much as their projections deviate from the circle around the axis
+        let count = self.arc_segment_count(helix.radius, sweep);
+        (0..=count)
+            .map(|i| {
+                let angle = sweep * i as f64 / count as f64;
+                let height = start_height + helix.turn_height * angle / (PI * 2.0);
+                let (sin, cos) = angle.sin_cos();
+                &helix.axis_base_point
+                    + &axis * height
+                    + (&x_axis * cos + &y_axis * sin) * helix.radius
+            })
+            .collect()
+    }
+    fn polyline(&self, poly: &Polyline) -> Vec<Vec<Point>> {
+        if poly.is_3d_polygon_mesh() || poly.is_polyface_mesh() {
+            return vec![];
+        }
+
+        // the spline frame is only drawn when there are no fit vertices
+        let (frame, vertices): (Vec<&Vertex>, Vec<&Vertex>) = poly
+            .vertices()
+            .partition(|v| v.is_spline_frame_control_point());
+        if vertices.is_empty() && !frame.is_empty() {
+            let degree = match poly.surface_type {
+                PolylineCurvedAndSmoothSurfaceType::QuadraticBSpline => 2,
+                _ => 3,
+            };
+            let control_points = frame
+                .iter()
+                .map(|v| {
+                    if poly.is_3d_polyline() {
+                        v.location.clone()
+                    } else {
+                        Point::new(v.location.x, v.location.y, poly.location.z)
+                    }
+                })
+                .collect::<Vec<_>>();
+            let points = self.spline(&b_spline(control_points, degree, poly.is_closed()));
+            return if poly.is_3d_polyline() {
+                vec![points]
+            } else {
+                vec![points.iter().map(|p| p.ocs_to_wcs(&poly.normal)).collect()]
+            };
+        }
+
+        if poly.is_3d_polyline() {
+            let mut points = vertices
+                .iter()
+                .map(|v| v.location.clone())
+                .collect::<Vec<_>>();
+            if poly.is_closed() && points.len() > 1 {
+                points.push(points[0].clone());
+            }
+            vec![points]
+        } else {
+            let vertices = vertices
+                .iter()
+                .map(|v| {
+                    (
+                        Point::new(v.location.x, v.location.y, poly.location.z),
+                        v.bulge,
+                    )
+                })
+                .collect::<Vec<_>>();
+            vec![self.ocs_polyline(&vertices, poly.is_closed(), &poly.normal)]
+        }
+    }
+    fn spline(&self, spline: &Spline) -> Vec<Point> {
+        let spans = match spline.knot_spans() {
+            Some(spans) => spans,
+            None if !spline.fit_points.is_empty() => return spline.fit_points.clone(),
+            None => return spline.control_points.clone(),
+        };
+
+        let point_at = |span: usize, t: f64| spline.point_in_span(span, t);
+        let mut points = vec![point_at(spans[0].0, spans[0].1)];
+        for (span, start, end) in spans {
+            // halving only checks the middle of each piece, so start with a piece per possible change of curvature
+            let pieces = spline.degree() + 1;
+            for i in 0..pieces {
+                let piece_start = start + (end - start) * i as f64 / pieces as f64;
+                let piece_end = start + (end - start) * (i + 1) as f64 / pieces as f64;
+                let first = points
+                    .last()
+                    .cloned()
+                    .expect("points start with the first point");
+                self.subdivide(
+                    &|t| point_at(span, t),
+                    (piece_start, &first),
+                    (piece_end, &point_at(span, piece_end)),
+                    0,
+                    &mut points,
+                );
+            }
+        }
+
+        points
+    }
+    /// Adds the points after `start` through `end` of the curve `point_at`, halving the parameter range until the
+    /// segments are within the tolerances.
+    fn subdivide<F: Fn(f64) -> Point>(
+        &self,
+        point_at: &F,
+        start: (f64, &Point),
+        end: (f64, &Point),
+        depth: usize,
+        points: &mut Vec<Point>,
+    ) {
+        let t = (start.0 + end.0) / 2.0;
+        let middle = point_at(t);
+        if depth < MAX_SUBDIVISION_DEPTH && !self.is_within_tolerances(start.1, &middle, end.1) {
+            self.subdivide(point_at, start, (t, &middle), depth + 1, points);
+            self.subdivide(point_at, (t, &middle), end, depth + 1, points);
+        } else {
+            points.push(end.1.clone());
+        }
+    }
+    /// Returns whether the segment from `start` to `end` is close enough to the curve through `middle`.
+    fn is_within_tolerances(&self, start: &Point, middle: &Point, end: &Point) -> bool {
+        let first = middle - start;
+        let second = end - middle;
+        if first.angle_to(&second) > self.max_angle {
+            return false;
+        }
+
+        self.tolerance <= 0.0 || distance_to_segment(middle, start, end) <= self.tolerance
+    }
+}
+
+/// Makes the last point of a closed curve exactly its first.
+fn closed(mut points: Vec<Point>) -> Vec<Point> {
+    if let Some(first) = points.first().cloned() {
+        if let Some(last) = points.last_mut() {
+            *last = first;
+        }
+    }
+    points
+}
+
+fn distance_to_segment(point: &Point, start: &Point, end: &Point) -> f64 {
+    let segment = end - start;
+    let length_squared = segment.length_squared();
+    if length_squared == 0.0 {
+        return point.distance_to(start);
+    }
+    let t = ((point - start).dot(&segment) / length_squared).clamp(0.0, 1.0);
+    point.distance_to(&start.lerp(end, t))
+}
+
+/// Returns a uniform B-spline through the control points, clamped to the first and last control points if it's open.
+fn b_spline(mut control_points: Vec<Point>, degree: usize, is_closed: bool) -> Spline {
+    let degree = degree.min(control_points.len().saturating_sub(1)).max(1);
+    if !is_closed || control_points.len() <= degree {
+        return Spline::from_control_points(degree, control_points);
+    }
+
+    // wrapping the first control points around makes the curve periodic
+    let wrapped = control_points[..degree].to_vec();
+    control_points.extend(wrapped);
+    let knot_values = (0..control_points.len() + degree + 1)
+        .map(|i| i as f64)
+        .collect();
+    Spline {
+        degree_of_curve: degree as i32,
+        control_points,
+        knot_values,
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::entities::*;
+    use crate::enums::*;
+    use crate::*;
+    use std::f64::consts::PI;
+
+    fn assert_close(expected: &Point, actual: &Point) {
+        assert!(
+            expected.distance_to(actual) < 1e-9,
+            "expected {:?}, got {:?}",
+            expected,
+            actual
+        );
+    }
+
+    /// Asserts that every point is `radius` from `center`.
+    fn assert_on_circle(points: &[Point], center: &Point, radius: f64) {
+        for point in points {
+            assert!(
+                (point.distance_to(center) - radius).abs() < 1e-9,
+                "{:?} isn't on the circle",
+                point
+            );
+        }
+    }
+
+    /// Returns the largest distance from the middle of a segment to a circle.
+    fn max_circle_deviation(points: &[Point], center: &Point, radius: f64) -> f64 {
+        points
+            .windows(2)
+            .map(|w| radius - w[0].midpoint(&w[1]).distance_to(center))
+            .fold(0.0, f64::max)
+    }
+
+    fn single(entity: EntityType, tolerance: f64) -> Vec<Point> {
+        let mut polylines = Entity::new(entity).tessellate(tolerance);
+        assert_eq!(1, polylines.len());
+        polylines.remove(0)
+    }
+
+    #[test]
+    fn tessellate_circle_within_tolerance() {
+        let center = Point::new(1.0, 2.0, 0.0);
+        let points = single(EntityType::Circle(Circle::new(center.clone(), 10.0)), 0.01);
+        assert_on_circle(&points, &center, 10.0);
+        assert_eq!(points.first(), points.last());
+        let deviation = max_circle_deviation(&points, &center, 10.0);
+        assert!(deviation <= 0.01, "deviation {}", deviation);
+        // not much finer than needed
+        assert!(deviation > 0.005, "deviation {}", deviation);
+    }
+
+    #[test]
+    fn tessellate_circle_with_coarse_tolerance() {
+        let points = single(EntityType::Circle(Circle::new(Point::origin(), 1.0)), 5.0);
+        assert_eq!(5, points.len());
+    }
+
+    #[test]
+    fn tessellate_with_angle_tolerance() {
+        let circle = Entity::new(EntityType::Circle(Circle::new(Point::origin(), 1.0)));
+        let polylines = circle.tessellate_with_angle_tolerance(5.0, 10.0);
+        assert_eq!(37, polylines[0].len());
+    }
+
+    #[test]
+    fn tessellate_arc_in_ocs() {
+        // with a normal of -Z the OCS X axis is the WCS -X axis
+        let arc = Arc {
+            center: Point::new(1.0, 0.0, 0.0),
+            radius: 1.0,
+            start_angle: 0.0,
+            end_angle: 90.0,
+            normal: Vector::new(0.0, 0.0, -1.0),
+            ..Default::default()
+        };
+        let points = single(EntityType::Arc(arc), 0.001);
+        assert_close(&Point::new(-2.0, 0.0, 0.0), &points[0]);
+        assert_close(&Point::new(-1.0, 1.0, 0.0), points.last().unwrap());
+        assert_on_circle(&points, &Point::new(-1.0, 0.0, 0.0), 1.0);
+    }
+
+    #[test]
+    fn tessellate_partial_ellipse() {
+        let ellipse = Ellipse {
+            center: Point::origin(),
+            major_axis: Vector::new(2.0, 0.0, 0.0),
+            minor_axis_ratio: 0.5,
+            start_parameter: 0.0,
+            end_parameter: PI,
+            ..Default::default()
+        };
+        let points = single(EntityType::Ellipse(ellipse), 0.001);
+        assert_close(&Point::new(2.0, 0.0, 0.0), &points[0]);
+        assert_close(&Point::new(-2.0, 0.0, 0.0), points.last().unwrap());
+        for point in &points {
+            assert!((point.x * point.x / 4.0 + point.y * point.y - 1.0).abs() < 1e-9);
+            assert!(point.y >= 0.0);
+        }
+    }
+
+    #[test]
+    fn tessellate_lw_polyline_with_bulge() {
+        // a semicircle from (0,0) to (2,0) bulging down, then straight back up to the start
+        let poly = LwPolyline {
+            vertices: vec![
+                LwPolylineVertex {
+                    x: 0.0,
+                    y: 0.0,
+                    bulge: 1.0,
+                    ..Default::default()
+                },
+                LwPolylineVertex {
+                    x: 2.0,
+                    y: 0.0,
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+        let points = single(EntityType::LwPolyline(poly.clone()), 0.001);
+        assert_close(&Point::origin(), &points[0]);
+        assert_close(&Point::new(2.0, 0.0, 0.0), points.last().unwrap());
+        assert_on_circle(&points, &Point::new(1.0, 0.0, 0.0), 1.0);
+        assert!(points.iter().all(|p| p.y <= 1e-9));
+
+        let mut closed = poly;
+        closed.set_is_closed(true);
+        let points = single(EntityType::LwPolyline(closed), 0.001);
+        assert_eq!(points.first(), points.last());
+    }
+
+    #[test]
+    fn tessellate_polyline_uses_fit_vertices() {
+        let mut drawing = Drawing::new();
+        let mut poly = Polyline::default();
+        for (x, is_control_point) in &[(0.0, true), (0.5, false), (1.0, true), (1.5, false)] {
+            let mut vertex = Vertex::new(Point::new(*x, 0.0, 0.0));
+            vertex.set_is_spline_frame_control_point(*is_control_point);
+            vertex.set_is_spline_vertex_created_by_spline_fitting(!*is_control_point);
+            poly.add_vertex(&mut drawing, vertex);
+        }
+        let points = single(EntityType::Polyline(poly), 0.001);
+        assert_eq!(
+            vec![Point::new(0.5, 0.0, 0.0), Point::new(1.5, 0.0, 0.0)],
+            points
+        );
+    }
+
+    #[test]
+    fn tessellate_polyline_spline_frame() {
+        let mut drawing = Drawing::new();
+        let mut poly = Polyline {
+            surface_type: PolylineCurvedAndSmoothSurfaceType::QuadraticBSpline,
+            ..Default::default()
+        };
+        for (x, y) in &[(0.0, 0.0), (1.0, 1.0), (2.0, 0.0)] {
+            let mut vertex = Vertex::new(Point::new(*x, *y, 0.0));
+            vertex.set_is_spline_frame_control_point(true);
+            poly.add_vertex(&mut drawing, vertex);
+        }
+        // a quadratic Bezier curve
+        let points = single(EntityType::Polyline(poly), 0.001);
+        assert_close(&Point::origin(), &points[0]);
+        assert_close(&Point::new(2.0, 0.0, 0.0), points.last().unwrap());
+        let highest = points.iter().map(|p| p.y).fold(0.0, f64::max);
+        assert!(highest <= 0.5 && highest > 0.499, "highest {}", highest);
+    }
+
+    #[test]
+    fn tessellate_3d_polyline() {
+        let mut drawing = Drawing::new();
+        let mut poly = Polyline::default();
+        poly.set_is_3d_polyline(true);
+        poly.set_is_closed(true);
+        for z in &[1.0, 2.0, 3.0] {
+            poly.add_vertex(&mut drawing, Vertex::new(Point::new(0.0, *z, *z)));
+        }
+        let points = single(EntityType::Polyline(poly), 0.001);
+        assert_eq!(
+            vec![
+                Point::new(0.0, 1.0, 1.0),
+                Point::new(0.0, 2.0, 2.0),
+                Point::new(0.0, 3.0, 3.0),
+                Point::new(0.0, 1.0, 1.0),
+            ],
+            points
+        );
+    }
+
+    #[test]
+    fn tessellate_spline_within_tolerance() {
+        // every point of the curve is near the tessellation
+        let spline = Spline {
+            degree_of_curve: 3,
+            control_points: vec![
+                Point::new(0.0, 0.0, 0.0),
+                Point::new(1.0, 2.0, 0.0),
+                Point::new(3.0, -2.0, 0.0),
+                Point::new(4.0, 0.0, 0.0),
+            ],
+            knot_values: vec![0.0, 0.0, 0.0, 0.0, 1.0, 1.0, 1.0, 1.0],
+            ..Default::default()
+        };
+        let points = single(EntityType::Spline(spline.clone()), 0.001);
+        assert_close(&Point::origin(), &points[0]);
+        assert_close(&Point::new(4.0, 0.0, 0.0), points.last().unwrap());
+        let samples = spline.sample_points(1000).unwrap();
+        for sample in &samples {
+            let distance = points
+                .windows(2)
+                .map(|w| super::distance_to_segment(sample, &w[0], &w[1]))
+                .fold(f64::MAX, f64::min);
+            assert!(distance <= 0.001, "{:?} is {} away", sample, distance);
+        }
+    }
+
+    #[test]
+    fn tessellate_spline_without_knots_uses_fit_points() {
+        let spline = Spline {
+            fit_points: vec![Point::origin(), Point::new(1.0, 1.0, 0.0)],
+            ..Default::default()
+        };
+        let points = single(EntityType::Spline(spline), 0.001);
+        assert_eq!(vec![Point::origin(), Point::new(1.0, 1.0, 0.0)], points);
+    }
+
+    #[test]
+    fn tessellate_helix() {
+        let helix = Helix {
+            axis_base_point: Point::origin(),
+            start_point: Point::new(2.0, 0.0, 0.0),
+            axis_vector: Vector::z_axis(),
+            radius: 2.0,
+            number_of_turns: 2.5,
+            turn_height: 4.0,
+            is_right_handed: true,
+            ..Default::default()
+        };
+        let points = single(EntityType::Helix(helix.clone()), 0.01);
+        assert_close(&Point::new(2.0, 0.0, 0.0), &points[0]);
+        assert_close(&Point::new(-2.0, 0.0, 10.0), points.last().unwrap());
+        // counterclockwise about the axis
+        assert!(points[1].y > 0.0);
+        for point in &points {
+            assert!(((point.x * point.x + point.y * point.y).sqrt() - 2.0).abs() < 1e-9);
+        }
+
+        let left_handed = Helix {
+            is_right_handed: false,
+            ..helix
+        };
+        let points = single(EntityType::Helix(left_handed), 0.01);
+        assert!(points[1].y < 0.0);
+    }
+
+    #[test]
+    fn tessellate_unsupported_entity() {
+        let text = Entity::new(EntityType::Text(Text::default()));
+        assert!(text.tessellate(0.01).is_empty());
+    }
+}
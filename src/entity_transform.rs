@@ -0,0 +1,1437 @@
+// `Entity::transform()`; other implementation is in `entity.rs` and `generated/entities.rs`
+
+use std::f64::consts::PI;
+
+use crate::{
+    HatchBoundaryPath, HatchEdge, MLeaderContextData, Point, TransformationMatrix, Vector,
+};
+
+use crate::entities::*;
+
+const TEXT_UPSIDE_DOWN_FLAG: i32 = 4;
+
+impl Entity {
+    /// Applies the specified transformation to the entity.
+    ///
+    /// Points stored in an object coordinate system (OCS) are mapped through world coordinates and the normal is
+    /// updated, arc and text angles are measured in the new OCS, and mirroring reverses arcs and polyline bulges.  A
+    /// circle or arc that is scaled differently along its two in-plane axes becomes an `Ellipse`.  Entities whose
+    /// geometry is stored in opaque data (3D solids, bodies, regions, surfaces, proxy and OLE entities, and unknown
+    /// entities) are left unchanged.
+    pub fn transform(&mut self, matrix: &TransformationMatrix) {
+        let replacement = match self.specific {
+            EntityType::Arc(ref mut arc) => transform_arc(matrix, arc),
+            EntityType::Circle(ref mut circle) => transform_circle(matrix, circle),
+            ref mut specific => {
+                specific.transform(matrix);
+                None
+            }
+        };
+        if let Some(ellipse) = replacement {
+            self.specific = EntityType::Ellipse(ellipse);
+        }
+    }
+}
+
+impl EntityType {
+    fn transform(&mut self, matrix: &TransformationMatrix) {
+        let m = matrix;
+        match self {
+            EntityType::Face3D(ref mut face) => {
+                transform_points(
+                    m,
+                    &mut [
+                        &mut face.first_corner,
+                        &mut face.second_corner,
+                        &mut face.third_corner,
+                        &mut face.fourth_corner,
+                    ],
+                );
+            }
+            EntityType::Arc(_) | EntityType::Circle(_) => (), // handled by `Entity::transform()`
+            EntityType::ArcAlignedText(ref mut text) => {
+                let ocs = OcsTransform::new(m, &text.extrusion_direction);
+                let scale = ocs.area_scale();
+                text.center_point = ocs.point(&text.center_point);
+                text.arc_radius *= scale;
+                text.text_height *= scale;
+                text.character_spacing *= scale;
+                text.offset_from_arc *= scale;
+                text.right_offset *= scale;
+                text.left_offset *= scale;
+                let (start, end) = ocs.arc_angles(text.start_angle, text.end_angle);
+                text.start_angle = start;
+                text.end_angle = end;
+                text.extrusion_direction = ocs.new_normal.clone();
+            }
+            EntityType::AttributeDefinition(ref mut att) => {
+                let ocs = OcsTransform::new(m, &att.normal);
+                ocs.text(
+                    &mut att.location,
+                    &mut att.second_alignment_point,
+                    &mut att.rotation,
+                    &mut att.text_height,
+                    &mut att.relative_x_scale_factor,
+                    &mut att.text_generation_flags,
+                );
+                att.thickness = ocs.thickness(att.thickness);
+                att.alignment_point = ocs.point(&att.alignment_point);
+                att.normal = ocs.new_normal.clone();
+                transform_m_text(m, &mut att.m_text);
+            }
+            EntityType::Attribute(ref mut att) => transform_attribute(m, att),
+            EntityType::RotatedDimension(ref mut dim) => {
+                let ocs = transform_dimension_base(m, &mut dim.dimension_base);
+                dim.insertion_point = ocs.point(&dim.insertion_point);
+                transform_points(
+                    m,
+                    &mut [&mut dim.definition_point_2, &mut dim.definition_point_3],
+                );
+                dim.rotation_angle = ocs.angle(dim.rotation_angle);
+                dim.extension_line_angle = ocs.angle(dim.extension_line_angle);
+            }
+            EntityType::RadialDimension(ref mut dim) => {
+                let ocs = transform_dimension_base(m, &mut dim.dimension_base);
+                dim.definition_point_2 = m.transform_point(&dim.definition_point_2);
+                dim.leader_length *= ocs.area_scale();
+            }
+            EntityType::DiameterDimension(ref mut dim) => {
+                let ocs = transform_dimension_base(m, &mut dim.dimension_base);
+                dim.definition_point_2 = m.transform_point(&dim.definition_point_2);
+                dim.leader_length *= ocs.area_scale();
+            }
+            EntityType::AngularThreePointDimension(ref mut dim) => {
+                let ocs = transform_dimension_base(m, &mut dim.dimension_base);
+                transform_points(
+                    m,
+                    &mut [
+                        &mut dim.definition_point_2,
+                        &mut dim.definition_point_3,
+                        &mut dim.definition_point_4,
+                    ],
+                );
+                dim.definition_point_5 = ocs.point(&dim.definition_point_5);
+            }
+            EntityType::OrdinateDimension(ref mut dim) => {
+                transform_dimension_base(m, &mut dim.dimension_base);
+                transform_points(
+                    m,
+                    &mut [&mut dim.definition_point_2, &mut dim.definition_point_3],
+                );
+            }
+            EntityType::Ellipse(ref mut ellipse) => {
+                let new_normal = m.transform_normal(&ellipse.normal);
+                let minor_axis = ellipse
+                    .normal
+                    .normalized()
+                    .cross(&ellipse.major_axis)
+                    .scaled(ellipse.minor_axis_ratio);
+                let t = EllipseTransform::new(
+                    m,
+                    &ellipse.center,
+                    &ellipse.major_axis,
+                    &minor_axis,
+                    &new_normal,
+                );
+                let (start, end) = t.parameters(ellipse.start_parameter, ellipse.end_parameter);
+                ellipse.center = t.center;
+                ellipse.major_axis = t.major_axis;
+                ellipse.normal = new_normal;
+                ellipse.minor_axis_ratio = t.minor_axis_ratio;
+                ellipse.start_parameter = start;
+                ellipse.end_parameter = end;
+            }
+            EntityType::Hatch(ref mut hatch) => {
+                let ocs = OcsTransform::new(m, &hatch.extrusion_direction);
+                let elevation = hatch.elevation_point.z;
+                for path in &mut hatch.boundary_paths {
+                    transform_hatch_boundary_path(&ocs, elevation, path);
+                }
+                let scale = ocs.area_scale();
+                hatch.pattern_angle = ocs.angle(hatch.pattern_angle);
+                hatch.pattern_scale *= scale;
+                for line in &mut hatch.pattern_definition_lines {
+                    let base_point = Point::new(line.base_point.x, line.base_point.y, elevation);
+                    line.angle = ocs.angle(line.angle);
+                    line.base_point = ocs.point(&base_point);
+                    line.base_point.z = 0.0;
+                    line.offset = ocs.vector(&line.offset);
+                    line.offset.z = 0.0;
+                    for dash_length in &mut line.dash_lengths {
+                        *dash_length *= scale;
+                    }
+                }
+                for seed_point in &mut hatch.seed_points {
+                    let p = Point::new(seed_point.x, seed_point.y, elevation);
+                    *seed_point = ocs.point(&p);
+                    seed_point.z = 0.0;
+                }
+                hatch.gradient_angle = ocs.angle(hatch.gradient_angle.to_degrees()).to_radians();
+                hatch.elevation_point = Point::new(0.0, 0.0, ocs.elevation(elevation));
+                hatch.extrusion_direction = ocs.new_normal.clone();
+            }
+            EntityType::Helix(ref mut helix) => {
+                let old_axis = helix.axis_vector.normalized();
+                helix.axis_base_point = m.transform_point(&helix.axis_base_point);
+                helix.start_point = m.transform_point(&helix.start_point);
+                helix.axis_vector = m.transform_vector(&helix.axis_vector);
+                helix.turn_height *= m.transform_vector(&old_axis).length();
+                let axis = helix.axis_vector.normalized();
+                let offset = Vector::new(
+                    helix.start_point.x - helix.axis_base_point.x,
+                    helix.start_point.y - helix.axis_base_point.y,
+                    helix.start_point.z - helix.axis_base_point.z,
+                );
+                helix.radius = offset.plus(&axis.scaled(-offset.dot(&axis))).length();
+                if m.linear_determinant() < 0.0 {
+                    helix.is_right_handed = !helix.is_right_handed;
+                }
+            }
+            EntityType::Image(ref mut image) => {
+                image.location = m.transform_point(&image.location);
+                image.u_vector = m.transform_vector(&image.u_vector);
+                image.v_vector = m.transform_vector(&image.v_vector);
+            }
+            EntityType::Insert(ref mut insert) => {
+                let ocs = OcsTransform::new(m, &insert.extrusion_direction);
+                let block = ocs.block_reference(
+                    insert.rotation,
+                    insert.x_scale_factor,
+                    insert.y_scale_factor,
+                    insert.z_scale_factor,
+                );
+                insert.location = ocs.point(&insert.location);
+                insert.rotation = block.rotation;
+                insert.x_scale_factor = block.x_scale_factor;
+                insert.y_scale_factor = block.y_scale_factor;
+                insert.z_scale_factor = block.z_scale_factor;
+                insert.column_spacing *= block.x_length_scale;
+                insert.row_spacing *= block.y_length_scale;
+                insert.extrusion_direction = ocs.new_normal.clone();
+                for att in insert.attributes_mut() {
+                    transform_attribute(m, att);
+                }
+            }
+            EntityType::Leader(ref mut leader) => {
+                let scale = m.length_scale();
+                for vertex in &mut leader.vertices {
+                    *vertex = m.transform_point(vertex);
+                }
+                leader.text_annotation_height *= scale;
+                leader.text_annotation_width *= scale;
+                leader.normal = m.transform_normal(&leader.normal);
+                leader.right = m.transform_vector(&leader.right).normalized();
+                leader.block_offset = m.transform_vector(&leader.block_offset);
+                leader.annotation_offset = m.transform_vector(&leader.annotation_offset);
+            }
+            EntityType::Light(ref mut light) => {
+                light.position = m.transform_point(&light.position);
+                light.target_location = m.transform_point(&light.target_location);
+            }
+            EntityType::Line(ref mut line) => {
+                line.p1 = m.transform_point(&line.p1);
+                line.p2 = m.transform_point(&line.p2);
+                let ocs = OcsTransform::new(m, &line.extrusion_direction);
+                line.thickness = ocs.thickness(line.thickness);
+                line.extrusion_direction = ocs.new_normal.clone();
+            }
+            EntityType::LwPolyline(ref mut poly) => {
+                let ocs = OcsTransform::new(m, &poly.extrusion_direction);
+                let width_scale = ocs.area_scale();
+                for vertex in &mut poly.vertices {
+                    let p = ocs.point(&Point::new(vertex.x, vertex.y, poly.elevation));
+                    vertex.x = p.x;
+                    vertex.y = p.y;
+                    vertex.bulge = ocs.bulge(vertex.bulge);
+                    vertex.starting_width *= width_scale;
+                    vertex.ending_width *= width_scale;
+                }
+                poly.constant_width *= width_scale;
+                poly.thickness = ocs.thickness(poly.thickness);
+                poly.elevation = ocs.elevation(poly.elevation);
+                poly.extrusion_direction = ocs.new_normal.clone();
+            }
+            EntityType::Mesh(ref mut mesh) => {
+                for vertex in &mut mesh.vertices {
+                    *vertex = m.transform_point(vertex);
+                }
+            }
+            EntityType::MLine(ref mut mline) => {
+                mline.start_point = m.transform_point(&mline.start_point);
+                for vertex in &mut mline.vertices {
+                    *vertex = m.transform_point(vertex);
+                }
+                for direction in &mut mline.segment_directions {
+                    *direction = m.transform_vector(direction).normalized();
+                }
+                for direction in &mut mline.miter_directions {
+                    *direction = m.transform_vector(direction).normalized();
+                }
+                let ocs = OcsTransform::new(m, &mline.normal);
+                mline.scale_factor *= ocs.area_scale();
+                mline.normal = ocs.new_normal.clone();
+            }
+            EntityType::MLeader(ref mut mleader) => {
+                let scale = m.length_scale();
+                mleader.dogleg_length *= scale;
+                mleader.arrowhead_size *= scale;
+                transform_mleader_context_data(m, &mut mleader.context_data);
+            }
+            EntityType::MText(ref mut mtext) => transform_m_text(m, mtext),
+            EntityType::Ole2Frame(ref mut frame) => {
+                frame.upper_left_corner = m.transform_point(&frame.upper_left_corner);
+                frame.lower_right_corner = m.transform_point(&frame.lower_right_corner);
+            }
+            EntityType::ModelPoint(ref mut point) => {
+                point.location = m.transform_point(&point.location);
+                let ocs = OcsTransform::new(m, &point.extrusion_direction);
+                point.angle = ocs.angle(point.angle);
+                point.thickness = ocs.thickness(point.thickness);
+                point.extrusion_direction = ocs.new_normal.clone();
+            }
+            EntityType::Polyline(ref mut poly) => {
+                if poly.is_3d_polyline() || poly.is_3d_polygon_mesh() || poly.is_polyface_mesh() {
+                    // vertices are in world coordinates
+                    for vertex in poly.vertices_mut() {
+                        let is_face_record =
+                            vertex.is_polyface_mesh_vertex() && !vertex.is_3d_polygon_mesh();
+                        if !is_face_record {
+                            vertex.location = m.transform_point(&vertex.location);
+                        }
+                    }
+                } else {
+                    let ocs = OcsTransform::new(m, &poly.normal);
+                    let width_scale = ocs.area_scale();
+                    for vertex in poly.vertices_mut() {
+                        vertex.location = ocs.point(&vertex.location);
+                        vertex.bulge = ocs.bulge(vertex.bulge);
+                        vertex.starting_width *= width_scale;
+                        vertex.ending_width *= width_scale;
+                        vertex.curve_fit_tangent_direction =
+                            ocs.angle(vertex.curve_fit_tangent_direction);
+                    }
+                    poly.default_starting_width *= width_scale;
+                    poly.default_ending_width *= width_scale;
+                    poly.thickness = ocs.thickness(poly.thickness);
+                    poly.location = Point::new(0.0, 0.0, ocs.elevation(poly.location.z));
+                    poly.normal = ocs.new_normal.clone();
+                }
+            }
+            EntityType::Ray(ref mut ray) => {
+                ray.start_point = m.transform_point(&ray.start_point);
+                ray.unit_direction_vector =
+                    m.transform_vector(&ray.unit_direction_vector).normalized();
+            }
+            EntityType::RText(ref mut text) => {
+                let ocs = OcsTransform::new(m, &text.extrusion_direction);
+                text.insertion_point = m.transform_point(&text.insertion_point);
+                text.text_height *= ocs.area_scale();
+                text.rotation_angle = ocs.angle(text.rotation_angle);
+                text.extrusion_direction = ocs.new_normal.clone();
+            }
+            EntityType::Section(ref mut section) => {
+                let old_vertical = section.vertical_direction.normalized();
+                let height_scale = m.transform_vector(&old_vertical).length();
+                for vertex in &mut section.vertices {
+                    *vertex = m.transform_point(vertex);
+                }
+                for vertex in &mut section.back_line_vertices {
+                    *vertex = m.transform_point(vertex);
+                }
+                section.vertical_direction =
+                    m.transform_vector(&section.vertical_direction).normalized();
+                section.top_height *= height_scale;
+                section.bottom_height *= height_scale;
+            }
+            EntityType::Shape(ref mut shape) => {
+                let ocs = OcsTransform::new(m, &shape.extrusion_direction);
+                let mut unused = Point::origin();
+                let mut flags = 0;
+                ocs.text(
+                    &mut shape.location,
+                    &mut unused,
+                    &mut shape.rotation_angle,
+                    &mut shape.size,
+                    &mut shape.relative_x_scale_factor,
+                    &mut flags,
+                );
+                shape.thickness = ocs.thickness(shape.thickness);
+                shape.extrusion_direction = ocs.new_normal.clone();
+            }
+            EntityType::Solid(ref mut solid) => {
+                let ocs = OcsTransform::new(m, &solid.extrusion_direction);
+                ocs.points(&mut [
+                    &mut solid.first_corner,
+                    &mut solid.second_corner,
+                    &mut solid.third_corner,
+                    &mut solid.fourth_corner,
+                ]);
+                solid.thickness = ocs.thickness(solid.thickness);
+                solid.extrusion_direction = ocs.new_normal.clone();
+            }
+            EntityType::Spline(ref mut spline) => {
+                for point in &mut spline.control_points {
+                    *point = m.transform_point(point);
+                }
+                for point in &mut spline.fit_points {
+                    *point = m.transform_point(point);
+                }
+                spline.start_tangent = transform_point_as_vector(m, &spline.start_tangent);
+                spline.end_tangent = transform_point_as_vector(m, &spline.end_tangent);
+                if spline.normal != Vector::zero() {
+                    spline.normal = m.transform_normal(&spline.normal);
+                }
+            }
+            EntityType::Table(ref mut table) => {
+                let direction = table.horizontal_direction.normalized();
+                // rows grow downwards from the insertion point
+                let down = direction.cross(&Vector::z_axis());
+                let width_scale = m.transform_vector(&direction).length();
+                let height_scale = m.transform_vector(&down).length();
+                table.insertion_point = m.transform_point(&table.insertion_point);
+                table.horizontal_direction = m.transform_vector(&direction).normalized();
+                for width in &mut table.column_widths {
+                    *width *= width_scale;
+                }
+                for height in &mut table.row_heights {
+                    *height *= height_scale;
+                }
+            }
+            EntityType::Text(ref mut text) => {
+                let ocs = OcsTransform::new(m, &text.normal);
+                ocs.text(
+                    &mut text.location,
+                    &mut text.second_alignment_point,
+                    &mut text.rotation,
+                    &mut text.text_height,
+                    &mut text.relative_x_scale_factor,
+                    &mut text.text_generation_flags,
+                );
+                text.thickness = ocs.thickness(text.thickness);
+                text.normal = ocs.new_normal.clone();
+            }
+            EntityType::Tolerance(ref mut tolerance) => {
+                tolerance.insertion_point = m.transform_point(&tolerance.insertion_point);
+                tolerance.direction_vector =
+                    m.transform_vector(&tolerance.direction_vector).normalized();
+                tolerance.extrusion_direction = m.transform_normal(&tolerance.extrusion_direction);
+            }
+            EntityType::Trace(ref mut trace) => {
+                let ocs = OcsTransform::new(m, &trace.extrusion_direction);
+                ocs.points(&mut [
+                    &mut trace.first_corner,
+                    &mut trace.second_corner,
+                    &mut trace.third_corner,
+                    &mut trace.fourth_corner,
+                ]);
+                trace.thickness = ocs.thickness(trace.thickness);
+                trace.extrusion_direction = ocs.new_normal.clone();
+            }
+            EntityType::DgnUnderlay(ref mut underlay) => transform_underlay(
+                m,
+                &mut underlay.insertion_point,
+                &mut underlay.x_scale,
+                &mut underlay.y_scale,
+                &mut underlay.z_scale,
+                &mut underlay.rotation_angle,
+                &mut underlay.normal,
+            ),
+            EntityType::DwfUnderlay(ref mut underlay) => transform_underlay(
+                m,
+                &mut underlay.insertion_point,
+                &mut underlay.x_scale,
+                &mut underlay.y_scale,
+                &mut underlay.z_scale,
+                &mut underlay.rotation_angle,
+                &mut underlay.normal,
+            ),
+            EntityType::PdfUnderlay(ref mut underlay) => transform_underlay(
+                m,
+                &mut underlay.insertion_point,
+                &mut underlay.x_scale,
+                &mut underlay.y_scale,
+                &mut underlay.z_scale,
+                &mut underlay.rotation_angle,
+                &mut underlay.normal,
+            ),
+            EntityType::Vertex(ref mut vertex) => {
+                vertex.location = m.transform_point(&vertex.location);
+            }
+            EntityType::Viewport(ref mut viewport) => {
+                viewport.center = m.transform_point(&viewport.center);
+                viewport.width *= m.transform_vector(&Vector::x_axis()).length();
+                viewport.height *= m.transform_vector(&Vector::y_axis()).length();
+            }
+            EntityType::Wipeout(ref mut wipeout) => {
+                wipeout.location = m.transform_point(&wipeout.location);
+                wipeout.u_vector = m.transform_vector(&wipeout.u_vector);
+                wipeout.v_vector = m.transform_vector(&wipeout.v_vector);
+            }
+            EntityType::XLine(ref mut xline) => {
+                xline.first_point = m.transform_point(&xline.first_point);
+                xline.unit_direction_vector = m
+                    .transform_vector(&xline.unit_direction_vector)
+                    .normalized();
+            }
+            // geometry is stored in opaque modeler, proxy, or OLE data
+            EntityType::Solid3D(_)
+            | EntityType::ProxyEntity(_)
+            | EntityType::Body(_)
+            | EntityType::OleFrame(_)
+            | EntityType::Region(_)
+            | EntityType::ExtrudedSurface(_)
+            | EntityType::LoftedSurface(_)
+            | EntityType::NurbSurface(_)
+            | EntityType::PlaneSurface(_)
+            | EntityType::RevolvedSurface(_)
+            | EntityType::SweptSurface(_) => (),
+            EntityType::Seqend(_) | EntityType::Unknown { .. } => (),
+        }
+    }
+}
+
+/// Returns the `Ellipse` replacing the arc if it could not be kept as an arc.
+fn transform_arc(m: &TransformationMatrix, arc: &mut Arc) -> Option<Ellipse> {
+    let ocs = OcsTransform::new(m, &arc.normal);
+    match ocs.uniform_scale() {
+        Some(scale) => {
+            let (start, end) = ocs.arc_angles(arc.start_angle, arc.end_angle);
+            arc.center = ocs.point(&arc.center);
+            arc.radius *= scale;
+            arc.start_angle = start;
+            arc.end_angle = end;
+            arc.thickness = ocs.thickness(arc.thickness);
+            arc.normal = ocs.new_normal.clone();
+            None
+        }
+        None => Some(ocs.circular_arc_as_ellipse(
+            &arc.center,
+            arc.radius,
+            arc.start_angle.to_radians(),
+            arc.end_angle.to_radians(),
+        )),
+    }
+}
+
+/// Returns the `Ellipse` replacing the circle if it could not be kept as a circle.
+fn transform_circle(m: &TransformationMatrix, circle: &mut Circle) -> Option<Ellipse> {
+    let ocs = OcsTransform::new(m, &circle.normal);
+    match ocs.uniform_scale() {
+        Some(scale) => {
+            circle.center = ocs.point(&circle.center);
+            circle.radius *= scale;
+            circle.thickness = ocs.thickness(circle.thickness);
+            circle.normal = ocs.new_normal.clone();
+            None
+        }
+        None => Some(ocs.circular_arc_as_ellipse(&circle.center, circle.radius, 0.0, PI * 2.0)),
+    }
+}
+
+fn transform_attribute(m: &TransformationMatrix, att: &mut Attribute) {
+    let ocs = OcsTransform::new(m, &att.normal);
+    ocs.text(
+        &mut att.location,
+        &mut att.second_alignment_point,
+        &mut att.rotation,
+        &mut att.text_height,
+        &mut att.relative_x_scale_factor,
+        &mut att.text_generation_flags,
+    );
+    att.thickness = ocs.thickness(att.thickness);
+    att.alignment_point = ocs.point(&att.alignment_point);
+    att.normal = ocs.new_normal.clone();
+    transform_m_text(m, &mut att.m_text);
+}
+
+fn transform_dimension_base<'a>(
+    m: &'a TransformationMatrix,
+    dim: &mut DimensionBase,
+) -> OcsTransform<'a> {
+    let ocs = OcsTransform::new(m, &dim.normal);
+    dim.definition_point_1 = m.transform_point(&dim.definition_point_1);
+    dim.text_mid_point = ocs.point(&dim.text_mid_point);
+    dim.text_rotation_angle = ocs.angle(dim.text_rotation_angle);
+    dim.horizontal_direction_angle = ocs.angle(dim.horizontal_direction_angle);
+    dim.normal = ocs.new_normal.clone();
+    ocs
+}
+
+fn transform_hatch_boundary_path(ocs: &OcsTransform, elevation: f64, path: &mut HatchBoundaryPath) {
+    let width_scale = ocs.area_scale();
+    let point_2d = |x: f64, y: f64| {
+        let p = ocs.point(&Point::new(x, y, elevation));
+        Point::new(p.x, p.y, 0.0)
+    };
+    for vertex in &mut path.vertices {
+        let p = point_2d(vertex.x, vertex.y);
+        vertex.x = p.x;
+        vertex.y = p.y;
+        vertex.bulge = ocs.bulge(vertex.bulge);
+        vertex.starting_width *= width_scale;
+        vertex.ending_width *= width_scale;
+    }
+    for edge in &mut path.edges {
+        let replacement = match edge {
+            HatchEdge::Line {
+                ref mut start,
+                ref mut end,
+            } => {
+                *start = point_2d(start.x, start.y);
+                *end = point_2d(end.x, end.y);
+                None
+            }
+            HatchEdge::CircularArc {
+                ref mut center,
+                ref mut radius,
+                ref mut start_angle,
+                ref mut end_angle,
+                ref mut is_counter_clockwise,
+            } => {
+                // clockwise edges store their angles mirrored
+                let (start, end) = if *is_counter_clockwise {
+                    (*start_angle, *end_angle)
+                } else {
+                    (360.0 - *start_angle, 360.0 - *end_angle)
+                };
+                let new_is_counter_clockwise = *is_counter_clockwise != ocs.is_mirrored;
+                match ocs.uniform_scale() {
+                    Some(scale) => {
+                        let (start, end) = (ocs.angle(start), ocs.angle(end));
+                        *center = point_2d(center.x, center.y);
+                        *radius *= scale;
+                        *is_counter_clockwise = new_is_counter_clockwise;
+                        *start_angle = hatch_edge_angle(start, new_is_counter_clockwise);
+                        *end_angle = hatch_edge_angle(end, new_is_counter_clockwise);
+                        None
+                    }
+                    None => {
+                        let t = ocs.ellipse_transform(
+                            &Point::new(center.x, center.y, elevation).ocs_to_wcs(&ocs.old_normal),
+                            &ocs.old_x_axis.scaled(*radius),
+                            &ocs.old_y_axis.scaled(*radius),
+                        );
+                        let start = t.parameter(start.to_radians()).to_degrees();
+                        let end = t.parameter(end.to_radians()).to_degrees();
+                        Some(HatchEdge::EllipticArc {
+                            center: point_2d(center.x, center.y),
+                            major_axis: ocs.wcs_vector_to_new_ocs(&t.major_axis),
+                            minor_axis_ratio: t.minor_axis_ratio,
+                            start_angle: hatch_edge_angle(start, new_is_counter_clockwise),
+                            end_angle: hatch_edge_angle(end, new_is_counter_clockwise),
+                            is_counter_clockwise: new_is_counter_clockwise,
+                        })
+                    }
+                }
+            }
+            HatchEdge::EllipticArc {
+                ref mut center,
+                ref mut major_axis,
+                ref mut minor_axis_ratio,
+                ref mut start_angle,
+                ref mut end_angle,
+                ref mut is_counter_clockwise,
+            } => {
+                let (start, end) = if *is_counter_clockwise {
+                    (*start_angle, *end_angle)
+                } else {
+                    (360.0 - *start_angle, 360.0 - *end_angle)
+                };
+                let major = ocs.old_ocs_vector_to_wcs(major_axis);
+                let minor = ocs.old_normal.cross(&major).scaled(*minor_axis_ratio);
+                let t = ocs.ellipse_transform(
+                    &Point::new(center.x, center.y, elevation).ocs_to_wcs(&ocs.old_normal),
+                    &major,
+                    &minor,
+                );
+                let new_is_counter_clockwise = *is_counter_clockwise != ocs.is_mirrored;
+                let start = t.parameter(start.to_radians()).to_degrees();
+                let end = t.parameter(end.to_radians()).to_degrees();
+                *center = point_2d(center.x, center.y);
+                *major_axis = ocs.wcs_vector_to_new_ocs(&t.major_axis);
+                *minor_axis_ratio = t.minor_axis_ratio;
+                *start_angle = hatch_edge_angle(start, new_is_counter_clockwise);
+                *end_angle = hatch_edge_angle(end, new_is_counter_clockwise);
+                *is_counter_clockwise = new_is_counter_clockwise;
+                None
+            }
+            HatchEdge::Spline {
+                ref mut control_points,
+                ref mut fit_points,
+                ref mut start_tangent,
+                ref mut end_tangent,
+                ..
+            } => {
+                for point in control_points.iter_mut().chain(fit_points.iter_mut()) {
+                    *point = point_2d(point.x, point.y);
+                }
+                *start_tangent = ocs.vector(start_tangent);
+                start_tangent.z = 0.0;
+                *end_tangent = ocs.vector(end_tangent);
+                end_tangent.z = 0.0;
+                None
+            }
+        };
+        if let Some(replacement) = replacement {
+            *edge = replacement;
+        }
+    }
+}
+
+/// Returns the angle as stored in a hatch edge with the specified direction.
+fn hatch_edge_angle(angle: f64, is_counter_clockwise: bool) -> f64 {
+    let angle = normalize_angle(angle);
+    if is_counter_clockwise {
+        angle
+    } else {
+        normalize_angle(360.0 - angle)
+    }
+}
+
+fn transform_m_text(m: &TransformationMatrix, mtext: &mut MText) {
+    let old_normal = mtext.extrusion_direction.normalized();
+    let (x_axis, y_axis) = old_normal.arbitrary_axes();
+    let direction = if mtext.x_axis_direction.length() == 0.0 {
+        let (sin, cos) = mtext.rotation_angle.sin_cos();
+        x_axis.scaled(cos).plus(&y_axis.scaled(sin))
+    } else {
+        mtext.x_axis_direction.normalized()
+    };
+    let up = old_normal.cross(&direction);
+    let new_direction = m.transform_vector(&direction);
+    let new_normal = m.transform_normal(&mtext.extrusion_direction);
+    let width_scale = new_direction.length();
+    let height_scale = text_height_scale(&new_direction, &m.transform_vector(&up));
+    let (new_x_axis, new_y_axis) = new_normal.arbitrary_axes();
+
+    mtext.insertion_point = m.transform_point(&mtext.insertion_point);
+    mtext.initial_text_height *= height_scale;
+    mtext.reference_rectangle_width *= width_scale;
+    mtext.horizontal_width *= width_scale;
+    mtext.vertical_height *= height_scale;
+    mtext.column_width *= width_scale;
+    mtext.column_gutter *= width_scale;
+    for height in &mut mtext.column_heights {
+        *height *= height_scale;
+    }
+    mtext.x_axis_direction = new_direction.normalized();
+    mtext.rotation_angle = new_direction
+        .dot(&new_y_axis)
+        .atan2(new_direction.dot(&new_x_axis));
+    mtext.extrusion_direction = new_normal;
+}
+
+fn transform_mleader_context_data(m: &TransformationMatrix, data: &mut MLeaderContextData) {
+    let scale = m.length_scale();
+    data.content_scale *= scale;
+    data.content_base_point = m.transform_point(&data.content_base_point);
+    data.text_height *= scale;
+    data.arrowhead_size *= scale;
+    data.landing_gap *= scale;
+    data.text_normal = m.transform_normal(&data.text_normal);
+    data.text_location = m.transform_point(&data.text_location);
+    data.text_direction = m.transform_vector(&data.text_direction).normalized();
+    data.text_width *= scale;
+    data.text_boundary_height *= scale;
+    data.text_column_width *= scale;
+    data.text_column_gutter_width *= scale;
+    for size in &mut data.text_column_sizes {
+        *size *= scale;
+    }
+    data.block_content_normal = m.transform_normal(&data.block_content_normal);
+    data.block_content_location = m.transform_point(&data.block_content_location);
+    data.block_content_scale = data.block_content_scale.scaled(scale);
+    data.block_content_transformation_matrix =
+        m.multiply(&data.block_content_transformation_matrix);
+    data.plane_origin_point = m.transform_point(&data.plane_origin_point);
+    data.plane_x_axis = m.transform_vector(&data.plane_x_axis).normalized();
+    data.plane_y_axis = m.transform_vector(&data.plane_y_axis).normalized();
+    for leader in &mut data.leaders {
+        leader.last_leader_line_point = m.transform_point(&leader.last_leader_line_point);
+        leader.dogleg_vector = m.transform_vector(&leader.dogleg_vector).normalized();
+        leader.dogleg_length *= scale;
+        transform_points_in_place(m, &mut leader.break_start_points);
+        transform_points_in_place(m, &mut leader.break_end_points);
+        for line in &mut leader.leader_lines {
+            transform_points_in_place(m, &mut line.vertices);
+            transform_points_in_place(m, &mut line.break_start_points);
+            transform_points_in_place(m, &mut line.break_end_points);
+            line.arrowhead_size *= scale;
+        }
+    }
+}
+
+fn transform_underlay(
+    m: &TransformationMatrix,
+    insertion_point: &mut Point,
+    x_scale: &mut f64,
+    y_scale: &mut f64,
+    z_scale: &mut f64,
+    rotation_angle: &mut f64,
+    normal: &mut Vector,
+) {
+    let ocs = OcsTransform::new(m, normal);
+    let block = ocs.block_reference(*rotation_angle, *x_scale, *y_scale, *z_scale);
+    *insertion_point = m.transform_point(insertion_point);
+    *x_scale = block.x_scale_factor;
+    *y_scale = block.y_scale_factor;
+    *z_scale = block.z_scale_factor;
+    *rotation_angle = block.rotation;
+    *normal = ocs.new_normal.clone();
+}
+
+fn transform_points(m: &TransformationMatrix, points: &mut [&mut Point]) {
+    for point in points.iter_mut() {
+        **point = m.transform_point(point);
+    }
+}
+
+fn transform_points_in_place(m: &TransformationMatrix, points: &mut [Point]) {
+    for point in points.iter_mut() {
+        *point = m.transform_point(point);
+    }
+}
+
+fn transform_point_as_vector(m: &TransformationMatrix, p: &Point) -> Point {
+    Point::from_vector(&m.transform_vector(&p.as_vector()))
+}
+
+/// Returns the factor applied to the height of text running along `direction` with the transformed up vector `up`.
+fn text_height_scale(direction: &Vector, up: &Vector) -> f64 {
+    let length = direction.length();
+    if length == 0.0 {
+        up.length()
+    } else {
+        direction.cross(up).length() / length
+    }
+}
+
+/// Returns the angle in degrees in the range [0, 360).
+fn normalize_angle(angle: f64) -> f64 {
+    let angle = angle.rem_euclid(360.0);
+    if (angle - 360.0).abs() < 1e-10 {
+        0.0
+    } else {
+        angle
+    }
+}
+
+/// Maps values stored in one object coordinate system (OCS) to the OCS of the transformed normal.
+struct OcsTransform<'a> {
+    matrix: &'a TransformationMatrix,
+    old_normal: Vector,
+    old_x_axis: Vector,
+    old_y_axis: Vector,
+    new_normal: Vector,
+    new_x_axis: Vector,
+    new_y_axis: Vector,
+    is_mirrored: bool,
+}
+
+struct BlockReferenceTransform {
+    rotation: f64,
+    x_scale_factor: f64,
+    y_scale_factor: f64,
+    z_scale_factor: f64,
+    x_length_scale: f64,
+    y_length_scale: f64,
+}
+
+impl<'a> OcsTransform<'a> {
+    fn new(matrix: &'a TransformationMatrix, normal: &Vector) -> Self {
+        let old_normal = normal.normalized();
+        let (old_x_axis, old_y_axis) = old_normal.arbitrary_axes();
+        let new_normal = matrix.transform_normal(normal);
+        let (new_x_axis, new_y_axis) = new_normal.arbitrary_axes();
+        OcsTransform {
+            matrix,
+            old_normal,
+            old_x_axis,
+            old_y_axis,
+            new_normal,
+            new_x_axis,
+            new_y_axis,
+            is_mirrored: matrix.linear_determinant() < 0.0,
+        }
+    }
+    fn point(&self, p: &Point) -> Point {
+        let wcs = p.ocs_to_wcs(&self.old_normal);
+        self.matrix
+            .transform_point(&wcs)
+            .wcs_to_ocs(&self.new_normal)
+    }
+    fn points(&self, points: &mut [&mut Point]) {
+        for point in points.iter_mut() {
+            **point = self.point(point);
+        }
+    }
+    fn vector(&self, v: &Vector) -> Vector {
+        let wcs = self.old_ocs_vector_to_wcs(v);
+        self.wcs_vector_to_new_ocs(&self.matrix.transform_vector(&wcs))
+    }
+    fn old_ocs_vector_to_wcs(&self, v: &Vector) -> Vector {
+        self.old_x_axis
+            .scaled(v.x)
+            .plus(&self.old_y_axis.scaled(v.y))
+            .plus(&self.old_normal.scaled(v.z))
+    }
+    fn wcs_vector_to_new_ocs(&self, v: &Vector) -> Vector {
+        Vector::new(
+            v.dot(&self.new_x_axis),
+            v.dot(&self.new_y_axis),
+            v.dot(&self.new_normal),
+        )
+    }
+    /// Returns the transformed direction, in world coordinates, of the in-plane angle in degrees.
+    fn direction(&self, angle: f64) -> Vector {
+        let (sin, cos) = angle.to_radians().sin_cos();
+        self.matrix.transform_vector(
+            &self
+                .old_x_axis
+                .scaled(cos)
+                .plus(&self.old_y_axis.scaled(sin)),
+        )
+    }
+    /// Maps an in-plane angle in degrees.
+    fn angle(&self, angle: f64) -> f64 {
+        let direction = self.direction(angle);
+        normalize_angle(
+            direction
+                .dot(&self.new_y_axis)
+                .atan2(direction.dot(&self.new_x_axis))
+                .to_degrees(),
+        )
+    }
+    /// Maps the angles in degrees of a counter-clockwise arc; mirroring swaps the start and end.
+    fn arc_angles(&self, start: f64, end: f64) -> (f64, f64) {
+        if self.is_mirrored {
+            (self.angle(end), self.angle(start))
+        } else {
+            (self.angle(start), self.angle(end))
+        }
+    }
+    fn bulge(&self, bulge: f64) -> f64 {
+        if self.is_mirrored {
+            -bulge
+        } else {
+            bulge
+        }
+    }
+    /// Maps the OCS Z value of the plane.
+    fn elevation(&self, elevation: f64) -> f64 {
+        self.point(&Point::new(0.0, 0.0, elevation)).z
+    }
+    fn thickness(&self, thickness: f64) -> f64 {
+        thickness
+            * self
+                .matrix
+                .transform_vector(&self.old_normal)
+                .dot(&self.new_normal)
+    }
+    /// Returns the scale factor of in-plane lengths if it is the same in every direction.
+    fn uniform_scale(&self) -> Option<f64> {
+        let x = self.matrix.transform_vector(&self.old_x_axis);
+        let y = self.matrix.transform_vector(&self.old_y_axis);
+        let (x_length, y_length) = (x.length(), y.length());
+        let tolerance = 1e-9 * x_length.max(y_length);
+        if (x_length - y_length).abs() <= tolerance && x.dot(&y).abs() <= tolerance * x_length {
+            Some(x_length)
+        } else {
+            None
+        }
+    }
+    /// Returns the scale factor of in-plane areas expressed as a length scale; used for widths and sizes.
+    fn area_scale(&self) -> f64 {
+        let x = self.matrix.transform_vector(&self.old_x_axis);
+        let y = self.matrix.transform_vector(&self.old_y_axis);
+        x.cross(&y).length().sqrt()
+    }
+    fn ellipse_transform(&self, center: &Point, u: &Vector, v: &Vector) -> EllipseTransform {
+        EllipseTransform::new(self.matrix, center, u, v, &self.new_normal)
+    }
+    /// Converts a circular arc in the old OCS to an ellipse; angles are in radians.
+    fn circular_arc_as_ellipse(
+        &self,
+        center: &Point,
+        radius: f64,
+        start: f64,
+        end: f64,
+    ) -> Ellipse {
+        let t = self.ellipse_transform(
+            &center.ocs_to_wcs(&self.old_normal),
+            &self.old_x_axis.scaled(radius),
+            &self.old_y_axis.scaled(radius),
+        );
+        let (start_parameter, end_parameter) = t.parameters(start, end);
+        Ellipse {
+            center: t.center,
+            major_axis: t.major_axis,
+            normal: self.new_normal.clone(),
+            minor_axis_ratio: t.minor_axis_ratio,
+            start_parameter,
+            end_parameter,
+        }
+    }
+    /// Maps the rotation and scale factors of a block reference in this OCS.
+    fn block_reference(&self, rotation: f64, x: f64, y: f64, z: f64) -> BlockReferenceTransform {
+        let x_direction = self.direction(rotation);
+        let y_direction = self.direction(rotation + 90.0);
+        let x_length_scale = x_direction.length();
+        let y_length_scale = y_direction.length();
+        let z_scale = self
+            .matrix
+            .transform_vector(&self.old_normal)
+            .dot(&self.new_normal);
+        BlockReferenceTransform {
+            rotation: self.angle(rotation),
+            x_scale_factor: x * x_length_scale,
+            y_scale_factor: if self.is_mirrored {
+                -y * y_length_scale
+            } else {
+                y * y_length_scale
+            },
+            z_scale_factor: z * z_scale,
+            x_length_scale,
+            y_length_scale,
+        }
+    }
+    /// Maps the values of a text-like entity in this OCS.
+    fn text(
+        &self,
+        location: &mut Point,
+        second_alignment_point: &mut Point,
+        rotation: &mut f64,
+        height: &mut f64,
+        x_scale_factor: &mut f64,
+        text_generation_flags: &mut i32,
+    ) {
+        let direction = self.direction(*rotation);
+        let up = self.direction(*rotation + 90.0);
+        let height_scale = text_height_scale(&direction, &up);
+        *location = self.point(location);
+        *second_alignment_point = self.point(second_alignment_point);
+        *rotation = self.angle(*rotation);
+        *height *= height_scale;
+        if height_scale != 0.0 {
+            *x_scale_factor *= direction.length() / height_scale;
+        }
+        if self.is_mirrored {
+            // the text still runs along the mapped baseline, but the glyphs are flipped across it
+            *text_generation_flags ^= TEXT_UPSIDE_DOWN_FLAG;
+        }
+    }
+}
+
+/// The transformation of an elliptical arc given by its center and two conjugate semi-diameters `u` and `v`, with
+/// points at `center + u * cos(t) + v * sin(t)`.
+struct EllipseTransform {
+    center: Point,
+    major_axis: Vector,
+    minor_axis_ratio: f64,
+    parameter_offset: f64,
+    is_mirrored: bool,
+}
+
+impl EllipseTransform {
+    fn new(
+        m: &TransformationMatrix,
+        center: &Point,
+        u: &Vector,
+        v: &Vector,
+        new_normal: &Vector,
+    ) -> Self {
+        let u = m.transform_vector(u);
+        let mut v = m.transform_vector(v);
+        let is_mirrored = u.cross(&v).dot(new_normal) < 0.0;
+        if is_mirrored {
+            // reverse the parameter direction to be counter-clockwise about the new normal
+            v = v.scaled(-1.0);
+        }
+
+        // the major axis is at the parameter where the distance from the center is greatest
+        let offset = 0.5 * (2.0 * u.dot(&v)).atan2(u.dot(&u) - v.dot(&v));
+        let (sin, cos) = offset.sin_cos();
+        let major_axis = u.scaled(cos).plus(&v.scaled(sin));
+        let minor_axis = u.scaled(-sin).plus(&v.scaled(cos));
+        let major_length = major_axis.length();
+        let minor_axis_ratio = if major_length == 0.0 {
+            1.0
+        } else {
+            minor_axis.length() / major_length
+        };
+        EllipseTransform {
+            center: m.transform_point(center),
+            major_axis,
+            minor_axis_ratio,
+            parameter_offset: offset,
+            is_mirrored,
+        }
+    }
+    /// Maps a parameter in radians of a point on the original ellipse.
+    fn parameter(&self, t: f64) -> f64 {
+        let t = if self.is_mirrored { -t } else { t };
+        (t - self.parameter_offset).rem_euclid(PI * 2.0)
+    }
+    /// Maps the parameters in radians of a counter-clockwise arc.
+    fn parameters(&self, start: f64, end: f64) -> (f64, f64) {
+        if (end - start - PI * 2.0).abs() < 1e-10 {
+            // full ellipse
+            return (0.0, PI * 2.0);
+        }
+        if self.is_mirrored {
+            (self.parameter(end), self.parameter(start))
+        } else {
+            (self.parameter(start), self.parameter(end))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::entities::*;
+    use crate::*;
+
+    const TOLERANCE: f64 = 1e-9;
+
+    fn assert_close(expected: f64, actual: f64) {
+        assert!(
+            (expected - actual).abs() < TOLERANCE,
+            "expected {} but was {}",
+            expected,
+            actual
+        );
+    }
+
+    fn assert_point_close(expected: &Point, actual: &Point) {
+        assert!(
+            (expected.x - actual.x).abs() < TOLERANCE
+                && (expected.y - actual.y).abs() < TOLERANCE
+                && (expected.z - actual.z).abs() < TOLERANCE,
+            "expected {:?} but was {:?}",
+            expected,
+            actual
+        );
+    }
+
+    fn arc_point(arc: &Arc, angle: f64) -> Point {
+        let (sin, cos) = angle.to_radians().sin_cos();
+        Point::new(
+            arc.center.x + arc.radius * cos,
+            arc.center.y + arc.radius * sin,
+            arc.center.z,
+        )
+        .ocs_to_wcs(&arc.normal)
+    }
+
+    fn ellipse_point(ellipse: &Ellipse, parameter: f64) -> Point {
+        let minor_axis = ellipse
+            .normal
+            .normalized()
+            .cross(&ellipse.major_axis)
+            .scaled(ellipse.minor_axis_ratio);
+        let (sin, cos) = parameter.sin_cos();
+        let offset = ellipse.major_axis.scaled(cos).plus(&minor_axis.scaled(sin));
+        Point::new(
+            ellipse.center.x + offset.x,
+            ellipse.center.y + offset.y,
+            ellipse.center.z + offset.z,
+        )
+    }
+
+    fn transformed(specific: EntityType, matrix: &TransformationMatrix) -> EntityType {
+        let mut ent = Entity::new(specific);
+        ent.transform(matrix);
+        ent.specific
+    }
+
+    #[test]
+    fn translate_line() {
+        let line = Line::new(Point::new(1.0, 2.0, 3.0), Point::new(4.0, 5.0, 6.0));
+        let matrix = TransformationMatrix::translation(&Vector::new(1.0, 1.0, 1.0));
+        match transformed(EntityType::Line(line), &matrix) {
+            EntityType::Line(ref line) => {
+                assert_eq!(Point::new(2.0, 3.0, 4.0), line.p1);
+                assert_eq!(Point::new(5.0, 6.0, 7.0), line.p2);
+            }
+            _ => panic!("expected a line"),
+        }
+    }
+
+    #[test]
+    fn rotate_arc() {
+        let arc = Arc::new(Point::new(1.0, 0.0, 0.0), 1.0, 0.0, 90.0);
+        match transformed(
+            EntityType::Arc(arc),
+            &TransformationMatrix::rotation_about_z(90.0),
+        ) {
+            EntityType::Arc(ref arc) => {
+                assert_point_close(&Point::new(0.0, 1.0, 0.0), &arc.center);
+                assert_close(1.0, arc.radius);
+                assert_close(90.0, arc.start_angle);
+                assert_close(180.0, arc.end_angle);
+            }
+            _ => panic!("expected an arc"),
+        }
+    }
+
+    #[test]
+    fn mirror_arc_swaps_angles() {
+        let arc = Arc::new(Point::new(1.0, 0.0, 0.0), 1.0, 0.0, 90.0);
+        let matrix = TransformationMatrix::mirror(&Point::origin(), &Vector::x_axis());
+        match transformed(EntityType::Arc(arc), &matrix) {
+            EntityType::Arc(ref arc) => {
+                assert_point_close(&Point::new(-1.0, 0.0, 0.0), &arc.center);
+                assert_eq!(Vector::z_axis(), arc.normal);
+                assert_close(90.0, arc.start_angle);
+                assert_close(180.0, arc.end_angle);
+            }
+            _ => panic!("expected an arc"),
+        }
+    }
+
+    #[test]
+    fn rotate_arc_out_of_plane() {
+        let original = Arc::new(Point::new(1.0, 2.0, 3.0), 2.0, 30.0, 120.0);
+        let matrix = TransformationMatrix::rotation_about_axis(
+            &Point::new(1.0, 1.0, 0.0),
+            &Vector::new(1.0, 2.0, 0.5),
+            75.0,
+        );
+        match transformed(EntityType::Arc(original.clone()), &matrix) {
+            EntityType::Arc(ref arc) => {
+                assert_point_close(
+                    &matrix.transform_point(&arc_point(&original, 30.0)),
+                    &arc_point(arc, arc.start_angle),
+                );
+                assert_point_close(
+                    &matrix.transform_point(&arc_point(&original, 120.0)),
+                    &arc_point(arc, arc.end_angle),
+                );
+                assert_point_close(
+                    &matrix.transform_point(&arc_point(&original, 75.0)),
+                    &arc_point(
+                        arc,
+                        arc.start_angle + (arc.end_angle - arc.start_angle).rem_euclid(360.0) / 2.0,
+                    ),
+                );
+            }
+            _ => panic!("expected an arc"),
+        }
+    }
+
+    #[test]
+    fn non_uniformly_scaled_circle_becomes_ellipse() {
+        let circle = Circle::new(Point::new(1.0, 1.0, 0.0), 1.0);
+        match transformed(
+            EntityType::Circle(circle),
+            &TransformationMatrix::scale(2.0, 1.0, 1.0),
+        ) {
+            EntityType::Ellipse(ref ellipse) => {
+                assert_point_close(&Point::new(2.0, 1.0, 0.0), &ellipse.center);
+                assert_close(2.0, ellipse.major_axis.length());
+                assert_close(0.0, ellipse.major_axis.y);
+                assert_close(0.5, ellipse.minor_axis_ratio);
+                assert_close(0.0, ellipse.start_parameter);
+                assert_close(std::f64::consts::PI * 2.0, ellipse.end_parameter);
+            }
+            _ => panic!("expected an ellipse"),
+        }
+    }
+
+    #[test]
+    fn non_uniformly_scaled_arc_becomes_elliptical_arc() {
+        let original = Arc::new(Point::new(0.0, 0.0, 0.0), 1.0, 0.0, 90.0);
+        let matrix = TransformationMatrix::scale(1.0, 2.0, 1.0);
+        match transformed(EntityType::Arc(original.clone()), &matrix) {
+            EntityType::Ellipse(ref ellipse) => {
+                assert_close(2.0, ellipse.major_axis.length());
+                assert_close(0.5, ellipse.minor_axis_ratio);
+                assert_point_close(
+                    &matrix.transform_point(&arc_point(&original, 0.0)),
+                    &ellipse_point(ellipse, ellipse.start_parameter),
+                );
+                assert_point_close(
+                    &matrix.transform_point(&arc_point(&original, 90.0)),
+                    &ellipse_point(ellipse, ellipse.end_parameter),
+                );
+            }
+            _ => panic!("expected an ellipse"),
+        }
+    }
+
+    #[test]
+    fn sheared_and_mirrored_ellipse() {
+        let original = Ellipse {
+            center: Point::new(1.0, 2.0, 0.0),
+            major_axis: Vector::new(3.0, 1.0, 0.0),
+            normal: Vector::z_axis(),
+            minor_axis_ratio: 0.4,
+            start_parameter: 0.5,
+            end_parameter: 2.0,
+        };
+        let mut matrix = TransformationMatrix::mirror(&Point::origin(), &Vector::y_axis());
+        matrix.m12 = 0.7; // shear
+        match transformed(EntityType::Ellipse(original.clone()), &matrix) {
+            EntityType::Ellipse(ref ellipse) => {
+                assert!(ellipse.minor_axis_ratio <= 1.0);
+                // the arc is reversed, so the old end is the new start
+                assert_point_close(
+                    &matrix.transform_point(&ellipse_point(&original, 2.0)),
+                    &ellipse_point(ellipse, ellipse.start_parameter),
+                );
+                assert_point_close(
+                    &matrix.transform_point(&ellipse_point(&original, 0.5)),
+                    &ellipse_point(ellipse, ellipse.end_parameter),
+                );
+                assert_point_close(
+                    &matrix.transform_point(&ellipse_point(&original, 1.0)),
+                    &ellipse_point(ellipse, ellipse.end_parameter - 0.5),
+                );
+            }
+            _ => panic!("expected an ellipse"),
+        }
+    }
+
+    #[test]
+    fn mirror_lw_polyline_negates_bulges() {
+        let mut poly = LwPolyline::default();
+        poly.vertices.push(LwPolylineVertex {
+            x: 1.0,
+            y: 0.0,
+            bulge: 1.0,
+            ..Default::default()
+        });
+        poly.vertices.push(LwPolylineVertex {
+            x: 2.0,
+            y: 1.0,
+            ..Default::default()
+        });
+        let matrix = TransformationMatrix::mirror(&Point::origin(), &Vector::y_axis());
+        match transformed(EntityType::LwPolyline(poly), &matrix) {
+            EntityType::LwPolyline(ref poly) => {
+                assert_close(1.0, poly.vertices[0].x);
+                assert_close(0.0, poly.vertices[0].y);
+                assert_close(-1.0, poly.vertices[0].bulge);
+                assert_close(2.0, poly.vertices[1].x);
+                assert_close(-1.0, poly.vertices[1].y);
+                assert_eq!(Vector::z_axis(), poly.extrusion_direction);
+            }
+            _ => panic!("expected a polyline"),
+        }
+    }
+
+    #[test]
+    fn transform_lw_polyline_with_elevation_out_of_plane() {
+        let mut poly = LwPolyline {
+            elevation: 3.0,
+            ..Default::default()
+        };
+        poly.vertices.push(LwPolylineVertex {
+            x: 1.0,
+            y: 2.0,
+            ..Default::default()
+        });
+        let matrix = TransformationMatrix::rotation_about_x(90.0);
+        match transformed(EntityType::LwPolyline(poly), &matrix) {
+            EntityType::LwPolyline(ref poly) => {
+                let v = &poly.vertices[0];
+                assert_point_close(
+                    &Point::new(1.0, -3.0, 2.0),
+                    &Point::new(v.x, v.y, poly.elevation).ocs_to_wcs(&poly.extrusion_direction),
+                );
+            }
+            _ => panic!("expected a polyline"),
+        }
+    }
+
+    #[test]
+    fn mirror_hatch_arc_edge_reverses_direction() {
+        let mut path = HatchBoundaryPath::default();
+        path.edges.push(HatchEdge::CircularArc {
+            center: Point::origin(),
+            radius: 1.0,
+            start_angle: 0.0,
+            end_angle: 90.0,
+            is_counter_clockwise: true,
+        });
+        let mut hatch = Hatch::default();
+        hatch.boundary_paths.push(path);
+        let matrix = TransformationMatrix::mirror(&Point::origin(), &Vector::x_axis());
+        match transformed(EntityType::Hatch(hatch), &matrix) {
+            EntityType::Hatch(ref hatch) => match hatch.boundary_paths[0].edges[0] {
+                HatchEdge::CircularArc {
+                    start_angle,
+                    end_angle,
+                    is_counter_clockwise,
+                    ..
+                } => {
+                    // clockwise edges store their angles mirrored; the edge runs clockwise from 180 to 90 degrees
+                    assert!(!is_counter_clockwise);
+                    assert_close(180.0, start_angle);
+                    assert_close(270.0, end_angle);
+                }
+                _ => panic!("expected an arc edge"),
+            },
+            _ => panic!("expected a hatch"),
+        }
+    }
+
+    #[test]
+    fn rotate_and_scale_text() {
+        let text = Text {
+            location: Point::new(1.0, 0.0, 0.0),
+            text_height: 1.0,
+            rotation: 10.0,
+            ..Default::default()
+        };
+        let matrix = TransformationMatrix::rotation_about_z(30.0)
+            .multiply(&TransformationMatrix::scale(2.0, 2.0, 2.0));
+        match transformed(EntityType::Text(text), &matrix) {
+            EntityType::Text(ref text) => {
+                let (sin, cos) = 30.0f64.to_radians().sin_cos();
+                assert_point_close(&Point::new(2.0 * cos, 2.0 * sin, 0.0), &text.location);
+                assert_close(40.0, text.rotation);
+                assert_close(2.0, text.text_height);
+                assert_close(1.0, text.relative_x_scale_factor);
+                assert!(!text.is_text_upside_down());
+            }
+            _ => panic!("expected text"),
+        }
+    }
+
+    #[test]
+    fn mirror_text() {
+        let text = Text {
+            text_height: 1.0,
+            ..Default::default()
+        };
+        let matrix = TransformationMatrix::mirror(&Point::origin(), &Vector::y_axis());
+        match transformed(EntityType::Text(text), &matrix) {
+            EntityType::Text(ref text) => {
+                assert_close(0.0, text.rotation);
+                assert!(text.is_text_upside_down());
+            }
+            _ => panic!("expected text"),
+        }
+    }
+
+    #[test]
+    fn rotate_and_mirror_insert() {
+        let insert = Insert {
+            location: Point::new(1.0, 0.0, 0.0),
+            ..Default::default()
+        };
+        let matrix = TransformationMatrix::mirror(&Point::origin(), &Vector::x_axis())
+            .then(&TransformationMatrix::scale(3.0, 3.0, 3.0));
+        match transformed(EntityType::Insert(insert), &matrix) {
+            EntityType::Insert(ref insert) => {
+                assert_point_close(&Point::new(-3.0, 0.0, 0.0), &insert.location);
+                assert_close(180.0, insert.rotation);
+                assert_close(3.0, insert.x_scale_factor);
+                assert_close(-3.0, insert.y_scale_factor);
+                assert_close(3.0, insert.z_scale_factor);
+            }
+            _ => panic!("expected an insert"),
+        }
+    }
+}
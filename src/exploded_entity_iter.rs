@@ -0,0 +1,257 @@
+use std::collections::VecDeque;
+
+use crate::{Block, Drawing, Handle, TransformationMatrix};
+
+use crate::entities::*;
+
+/// Nested block references deeper than this are assumed to be circular and are ignored.
+pub(crate) const MAX_BLOCK_DEPTH: usize = 32;
+
+/// Yields world coordinate copies of the entities placed by a block reference, expanding nested block references.
+pub(crate) struct ExplodedEntityIter<'a> {
+    drawing: &'a Drawing,
+    pending: VecDeque<Entity>,
+    stack: Vec<BlockFrame<'a>>,
+}
+
+/// The state of a single block being expanded.
+struct BlockFrame<'a> {
+    block: &'a Block,
+    transformations: Vec<TransformationMatrix>,
+    transformation_index: usize,
+    entity_index: usize,
+    depth: usize,
+}
+
+impl<'a> ExplodedEntityIter<'a> {
+    pub(crate) fn new(drawing: &'a Drawing, insert: &Insert) -> Self {
+        let mut iter = ExplodedEntityIter {
+            drawing,
+            pending: VecDeque::new(),
+            stack: vec![],
+        };
+        iter.push_insert(insert, &TransformationMatrix::identity(), 0);
+        iter
+    }
+    fn push_insert(&mut self, insert: &Insert, matrix: &TransformationMatrix, depth: usize) {
+        // attributes are already in the coordinates of the reference
+        for att in insert.attributes() {
+            self.pending.push_back(ExplodedEntityIter::copy_of(
+                &Entity::new(EntityType::Attribute(att.clone())),
+                matrix,
+            ));
+        }
+        if depth >= MAX_BLOCK_DEPTH {
+            return;
+        }
+        if let Some(block) = self.drawing.block_by_name(&insert.name) {
+            let transformations = insert
+                .block_transformations(&block.base_point)
+                .iter()
+                .map(|t| matrix.multiply(t))
+                .collect();
+            self.stack.push(BlockFrame {
+                block,
+                transformations,
+                transformation_index: 0,
+                entity_index: 0,
+                depth,
+            });
+        }
+    }
+    fn copy_of(entity: &Entity, matrix: &TransformationMatrix) -> Entity {
+        let mut copy = entity.clone();
+        copy.common.handle = Handle::empty();
+        copy.transform(matrix);
+        copy
+    }
+}
+
+impl<'a> Iterator for ExplodedEntityIter<'a> {
+    type Item = Entity;
+
+    fn next(&mut self) -> Option<Entity> {
+        loop {
+            if let Some(entity) = self.pending.pop_front() {
+                return Some(entity);
+            }
+
+            let frame = self.stack.last_mut()?;
+            if frame.transformation_index >= frame.transformations.len() {
+                self.stack.pop();
+                continue;
+            }
+            if frame.entity_index >= frame.block.entities.len() {
+                frame.transformation_index += 1;
+                frame.entity_index = 0;
+                continue;
+            }
+
+            let entity = &frame.block.entities[frame.entity_index];
+            let matrix = frame.transformations[frame.transformation_index];
+            let depth = frame.depth;
+            frame.entity_index += 1;
+            match entity.specific {
+                EntityType::Insert(ref insert) => self.push_insert(insert, &matrix, depth + 1),
+                // only constant attribute definitions are shown by a block reference; the others are replaced by its
+                // attributes
+                EntityType::AttributeDefinition(ref att) if !att.is_constant() => (),
+                _ => return Some(ExplodedEntityIter::copy_of(entity, &matrix)),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::entities::*;
+    use crate::*;
+
+    fn assert_point_close(expected: &Point, actual: &Point) {
+        assert!(
+            (expected.x - actual.x).abs() < 1e-9
+                && (expected.y - actual.y).abs() < 1e-9
+                && (expected.z - actual.z).abs() < 1e-9,
+            "expected {:?} but was {:?}",
+            expected,
+            actual
+        );
+    }
+
+    fn drawing_with_blocks() -> Drawing {
+        let mut drawing = Drawing::new();
+        let mut block = Block {
+            name: String::from("inner"),
+            base_point: Point::new(1.0, 0.0, 0.0),
+            ..Default::default()
+        };
+        block.entities.push(Entity::new(EntityType::Line(Line::new(
+            Point::new(1.0, 0.0, 0.0),
+            Point::new(2.0, 0.0, 0.0),
+        ))));
+        block
+            .entities
+            .push(Entity::new(EntityType::AttributeDefinition(
+                Default::default(),
+            )));
+        drawing.add_block(block);
+        let mut outer = Block {
+            name: String::from("outer"),
+            ..Default::default()
+        };
+        outer
+            .entities
+            .push(Entity::new(EntityType::Circle(Circle::new(
+                Point::origin(),
+                1.0,
+            ))));
+        outer.entities.push(Entity::new(EntityType::Insert(Insert {
+            name: String::from("INNER"),
+            location: Point::new(0.0, 5.0, 0.0),
+            ..Default::default()
+        })));
+        drawing.add_block(outer);
+        drawing
+    }
+
+    #[test]
+    fn explode_scaled_and_rotated_insert() {
+        let drawing = drawing_with_blocks();
+        let insert = Insert {
+            name: String::from("inner"),
+            location: Point::new(10.0, 0.0, 0.0),
+            x_scale_factor: 2.0,
+            y_scale_factor: 2.0,
+            rotation: 90.0,
+            ..Default::default()
+        };
+        let entities = drawing.explode_insert(&insert).collect::<Vec<_>>();
+        assert_eq!(1, entities.len());
+        match entities[0].specific {
+            EntityType::Line(ref line) => {
+                assert_point_close(&Point::new(10.0, 0.0, 0.0), &line.p1);
+                assert_point_close(&Point::new(10.0, 2.0, 0.0), &line.p2);
+            }
+            _ => panic!("expected a line"),
+        }
+        assert!(entities[0].common.handle.is_empty());
+    }
+
+    #[test]
+    fn explode_insert_with_extrusion() {
+        let drawing = drawing_with_blocks();
+        let insert = Insert {
+            name: String::from("inner"),
+            location: Point::new(3.0, 0.0, 0.0),
+            extrusion_direction: Vector::new(0.0, 0.0, -1.0),
+            ..Default::default()
+        };
+        let entities = drawing.explode_insert(&insert).collect::<Vec<_>>();
+        match entities[0].specific {
+            EntityType::Line(ref line) => {
+                // the OCS X axis of a -Z extrusion is the world -X axis
+                assert_point_close(&Point::new(-3.0, 0.0, 0.0), &line.p1);
+                assert_point_close(&Point::new(-4.0, 0.0, 0.0), &line.p2);
+            }
+            _ => panic!("expected a line"),
+        }
+    }
+
+    #[test]
+    fn explode_nested_and_arrayed_insert() {
+        let drawing = drawing_with_blocks();
+        let insert = Insert {
+            name: String::from("outer"),
+            column_count: 2,
+            row_count: 3,
+            column_spacing: 10.0,
+            row_spacing: 20.0,
+            ..Default::default()
+        };
+        let entities = drawing.explode_insert(&insert).collect::<Vec<_>>();
+        assert_eq!(12, entities.len());
+        let lines = entities
+            .iter()
+            .filter_map(|e| match e.specific {
+                EntityType::Line(ref line) => Some(line),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(6, lines.len());
+        assert_point_close(&Point::new(0.0, 5.0, 0.0), &lines[0].p1);
+        assert_point_close(&Point::new(10.0, 5.0, 0.0), &lines[1].p1);
+        assert_point_close(&Point::new(10.0, 45.0, 0.0), &lines[5].p1);
+    }
+
+    #[test]
+    fn explode_insert_includes_attributes() {
+        let mut drawing = drawing_with_blocks();
+        let mut insert = Insert {
+            name: String::from("inner"),
+            ..Default::default()
+        };
+        insert.add_attribute(
+            &mut drawing,
+            Attribute {
+                value: String::from("value"),
+                ..Default::default()
+            },
+        );
+        let entities = drawing.explode_insert(&insert).collect::<Vec<_>>();
+        assert_eq!(2, entities.len());
+        match entities[0].specific {
+            EntityType::Attribute(ref att) => assert_eq!("value", att.value),
+            _ => panic!("expected an attribute"),
+        }
+    }
+
+    #[test]
+    fn explode_insert_of_missing_block() {
+        let drawing = Drawing::new();
+        let insert = Insert {
+            name: String::from("missing"),
+            ..Default::default()
+        };
+        assert_eq!(0, drawing.explode_insert(&insert).count());
+    }
+}
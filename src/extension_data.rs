@@ -1,4 +1,4 @@
-use crate::{CodePair, DxfError, DxfResult};
+use crate::{CodePair, DxfError, DxfResult, Handle};
 
 use crate::code_pair_put_back::CodePairPutBack;
 
@@ -63,6 +63,20 @@ impl ExtensionGroup {
             items,
         })
     }
+    pub(crate) fn add_pointers(&self, handles: &mut Vec<Handle>) {
+        for item in &self.items {
+            match item {
+                // e.g., the owners listed by `{ACAD_REACTORS`
+                ExtensionGroupItem::CodePair(pair) if is_pointer_code(pair.code) => {
+                    if let Ok(handle) = pair.as_handle() {
+                        handles.push(handle);
+                    }
+                }
+                ExtensionGroupItem::CodePair(_) => (),
+                ExtensionGroupItem::Group(ref group) => group.add_pointers(handles),
+            }
+        }
+    }
     pub(crate) fn add_code_pairs(&self, pairs: &mut Vec<CodePair>) {
         if !self.items.is_empty() {
             let mut full_group_name = String::new();
@@ -79,3 +93,7 @@ impl ExtensionGroup {
         }
     }
 }
+
+fn is_pointer_code(code: i32) -> bool {
+    (320..=369).contains(&code) || (390..=399).contains(&code) || (480..=481).contains(&code)
+}
@@ -0,0 +1,381 @@
+use std::collections::HashMap;
+
+use crate::entities::*;
+use crate::objects::*;
+use crate::{Block, Drawing, DrawingItem, ExtensionGroup, Handle, XData};
+
+/// Constant-time lookups of the items in a `Drawing` by handle, and of the items that point at a given handle.
+///
+/// Created with `Drawing::build_handle_index()`.  The index borrows the drawing, so it can't outlive any change to it;
+/// build a new one after calling any of the `add_*` or `remove_*` functions.
+pub struct HandleIndex<'a> {
+    items: HashMap<Handle, DrawingItem<'a>>,
+    references: HashMap<Handle, Vec<Handle>>,
+}
+
+// public implementation
+impl<'a> HandleIndex<'a> {
+    /// Gets the `DrawingItem` with the specified handle or `None`.  Unlike `Drawing::item_by_handle()`, entities
+    /// contained in blocks are also found.
+    pub fn item_by_handle(&self, handle: Handle) -> Option<DrawingItem<'a>> {
+        self.items.get(&handle).copied()
+    }
+    /// Returns the handles of all items that have a pointer to the specified handle, e.g., the entities on a layer's
+    /// owning block record or the dictionaries that contain an object.
+    pub fn handles_referencing(&self, handle: Handle) -> &[Handle] {
+        match self.references.get(&handle) {
+            Some(handles) => handles,
+            None => &[],
+        }
+    }
+    /// Returns all items that have a pointer to the specified handle.
+    pub fn items_referencing(&self, handle: Handle) -> impl Iterator<Item = DrawingItem<'a>> + '_ {
+        self.handles_referencing(handle)
+            .iter()
+            .filter_map(move |h| self.item_by_handle(*h))
+    }
+    /// Returns the number of indexed items.
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+    /// Returns `true` if the drawing contains no items with a handle.
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+}
+
+// internal visibility only
+impl<'a> HandleIndex<'a> {
+    /// Returns every pointer to a handle that isn't in the index as `(pointer, referencing item handle)`, sorted by the
+    /// referencing handle.
+    pub(crate) fn unresolved_pointers(&self) -> Vec<(Handle, Handle)> {
+        let mut pointers = self
+            .references
+            .iter()
+            .filter(|(pointer, _)| !self.items.contains_key(pointer))
+            .flat_map(|(pointer, handles)| handles.iter().map(move |h| (*pointer, *h)))
+            .collect::<Vec<_>>();
+        pointers.sort_by_key(|(pointer, handle)| (handle.0, pointer.0));
+        pointers
+    }
+    pub(crate) fn new(drawing: &'a Drawing) -> Self {
+        let mut index = HandleIndex {
+            items: HashMap::new(),
+            references: HashMap::new(),
+        };
+        let mut pointers = vec![];
+
+        // the same order as `Drawing::item_by_handle()` so the first item wins on duplicate handles
+        for item in drawing.app_ids() {
+            item.add_pointers(&mut pointers);
+            add_extension_pointers(&item.extension_data_groups, &item.x_data, &mut pointers);
+            index.add(DrawingItem::AppId(item), &mut pointers);
+        }
+        for item in drawing.blocks() {
+            add_block_pointers(item, &mut pointers);
+            index.add(DrawingItem::Block(item), &mut pointers);
+        }
+        for item in drawing.block_records() {
+            item.add_pointers(&mut pointers);
+            add_extension_pointers(&item.extension_data_groups, &item.x_data, &mut pointers);
+            index.add(DrawingItem::BlockRecord(item), &mut pointers);
+        }
+        for item in drawing.dim_styles() {
+            item.add_pointers(&mut pointers);
+            add_extension_pointers(&item.extension_data_groups, &item.x_data, &mut pointers);
+            index.add(DrawingItem::DimStyle(item), &mut pointers);
+        }
+        for item in drawing.entities() {
+            add_entity_pointers(item, &mut pointers);
+            index.add(DrawingItem::Entity(item), &mut pointers);
+        }
+        for item in drawing.layers() {
+            item.add_pointers(&mut pointers);
+            add_extension_pointers(&item.extension_data_groups, &item.x_data, &mut pointers);
+            index.add(DrawingItem::Layer(item), &mut pointers);
+        }
+        for item in drawing.line_types() {
+            item.add_pointers(&mut pointers);
+            add_extension_pointers(&item.extension_data_groups, &item.x_data, &mut pointers);
+            index.add(DrawingItem::LineType(item), &mut pointers);
+        }
+        for item in drawing.objects() {
+            add_object_pointers(item, &mut pointers);
+            index.add(DrawingItem::Object(item), &mut pointers);
+        }
+        for item in drawing.styles() {
+            item.add_pointers(&mut pointers);
+            add_extension_pointers(&item.extension_data_groups, &item.x_data, &mut pointers);
+            index.add(DrawingItem::Style(item), &mut pointers);
+        }
+        for item in drawing.ucss() {
+            item.add_pointers(&mut pointers);
+            add_extension_pointers(&item.extension_data_groups, &item.x_data, &mut pointers);
+            index.add(DrawingItem::Ucs(item), &mut pointers);
+        }
+        for item in drawing.views() {
+            item.add_pointers(&mut pointers);
+            add_extension_pointers(&item.extension_data_groups, &item.x_data, &mut pointers);
+            index.add(DrawingItem::View(item), &mut pointers);
+        }
+        for item in drawing.view_ports() {
+            item.add_pointers(&mut pointers);
+            add_extension_pointers(&item.extension_data_groups, &item.x_data, &mut pointers);
+            index.add(DrawingItem::ViewPort(item), &mut pointers);
+        }
+
+        // block entities aren't found by `Drawing::item_by_handle()`, so they come last
+        for block in drawing.blocks() {
+            for item in &block.entities {
+                add_entity_pointers(item, &mut pointers);
+                index.add(DrawingItem::Entity(item), &mut pointers);
+            }
+        }
+
+        index
+    }
+}
+
+// private implementation
+impl<'a> HandleIndex<'a> {
+    fn add(&mut self, item: DrawingItem<'a>, pointers: &mut Vec<Handle>) {
+        let handle = item.handle();
+        if !handle.is_empty() {
+            self.items.entry(handle).or_insert(item);
+        }
+
+        pointers.sort_by_key(|h| h.0);
+        pointers.dedup();
+        for pointer in pointers.drain(..) {
+            if !pointer.is_empty() {
+                self.references.entry(pointer).or_default().push(handle);
+            }
+        }
+    }
+}
+
+fn add_extension_pointers(
+    extension_data_groups: &[ExtensionGroup],
+    x_data: &[XData],
+    pointers: &mut Vec<Handle>,
+) {
+    for group in extension_data_groups {
+        group.add_pointers(pointers);
+    }
+    for x in x_data {
+        x.add_pointers(pointers);
+    }
+}
+
+fn add_block_pointers(block: &Block, pointers: &mut Vec<Handle>) {
+    pointers.push(block.__owner_handle);
+    add_extension_pointers(&block.extension_data_groups, &block.x_data, pointers);
+}
+
+fn add_entity_pointers(entity: &Entity, pointers: &mut Vec<Handle>) {
+    entity.common.add_pointers(pointers);
+    add_extension_pointers(
+        &entity.common.extension_data_groups,
+        &entity.common.x_data,
+        pointers,
+    );
+    entity.specific.add_pointers(pointers);
+    match entity.specific {
+        EntityType::Hatch(ref hatch) => {
+            for path in &hatch.boundary_paths {
+                path.add_pointers(pointers);
+            }
+        }
+        EntityType::Image(ref image) => {
+            add_string_pointer(&image.image_def_reference, pointers);
+            add_string_pointer(&image.image_def_reactor_reference, pointers);
+        }
+        EntityType::MLeader(ref mleader) => {
+            mleader.context_data.add_pointers(pointers);
+            for arrowhead in &mleader.arrowhead_overrides {
+                pointers.push(arrowhead.__arrowhead_handle);
+            }
+            for attribute in &mleader.block_attributes {
+                pointers.push(attribute.__attribute_definition_handle);
+            }
+        }
+        EntityType::Table(ref table) => {
+            for cell in &table.cells {
+                cell.add_pointers(pointers);
+            }
+        }
+        EntityType::Wipeout(ref wipeout) => {
+            add_string_pointer(&wipeout.image_def_reference, pointers);
+            add_string_pointer(&wipeout.image_def_reactor_reference, pointers);
+        }
+        _ => (),
+    }
+}
+
+fn add_object_pointers(object: &Object, pointers: &mut Vec<Handle>) {
+    object.common.add_pointers(pointers);
+    add_extension_pointers(
+        &object.common.extension_data_groups,
+        &object.common.x_data,
+        pointers,
+    );
+    object.specific.add_pointers(pointers);
+    match object.specific {
+        ObjectType::Dictionary(ref dict) => {
+            pointers.extend(dict.value_handles.values());
+        }
+        ObjectType::DictionaryWithDefault(ref dict) => {
+            pointers.push(dict.default_handle);
+            pointers.extend(dict.value_handles.values());
+        }
+        ObjectType::SectionSettings(ref settings) => {
+            for geometry_settings in &settings.geometry_settings {
+                geometry_settings.add_pointers(pointers);
+            }
+        }
+        _ => (),
+    }
+}
+
+// image definitions are referenced by hex strings instead of handles
+fn add_string_pointer(value: &str, pointers: &mut Vec<Handle>) {
+    if let Ok(handle) = u64::from_str_radix(value, 16) {
+        pointers.push(Handle(handle));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::entities::*;
+    use crate::helper_functions::tests::*;
+    use crate::objects::*;
+    use crate::tables::*;
+    use crate::*;
+
+    fn handles<'a>(items: impl Iterator<Item = DrawingItem<'a>>) -> Vec<Handle> {
+        let mut handles = items.map(|i| i.handle()).collect::<Vec<_>>();
+        handles.sort_by_key(|h| h.0);
+        handles
+    }
+
+    #[test]
+    fn look_up_items_by_handle() {
+        let mut drawing = Drawing::new();
+        let layer_handle = drawing.add_layer(Layer::default()).handle;
+        let entity_handle = drawing
+            .add_entity(Entity::new(EntityType::Line(Line::default())))
+            .common
+            .handle;
+        let object_handle = drawing
+            .add_object(Object::new(ObjectType::PlaceHolder(PlaceHolder::default())))
+            .common
+            .handle;
+        let index = drawing.build_handle_index();
+        match index.item_by_handle(layer_handle) {
+            Some(DrawingItem::Layer(_)) => (),
+            _ => panic!("expected a layer"),
+        }
+        match index.item_by_handle(entity_handle) {
+            Some(DrawingItem::Entity(_)) => (),
+            _ => panic!("expected an entity"),
+        }
+        match index.item_by_handle(object_handle) {
+            Some(DrawingItem::Object(_)) => (),
+            _ => panic!("expected an object"),
+        }
+        assert!(index.item_by_handle(Handle(0xFFFF)).is_none());
+        assert!(index.item_by_handle(Handle::empty()).is_none());
+    }
+
+    #[test]
+    fn look_up_block_entities_by_handle() {
+        let mut drawing = Drawing::new();
+        let mut block = Block::default();
+        block
+            .entities
+            .push(Entity::new(EntityType::Line(Line::default())));
+        let line_handle = drawing.add_block(block).entities[0].common.handle;
+        let index = drawing.build_handle_index();
+        match index.item_by_handle(line_handle) {
+            Some(DrawingItem::Entity(_)) => (),
+            _ => panic!("expected an entity"),
+        }
+    }
+
+    #[test]
+    fn items_referencing_owner() {
+        let drawing = drawing_from_pairs(vec![
+            CodePair::new_str(0, "SECTION"),
+            CodePair::new_str(2, "ENTITIES"),
+            CodePair::new_str(0, "LINE"),
+            CodePair::new_str(5, "A1"),
+            CodePair::new_str(330, "B1"),
+            CodePair::new_str(0, "CIRCLE"),
+            CodePair::new_str(5, "A2"),
+            CodePair::new_str(330, "B1"),
+            CodePair::new_str(0, "ARC"),
+            CodePair::new_str(5, "A3"),
+            CodePair::new_str(330, "B2"),
+            CodePair::new_str(0, "ENDSEC"),
+            CodePair::new_str(0, "EOF"),
+        ]);
+        let index = drawing.build_handle_index();
+        assert_eq!(
+            vec![Handle(0xA1), Handle(0xA2)],
+            handles(index.items_referencing(Handle(0xB1)))
+        );
+        assert_eq!(&[Handle(0xA3)], index.handles_referencing(Handle(0xB2)));
+        assert!(index.handles_referencing(Handle(0xB3)).is_empty());
+    }
+
+    #[test]
+    fn dictionary_values_are_references() {
+        let drawing = drawing_from_pairs(vec![
+            CodePair::new_str(0, "SECTION"),
+            CodePair::new_str(2, "OBJECTS"),
+            CodePair::new_str(0, "DICTIONARY"),
+            CodePair::new_str(5, "C1"),
+            CodePair::new_str(3, "key"),
+            CodePair::new_str(350, "C2"),
+            CodePair::new_str(0, "ACDBPLACEHOLDER"),
+            CodePair::new_str(5, "C2"),
+            CodePair::new_str(102, "{ACAD_REACTORS"),
+            CodePair::new_str(330, "C1"),
+            CodePair::new_str(102, "}"),
+            CodePair::new_str(0, "ENDSEC"),
+            CodePair::new_str(0, "EOF"),
+        ]);
+        let index = drawing.build_handle_index();
+        assert_eq!(&[Handle(0xC1)], index.handles_referencing(Handle(0xC2)));
+        assert_eq!(&[Handle(0xC2)], index.handles_referencing(Handle(0xC1)));
+    }
+
+    #[test]
+    fn multiple_pointers_are_references() {
+        let mut drawing = Drawing::new();
+        let layer_handle = drawing.add_layer(Layer::default()).handle;
+        let mut viewport = Viewport::default();
+        viewport.__frozen_layers_handle.push(layer_handle);
+        let viewport_handle = drawing
+            .add_entity(Entity::new(EntityType::Viewport(viewport)))
+            .common
+            .handle;
+        let index = drawing.build_handle_index();
+        assert_eq!(&[viewport_handle], index.handles_referencing(layer_handle));
+    }
+
+    #[test]
+    fn x_data_handles_are_references() {
+        let mut drawing = Drawing::new();
+        let mut line = Entity::new(EntityType::Line(Line::default()));
+        line.common.x_data.push(XData {
+            application_name: String::from("ACAD"),
+            items: vec![XDataItem::ControlGroup(vec![XDataItem::Handle(Handle(
+                0xD1,
+            ))])],
+        });
+        let line_handle = drawing.add_entity(line).common.handle;
+        let index = drawing.build_handle_index();
+        assert_eq!(&[line_handle], index.handles_referencing(Handle(0xD1)));
+    }
+}
@@ -0,0 +1,578 @@
+use crate::{
+    CodePair, Drawing, DrawingItem, DxfError, DxfResult, Handle, LwPolylineVertex, Point, Vector,
+};
+
+use crate::code_pair_put_back::CodePairPutBack;
+use crate::entities::Entity;
+use crate::enums::*;
+use crate::helper_functions::*;
+
+/// A single edge of a non-polyline `HatchBoundaryPath`.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub enum HatchEdge {
+    Line {
+        start: Point,
+        end: Point,
+    },
+    /// Angles are in degrees.
+    CircularArc {
+        center: Point,
+        radius: f64,
+        start_angle: f64,
+        end_angle: f64,
+        is_counter_clockwise: bool,
+    },
+    /// The major axis is relative to the center and angles are in degrees.
+    EllipticArc {
+        center: Point,
+        major_axis: Vector,
+        minor_axis_ratio: f64,
+        start_angle: f64,
+        end_angle: f64,
+        is_counter_clockwise: bool,
+    },
+    Spline {
+        degree_of_curve: i32,
+        is_rational: bool,
+        is_periodic: bool,
+        knot_values: Vec<f64>,
+        control_points: Vec<Point>,
+        weights: Vec<f64>,
+        fit_points: Vec<Point>,
+        start_tangent: Vector,
+        end_tangent: Vector,
+    },
+}
+
+/// A single boundary loop of a `Hatch`.  Polyline paths store their data in `vertices`, all other paths store their data
+/// in `edges`.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct HatchBoundaryPath {
+    /// Boundary path type flags.
+    pub flags: i32,
+    /// Whether a polyline path is closed.
+    pub is_closed: bool,
+    /// The vertices of a polyline path; only `x`, `y`, and `bulge` are used.
+    pub vertices: Vec<LwPolylineVertex>,
+    /// The edges of a non-polyline path.
+    pub edges: Vec<HatchEdge>,
+    #[doc(hidden)]
+    pub __source_boundary_objects_handle: Vec<Handle>,
+}
+
+impl Default for HatchBoundaryPath {
+    fn default() -> Self {
+        HatchBoundaryPath {
+            flags: 0,
+            is_closed: true,
+            vertices: vec![],
+            edges: vec![],
+            __source_boundary_objects_handle: vec![],
+        }
+    }
+}
+
+// public implementation
+impl HatchBoundaryPath {
+    /// Creates a new polyline boundary path from the given vertices.
+    pub fn from_vertices(vertices: Vec<LwPolylineVertex>) -> Self {
+        let mut path = HatchBoundaryPath {
+            vertices,
+            ..Default::default()
+        };
+        path.set_is_external(true);
+        path.set_is_polyline(true);
+        path
+    }
+    /// Creates a new boundary path from the given edges.
+    pub fn from_edges(edges: Vec<HatchEdge>) -> Self {
+        let mut path = HatchBoundaryPath {
+            edges,
+            ..Default::default()
+        };
+        path.set_is_external(true);
+        path
+    }
+    pub fn is_external(&self) -> bool {
+        self.flag(1)
+    }
+    pub fn set_is_external(&mut self, val: bool) {
+        self.set_flag(1, val)
+    }
+    pub fn is_polyline(&self) -> bool {
+        self.flag(2)
+    }
+    pub fn set_is_polyline(&mut self, val: bool) {
+        self.set_flag(2, val)
+    }
+    pub fn is_derived(&self) -> bool {
+        self.flag(4)
+    }
+    pub fn set_is_derived(&mut self, val: bool) {
+        self.set_flag(4, val)
+    }
+    pub fn is_text_box(&self) -> bool {
+        self.flag(8)
+    }
+    pub fn set_is_text_box(&mut self, val: bool) {
+        self.set_flag(8, val)
+    }
+    pub fn is_outermost(&self) -> bool {
+        self.flag(16)
+    }
+    pub fn set_is_outermost(&mut self, val: bool) {
+        self.set_flag(16, val)
+    }
+    /// Returns the entities that were used to create this path.
+    pub fn source_boundary_objects<'a>(&self, drawing: &'a Drawing) -> Vec<&'a Entity> {
+        self.__source_boundary_objects_handle
+            .iter()
+            .filter_map(|&h| match drawing.item_by_handle(h) {
+                Some(DrawingItem::Entity(val)) => Some(val),
+                _ => None,
+            })
+            .collect()
+    }
+    pub fn add_source_boundary_object(&mut self, item: &Entity) {
+        self.__source_boundary_objects_handle
+            .push(DrawingItem::Entity(item).handle());
+    }
+}
+
+// internal visibility only
+impl HatchBoundaryPath {
+    pub(crate) fn add_pointers(&self, handles: &mut Vec<Handle>) {
+        handles.extend_from_slice(&self.__source_boundary_objects_handle);
+    }
+    pub(crate) fn read(iter: &mut CodePairPutBack) -> DxfResult<Option<HatchBoundaryPath>> {
+        // check the first pair; only code 92 can start one of these
+        let mut path = HatchBoundaryPath::default();
+        match iter.next() {
+            Some(Ok(pair @ CodePair { code: 92, .. })) => {
+                path.flags = pair.assert_i32()?;
+            }
+            Some(Ok(pair)) => {
+                iter.put_back(Ok(pair));
+                return Ok(None);
+            }
+            Some(Err(e)) => return Err(e),
+            None => return Ok(None),
+        }
+
+        loop {
+            let pair = match iter.next() {
+                Some(Ok(pair)) => pair,
+                Some(Err(e)) => return Err(e),
+                None => return Ok(Some(path)),
+            };
+
+            match pair.code {
+                72 if !path.is_polyline() => {
+                    let edge = HatchEdge::read(&pair, iter)?;
+                    path.edges.push(edge);
+                }
+                72 => (), // has bulge flag; recomputed when writing
+                73 if path.is_polyline() => {
+                    path.is_closed = as_bool(pair.assert_i16()?);
+                }
+                93 => (), // vertex/edge count; recomputed when writing
+                10 if path.is_polyline() => {
+                    path.vertices.push(LwPolylineVertex {
+                        x: pair.assert_f64()?,
+                        ..Default::default()
+                    });
+                }
+                20 if path.is_polyline() => {
+                    vec_last!(path.vertices).y = pair.assert_f64()?;
+                }
+                42 if path.is_polyline() => {
+                    vec_last!(path.vertices).bulge = pair.assert_f64()?;
+                }
+                97 => (), // source boundary object count
+                330 => {
+                    path.__source_boundary_objects_handle
+                        .push(pair.as_handle()?);
+                }
+                _ => {
+                    // end of the path; put the pair back and return what we have
+                    iter.put_back(Ok(pair));
+                    break;
+                }
+            }
+        }
+
+        Ok(Some(path))
+    }
+    pub(crate) fn add_code_pairs(&self, pairs: &mut Vec<CodePair>, version: AcadVersion) {
+        pairs.push(CodePair::new_i32(92, self.flags));
+        if self.is_polyline() {
+            let has_bulge = self.vertices.iter().any(|v| v.bulge != 0.0);
+            pairs.push(CodePair::new_i16(72, as_i16(has_bulge)));
+            pairs.push(CodePair::new_i16(73, as_i16(self.is_closed)));
+            pairs.push(CodePair::new_i32(93, self.vertices.len() as i32));
+            for v in &self.vertices {
+                pairs.push(CodePair::new_f64(10, v.x));
+                pairs.push(CodePair::new_f64(20, v.y));
+                if has_bulge {
+                    pairs.push(CodePair::new_f64(42, v.bulge));
+                }
+            }
+        } else {
+            pairs.push(CodePair::new_i32(93, self.edges.len() as i32));
+            for edge in &self.edges {
+                edge.add_code_pairs(pairs, version);
+            }
+        }
+
+        pairs.push(CodePair::new_i32(
+            97,
+            self.__source_boundary_objects_handle.len() as i32,
+        ));
+        for handle in &self.__source_boundary_objects_handle {
+            pairs.push(CodePair::new_string(330, &handle.as_string()));
+        }
+    }
+}
+
+// private implementation
+impl HatchBoundaryPath {
+    fn flag(&self, mask: i32) -> bool {
+        self.flags & mask != 0
+    }
+    fn set_flag(&mut self, mask: i32, val: bool) {
+        if val {
+            self.flags |= mask;
+        } else {
+            self.flags &= !mask;
+        }
+    }
+}
+
+// internal visibility only
+impl HatchEdge {
+    pub(crate) fn edge_type(&self) -> i16 {
+        match self {
+            HatchEdge::Line { .. } => 1,
+            HatchEdge::CircularArc { .. } => 2,
+            HatchEdge::EllipticArc { .. } => 3,
+            HatchEdge::Spline { .. } => 4,
+        }
+    }
+    pub(crate) fn read(edge_type: &CodePair, iter: &mut CodePairPutBack) -> DxfResult<HatchEdge> {
+        let mut edge = match edge_type.assert_i16()? {
+            1 => HatchEdge::Line {
+                start: Point::origin(),
+                end: Point::origin(),
+            },
+            2 => HatchEdge::CircularArc {
+                center: Point::origin(),
+                radius: 0.0,
+                start_angle: 0.0,
+                end_angle: 360.0,
+                is_counter_clockwise: true,
+            },
+            3 => HatchEdge::EllipticArc {
+                center: Point::origin(),
+                major_axis: Vector::x_axis(),
+                minor_axis_ratio: 1.0,
+                start_angle: 0.0,
+                end_angle: 360.0,
+                is_counter_clockwise: true,
+            },
+            4 => HatchEdge::Spline {
+                degree_of_curve: 3,
+                is_rational: false,
+                is_periodic: false,
+                knot_values: vec![],
+                control_points: vec![],
+                weights: vec![],
+                fit_points: vec![],
+                start_tangent: Vector::zero(),
+                end_tangent: Vector::zero(),
+            },
+            _ => return Err(DxfError::UnexpectedEnumValue(edge_type.offset)),
+        };
+
+        let mut read_fit_point_count = false;
+        loop {
+            let pair = match iter.next() {
+                Some(Ok(pair)) => pair,
+                Some(Err(e)) => return Err(e),
+                None => return Ok(edge),
+            };
+
+            let handled = match edge {
+                HatchEdge::Line {
+                    ref mut start,
+                    ref mut end,
+                } => match pair.code {
+                    10 => {
+                        start.x = pair.assert_f64()?;
+                        true
+                    }
+                    20 => {
+                        start.y = pair.assert_f64()?;
+                        true
+                    }
+                    11 => {
+                        end.x = pair.assert_f64()?;
+                        true
+                    }
+                    21 => {
+                        end.y = pair.assert_f64()?;
+                        true
+                    }
+                    _ => false,
+                },
+                HatchEdge::CircularArc {
+                    ref mut center,
+                    ref mut radius,
+                    ref mut start_angle,
+                    ref mut end_angle,
+                    ref mut is_counter_clockwise,
+                } => match pair.code {
+                    10 => {
+                        center.x = pair.assert_f64()?;
+                        true
+                    }
+                    20 => {
+                        center.y = pair.assert_f64()?;
+                        true
+                    }
+                    40 => {
+                        *radius = pair.assert_f64()?;
+                        true
+                    }
+                    50 => {
+                        *start_angle = pair.assert_f64()?;
+                        true
+                    }
+                    51 => {
+                        *end_angle = pair.assert_f64()?;
+                        true
+                    }
+                    73 => {
+                        *is_counter_clockwise = as_bool(pair.assert_i16()?);
+                        true
+                    }
+                    _ => false,
+                },
+                HatchEdge::EllipticArc {
+                    ref mut center,
+                    ref mut major_axis,
+                    ref mut minor_axis_ratio,
+                    ref mut start_angle,
+                    ref mut end_angle,
+                    ref mut is_counter_clockwise,
+                } => match pair.code {
+                    10 => {
+                        center.x = pair.assert_f64()?;
+                        true
+                    }
+                    20 => {
+                        center.y = pair.assert_f64()?;
+                        true
+                    }
+                    11 => {
+                        major_axis.x = pair.assert_f64()?;
+                        true
+                    }
+                    21 => {
+                        major_axis.y = pair.assert_f64()?;
+                        true
+                    }
+                    40 => {
+                        *minor_axis_ratio = pair.assert_f64()?;
+                        true
+                    }
+                    50 => {
+                        *start_angle = pair.assert_f64()?;
+                        true
+                    }
+                    51 => {
+                        *end_angle = pair.assert_f64()?;
+                        true
+                    }
+                    73 => {
+                        *is_counter_clockwise = as_bool(pair.assert_i16()?);
+                        true
+                    }
+                    _ => false,
+                },
+                HatchEdge::Spline {
+                    ref mut degree_of_curve,
+                    ref mut is_rational,
+                    ref mut is_periodic,
+                    ref mut knot_values,
+                    ref mut control_points,
+                    ref mut weights,
+                    ref mut fit_points,
+                    ref mut start_tangent,
+                    ref mut end_tangent,
+                } => match pair.code {
+                    94 => {
+                        *degree_of_curve = pair.assert_i32()?;
+                        true
+                    }
+                    73 => {
+                        *is_rational = as_bool(pair.assert_i16()?);
+                        true
+                    }
+                    74 => {
+                        *is_periodic = as_bool(pair.assert_i16()?);
+                        true
+                    }
+                    95 | 96 => true, // knot and control point counts
+                    40 => {
+                        knot_values.push(pair.assert_f64()?);
+                        true
+                    }
+                    10 => {
+                        control_points.push(Point::new(pair.assert_f64()?, 0.0, 0.0));
+                        true
+                    }
+                    20 => {
+                        vec_last!(control_points).y = pair.assert_f64()?;
+                        true
+                    }
+                    42 => {
+                        weights.push(pair.assert_f64()?);
+                        true
+                    }
+                    97 if !read_fit_point_count => {
+                        // code 97 is either the fit point count (R2010+) or the start of the path's source boundary
+                        // objects; only the former is followed by fit point, tangent, or another 97 pair
+                        match iter.next() {
+                            Some(Ok(next)) => {
+                                let is_fit_point_count = matches!(next.code, 11 | 12 | 13 | 97);
+                                iter.put_back(Ok(next));
+                                if is_fit_point_count {
+                                    read_fit_point_count = true;
+                                }
+                                is_fit_point_count
+                            }
+                            Some(Err(e)) => return Err(e),
+                            None => false,
+                        }
+                    }
+                    11 => {
+                        fit_points.push(Point::new(pair.assert_f64()?, 0.0, 0.0));
+                        true
+                    }
+                    21 => {
+                        vec_last!(fit_points).y = pair.assert_f64()?;
+                        true
+                    }
+                    12 => {
+                        start_tangent.x = pair.assert_f64()?;
+                        true
+                    }
+                    22 => {
+                        start_tangent.y = pair.assert_f64()?;
+                        true
+                    }
+                    13 => {
+                        end_tangent.x = pair.assert_f64()?;
+                        true
+                    }
+                    23 => {
+                        end_tangent.y = pair.assert_f64()?;
+                        true
+                    }
+                    _ => false,
+                },
+            };
+
+            if !handled {
+                // end of the edge; put the pair back and return what we have
+                iter.put_back(Ok(pair));
+                return Ok(edge);
+            }
+        }
+    }
+    pub(crate) fn add_code_pairs(&self, pairs: &mut Vec<CodePair>, version: AcadVersion) {
+        pairs.push(CodePair::new_i16(72, self.edge_type()));
+        match self {
+            HatchEdge::Line { start, end } => {
+                pairs.push(CodePair::new_f64(10, start.x));
+                pairs.push(CodePair::new_f64(20, start.y));
+                pairs.push(CodePair::new_f64(11, end.x));
+                pairs.push(CodePair::new_f64(21, end.y));
+            }
+            HatchEdge::CircularArc {
+                center,
+                radius,
+                start_angle,
+                end_angle,
+                is_counter_clockwise,
+            } => {
+                pairs.push(CodePair::new_f64(10, center.x));
+                pairs.push(CodePair::new_f64(20, center.y));
+                pairs.push(CodePair::new_f64(40, *radius));
+                pairs.push(CodePair::new_f64(50, *start_angle));
+                pairs.push(CodePair::new_f64(51, *end_angle));
+                pairs.push(CodePair::new_i16(73, as_i16(*is_counter_clockwise)));
+            }
+            HatchEdge::EllipticArc {
+                center,
+                major_axis,
+                minor_axis_ratio,
+                start_angle,
+                end_angle,
+                is_counter_clockwise,
+            } => {
+                pairs.push(CodePair::new_f64(10, center.x));
+                pairs.push(CodePair::new_f64(20, center.y));
+                pairs.push(CodePair::new_f64(11, major_axis.x));
+                pairs.push(CodePair::new_f64(21, major_axis.y));
+                pairs.push(CodePair::new_f64(40, *minor_axis_ratio));
+                pairs.push(CodePair::new_f64(50, *start_angle));
+                pairs.push(CodePair::new_f64(51, *end_angle));
+                pairs.push(CodePair::new_i16(73, as_i16(*is_counter_clockwise)));
+            }
+            HatchEdge::Spline {
+                degree_of_curve,
+                is_rational,
+                is_periodic,
+                knot_values,
+                control_points,
+                weights,
+                fit_points,
+                start_tangent,
+                end_tangent,
+            } => {
+                pairs.push(CodePair::new_i32(94, *degree_of_curve));
+                pairs.push(CodePair::new_i16(73, as_i16(*is_rational)));
+                pairs.push(CodePair::new_i16(74, as_i16(*is_periodic)));
+                pairs.push(CodePair::new_i32(95, knot_values.len() as i32));
+                pairs.push(CodePair::new_i32(96, control_points.len() as i32));
+                for knot in knot_values {
+                    pairs.push(CodePair::new_f64(40, *knot));
+                }
+                for (i, cp) in control_points.iter().enumerate() {
+                    pairs.push(CodePair::new_f64(10, cp.x));
+                    pairs.push(CodePair::new_f64(20, cp.y));
+                    if *is_rational {
+                        pairs.push(CodePair::new_f64(
+                            42,
+                            weights.get(i).cloned().unwrap_or(1.0),
+                        ));
+                    }
+                }
+                if version >= AcadVersion::R2010 {
+                    pairs.push(CodePair::new_i32(97, fit_points.len() as i32));
+                    for fp in fit_points {
+                        pairs.push(CodePair::new_f64(11, fp.x));
+                        pairs.push(CodePair::new_f64(21, fp.y));
+                    }
+                    pairs.push(CodePair::new_f64(12, start_tangent.x));
+                    pairs.push(CodePair::new_f64(22, start_tangent.y));
+                    pairs.push(CodePair::new_f64(13, end_tangent.x));
+                    pairs.push(CodePair::new_f64(23, end_tangent.y));
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,92 @@
+use crate::{CodePair, DxfResult, Point, Vector};
+
+use crate::code_pair_put_back::CodePairPutBack;
+
+/// A single line of a `Hatch` pattern definition.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct HatchPatternDefinitionLine {
+    /// The line angle in degrees.
+    pub angle: f64,
+    pub base_point: Point,
+    pub offset: Vector,
+    /// Alternating dash (positive) and space (negative) lengths.
+    pub dash_lengths: Vec<f64>,
+}
+
+impl Default for HatchPatternDefinitionLine {
+    fn default() -> Self {
+        HatchPatternDefinitionLine {
+            angle: 0.0,
+            base_point: Point::origin(),
+            offset: Vector::zero(),
+            dash_lengths: vec![],
+        }
+    }
+}
+
+// internal visibility only
+impl HatchPatternDefinitionLine {
+    pub(crate) fn read(
+        iter: &mut CodePairPutBack,
+    ) -> DxfResult<Option<HatchPatternDefinitionLine>> {
+        // check the first pair; only code 53 can start one of these
+        let mut line = HatchPatternDefinitionLine::default();
+        match iter.next() {
+            Some(Ok(pair @ CodePair { code: 53, .. })) => {
+                line.angle = pair.assert_f64()?;
+            }
+            Some(Ok(pair)) => {
+                iter.put_back(Ok(pair));
+                return Ok(None);
+            }
+            Some(Err(e)) => return Err(e),
+            None => return Ok(None),
+        }
+
+        loop {
+            let pair = match iter.next() {
+                Some(Ok(pair)) => pair,
+                Some(Err(e)) => return Err(e),
+                None => return Ok(Some(line)),
+            };
+
+            match pair.code {
+                43 => {
+                    line.base_point.x = pair.assert_f64()?;
+                }
+                44 => {
+                    line.base_point.y = pair.assert_f64()?;
+                }
+                45 => {
+                    line.offset.x = pair.assert_f64()?;
+                }
+                46 => {
+                    line.offset.y = pair.assert_f64()?;
+                }
+                79 => (), // dash length count; recomputed when writing
+                49 => {
+                    line.dash_lengths.push(pair.assert_f64()?);
+                }
+                _ => {
+                    // end of the line; put the pair back and return what we have
+                    iter.put_back(Ok(pair));
+                    break;
+                }
+            }
+        }
+
+        Ok(Some(line))
+    }
+    pub(crate) fn add_code_pairs(&self, pairs: &mut Vec<CodePair>) {
+        pairs.push(CodePair::new_f64(53, self.angle));
+        pairs.push(CodePair::new_f64(43, self.base_point.x));
+        pairs.push(CodePair::new_f64(44, self.base_point.y));
+        pairs.push(CodePair::new_f64(45, self.offset.x));
+        pairs.push(CodePair::new_f64(46, self.offset.y));
+        pairs.push(CodePair::new_i16(79, self.dash_lengths.len() as i16));
+        for dash_length in &self.dash_lengths {
+            pairs.push(CodePair::new_f64(49, *dash_length));
+        }
+    }
+}
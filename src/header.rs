@@ -3,7 +3,10 @@
 use crate::code_pair_put_back::CodePairPutBack;
 use crate::enums::*;
 use crate::helper_functions::*;
-use crate::{CodePair, DxfError, DxfResult};
+use crate::{CodePair, DxfError, DxfResult, SkippedContent};
+
+extern crate encoding_rs;
+use self::encoding_rs::Encoding;
 
 pub use crate::generated::header::*;
 
@@ -18,6 +21,11 @@ impl Header {
         default_if_empty(&mut self.dimension_style_name, "STANDARD");
         default_if_empty(&mut self.file_name, ".");
     }
+    /// Returns the text encoding named by `drawing_code_page` (`$DWGCODEPAGE`), or `None` if it isn't recognized.
+    /// Drawings before R2007 store their text in this encoding; later versions always use UTF-8.
+    pub fn code_page_encoding(&self) -> Option<&'static Encoding> {
+        encoding_from_code_page(&self.drawing_code_page)
+    }
     pub(crate) fn read(iter: &mut CodePairPutBack) -> DxfResult<Header> {
         let mut header = Header::default();
         loop {
@@ -38,12 +46,22 @@ impl Header {
                                             iter.put_back(Ok(pair));
                                             break;
                                         } else {
-                                            header
-                                                .set_header_value(&last_header_variable, &pair)?;
+                                            if let Err(e) = header
+                                                .set_header_value(&last_header_variable, &pair)
+                                            {
+                                                iter.recover(e, SkippedContent::Pair)?;
+                                            }
                                             if last_header_variable == "$ACADVER"
                                                 && header.version >= AcadVersion::R2007
                                             {
                                                 iter.read_as_utf8();
+                                            } else if last_header_variable == "$DWGCODEPAGE"
+                                                && header.version < AcadVersion::R2007
+                                            {
+                                                if let Some(encoding) = header.code_page_encoding()
+                                                {
+                                                    iter.read_with_code_page_encoding(encoding);
+                                                }
                                             }
                                         }
                                     }
@@ -52,7 +70,10 @@ impl Header {
                                 }
                             }
                         }
-                        _ => return Err(DxfError::UnexpectedCodePair(pair, String::from(""))),
+                        _ => iter.recover(
+                            DxfError::UnexpectedCodePair(pair, String::from("")),
+                            SkippedContent::Pair,
+                        )?,
                     }
                 }
                 Some(Err(e)) => return Err(e),
@@ -70,6 +91,40 @@ impl Header {
     }
 }
 
+/// Returns the text encoding named by a `$DWGCODEPAGE` value, or `None` if it isn't recognized.
+pub(crate) fn encoding_from_code_page(code_page: &str) -> Option<&'static Encoding> {
+    let code_page = code_page.to_ascii_uppercase();
+    let encoding = match &*code_page {
+        "ANSI_874" => encoding_rs::WINDOWS_874,
+        "ANSI_932" | "DOS932" => encoding_rs::SHIFT_JIS,
+        "ANSI_936" | "GB2312" => encoding_rs::GBK,
+        "ANSI_949" | "KSC5601" => encoding_rs::EUC_KR,
+        "ANSI_950" | "BIG5" => encoding_rs::BIG5,
+        "ANSI_1250" => encoding_rs::WINDOWS_1250,
+        "ANSI_1251" => encoding_rs::WINDOWS_1251,
+        "ANSI_1252" | "ISO8859-1" => encoding_rs::WINDOWS_1252,
+        "ANSI_1253" => encoding_rs::WINDOWS_1253,
+        "ANSI_1254" | "ISO8859-9" => encoding_rs::WINDOWS_1254,
+        "ANSI_1255" => encoding_rs::WINDOWS_1255,
+        "ANSI_1256" => encoding_rs::WINDOWS_1256,
+        "ANSI_1257" => encoding_rs::WINDOWS_1257,
+        "ANSI_1258" => encoding_rs::WINDOWS_1258,
+        "DOS866" => encoding_rs::IBM866,
+        "ISO8859-2" => encoding_rs::ISO_8859_2,
+        "ISO8859-3" => encoding_rs::ISO_8859_3,
+        "ISO8859-4" => encoding_rs::ISO_8859_4,
+        "ISO8859-5" => encoding_rs::ISO_8859_5,
+        "ISO8859-6" => encoding_rs::ISO_8859_6,
+        "ISO8859-7" => encoding_rs::ISO_8859_7,
+        "ISO8859-8" => encoding_rs::ISO_8859_8,
+        "KOI8-R" => encoding_rs::KOI8_R,
+        "MACINTOSH" => encoding_rs::MACINTOSH,
+        "UTF8" | "UTF-8" => encoding_rs::UTF_8,
+        _ => return None,
+    };
+    Some(encoding)
+}
+
 #[cfg(test)]
 mod tests {
     use crate::entities::*;
@@ -239,6 +294,18 @@ mod tests {
         assert_eq!(".", header.file_name);
     }
 
+    #[test]
+    fn code_page_encoding() {
+        let mut header = Header::default();
+        assert_eq!(Some(encoding_rs::WINDOWS_1252), header.code_page_encoding());
+        header.drawing_code_page = String::from("ansi_1251");
+        assert_eq!(Some(encoding_rs::WINDOWS_1251), header.code_page_encoding());
+        header.drawing_code_page = String::from("ANSI_936");
+        assert_eq!(Some(encoding_rs::GBK), header.code_page_encoding());
+        header.drawing_code_page = String::from("not-a-code-page");
+        assert_eq!(None, header.code_page_encoding());
+    }
+
     #[test]
     fn read_header_flags() {
         let drawing = from_section(
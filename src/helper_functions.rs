@@ -212,6 +212,12 @@ pub(crate) fn default_if_empty(val: &mut String, default: &str) {
     }
 }
 
+pub(crate) fn rename_if_matches(val: &mut String, old_name: &str, new_name: &str) {
+    if val.eq_ignore_ascii_case(old_name) {
+        *val = String::from(new_name);
+    }
+}
+
 pub(crate) fn ensure_positive_or_default(val: &mut f64, default: f64) {
     if *val <= 0.0 {
         *val = default
@@ -370,16 +376,6 @@ macro_rules! try_into_option {
     };
 }
 
-// safely unwrap an Option<DxfResult<T>>
-macro_rules! try_from_dxf_result {
-    ($expr : expr) => {
-        match $expr {
-            Ok(v) => v,
-            Err(e) => return Some(Err(e)),
-        }
-    };
-}
-
 // safely unwrap an Option<io::Result<T>> into Err()
 macro_rules! try_option_io_result_into_err {
     ($expr : expr) => {
@@ -436,6 +432,37 @@ macro_rules! vec_last {
     };
 }
 
+// Implements a binary operator for all combinations of owned and borrowed operands
+macro_rules! impl_binary_op {
+    ($op: ident, $method: ident, $lhs: ty, $rhs: ty, $output: ty, |$a: ident, $b: ident| $body: expr) => {
+        impl<'a, 'b> ::std::ops::$op<&'b $rhs> for &'a $lhs {
+            type Output = $output;
+            fn $method(self, other: &'b $rhs) -> $output {
+                let ($a, $b) = (self, other);
+                $body
+            }
+        }
+        impl<'b> ::std::ops::$op<&'b $rhs> for $lhs {
+            type Output = $output;
+            fn $method(self, other: &'b $rhs) -> $output {
+                ::std::ops::$op::$method(&self, other)
+            }
+        }
+        impl<'a> ::std::ops::$op<$rhs> for &'a $lhs {
+            type Output = $output;
+            fn $method(self, other: $rhs) -> $output {
+                ::std::ops::$op::$method(self, &other)
+            }
+        }
+        impl ::std::ops::$op<$rhs> for $lhs {
+            type Output = $output;
+            fn $method(self, other: $rhs) -> $output {
+                ::std::ops::$op::$method(&self, &other)
+            }
+        }
+    };
+}
+
 pub(crate) fn read_u8_strict<T: Read>(reader: &mut T) -> DxfResult<u8> {
     let u = try_from_option_io_result!(read_u8(reader));
     Ok(u)
@@ -455,19 +482,6 @@ pub(crate) fn read_i32<T: Read>(reader: &mut T) -> DxfResult<i32> {
     Ok(LittleEndian::read_i32(&[a, b, c, d]))
 }
 
-#[allow(clippy::many_single_char_names)]
-pub(crate) fn read_i64<T: Read>(reader: &mut T) -> DxfResult<i64> {
-    let a = try_from_option_io_result!(read_u8(reader));
-    let b = try_from_option_io_result!(read_u8(reader));
-    let c = try_from_option_io_result!(read_u8(reader));
-    let d = try_from_option_io_result!(read_u8(reader));
-    let e = try_from_option_io_result!(read_u8(reader));
-    let f = try_from_option_io_result!(read_u8(reader));
-    let g = try_from_option_io_result!(read_u8(reader));
-    let h = try_from_option_io_result!(read_u8(reader));
-    Ok(LittleEndian::read_i64(&[a, b, c, d, e, f, g, h]))
-}
-
 pub(crate) fn read_f32<T: Read>(reader: &mut T) -> DxfResult<f32> {
     let a = try_from_option_io_result!(read_u8(reader));
     let b = try_from_option_io_result!(read_u8(reader));
@@ -554,7 +568,15 @@ pub mod tests {
         println!("reading from pairs: {:?}", pairs);
         let iter = DirectCodePairIter::new(pairs);
         let iter = Box::new(iter);
-        unwrap_drawing(Drawing::load_from_iter(iter))
+        unwrap_drawing(Drawing::load_from_iter(iter, &LoadOptions::new()).map(|(d, _)| d))
+    }
+
+    pub fn drawing_with_unknown_items_from_pairs(pairs: Vec<CodePair>) -> Drawing {
+        println!("reading from pairs: {:?}", pairs);
+        let iter = DirectCodePairIter::new(pairs);
+        let iter = Box::new(iter);
+        let options = LoadOptions::new().preserve_unknown_items(true);
+        unwrap_drawing(Drawing::load_from_iter(iter, &options).map(|(d, _)| d))
     }
 
     pub fn parse_drawing(s: &str) -> Drawing {
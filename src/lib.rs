@@ -19,6 +19,22 @@
 //! > Note that `serde` support is intended to aid in debugging and since the serialized format is heavily
 //! dependent on the layout of the structures, it may change at any time.
 //!
+//! To load and save drawings over `futures` `AsyncRead`/`AsyncWrite` streams with `Drawing::load_async` and
+//! `Drawing::save_async`, enable the `async` feature.  Tokio streams can be adapted with `tokio_util::compat`.
+//!
+//! ``` toml
+//! [dependencies]
+//! dxf = { version = "0.5.0", features = ["async"] }
+//! ```
+//!
+//! To read the entities of large text files on multiple threads with `LoadOptions::parallel_entities`, enable the
+//! `parallel` feature:
+//!
+//! ``` toml
+//! [dependencies]
+//! dxf = { version = "0.5.0", features = ["parallel"] }
+//! ```
+//!
 //! And finally add:
 //!
 //! ``` rust
@@ -51,6 +67,21 @@
 //! # }
 //! ```
 //!
+//! Read a large DXF file one item at a time:
+//!
+//! ``` rust
+//! # fn ex() -> dxf::DxfResult<()> {
+//! use dxf::{Drawing, DrawingStreamItem};
+//!
+//! for item in Drawing::stream_file("path/to/file.dxf")? {
+//!     if let DrawingStreamItem::Entity(e) = item? {
+//!         println!("found entity on layer {}", e.common.layer);
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
 //! Saving a DXF file to disk:
 //!
 //! ``` rust
@@ -133,6 +164,11 @@ pub use crate::code_pair::CodePair;
 mod code_pair_value;
 pub use crate::code_pair_value::CodePairValue;
 
+mod binary_code_pair_reader;
+pub use crate::binary_code_pair_reader::{
+    BinaryCodePairReader, BorrowedCodePair, BorrowedCodePairValue,
+};
+
 mod data_table_value;
 pub use crate::data_table_value::DataTableValue;
 
@@ -161,15 +197,48 @@ pub use crate::drawing::Drawing;
 mod drawing_item;
 pub use crate::drawing_item::{DrawingItem, DrawingItemMut};
 
+mod drawing_stream;
+pub use crate::drawing_stream::{DrawingStream, DrawingStreamItem};
+
+mod handle_index;
+pub use crate::handle_index::HandleIndex;
+
+mod load_options;
+pub use crate::load_options::{DrawingSection, LoadOptions, LoadWarning, SkippedContent};
+
+#[cfg(feature = "parallel")]
+mod parallel_entities;
+
+mod hatch_boundary_path;
+pub use crate::hatch_boundary_path::{HatchBoundaryPath, HatchEdge};
+
+mod hatch_pattern_definition_line;
+pub use crate::hatch_pattern_definition_line::HatchPatternDefinitionLine;
+
+mod mleader_context_data;
+pub use crate::mleader_context_data::{MLeaderContextData, MLeaderLeader, MLeaderLeaderLine};
+
+mod mtext_document;
+pub use crate::mtext_document::{
+    MTextColor, MTextDocument, MTextFont, MTextFormat, MTextHeight, MTextParagraph, MTextRun,
+    MTextRunContent, MTextStack, MTextStackStyle,
+};
+
 mod section_geometry_settings;
 pub use crate::section_geometry_settings::SectionGeometrySettings;
 
 mod section_type_settings;
 pub use crate::section_type_settings::SectionTypeSettings;
 
+mod table_cell;
+pub use crate::table_cell::{TableCell, TableCellAttribute, TableCellRange};
+
 mod table_cell_style;
 pub use crate::table_cell_style::TableCellStyle;
 
+mod text_control_codes;
+pub use crate::text_control_codes::{DecodedText, DecodedTextRun};
+
 mod transformation_matrix;
 pub use crate::transformation_matrix::TransformationMatrix;
 
@@ -184,6 +253,9 @@ pub use crate::point::Point;
 mod vector;
 pub use crate::vector::Vector;
 
+mod version_conversion;
+pub use crate::version_conversion::{LossyConversion, LossyConversionKind};
+
 mod generated;
 pub mod entities {
     pub use crate::generated::entities::*;
@@ -204,6 +276,12 @@ mod code_pair_writer;
 mod block;
 pub use crate::block::Block;
 
+mod audit;
+pub use crate::audit::{AuditIssue, AuditIssueKind};
+
+mod bounding_box;
+pub use crate::bounding_box::BoundingBox;
+
 mod class;
 pub use crate::class::Class;
 
@@ -214,7 +292,15 @@ mod line_weight;
 pub use crate::line_weight::LineWeight;
 
 mod entity;
-pub use crate::entity::LwPolylineVertex;
+mod entity_extents;
+mod entity_tessellation;
+mod entity_transform;
+mod exploded_entity_iter;
+mod spline;
+pub use crate::entity::{
+    HatchGradientColor, LwPolylineVertex, MLeaderArrowheadOverride, MLeaderBlockAttribute,
+    MeshPropertyOverride, MeshSubentityOverride,
+};
 
 mod object;
 pub use crate::object::{GeoMeshPoint, MLineStyleElement};
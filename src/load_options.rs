@@ -0,0 +1,716 @@
+use std::collections::HashSet;
+use std::fmt;
+
+use encoding_rs::Encoding;
+
+use crate::entities::{Entity, EntityType};
+use crate::DxfError;
+
+/// Options for `Drawing::load_with_options()`.
+///
+/// ```
+/// # use dxf::*;
+/// # fn main() -> DxfResult<()> {
+/// let text = "0\nSECTION\n2\nENTITIES\n0\nLINE\n8\nwalls\n10\nnot-a-number\n0\nCIRCLE\n0\nENDSEC\n0\nEOF\n";
+/// let options = LoadOptions::new().lenient(true);
+/// let (drawing, warnings) = Drawing::load_with_options(&mut text.as_bytes(), &options)?;
+/// assert_eq!(2, drawing.entities().count());
+/// assert_eq!(10, warnings[0].offset);
+///
+/// // only read the lines and arcs on layer `walls`
+/// let options = LoadOptions::new()
+///     .lenient(true)
+///     .skip_section(DrawingSection::Blocks)
+///     .skip_section(DrawingSection::Objects)
+///     .entity_types(["LINE", "ARC"])
+///     .entity_layers(["walls"]);
+/// let (drawing, _) = Drawing::load_with_options(&mut text.as_bytes(), &options)?;
+/// assert_eq!(1, drawing.entities().count());
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct LoadOptions {
+    pub(crate) encoding: Option<&'static Encoding>,
+    pub(crate) preserve_unknown_items: bool,
+    pub(crate) lenient: bool,
+    pub(crate) decode_control_codes: bool,
+    skipped_sections: Vec<DrawingSection>,
+    entity_types: Option<HashSet<String>>,
+    entity_layers: Option<HashSet<String>>,
+    #[cfg(feature = "parallel")]
+    pub(crate) parallel_entities: bool,
+}
+
+/// The sections of a DXF file that can be skipped with `LoadOptions::skip_section()`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DrawingSection {
+    /// The `HEADER` section with the `Drawing::header` variables.
+    Header,
+    /// The `CLASSES` section.
+    Classes,
+    /// The `TABLES` section with the layers, line types, styles, etc.
+    Tables,
+    /// The `BLOCKS` section.
+    Blocks,
+    /// The `ENTITIES` section.
+    Entities,
+    /// The `OBJECTS` section.
+    Objects,
+    /// The `THUMBNAILIMAGE` section.
+    Thumbnail,
+}
+
+impl LoadOptions {
+    /// Creates options that load the drawing the same way as `Drawing::load()`.
+    pub fn new() -> Self {
+        LoadOptions::default()
+    }
+    /// Reads text with the specified encoding, regardless of `$DWGCODEPAGE`.
+    pub fn encoding(mut self, encoding: &'static Encoding) -> Self {
+        self.encoding = Some(encoding);
+        self
+    }
+    /// Keeps unsupported entities and objects as `EntityType::Unknown` and `ObjectType::Unknown` so they are written
+    /// back unchanged when the drawing is saved.
+    pub fn preserve_unknown_items(mut self, preserve_unknown_items: bool) -> Self {
+        self.preserve_unknown_items = preserve_unknown_items;
+        self
+    }
+    /// Recovers from malformed content instead of failing.  Code pairs with invalid values are skipped, so the values
+    /// they would have set keep their defaults, and items that can't be read are skipped up to the next `0` code
+    /// pair.  When the lines of a text file are out of alignment, e.g., because a line is missing, the lines up to
+    /// the next `0` code followed by a known item type are skipped.  Each recovery is reported as a `LoadWarning`.
+    /// Reading binary files and I/O errors still fail.
+    pub fn lenient(mut self, lenient: bool) -> Self {
+        self.lenient = lenient;
+        self
+    }
+    /// Decodes the control codes, e.g., `%%d`, in the values of `Text`, `Attribute`, and `AttributeDefinition`
+    /// entities, and in the prompts of `AttributeDefinition` entities, the same as `DecodedText::plain_text()`.  The
+    /// `%%u` and `%%o` underline and overline toggles are removed.
+    pub fn decode_control_codes(mut self, decode_control_codes: bool) -> Self {
+        self.decode_control_codes = decode_control_codes;
+        self
+    }
+    /// Skips the section without reading its items; they are left empty or at their defaults in the `Drawing`.
+    /// Skipping `DrawingSection::Header` also skips detecting the text encoding from `$ACADVER` and `$DWGCODEPAGE`.
+    pub fn skip_section(mut self, section: DrawingSection) -> Self {
+        if !self.skipped_sections.contains(&section) {
+            self.skipped_sections.push(section);
+        }
+        self
+    }
+    /// Only keeps the entities in the `ENTITIES` section with the specified type strings, e.g., `LINE`.  Other entities
+    /// are skipped without being fully read.  The contents of blocks are always read.
+    pub fn entity_types<I, S>(mut self, entity_types: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.entity_types = Some(
+            entity_types
+                .into_iter()
+                .map(|t| t.as_ref().to_uppercase())
+                .collect(),
+        );
+        self
+    }
+    /// Only keeps the entities in the `ENTITIES` section that are on the specified layers.  Layer names are compared
+    /// case-insensitively.  The contents of blocks are always read.
+    pub fn entity_layers<I, S>(mut self, layers: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.entity_layers = Some(
+            layers
+                .into_iter()
+                .map(|l| l.as_ref().to_uppercase())
+                .collect(),
+        );
+        self
+    }
+    /// Splits the `ENTITIES` section of text files into chunks that are read on the rayon thread pool.  The entities
+    /// and their handles are the same as when read sequentially.  Binary files, and all files when the thread pool only
+    /// has one thread, are read sequentially.  Requires the `parallel` feature.
+    #[cfg(feature = "parallel")]
+    pub fn parallel_entities(mut self, parallel_entities: bool) -> Self {
+        self.parallel_entities = parallel_entities;
+        self
+    }
+}
+
+// internal visibility only
+impl LoadOptions {
+    /// Returns `true` if the section with the specified name, e.g., `ENTITIES`, shouldn't be read.
+    pub(crate) fn skips_section(&self, name: &str) -> bool {
+        self.skipped_sections.iter().any(|s| s.name() == name)
+    }
+    /// Returns `true` if an entity read with the specified type string could be kept, either by itself or as part of
+    /// the entity owning it.
+    pub(crate) fn reads_entity_type(&self, type_string: &str) -> bool {
+        let entity_types = match self.entity_types {
+            Some(ref entity_types) => entity_types,
+            None => return true,
+        };
+        match type_string {
+            // these can be gathered into an `INSERT`, `POLYLINE`, or attribute
+            "ATTRIB" | "MTEXT" | "SEQEND" | "VERTEX" => true,
+            _ => {
+                let canonical = EntityType::from_type_string(type_string)
+                    .map(|t| t.to_type_string().to_string())
+                    .unwrap_or_else(|| type_string.to_string());
+                entity_types.contains(&canonical)
+            }
+        }
+    }
+    /// Returns `true` if the entity passes the type and layer filters.
+    pub(crate) fn keeps_entity(&self, entity: &Entity) -> bool {
+        let is_type_kept = match self.entity_types {
+            Some(ref entity_types) => entity_types.contains(entity.specific.to_type_string()),
+            None => true,
+        };
+        let is_layer_kept = match self.entity_layers {
+            Some(ref layers) => layers.contains(&entity.common.layer.to_uppercase()),
+            None => true,
+        };
+        is_type_kept && is_layer_kept
+    }
+}
+
+impl DrawingSection {
+    fn name(self) -> &'static str {
+        match self {
+            DrawingSection::Header => "HEADER",
+            DrawingSection::Classes => "CLASSES",
+            DrawingSection::Tables => "TABLES",
+            DrawingSection::Blocks => "BLOCKS",
+            DrawingSection::Entities => "ENTITIES",
+            DrawingSection::Objects => "OBJECTS",
+            DrawingSection::Thumbnail => "THUMBNAILIMAGE",
+        }
+    }
+}
+
+/// A problem that was recovered from while loading a drawing with `LoadOptions::lenient()`.
+#[derive(Debug)]
+pub struct LoadWarning {
+    /// The line number (text files) where the problem was found.
+    pub offset: usize,
+    /// The error that a strict load would have returned.
+    pub error: DxfError,
+    /// What was skipped to recover.
+    pub skipped: SkippedContent,
+}
+
+/// The content skipped to recover from a `LoadWarning`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SkippedContent {
+    /// The single invalid code pair.
+    Pair,
+    /// The rest of the entity, object, table, or block.
+    Item,
+    /// The rest of the section.
+    Section,
+    /// The lines up to the next item when the code pairs were out of alignment, e.g., because a line is missing.
+    Lines,
+}
+
+impl fmt::Display for LoadWarning {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        let skipped = match self.skipped {
+            SkippedContent::Pair => "the code pair was",
+            SkippedContent::Item => "the item was",
+            SkippedContent::Section => "the section was",
+            SkippedContent::Lines => "the lines up to the next item were",
+        };
+        write!(
+            formatter,
+            "line/offset {}: {}; {} skipped",
+            self.offset, self.error, skipped
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::entities::*;
+    use crate::enums::*;
+    use crate::helper_functions::tests::*;
+    use crate::*;
+
+    fn load_lenient(text: &str) -> (Drawing, Vec<LoadWarning>) {
+        let options = LoadOptions::new().lenient(true);
+        match Drawing::load_with_options(&mut text.as_bytes(), &options) {
+            Ok(result) => result,
+            Err(e) => panic!("unable to load drawing: {:?}: {}", e, e),
+        }
+    }
+
+    fn skipped(warnings: &[LoadWarning]) -> Vec<(usize, SkippedContent)> {
+        warnings.iter().map(|w| (w.offset, w.skipped)).collect()
+    }
+
+    fn lines(drawing: &Drawing) -> Vec<&Line> {
+        drawing
+            .entities()
+            .map(|e| match e.specific {
+                EntityType::Line(ref line) => line,
+                _ => panic!("expected a line"),
+            })
+            .collect()
+    }
+
+    fn invalid_value() -> &'static str {
+        "
+  0
+SECTION
+  2
+ENTITIES
+  0
+LINE
+ 10
+one
+ 20
+2.0
+  0
+ENDSEC
+  0
+EOF"
+        .trim()
+    }
+
+    #[test]
+    fn strict_load_fails_on_invalid_value() {
+        match Drawing::load(&mut invalid_value().as_bytes()) {
+            Err(DxfError::ParseFloatError(_, 8)) => (),
+            Err(e) => panic!("expected a parse error, got {:?}", e),
+            Ok(_) => panic!("expected a parse error"),
+        }
+    }
+
+    #[test]
+    fn lenient_load_skips_invalid_value() {
+        let (drawing, warnings) = load_lenient(invalid_value());
+        assert_eq!(vec![(8, SkippedContent::Pair)], skipped(&warnings));
+        let lines = lines(&drawing);
+        assert_eq!(1, lines.len());
+        assert_eq!(Point::new(0.0, 2.0, 0.0), lines[0].p1);
+    }
+
+    #[test]
+    fn lenient_load_skips_invalid_code() {
+        let (drawing, warnings) = load_lenient(
+            "
+  0
+SECTION
+  2
+ENTITIES
+  0
+LINE
+ten
+1.0
+ 20
+2.0
+  0
+ENDSEC
+  0
+EOF"
+            .trim(),
+        );
+        assert_eq!(vec![(7, SkippedContent::Pair)], skipped(&warnings));
+        assert_eq!(Point::new(0.0, 2.0, 0.0), lines(&drawing)[0].p1);
+    }
+
+    #[test]
+    fn lenient_load_skips_malformed_entity() {
+        let (drawing, warnings) = load_lenient(
+            "
+  0
+SECTION
+  2
+ENTITIES
+  0
+LINE
+  5
+not-a-handle
+ 10
+1.0
+  0
+LINE
+ 10
+3.0
+  0
+ENDSEC
+  0
+EOF"
+            .trim(),
+        );
+        assert_eq!(vec![(7, SkippedContent::Item)], skipped(&warnings));
+        let lines = lines(&drawing);
+        assert_eq!(1, lines.len());
+        assert_eq!(3.0, lines[0].p1.x);
+    }
+
+    #[test]
+    fn lenient_load_skips_malformed_table() {
+        let (drawing, warnings) = load_lenient(
+            "
+  0
+SECTION
+  2
+TABLES
+  0
+NOT-A-TABLE
+  2
+LAYER
+  0
+TABLE
+  2
+LAYER
+  0
+LAYER
+  2
+walls
+  0
+ENDTAB
+  0
+ENDSEC
+  0
+EOF"
+            .trim(),
+        );
+        assert_eq!(vec![(5, SkippedContent::Item)], skipped(&warnings));
+        assert!(drawing.layers().any(|l| l.name == "walls"));
+    }
+
+    #[test]
+    fn lenient_load_recovers_from_missing_endsec() {
+        let (drawing, warnings) = load_lenient(
+            "
+  0
+SECTION
+  2
+HEADER
+  9
+$CLAYER
+  8
+walls
+  0
+SECTION
+  2
+ENTITIES
+  0
+LINE
+  0
+ENDSEC
+  0
+EOF"
+            .trim(),
+        );
+        assert_eq!(vec![(9, SkippedContent::Section)], skipped(&warnings));
+        assert_eq!("walls", drawing.header.current_layer);
+        assert_eq!(1, drawing.entities().count());
+    }
+
+    #[test]
+    fn lenient_load_recovers_from_missing_line() {
+        // the value of the layer is missing, so every following line is read as the wrong half of a pair
+        let text = "
+  0
+SECTION
+  2
+ENTITIES
+  0
+LINE
+  8
+ 10
+1.0
+ 20
+2.0
+  0
+CIRCLE
+ 10
+3.0
+ 40
+4.0
+  0
+ENDSEC
+  0
+EOF"
+        .trim();
+        match Drawing::load(&mut text.as_bytes()) {
+            Err(DxfError::ParseIntError(_, 9)) => (),
+            Err(e) => panic!("expected a parse error, got {:?}", e),
+            Ok(_) => panic!("expected a parse error"),
+        }
+
+        let (drawing, warnings) = load_lenient(text);
+        assert_eq!(vec![(9, SkippedContent::Lines)], skipped(&warnings));
+        let entities = drawing.entities().collect::<Vec<_>>();
+        assert_eq!(2, entities.len());
+        assert_eq!(" 10", entities[0].common.layer);
+        match entities[1].specific {
+            EntityType::Circle(ref circle) => {
+                assert_eq!(3.0, circle.center.x);
+                assert_eq!(4.0, circle.radius);
+            }
+            _ => panic!("expected a circle"),
+        }
+    }
+
+    #[test]
+    fn lenient_load_recovers_from_missing_line_before_item() {
+        // the value of the layer is missing, so the `0` code of the circle is read as the layer
+        let (drawing, warnings) = load_lenient(
+            "
+  0
+SECTION
+  2
+ENTITIES
+  0
+LINE
+  8
+  0
+CIRCLE
+ 40
+4.0
+  0
+ENDSEC
+  0
+EOF"
+            .trim(),
+        );
+        assert_eq!(vec![(9, SkippedContent::Lines)], skipped(&warnings));
+        let entities = drawing.entities().collect::<Vec<_>>();
+        assert_eq!(2, entities.len());
+        match entities[1].specific {
+            EntityType::Circle(ref circle) => assert_eq!(4.0, circle.radius),
+            _ => panic!("expected a circle"),
+        }
+    }
+
+    #[test]
+    fn lenient_load_recovers_from_extra_line() {
+        // an extra line after the color shifts the rest of the pairs by one line
+        let (drawing, warnings) = load_lenient(
+            "
+  0
+SECTION
+  2
+ENTITIES
+  0
+LINE
+ 62
+1
+extra
+ 10
+1.0
+  0
+CIRCLE
+ 40
+4.0
+  0
+ENDSEC
+  0
+EOF"
+            .trim(),
+        );
+        assert_eq!(vec![(9, SkippedContent::Lines)], skipped(&warnings));
+        let entities = drawing.entities().collect::<Vec<_>>();
+        assert_eq!(2, entities.len());
+        assert_eq!(Color::from_index(1), entities[0].common.color);
+        match entities[1].specific {
+            EntityType::Circle(ref circle) => assert_eq!(4.0, circle.radius),
+            _ => panic!("expected a circle"),
+        }
+    }
+
+    #[test]
+    fn warning_display() {
+        let (_, warnings) = load_lenient(invalid_value());
+        assert_eq!(
+            "line/offset 8: invalid float literal at line/offset 8; the code pair was skipped",
+            warnings[0].to_string()
+        );
+    }
+
+    fn load_with(drawing: &Drawing, options: &LoadOptions) -> Drawing {
+        let text = to_test_string(drawing);
+        match Drawing::load_with_options(&mut text.as_bytes(), options) {
+            Ok((drawing, _)) => drawing,
+            Err(e) => panic!("unable to load drawing: {:?}: {}", e, e),
+        }
+    }
+
+    fn entity_on_layer(specific: EntityType, layer: &str) -> Entity {
+        let mut entity = Entity::new(specific);
+        entity.common.layer = String::from(layer);
+        entity
+    }
+
+    fn type_strings(drawing: &Drawing) -> Vec<&str> {
+        drawing
+            .entities()
+            .map(|e| e.specific.to_type_string())
+            .collect()
+    }
+
+    #[test]
+    fn skip_sections() {
+        let mut drawing = Drawing::new();
+        drawing.header.current_layer = String::from("walls");
+        drawing.add_layer(tables::Layer {
+            name: String::from("walls"),
+            ..Default::default()
+        });
+        drawing.add_entity(Entity::new(EntityType::Line(Line::default())));
+        let options = LoadOptions::new()
+            .skip_section(DrawingSection::Header)
+            .skip_section(DrawingSection::Entities);
+        let drawing = load_with(&drawing, &options);
+        assert_eq!("0", drawing.header.current_layer);
+        assert_eq!(0, drawing.entities().count());
+        assert!(drawing.layers().any(|l| l.name == "walls"));
+    }
+
+    #[test]
+    fn filter_entities_by_type() {
+        let mut drawing = Drawing::new();
+        let mut poly = Polyline::default();
+        poly.add_vertex(&mut drawing, Vertex::new(Point::new(1.0, 2.0, 0.0)));
+        poly.add_vertex(&mut drawing, Vertex::new(Point::new(3.0, 4.0, 0.0)));
+        drawing.add_entity(Entity::new(EntityType::Polyline(poly)));
+        drawing.add_entity(Entity::new(EntityType::Line(Line::default())));
+        drawing.add_entity(Entity::new(EntityType::Circle(Circle::default())));
+        drawing.add_entity(Entity::new(EntityType::Arc(Arc::default())));
+
+        let options = LoadOptions::new().entity_types(["polyline", "ARC"]);
+        let drawing = load_with(&drawing, &options);
+        assert_eq!(vec!["POLYLINE", "ARC"], type_strings(&drawing));
+        let first = drawing.entities().next().unwrap();
+        match first.specific {
+            EntityType::Polyline(ref poly) => assert_eq!(2, poly.vertices().count()),
+            _ => panic!("expected a polyline"),
+        }
+    }
+
+    #[test]
+    fn filter_entities_by_layer() {
+        let mut drawing = Drawing::new();
+        drawing.add_entity(entity_on_layer(EntityType::Line(Line::default()), "Walls"));
+        drawing.add_entity(entity_on_layer(EntityType::Line(Line::default()), "doors"));
+        drawing.add_entity(entity_on_layer(
+            EntityType::Circle(Circle::default()),
+            "walls",
+        ));
+        let options = LoadOptions::new()
+            .entity_types(["LINE"])
+            .entity_layers(["WALLS"]);
+        let drawing = load_with(&drawing, &options);
+        let layers = drawing
+            .entities()
+            .map(|e| e.common.layer.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(vec!["Walls"], layers);
+    }
+
+    #[test]
+    fn filter_unknown_entities_by_layer() {
+        let text = "0\nSECTION\n2\nENTITIES\n0\nUNSUPPORTED_ENTITY\n8\nwalls\n0\nUNSUPPORTED_ENTITY\n8\ndoors\n0\nENDSEC\n0\nEOF\n";
+        let options = LoadOptions::new()
+            .preserve_unknown_items(true)
+            .entity_layers(["WALLS"]);
+        let (drawing, _) = Drawing::load_with_options(&mut text.as_bytes(), &options).unwrap();
+        let layers = drawing
+            .entities()
+            .map(|e| e.common.layer.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(vec!["walls"], layers);
+    }
+
+    #[test]
+    fn entity_filters_do_not_apply_to_blocks() {
+        let mut drawing = Drawing::new();
+        let mut block = Block {
+            name: String::from("b"),
+            ..Default::default()
+        };
+        block
+            .entities
+            .push(Entity::new(EntityType::Circle(Circle::default())));
+        drawing.add_block(block);
+        let options = LoadOptions::new().entity_types(["LINE"]);
+        let drawing = load_with(&drawing, &options);
+        let block = drawing.blocks().find(|b| b.name == "b").unwrap();
+        assert_eq!(1, block.entities.len());
+    }
+
+    #[test]
+    fn decode_control_codes_in_text_entities() {
+        let mut drawing = Drawing::new();
+        drawing.header.version = AcadVersion::R2000;
+        let text = Text {
+            value: String::from("%%c10%%p0.1"),
+            ..Default::default()
+        };
+        drawing.add_entity(Entity::new(EntityType::Text(text)));
+        let mut insert = Insert::default();
+        insert.add_attribute(
+            &mut drawing,
+            Attribute {
+                value: String::from("%%u90%%d"),
+                ..Default::default()
+            },
+        );
+        drawing.add_entity(Entity::new(EntityType::Insert(insert)));
+        let mut block = Block {
+            name: String::from("b"),
+            ..Default::default()
+        };
+        block
+            .entities
+            .push(Entity::new(EntityType::AttributeDefinition(
+                AttributeDefinition {
+                    value: String::from("100%%%"),
+                    prompt: String::from("%%p?"),
+                    ..Default::default()
+                },
+            )));
+        drawing.add_block(block);
+
+        let loaded = load_with(&drawing, &LoadOptions::new());
+        let values = |d: &Drawing| {
+            let mut values = vec![];
+            for e in d.entities() {
+                match e.specific {
+                    EntityType::Text(ref t) => values.push(t.value.clone()),
+                    EntityType::Insert(ref ins) => {
+                        values.extend(ins.attributes().map(|a| a.value.clone()))
+                    }
+                    _ => (),
+                }
+            }
+            let block = d.blocks().find(|b| b.name == "b").unwrap();
+            match block.entities[0].specific {
+                EntityType::AttributeDefinition(ref att) => {
+                    values.push(att.value.clone());
+                    values.push(att.prompt.clone());
+                }
+                _ => panic!("expected an attribute definition"),
+            }
+            values
+        };
+        assert_eq!(
+            vec!["%%c10%%p0.1", "%%u90%%d", "100%%%", "%%p?"],
+            values(&loaded)
+        );
+
+        let decoded = load_with(&drawing, &LoadOptions::new().decode_control_codes(true));
+        assert_eq!(
+            vec!["\u{d8}10\u{b1}0.1", "90\u{b0}", "100%", "\u{b1}?"],
+            values(&decoded)
+        );
+    }
+}
@@ -0,0 +1,275 @@
+use crate::entities::*;
+use crate::enums::*;
+use crate::tables::*;
+use crate::*;
+
+use futures_util::future::FutureExt;
+use futures_util::io::{AsyncRead, Cursor};
+use std::future::Future;
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+// in-memory readers and writers never wait, so the futures complete on their first poll
+fn run<F: Future>(future: F) -> F::Output {
+    future
+        .now_or_never()
+        .expect("expected the future to complete")
+}
+
+// returns at most `chunk_size` bytes from each read, so code pairs are split across reads
+struct ChunkedReader {
+    bytes: Vec<u8>,
+    position: usize,
+    chunk_size: usize,
+}
+
+impl ChunkedReader {
+    fn new(bytes: &[u8], chunk_size: usize) -> Self {
+        ChunkedReader {
+            bytes: bytes.to_vec(),
+            position: 0,
+            chunk_size,
+        }
+    }
+}
+
+impl AsyncRead for ChunkedReader {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let remaining = &self.bytes[self.position..];
+        let count = remaining.len().min(self.chunk_size).min(buf.len());
+        buf[..count].copy_from_slice(&remaining[..count]);
+        self.position += count;
+        Poll::Ready(Ok(count))
+    }
+}
+
+fn drawing_with_line() -> Drawing {
+    let mut drawing = Drawing::new();
+    drawing.add_entity(Entity::new(EntityType::Line(Line::new(
+        Point::new(1.0, 2.0, 3.0),
+        Point::new(4.0, 5.0, 6.0),
+    ))));
+    drawing
+}
+
+fn assert_single_line(drawing: &Drawing) {
+    let entities = drawing.entities().collect::<Vec<_>>();
+    assert_eq!(1, entities.len());
+    match entities[0].specific {
+        EntityType::Line(ref line) => {
+            assert_eq!(Point::new(1.0, 2.0, 3.0), line.p1);
+            assert_eq!(Point::new(4.0, 5.0, 6.0), line.p2);
+        }
+        _ => panic!("expected a line"),
+    }
+}
+
+#[test]
+fn load_async_matches_load() {
+    let mut bytes = vec![];
+    drawing_with_line().save(&mut bytes).unwrap();
+    let drawing = run(Drawing::load_async(&mut Cursor::new(bytes))).unwrap();
+    assert_single_line(&drawing);
+}
+
+#[test]
+fn save_async_matches_save() {
+    let drawing = drawing_with_line();
+    let mut expected = vec![];
+    drawing.save(&mut expected).unwrap();
+    let mut actual = Cursor::new(vec![]);
+    run(drawing.save_async(&mut actual)).unwrap();
+    assert_eq!(expected, actual.into_inner());
+}
+
+#[test]
+fn round_trip_binary_async() {
+    let mut drawing = drawing_with_line();
+    drawing.header.version = AcadVersion::R2000;
+    let mut expected = vec![];
+    drawing.save_binary(&mut expected).unwrap();
+    let mut writer = Cursor::new(vec![]);
+    run(drawing.save_binary_async(&mut writer)).unwrap();
+    let bytes = writer.into_inner();
+    assert_eq!(expected, bytes);
+
+    let drawing = run(Drawing::load_async(&mut Cursor::new(bytes))).unwrap();
+    assert_single_line(&drawing);
+}
+
+#[test]
+fn save_async_writes_drawings_larger_than_one_chunk() {
+    let mut drawing = Drawing::new();
+    for _ in 0..1000 {
+        drawing.add_entity(Entity::new(EntityType::Line(Line::default())));
+    }
+    let mut expected = vec![];
+    drawing.save(&mut expected).unwrap();
+    let mut actual = Cursor::new(vec![]);
+    run(drawing.save_async(&mut actual)).unwrap();
+    assert_eq!(expected, actual.into_inner());
+}
+
+#[test]
+fn load_async_with_options_reports_warnings() {
+    let text = "
+  0
+SECTION
+  2
+ENTITIES
+  0
+LINE
+ 10
+one
+ 20
+2.0
+  0
+ENDSEC
+  0
+EOF"
+    .trim();
+    let options = LoadOptions::new().lenient(true);
+    let (drawing, warnings) = run(Drawing::load_async_with_options(
+        &mut Cursor::new(text.as_bytes()),
+        &options,
+    ))
+    .unwrap();
+    assert_eq!(1, drawing.entities().count());
+    assert_eq!(1, warnings.len());
+    assert_eq!(SkippedContent::Pair, warnings[0].skipped);
+}
+
+fn drawing_with_items() -> Drawing {
+    let mut drawing = Drawing::new();
+    drawing.header.version = AcadVersion::R2000;
+    drawing.add_layer(Layer {
+        name: String::from("layer"),
+        ..Default::default()
+    });
+    for i in 0..3 {
+        let mut line = Entity::new(EntityType::Line(Line::new(
+            Point::new(i as f64, 0.0, 0.0),
+            Point::new(0.0, i as f64, 0.0),
+        )));
+        line.common.layer = String::from("layer");
+        drawing.add_entity(line);
+    }
+
+    let mut poly = Polyline::default();
+    for i in 0..3 {
+        poly.add_vertex(&mut drawing, Vertex::new(Point::new(i as f64, 1.0, 0.0)));
+    }
+    drawing.add_entity(Entity::new(EntityType::Polyline(poly)));
+
+    let mut ins = Insert::default();
+    ins.add_attribute(
+        &mut drawing,
+        Attribute {
+            value: String::from("value"),
+            ..Default::default()
+        },
+    );
+    drawing.add_entity(Entity::new(EntityType::Insert(ins)));
+    drawing
+}
+
+fn saved(drawing: &Drawing) -> Vec<u8> {
+    let mut bytes = vec![];
+    drawing.save(&mut bytes).unwrap();
+    bytes
+}
+
+#[test]
+fn load_async_reads_text_split_across_reads() {
+    let bytes = saved(&drawing_with_items());
+    let expected = saved(&Drawing::load(&mut bytes.as_slice()).unwrap());
+    for chunk_size in &[1, 3, 7, 1000] {
+        let mut reader = ChunkedReader::new(&bytes, *chunk_size);
+        let drawing = run(Drawing::load_async(&mut reader)).unwrap();
+        assert_eq!(expected, saved(&drawing), "chunk size {}", chunk_size);
+    }
+}
+
+#[test]
+fn load_async_reads_binary_split_across_reads() {
+    let mut bytes = vec![];
+    drawing_with_items().save_binary(&mut bytes).unwrap();
+    let expected = saved(&Drawing::load(&mut bytes.as_slice()).unwrap());
+    for chunk_size in &[1, 3, 7, 1000] {
+        let mut reader = ChunkedReader::new(&bytes, *chunk_size);
+        let drawing = run(Drawing::load_async(&mut reader)).unwrap();
+        assert_eq!(expected, saved(&drawing), "chunk size {}", chunk_size);
+    }
+}
+
+#[test]
+fn load_async_reads_code_page_text_split_across_reads() {
+    let mut bytes = b"  0\r\nSECTION\r\n  2\r\nHEADER\r\n  9\r\n$ACADVER\r\n  1\r\nAC1015\r\n  9\r\n$DWGCODEPAGE\r\n  3\r\nANSI_1251\r\n  0\r\nENDSEC\r\n  0\r\nSECTION\r\n  2\r\nENTITIES\r\n  0\r\nLINE\r\n  8\r\n".to_vec();
+    bytes.extend_from_slice(&[0xD1, 0xEB, 0xEE, 0xE9]); // "Слой" in windows-1251
+    bytes.extend_from_slice(b"\r\n  0\r\nENDSEC\r\n  0\r\nEOF\r\n");
+    for chunk_size in &[1, 3, 7] {
+        let mut reader = ChunkedReader::new(&bytes, *chunk_size);
+        let drawing = run(Drawing::load_async(&mut reader)).unwrap();
+        let entities = drawing.entities().collect::<Vec<_>>();
+        assert_eq!(1, entities.len());
+        assert_eq!(
+            "Слой", entities[0].common.layer,
+            "chunk size {}",
+            chunk_size
+        );
+    }
+}
+
+#[test]
+fn load_async_with_options_recovers_when_split_across_reads() {
+    // the value of the layer is missing, so the following lines are out of alignment
+    let text = "
+  0
+SECTION
+  2
+ENTITIES
+  0
+LINE
+  8
+ 10
+1.0
+ 20
+2.0
+  0
+CIRCLE
+ 10
+3.0
+ 40
+4.0
+  0
+ENDSEC
+  0
+EOF"
+    .trim();
+    let options = LoadOptions::new().lenient(true);
+    let (expected_drawing, expected_warnings) =
+        Drawing::load_with_options(&mut text.as_bytes(), &options).unwrap();
+    for chunk_size in &[1, 3, 7] {
+        let mut reader = ChunkedReader::new(text.as_bytes(), *chunk_size);
+        let (drawing, warnings) =
+            run(Drawing::load_async_with_options(&mut reader, &options)).unwrap();
+        assert_eq!(saved(&expected_drawing), saved(&drawing));
+        assert_eq!(expected_warnings.len(), warnings.len());
+        assert_eq!(2, drawing.entities().count());
+    }
+}
+
+#[test]
+fn async_futures_can_be_sent_between_threads() {
+    fn assert_send<T: Send>(_: T) {}
+    let drawing = Drawing::new();
+    let mut reader = Cursor::new(vec![]);
+    let mut writer = Cursor::new(vec![]);
+    assert_send(Drawing::load_async(&mut reader));
+    assert_send(drawing.save_async(&mut writer));
+}
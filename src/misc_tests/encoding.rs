@@ -523,3 +523,177 @@ fn round_trip_thumbnail(thumbnail: image::DynamicImage) -> image::DynamicImage {
     let drawing = drawing_from_pairs(drawing_pairs);
     drawing.thumbnail.unwrap()
 }
+
+fn drawing_bytes_with_code_page(version: &str, code_page: &str, text: &[u8]) -> Vec<u8> {
+    let mut bytes = vec![];
+    for line in &[
+        "  0",
+        "SECTION",
+        "  2",
+        "HEADER",
+        "  9",
+        "$ACADVER",
+        "  1",
+        version,
+        "  9",
+        "$DWGCODEPAGE",
+        "  3",
+        code_page,
+        "  0",
+        "ENDSEC",
+        "  0",
+        "SECTION",
+        "  2",
+        "ENTITIES",
+        "  0",
+        "LINE",
+        "  8",
+    ] {
+        bytes.extend_from_slice(line.as_bytes());
+        bytes.extend_from_slice(b"\r\n");
+    }
+    bytes.extend_from_slice(text);
+    bytes.extend_from_slice(b"\r\n");
+    for line in &["  0", "ENDSEC", "  0", "EOF"] {
+        bytes.extend_from_slice(line.as_bytes());
+        bytes.extend_from_slice(b"\r\n");
+    }
+    bytes
+}
+
+fn first_entity_layer(drawing: &Drawing) -> String {
+    drawing.entities().next().unwrap().common.layer.clone()
+}
+
+#[test]
+fn read_with_encoding_from_code_page() {
+    // these bytes represent `Слой` in Windows-1251
+    let bytes = drawing_bytes_with_code_page("AC1015", "ANSI_1251", &[0xD1, 0xEB, 0xEE, 0xE9]);
+    let drawing = unwrap_drawing(Drawing::load(&mut bytes.as_slice()));
+    assert_eq!("Слой", first_entity_layer(&drawing));
+
+    // these bytes represent `不` in GBK
+    let bytes = drawing_bytes_with_code_page("AC1015", "ANSI_936", &[0xB2, 0xBB]);
+    let drawing = unwrap_drawing(Drawing::load(&mut bytes.as_slice()));
+    assert_eq!("不", first_entity_layer(&drawing));
+
+    // these bytes represent `レイヤ` in Shift-JIS
+    let bytes =
+        drawing_bytes_with_code_page("AC1015", "ANSI_932", &[0x83, 0x8C, 0x83, 0x43, 0x83, 0x84]);
+    let drawing = unwrap_drawing(Drawing::load(&mut bytes.as_slice()));
+    assert_eq!("レイヤ", first_entity_layer(&drawing));
+}
+
+#[test]
+fn stream_with_encoding_from_code_page() {
+    let bytes = drawing_bytes_with_code_page("AC1015", "ANSI_1251", &[0xD1, 0xEB, 0xEE, 0xE9]);
+    let layer = Drawing::stream(Cursor::new(bytes))
+        .unwrap()
+        .find_map(|item| match item.unwrap() {
+            DrawingStreamItem::Entity(e) => Some(e.common.layer),
+            _ => None,
+        });
+    assert_eq!(Some(String::from("Слой")), layer);
+}
+
+#[test]
+fn explicit_encoding_overrides_code_page() {
+    let bytes = drawing_bytes_with_code_page("AC1015", "ANSI_1251", &[0xD1, 0xEB, 0xEE, 0xE9]);
+    let drawing = unwrap_drawing(Drawing::load_with_encoding(
+        &mut bytes.as_slice(),
+        encoding_rs::WINDOWS_1252,
+    ));
+    assert_eq!("Ñëîé", first_entity_layer(&drawing));
+}
+
+#[test]
+fn code_page_is_ignored_for_utf8_versions() {
+    let bytes = drawing_bytes_with_code_page("AC1021", "ANSI_1251", "Слой".as_bytes());
+    let drawing = unwrap_drawing(Drawing::load(&mut bytes.as_slice()));
+    assert_eq!("Слой", first_entity_layer(&drawing));
+}
+
+fn binary_drawing_bytes_with_code_page(version: &str, code_page: &str, text: &[u8]) -> Vec<u8> {
+    let mut bytes = b"AutoCAD Binary DXF\r\n\x1A\x00".to_vec();
+    let mut write_pair = |code: i16, value: &[u8]| {
+        bytes.extend_from_slice(&code.to_le_bytes());
+        bytes.extend_from_slice(value);
+        bytes.push(0);
+    };
+    write_pair(0, b"SECTION");
+    write_pair(2, b"HEADER");
+    write_pair(9, b"$ACADVER");
+    write_pair(1, version.as_bytes());
+    write_pair(9, b"$DWGCODEPAGE");
+    write_pair(3, code_page.as_bytes());
+    write_pair(0, b"ENDSEC");
+    write_pair(0, b"SECTION");
+    write_pair(2, b"ENTITIES");
+    write_pair(0, b"LINE");
+    write_pair(8, text);
+    write_pair(0, b"ENDSEC");
+    write_pair(0, b"EOF");
+    bytes
+}
+
+#[test]
+fn read_binary_with_encoding_from_code_page() {
+    // these bytes represent `Слой` in Windows-1251
+    let bytes =
+        binary_drawing_bytes_with_code_page("AC1015", "ANSI_1251", &[0xD1, 0xEB, 0xEE, 0xE9]);
+    let drawing = unwrap_drawing(Drawing::load(&mut bytes.as_slice()));
+    assert_eq!("Слой", first_entity_layer(&drawing));
+
+    let layer = Drawing::stream(Cursor::new(bytes.clone()))
+        .unwrap()
+        .find_map(|item| match item.unwrap() {
+            DrawingStreamItem::Entity(e) => Some(e.common.layer),
+            _ => None,
+        });
+    assert_eq!(Some(String::from("Слой")), layer);
+
+    let layer = BinaryCodePairReader::new(&bytes)
+        .unwrap()
+        .map(|pair| pair.unwrap())
+        .find(|pair| pair.code == 8)
+        .unwrap();
+    assert_eq!(Some("Слой"), layer.value.as_str());
+}
+
+#[test]
+fn explicit_encoding_overrides_code_page_in_binary() {
+    let bytes =
+        binary_drawing_bytes_with_code_page("AC1015", "ANSI_1251", &[0xD1, 0xEB, 0xEE, 0xE9]);
+    let drawing = unwrap_drawing(Drawing::load_with_encoding(
+        &mut bytes.as_slice(),
+        encoding_rs::WINDOWS_1252,
+    ));
+    assert_eq!("Ñëîé", first_entity_layer(&drawing));
+}
+
+#[test]
+fn code_page_is_ignored_for_utf8_versions_in_binary() {
+    let bytes = binary_drawing_bytes_with_code_page("AC1021", "ANSI_1251", "Слой".as_bytes());
+    let drawing = unwrap_drawing(Drawing::load(&mut bytes.as_slice()));
+    assert_eq!("Слой", first_entity_layer(&drawing));
+
+    let layer = BinaryCodePairReader::new(&bytes)
+        .unwrap()
+        .map(|pair| pair.unwrap())
+        .find(|pair| pair.code == 8)
+        .unwrap();
+    assert_eq!(Some("Слой"), layer.value.as_str());
+}
+
+#[test]
+fn round_trip_non_ascii_text_in_binary() {
+    for &version in &[AcadVersion::R12, AcadVersion::R2000, AcadVersion::R2018] {
+        let mut drawing = Drawing::new();
+        drawing.header.version = version;
+        let mut line = Entity::new(EntityType::Line(Line::default()));
+        line.common.layer = String::from("Слой");
+        drawing.add_entity(line);
+        let drawing = unwrap_drawing(Drawing::load(&mut to_binary(&drawing).as_slice()));
+        assert_eq!("Слой", first_entity_layer(&drawing));
+    }
+}
@@ -1,3 +1,6 @@
+#[cfg(all(test, feature = "async"))]
+mod async_io;
+
 #[cfg(test)]
 mod encoding;
 
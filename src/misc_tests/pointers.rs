@@ -113,3 +113,104 @@ fn set_pointer_on_entity() {
         ],
     );
 }
+
+fn add_paper_space_layout(drawing: &mut Drawing) -> (Handle, Handle, Handle) {
+    let block_record_handle = drawing
+        .block_records()
+        .find(|b| b.name == "*PAPER_SPACE")
+        .unwrap()
+        .handle;
+    let viewport_handle = drawing
+        .add_entity(Entity::new(EntityType::Viewport(Default::default())))
+        .common
+        .handle;
+    let layout = Layout {
+        layout_name: String::from("Layout1"),
+        __block_record_handle: block_record_handle,
+        __viewport_handle: viewport_handle,
+        ..Default::default()
+    };
+    let layout_handle = drawing
+        .add_object(Object::new(ObjectType::Layout(layout)))
+        .common
+        .handle;
+    (block_record_handle, viewport_handle, layout_handle)
+}
+
+#[test]
+fn follow_layout_pointers_to_block_record_and_viewport() {
+    let mut drawing = Drawing::new();
+    drawing.header.version = AcadVersion::R2000;
+    let (_, viewport_handle, layout_handle) = add_paper_space_layout(&mut drawing);
+
+    let layout = match drawing.objects().next().unwrap().specific {
+        ObjectType::Layout(ref layout) => layout,
+        _ => panic!("expected a layout"),
+    };
+    assert_eq!("*PAPER_SPACE", layout.block_record(&drawing).unwrap().name);
+    assert_eq!(
+        viewport_handle,
+        layout.viewport(&drawing).unwrap().common.handle
+    );
+
+    // adding the layout pointed the block record back at it
+    let block_record = drawing
+        .block_records()
+        .find(|b| b.name == "*PAPER_SPACE")
+        .unwrap();
+    match block_record.layout(&drawing) {
+        Some(DrawingItem::Object(obj)) => assert_eq!(layout_handle, obj.common.handle),
+        _ => panic!("expected a layout"),
+    }
+}
+
+#[test]
+fn write_layout_and_block_record_pointers() {
+    let mut drawing = Drawing::new();
+    drawing.header.version = AcadVersion::R2000;
+    let (block_record_handle, viewport_handle, layout_handle) =
+        add_paper_space_layout(&mut drawing);
+    assert_contains_pairs(
+        &drawing,
+        vec![
+            CodePair::new_str(0, "BLOCK_RECORD"),
+            CodePair::new_string(5, &block_record_handle.as_string()),
+            CodePair::new_str(100, "AcDbSymbolTableRecord"),
+            CodePair::new_str(100, "AcDbBlockTableRecord"),
+            CodePair::new_str(2, "*PAPER_SPACE"),
+            CodePair::new_i16(70, 0),
+            CodePair::new_string(340, &layout_handle.as_string()),
+        ],
+    );
+    assert_contains_pairs(
+        &drawing,
+        vec![
+            CodePair::new_string(330, &block_record_handle.as_string()),
+            CodePair::new_string(331, &viewport_handle.as_string()),
+        ],
+    );
+}
+
+#[test]
+fn normalize_drops_stale_layout_links() {
+    let mut drawing = Drawing::new();
+    drawing.header.version = AcadVersion::R2000;
+    add_paper_space_layout(&mut drawing);
+    drawing.remove_entity(0); // the viewport
+    drawing.remove_object(0); // the layout
+    drawing.add_object(Object::new(ObjectType::Layout(Layout {
+        __viewport_handle: Handle(0xabcd),
+        ..Default::default()
+    })));
+    drawing.normalize();
+
+    let block_record = drawing
+        .block_records()
+        .find(|b| b.name == "*PAPER_SPACE")
+        .unwrap();
+    assert!(block_record.layout(&drawing).is_none());
+    match drawing.objects().next().unwrap().specific {
+        ObjectType::Layout(ref layout) => assert_eq!(Handle::empty(), layout.__viewport_handle),
+        _ => panic!("expected a layout"),
+    };
+}
@@ -0,0 +1,628 @@
+use enum_primitive::FromPrimitive;
+
+use crate::{
+    CodePair, Drawing, DrawingItem, DxfError, DxfResult, Handle, Point, TransformationMatrix,
+    Vector,
+};
+
+use crate::code_pair_put_back::CodePairPutBack;
+use crate::enums::*;
+use crate::tables::{BlockRecord, LineType, Style};
+
+/// The `CONTEXT_DATA{` group of an `MLeader` containing its leaders and its MText or block content.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct MLeaderContextData {
+    pub content_scale: f64,
+    pub content_base_point: Point,
+    pub text_height: f64,
+    pub arrowhead_size: f64,
+    pub landing_gap: f64,
+    pub text_left_attachment: i16,
+    pub text_right_attachment: i16,
+    pub text_alignment_type: i16,
+    pub block_content_connection_type: i16,
+    pub has_m_text_content: bool,
+    pub text: String,
+    pub text_normal: Vector,
+    #[doc(hidden)]
+    pub __text_style_handle: Handle,
+    pub text_location: Point,
+    pub text_direction: Vector,
+    /// Text rotation in radians.
+    pub text_rotation: f64,
+    pub text_width: f64,
+    pub text_boundary_height: f64,
+    pub text_line_spacing_factor: f64,
+    pub text_line_spacing_style: i16,
+    pub text_color: i32,
+    pub text_attachment: i16,
+    pub text_flow_direction: i16,
+    pub text_background_color: i32,
+    pub text_background_scale_factor: f64,
+    pub text_background_transparency: i32,
+    pub is_text_background_color_on: bool,
+    pub is_text_background_fill_on: bool,
+    pub text_column_type: i16,
+    pub use_text_auto_height: bool,
+    pub text_column_width: f64,
+    pub text_column_gutter_width: f64,
+    pub is_text_column_flow_reversed: bool,
+    pub text_column_sizes: Vec<f64>,
+    pub use_text_word_break: bool,
+    pub has_block_content: bool,
+    #[doc(hidden)]
+    pub __block_content_handle: Handle,
+    pub block_content_normal: Vector,
+    pub block_content_location: Point,
+    pub block_content_scale: Vector,
+    /// Block rotation in radians.
+    pub block_content_rotation: f64,
+    pub block_content_color: i32,
+    pub block_content_transformation_matrix: TransformationMatrix,
+    pub plane_origin_point: Point,
+    pub plane_x_axis: Vector,
+    pub plane_y_axis: Vector,
+    pub is_plane_normal_reversed: bool,
+    pub leaders: Vec<MLeaderLeader>,
+    pub bottom_text_attachment_direction: BottomTextAttachmentDirection,
+    pub top_text_attachment_direction: TopTextAttachmentDirection,
+}
+
+impl Default for MLeaderContextData {
+    fn default() -> Self {
+        MLeaderContextData {
+            content_scale: 1.0,
+            content_base_point: Point::origin(),
+            text_height: 0.18,
+            arrowhead_size: 0.18,
+            landing_gap: 0.09,
+            text_left_attachment: 1,
+            text_right_attachment: 1,
+            text_alignment_type: 0,
+            block_content_connection_type: 0,
+            has_m_text_content: true,
+            text: String::new(),
+            text_normal: Vector::z_axis(),
+            __text_style_handle: Handle::empty(),
+            text_location: Point::origin(),
+            text_direction: Vector::x_axis(),
+            text_rotation: 0.0,
+            text_width: 0.0,
+            text_boundary_height: 0.0,
+            text_line_spacing_factor: 1.0,
+            text_line_spacing_style: 1,
+            text_color: 0,
+            text_attachment: 1,
+            text_flow_direction: 1,
+            text_background_color: 0,
+            text_background_scale_factor: 1.5,
+            text_background_transparency: 0,
+            is_text_background_color_on: false,
+            is_text_background_fill_on: false,
+            text_column_type: 0,
+            use_text_auto_height: false,
+            text_column_width: 0.0,
+            text_column_gutter_width: 0.0,
+            is_text_column_flow_reversed: false,
+            text_column_sizes: vec![],
+            use_text_word_break: false,
+            has_block_content: false,
+            __block_content_handle: Handle::empty(),
+            block_content_normal: Vector::z_axis(),
+            block_content_location: Point::origin(),
+            block_content_scale: Vector::new(1.0, 1.0, 1.0),
+            block_content_rotation: 0.0,
+            block_content_color: 0,
+            block_content_transformation_matrix: TransformationMatrix::identity(),
+            plane_origin_point: Point::origin(),
+            plane_x_axis: Vector::x_axis(),
+            plane_y_axis: Vector::y_axis(),
+            is_plane_normal_reversed: false,
+            leaders: vec![],
+            bottom_text_attachment_direction: BottomTextAttachmentDirection::Center,
+            top_text_attachment_direction: TopTextAttachmentDirection::Center,
+        }
+    }
+}
+
+/// A single `LEADER{` group of an `MLeader`.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct MLeaderLeader {
+    pub has_set_last_leader_line_point: bool,
+    pub has_set_dogleg_vector: bool,
+    pub last_leader_line_point: Point,
+    pub dogleg_vector: Vector,
+    pub break_start_points: Vec<Point>,
+    pub break_end_points: Vec<Point>,
+    pub leader_branch_index: i32,
+    pub dogleg_length: f64,
+    pub leader_lines: Vec<MLeaderLeaderLine>,
+    pub attachment_direction: TextAttachmentDirection,
+}
+
+impl Default for MLeaderLeader {
+    fn default() -> Self {
+        MLeaderLeader {
+            has_set_last_leader_line_point: true,
+            has_set_dogleg_vector: true,
+            last_leader_line_point: Point::origin(),
+            dogleg_vector: Vector::x_axis(),
+            break_start_points: vec![],
+            break_end_points: vec![],
+            leader_branch_index: 0,
+            dogleg_length: 0.36,
+            leader_lines: vec![],
+            attachment_direction: TextAttachmentDirection::Horizontal,
+        }
+    }
+}
+
+/// A single `LEADER_LINE{` group of an `MLeaderLeader`.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct MLeaderLeaderLine {
+    pub vertices: Vec<Point>,
+    pub break_point_indices: Vec<i32>,
+    pub break_start_points: Vec<Point>,
+    pub break_end_points: Vec<Point>,
+    pub leader_line_index: i32,
+    pub leader_line_type: i16,
+    pub leader_line_color: i32,
+    #[doc(hidden)]
+    pub __line_type_handle: Handle,
+    pub leader_line_weight: i16,
+    pub arrowhead_size: f64,
+    #[doc(hidden)]
+    pub __arrowhead_handle: Handle,
+    /// Flags indicating which of the leader line's values override the `MLeader`'s.
+    pub override_flags: i32,
+}
+
+impl Default for MLeaderLeaderLine {
+    fn default() -> Self {
+        MLeaderLeaderLine {
+            vertices: vec![],
+            break_point_indices: vec![],
+            break_start_points: vec![],
+            break_end_points: vec![],
+            leader_line_index: 0,
+            leader_line_type: 1,
+            leader_line_color: 0,
+            __line_type_handle: Handle::empty(),
+            leader_line_weight: 0,
+            arrowhead_size: 0.18,
+            __arrowhead_handle: Handle::empty(),
+            override_flags: 0,
+        }
+    }
+}
+
+// public implementation
+impl MLeaderContextData {
+    pub fn text_style<'a>(&self, drawing: &'a Drawing) -> Option<&'a Style> {
+        match drawing.item_by_handle(self.__text_style_handle) {
+            Some(DrawingItem::Style(val)) => Some(val),
+            _ => None,
+        }
+    }
+    pub fn set_text_style(&mut self, item: &Style) {
+        self.__text_style_handle = DrawingItem::Style(item).handle();
+    }
+    pub fn block_content<'a>(&self, drawing: &'a Drawing) -> Option<&'a BlockRecord> {
+        match drawing.item_by_handle(self.__block_content_handle) {
+            Some(DrawingItem::BlockRecord(val)) => Some(val),
+            _ => None,
+        }
+    }
+    pub fn set_block_content(&mut self, item: &BlockRecord) {
+        self.__block_content_handle = DrawingItem::BlockRecord(item).handle();
+    }
+}
+
+impl MLeaderLeaderLine {
+    pub fn line_type<'a>(&self, drawing: &'a Drawing) -> Option<&'a LineType> {
+        match drawing.item_by_handle(self.__line_type_handle) {
+            Some(DrawingItem::LineType(val)) => Some(val),
+            _ => None,
+        }
+    }
+    pub fn set_line_type(&mut self, item: &LineType) {
+        self.__line_type_handle = DrawingItem::LineType(item).handle();
+    }
+    pub fn arrowhead<'a>(&self, drawing: &'a Drawing) -> Option<&'a BlockRecord> {
+        match drawing.item_by_handle(self.__arrowhead_handle) {
+            Some(DrawingItem::BlockRecord(val)) => Some(val),
+            _ => None,
+        }
+    }
+    pub fn set_arrowhead(&mut self, item: &BlockRecord) {
+        self.__arrowhead_handle = DrawingItem::BlockRecord(item).handle();
+    }
+}
+
+// returns the next pair of a `{`/`}` delimited group, or `None` if the group ended unexpectedly
+macro_rules! next_group_pair {
+    ($iter: expr) => {
+        match $iter.next() {
+            Some(Ok(pair @ CodePair { code: 0, .. })) => {
+                $iter.put_back(Ok(pair));
+                None
+            }
+            Some(Ok(pair)) => Some(pair),
+            Some(Err(e)) => return Err(e),
+            None => None,
+        }
+    };
+}
+
+// internal visibility only
+impl MLeaderContextData {
+    pub(crate) fn add_pointers(&self, handles: &mut Vec<Handle>) {
+        handles.push(self.__text_style_handle);
+        handles.push(self.__block_content_handle);
+        for leader in &self.leaders {
+            for line in &leader.leader_lines {
+                handles.push(line.__line_type_handle);
+                handles.push(line.__arrowhead_handle);
+            }
+        }
+    }
+    /// Reads the group after the opening `300/CONTEXT_DATA{` pair has been consumed.
+    pub(crate) fn read(iter: &mut CodePairPutBack) -> DxfResult<MLeaderContextData> {
+        let mut context = MLeaderContextData::default();
+        let mut matrix_values = vec![];
+        while let Some(pair) = next_group_pair!(iter) {
+            match pair.code {
+                301 => break, // }
+                302 => {
+                    // LEADER{
+                    context.leaders.push(MLeaderLeader::read(iter)?);
+                }
+                10 => context.content_base_point.x = pair.assert_f64()?,
+                20 => context.content_base_point.y = pair.assert_f64()?,
+                30 => context.content_base_point.z = pair.assert_f64()?,
+                11 => context.text_normal.x = pair.assert_f64()?,
+                21 => context.text_normal.y = pair.assert_f64()?,
+                31 => context.text_normal.z = pair.assert_f64()?,
+                12 => context.text_location.x = pair.assert_f64()?,
+                22 => context.text_location.y = pair.assert_f64()?,
+                32 => context.text_location.z = pair.assert_f64()?,
+                13 => context.text_direction.x = pair.assert_f64()?,
+                23 => context.text_direction.y = pair.assert_f64()?,
+                33 => context.text_direction.z = pair.assert_f64()?,
+                14 => context.block_content_normal.x = pair.assert_f64()?,
+                24 => context.block_content_normal.y = pair.assert_f64()?,
+                34 => context.block_content_normal.z = pair.assert_f64()?,
+                15 => context.block_content_location.x = pair.assert_f64()?,
+                25 => context.block_content_location.y = pair.assert_f64()?,
+                35 => context.block_content_location.z = pair.assert_f64()?,
+                16 => context.block_content_scale.x = pair.assert_f64()?,
+                26 => context.block_content_scale.y = pair.assert_f64()?,
+                36 => context.block_content_scale.z = pair.assert_f64()?,
+                40 => context.content_scale = pair.assert_f64()?,
+                41 => context.text_height = pair.assert_f64()?,
+                42 => context.text_rotation = pair.assert_f64()?,
+                43 => context.text_width = pair.assert_f64()?,
+                44 => context.text_boundary_height = pair.assert_f64()?,
+                45 => context.text_line_spacing_factor = pair.assert_f64()?,
+                46 => context.block_content_rotation = pair.assert_f64()?,
+                47 => matrix_values.push(pair.assert_f64()?),
+                90 => context.text_color = pair.assert_i32()?,
+                91 => context.text_background_color = pair.assert_i32()?,
+                92 => context.text_background_transparency = pair.assert_i32()?,
+                93 => context.block_content_color = pair.assert_i32()?,
+                110 => context.plane_origin_point.x = pair.assert_f64()?,
+                120 => context.plane_origin_point.y = pair.assert_f64()?,
+                130 => context.plane_origin_point.z = pair.assert_f64()?,
+                111 => context.plane_x_axis.x = pair.assert_f64()?,
+                121 => context.plane_x_axis.y = pair.assert_f64()?,
+                131 => context.plane_x_axis.z = pair.assert_f64()?,
+                112 => context.plane_y_axis.x = pair.assert_f64()?,
+                122 => context.plane_y_axis.y = pair.assert_f64()?,
+                132 => context.plane_y_axis.z = pair.assert_f64()?,
+                140 => context.arrowhead_size = pair.assert_f64()?,
+                141 => context.text_background_scale_factor = pair.assert_f64()?,
+                142 => context.text_column_width = pair.assert_f64()?,
+                143 => context.text_column_gutter_width = pair.assert_f64()?,
+                144 => context.text_column_sizes.push(pair.assert_f64()?),
+                145 => context.landing_gap = pair.assert_f64()?,
+                170 => context.text_line_spacing_style = pair.assert_i16()?,
+                171 => context.text_attachment = pair.assert_i16()?,
+                172 => context.text_flow_direction = pair.assert_i16()?,
+                173 => context.text_column_type = pair.assert_i16()?,
+                174 => context.text_left_attachment = pair.assert_i16()?,
+                175 => context.text_right_attachment = pair.assert_i16()?,
+                176 => context.text_alignment_type = pair.assert_i16()?,
+                177 => context.block_content_connection_type = pair.assert_i16()?,
+                272 => {
+                    context.bottom_text_attachment_direction = enum_from_number!(
+                        BottomTextAttachmentDirection,
+                        Center,
+                        from_i16,
+                        pair.assert_i16()?
+                    )
+                }
+                273 => {
+                    context.top_text_attachment_direction = enum_from_number!(
+                        TopTextAttachmentDirection,
+                        Center,
+                        from_i16,
+                        pair.assert_i16()?
+                    )
+                }
+                290 => context.has_m_text_content = pair.assert_bool()?,
+                291 => context.is_text_background_color_on = pair.assert_bool()?,
+                292 => context.is_text_background_fill_on = pair.assert_bool()?,
+                293 => context.use_text_auto_height = pair.assert_bool()?,
+                294 => context.is_text_column_flow_reversed = pair.assert_bool()?,
+                295 => context.use_text_word_break = pair.assert_bool()?,
+                296 => context.has_block_content = pair.assert_bool()?,
+                297 => context.is_plane_normal_reversed = pair.assert_bool()?,
+                304 => context.text = pair.assert_string()?,
+                340 => context.__text_style_handle = pair.as_handle()?,
+                341 => context.__block_content_handle = pair.as_handle()?,
+                _ => (), // unknown code, just ignore
+            }
+        }
+
+        if matrix_values.len() == 16 {
+            context.block_content_transformation_matrix =
+                TransformationMatrix::from_vec(&matrix_values);
+        }
+
+        Ok(context)
+    }
+    pub(crate) fn add_code_pairs(&self, pairs: &mut Vec<CodePair>, version: AcadVersion) {
+        pairs.push(CodePair::new_str(300, "CONTEXT_DATA{"));
+        pairs.push(CodePair::new_f64(40, self.content_scale));
+        pairs.push(CodePair::new_f64(10, self.content_base_point.x));
+        pairs.push(CodePair::new_f64(20, self.content_base_point.y));
+        pairs.push(CodePair::new_f64(30, self.content_base_point.z));
+        pairs.push(CodePair::new_f64(41, self.text_height));
+        pairs.push(CodePair::new_f64(140, self.arrowhead_size));
+        pairs.push(CodePair::new_f64(145, self.landing_gap));
+        pairs.push(CodePair::new_i16(174, self.text_left_attachment));
+        pairs.push(CodePair::new_i16(175, self.text_right_attachment));
+        pairs.push(CodePair::new_i16(176, self.text_alignment_type));
+        pairs.push(CodePair::new_i16(177, self.block_content_connection_type));
+        pairs.push(CodePair::new_bool(290, self.has_m_text_content));
+        if self.has_m_text_content {
+            pairs.push(CodePair::new_string(304, &self.text));
+            pairs.push(CodePair::new_f64(11, self.text_normal.x));
+            pairs.push(CodePair::new_f64(21, self.text_normal.y));
+            pairs.push(CodePair::new_f64(31, self.text_normal.z));
+            pairs.push(CodePair::new_string(
+                340,
+                &self.__text_style_handle.as_string(),
+            ));
+            pairs.push(CodePair::new_f64(12, self.text_location.x));
+            pairs.push(CodePair::new_f64(22, self.text_location.y));
+            pairs.push(CodePair::new_f64(32, self.text_location.z));
+            pairs.push(CodePair::new_f64(13, self.text_direction.x));
+            pairs.push(CodePair::new_f64(23, self.text_direction.y));
+            pairs.push(CodePair::new_f64(33, self.text_direction.z));
+            pairs.push(CodePair::new_f64(42, self.text_rotation));
+            pairs.push(CodePair::new_f64(43, self.text_width));
+            pairs.push(CodePair::new_f64(44, self.text_boundary_height));
+            pairs.push(CodePair::new_f64(45, self.text_line_spacing_factor));
+            pairs.push(CodePair::new_i16(170, self.text_line_spacing_style));
+            pairs.push(CodePair::new_i32(90, self.text_color));
+            pairs.push(CodePair::new_i16(171, self.text_attachment));
+            pairs.push(CodePair::new_i16(172, self.text_flow_direction));
+            pairs.push(CodePair::new_i32(91, self.text_background_color));
+            pairs.push(CodePair::new_f64(141, self.text_background_scale_factor));
+            pairs.push(CodePair::new_i32(92, self.text_background_transparency));
+            pairs.push(CodePair::new_bool(291, self.is_text_background_color_on));
+            pairs.push(CodePair::new_bool(292, self.is_text_background_fill_on));
+            pairs.push(CodePair::new_i16(173, self.text_column_type));
+            pairs.push(CodePair::new_bool(293, self.use_text_auto_height));
+            pairs.push(CodePair::new_f64(142, self.text_column_width));
+            pairs.push(CodePair::new_f64(143, self.text_column_gutter_width));
+            pairs.push(CodePair::new_bool(294, self.is_text_column_flow_reversed));
+            for size in &self.text_column_sizes {
+                pairs.push(CodePair::new_f64(144, *size));
+            }
+            pairs.push(CodePair::new_bool(295, self.use_text_word_break));
+        }
+        pairs.push(CodePair::new_bool(296, self.has_block_content));
+        if self.has_block_content {
+            pairs.push(CodePair::new_string(
+                341,
+                &self.__block_content_handle.as_string(),
+            ));
+            pairs.push(CodePair::new_f64(14, self.block_content_normal.x));
+            pairs.push(CodePair::new_f64(24, self.block_content_normal.y));
+            pairs.push(CodePair::new_f64(34, self.block_content_normal.z));
+            pairs.push(CodePair::new_f64(15, self.block_content_location.x));
+            pairs.push(CodePair::new_f64(25, self.block_content_location.y));
+            pairs.push(CodePair::new_f64(35, self.block_content_location.z));
+            pairs.push(CodePair::new_f64(16, self.block_content_scale.x));
+            pairs.push(CodePair::new_f64(26, self.block_content_scale.y));
+            pairs.push(CodePair::new_f64(36, self.block_content_scale.z));
+            pairs.push(CodePair::new_f64(46, self.block_content_rotation));
+            pairs.push(CodePair::new_i32(93, self.block_content_color));
+            for value in self.block_content_transformation_matrix.values() {
+                pairs.push(CodePair::new_f64(47, value));
+            }
+        }
+        pairs.push(CodePair::new_f64(110, self.plane_origin_point.x));
+        pairs.push(CodePair::new_f64(120, self.plane_origin_point.y));
+        pairs.push(CodePair::new_f64(130, self.plane_origin_point.z));
+        pairs.push(CodePair::new_f64(111, self.plane_x_axis.x));
+        pairs.push(CodePair::new_f64(121, self.plane_x_axis.y));
+        pairs.push(CodePair::new_f64(131, self.plane_x_axis.z));
+        pairs.push(CodePair::new_f64(112, self.plane_y_axis.x));
+        pairs.push(CodePair::new_f64(122, self.plane_y_axis.y));
+        pairs.push(CodePair::new_f64(132, self.plane_y_axis.z));
+        pairs.push(CodePair::new_bool(297, self.is_plane_normal_reversed));
+        for leader in &self.leaders {
+            leader.add_code_pairs(pairs, version);
+        }
+        if version >= AcadVersion::R2010 {
+            pairs.push(CodePair::new_i16(
+                272,
+                self.bottom_text_attachment_direction as i16,
+            ));
+            pairs.push(CodePair::new_i16(
+                273,
+                self.top_text_attachment_direction as i16,
+            ));
+        }
+        pairs.push(CodePair::new_str(301, "}"));
+    }
+}
+
+// internal visibility only
+impl MLeaderLeader {
+    /// Reads the group after the opening `302/LEADER{` pair has been consumed.
+    pub(crate) fn read(iter: &mut CodePairPutBack) -> DxfResult<MLeaderLeader> {
+        let mut leader = MLeaderLeader::default();
+        while let Some(pair) = next_group_pair!(iter) {
+            match pair.code {
+                303 => break, // }
+                304 => {
+                    // LEADER_LINE{
+                    leader.leader_lines.push(MLeaderLeaderLine::read(iter)?);
+                }
+                10 => leader.last_leader_line_point.x = pair.assert_f64()?,
+                20 => leader.last_leader_line_point.y = pair.assert_f64()?,
+                30 => leader.last_leader_line_point.z = pair.assert_f64()?,
+                11 => leader.dogleg_vector.x = pair.assert_f64()?,
+                21 => leader.dogleg_vector.y = pair.assert_f64()?,
+                31 => leader.dogleg_vector.z = pair.assert_f64()?,
+                12 => leader
+                    .break_start_points
+                    .push(Point::new(pair.assert_f64()?, 0.0, 0.0)),
+                22 => vec_last!(leader.break_start_points).y = pair.assert_f64()?,
+                32 => vec_last!(leader.break_start_points).z = pair.assert_f64()?,
+                13 => leader
+                    .break_end_points
+                    .push(Point::new(pair.assert_f64()?, 0.0, 0.0)),
+                23 => vec_last!(leader.break_end_points).y = pair.assert_f64()?,
+                33 => vec_last!(leader.break_end_points).z = pair.assert_f64()?,
+                40 => leader.dogleg_length = pair.assert_f64()?,
+                90 => leader.leader_branch_index = pair.assert_i32()?,
+                271 => {
+                    leader.attachment_direction = enum_from_number!(
+                        TextAttachmentDirection,
+                        Horizontal,
+                        from_i16,
+                        pair.assert_i16()?
+                    )
+                }
+                290 => leader.has_set_last_leader_line_point = pair.assert_bool()?,
+                291 => leader.has_set_dogleg_vector = pair.assert_bool()?,
+                _ => (), // unknown code, just ignore
+            }
+        }
+
+        Ok(leader)
+    }
+    pub(crate) fn add_code_pairs(&self, pairs: &mut Vec<CodePair>, version: AcadVersion) {
+        pairs.push(CodePair::new_str(302, "LEADER{"));
+        pairs.push(CodePair::new_bool(290, self.has_set_last_leader_line_point));
+        pairs.push(CodePair::new_bool(291, self.has_set_dogleg_vector));
+        pairs.push(CodePair::new_f64(10, self.last_leader_line_point.x));
+        pairs.push(CodePair::new_f64(20, self.last_leader_line_point.y));
+        pairs.push(CodePair::new_f64(30, self.last_leader_line_point.z));
+        pairs.push(CodePair::new_f64(11, self.dogleg_vector.x));
+        pairs.push(CodePair::new_f64(21, self.dogleg_vector.y));
+        pairs.push(CodePair::new_f64(31, self.dogleg_vector.z));
+        for (start, end) in self.break_start_points.iter().zip(&self.break_end_points) {
+            pairs.push(CodePair::new_f64(12, start.x));
+            pairs.push(CodePair::new_f64(22, start.y));
+            pairs.push(CodePair::new_f64(32, start.z));
+            pairs.push(CodePair::new_f64(13, end.x));
+            pairs.push(CodePair::new_f64(23, end.y));
+            pairs.push(CodePair::new_f64(33, end.z));
+        }
+        pairs.push(CodePair::new_i32(90, self.leader_branch_index));
+        pairs.push(CodePair::new_f64(40, self.dogleg_length));
+        for leader_line in &self.leader_lines {
+            leader_line.add_code_pairs(pairs, version);
+        }
+        if version >= AcadVersion::R2010 {
+            pairs.push(CodePair::new_i16(271, self.attachment_direction as i16));
+        }
+        pairs.push(CodePair::new_str(303, "}"));
+    }
+}
+
+// internal visibility only
+impl MLeaderLeaderLine {
+    /// Reads the group after the opening `304/LEADER_LINE{` pair has been consumed.
+    pub(crate) fn read(iter: &mut CodePairPutBack) -> DxfResult<MLeaderLeaderLine> {
+        let mut line = MLeaderLeaderLine::default();
+        while let Some(pair) = next_group_pair!(iter) {
+            match pair.code {
+                305 => break, // }
+                10 => line.vertices.push(Point::new(pair.assert_f64()?, 0.0, 0.0)),
+                20 => vec_last!(line.vertices).y = pair.assert_f64()?,
+                30 => vec_last!(line.vertices).z = pair.assert_f64()?,
+                11 => line
+                    .break_start_points
+                    .push(Point::new(pair.assert_f64()?, 0.0, 0.0)),
+                21 => vec_last!(line.break_start_points).y = pair.assert_f64()?,
+                31 => vec_last!(line.break_start_points).z = pair.assert_f64()?,
+                12 => line
+                    .break_end_points
+                    .push(Point::new(pair.assert_f64()?, 0.0, 0.0)),
+                22 => vec_last!(line.break_end_points).y = pair.assert_f64()?,
+                32 => vec_last!(line.break_end_points).z = pair.assert_f64()?,
+                40 => line.arrowhead_size = pair.assert_f64()?,
+                90 => line.break_point_indices.push(pair.assert_i32()?),
+                91 => line.leader_line_index = pair.assert_i32()?,
+                92 => line.leader_line_color = pair.assert_i32()?,
+                93 => line.override_flags = pair.assert_i32()?,
+                170 => line.leader_line_type = pair.assert_i16()?,
+                171 => line.leader_line_weight = pair.assert_i16()?,
+                340 => line.__line_type_handle = pair.as_handle()?,
+                341 => line.__arrowhead_handle = pair.as_handle()?,
+                _ => (), // unknown code, just ignore
+            }
+        }
+
+        Ok(line)
+    }
+    pub(crate) fn add_code_pairs(&self, pairs: &mut Vec<CodePair>, version: AcadVersion) {
+        pairs.push(CodePair::new_str(304, "LEADER_LINE{"));
+        for vertex in &self.vertices {
+            pairs.push(CodePair::new_f64(10, vertex.x));
+            pairs.push(CodePair::new_f64(20, vertex.y));
+            pairs.push(CodePair::new_f64(30, vertex.z));
+        }
+        for ((index, start), end) in self
+            .break_point_indices
+            .iter()
+            .zip(&self.break_start_points)
+            .zip(&self.break_end_points)
+        {
+            pairs.push(CodePair::new_i32(90, *index));
+            pairs.push(CodePair::new_f64(11, start.x));
+            pairs.push(CodePair::new_f64(21, start.y));
+            pairs.push(CodePair::new_f64(31, start.z));
+            pairs.push(CodePair::new_f64(12, end.x));
+            pairs.push(CodePair::new_f64(22, end.y));
+            pairs.push(CodePair::new_f64(32, end.z));
+        }
+        pairs.push(CodePair::new_i32(91, self.leader_line_index));
+        if version >= AcadVersion::R2010 {
+            pairs.push(CodePair::new_i16(170, self.leader_line_type));
+            pairs.push(CodePair::new_i32(92, self.leader_line_color));
+            pairs.push(CodePair::new_string(
+                340,
+                &self.__line_type_handle.as_string(),
+            ));
+            pairs.push(CodePair::new_i16(171, self.leader_line_weight));
+            pairs.push(CodePair::new_f64(40, self.arrowhead_size));
+            pairs.push(CodePair::new_string(
+                341,
+                &self.__arrowhead_handle.as_string(),
+            ));
+            pairs.push(CodePair::new_i32(93, self.override_flags));
+        }
+        pairs.push(CodePair::new_str(305, "}"));
+    }
+}
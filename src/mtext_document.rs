@@ -0,0 +1,740 @@
+use std::fmt;
+use std::iter::Peekable;
+use std::str::Chars;
+
+use crate::Color;
+
+/// The formatted contents of an `MText` split into paragraphs of runs, as parsed from the inline formatting codes,
+/// e.g., `\P`, `{\H2x;\C1;text}`, or `\S1/2;`.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct MTextDocument {
+    pub paragraphs: Vec<MTextParagraph>,
+}
+
+/// A paragraph of `MText` content.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct MTextParagraph {
+    pub runs: Vec<MTextRun>,
+    /// The value of the `\p` paragraph code, e.g., `xqc` to center the paragraph.
+    pub properties: Option<String>,
+    /// Whether the paragraph starts a new column, `\N`, instead of a new line, `\P`.
+    pub starts_column: bool,
+}
+
+/// A run of `MText` content with a single format.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct MTextRun {
+    pub content: MTextRunContent,
+    pub format: MTextFormat,
+}
+
+/// The content of an `MText` run.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub enum MTextRunContent {
+    /// Text, where a non-breaking space, `\~`, is `'\u{a0}'`.
+    Text(String),
+    /// Text stacked with `\S`, e.g., a fraction.
+    Stack(MTextStack),
+}
+
+/// Text stacked above other text with `\S`.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct MTextStack {
+    pub top: String,
+    pub bottom: String,
+    pub style: MTextStackStyle,
+}
+
+/// How the parts of stacked `MText` are separated.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub enum MTextStackStyle {
+    /// A horizontal line, written as `/`.
+    Horizontal,
+    /// A diagonal line, written as `#`.
+    Diagonal,
+    /// No line, as used for tolerances, written as `^`.
+    Tolerance,
+}
+
+/// The formatting of an `MText` run; `None` values and `false` flags use the entity's settings.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct MTextFormat {
+    pub font: Option<MTextFont>,
+    pub height: Option<MTextHeight>,
+    pub color: Option<MTextColor>,
+    pub is_underlined: bool,
+    pub is_overlined: bool,
+    pub is_struck_through: bool,
+    /// The obliquing angle in degrees, `\Q`.
+    pub oblique_angle: Option<f64>,
+    /// The spacing between characters as a factor of the normal spacing, `\T`.
+    pub tracking: Option<f64>,
+    /// The width of the characters as a factor of their normal width, `\W`.
+    pub width_factor: Option<f64>,
+    /// The vertical alignment relative to the rest of the line, `\A`: 0 for bottom, 1 for center, and 2 for top.
+    pub alignment: Option<i32>,
+}
+
+/// The font of an `MText` run.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct MTextFont {
+    /// The name of a TrueType font, or the file name of an SHX font.
+    pub name: String,
+    /// Whether the font is an SHX font, `\F`, instead of a TrueType font, `\f`.
+    pub is_shape_file: bool,
+    pub is_bold: bool,
+    pub is_italic: bool,
+    pub character_set: i32,
+    pub pitch: i32,
+}
+
+/// The text height of an `MText` run.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub enum MTextHeight {
+    Absolute(f64),
+    /// A factor of the entity's text height.
+    Relative(f64),
+}
+
+/// The color of an `MText` run.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub enum MTextColor {
+    /// An indexed color, `\C`.
+    Indexed(Color),
+    /// A 24-bit color, `\c`.
+    TrueColor(i32),
+}
+
+// public implementation
+impl MTextDocument {
+    /// Parses `MText` contents.  Unknown formatting codes are kept as text, the same as AutoCAD shows them, and the
+    /// result always has at least one paragraph.
+    pub fn parse(text: &str) -> Self {
+        let mut parser = Parser {
+            paragraphs: vec![MTextParagraph::default()],
+            format: MTextFormat::default(),
+            groups: vec![],
+            text: String::new(),
+        };
+        let mut chars = text.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '{' => parser.groups.push(parser.format.clone()),
+                '}' => {
+                    if let Some(format) = parser.groups.pop() {
+                        parser.set_format(format);
+                    }
+                }
+                '\\' => match chars.next() {
+                    Some('P') => parser.start_paragraph(false),
+                    Some('N') => parser.start_paragraph(true),
+                    Some('~') => parser.text.push('\u{a0}'),
+                    Some('L') => parser.update_format(|f| f.is_underlined = true),
+                    Some('l') => parser.update_format(|f| f.is_underlined = false),
+                    Some('O') => parser.update_format(|f| f.is_overlined = true),
+                    Some('o') => parser.update_format(|f| f.is_overlined = false),
+                    Some('K') => parser.update_format(|f| f.is_struck_through = true),
+                    Some('k') => parser.update_format(|f| f.is_struck_through = false),
+                    Some('S') => {
+                        let stack = parse_stack(&read_value(&mut chars));
+                        parser.add_run(MTextRunContent::Stack(stack));
+                    }
+                    Some('U') if chars.peek() == Some(&'+') => {
+                        chars.next();
+                        let code = chars.by_ref().take(4).collect::<String>();
+                        if let Some(c) = u32::from_str_radix(&code, 16)
+                            .ok()
+                            .and_then(std::char::from_u32)
+                        {
+                            parser.text.push(c);
+                        }
+                    }
+                    Some('p') => {
+                        let properties = read_value(&mut chars);
+                        parser.flush();
+                        let paragraph = parser.paragraphs.last_mut().expect("there's a paragraph");
+                        paragraph.properties = Some(properties);
+                    }
+                    Some(code @ 'A') | Some(code @ 'C') | Some(code @ 'c') | Some(code @ 'F')
+                    | Some(code @ 'f') | Some(code @ 'H') | Some(code @ 'Q') | Some(code @ 'T')
+                    | Some(code @ 'W') => {
+                        let value = read_value(&mut chars);
+                        parser.update_format(|f| apply_code(f, code, &value));
+                    }
+                    Some(c @ '\\') | Some(c @ '{') | Some(c @ '}') => parser.text.push(c),
+                    Some(c) => {
+                        parser.text.push('\\');
+                        parser.text.push(c);
+                    }
+                    None => parser.text.push('\\'),
+                },
+                _ => parser.text.push(c),
+            }
+        }
+
+        parser.flush();
+        MTextDocument {
+            paragraphs: parser.paragraphs,
+        }
+    }
+    /// Returns the text without formatting, with a line for each paragraph.  Stacked text is written as `top/bottom`
+    /// and non-breaking spaces as spaces.
+    pub fn plain_text(&self) -> String {
+        self.paragraphs
+            .iter()
+            .map(|p| p.plain_text())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+impl MTextParagraph {
+    /// Returns the text of the paragraph without formatting.  Stacked text is written as `top/bottom` and
+    /// non-breaking spaces as spaces.
+    pub fn plain_text(&self) -> String {
+        let mut result = String::new();
+        for run in &self.runs {
+            match run.content {
+                MTextRunContent::Text(ref text) => {
+                    result.extend(text.chars().map(|c| if c == '\u{a0}' { ' ' } else { c }))
+                }
+                MTextRunContent::Stack(ref stack) => {
+                    result.push_str(&stack.top);
+                    result.push('/');
+                    result.push_str(&stack.bottom);
+                }
+            }
+        }
+        result
+    }
+}
+
+impl MTextRun {
+    pub fn new(text: &str, format: MTextFormat) -> Self {
+        MTextRun {
+            content: MTextRunContent::Text(String::from(text)),
+            format,
+        }
+    }
+}
+
+/// Writes the contents with inline formatting codes, grouping each formatted run in `{}`.
+impl fmt::Display for MTextDocument {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        for (i, paragraph) in self.paragraphs.iter().enumerate() {
+            if i > 0 {
+                formatter.write_str(if paragraph.starts_column {
+                    "\\N"
+                } else {
+                    "\\P"
+                })?;
+            }
+            if let Some(ref properties) = paragraph.properties {
+                write!(formatter, "\\p{};", properties)?;
+            }
+            for run in &paragraph.runs {
+                let is_formatted = run.format != MTextFormat::default();
+                if is_formatted {
+                    formatter.write_str("{")?;
+                    write_format(formatter, &run.format)?;
+                }
+                match run.content {
+                    MTextRunContent::Text(ref text) => write_escaped(formatter, text, "")?,
+                    MTextRunContent::Stack(ref stack) => {
+                        let separator = match stack.style {
+                            MTextStackStyle::Horizontal => '/',
+                            MTextStackStyle::Diagonal => '#',
+                            MTextStackStyle::Tolerance => '^',
+                        };
+                        formatter.write_str("\\S")?;
+                        write_escaped(formatter, &stack.top, STACK_SPECIAL_CHARACTERS)?;
+                        write!(formatter, "{}", separator)?;
+                        write_escaped(formatter, &stack.bottom, STACK_SPECIAL_CHARACTERS)?;
+                        formatter.write_str(";")?;
+                    }
+                }
+                if is_formatted {
+                    formatter.write_str("}")?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+// private implementation
+
+/// The characters that have to be escaped in stacked text in addition to those in all text.
+const STACK_SPECIAL_CHARACTERS: &str = "/#^;";
+
+struct Parser {
+    paragraphs: Vec<MTextParagraph>,
+    format: MTextFormat,
+    /// The formats to restore at the end of each `{}` group.
+    groups: Vec<MTextFormat>,
+    /// The text read since the last run was added.
+    text: String,
+}
+
+impl Parser {
+    /// Adds the text read so far as a run, joining it to the previous run if that has the same format.
+    fn flush(&mut self) {
+        if self.text.is_empty() {
+            return;
+        }
+        let text = std::mem::take(&mut self.text);
+        let runs = &mut self
+            .paragraphs
+            .last_mut()
+            .expect("there's a paragraph")
+            .runs;
+        if let Some(MTextRun {
+            content: MTextRunContent::Text(ref mut previous),
+            ref format,
+        }) = runs.last_mut()
+        {
+            if *format == self.format {
+                previous.push_str(&text);
+                return;
+            }
+        }
+        runs.push(MTextRun {
+            content: MTextRunContent::Text(text),
+            format: self.format.clone(),
+        });
+    }
+    fn add_run(&mut self, content: MTextRunContent) {
+        self.flush();
+        let paragraph = self.paragraphs.last_mut().expect("there's a paragraph");
+        paragraph.runs.push(MTextRun {
+            content,
+            format: self.format.clone(),
+        });
+    }
+    fn set_format(&mut self, format: MTextFormat) {
+        if format != self.format {
+            self.flush();
+            self.format = format;
+        }
+    }
+    fn update_format<F: FnOnce(&mut MTextFormat)>(&mut self, update: F) {
+        let mut format = self.format.clone();
+        update(&mut format);
+        self.set_format(format);
+    }
+    /// Starts a new paragraph; the format carries over.
+    fn start_paragraph(&mut self, starts_column: bool) {
+        self.flush();
+        self.paragraphs.push(MTextParagraph {
+            starts_column,
+            ..Default::default()
+        });
+    }
+}
+
+/// Reads the value of a formatting code up to the terminating `;`, keeping escaped characters escaped.
+fn read_value(chars: &mut Peekable<Chars>) -> String {
+    let mut value = String::new();
+    while let Some(c) = chars.next() {
+        match c {
+            ';' => break,
+            '\\' => {
+                value.push(c);
+                if let Some(c) = chars.next() {
+                    value.push(c);
+                }
+            }
+            _ => value.push(c),
+        }
+    }
+    value
+}
+
+/// Parses the value of a `\S` code, which is split by the first unescaped `/`, `#`, or `^`.
+fn parse_stack(value: &str) -> MTextStack {
+    let mut parts = [String::new(), String::new()];
+    let mut style = None;
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        let part = if style.is_some() { 1 } else { 0 };
+        match c {
+            '\\' => {
+                if let Some(c) = chars.next() {
+                    parts[part].push(c);
+                }
+            }
+            '/' if style.is_none() => style = Some(MTextStackStyle::Horizontal),
+            '#' if style.is_none() => style = Some(MTextStackStyle::Diagonal),
+            '^' if style.is_none() => style = Some(MTextStackStyle::Tolerance),
+            _ => parts[part].push(c),
+        }
+    }
+    let [top, bottom] = parts;
+    MTextStack {
+        top,
+        bottom,
+        style: style.unwrap_or(MTextStackStyle::Horizontal),
+    }
+}
+
+/// Applies a formatting code with a value; invalid values are ignored.
+fn apply_code(format: &mut MTextFormat, code: char, value: &str) {
+    let value = value.trim();
+    // factors may be written with a trailing `x`
+    let factor = value.trim_end_matches(&['x', 'X'][..]).parse::<f64>().ok();
+    match code {
+        'A' => format.alignment = value.parse().ok().or(format.alignment),
+        'C' => {
+            if let Ok(index) = value.parse() {
+                format.color = Some(MTextColor::Indexed(Color::from_raw_value(index)));
+            }
+        }
+        'c' => {
+            if let Ok(rgb) = value.parse() {
+                format.color = Some(MTextColor::TrueColor(rgb));
+            }
+        }
+        'F' | 'f' => format.font = Some(parse_font(value, code == 'F')),
+        'H' => {
+            if let Some(height) = factor {
+                format.height = Some(match (value.ends_with(&['x', 'X'][..]), format.height) {
+                    // relative heights are relative to the current height
+                    (true, Some(MTextHeight::Absolute(current))) => {
+                        MTextHeight::Absolute(current * height)
+                    }
+                    (true, Some(MTextHeight::Relative(current))) => {
+                        MTextHeight::Relative(current * height)
+                    }
+                    (true, None) => MTextHeight::Relative(height),
+                    (false, _) => MTextHeight::Absolute(height),
+                });
+            }
+        }
+        'Q' => format.oblique_angle = factor.or(format.oblique_angle),
+        'T' => format.tracking = factor.or(format.tracking),
+        'W' => format.width_factor = factor.or(format.width_factor),
+        _ => (),
+    }
+}
+
+/// Parses a font value like `Arial|b1|i0|c0|p34` or `romans.shx`.
+fn parse_font(value: &str, is_shape_file: bool) -> MTextFont {
+    let mut parts = value.split('|');
+    let mut font = MTextFont {
+        name: String::from(parts.next().unwrap_or("")),
+        is_shape_file,
+        ..Default::default()
+    };
+    for part in parts {
+        let mut chars = part.chars();
+        let key = chars.next();
+        let value = chars.as_str();
+        match key {
+            Some('b') => font.is_bold = value == "1",
+            Some('i') => font.is_italic = value == "1",
+            Some('c') => font.character_set = value.parse().unwrap_or(0),
+            Some('p') => font.pitch = value.parse().unwrap_or(0),
+            _ => (),
+        }
+    }
+    font
+}
+
+fn write_format(formatter: &mut fmt::Formatter, format: &MTextFormat) -> fmt::Result {
+    if let Some(ref font) = format.font {
+        if font.is_shape_file {
+            write!(formatter, "\\F{}", font.name)?;
+            if font.character_set != 0 {
+                write!(formatter, "|c{}", font.character_set)?;
+            }
+            formatter.write_str(";")?;
+        } else {
+            write!(
+                formatter,
+                "\\f{}|b{}|i{}|c{}|p{};",
+                font.name,
+                font.is_bold as i32,
+                font.is_italic as i32,
+                font.character_set,
+                font.pitch
+            )?;
+        }
+    }
+    match format.height {
+        Some(MTextHeight::Absolute(height)) => write!(formatter, "\\H{};", height)?,
+        Some(MTextHeight::Relative(height)) => write!(formatter, "\\H{}x;", height)?,
+        None => (),
+    }
+    match format.color {
+        Some(MTextColor::Indexed(ref color)) => write!(formatter, "\\C{};", color.raw_value())?,
+        Some(MTextColor::TrueColor(rgb)) => write!(formatter, "\\c{};", rgb)?,
+        None => (),
+    }
+    if format.is_underlined {
+        formatter.write_str("\\L")?;
+    }
+    if format.is_overlined {
+        formatter.write_str("\\O")?;
+    }
+    if format.is_struck_through {
+        formatter.write_str("\\K")?;
+    }
+    if let Some(angle) = format.oblique_angle {
+        write!(formatter, "\\Q{};", angle)?;
+    }
+    if let Some(tracking) = format.tracking {
+        write!(formatter, "\\T{};", tracking)?;
+    }
+    if let Some(width_factor) = format.width_factor {
+        write!(formatter, "\\W{};", width_factor)?;
+    }
+    if let Some(alignment) = format.alignment {
+        write!(formatter, "\\A{};", alignment)?;
+    }
+    Ok(())
+}
+
+/// Writes text, escaping the characters that would otherwise start formatting codes or groups, and `special`.
+fn write_escaped(formatter: &mut fmt::Formatter, text: &str, special: &str) -> fmt::Result {
+    for c in text.chars() {
+        match c {
+            '\\' | '{' | '}' => write!(formatter, "\\{}", c)?,
+            '\u{a0}' => formatter.write_str("\\~")?,
+            _ if special.contains(c) => write!(formatter, "\\{}", c)?,
+            _ => write!(formatter, "{}", c)?,
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    fn text_run(text: &str, format: MTextFormat) -> MTextRun {
+        MTextRun::new(text, format)
+    }
+
+    fn paragraph(runs: Vec<MTextRun>) -> MTextParagraph {
+        MTextParagraph {
+            runs,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn parse_plain_text() {
+        let document = MTextDocument::parse("line 1\\Pline 2");
+        assert_eq!(
+            vec![
+                paragraph(vec![text_run("line 1", MTextFormat::default())]),
+                paragraph(vec![text_run("line 2", MTextFormat::default())]),
+            ],
+            document.paragraphs
+        );
+    }
+
+    #[test]
+    fn parse_empty_text() {
+        let document = MTextDocument::parse("");
+        assert_eq!(vec![MTextParagraph::default()], document.paragraphs);
+        assert_eq!("", document.plain_text());
+    }
+
+    #[test]
+    fn parse_groups_restore_format() {
+        let document = MTextDocument::parse("a{\\H2x;\\C1;\\Lb}c");
+        let bold = MTextFormat {
+            height: Some(MTextHeight::Relative(2.0)),
+            color: Some(MTextColor::Indexed(Color::from_index(1))),
+            is_underlined: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            vec![paragraph(vec![
+                text_run("a", MTextFormat::default()),
+                text_run("b", bold),
+                text_run("c", MTextFormat::default()),
+            ])],
+            document.paragraphs
+        );
+    }
+
+    #[test]
+    fn parse_format_carries_across_paragraphs() {
+        let document = MTextDocument::parse("\\Lone\\Ptwo\\lthree");
+        let underlined = MTextFormat {
+            is_underlined: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            vec![
+                paragraph(vec![text_run("one", underlined.clone())]),
+                paragraph(vec![
+                    text_run("two", underlined),
+                    text_run("three", MTextFormat::default()),
+                ]),
+            ],
+            document.paragraphs
+        );
+    }
+
+    #[test]
+    fn parse_relative_heights() {
+        let document = MTextDocument::parse("{\\H3;a{\\H0.5x;b}}{\\H2x;{\\H2x;c}}");
+        let heights = document.paragraphs[0]
+            .runs
+            .iter()
+            .map(|r| r.format.height)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                Some(MTextHeight::Absolute(3.0)),
+                Some(MTextHeight::Absolute(1.5)),
+                Some(MTextHeight::Relative(4.0)),
+            ],
+            heights
+        );
+    }
+
+    #[test]
+    fn parse_font() {
+        let document = MTextDocument::parse("{\\fArial|b1|i0|c0|p34;a}{\\Fromans.shx;b}");
+        let runs = &document.paragraphs[0].runs;
+        assert_eq!(
+            Some(MTextFont {
+                name: String::from("Arial"),
+                is_shape_file: false,
+                is_bold: true,
+                is_italic: false,
+                character_set: 0,
+                pitch: 34,
+            }),
+            runs[0].format.font
+        );
+        assert_eq!(
+            Some(MTextFont {
+                name: String::from("romans.shx"),
+                is_shape_file: true,
+                ..Default::default()
+            }),
+            runs[1].format.font
+        );
+    }
+
+    #[test]
+    fn parse_stacked_text() {
+        let document = MTextDocument::parse("1\\S1/2;\" and \\S+0.1^-0.2; and \\S3#4;");
+        let stacks = document.paragraphs[0]
+            .runs
+            .iter()
+            .filter_map(|r| match r.content {
+                MTextRunContent::Stack(ref stack) => Some(stack.clone()),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                MTextStack {
+                    top: String::from("1"),
+                    bottom: String::from("2"),
+                    style: MTextStackStyle::Horizontal,
+                },
+                MTextStack {
+                    top: String::from("+0.1"),
+                    bottom: String::from("-0.2"),
+                    style: MTextStackStyle::Tolerance,
+                },
+                MTextStack {
+                    top: String::from("3"),
+                    bottom: String::from("4"),
+                    style: MTextStackStyle::Diagonal,
+                },
+            ],
+            stacks
+        );
+        assert_eq!("11/2\" and +0.1/-0.2 and 3/4", document.plain_text());
+    }
+
+    #[test]
+    fn parse_escapes_and_unicode() {
+        let document = MTextDocument::parse("\\\\a\\{b\\}\\~\\U+00E9");
+        assert_eq!(
+            "\\a{b}\u{a0}\u{e9}",
+            match document.paragraphs[0].runs[0].content {
+                MTextRunContent::Text(ref text) => text.as_str(),
+                _ => panic!("expected text"),
+            }
+        );
+        assert_eq!("\\a{b} \u{e9}", document.plain_text());
+    }
+
+    #[test]
+    fn parse_unknown_codes_as_text() {
+        let document = MTextDocument::parse("\\Xabc\\U00E9\\");
+        assert_eq!("\\Xabc\\U00E9\\", document.plain_text());
+        assert_eq!(document, MTextDocument::parse(&document.to_string()));
+    }
+
+    #[test]
+    fn parse_paragraph_properties_and_columns() {
+        let document = MTextDocument::parse("\\pxqc;centered\\Nnext column");
+        assert_eq!(Some(String::from("xqc")), document.paragraphs[0].properties);
+        assert!(!document.paragraphs[0].starts_column);
+        assert!(document.paragraphs[1].starts_column);
+        assert_eq!("centered\nnext column", document.plain_text());
+    }
+
+    #[test]
+    fn write_formatted_runs() {
+        let document = MTextDocument {
+            paragraphs: vec![
+                paragraph(vec![
+                    text_run("plain {text}", MTextFormat::default()),
+                    text_run(
+                        "red",
+                        MTextFormat {
+                            color: Some(MTextColor::Indexed(Color::from_index(1))),
+                            height: Some(MTextHeight::Relative(1.5)),
+                            ..Default::default()
+                        },
+                    ),
+                ]),
+                MTextParagraph {
+                    runs: vec![MTextRun {
+                        content: MTextRunContent::Stack(MTextStack {
+                            top: String::from("1"),
+                            bottom: String::from("2"),
+                            style: MTextStackStyle::Diagonal,
+                        }),
+                        format: MTextFormat::default(),
+                    }],
+                    properties: Some(String::from("xqr")),
+                    starts_column: false,
+                },
+            ],
+        };
+        assert_eq!(
+            "plain \\{text\\}{\\H1.5x;\\C1;red}\\P\\pxqr;\\S1#2;",
+            document.to_string()
+        );
+    }
+
+    #[test]
+    fn round_trip_formatting() {
+        let text = "{\\fArial|b1|i1|c0|p34;\\H2.5;\\c16711680;\\L\\O\\K\\Q15;\\T1.1;\\W0.8;\\A1;x}\\Ny\\~z\\\\\\S1\\/2/3;";
+        let document = MTextDocument::parse(text);
+        assert_eq!(text, document.to_string());
+        assert_eq!(document, MTextDocument::parse(&document.to_string()));
+    }
+}
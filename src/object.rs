@@ -128,6 +128,34 @@ impl Object {
 
                             return Ok(Some(obj));
                         }
+                        None if iter.preserve_unknown_items() => {
+                            // keep the unsupported object's pairs so they can be written back
+                            let mut common = ObjectCommon::default();
+                            let mut pairs = vec![];
+                            loop {
+                                match iter.next() {
+                                    Some(Ok(pair @ CodePair { code: 0, .. })) => {
+                                        // found another object or ENDSEC
+                                        iter.put_back(Ok(pair));
+                                        break;
+                                    }
+                                    Some(Ok(pair)) => {
+                                        if pair.code == 5 && common.handle.is_empty() {
+                                            common.handle = pair.as_handle()?;
+                                        } else {
+                                            pairs.push(pair);
+                                        }
+                                    }
+                                    Some(Err(e)) => return Err(e),
+                                    None => return Err(DxfError::UnexpectedEndOfInput),
+                                }
+                            }
+
+                            return Ok(Some(Object {
+                                common,
+                                specific: ObjectType::Unknown { type_string, pairs },
+                            }));
+                        }
                         None => {
                             // swallow unsupported object
                             loop {
@@ -612,6 +640,9 @@ impl Object {
                         layout.elevation = pair.assert_f64()?;
                     }
                     330 => {
+                        layout.__block_record_handle = pair.as_handle()?;
+                    }
+                    331 => {
                         layout.__viewport_handle = pair.as_handle()?;
                     }
                     345 => {
@@ -1474,7 +1505,14 @@ impl Object {
         }
     }
     pub(crate) fn add_code_pairs(&self, pairs: &mut Vec<CodePair>, version: AcadVersion) {
-        if self.specific.is_supported_on_version(version) {
+        if let ObjectType::Unknown { .. } = self.specific {
+            // unsupported objects are written back as they were read
+            pairs.push(CodePair::new_str(0, self.specific.to_type_string()));
+            if !self.common.handle.is_empty() {
+                pairs.push(CodePair::new_string(5, &self.common.handle.as_string()));
+            }
+            self.specific.add_code_pairs(pairs, version);
+        } else if self.specific.is_supported_on_version(version) {
             pairs.push(CodePair::new_str(0, self.specific.to_type_string()));
             self.common.add_code_pairs(pairs, version);
             if !self.add_custom_code_pairs(pairs, version) {
@@ -1705,6 +1743,67 @@ mod tests {
         assert_eq!(0, drawing.objects().count());
     }
 
+    #[test]
+    fn read_unsupported_object_as_unknown() {
+        let drawing = drawing_with_unknown_items_from_pairs(vec![
+            CodePair::new_str(0, "SECTION"),
+            CodePair::new_str(2, "OBJECTS"),
+            CodePair::new_str(0, "UNSUPPORTED_OBJECT"),
+            CodePair::new_str(5, "A1"),
+            CodePair::new_str(330, "C"),
+            CodePair::new_str(100, "AcDbUnsupported"),
+            CodePair::new_str(1, "unsupported string"),
+            CodePair::new_str(0, "ENDSEC"),
+            CodePair::new_str(0, "EOF"),
+        ]);
+        let objects = drawing.objects().collect::<Vec<_>>();
+        assert_eq!(1, objects.len());
+        assert_eq!(Handle(0xa1), objects[0].common.handle);
+        assert_eq!(
+            ObjectType::Unknown {
+                type_string: String::from("UNSUPPORTED_OBJECT"),
+                pairs: vec![
+                    CodePair::new_str(330, "C"),
+                    CodePair::new_str(100, "AcDbUnsupported"),
+                    CodePair::new_str(1, "unsupported string"),
+                ],
+            },
+            objects[0].specific
+        );
+    }
+
+    #[test]
+    fn round_trip_unknown_object() {
+        let mut drawing = Drawing::new();
+        drawing.header.version = AcadVersion::R2000;
+        drawing.add_object(Object::new(ObjectType::Unknown {
+            type_string: String::from("UNSUPPORTED_OBJECT"),
+            pairs: vec![
+                CodePair::new_str(100, "AcDbUnsupported"),
+                CodePair::new_i32(90, 42),
+            ],
+        }));
+        let handle = drawing.objects().next().unwrap().common.handle;
+        assert_contains_pairs(
+            &drawing,
+            vec![
+                CodePair::new_str(0, "UNSUPPORTED_OBJECT"),
+                CodePair::new_string(5, &handle.as_string()),
+                CodePair::new_str(100, "AcDbUnsupported"),
+                CodePair::new_i32(90, 42),
+            ],
+        );
+
+        let round_tripped = drawing_with_unknown_items_from_pairs(drawing.code_pairs().unwrap());
+        let objects = round_tripped.objects().collect::<Vec<_>>();
+        assert_eq!(1, objects.len());
+        assert_eq!(handle, objects[0].common.handle);
+        assert_eq!(
+            drawing.objects().next().unwrap().specific,
+            objects[0].specific
+        );
+    }
+
     #[test]
     fn read_unsupported_object_between_supported_objects() {
         let drawing = drawing_from_pairs(vec![
@@ -1,5 +1,6 @@
 use crate::code_pair_put_back::CodePairPutBack;
 use crate::objects::Object;
+use crate::SkippedContent;
 
 pub(crate) struct ObjectIter<'a> {
     pub iter: &'a mut CodePairPutBack,
@@ -9,9 +10,23 @@ impl<'a> Iterator for ObjectIter<'a> {
     type Item = Object;
 
     fn next(&mut self) -> Option<Object> {
-        match Object::read(self.iter) {
-            Ok(Some(o)) => Some(o),
-            Ok(None) | Err(_) => None,
+        loop {
+            match Object::read(self.iter) {
+                Ok(Some(o)) => return Some(o),
+                Ok(None) => return None,
+                Err(e) => {
+                    // when lenient, drop the malformed object and continue with the next one
+                    let result = self
+                        .iter
+                        .recover(e, SkippedContent::Item)
+                        .and_then(|_| self.iter.skip_to_next_item());
+                    if let Err(e) = result {
+                        // leave the error for the section reader instead of silently ending the section
+                        self.iter.put_back(Err(e));
+                        return None;
+                    }
+                }
+            }
         }
     }
 }
@@ -0,0 +1,354 @@
+use rayon::prelude::*;
+
+use crate::code_pair_iter::{new_code_pair_iter_from_text, CodePairIter};
+use crate::code_pair_put_back::CodePairPutBack;
+use crate::entities::*;
+use crate::entity_iter::EntityIter;
+use crate::{DxfResult, LoadOptions, LoadWarning};
+
+/// The number of entities parsed together by one task.
+const ENTITIES_PER_CHUNK: usize = 2048;
+
+/// A run of whole entities in the text of the `ENTITIES` section.
+struct Chunk {
+    start: usize,
+    end: usize,
+    /// The line number of the first line of the chunk.
+    offset: usize,
+}
+
+/// Reads the entities up to the `ENDSEC` of the `ENTITIES` section by splitting its text at the `0` code pairs and
+/// parsing the chunks on the rayon thread pool.  The entities are returned in file order, uncombined and without
+/// handles assigned, the same as they would be read by an `EntityIter`.  Returns `None` without reading anything if
+/// the section can't be split, e.g., when reading a binary file, or there's only one thread to read it on; the caller
+/// then reads the section sequentially.
+pub(crate) fn read_entities_in_parallel(
+    iter: &mut CodePairPutBack,
+    options: &LoadOptions,
+) -> DxfResult<Option<Vec<Entity>>> {
+    if rayon::current_num_threads() < 2 {
+        // splitting only adds overhead
+        return Ok(None);
+    }
+
+    let preserve_unknown_items = iter.preserve_unknown_items();
+    let (results, section_length, section_lines) = {
+        let unread = match iter.unread_text() {
+            Some(unread) => unread,
+            None => return Ok(None),
+        };
+        let (chunks, section_length, section_lines) = split_entities(unread.text, unread.offset);
+        let results = chunks
+            .into_par_iter()
+            .map(|chunk| {
+                // the entity reader expects the section to be terminated
+                let mut text = unread.text[chunk.start..chunk.end].to_vec();
+                text.extend_from_slice(b"0\nENDSEC\n");
+                read_chunk(
+                    new_code_pair_iter_from_text(text, unread.string_encoding, chunk.offset),
+                    preserve_unknown_items,
+                    options,
+                )
+            })
+            .collect::<Vec<_>>();
+        (results, section_length, section_lines)
+    };
+
+    // report the same error a sequential read would have
+    let mut chunks = vec![];
+    for result in results {
+        match result? {
+            Some(chunk) => chunks.push(chunk),
+            None => return Ok(None),
+        }
+    }
+
+    let mut entities = vec![];
+    for (chunk_entities, warnings) in chunks {
+        entities.extend(chunk_entities);
+        iter.add_warnings(warnings);
+    }
+
+    iter.skip_text(section_length, section_lines);
+    Ok(Some(entities))
+}
+
+/// Finds the chunks of whole entities before the `ENDSEC` code pair, and the length in bytes and lines of the text
+/// they span.
+fn split_entities(text: &[u8], offset: usize) -> (Vec<Chunk>, usize, usize) {
+    let mut chunks = vec![];
+    let mut chunk_start = 0;
+    let mut chunk_offset = offset;
+    let mut entity_count = 0;
+    let mut position = 0;
+    let mut line_count = 0;
+    while let Some(code_line) = next_line(text, position) {
+        let value_line = match next_line(text, code_line.1) {
+            Some(line) => line,
+            None => break,
+        };
+        if trimmed(&text[code_line.0..code_line.1]) == b"0" {
+            if trimmed(&text[value_line.0..value_line.1]) == b"ENDSEC" {
+                break;
+            }
+
+            if entity_count == ENTITIES_PER_CHUNK {
+                chunks.push(Chunk {
+                    start: chunk_start,
+                    end: position,
+                    offset: chunk_offset,
+                });
+                chunk_start = position;
+                chunk_offset = offset + line_count;
+                entity_count = 0;
+            }
+
+            entity_count += 1;
+        }
+
+        position = value_line.1;
+        line_count += 2;
+    }
+
+    if position > chunk_start {
+        chunks.push(Chunk {
+            start: chunk_start,
+            end: position,
+            offset: chunk_offset,
+        });
+    }
+
+    (chunks, position, line_count)
+}
+
+/// Returns the start of the line at `start` and the start of the following line.
+fn next_line(text: &[u8], start: usize) -> Option<(usize, usize)> {
+    if start >= text.len() {
+        return None;
+    }
+
+    match text[start..].iter().position(|&b| b == b'\n') {
+        Some(length) => Some((start, start + length + 1)),
+        None => Some((start, text.len())),
+    }
+}
+
+fn trimmed(line: &[u8]) -> &[u8] {
+    let start = line
+        .iter()
+        .position(|b| !b.is_ascii_whitespace())
+        .unwrap_or(line.len());
+    let end = line
+        .iter()
+        .rposition(|b| !b.is_ascii_whitespace())
+        .map_or(start, |i| i + 1);
+    &line[start..end]
+}
+
+/// Reads all of the entities in a chunk.  Returns `None` if the chunk doesn't only contain entities.
+#[allow(clippy::type_complexity)]
+fn read_chunk(
+    iter: Box<dyn CodePairIter>,
+    preserve_unknown_items: bool,
+    options: &LoadOptions,
+) -> DxfResult<Option<(Vec<Entity>, Vec<LoadWarning>)>> {
+    let mut iter = CodePairPutBack::from_code_pair_iter(iter);
+    iter.set_preserve_unknown_items(preserve_unknown_items);
+    iter.set_lenient(options.lenient);
+    let mut entities = vec![];
+    {
+        let mut entity_iter = EntityIter { iter: &mut iter };
+        while let Some(entity) = entity_iter.next_of_type(|t| options.reads_entity_type(t)) {
+            entities.push(entity);
+        }
+    }
+
+    match iter.next() {
+        Some(Ok(ref pair)) if pair.code == 0 && pair.assert_string()? == "ENDSEC" => {
+            Ok(Some((entities, iter.take_warnings())))
+        }
+        Some(Err(e)) => Err(e),
+        // e.g., an `ENDBLK`; leave it to the sequential reader
+        _ => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::entities::*;
+    use crate::enums::*;
+    use crate::helper_functions::tests::*;
+    use crate::*;
+
+    fn load(text: &str, options: LoadOptions) -> DxfResult<(Drawing, Vec<LoadWarning>)> {
+        // the machine running the tests might only have one core
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(4)
+            .build()
+            .unwrap()
+            .install(|| Drawing::load_with_options(&mut text.as_bytes(), &options))
+    }
+
+    fn load_both_ways(text: &str, options: LoadOptions) -> (Drawing, Drawing) {
+        let (sequential, _) = load(text, options.clone()).unwrap();
+        let (parallel, _) = load(text, options.parallel_entities(true)).unwrap();
+        (sequential, parallel)
+    }
+
+    /// Enough entities for several chunks, with children that have to be combined.
+    fn large_drawing() -> Drawing {
+        let mut drawing = Drawing::new();
+        drawing.header.version = AcadVersion::R2000;
+        for i in 0..5000 {
+            let x = f64::from(i);
+            match i % 3 {
+                0 => {
+                    let mut poly = Polyline::default();
+                    poly.add_vertex(&mut drawing, Vertex::new(Point::new(x, 0.0, 0.0)));
+                    poly.add_vertex(&mut drawing, Vertex::new(Point::new(x, 1.0, 0.0)));
+                    drawing.add_entity(Entity::new(EntityType::Polyline(poly)));
+                }
+                1 => {
+                    let text = Text {
+                        value: format!("text {}", i),
+                        ..Default::default()
+                    };
+                    drawing.add_entity(Entity::new(EntityType::Text(text)));
+                }
+                _ => {
+                    let mut line = Entity::new(EntityType::Line(Line::new(
+                        Point::new(x, 0.0, 0.0),
+                        Point::new(x, 1.0, 0.0),
+                    )));
+                    line.common.layer = String::from(if i % 2 == 0 { "even" } else { "odd" });
+                    drawing.add_entity(line);
+                }
+            }
+        }
+        drawing
+    }
+
+    fn entities_text(entities: &str) -> String {
+        format!("0\nSECTION\n2\nENTITIES\n{}0\nENDSEC\n0\nEOF\n", entities)
+    }
+
+    #[test]
+    fn read_parallel_matches_sequential() {
+        let text = to_test_string(&large_drawing());
+        let (sequential, parallel) = load_both_ways(&text, LoadOptions::new());
+        assert_eq!(5000, parallel.entities().count());
+        assert_eq!(to_test_string(&sequential), to_test_string(&parallel));
+    }
+
+    #[test]
+    fn read_parallel_assigns_handles_in_file_order() {
+        let mut entities = String::new();
+        for i in 0..5000 {
+            entities.push_str(&format!("0\nLINE\n10\n{}.0\n", i));
+        }
+        let (sequential, parallel) = load_both_ways(&entities_text(&entities), LoadOptions::new());
+        let handles = |d: &Drawing| d.entities().map(|e| e.common.handle).collect::<Vec<_>>();
+        assert_eq!(handles(&sequential), handles(&parallel));
+        match parallel.entities().last().unwrap().specific {
+            EntityType::Line(ref line) => assert_eq!(4999.0, line.p1.x),
+            _ => panic!("expected a line"),
+        }
+    }
+
+    #[test]
+    fn read_parallel_reports_the_first_error() {
+        let mut entities = String::new();
+        for i in 0..5000 {
+            let x = if i == 3000 || i == 4000 {
+                "not-a-number".to_string()
+            } else {
+                format!("{}.0", i)
+            };
+            entities.push_str(&format!("0\nLINE\n10\n{}\n", x));
+        }
+        let text = entities_text(&entities);
+        let options = LoadOptions::new().parallel_entities(true);
+        match load(&text, options) {
+            // the value of the 3001st line is on line 4 + 4 * 3000 + 4
+            Err(DxfError::ParseFloatError(_, 12008)) => (),
+            Err(e) => panic!("expected a parse error, got {:?}", e),
+            Ok(_) => panic!("expected a parse error"),
+        }
+    }
+
+    #[test]
+    fn read_parallel_keeps_warnings_in_file_order() {
+        let mut entities = String::new();
+        for i in 0..5000 {
+            let x = if i == 10 || i == 3000 {
+                "not-a-number".to_string()
+            } else {
+                format!("{}.0", i)
+            };
+            entities.push_str(&format!("0\nLINE\n10\n{}\n", x));
+        }
+        let text = entities_text(&entities);
+        let (drawing, warnings) = load(
+            &text,
+            LoadOptions::new().lenient(true).parallel_entities(true),
+        )
+        .unwrap();
+        assert_eq!(5000, drawing.entities().count());
+        assert_eq!(
+            vec![48, 12008],
+            warnings.iter().map(|w| w.offset).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn read_parallel_applies_filters() {
+        let text = to_test_string(&large_drawing());
+        let options = LoadOptions::new()
+            .entity_types(["LINE"])
+            .entity_layers(["even"]);
+        let (sequential, parallel) = load_both_ways(&text, options);
+        assert_eq!(833, parallel.entities().count());
+        assert_eq!(to_test_string(&sequential), to_test_string(&parallel));
+    }
+
+    #[test]
+    fn read_parallel_falls_back_for_binary_files() {
+        let bytes = to_binary(&large_drawing());
+        let options = LoadOptions::new().parallel_entities(true);
+        let (sequential, _) =
+            Drawing::load_with_options(&mut bytes.as_slice(), &LoadOptions::new()).unwrap();
+        let (parallel, _) = rayon::ThreadPoolBuilder::new()
+            .num_threads(4)
+            .build()
+            .unwrap()
+            .install(|| Drawing::load_with_options(&mut bytes.as_slice(), &options))
+            .unwrap();
+        assert_eq!(to_test_string(&sequential), to_test_string(&parallel));
+    }
+
+    #[test]
+    fn read_parallel_empty_section() {
+        let (_, parallel) = load_both_ways(&entities_text(""), LoadOptions::new());
+        assert_eq!(0, parallel.entities().count());
+    }
+
+    #[test]
+    fn split_entities_at_entity_boundaries() {
+        let mut entities = String::new();
+        for i in 0..5000 {
+            entities.push_str(&format!("0\nLINE\n10\n{}.0\n", i));
+        }
+        let text = format!("{}0\nENDSEC\n0\nEOF\n", entities);
+        let (chunks, length, lines) = super::split_entities(text.as_bytes(), 5);
+        assert_eq!(
+            vec![(5, 0), (5 + 4 * 2048, 2048), (5 + 4 * 4096, 4096)],
+            chunks
+                .iter()
+                .map(|c| (c.offset, text[..c.start].matches("LINE").count()))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(entities.len(), length);
+        assert_eq!(4 * 5000, lines);
+        assert!(text[length..].starts_with("0\nENDSEC"));
+    }
+}
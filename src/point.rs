@@ -1,4 +1,6 @@
-use crate::{CodePair, DxfError, DxfResult};
+use std::ops::{AddAssign, SubAssign};
+
+use crate::{CodePair, DxfError, DxfResult, Vector};
 
 /// Represents a simple point in Cartesian space.
 #[derive(Clone, Debug, Default, PartialEq)]
@@ -21,6 +23,43 @@ impl Point {
     pub fn origin() -> Point {
         Point::new(0.0, 0.0, 0.0)
     }
+    /// Returns the vector from the origin to this point.
+    pub fn as_vector(&self) -> Vector {
+        Vector::new(self.x, self.y, self.z)
+    }
+    /// Returns the point at the end of the vector from the origin.
+    pub fn from_vector(v: &Vector) -> Point {
+        Point::new(v.x, v.y, v.z)
+    }
+    /// Returns the distance between this point and `other`.
+    pub fn distance_to(&self, other: &Point) -> f64 {
+        (other - self).length()
+    }
+    /// Returns the point halfway between this point and `other`.
+    pub fn midpoint(&self, other: &Point) -> Point {
+        self.lerp(other, 0.5)
+    }
+    /// Returns the point at the fraction `t` of the way from this point to `other`.
+    pub fn lerp(&self, other: &Point, t: f64) -> Point {
+        self + (other - self) * t
+    }
+    /// Converts this point from the object coordinate system (OCS) with the specified normal to world coordinates
+    /// (WCS), using the DXF arbitrary axis algorithm.  `Entity::ocs_to_wcs()` uses the entity's normal.
+    pub fn ocs_to_wcs(&self, normal: &Vector) -> Point {
+        let (x_axis, y_axis) = normal.arbitrary_axes();
+        let v = x_axis
+            .scaled(self.x)
+            .plus(&y_axis.scaled(self.y))
+            .plus(&normal.normalized().scaled(self.z));
+        Point::from_vector(&v)
+    }
+    /// Converts this point from world coordinates (WCS) to the object coordinate system (OCS) with the specified
+    /// normal, using the DXF arbitrary axis algorithm.  `Entity::wcs_to_ocs()` uses the entity's normal.
+    pub fn wcs_to_ocs(&self, normal: &Vector) -> Point {
+        let (x_axis, y_axis) = normal.arbitrary_axes();
+        let v = self.as_vector();
+        Point::new(v.dot(&x_axis), v.dot(&y_axis), v.dot(&normal.normalized()))
+    }
     pub(crate) fn set(&mut self, pair: &CodePair) -> DxfResult<()> {
         match pair.code {
             10 => self.x = pair.assert_f64()?,
@@ -37,3 +76,127 @@ impl Point {
         Ok(())
     }
 }
+
+impl_binary_op!(Add, add, Point, Vector, Point, |a, b| Point::new(
+    a.x + b.x,
+    a.y + b.y,
+    a.z + b.z
+));
+impl_binary_op!(Sub, sub, Point, Vector, Point, |a, b| Point::new(
+    a.x - b.x,
+    a.y - b.y,
+    a.z - b.z
+));
+impl_binary_op!(Sub, sub, Point, Point, Vector, |a, b| Vector::new(
+    a.x - b.x,
+    a.y - b.y,
+    a.z - b.z
+));
+impl_binary_op!(Mul, mul, Point, f64, Point, |a, b| Point::new(
+    a.x * b,
+    a.y * b,
+    a.z * b
+));
+impl_binary_op!(Div, div, Point, f64, Point, |a, b| Point::new(
+    a.x / b,
+    a.y / b,
+    a.z / b
+));
+
+impl AddAssign<&Vector> for Point {
+    fn add_assign(&mut self, v: &Vector) {
+        self.x += v.x;
+        self.y += v.y;
+        self.z += v.z;
+    }
+}
+
+impl AddAssign<Vector> for Point {
+    fn add_assign(&mut self, v: Vector) {
+        *self += &v;
+    }
+}
+
+impl SubAssign<&Vector> for Point {
+    fn sub_assign(&mut self, v: &Vector) {
+        self.x -= v.x;
+        self.y -= v.y;
+        self.z -= v.z;
+    }
+}
+
+impl SubAssign<Vector> for Point {
+    fn sub_assign(&mut self, v: Vector) {
+        *self -= &v;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    fn assert_close(expected: &Point, actual: &Point) {
+        assert!(
+            expected.distance_to(actual) < 1e-12,
+            "expected {:?}, got {:?}",
+            expected,
+            actual
+        );
+    }
+
+    #[test]
+    fn point_operators() {
+        let p = Point::new(1.0, 2.0, 3.0);
+        let v = Vector::new(1.0, 1.0, 1.0);
+        assert_eq!(Point::new(2.0, 3.0, 4.0), &p + &v);
+        assert_eq!(Point::new(0.0, 1.0, 2.0), p.clone() - v.clone());
+        assert_eq!(Vector::new(1.0, 2.0, 3.0), &p - &Point::origin());
+        assert_eq!(Point::new(2.0, 4.0, 6.0), &p * 2.0);
+        assert_eq!(Point::new(0.5, 1.0, 1.5), &p / 2.0);
+
+        let mut q = p.clone();
+        q += &v;
+        q -= Vector::new(0.0, 0.0, 3.0);
+        assert_eq!(Point::new(2.0, 3.0, 1.0), q);
+    }
+
+    #[test]
+    fn point_distance_and_interpolation() {
+        let a = Point::new(1.0, 1.0, 1.0);
+        let b = Point::new(4.0, 5.0, 1.0);
+        assert_eq!(5.0, a.distance_to(&b));
+        assert_eq!(Point::new(2.5, 3.0, 1.0), a.midpoint(&b));
+        assert_eq!(a, a.lerp(&b, 0.0));
+        assert_eq!(b, a.lerp(&b, 1.0));
+        assert_eq!(Point::new(7.0, 9.0, 1.0), a.lerp(&b, 2.0));
+    }
+
+    #[test]
+    fn ocs_to_wcs_with_negative_z_normal() {
+        // the OCS X axis points in the -X direction
+        let normal = Vector::new(0.0, 0.0, -1.0);
+        let ocs = Point::new(1.0, 2.0, 3.0);
+        assert_close(&Point::new(-1.0, 2.0, -3.0), &ocs.ocs_to_wcs(&normal));
+    }
+
+    #[test]
+    fn ocs_to_wcs_with_tilted_normal() {
+        let normal = Vector::new(1.0, 0.0, 0.0);
+        assert_close(
+            &Point::new(3.0, 1.0, 2.0),
+            &Point::new(1.0, 2.0, 3.0).ocs_to_wcs(&normal),
+        );
+    }
+
+    #[test]
+    fn ocs_round_trip() {
+        let normal = Vector::new(1.0, 1.0, 1.0);
+        let wcs = Point::new(3.0, -4.0, 5.0);
+        let ocs = wcs.wcs_to_ocs(&normal);
+        assert_close(&wcs, &ocs.ocs_to_wcs(&normal));
+
+        // the distance along the normal is the OCS elevation
+        let elevation = wcs.as_vector().dot(&normal.normalized());
+        assert!((elevation - ocs.z).abs() < 1e-12);
+    }
+}
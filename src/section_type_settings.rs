@@ -29,6 +29,10 @@ impl Default for SectionTypeSettings {
 
 // internal visibility only
 impl SectionTypeSettings {
+    pub(crate) fn add_pointers(&self, handles: &mut Vec<Handle>) {
+        handles.extend_from_slice(&self.source_object_handles);
+        handles.push(self.destination_object_handle);
+    }
     pub(crate) fn read(iter: &mut CodePairPutBack) -> DxfResult<Option<SectionTypeSettings>> {
         // check the first pair and only continue if it's not 0
         match iter.next() {
@@ -0,0 +1,860 @@
+// `Spline` evaluation and construction; other implementation is in `generated/entities.rs`
+
+use crate::{Point, Vector};
+
+use crate::entities::*;
+
+/// The nodes and weights of 5-point Gauss-Legendre quadrature on [-1, 1].
+const GAUSS_LEGENDRE: [(f64, f64); 5] = [
+    (0.0, 0.568_888_888_888_888_9),
+    (-0.538_469_310_105_683_1, 0.478_628_670_499_366_5),
+    (0.538_469_310_105_683_1, 0.478_628_670_499_366_5),
+    (-0.906_179_845_938_664, 0.236_926_885_056_189_1),
+    (0.906_179_845_938_664, 0.236_926_885_056_189_1),
+];
+
+/// How many times a piece of a knot span may be halved while integrating the arc length.
+const MAX_INTEGRATION_DEPTH: usize = 16;
+
+/// The relative error at which the arc length of a piece of a knot span is accepted.
+const ARC_LENGTH_TOLERANCE: f64 = 1e-12;
+
+// public implementation
+impl Spline {
+    /// Creates a non-rational spline of the specified degree with clamped, uniform knots, so the curve starts at the
+    /// first control point and ends at the last.  The degree is reduced if there aren't enough control points for it.
+    /// The closed, planar, and linear flags and the normal are set from the control points.
+    pub fn from_control_points(degree: usize, control_points: Vec<Point>) -> Self {
+        let degree = degree.min(control_points.len().saturating_sub(1)).max(1);
+        let last = control_points.len().saturating_sub(degree) as f64;
+        let knot_values = (0..control_points.len() + degree + 1)
+            .map(|i| (i as f64 - degree as f64).clamp(0.0, last))
+            .collect();
+        let mut spline = Spline {
+            degree_of_curve: degree as i32,
+            control_points,
+            knot_values,
+            ..Default::default()
+        };
+        spline.update_flags();
+        spline
+    }
+    /// Creates a rational spline like `from_control_points()` with the specified weight for each control point.  The
+    /// rational flag is set if any weight isn't 1.
+    pub fn from_weighted_control_points(
+        degree: usize,
+        control_points: Vec<Point>,
+        weight_values: Vec<f64>,
+    ) -> Self {
+        let mut spline = Spline::from_control_points(degree, control_points);
+        spline.weight_values = weight_values;
+        spline.update_flags();
+        spline
+    }
+    /// Creates a cubic spline through the fit points, or `None` if there are fewer than two fit points or two
+    /// consecutive fit points are the same.  The tangents are directions at the first and last fit points; they're
+    /// estimated from the neighboring fit points if not specified.  See `interpolate_fit_points()`.
+    pub fn from_fit_points(
+        fit_points: Vec<Point>,
+        start_tangent: Option<Vector>,
+        end_tangent: Option<Vector>,
+    ) -> Option<Self> {
+        let tangent = |t: Option<Vector>| t.map_or_else(Point::origin, |t| Point::from_vector(&t));
+        let mut spline = Spline {
+            fit_points,
+            start_tangent: tangent(start_tangent),
+            end_tangent: tangent(end_tangent),
+            ..Default::default()
+        };
+        if spline.interpolate_fit_points() {
+            Some(spline)
+        } else {
+            None
+        }
+    }
+    /// Returns the range of parameters the curve is defined over, or `None` if the knot values don't match the degree
+    /// and control points.
+    pub fn parameter_range(&self) -> Option<(f64, f64)> {
+        let spans = self.knot_spans()?;
+        Some((spans[0].1, spans[spans.len() - 1].2))
+    }
+    /// Returns the point on the curve at parameter `t`, or `None` if the knot values don't match the degree and
+    /// control points.  `t` is clamped to `parameter_range()`.
+    pub fn point_at(&self, t: f64) -> Option<Point> {
+        let (span, t) = self.span_at(t)?;
+        Some(self.point_in_span(span, t))
+    }
+    /// Returns the derivative of the specified order of the curve with respect to its parameter at `t`, or `None` if
+    /// the knot values don't match the degree and control points.  `t` is clamped to `parameter_range()` and the
+    /// derivative at a knot is taken from the following knot span.
+    pub fn derivative_at(&self, t: f64, order: usize) -> Option<Vector> {
+        let (span, t) = self.span_at(t)?;
+        let mut derivatives = self.derivatives_in_span(span, t, order);
+        Some(derivatives.swap_remove(order))
+    }
+    /// Returns the length of the curve, or `None` if the knot values don't match the degree and control points.
+    pub fn arc_length(&self) -> Option<f64> {
+        let (start, end) = self.parameter_range()?;
+        self.arc_length_between(start, end)
+    }
+    /// Returns the length of the curve between parameters `start` and `end`, clamped to `parameter_range()`, or
+    /// `None` if the knot values don't match the degree and control points.
+    pub fn arc_length_between(&self, start: f64, end: f64) -> Option<f64> {
+        let (start, end) = if start <= end {
+            (start, end)
+        } else {
+            (end, start)
+        };
+        let mut length = 0.0;
+        for (span, span_start, span_end) in self.knot_spans()? {
+            let (a, b) = (start.max(span_start), end.min(span_end));
+            if a < b {
+                let whole = self.integrate_speed(span, a, b);
+                length += self.arc_length_in_span(span, a, b, whole, 0);
+            }
+        }
+        Some(length)
+    }
+    /// Inserts a knot at parameter `t` without changing the shape of the curve, adding a control point, and a weight
+    /// if the spline has weights.  Returns `false` and leaves the spline unchanged if the knot values don't match the
+    /// degree and control points, `t` isn't strictly inside `parameter_range()`, or the knot would occur more times
+    /// than the degree.
+    pub fn insert_knot(&mut self, t: f64) -> bool {
+        let (start, end) = match self.parameter_range() {
+            Some(range) => range,
+            None => return false,
+        };
+        let degree = self.degree();
+        let multiplicity = self.knot_values.iter().filter(|&&k| k == t).count();
+        if t <= start || t >= end || multiplicity >= degree {
+            return false;
+        }
+        let span = match self.span_at(t) {
+            Some((span, _)) => span,
+            None => return false,
+        };
+
+        // Boehm's algorithm on the control points in homogeneous coordinates
+        let has_weights = self.has_weights();
+        let homogeneous = (0..self.control_points.len())
+            .map(|i| self.homogeneous_point(i))
+            .collect::<Vec<_>>();
+        let mut points = Vec::with_capacity(homogeneous.len() + 1);
+        for i in 0..=homogeneous.len() {
+            let point = if i + degree <= span {
+                homogeneous[i]
+            } else if i > span - multiplicity {
+                homogeneous[i - 1]
+            } else {
+                let knots = &self.knot_values;
+                let alpha = (t - knots[i]) / (knots[i + degree] - knots[i]);
+                let (previous, current) = (homogeneous[i - 1], homogeneous[i]);
+                [
+                    previous[0] + (current[0] - previous[0]) * alpha,
+                    previous[1] + (current[1] - previous[1]) * alpha,
+                    previous[2] + (current[2] - previous[2]) * alpha,
+                    previous[3] + (current[3] - previous[3]) * alpha,
+                ]
+            };
+            points.push(point);
+        }
+
+        self.knot_values.insert(span + 1, t);
+        self.control_points = points
+            .iter()
+            .map(|p| Point::new(p[0] / p[3], p[1] / p[3], p[2] / p[3]))
+            .collect();
+        if has_weights {
+            self.weight_values = points.iter().map(|p| p[3]).collect();
+        }
+        true
+    }
+    /// Replaces the control points, knots, and weights with those of a cubic spline that passes through the fit
+    /// points, using the chord length between the fit points as the parameter.  The start and end tangents give the
+    /// direction of the curve at the first and last fit points; they're estimated from the neighboring fit points
+    /// if they're zero.  Returns `false` and leaves the spline unchanged if there are fewer than two fit points or two
+    /// consecutive fit points are the same.
+    pub fn interpolate_fit_points(&mut self) -> bool {
+        let fit_points = &self.fit_points;
+        let count = fit_points.len();
+        if count < 2 {
+            return false;
+        }
+        let mut parameters = vec![0.0];
+        for pair in fit_points.windows(2) {
+            let distance = pair[0].distance_to(&pair[1]);
+            if distance == 0.0 {
+                return false;
+            }
+            parameters.push(parameters[parameters.len() - 1] + distance);
+        }
+
+        // the parameter is close to the arc length, so the tangents have about unit length
+        let last = count - 1;
+        let start_tangent = tangent_or_estimate(&self.start_tangent, || {
+            end_derivative(fit_points, &parameters, [0, 1, 2])
+        });
+        let end_tangent = tangent_or_estimate(&self.end_tangent, || {
+            end_derivative(
+                fit_points,
+                &parameters,
+                [last, last - 1, last.saturating_sub(2)],
+            )
+        });
+
+        let length = parameters[last];
+        let mut knots = vec![0.0; 4];
+        knots.extend_from_slice(&parameters[1..last]);
+        knots.extend_from_slice(&[length; 4]);
+
+        // the ends are fixed by the first and last fit points and tangents; the rest come from interpolating the
+        // interior fit points, which only involves three consecutive control points each
+        let second = &fit_points[0] + &start_tangent * (knots[4] / 3.0);
+        let second_to_last = &fit_points[last] - &end_tangent * ((length - knots[count + 1]) / 3.0);
+        let mut rows = vec![];
+        for (k, fit_point) in fit_points.iter().enumerate().take(last).skip(1) {
+            let basis = basis_function_derivatives(&knots, 3, k + 3, parameters[k], 0);
+            rows.push((
+                [basis[0][0], basis[0][1], basis[0][2]],
+                fit_point.as_vector(),
+            ));
+        }
+        if let Some(first) = rows.first_mut() {
+            first.1 -= second.as_vector() * first.0[0];
+            first.0[0] = 0.0;
+        }
+        if let Some(last) = rows.last_mut() {
+            last.1 -= second_to_last.as_vector() * last.0[2];
+            last.0[2] = 0.0;
+        }
+
+        let mut control_points = vec![fit_points[0].clone(), second];
+        control_points.extend(solve_tridiagonal(rows).iter().map(Point::from_vector));
+        control_points.push(second_to_last);
+        control_points.push(fit_points[last].clone());
+
+        self.degree_of_curve = 3;
+        self.knot_values = knots;
+        self.control_points = control_points;
+        self.weight_values.clear();
+        self.update_flags();
+        true
+    }
+}
+
+// internal visibility only
+impl Spline {
+    /// Returns the degree of the curve used for evaluation, which is at least 1.
+    pub(crate) fn degree(&self) -> usize {
+        self.degree_of_curve.max(1) as usize
+    }
+    /// Returns the non-empty knot spans of the curve as `(span, start, end)`, or `None` if the knot values don't match
+    /// the degree and control points or there are no non-empty spans.
+    pub(crate) fn knot_spans(&self) -> Option<Vec<(usize, f64, f64)>> {
+        let degree = self.degree();
+        let count = self.control_points.len();
+        let knots = &self.knot_values;
+        if count <= degree || knots.len() != count + degree + 1 {
+            return None;
+        }
+
+        let spans = (degree..count)
+            .map(|span| (span, knots[span], knots[span + 1]))
+            .filter(|(_, start, end)| end > start)
+            .collect::<Vec<_>>();
+        if spans.is_empty() {
+            None
+        } else {
+            Some(spans)
+        }
+    }
+    /// Returns points along the curve with `segments_per_span` segments for each non-empty knot span, or `None` if
+    /// the knot values don't match the degree and control points.
+    pub(crate) fn sample_points(&self, segments_per_span: usize) -> Option<Vec<Point>> {
+        let mut points = vec![];
+        for (span, start, end) in self.knot_spans()? {
+            // adjacent spans share their end points
+            let first = if points.is_empty() { 0 } else { 1 };
+            for i in first..=segments_per_span {
+                let t = start + (end - start) * i as f64 / segments_per_span as f64;
+                points.push(self.point_in_span(span, t));
+            }
+        }
+
+        Some(points)
+    }
+    /// Evaluates the curve at `t`, which must be in the knot span starting at `span`.
+    pub(crate) fn point_in_span(&self, span: usize, t: f64) -> Point {
+        Point::from_vector(&self.derivatives_in_span(span, t, 0)[0])
+    }
+}
+
+// private implementation
+impl Spline {
+    /// Returns whether there's a weight for each control point.
+    fn has_weights(&self) -> bool {
+        !self.weight_values.is_empty() && self.weight_values.len() == self.control_points.len()
+    }
+    /// Returns the control point at `index` multiplied by its weight, followed by the weight.
+    fn homogeneous_point(&self, index: usize) -> [f64; 4] {
+        let point = &self.control_points[index];
+        let weight = if self.has_weights() {
+            self.weight_values[index]
+        } else {
+            1.0
+        };
+        [point.x * weight, point.y * weight, point.z * weight, weight]
+    }
+    /// Returns the knot span containing `t`, clamped to the parameter range, and the clamped parameter.
+    fn span_at(&self, t: f64) -> Option<(usize, f64)> {
+        let spans = self.knot_spans()?;
+        let (first, last) = (&spans[0], &spans[spans.len() - 1]);
+        let t = t.max(first.1).min(last.2);
+        let span = spans.iter().find(|(_, _, end)| t < *end).unwrap_or(last).0;
+        Some((span, t))
+    }
+    /// Returns the point at `t` as a vector followed by the derivatives up to `order`.
+    fn derivatives_in_span(&self, span: usize, t: f64, order: usize) -> Vec<Vector> {
+        let degree = self.degree();
+        let basis = basis_function_derivatives(&self.knot_values, degree, span, t, order);
+
+        // the derivatives of the curve in homogeneous coordinates
+        let homogeneous = basis
+            .iter()
+            .map(|functions| {
+                let mut sum = [0.0; 4];
+                for (j, function) in functions.iter().enumerate() {
+                    let point = self.homogeneous_point(span - degree + j);
+                    for (s, p) in sum.iter_mut().zip(point.iter()) {
+                        *s += function * p;
+                    }
+                }
+                sum
+            })
+            .collect::<Vec<_>>();
+
+        // the quotient rule, applied repeatedly
+        let weight = homogeneous[0][3];
+        let mut derivatives: Vec<Vector> = vec![];
+        for k in 0..=order {
+            let h = &homogeneous[k];
+            let mut derivative = Vector::new(h[0], h[1], h[2]);
+            for i in 1..=k {
+                derivative -= &derivatives[k - i] * (binomial(k, i) * homogeneous[i][3]);
+            }
+            derivatives.push(derivative / weight);
+        }
+        derivatives
+    }
+    /// Integrates the speed of the curve over the knot span starting at `span` from `start` to `end`.
+    fn integrate_speed(&self, span: usize, start: f64, end: f64) -> f64 {
+        let (middle, half) = ((start + end) / 2.0, (end - start) / 2.0);
+        GAUSS_LEGENDRE
+            .iter()
+            .map(|(node, weight)| {
+                let t = middle + half * node;
+                weight * self.derivatives_in_span(span, t, 1)[1].length()
+            })
+            .sum::<f64>()
+            * half
+    }
+    /// Returns the arc length from `start` to `end`, halving the range until the estimate `whole` agrees with the sum
+    /// of its halves.
+    fn arc_length_in_span(
+        &self,
+        span: usize,
+        start: f64,
+        end: f64,
+        whole: f64,
+        depth: usize,
+    ) -> f64 {
+        let middle = (start + end) / 2.0;
+        let first = self.integrate_speed(span, start, middle);
+        let second = self.integrate_speed(span, middle, end);
+        let halves = first + second;
+        if depth >= MAX_INTEGRATION_DEPTH
+            || (halves - whole).abs() <= ARC_LENGTH_TOLERANCE * halves.max(1.0)
+        {
+            halves
+        } else {
+            self.arc_length_in_span(span, start, middle, first, depth + 1)
+                + self.arc_length_in_span(span, middle, end, second, depth + 1)
+        }
+    }
+    /// Sets the rational, closed, planar, and linear flags and the normal from the control points and weights.
+    fn update_flags(&mut self) {
+        let is_rational = self.has_weights() && self.weight_values.iter().any(|&w| w != 1.0);
+        self.set_is_rational(is_rational);
+        let tolerance = self.control_point_tolerance;
+        let points = &self.control_points;
+        let is_closed =
+            points.len() > 2 && points[0].distance_to(&points[points.len() - 1]) <= tolerance;
+        let (is_linear, normal) = plane_of(points, tolerance);
+        self.set_is_closed(is_closed);
+        self.set_is_linear(is_linear);
+        self.set_is_planar(is_linear || normal.is_some());
+        if let Some(normal) = normal {
+            // prefer the normal that faces up
+            let faces_up = normal.z > 0.0
+                || (normal.z == 0.0 && (normal.y > 0.0 || (normal.y == 0.0 && normal.x > 0.0)));
+            self.normal = if faces_up { normal } else { -normal };
+        }
+    }
+}
+
+/// Returns whether the points are on a line, and if not, the normal of the plane they're on, if any.
+fn plane_of(points: &[Point], tolerance: f64) -> (bool, Option<Vector>) {
+    let first = match points.first() {
+        Some(first) => first,
+        None => return (true, None),
+    };
+    // the point furthest from the first and the point furthest from the line through both span the plane
+    let farthest = points
+        .iter()
+        .max_by(|a, b| first.distance_to(a).total_cmp(&first.distance_to(b)))
+        .expect("there's a first point");
+    if first.distance_to(farthest) <= tolerance {
+        return (true, None);
+    }
+    let direction = (farthest - first).normalized();
+    let normal = points
+        .iter()
+        .map(|p| direction.cross(&(p - first)))
+        .max_by(|a, b| a.length().total_cmp(&b.length()))
+        .expect("there's a first point");
+    if normal.length() <= tolerance {
+        return (true, None);
+    }
+    let normal = normal.normalized();
+    let is_planar = points
+        .iter()
+        .all(|p| (p - first).dot(&normal).abs() <= tolerance);
+    (false, if is_planar { Some(normal) } else { None })
+}
+
+/// Returns the values of the `degree + 1` basis functions that are non-zero in the knot span starting at `span`,
+/// evaluated at `t`, and of their derivatives up to `order`, as `[derivative][function]`.
+fn basis_function_derivatives(
+    knots: &[f64],
+    degree: usize,
+    span: usize,
+    t: f64,
+    order: usize,
+) -> Vec<Vec<f64>> {
+    // the basis functions of each degree and the differences of the knots, as in "The NURBS Book", algorithm A2.3
+    let mut ndu = vec![vec![0.0; degree + 1]; degree + 1];
+    let mut left = vec![0.0; degree + 1];
+    let mut right = vec![0.0; degree + 1];
+    ndu[0][0] = 1.0;
+    for j in 1..=degree {
+        left[j] = t - knots[span + 1 - j];
+        right[j] = knots[span + j] - t;
+        let mut saved = 0.0;
+        for r in 0..j {
+            ndu[j][r] = right[r + 1] + left[j - r];
+            let temp = ndu[r][j - 1] / ndu[j][r];
+            ndu[r][j] = saved + right[r + 1] * temp;
+            saved = left[j - r] * temp;
+        }
+        ndu[j][j] = saved;
+    }
+
+    let mut derivatives = vec![vec![0.0; degree + 1]; order + 1];
+    for (j, value) in derivatives[0].iter_mut().enumerate() {
+        *value = ndu[j][degree];
+    }
+    // derivatives of higher order than the degree are zero
+    let computed = order.min(degree);
+    let p = degree as isize;
+    for r in 0..=p {
+        let mut a = [vec![0.0; degree + 1], vec![0.0; degree + 1]];
+        let (mut s1, mut s2) = (0, 1);
+        a[0][0] = 1.0;
+        for k in 1..=computed as isize {
+            let mut d = 0.0;
+            let (rk, pk) = (r - k, (p - k) as usize);
+            if r >= k {
+                a[s2][0] = a[s1][0] / ndu[pk + 1][rk as usize];
+                d = a[s2][0] * ndu[rk as usize][pk];
+            }
+            let j1 = if rk >= -1 { 1 } else { -rk };
+            let j2 = if r - 1 <= pk as isize { k - 1 } else { p - r };
+            for j in j1..=j2 {
+                let (j, index) = (j as usize, (rk + j) as usize);
+                a[s2][j] = (a[s1][j] - a[s1][j - 1]) / ndu[pk + 1][index];
+                d += a[s2][j] * ndu[index][pk];
+            }
+            if r <= pk as isize {
+                a[s2][k as usize] = -a[s1][k as usize - 1] / ndu[pk + 1][r as usize];
+                d += a[s2][k as usize] * ndu[r as usize][pk];
+            }
+            derivatives[k as usize][r as usize] = d;
+            std::mem::swap(&mut s1, &mut s2);
+        }
+    }
+
+    // multiply by degree! / (degree - k)!
+    let mut factor = degree as f64;
+    for (k, functions) in derivatives
+        .iter_mut()
+        .enumerate()
+        .take(computed + 1)
+        .skip(1)
+    {
+        for value in functions {
+            *value *= factor;
+        }
+        factor *= (degree - k) as f64;
+    }
+    derivatives
+}
+
+fn binomial(n: usize, k: usize) -> f64 {
+    (0..k).fold(1.0, |result, i| result * (n - i) as f64 / (i + 1) as f64)
+}
+
+/// Returns the direction of `tangent`, or of the estimate if `tangent` is zero.
+fn tangent_or_estimate<F: FnOnce() -> Vector>(tangent: &Point, estimate: F) -> Vector {
+    let tangent = tangent.as_vector();
+    if tangent.is_zero() {
+        estimate().normalized()
+    } else {
+        tangent.normalized()
+    }
+}
+
+/// Returns the derivative at the fit point `indices[0]` of the parabola through it and the next two fit points, or
+/// the direction to the next fit point if there are only two.
+fn end_derivative(points: &[Point], parameters: &[f64], indices: [usize; 3]) -> Vector {
+    let [i, j, k] = indices;
+    let first = (&points[j] - &points[i]) / (parameters[j] - parameters[i]);
+    if points.len() < 3 {
+        return first;
+    }
+    let second = (&points[k] - &points[j]) / (parameters[k] - parameters[j]);
+    let curvature = (&second - &first) / (parameters[k] - parameters[i]);
+    first + curvature * (parameters[i] - parameters[j])
+}
+
+/// Solves the system where each row has coefficients for the previous, current, and next unknowns, and the value the
+/// row sums to.
+fn solve_tridiagonal(mut rows: Vec<([f64; 3], Vector)>) -> Vec<Vector> {
+    // the rows of a B-spline interpolation don't need pivoting
+    for i in 1..rows.len() {
+        let factor = rows[i].0[0] / rows[i - 1].0[1];
+        let (previous_next, previous_value) = (rows[i - 1].0[2], rows[i - 1].1.clone());
+        let row = &mut rows[i];
+        row.0[1] -= factor * previous_next;
+        row.1 -= previous_value * factor;
+    }
+    let mut result = vec![Vector::zero(); rows.len()];
+    for i in (0..rows.len()).rev() {
+        let (coefficients, value) = &rows[i];
+        let next = if i + 1 < rows.len() {
+            &result[i + 1] * coefficients[2]
+        } else {
+            Vector::zero()
+        };
+        result[i] = (value - &next) / coefficients[1];
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::entities::*;
+    use crate::enums::*;
+    use crate::helper_functions::tests::*;
+    use crate::*;
+    use std::f64::consts::{FRAC_1_SQRT_2, PI};
+
+    fn assert_close(expected: &Point, actual: &Point) {
+        assert!(
+            expected.distance_to(actual) < 1e-9,
+            "expected {:?}, got {:?}",
+            expected,
+            actual
+        );
+    }
+
+    fn assert_vector_close(expected: &Vector, actual: &Vector) {
+        assert!(
+            (expected - actual).length() < 1e-6,
+            "expected {:?}, got {:?}",
+            expected,
+            actual
+        );
+    }
+
+    /// A quarter of the unit circle in the XY plane, as a rational quadratic curve.
+    fn quarter_circle() -> Spline {
+        Spline::from_weighted_control_points(
+            2,
+            vec![
+                Point::new(1.0, 0.0, 0.0),
+                Point::new(1.0, 1.0, 0.0),
+                Point::new(0.0, 1.0, 0.0),
+            ],
+            vec![1.0, FRAC_1_SQRT_2, 1.0],
+        )
+    }
+
+    fn cubic() -> Spline {
+        Spline::from_control_points(
+            3,
+            vec![
+                Point::new(0.0, 0.0, 0.0),
+                Point::new(1.0, 2.0, 0.0),
+                Point::new(2.0, -1.0, 1.0),
+                Point::new(4.0, 1.0, 0.0),
+                Point::new(5.0, 0.0, 2.0),
+            ],
+        )
+    }
+
+    #[test]
+    fn from_control_points_fills_knots_and_flags() {
+        let spline = cubic();
+        assert_eq!(3, spline.degree_of_curve);
+        assert_eq!(
+            vec![0.0, 0.0, 0.0, 0.0, 1.0, 2.0, 2.0, 2.0, 2.0],
+            spline.knot_values
+        );
+        assert!(!spline.is_rational());
+        assert!(!spline.is_planar());
+        assert_eq!(Some((0.0, 2.0)), spline.parameter_range());
+
+        // not enough control points for a cubic
+        let line = Spline::from_control_points(3, vec![Point::origin(), Point::new(3.0, 4.0, 0.0)]);
+        assert_eq!(1, line.degree_of_curve);
+        assert_eq!(vec![0.0, 0.0, 1.0, 1.0], line.knot_values);
+        assert!(line.is_linear());
+        assert!(line.is_planar());
+    }
+
+    #[test]
+    fn from_weighted_control_points_sets_flags() {
+        let spline = quarter_circle();
+        assert!(spline.is_rational());
+        assert!(spline.is_planar());
+        assert!(!spline.is_linear());
+        assert!(!spline.is_closed());
+        assert_eq!(Vector::z_axis(), spline.normal);
+    }
+
+    #[test]
+    fn point_at_uses_weights() {
+        let spline = quarter_circle();
+        assert_close(&Point::new(1.0, 0.0, 0.0), &spline.point_at(0.0).unwrap());
+        assert_close(
+            &Point::new(FRAC_1_SQRT_2, FRAC_1_SQRT_2, 0.0),
+            &spline.point_at(0.5).unwrap(),
+        );
+        assert_close(&Point::new(0.0, 1.0, 0.0), &spline.point_at(1.0).unwrap());
+        for i in 0..=10 {
+            let point = spline.point_at(f64::from(i) / 10.0).unwrap();
+            assert!((point.distance_to(&Point::origin()) - 1.0).abs() < 1e-12);
+        }
+        // clamped to the parameter range
+        assert_close(&Point::new(0.0, 1.0, 0.0), &spline.point_at(2.0).unwrap());
+    }
+
+    #[test]
+    fn point_at_invalid_knots() {
+        let spline = Spline {
+            control_points: vec![Point::origin(), Point::new(1.0, 0.0, 0.0)],
+            knot_values: vec![0.0, 1.0],
+            ..Default::default()
+        };
+        assert_eq!(None, spline.point_at(0.5));
+        assert_eq!(None, spline.arc_length());
+    }
+
+    #[test]
+    fn derivative_at_rational_curve() {
+        let spline = quarter_circle();
+        assert_vector_close(
+            &Vector::new(0.0, 2.0 * FRAC_1_SQRT_2, 0.0),
+            &spline.derivative_at(0.0, 1).unwrap(),
+        );
+        // the velocity is tangent to the circle
+        let point = spline.point_at(0.3).unwrap();
+        let velocity = spline.derivative_at(0.3, 1).unwrap();
+        assert!(point.as_vector().dot(&velocity).abs() < 1e-12);
+        assert_eq!(
+            spline.point_at(0.3).unwrap().as_vector(),
+            spline.derivative_at(0.3, 0).unwrap()
+        );
+    }
+
+    #[test]
+    fn derivative_at_matches_finite_differences() {
+        let spline = cubic();
+        let h = 1e-5;
+        for &t in &[0.2, 0.7, 1.5] {
+            let before = spline.point_at(t - h).unwrap();
+            let at = spline.point_at(t).unwrap();
+            let after = spline.point_at(t + h).unwrap();
+            let first = (&after - &before) / (2.0 * h);
+            let second = (&after - &at - (&at - &before)) / (h * h);
+            assert_vector_close(&first, &spline.derivative_at(t, 1).unwrap());
+            assert!((second - spline.derivative_at(t, 2).unwrap()).length() < 1e-3);
+        }
+        // past the degree
+        assert_eq!(Vector::zero(), spline.derivative_at(0.5, 4).unwrap());
+    }
+
+    #[test]
+    fn arc_length_of_quarter_circle() {
+        let spline = quarter_circle();
+        assert!((spline.arc_length().unwrap() - PI / 2.0).abs() < 1e-10);
+        let half = spline.arc_length_between(0.0, 0.5).unwrap();
+        assert!((half - PI / 4.0).abs() < 1e-10);
+        assert_eq!(half, spline.arc_length_between(0.5, 0.0).unwrap());
+    }
+
+    #[test]
+    fn arc_length_of_line() {
+        let spline = Spline::from_control_points(
+            2,
+            vec![
+                Point::origin(),
+                Point::new(1.5, 2.0, 0.0),
+                Point::new(3.0, 4.0, 0.0),
+            ],
+        );
+        assert!((spline.arc_length().unwrap() - 5.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn insert_knot_keeps_shape() {
+        for original in &[quarter_circle(), cubic()] {
+            let mut spline = original.clone();
+            assert!(spline.insert_knot(0.3));
+            assert!(spline.insert_knot(0.3));
+            assert_eq!(
+                original.control_points.len() + 2,
+                spline.control_points.len()
+            );
+            assert_eq!(original.knot_values.len() + 2, spline.knot_values.len());
+            assert_eq!(
+                original.weight_values.len() + if original.is_rational() { 2 } else { 0 },
+                spline.weight_values.len()
+            );
+            for i in 0..=20 {
+                let t = f64::from(i) / 10.0;
+                assert_close(&original.point_at(t).unwrap(), &spline.point_at(t).unwrap());
+            }
+        }
+    }
+
+    #[test]
+    fn insert_knot_rejects_invalid_knots() {
+        let mut spline = quarter_circle();
+        assert!(!spline.insert_knot(0.0));
+        assert!(!spline.insert_knot(1.5));
+        assert!(spline.insert_knot(0.5));
+        assert!(spline.insert_knot(0.5));
+        // the multiplicity would exceed the degree
+        assert!(!spline.insert_knot(0.5));
+        assert_eq!(5, spline.control_points.len());
+    }
+
+    #[test]
+    fn from_fit_points_passes_through_fit_points() {
+        let fit_points = vec![
+            Point::new(0.0, 0.0, 0.0),
+            Point::new(1.0, 1.0, 0.0),
+            Point::new(3.0, 1.0, 0.0),
+            Point::new(4.0, 3.0, 0.0),
+            Point::new(6.0, 2.0, 0.0),
+        ];
+        let spline = Spline::from_fit_points(
+            fit_points.clone(),
+            Some(Vector::new(0.0, 2.0, 0.0)),
+            Some(Vector::x_axis()),
+        )
+        .unwrap();
+        assert_eq!(3, spline.degree_of_curve);
+        assert_eq!(7, spline.control_points.len());
+        assert_eq!(fit_points, spline.fit_points);
+        assert_eq!(Point::new(0.0, 2.0, 0.0), spline.start_tangent);
+        assert!(spline.is_planar());
+
+        // the fit points are at the chord length parameters, which are the interior knots
+        let mut parameter = 0.0;
+        for (i, fit_point) in fit_points.iter().enumerate() {
+            if i > 0 {
+                parameter += fit_points[i - 1].distance_to(fit_point);
+            }
+            assert_close(fit_point, &spline.point_at(parameter).unwrap());
+        }
+
+        let (start, end) = spline.parameter_range().unwrap();
+        assert_vector_close(&Vector::y_axis(), &spline.derivative_at(start, 1).unwrap());
+        assert_vector_close(&Vector::x_axis(), &spline.derivative_at(end, 1).unwrap());
+    }
+
+    #[test]
+    fn from_fit_points_estimates_tangents() {
+        // points on a parabola
+        let fit_points = (0..5)
+            .map(|i| {
+                let x = f64::from(i);
+                Point::new(x, x * x, 0.0)
+            })
+            .collect::<Vec<_>>();
+        let spline = Spline::from_fit_points(fit_points.clone(), None, None).unwrap();
+        assert_close(
+            &fit_points[4],
+            &spline
+                .point_at(spline.parameter_range().unwrap().1)
+                .unwrap(),
+        );
+        let start_direction = spline.derivative_at(0.0, 1).unwrap().normalized();
+        // flatter than the first chord, like the parabola
+        assert!(
+            start_direction.x > start_direction.y,
+            "{:?}",
+            start_direction
+        );
+        assert!(start_direction.y > 0.0, "{:?}", start_direction);
+
+        let two_points =
+            Spline::from_fit_points(vec![Point::origin(), Point::new(2.0, 0.0, 0.0)], None, None)
+                .unwrap();
+        assert_close(
+            &Point::new(1.0, 0.0, 0.0),
+            &two_points.point_at(1.0).unwrap(),
+        );
+        assert!(two_points.is_linear());
+    }
+
+    #[test]
+    fn from_fit_points_rejects_repeated_points() {
+        assert!(Spline::from_fit_points(vec![Point::origin()], None, None).is_none());
+        assert!(Spline::from_fit_points(
+            vec![Point::origin(), Point::origin(), Point::new(1.0, 0.0, 0.0)],
+            None,
+            None
+        )
+        .is_none());
+    }
+
+    #[test]
+    fn round_trip_weights() {
+        let mut drawing = Drawing::new();
+        drawing.header.version = AcadVersion::R2000;
+        drawing.add_entity(Entity::new(EntityType::Spline(quarter_circle())));
+        let drawing = parse_drawing(&to_test_string(&drawing));
+        let entities = drawing.entities().collect::<Vec<_>>();
+        match entities[0].specific {
+            EntityType::Spline(ref spline) => {
+                assert_eq!(3, spline.weight_values.len());
+                assert!((FRAC_1_SQRT_2 - spline.weight_values[1]).abs() < 1e-9);
+                assert!(spline.is_rational());
+            }
+            _ => panic!("expected a spline"),
+        }
+    }
+}
@@ -0,0 +1,308 @@
+use enum_primitive::FromPrimitive;
+
+use crate::{CodePair, Color, Drawing, DrawingItem, DxfResult, Handle};
+
+use crate::code_pair_put_back::CodePairPutBack;
+use crate::entities::Entity;
+use crate::enums::*;
+use crate::helper_functions::*;
+use crate::objects::Object;
+use crate::tables::BlockRecord;
+
+/// A single cell of a `Table` entity, containing either text or a block reference.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct TableCell {
+    pub cell_type: TableCellType,
+    pub flags: i16,
+    /// Whether this cell is part of a merged range.
+    pub is_merged: bool,
+    pub is_autofit: bool,
+    /// The number of columns covered when this cell is the top left cell of a merged range.
+    pub merged_column_count: i16,
+    /// The number of rows covered when this cell is the top left cell of a merged range.
+    pub merged_row_count: i16,
+    pub override_flags: i32,
+    pub virtual_edge_flags: i16,
+    pub rotation: f64,
+    #[doc(hidden)]
+    pub __field_handle: Handle,
+    /// The text of a text cell.
+    pub text: String,
+    pub text_style_name_override: Option<String>,
+    pub text_height_override: Option<f64>,
+    pub alignment_override: Option<i16>,
+    pub content_color_override: Option<Color>,
+    pub fill_color_override: Option<Color>,
+    #[doc(hidden)]
+    pub __block_record_handle: Handle,
+    /// The scale of the block shown in a block cell.
+    pub block_scale: f64,
+    /// The attribute values of the block shown in a block cell.
+    pub attributes: Vec<TableCellAttribute>,
+}
+
+impl Default for TableCell {
+    fn default() -> Self {
+        TableCell {
+            cell_type: TableCellType::Text,
+            flags: 0,
+            is_merged: false,
+            is_autofit: false,
+            merged_column_count: 1,
+            merged_row_count: 1,
+            override_flags: 0,
+            virtual_edge_flags: 0,
+            rotation: 0.0,
+            __field_handle: Handle::empty(),
+            text: String::new(),
+            text_style_name_override: None,
+            text_height_override: None,
+            alignment_override: None,
+            content_color_override: None,
+            fill_color_override: None,
+            __block_record_handle: Handle::empty(),
+            block_scale: 1.0,
+            attributes: vec![],
+        }
+    }
+}
+
+/// The value of an attribute definition shown in a block `TableCell`.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct TableCellAttribute {
+    #[doc(hidden)]
+    pub __attribute_definition_handle: Handle,
+    pub value: String,
+}
+
+impl Default for TableCellAttribute {
+    fn default() -> Self {
+        TableCellAttribute {
+            __attribute_definition_handle: Handle::empty(),
+            value: String::new(),
+        }
+    }
+}
+
+/// A rectangular range of cells in a `Table`, given as inclusive row and column indices.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct TableCellRange {
+    pub top_row: usize,
+    pub left_column: usize,
+    pub bottom_row: usize,
+    pub right_column: usize,
+}
+
+// public implementation
+impl TableCell {
+    /// Creates a text cell.
+    pub fn new_text(text: &str) -> Self {
+        TableCell {
+            text: String::from(text),
+            ..Default::default()
+        }
+    }
+    /// Creates a block cell showing the specified block.
+    pub fn new_block(block_record: &BlockRecord) -> Self {
+        let mut cell = TableCell {
+            cell_type: TableCellType::Block,
+            ..Default::default()
+        };
+        cell.set_block_record(block_record);
+        cell
+    }
+    pub fn field<'a>(&self, drawing: &'a Drawing) -> Option<&'a Object> {
+        match drawing.item_by_handle(self.__field_handle) {
+            Some(DrawingItem::Object(val)) => Some(val),
+            _ => None,
+        }
+    }
+    pub fn set_field(&mut self, item: &Object) {
+        self.__field_handle = DrawingItem::Object(item).handle();
+    }
+    pub fn block_record<'a>(&self, drawing: &'a Drawing) -> Option<&'a BlockRecord> {
+        match drawing.item_by_handle(self.__block_record_handle) {
+            Some(DrawingItem::BlockRecord(val)) => Some(val),
+            _ => None,
+        }
+    }
+    pub fn set_block_record(&mut self, item: &BlockRecord) {
+        self.__block_record_handle = DrawingItem::BlockRecord(item).handle();
+    }
+}
+
+impl TableCellAttribute {
+    pub fn attribute_definition<'a>(&self, drawing: &'a Drawing) -> Option<&'a Entity> {
+        match drawing.item_by_handle(self.__attribute_definition_handle) {
+            Some(DrawingItem::Entity(val)) => Some(val),
+            _ => None,
+        }
+    }
+    pub fn set_attribute_definition(&mut self, item: &Entity) {
+        self.__attribute_definition_handle = DrawingItem::Entity(item).handle();
+    }
+}
+
+impl TableCellRange {
+    pub fn new(top_row: usize, left_column: usize, bottom_row: usize, right_column: usize) -> Self {
+        TableCellRange {
+            top_row,
+            left_column,
+            bottom_row,
+            right_column,
+        }
+    }
+    pub fn row_count(&self) -> usize {
+        self.bottom_row - self.top_row + 1
+    }
+    pub fn column_count(&self) -> usize {
+        self.right_column - self.left_column + 1
+    }
+    pub fn contains(&self, row: usize, column: usize) -> bool {
+        row >= self.top_row
+            && row <= self.bottom_row
+            && column >= self.left_column
+            && column <= self.right_column
+    }
+}
+
+// internal visibility only
+impl TableCell {
+    pub(crate) fn add_pointers(&self, handles: &mut Vec<Handle>) {
+        handles.push(self.__field_handle);
+        handles.push(self.__block_record_handle);
+        for attribute in &self.attributes {
+            handles.push(attribute.__attribute_definition_handle);
+        }
+    }
+    pub(crate) fn read(iter: &mut CodePairPutBack) -> DxfResult<Option<TableCell>> {
+        // check the first pair; only code 171 can start a cell
+        let mut cell = TableCell::default();
+        match iter.next() {
+            Some(Ok(pair @ CodePair { code: 171, .. })) => {
+                cell.cell_type =
+                    enum_from_number!(TableCellType, Text, from_i16, pair.assert_i16()?);
+            }
+            Some(Ok(pair)) => {
+                iter.put_back(Ok(pair));
+                return Ok(None);
+            }
+            Some(Err(e)) => return Err(e),
+            None => return Ok(None),
+        }
+
+        loop {
+            let pair = match iter.next() {
+                Some(Ok(pair)) => pair,
+                Some(Err(e)) => return Err(e),
+                None => return Ok(Some(cell)),
+            };
+
+            match pair.code {
+                172 => cell.flags = pair.assert_i16()?,
+                173 => cell.is_merged = as_bool(pair.assert_i16()?),
+                174 => cell.is_autofit = as_bool(pair.assert_i16()?),
+                175 => cell.merged_column_count = pair.assert_i16()?,
+                176 => cell.merged_row_count = pair.assert_i16()?,
+                91 => cell.override_flags = pair.assert_i32()?,
+                178 => cell.virtual_edge_flags = pair.assert_i16()?,
+                145 => cell.rotation = pair.assert_f64()?,
+                344 => cell.__field_handle = pair.as_handle()?,
+                // long text is split over any number of 2 codes followed by a final 1
+                1 | 2 => cell.text.push_str(&pair.assert_string()?),
+                7 => cell.text_style_name_override = Some(pair.assert_string()?),
+                140 => cell.text_height_override = Some(pair.assert_f64()?),
+                170 => cell.alignment_override = Some(pair.assert_i16()?),
+                64 => cell.content_color_override = Some(Color::from_raw_value(pair.assert_i16()?)),
+                63 => cell.fill_color_override = Some(Color::from_raw_value(pair.assert_i16()?)),
+                340 => cell.__block_record_handle = pair.as_handle()?,
+                144 => cell.block_scale = pair.assert_f64()?,
+                179 => (), // attribute count; recomputed when writing
+                331 => {
+                    cell.attributes.push(TableCellAttribute {
+                        __attribute_definition_handle: pair.as_handle()?,
+                        ..Default::default()
+                    });
+                }
+                300 => {
+                    if let Some(attribute) = cell.attributes.last_mut() {
+                        attribute.value = pair.assert_string()?;
+                    }
+                }
+                _ => {
+                    // the next cell or the end of the cells; put the pair back and return what we have
+                    iter.put_back(Ok(pair));
+                    break;
+                }
+            }
+        }
+
+        Ok(Some(cell))
+    }
+    pub(crate) fn add_code_pairs(&self, pairs: &mut Vec<CodePair>) {
+        pairs.push(CodePair::new_i16(171, self.cell_type as i16));
+        pairs.push(CodePair::new_i16(172, self.flags));
+        pairs.push(CodePair::new_i16(173, as_i16(self.is_merged)));
+        pairs.push(CodePair::new_i16(174, as_i16(self.is_autofit)));
+        pairs.push(CodePair::new_i16(175, self.merged_column_count));
+        pairs.push(CodePair::new_i16(176, self.merged_row_count));
+        pairs.push(CodePair::new_i32(91, self.override_flags));
+        pairs.push(CodePair::new_i16(178, self.virtual_edge_flags));
+        pairs.push(CodePair::new_f64(145, self.rotation));
+        match self.cell_type {
+            TableCellType::Text => {
+                if !self.__field_handle.is_empty() {
+                    pairs.push(CodePair::new_string(344, &self.__field_handle.as_string()));
+                }
+                TableCell::add_text_code_pairs(pairs, &self.text);
+            }
+            TableCellType::Block => {
+                pairs.push(CodePair::new_string(
+                    340,
+                    &self.__block_record_handle.as_string(),
+                ));
+                pairs.push(CodePair::new_f64(144, self.block_scale));
+                pairs.push(CodePair::new_i16(179, self.attributes.len() as i16));
+                for attribute in &self.attributes {
+                    pairs.push(CodePair::new_string(
+                        331,
+                        &attribute.__attribute_definition_handle.as_string(),
+                    ));
+                    pairs.push(CodePair::new_string(300, &attribute.value));
+                }
+            }
+        }
+        if let Some(ref text_style_name) = self.text_style_name_override {
+            pairs.push(CodePair::new_string(7, text_style_name));
+        }
+        if let Some(text_height) = self.text_height_override {
+            pairs.push(CodePair::new_f64(140, text_height));
+        }
+        if let Some(alignment) = self.alignment_override {
+            pairs.push(CodePair::new_i16(170, alignment));
+        }
+        if let Some(ref color) = self.content_color_override {
+            pairs.push(CodePair::new_i16(64, color.raw_value()));
+        }
+        if let Some(ref color) = self.fill_color_override {
+            pairs.push(CodePair::new_i16(63, color.raw_value()));
+        }
+    }
+}
+
+// private implementation
+impl TableCell {
+    fn add_text_code_pairs(pairs: &mut Vec<CodePair>, text: &str) {
+        // text longer than 250 characters is written as 250 character chunks with the remainder in the final 1 code
+        let chars = text.chars().collect::<Vec<_>>();
+        let mut chunks = chars.chunks(250).collect::<Vec<_>>();
+        let last = chunks.pop().unwrap_or(&[]);
+        for chunk in chunks {
+            pairs.push(CodePair::new_string(2, &chunk.iter().collect::<String>()));
+        }
+        pairs.push(CodePair::new_string(1, &last.iter().collect::<String>()));
+    }
+}
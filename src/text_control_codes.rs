@@ -0,0 +1,270 @@
+use std::fmt;
+
+/// The value of a `Text`, `Attribute`, or `AttributeDefinition` with the AutoCAD control codes decoded, e.g., `%%d`
+/// as `°` and the `%%u` and `%%o` toggles as underlined and overlined runs.
+///
+/// ```
+/// # use dxf::*;
+/// let decoded = DecodedText::decode("%%c10 %%uexact%%u %%p0.1%%d");
+/// assert_eq!("\u{d8}10 exact \u{b1}0.1\u{b0}", decoded.plain_text());
+/// assert!(decoded.runs[1].is_underlined);
+/// assert_eq!("%%c10 %%uexact%%u %%p0.1%%d", decoded.to_string());
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct DecodedText {
+    pub runs: Vec<DecodedTextRun>,
+}
+
+/// A run of decoded text with a single format.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct DecodedTextRun {
+    pub text: String,
+    pub is_underlined: bool,
+    pub is_overlined: bool,
+}
+
+// public implementation
+impl DecodedText {
+    /// Decodes the control codes in a value.  `%%c`, `%%d`, and `%%p` are decoded as `Ø`, `°`, and `±`, `%%%` as
+    /// `%`, and `%%nnn` as the character with the decimal code `nnn`.  Unknown control codes are kept as they are.
+    pub fn decode(value: &str) -> Self {
+        let mut decoded = DecodedText::default();
+        let mut run = DecodedTextRun::default();
+        let chars = value.chars().collect::<Vec<_>>();
+        let mut i = 0;
+        while i < chars.len() {
+            if chars[i] != '%' || chars.get(i + 1) != Some(&'%') {
+                run.text.push(chars[i]);
+                i += 1;
+                continue;
+            }
+
+            let code = chars.get(i + 2).map(|c| c.to_ascii_lowercase());
+            let (is_underlined, is_overlined) = match code {
+                Some('u') => (!run.is_underlined, run.is_overlined),
+                Some('o') => (run.is_underlined, !run.is_overlined),
+                _ => (run.is_underlined, run.is_overlined),
+            };
+            if is_underlined != run.is_underlined || is_overlined != run.is_overlined {
+                let next = DecodedTextRun {
+                    text: String::new(),
+                    is_underlined,
+                    is_overlined,
+                };
+                decoded.push_run(std::mem::replace(&mut run, next));
+                i += 3;
+                continue;
+            }
+
+            let special = match code {
+                Some('c') => Some('\u{d8}'),
+                Some('d') => Some('\u{b0}'),
+                Some('p') => Some('\u{b1}'),
+                Some('%') => Some('%'),
+                _ => None,
+            };
+            if let Some(c) = special {
+                run.text.push(c);
+                i += 3;
+                continue;
+            }
+
+            let digits = chars.iter().skip(i + 2).take(3).collect::<String>();
+            let character = match digits.len() {
+                3 if digits.chars().all(|c| c.is_ascii_digit()) => {
+                    digits.parse::<u32>().ok().and_then(std::char::from_u32)
+                }
+                _ => None,
+            };
+            match character {
+                Some(c) => {
+                    run.text.push(c);
+                    i += 5;
+                }
+                None => {
+                    run.text.push_str("%%");
+                    i += 2;
+                }
+            }
+        }
+
+        decoded.push_run(run);
+        decoded
+    }
+    /// Encodes plain text as a value, writing `Ø`, `°`, and `±` as control codes and escaping `%` where it would
+    /// otherwise start a control code.
+    pub fn encode(text: &str) -> String {
+        DecodedText {
+            runs: vec![DecodedTextRun {
+                text: String::from(text),
+                ..Default::default()
+            }],
+        }
+        .to_string()
+    }
+    /// Returns the decoded text without the underline and overline formatting.
+    pub fn plain_text(&self) -> String {
+        self.runs.iter().map(|r| r.text.as_str()).collect()
+    }
+}
+
+/// Writes the runs as a value with control codes, toggling underline and overline with `%%u` and `%%o`.
+impl fmt::Display for DecodedText {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        let mut is_underlined = false;
+        let mut is_overlined = false;
+        for run in &self.runs {
+            if run.is_underlined != is_underlined {
+                formatter.write_str("%%u")?;
+                is_underlined = run.is_underlined;
+            }
+            if run.is_overlined != is_overlined {
+                formatter.write_str("%%o")?;
+                is_overlined = run.is_overlined;
+            }
+            let mut chars = run.text.chars().peekable();
+            while let Some(c) = chars.next() {
+                match c {
+                    '\u{d8}' => formatter.write_str("%%c")?,
+                    '\u{b0}' => formatter.write_str("%%d")?,
+                    '\u{b1}' => formatter.write_str("%%p")?,
+                    // a `%` followed by anything written as a control code, or by a toggle in the next run, would
+                    // start a control code
+                    '%' if matches!(
+                        chars.peek(),
+                        None | Some('%') | Some('\u{d8}') | Some('\u{b0}') | Some('\u{b1}')
+                    ) =>
+                    {
+                        formatter.write_str("%%%")?
+                    }
+                    _ => write!(formatter, "{}", c)?,
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+// private implementation
+impl DecodedText {
+    /// Adds a run, skipping empty runs and joining runs with the same format.
+    fn push_run(&mut self, run: DecodedTextRun) {
+        if run.text.is_empty() {
+            return;
+        }
+        if let Some(previous) = self.runs.last_mut() {
+            if previous.is_underlined == run.is_underlined
+                && previous.is_overlined == run.is_overlined
+            {
+                previous.text.push_str(&run.text);
+                return;
+            }
+        }
+        self.runs.push(run);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    fn run(text: &str, is_underlined: bool, is_overlined: bool) -> DecodedTextRun {
+        DecodedTextRun {
+            text: String::from(text),
+            is_underlined,
+            is_overlined,
+        }
+    }
+
+    #[test]
+    fn decode_special_characters() {
+        let decoded = DecodedText::decode("%%C25 %%D %%p %%%");
+        assert_eq!("\u{d8}25 \u{b0} \u{b1} %", decoded.plain_text());
+    }
+
+    #[test]
+    fn decode_character_codes() {
+        assert_eq!("A1", DecodedText::decode("%%0651").plain_text());
+        // too few digits
+        assert_eq!("%%06", DecodedText::decode("%%06").plain_text());
+    }
+
+    #[test]
+    fn decode_unknown_codes_literally() {
+        assert_eq!("50%%x 5%", DecodedText::decode("50%%x 5%").plain_text());
+        assert_eq!("%%", DecodedText::decode("%%").plain_text());
+    }
+
+    #[test]
+    fn decode_underline_and_overline() {
+        let decoded = DecodedText::decode("a%%ub%%oc%%Ud%%Oe");
+        assert_eq!(
+            vec![
+                run("a", false, false),
+                run("b", true, false),
+                run("c", true, true),
+                run("d", false, true),
+                run("e", false, false),
+            ],
+            decoded.runs
+        );
+        assert_eq!("abcde", decoded.plain_text());
+    }
+
+    #[test]
+    fn decode_empty_runs_are_skipped() {
+        let decoded = DecodedText::decode("%%u%%ua%%o");
+        assert_eq!(vec![run("a", false, false)], decoded.runs);
+        assert_eq!(DecodedText::default(), DecodedText::decode(""));
+    }
+
+    #[test]
+    fn encode_plain_text() {
+        assert_eq!(
+            "%%c10%%d %%p5% %%%%u",
+            DecodedText::encode("\u{d8}10\u{b0} \u{b1}5% %%u")
+        );
+        assert_eq!("100%%%", DecodedText::encode("100%"));
+    }
+
+    #[test]
+    fn encode_percent_before_special_characters() {
+        assert_eq!("%%%%%c10", DecodedText::encode("%\u{d8}10"));
+        assert_eq!("50%%%%%d", DecodedText::encode("50%\u{b0}"));
+        assert_eq!("%%%%%p1", DecodedText::encode("%\u{b1}1"));
+    }
+
+    #[test]
+    fn encode_round_trips() {
+        for text in &[
+            "%\u{d8}10",
+            "50%\u{b0}",
+            "%\u{b1}",
+            "%%%",
+            "% %u %o %065 %%065",
+            "100%",
+            "%%c%%d",
+        ] {
+            assert_eq!(
+                *text,
+                DecodedText::decode(&DecodedText::encode(text)).plain_text()
+            );
+        }
+    }
+
+    #[test]
+    fn encode_runs() {
+        let decoded = DecodedText {
+            runs: vec![
+                run("50%", false, false),
+                run("under", true, false),
+                run("both", true, true),
+            ],
+        };
+        let value = decoded.to_string();
+        assert_eq!("50%%%%%uunder%%oboth", value);
+        assert_eq!(decoded, DecodedText::decode(&value));
+    }
+}
@@ -1,4 +1,11 @@
-/// Applies a transformation to a point.
+use std::ops::Mul;
+
+use crate::{Point, Vector};
+
+/// An affine transformation in 3D space.
+///
+/// Points are treated as column vectors, so the translation is stored in `m14`, `m24`, and `m34` and the product
+/// `a * b` applies `b` first and then `a`.  All angles are in degrees.
 #[derive(Clone, Copy, Debug, Default, PartialEq)]
 #[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 pub struct TransformationMatrix {
@@ -31,6 +38,181 @@ impl TransformationMatrix {
             ..Default::default()
         }
     }
+    /// Creates a transformation that moves by the specified offset.
+    pub fn translation(offset: &Vector) -> Self {
+        TransformationMatrix {
+            m14: offset.x,
+            m24: offset.y,
+            m34: offset.z,
+            ..TransformationMatrix::identity()
+        }
+    }
+    /// Creates a transformation that scales about the origin by the specified factors.
+    pub fn scale(x: f64, y: f64, z: f64) -> Self {
+        TransformationMatrix {
+            m11: x,
+            m22: y,
+            m33: z,
+            m44: 1.0,
+            ..Default::default()
+        }
+    }
+    /// Creates a transformation that rotates counter-clockwise about the X axis.
+    pub fn rotation_about_x(angle: f64) -> Self {
+        let (sin, cos) = angle.to_radians().sin_cos();
+        TransformationMatrix {
+            m22: cos,
+            m23: -sin,
+            m32: sin,
+            m33: cos,
+            ..TransformationMatrix::identity()
+        }
+    }
+    /// Creates a transformation that rotates counter-clockwise about the Y axis.
+    pub fn rotation_about_y(angle: f64) -> Self {
+        let (sin, cos) = angle.to_radians().sin_cos();
+        TransformationMatrix {
+            m11: cos,
+            m13: sin,
+            m31: -sin,
+            m33: cos,
+            ..TransformationMatrix::identity()
+        }
+    }
+    /// Creates a transformation that rotates counter-clockwise about the Z axis.
+    pub fn rotation_about_z(angle: f64) -> Self {
+        let (sin, cos) = angle.to_radians().sin_cos();
+        TransformationMatrix {
+            m11: cos,
+            m12: -sin,
+            m21: sin,
+            m22: cos,
+            ..TransformationMatrix::identity()
+        }
+    }
+    /// Creates a transformation that rotates counter-clockwise about the line through `base_point` in the direction of
+    /// `axis`.
+    pub fn rotation_about_axis(base_point: &Point, axis: &Vector, angle: f64) -> Self {
+        let a = axis.normalized();
+        let (sin, cos) = angle.to_radians().sin_cos();
+        let t = 1.0 - cos;
+        let rotation = TransformationMatrix {
+            m11: t * a.x * a.x + cos,
+            m12: t * a.x * a.y - sin * a.z,
+            m13: t * a.x * a.z + sin * a.y,
+            m21: t * a.x * a.y + sin * a.z,
+            m22: t * a.y * a.y + cos,
+            m23: t * a.y * a.z - sin * a.x,
+            m31: t * a.x * a.z - sin * a.y,
+            m32: t * a.y * a.z + sin * a.x,
+            m33: t * a.z * a.z + cos,
+            m44: 1.0,
+            ..Default::default()
+        };
+        TransformationMatrix::about_point(base_point, &rotation)
+    }
+    /// Creates a transformation that mirrors across the plane through `base_point` with the specified normal.
+    pub fn mirror(base_point: &Point, normal: &Vector) -> Self {
+        let n = normal.normalized();
+        let reflection = TransformationMatrix {
+            m11: 1.0 - 2.0 * n.x * n.x,
+            m12: -2.0 * n.x * n.y,
+            m13: -2.0 * n.x * n.z,
+            m21: -2.0 * n.x * n.y,
+            m22: 1.0 - 2.0 * n.y * n.y,
+            m23: -2.0 * n.y * n.z,
+            m31: -2.0 * n.x * n.z,
+            m32: -2.0 * n.y * n.z,
+            m33: 1.0 - 2.0 * n.z * n.z,
+            m44: 1.0,
+            ..Default::default()
+        };
+        TransformationMatrix::about_point(base_point, &reflection)
+    }
+    /// Returns the transformation that applies `self` and then `next`.
+    pub fn then(&self, next: &TransformationMatrix) -> Self {
+        next.multiply(self)
+    }
+    /// Returns the matrix product `self * other`, which applies `other` and then `self`.
+    pub fn multiply(&self, other: &TransformationMatrix) -> Self {
+        let a = self.rows();
+        let b = other.rows();
+        let mut result = [[0.0; 4]; 4];
+        for (i, row) in result.iter_mut().enumerate() {
+            for (j, value) in row.iter_mut().enumerate() {
+                *value = (0..4).map(|k| a[i][k] * b[k][j]).sum();
+            }
+        }
+        TransformationMatrix::from_rows(&result)
+    }
+    /// Returns the determinant of the full 4x4 matrix.
+    pub fn determinant(&self) -> f64 {
+        let m = self.rows();
+        (0..4)
+            .map(|j| {
+                let sign = if j % 2 == 0 { 1.0 } else { -1.0 };
+                sign * m[0][j] * TransformationMatrix::minor_3x3(&m, 0, j)
+            })
+            .sum()
+    }
+    /// Returns the inverse transformation, or `None` if the matrix is singular.
+    pub fn inverse(&self) -> Option<Self> {
+        // Gauss-Jordan elimination with partial pivoting
+        let mut m = self.rows();
+        let mut inv = TransformationMatrix::identity().rows();
+        for col in 0..4 {
+            let pivot = (col..4)
+                .max_by(|&a, &b| m[a][col].abs().partial_cmp(&m[b][col].abs()).unwrap())
+                .unwrap_or(col);
+            if m[pivot][col].abs() < 1e-12 {
+                return None;
+            }
+            m.swap(col, pivot);
+            inv.swap(col, pivot);
+            let divisor = m[col][col];
+            for j in 0..4 {
+                m[col][j] /= divisor;
+                inv[col][j] /= divisor;
+            }
+            for row in 0..4 {
+                if row != col {
+                    let factor = m[row][col];
+                    for j in 0..4 {
+                        m[row][j] -= factor * m[col][j];
+                        inv[row][j] -= factor * inv[col][j];
+                    }
+                }
+            }
+        }
+        Some(TransformationMatrix::from_rows(&inv))
+    }
+    /// Applies this transformation to a point.
+    pub fn transform_point(&self, point: &Point) -> Point {
+        let x = self.m11 * point.x + self.m12 * point.y + self.m13 * point.z + self.m14;
+        let y = self.m21 * point.x + self.m22 * point.y + self.m23 * point.z + self.m24;
+        let z = self.m31 * point.x + self.m32 * point.y + self.m33 * point.z + self.m34;
+        let w = self.m41 * point.x + self.m42 * point.y + self.m43 * point.z + self.m44;
+        if w == 0.0 || w == 1.0 {
+            Point::new(x, y, z)
+        } else {
+            Point::new(x / w, y / w, z / w)
+        }
+    }
+    /// Applies this transformation to a direction; the translation is ignored.
+    pub fn transform_vector(&self, vector: &Vector) -> Vector {
+        Vector::new(
+            self.m11 * vector.x + self.m12 * vector.y + self.m13 * vector.z,
+            self.m21 * vector.x + self.m22 * vector.y + self.m23 * vector.z,
+            self.m31 * vector.x + self.m32 * vector.y + self.m33 * vector.z,
+        )
+    }
+}
+
+impl Mul for TransformationMatrix {
+    type Output = TransformationMatrix;
+    fn mul(self, rhs: TransformationMatrix) -> TransformationMatrix {
+        self.multiply(&rhs)
+    }
 }
 
 // internal visibility only
@@ -55,6 +237,58 @@ impl TransformationMatrix {
             m44: TransformationMatrix::value_or_default(values, 15),
         }
     }
+    /// Creates the transformation from the object coordinate system (OCS) with the specified normal to world
+    /// coordinates.
+    pub(crate) fn from_ocs(normal: &Vector) -> Self {
+        let (x, y) = normal.arbitrary_axes();
+        let z = normal.normalized();
+        TransformationMatrix {
+            m11: x.x,
+            m12: y.x,
+            m13: z.x,
+            m21: x.y,
+            m22: y.y,
+            m23: z.y,
+            m31: x.z,
+            m32: y.z,
+            m33: z.z,
+            m44: 1.0,
+            ..Default::default()
+        }
+    }
+    /// Returns the determinant of the linear (upper left 3x3) part; a negative value means the transformation mirrors.
+    pub(crate) fn linear_determinant(&self) -> f64 {
+        TransformationMatrix::minor_3x3(&self.rows(), 3, 3)
+    }
+    /// Applies this transformation to a plane normal, returning a unit vector.  Normals transform by the inverse
+    /// transpose of the linear part so they stay perpendicular to the transformed plane; the result is left pointing to
+    /// the same side of the plane as before, so mirroring a plane in itself leaves its normal unchanged.
+    pub(crate) fn transform_normal(&self, normal: &Vector) -> Vector {
+        // the cofactor matrix is the inverse transpose scaled by the determinant
+        let cofactors = Vector::new(
+            (self.m22 * self.m33 - self.m23 * self.m32) * normal.x
+                + (self.m23 * self.m31 - self.m21 * self.m33) * normal.y
+                + (self.m21 * self.m32 - self.m22 * self.m31) * normal.z,
+            (self.m13 * self.m32 - self.m12 * self.m33) * normal.x
+                + (self.m11 * self.m33 - self.m13 * self.m31) * normal.y
+                + (self.m12 * self.m31 - self.m11 * self.m32) * normal.z,
+            (self.m12 * self.m23 - self.m13 * self.m22) * normal.x
+                + (self.m13 * self.m21 - self.m11 * self.m23) * normal.y
+                + (self.m11 * self.m22 - self.m12 * self.m21) * normal.z,
+        );
+        let determinant = self.linear_determinant();
+        if determinant == 0.0 || cofactors.length() == 0.0 {
+            normal.normalized()
+        } else if determinant < 0.0 {
+            cofactors.scaled(-1.0).normalized()
+        } else {
+            cofactors.normalized()
+        }
+    }
+    /// Returns the average factor by which lengths are scaled.
+    pub(crate) fn length_scale(&self) -> f64 {
+        self.linear_determinant().abs().cbrt()
+    }
     pub(crate) fn values(&self) -> Vec<f64> {
         vec![
             self.m11, self.m12, self.m13, self.m14, self.m21, self.m22, self.m23, self.m24,
@@ -71,6 +305,31 @@ impl TransformationMatrix {
 
 // private implementation
 impl TransformationMatrix {
+    fn about_point(base_point: &Point, matrix: &TransformationMatrix) -> Self {
+        let offset = base_point.as_vector();
+        TransformationMatrix::translation(&offset)
+            .multiply(matrix)
+            .multiply(&TransformationMatrix::translation(&offset.scaled(-1.0)))
+    }
+    fn rows(&self) -> [[f64; 4]; 4] {
+        [
+            [self.m11, self.m12, self.m13, self.m14],
+            [self.m21, self.m22, self.m23, self.m24],
+            [self.m31, self.m32, self.m33, self.m34],
+            [self.m41, self.m42, self.m43, self.m44],
+        ]
+    }
+    fn from_rows(rows: &[[f64; 4]; 4]) -> Self {
+        TransformationMatrix::from_vec(&rows.concat())
+    }
+    fn minor_3x3(m: &[[f64; 4]; 4], skip_row: usize, skip_column: usize) -> f64 {
+        let rows = (0..4).filter(|&r| r != skip_row).collect::<Vec<_>>();
+        let columns = (0..4).filter(|&c| c != skip_column).collect::<Vec<_>>();
+        let v = |r: usize, c: usize| m[rows[r]][columns[c]];
+        v(0, 0) * (v(1, 1) * v(2, 2) - v(1, 2) * v(2, 1))
+            - v(0, 1) * (v(1, 0) * v(2, 2) - v(1, 2) * v(2, 0))
+            + v(0, 2) * (v(1, 0) * v(2, 1) - v(1, 1) * v(2, 0))
+    }
     fn value_or_default(values: &[f64], index: usize) -> f64 {
         if values.len() > index {
             values[index]
@@ -79,3 +338,57 @@ impl TransformationMatrix {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    fn assert_matrix_close(expected: &TransformationMatrix, actual: &TransformationMatrix) {
+        for (e, a) in expected.values().iter().zip(actual.values().iter()) {
+            assert!(
+                (e - a).abs() < 1e-9,
+                "expected {:?} but was {:?}",
+                expected,
+                actual
+            );
+        }
+    }
+
+    #[test]
+    fn compose_transformations() {
+        let translate = TransformationMatrix::translation(&Vector::new(1.0, 0.0, 0.0));
+        let rotate = TransformationMatrix::rotation_about_z(90.0);
+        let p = translate.then(&rotate).transform_point(&Point::origin());
+        assert!((p.x - 0.0).abs() < 1e-9 && (p.y - 1.0).abs() < 1e-9);
+        assert_eq!(rotate * translate, translate.then(&rotate));
+    }
+
+    #[test]
+    fn invert_transformation() {
+        let matrix = TransformationMatrix::rotation_about_axis(
+            &Point::new(1.0, 2.0, 3.0),
+            &Vector::new(1.0, 1.0, 0.0),
+            33.0,
+        )
+        .then(&TransformationMatrix::scale(2.0, 3.0, 4.0));
+        let inverse = matrix.inverse().unwrap();
+        assert_matrix_close(&TransformationMatrix::identity(), &(matrix * inverse));
+        assert_matrix_close(&TransformationMatrix::identity(), &(inverse * matrix));
+        assert!((matrix.determinant() * inverse.determinant() - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn singular_transformation_has_no_inverse() {
+        assert_eq!(None, TransformationMatrix::scale(1.0, 0.0, 1.0).inverse());
+    }
+
+    #[test]
+    fn mirror_point() {
+        let matrix = TransformationMatrix::mirror(&Point::new(0.0, 0.0, 1.0), &Vector::z_axis());
+        assert_eq!(
+            Point::new(1.0, 2.0, -1.0),
+            matrix.transform_point(&Point::new(1.0, 2.0, 3.0))
+        );
+        assert!((matrix.determinant() + 1.0).abs() < 1e-9);
+    }
+}
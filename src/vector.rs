@@ -1,3 +1,5 @@
+use std::ops::{AddAssign, DivAssign, MulAssign, Neg, SubAssign};
+
 use crate::{CodePair, DxfError, DxfResult};
 
 /// Represents a simple vector in Cartesian space.
@@ -33,6 +35,93 @@ impl Vector {
     pub fn z_axis() -> Vector {
         Vector::new(0.0, 0.0, 1.0)
     }
+    /// Returns the dot product of this vector and `other`.
+    pub fn dot(&self, other: &Vector) -> f64 {
+        self.x * other.x + self.y * other.y + self.z * other.z
+    }
+    /// Returns the cross product of this vector and `other`.
+    pub fn cross(&self, other: &Vector) -> Vector {
+        Vector::new(
+            self.y * other.z - self.z * other.y,
+            self.z * other.x - self.x * other.z,
+            self.x * other.y - self.y * other.x,
+        )
+    }
+    /// Returns the length of the vector.
+    pub fn length(&self) -> f64 {
+        self.dot(self).sqrt()
+    }
+    /// Returns the square of the length of the vector, which avoids a square root when comparing lengths.
+    pub fn length_squared(&self) -> f64 {
+        self.dot(self)
+    }
+    /// Returns `true` if all components are zero.
+    pub fn is_zero(&self) -> bool {
+        self.x == 0.0 && self.y == 0.0 && self.z == 0.0
+    }
+    pub(crate) fn scaled(&self, factor: f64) -> Vector {
+        Vector::new(self.x * factor, self.y * factor, self.z * factor)
+    }
+    pub(crate) fn plus(&self, other: &Vector) -> Vector {
+        Vector::new(self.x + other.x, self.y + other.y, self.z + other.z)
+    }
+    /// Returns a unit vector in the same direction, or the vector unchanged if it has no length.
+    pub fn normalized(&self) -> Vector {
+        let length = self.length();
+        if length == 0.0 {
+            self.clone()
+        } else {
+            self.scaled(1.0 / length)
+        }
+    }
+    /// Returns the angle in radians between this vector and `other`, from 0 to π.  Returns 0 if either vector has no
+    /// length.
+    pub fn angle_to(&self, other: &Vector) -> f64 {
+        // more accurate than the arc cosine of the dot product for nearly parallel vectors
+        self.cross(other).length().atan2(self.dot(other))
+    }
+    /// Returns the angle in radians from this vector to `other`, counterclockwise when looking down `normal`, from 0 to
+    /// 2π.  The vectors are projected onto the plane perpendicular to `normal`.
+    pub fn angle_to_about(&self, other: &Vector, normal: &Vector) -> f64 {
+        let normal = normal.normalized();
+        let angle = self
+            .cross(other)
+            .dot(&normal)
+            .atan2(self.dot(other) - self.dot(&normal) * other.dot(&normal));
+        if angle < 0.0 {
+            angle + 2.0 * std::f64::consts::PI
+        } else {
+            angle
+        }
+    }
+    /// Returns the component of this vector in the direction of `other`.
+    pub fn projected_onto(&self, other: &Vector) -> Vector {
+        let length_squared = other.length_squared();
+        if length_squared == 0.0 {
+            Vector::zero()
+        } else {
+            other.scaled(self.dot(other) / length_squared)
+        }
+    }
+    /// Returns `true` if this vector and `other` point in the same or opposite directions, within `tolerance` radians.
+    pub fn is_parallel_to(&self, other: &Vector, tolerance: f64) -> bool {
+        let angle = self.angle_to(other);
+        angle <= tolerance || std::f64::consts::PI - angle <= tolerance
+    }
+    /// Returns the X and Y axes of the object coordinate system (OCS) with this vector as the normal, as computed by
+    /// the DXF arbitrary axis algorithm.
+    pub fn arbitrary_axes(&self) -> (Vector, Vector) {
+        let normal = self.normalized();
+        let limit = 1.0 / 64.0;
+        let x_axis = if normal.x.abs() < limit && normal.y.abs() < limit {
+            Vector::y_axis().cross(&normal)
+        } else {
+            Vector::z_axis().cross(&normal)
+        }
+        .normalized();
+        let y_axis = normal.cross(&x_axis).normalized();
+        (x_axis, y_axis)
+    }
     pub(crate) fn set(&mut self, pair: &CodePair) -> DxfResult<()> {
         match pair.code {
             10 => self.x = pair.assert_f64()?,
@@ -49,3 +138,178 @@ impl Vector {
         Ok(())
     }
 }
+
+impl_binary_op!(Add, add, Vector, Vector, Vector, |a, b| a.plus(b));
+impl_binary_op!(Sub, sub, Vector, Vector, Vector, |a, b| Vector::new(
+    a.x - b.x,
+    a.y - b.y,
+    a.z - b.z
+));
+impl_binary_op!(Mul, mul, Vector, f64, Vector, |a, b| a.scaled(*b));
+impl_binary_op!(Mul, mul, f64, Vector, Vector, |a, b| b.scaled(*a));
+impl_binary_op!(Div, div, Vector, f64, Vector, |a, b| Vector::new(
+    a.x / b,
+    a.y / b,
+    a.z / b
+));
+
+impl Neg for Vector {
+    type Output = Vector;
+    fn neg(self) -> Vector {
+        -&self
+    }
+}
+
+impl Neg for &Vector {
+    type Output = Vector;
+    fn neg(self) -> Vector {
+        Vector::new(-self.x, -self.y, -self.z)
+    }
+}
+
+impl AddAssign<&Vector> for Vector {
+    fn add_assign(&mut self, other: &Vector) {
+        self.x += other.x;
+        self.y += other.y;
+        self.z += other.z;
+    }
+}
+
+impl AddAssign for Vector {
+    fn add_assign(&mut self, other: Vector) {
+        *self += &other;
+    }
+}
+
+impl SubAssign<&Vector> for Vector {
+    fn sub_assign(&mut self, other: &Vector) {
+        self.x -= other.x;
+        self.y -= other.y;
+        self.z -= other.z;
+    }
+}
+
+impl SubAssign for Vector {
+    fn sub_assign(&mut self, other: Vector) {
+        *self -= &other;
+    }
+}
+
+impl MulAssign<f64> for Vector {
+    fn mul_assign(&mut self, factor: f64) {
+        self.x *= factor;
+        self.y *= factor;
+        self.z *= factor;
+    }
+}
+
+impl DivAssign<f64> for Vector {
+    fn div_assign(&mut self, divisor: f64) {
+        self.x /= divisor;
+        self.y /= divisor;
+        self.z /= divisor;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+    use std::f64::consts::PI;
+
+    fn assert_close(expected: &Vector, actual: &Vector) {
+        assert!(
+            (expected - actual).length() < 1e-12,
+            "expected {:?}, got {:?}",
+            expected,
+            actual
+        );
+    }
+
+    #[test]
+    fn vector_operators() {
+        let a = Vector::new(1.0, 2.0, 3.0);
+        let b = Vector::new(4.0, 5.0, 6.0);
+        assert_eq!(Vector::new(5.0, 7.0, 9.0), &a + &b);
+        assert_eq!(Vector::new(3.0, 3.0, 3.0), b.clone() - a.clone());
+        assert_eq!(Vector::new(2.0, 4.0, 6.0), &a * 2.0);
+        assert_eq!(Vector::new(2.0, 4.0, 6.0), 2.0 * &a);
+        assert_eq!(Vector::new(0.5, 1.0, 1.5), a.clone() / 2.0);
+        assert_eq!(Vector::new(-1.0, -2.0, -3.0), -&a);
+
+        let mut c = a.clone();
+        c += &b;
+        c -= Vector::new(1.0, 1.0, 1.0);
+        c *= 2.0;
+        c /= 4.0;
+        assert_eq!(Vector::new(2.0, 3.0, 4.0), c);
+    }
+
+    #[test]
+    fn vector_products() {
+        let a = Vector::new(1.0, 2.0, 3.0);
+        let b = Vector::new(4.0, 5.0, 6.0);
+        assert_eq!(32.0, a.dot(&b));
+        assert_eq!(Vector::new(-3.0, 6.0, -3.0), a.cross(&b));
+        assert_eq!(Vector::z_axis(), Vector::x_axis().cross(&Vector::y_axis()));
+        assert_eq!(14.0, a.length_squared());
+        assert_eq!(5.0, Vector::new(3.0, 4.0, 0.0).length());
+        assert_close(
+            &Vector::new(0.6, 0.8, 0.0),
+            &Vector::new(3.0, 4.0, 0.0).normalized(),
+        );
+        assert!(Vector::zero().is_zero());
+        assert!(!a.is_zero());
+    }
+
+    #[test]
+    fn vector_angles() {
+        let x = Vector::x_axis();
+        let y = Vector::y_axis();
+        assert!((x.angle_to(&y) - PI / 2.0).abs() < 1e-12);
+        assert!((y.angle_to(&x) - PI / 2.0).abs() < 1e-12);
+        assert!((x.angle_to(&-&x) - PI).abs() < 1e-12);
+        assert_eq!(0.0, x.angle_to(&Vector::zero()));
+
+        // counterclockwise about +Z, clockwise about -Z
+        assert!((x.angle_to_about(&y, &Vector::z_axis()) - PI / 2.0).abs() < 1e-12);
+        assert!((y.angle_to_about(&x, &Vector::z_axis()) - 3.0 * PI / 2.0).abs() < 1e-12);
+        assert!(
+            (x.angle_to_about(&y, &Vector::new(0.0, 0.0, -2.0)) - 3.0 * PI / 2.0).abs() < 1e-12
+        );
+
+        // components along the normal are ignored
+        let tilted = Vector::new(0.0, 1.0, 5.0);
+        assert!((x.angle_to_about(&tilted, &Vector::z_axis()) - PI / 2.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn vector_projection_and_parallel() {
+        let a = Vector::new(2.0, 3.0, 4.0);
+        assert_eq!(
+            Vector::new(2.0, 0.0, 0.0),
+            a.projected_onto(&Vector::new(5.0, 0.0, 0.0))
+        );
+        assert_eq!(Vector::zero(), a.projected_onto(&Vector::zero()));
+        assert!(a.is_parallel_to(&(&a * -3.0), 1e-9));
+        assert!(!a.is_parallel_to(&Vector::x_axis(), 1e-9));
+    }
+
+    #[test]
+    fn arbitrary_axes_for_z_axis_normal() {
+        let (x_axis, y_axis) = Vector::z_axis().arbitrary_axes();
+        assert_close(&Vector::x_axis(), &x_axis);
+        assert_close(&Vector::y_axis(), &y_axis);
+
+        let (x_axis, y_axis) = Vector::new(0.0, 0.0, -1.0).arbitrary_axes();
+        assert_close(&Vector::new(-1.0, 0.0, 0.0), &x_axis);
+        assert_close(&Vector::y_axis(), &y_axis);
+    }
+
+    #[test]
+    fn arbitrary_axes_for_tilted_normal() {
+        // the normal isn't close to the Z axis, so the X axis is Z x N
+        let (x_axis, y_axis) = Vector::new(1.0, 0.0, 0.0).arbitrary_axes();
+        assert_close(&Vector::y_axis(), &x_axis);
+        assert_close(&Vector::z_axis(), &y_axis);
+    }
+}
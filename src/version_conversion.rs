@@ -0,0 +1,700 @@
+// `Drawing::convert_to_version()`; lowers entities to types that an older `AcadVersion` can hold
+
+use std::f64::consts::PI;
+use std::fmt;
+
+use crate::entities::*;
+use crate::enums::*;
+use crate::{Color, Handle, Point, Vector};
+
+/// The number of segments used to approximate a full ellipse.
+const ELLIPSE_SEGMENTS: usize = 72;
+
+/// The number of segments used to approximate each knot span of a spline.
+const SPLINE_SEGMENTS_PER_SPAN: usize = 16;
+
+/// The distance between lines of text relative to the text height, as used by AutoCAD for `MTEXT`.
+const LINE_SPACING_FACTOR: f64 = 5.0 / 3.0;
+
+/// A change made by `Drawing::convert_to_version()` that lost information.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct LossyConversion {
+    /// The handle of the entity as it was before the conversion.
+    pub handle: Handle,
+    /// The type string of the entity before the conversion, e.g., `ELLIPSE`.
+    pub entity_type: String,
+    /// What was lost.
+    pub kind: LossyConversionKind,
+}
+
+/// The kinds of information lost by `Drawing::convert_to_version()`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub enum LossyConversionKind {
+    /// The curve was replaced by a `POLYLINE` approximating it with straight segments.
+    CurveApproximated,
+    /// The multi-line text was replaced by one `TEXT` per line; formatting and word wrapping were dropped.
+    TextFormattingRemoved,
+    /// The true color was replaced by the closest indexed color.
+    TrueColorApproximated,
+    /// The entity has no equivalent on the target version and was removed.
+    Removed,
+}
+
+impl fmt::Display for LossyConversion {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            formatter,
+            "{} ({}): ",
+            self.handle.as_string(),
+            self.entity_type
+        )?;
+        match self.kind {
+            LossyConversionKind::CurveApproximated => {
+                write!(formatter, "the curve was approximated by a polyline")
+            }
+            LossyConversionKind::TextFormattingRemoved => write!(
+                formatter,
+                "the text was split into single lines and its formatting was removed"
+            ),
+            LossyConversionKind::TrueColorApproximated => write!(
+                formatter,
+                "the true color was replaced by the closest indexed color"
+            ),
+            LossyConversionKind::Removed => {
+                write!(formatter, "the entity is not supported and was removed")
+            }
+        }
+    }
+}
+
+/// Lowers entities to the types supported by a version, assigning handles to the entities and vertices it creates.
+pub(crate) struct VersionConverter {
+    version: AcadVersion,
+    next_handle: Handle,
+    losses: Vec<LossyConversion>,
+}
+
+// internal visibility only
+impl VersionConverter {
+    pub(crate) fn new(version: AcadVersion, next_handle: Handle) -> Self {
+        VersionConverter {
+            version,
+            next_handle,
+            losses: vec![],
+        }
+    }
+    pub(crate) fn convert(&mut self, entities: Vec<Entity>) -> Vec<Entity> {
+        let mut result = Vec::with_capacity(entities.len());
+        for entity in entities {
+            let entity_type = entity.specific.to_type_string().to_string();
+            for mut converted in self.convert_entity(entity, &entity_type) {
+                self.convert_colors(&mut converted, &entity_type);
+                result.push(converted);
+            }
+        }
+        result
+    }
+    /// Returns the next available handle and the reported losses.
+    pub(crate) fn finish(self) -> (Handle, Vec<LossyConversion>) {
+        (self.next_handle, self.losses)
+    }
+}
+
+// private implementation
+impl VersionConverter {
+    fn convert_entity(&mut self, entity: Entity, entity_type: &str) -> Vec<Entity> {
+        if entity.specific.is_supported_on_version(self.version) {
+            return vec![entity];
+        }
+
+        let Entity { common, specific } = entity;
+        let (specific, loss) = match specific {
+            EntityType::LwPolyline(ref poly) => (vec![self.lw_polyline_to_polyline(poly)], None),
+            EntityType::Ellipse(ref ellipse) => match ellipse_to_circle_or_arc(ellipse) {
+                Some(specific) => (vec![specific], None),
+                None => (
+                    vec![self.ellipse_to_polyline(ellipse)],
+                    Some(LossyConversionKind::CurveApproximated),
+                ),
+            },
+            EntityType::Spline(ref spline) => (
+                vec![self.spline_to_polyline(spline)],
+                Some(LossyConversionKind::CurveApproximated),
+            ),
+            EntityType::MText(ref mtext) => (
+                m_text_to_texts(mtext),
+                Some(LossyConversionKind::TextFormattingRemoved),
+            ),
+            _ => (vec![], Some(LossyConversionKind::Removed)),
+        };
+        if let Some(kind) = loss {
+            self.report(&common, entity_type, kind);
+        }
+
+        // the first replacement keeps the original handle
+        let mut result = Vec::with_capacity(specific.len());
+        for specific in specific {
+            let mut common = common.clone();
+            if !result.is_empty() {
+                common.handle = self.next_handle();
+            }
+            result.push(Entity { common, specific });
+        }
+        result
+    }
+    fn convert_colors(&mut self, entity: &mut Entity, entity_type: &str) {
+        if self.version >= AcadVersion::R2004 {
+            return;
+        }
+
+        if Self::lower_true_color(&mut entity.common) {
+            self.report(
+                &entity.common,
+                entity_type,
+                LossyConversionKind::TrueColorApproximated,
+            );
+        }
+    }
+    /// Replaces a true color with the closest indexed color and returns `true` if there was one.
+    fn lower_true_color(common: &mut EntityCommon) -> bool {
+        if common.color_24_bit == 0 {
+            return false;
+        }
+
+        let rgb = common.color_24_bit;
+        common.color = Color::from_rgb((rgb >> 16) as u8, (rgb >> 8) as u8, rgb as u8);
+        common.color_24_bit = 0;
+        common.color_name = String::new();
+        true
+    }
+    fn report(&mut self, common: &EntityCommon, entity_type: &str, kind: LossyConversionKind) {
+        self.losses.push(LossyConversion {
+            handle: common.handle,
+            entity_type: entity_type.to_string(),
+            kind,
+        });
+    }
+    fn next_handle(&mut self) -> Handle {
+        let result = self.next_handle;
+        self.next_handle = self.next_handle.next_handle_value();
+        result
+    }
+    fn lw_polyline_to_polyline(&mut self, poly: &LwPolyline) -> EntityType {
+        let width_or_constant = |width: f64| {
+            if width == 0.0 {
+                poly.constant_width
+            } else {
+                width
+            }
+        };
+        let vertices = poly
+            .vertices
+            .iter()
+            .map(|v| Vertex {
+                location: Point::new(v.x, v.y, 0.0),
+                starting_width: width_or_constant(v.starting_width),
+                ending_width: width_or_constant(v.ending_width),
+                bulge: v.bulge,
+                ..Default::default()
+            })
+            .collect();
+        let mut result = self.polyline(vertices, poly.is_closed());
+        result.location = Point::new(0.0, 0.0, poly.elevation);
+        result.thickness = poly.thickness;
+        result.default_starting_width = poly.constant_width;
+        result.default_ending_width = poly.constant_width;
+        result.normal = poly.extrusion_direction.clone();
+        EntityType::Polyline(result)
+    }
+    fn ellipse_to_polyline(&mut self, ellipse: &Ellipse) -> EntityType {
+        let (start, sweep, is_full) = ellipse_sweep(ellipse);
+        let u = &ellipse.major_axis;
+        let v = ellipse_minor_axis(ellipse);
+        let segments = ((ELLIPSE_SEGMENTS as f64 * sweep / (PI * 2.0)).ceil() as usize).max(1);
+        let point_count = if is_full { segments } else { segments + 1 };
+        let points = (0..point_count)
+            .map(|i| {
+                let (sin, cos) = (start + sweep * i as f64 / segments as f64).sin_cos();
+                let offset = u.scaled(cos).plus(&v.scaled(sin));
+                Point::new(
+                    ellipse.center.x + offset.x,
+                    ellipse.center.y + offset.y,
+                    ellipse.center.z + offset.z,
+                )
+            })
+            .collect::<Vec<_>>();
+
+        // the ellipse is planar so the points all have the same elevation in its OCS
+        let normal = &ellipse.normal;
+        let elevation = ellipse.center.wcs_to_ocs(normal).z;
+        let vertices = points
+            .iter()
+            .map(|p| {
+                let p = p.wcs_to_ocs(normal);
+                Vertex::new(Point::new(p.x, p.y, 0.0))
+            })
+            .collect();
+        let mut result = self.polyline(vertices, is_full);
+        result.location = Point::new(0.0, 0.0, elevation);
+        result.normal = normal.clone();
+        EntityType::Polyline(result)
+    }
+    fn spline_to_polyline(&mut self, spline: &Spline) -> EntityType {
+        let mut points = match spline.sample_points(SPLINE_SEGMENTS_PER_SPAN) {
+            Some(points) => points,
+            None if !spline.fit_points.is_empty() => spline.fit_points.clone(),
+            None => spline.control_points.clone(),
+        };
+        let is_closed = spline.is_closed() && points.len() > 2;
+        if is_closed && points.first() == points.last() {
+            points.pop();
+        }
+
+        let is_flat = points.iter().all(|p| p.z == points[0].z);
+        let result = if is_flat {
+            let elevation = points.first().map_or(0.0, |p| p.z);
+            let vertices = points
+                .into_iter()
+                .map(|p| Vertex::new(Point::new(p.x, p.y, 0.0)))
+                .collect();
+            let mut result = self.polyline(vertices, is_closed);
+            result.location = Point::new(0.0, 0.0, elevation);
+            result
+        } else {
+            let vertices = points
+                .into_iter()
+                .map(|p| {
+                    let mut vertex = Vertex::new(p);
+                    vertex.set_is_3d_polyline_vertex(true);
+                    vertex
+                })
+                .collect();
+            let mut result = self.polyline(vertices, is_closed);
+            result.set_is_3d_polyline(true);
+            result
+        };
+        EntityType::Polyline(result)
+    }
+    fn polyline(&mut self, vertices: Vec<Vertex>, is_closed: bool) -> Polyline {
+        let mut result = Polyline {
+            contains_vertices: true,
+            ..Default::default()
+        };
+        result.set_is_closed(is_closed);
+        for vertex in vertices {
+            let handle = self.next_handle();
+            result.__vertices_and_handles.push((vertex, handle));
+        }
+        result.__seqend_handle = self.next_handle();
+        result
+    }
+}
+
+/// Returns the start parameter and the sweep of the ellipse in radians, and whether it's a full ellipse.
+fn ellipse_sweep(ellipse: &Ellipse) -> (f64, f64, bool) {
+    let sweep = (ellipse.end_parameter - ellipse.start_parameter).rem_euclid(PI * 2.0);
+    if sweep == 0.0 {
+        (ellipse.start_parameter, PI * 2.0, true)
+    } else {
+        (ellipse.start_parameter, sweep, false)
+    }
+}
+
+fn ellipse_minor_axis(ellipse: &Ellipse) -> Vector {
+    ellipse
+        .normal
+        .normalized()
+        .cross(&ellipse.major_axis)
+        .scaled(ellipse.minor_axis_ratio)
+}
+
+/// Converts an ellipse with equal axes to the equivalent `CIRCLE` or `ARC`.
+fn ellipse_to_circle_or_arc(ellipse: &Ellipse) -> Option<EntityType> {
+    if (ellipse.minor_axis_ratio - 1.0).abs() > 1e-9 {
+        return None;
+    }
+
+    let normal = &ellipse.normal;
+    let center = ellipse.center.wcs_to_ocs(normal);
+    let radius = ellipse.major_axis.length();
+    let (start, sweep, is_full) = ellipse_sweep(ellipse);
+    if is_full {
+        let mut circle = Circle::new(center, radius);
+        circle.normal = normal.clone();
+        return Some(EntityType::Circle(circle));
+    }
+
+    // parameters are measured from the major axis; arc angles are measured from the OCS X axis
+    let (x_axis, y_axis) = normal.arbitrary_axes();
+    let axis_angle = ellipse
+        .major_axis
+        .dot(&y_axis)
+        .atan2(ellipse.major_axis.dot(&x_axis));
+    let start = (axis_angle + start).to_degrees().rem_euclid(360.0);
+    let end = (start + sweep.to_degrees()).rem_euclid(360.0);
+    let mut arc = Arc::new(center, radius, start, end);
+    arc.normal = normal.clone();
+    Some(EntityType::Arc(arc))
+}
+
+/// Converts `MTEXT` to one `TEXT` per line, aligned the same way relative to the insertion point.
+fn m_text_to_texts(mtext: &MText) -> Vec<EntityType> {
+    let normal = &mtext.extrusion_direction;
+    let (x_axis, y_axis) = normal.arbitrary_axes();
+    let rotation = if mtext.x_axis_direction.length() == 0.0 {
+        mtext.rotation_angle
+    } else {
+        mtext
+            .x_axis_direction
+            .dot(&y_axis)
+            .atan2(mtext.x_axis_direction.dot(&x_axis))
+    };
+    let lines = mtext
+        .formatted_text()
+        .paragraphs
+        .iter()
+        .map(|p| p.plain_text())
+        .collect::<Vec<_>>();
+
+    let attachment = mtext.attachment_point as i32 - 1;
+    let horizontal = match attachment % 3 {
+        1 => HorizontalTextJustification::Center,
+        2 => HorizontalTextJustification::Right,
+        _ => HorizontalTextJustification::Left,
+    };
+    let vertical = match attachment / 3 {
+        1 => VerticalTextJustification::Middle,
+        2 => VerticalTextJustification::Bottom,
+        _ => VerticalTextJustification::Top,
+    };
+
+    // lines are stacked downwards and the whole block is anchored by the attachment point
+    let spacing = mtext.initial_text_height * LINE_SPACING_FACTOR * mtext.line_spacing_factor;
+    let last_line = lines.len().saturating_sub(1) as f64;
+    let first_offset = match vertical {
+        VerticalTextJustification::Middle => last_line * spacing / 2.0,
+        VerticalTextJustification::Bottom => last_line * spacing,
+        _ => 0.0,
+    };
+    let origin = mtext.insertion_point.wcs_to_ocs(normal);
+    let (sin, cos) = rotation.sin_cos();
+    lines
+        .into_iter()
+        .enumerate()
+        .filter(|(_, line)| !line.is_empty())
+        .map(|(i, line)| {
+            let offset = first_offset - i as f64 * spacing;
+            let location = Point::new(origin.x - sin * offset, origin.y + cos * offset, origin.z);
+            EntityType::Text(Text {
+                location: location.clone(),
+                second_alignment_point: location,
+                text_height: mtext.initial_text_height,
+                value: line,
+                rotation: rotation.to_degrees(),
+                text_style_name: mtext.text_style_name.clone(),
+                horizontal_text_justification: horizontal,
+                vertical_text_justification: vertical,
+                normal: normal.clone(),
+                ..Default::default()
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::PI;
+
+    use crate::entities::*;
+    use crate::enums::*;
+    use crate::helper_functions::tests::*;
+    use crate::*;
+
+    fn convert(entity: EntityType, version: AcadVersion) -> (Drawing, Vec<LossyConversion>) {
+        let mut drawing = Drawing::new();
+        drawing.add_entity(Entity::new(entity));
+        let losses = drawing.convert_to_version(version);
+        (drawing, losses)
+    }
+
+    fn single_entity(drawing: &Drawing) -> &EntityType {
+        let entities = drawing.entities().collect::<Vec<_>>();
+        assert_eq!(1, entities.len());
+        &entities[0].specific
+    }
+
+    fn assert_close(expected: &Point, actual: &Point) {
+        assert!(
+            (expected.x - actual.x).abs() < 1e-9
+                && (expected.y - actual.y).abs() < 1e-9
+                && (expected.z - actual.z).abs() < 1e-9,
+            "expected {:?}, got {:?}",
+            expected,
+            actual
+        );
+    }
+
+    #[test]
+    fn lw_polyline_becomes_polyline_without_loss() {
+        let mut poly = LwPolyline {
+            constant_width: 0.5,
+            elevation: 3.0,
+            ..Default::default()
+        };
+        poly.set_is_closed(true);
+        for (x, y, bulge) in &[(0.0, 0.0, 0.0), (1.0, 0.0, 1.0), (1.0, 1.0, 0.0)] {
+            poly.vertices.push(LwPolylineVertex {
+                x: *x,
+                y: *y,
+                bulge: *bulge,
+                ..Default::default()
+            });
+        }
+        let (drawing, losses) = convert(EntityType::LwPolyline(poly), AcadVersion::R12);
+        assert!(losses.is_empty());
+        assert_eq!(AcadVersion::R12, drawing.header.version);
+        match single_entity(&drawing) {
+            EntityType::Polyline(poly) => {
+                assert!(poly.is_closed());
+                assert!(!poly.is_3d_polyline());
+                assert_eq!(3.0, poly.location.z);
+                let vertices = poly.vertices().collect::<Vec<_>>();
+                assert_eq!(3, vertices.len());
+                assert_eq!(Point::new(1.0, 0.0, 0.0), vertices[1].location);
+                assert_eq!(1.0, vertices[1].bulge);
+                assert_eq!(0.5, vertices[1].starting_width);
+            }
+            _ => panic!("expected a polyline"),
+        }
+
+        let text = to_test_string(&drawing);
+        assert!(text.contains("POLYLINE"));
+        assert!(!text.contains("LWPOLYLINE"));
+        assert!(drawing.audit().is_empty());
+    }
+
+    #[test]
+    fn lw_polyline_is_kept_on_versions_that_support_it() {
+        let (drawing, losses) = convert(
+            EntityType::LwPolyline(LwPolyline::default()),
+            AcadVersion::R2000,
+        );
+        assert!(losses.is_empty());
+        assert!(matches!(single_entity(&drawing), EntityType::LwPolyline(_)));
+    }
+
+    #[test]
+    fn ellipse_becomes_approximated_polyline() {
+        let ellipse = Ellipse {
+            center: Point::new(1.0, 2.0, 0.0),
+            major_axis: Vector::new(2.0, 0.0, 0.0),
+            minor_axis_ratio: 0.5,
+            start_parameter: 0.0,
+            end_parameter: PI * 2.0,
+            ..Default::default()
+        };
+        let (drawing, losses) = convert(EntityType::Ellipse(ellipse), AcadVersion::R12);
+        assert_eq!(1, losses.len());
+        assert_eq!("ELLIPSE", losses[0].entity_type);
+        assert_eq!(LossyConversionKind::CurveApproximated, losses[0].kind);
+        let handle = drawing.entities().next().unwrap().common.handle;
+        assert_eq!(handle, losses[0].handle);
+        match single_entity(&drawing) {
+            EntityType::Polyline(poly) => {
+                assert!(poly.is_closed());
+                let vertices = poly.vertices().collect::<Vec<_>>();
+                assert_eq!(72, vertices.len());
+                assert_close(&Point::new(3.0, 2.0, 0.0), &vertices[0].location);
+                assert_close(&Point::new(1.0, 3.0, 0.0), &vertices[18].location);
+            }
+            _ => panic!("expected a polyline"),
+        }
+        assert!(drawing.audit().is_empty());
+    }
+
+    #[test]
+    fn circular_elliptic_arc_becomes_arc() {
+        let ellipse = Ellipse {
+            center: Point::new(1.0, 2.0, 0.0),
+            major_axis: Vector::new(0.0, 2.0, 0.0),
+            minor_axis_ratio: 1.0,
+            start_parameter: 0.0,
+            end_parameter: PI / 2.0,
+            ..Default::default()
+        };
+        let (drawing, losses) = convert(EntityType::Ellipse(ellipse), AcadVersion::R12);
+        assert!(losses.is_empty());
+        match single_entity(&drawing) {
+            EntityType::Arc(arc) => {
+                assert_close(&Point::new(1.0, 2.0, 0.0), &arc.center);
+                assert_eq!(2.0, arc.radius);
+                assert!((arc.start_angle - 90.0).abs() < 1e-9);
+                assert!((arc.end_angle - 180.0).abs() < 1e-9);
+            }
+            _ => panic!("expected an arc"),
+        }
+    }
+
+    #[test]
+    fn spline_becomes_approximated_polyline() {
+        let spline = Spline {
+            degree_of_curve: 2,
+            knot_values: vec![0.0, 0.0, 0.0, 1.0, 1.0, 1.0],
+            control_points: vec![
+                Point::new(0.0, 0.0, 0.0),
+                Point::new(1.0, 2.0, 0.0),
+                Point::new(2.0, 0.0, 0.0),
+            ],
+            ..Default::default()
+        };
+        let (drawing, losses) = convert(EntityType::Spline(spline), AcadVersion::R12);
+        assert_eq!(1, losses.len());
+        assert_eq!("SPLINE", losses[0].entity_type);
+        assert_eq!(LossyConversionKind::CurveApproximated, losses[0].kind);
+        match single_entity(&drawing) {
+            EntityType::Polyline(poly) => {
+                assert!(!poly.is_3d_polyline());
+                let vertices = poly.vertices().collect::<Vec<_>>();
+                assert_eq!(17, vertices.len());
+                assert_close(&Point::new(0.0, 0.0, 0.0), &vertices[0].location);
+                assert_close(&Point::new(1.0, 1.0, 0.0), &vertices[8].location);
+                assert_close(&Point::new(2.0, 0.0, 0.0), &vertices[16].location);
+            }
+            _ => panic!("expected a polyline"),
+        }
+    }
+
+    #[test]
+    fn non_planar_spline_becomes_3d_polyline() {
+        let spline = Spline {
+            degree_of_curve: 1,
+            knot_values: vec![0.0, 0.0, 1.0, 1.0],
+            control_points: vec![Point::new(0.0, 0.0, 0.0), Point::new(1.0, 1.0, 1.0)],
+            ..Default::default()
+        };
+        let (drawing, _) = convert(EntityType::Spline(spline), AcadVersion::R12);
+        match single_entity(&drawing) {
+            EntityType::Polyline(poly) => {
+                assert!(poly.is_3d_polyline());
+                assert!(poly.vertices().all(|v| v.is_3d_polyline_vertex()));
+                let last = poly.vertices().last().unwrap();
+                assert_close(&Point::new(1.0, 1.0, 1.0), &last.location);
+            }
+            _ => panic!("expected a polyline"),
+        }
+    }
+
+    #[test]
+    fn m_text_becomes_one_text_per_line() {
+        let mtext = MText {
+            insertion_point: Point::new(1.0, 2.0, 0.0),
+            initial_text_height: 3.0,
+            text: String::from("{\\fArial|b1;Hello}\\PWorld \\S1^2;"),
+            ..Default::default()
+        };
+        let (drawing, losses) = convert(EntityType::MText(mtext), AcadVersion::R12);
+        assert_eq!(1, losses.len());
+        assert_eq!("MTEXT", losses[0].entity_type);
+        assert_eq!(LossyConversionKind::TextFormattingRemoved, losses[0].kind);
+
+        let texts = drawing
+            .entities()
+            .map(|e| match e.specific {
+                EntityType::Text(ref text) => text,
+                _ => panic!("expected text"),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(2, texts.len());
+        assert_eq!("Hello", texts[0].value);
+        assert_eq!("World 1/2", texts[1].value);
+        assert_eq!(
+            VerticalTextJustification::Top,
+            texts[0].vertical_text_justification
+        );
+        assert_close(&Point::new(1.0, 2.0, 0.0), &texts[0].location);
+        assert_close(&Point::new(1.0, -3.0, 0.0), &texts[1].location);
+
+        let handles = drawing
+            .entities()
+            .map(|e| e.common.handle)
+            .collect::<Vec<_>>();
+        assert_eq!(losses[0].handle, handles[0]);
+        assert_ne!(handles[0], handles[1]);
+        assert!(drawing.audit().is_empty());
+    }
+
+    #[test]
+    fn true_color_becomes_closest_index() {
+        let mut line = Entity::new(EntityType::Line(Line::default()));
+        line.common.color_24_bit = 0x00F0_0505;
+        let mut drawing = Drawing::new();
+        drawing.add_entity(line.clone());
+        let losses = drawing.convert_to_version(AcadVersion::R2000);
+        assert_eq!(1, losses.len());
+        assert_eq!(LossyConversionKind::TrueColorApproximated, losses[0].kind);
+        let common = &drawing.entities().next().unwrap().common;
+        assert_eq!(Some(1), common.color.index());
+        assert_eq!(0, common.color_24_bit);
+
+        // later versions can hold the true color
+        let mut drawing = Drawing::new();
+        drawing.add_entity(line);
+        assert!(drawing.convert_to_version(AcadVersion::R2004).is_empty());
+        let common = &drawing.entities().next().unwrap().common;
+        assert_eq!(0x00F0_0505, common.color_24_bit);
+    }
+
+    #[test]
+    fn unsupported_entities_are_removed() {
+        let mut drawing = Drawing::new();
+        drawing.add_entity(Entity::new(EntityType::Ray(Ray::default())));
+        drawing.add_entity(Entity::new(EntityType::Line(Line::default())));
+        let mut block = Block {
+            name: String::from("b"),
+            ..Default::default()
+        };
+        block
+            .entities
+            .push(Entity::new(EntityType::Helix(Helix::default())));
+        drawing.add_block(block);
+
+        let losses = drawing.convert_to_version(AcadVersion::R12);
+        let removed = losses
+            .iter()
+            .map(|l| (l.entity_type.as_str(), l.kind))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                ("RAY", LossyConversionKind::Removed),
+                ("HELIX", LossyConversionKind::Removed)
+            ],
+            removed
+        );
+        assert_eq!(1, drawing.entities().count());
+        assert!(drawing
+            .blocks()
+            .find(|b| b.name == "b")
+            .unwrap()
+            .entities
+            .is_empty());
+    }
+
+    #[test]
+    fn converted_drawing_round_trips() {
+        let mut drawing = Drawing::new();
+        drawing.add_entity(Entity::new(EntityType::Ellipse(Ellipse {
+            major_axis: Vector::new(1.0, 0.0, 0.0),
+            minor_axis_ratio: 0.5,
+            end_parameter: PI,
+            ..Default::default()
+        })));
+        drawing.convert_to_version(AcadVersion::R12);
+        let reparsed = parse_drawing(&to_test_string(&drawing));
+        match single_entity(&reparsed) {
+            EntityType::Polyline(poly) => assert_eq!(37, poly.vertices().count()),
+            _ => panic!("expected a polyline"),
+        }
+    }
+}
@@ -49,6 +49,9 @@ pub enum XDataItem {
 }
 
 impl XData {
+    pub(crate) fn add_pointers(&self, handles: &mut Vec<Handle>) {
+        XDataItem::add_pointers(&self.items, handles);
+    }
     pub(crate) fn read_item(
         application_name: String,
         iter: &mut CodePairPutBack,
@@ -90,6 +93,15 @@ impl XData {
 }
 
 impl XDataItem {
+    fn add_pointers(items: &[XDataItem], handles: &mut Vec<Handle>) {
+        for item in items {
+            match item {
+                XDataItem::Handle(h) => handles.push(*h),
+                XDataItem::ControlGroup(ref items) => XDataItem::add_pointers(items, handles),
+                _ => (),
+            }
+        }
+    }
     fn read_item(pair: &CodePair, iter: &mut CodePairPutBack) -> DxfResult<XDataItem> {
         match pair.code {
             XDATA_STRING => Ok(XDataItem::Str(pair.assert_string()?)),